@@ -1,3 +1,5 @@
+/// Config to evaluate blob polynomials with the barycentric formula
+mod barycentric;
 /// Circuit implementation of aggregation circuit.
 mod circuit;
 /// Config for aggregation circuit
@@ -5,6 +7,7 @@ mod config;
 /// config for RLC circuit
 mod rlc;
 
+pub use barycentric::BarycentricEvaluationConfig;
 pub use circuit::AggregationCircuit;
 pub use config::AggregationConfig;
 pub(crate) use rlc::RlcConfig;
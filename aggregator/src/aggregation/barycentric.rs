@@ -0,0 +1,215 @@
+use halo2_proofs::{
+    circuit::Value,
+    halo2curves::{bls12_381::Scalar, bn256::Fr},
+};
+use itertools::Itertools;
+use num_bigint::{BigInt, BigUint};
+use snark_verifier::{
+    loader::halo2::halo2_ecc::{
+        bigint::{CRTInteger, OverflowInteger},
+        fields::{fp::FpConfig, FieldChip},
+        halo2_base::{
+            gates::{range::RangeConfig, GateInstructions, RangeInstructions},
+            utils::biguint_to_fe,
+            AssignedValue, Context, QuantumCell,
+        },
+    },
+    util::arithmetic::modulus,
+};
+
+use crate::{
+    blob::{
+        roots_of_unity, u256_to_biguint, BlobData, PointEvaluation, BLOB_WIDTH,
+        N_BYTES_PER_BLOB_FIELD,
+    },
+    constants::{BITS, LIMBS},
+};
+
+/// Config to prove the evaluation of a blob polynomial at a challenge point with the barycentric
+/// formula
+///   p(z) = (z^N - 1) / N * sum_i f_i * w_i / (z - w_i)
+///
+/// The arithmetic of the BLS12-381 scalar field is done by the non-native field chip.
+#[derive(Debug, Clone)]
+pub struct BarycentricEvaluationConfig {
+    /// The chip of the BLS12-381 scalar field, sharing the range config of the base field chip.
+    pub scalar: FpConfig<Fr, Scalar>,
+}
+
+/// The assigned cells of a blob point evaluation.
+pub(crate) struct AssignedBarycentricEvaluation {
+    /// the blob data, N_BLOB_BYTES bytes
+    pub(crate) blob_bytes: Vec<AssignedValue<Fr>>,
+    /// the challenge digest keccak(batch_data_hash || versioned_hash), 32 bytes in big-endian
+    pub(crate) challenge_digest_be: Vec<AssignedValue<Fr>>,
+    /// the challenge point z = challenge_digest mod p, 32 bytes in big-endian
+    pub(crate) challenge_be: Vec<AssignedValue<Fr>>,
+    /// the evaluation y = p(z), 32 bytes in big-endian
+    pub(crate) evaluation_be: Vec<AssignedValue<Fr>>,
+}
+
+impl BarycentricEvaluationConfig {
+    /// Construct the config from the range config of the non-native field chip.
+    pub fn construct(range: RangeConfig<Fr>) -> Self {
+        Self {
+            scalar: FpConfig::construct(range, BITS, LIMBS, modulus::<Scalar>()),
+        }
+    }
+
+    /// Assign the barycentric evaluation of the blob at the challenge point, and return the cells
+    /// of the blob data, the challenge digest, the challenge point and the evaluation.
+    ///
+    /// The blob field elements are composed from the blob bytes, and the challenge point is
+    /// reduced from the challenge digest: the caller shall bind those bytes to the keccak
+    /// preimage of the blob data hash and to the keccak digest of the challenge.
+    pub(crate) fn assign(
+        &self,
+        ctx: &mut Context<Fr>,
+        blob: &BlobData,
+        point_evaluation: &PointEvaluation,
+    ) -> AssignedBarycentricEvaluation {
+        let blob_bytes = blob
+            .to_bytes()
+            .iter()
+            .map(|&byte| self.load_byte(ctx, byte))
+            .collect::<Vec<_>>();
+
+        // z == challenge_digest mod p
+        let (challenge_digest, challenge_digest_be) =
+            self.load_be_bytes(ctx, &u256_to_biguint(&point_evaluation.challenge_digest));
+        let (challenge, challenge_be) =
+            self.load_canonical_be_bytes(ctx, &u256_to_biguint(&point_evaluation.challenge));
+        let challenge_digest = self.scalar.carry_mod(ctx, &challenge_digest);
+        self.scalar.assert_equal(ctx, &challenge_digest, &challenge);
+        let (evaluation, evaluation_be) =
+            self.load_canonical_be_bytes(ctx, &u256_to_biguint(&point_evaluation.evaluation));
+
+        // sum_i f_i * w_i / (z - w_i)
+        let mut sum = self.scalar.load_constant(ctx, BigUint::from(0u64));
+        for ((bytes_be, coefficient), root) in blob_bytes
+            .chunks(N_BYTES_PER_BLOB_FIELD)
+            .zip_eq(blob.coefficients().iter())
+            .zip_eq(roots_of_unity().iter())
+        {
+            // a blob field element of 31 bytes is smaller than p
+            let bytes_le = bytes_be.iter().rev().cloned().collect_vec();
+            let coefficient = self.compose_le_bytes(ctx, &bytes_le, &u256_to_biguint(coefficient));
+            let root = self.scalar.load_constant(ctx, root.clone());
+
+            let numerator = self.scalar.mul(ctx, &coefficient, &root);
+            let denominator = self.scalar.sub_no_carry(ctx, &challenge, &root);
+            let denominator = self.scalar.carry_mod(ctx, &denominator);
+            let term = self.scalar.divide(ctx, &numerator, &denominator);
+
+            let sum_no_carry = self.scalar.add_no_carry(ctx, &sum, &term);
+            sum = self.scalar.carry_mod(ctx, &sum_no_carry);
+        }
+
+        // z^N - 1
+        let challenge_pow = (0..BLOB_WIDTH.trailing_zeros())
+            .fold(challenge, |acc, _| self.scalar.mul(ctx, &acc, &acc));
+        let one = self.scalar.load_constant(ctx, BigUint::from(1u64));
+        let vanishing = self.scalar.sub_no_carry(ctx, &challenge_pow, &one);
+        let vanishing = self.scalar.carry_mod(ctx, &vanishing);
+
+        // y * N == (z^N - 1) * sum_i f_i * w_i / (z - w_i)
+        let blob_width = self
+            .scalar
+            .load_constant(ctx, BigUint::from(BLOB_WIDTH as u64));
+        let lhs = self.scalar.mul(ctx, &evaluation, &blob_width);
+        let rhs = self.scalar.mul(ctx, &vanishing, &sum);
+        self.scalar.assert_equal(ctx, &lhs, &rhs);
+
+        AssignedBarycentricEvaluation {
+            blob_bytes,
+            challenge_digest_be,
+            challenge_be,
+            evaluation_be,
+        }
+    }
+
+    fn load_byte(&self, ctx: &mut Context<Fr>, byte: u8) -> AssignedValue<Fr> {
+        let byte = self
+            .scalar
+            .range
+            .gate
+            .load_witness(ctx, Value::known(Fr::from(byte as u64)));
+        self.scalar.range.range_check(ctx, &byte, 8);
+        byte
+    }
+
+    /// Compose an integer from its bytes in little-endian, BITS / 8 bytes per limb. The limbs
+    /// are range checked by the bytes.
+    fn compose_le_bytes(
+        &self,
+        ctx: &mut Context<Fr>,
+        bytes_le: &[AssignedValue<Fr>],
+        value: &BigUint,
+    ) -> CRTInteger<Fr> {
+        let gate = &self.scalar.range.gate;
+        let powers_of_256 = (0..BITS / 8)
+            .map(|i| QuantumCell::Constant(biguint_to_fe(&(BigUint::from(1u64) << (8 * i)))))
+            .collect_vec();
+        let limbs = bytes_le
+            .chunks(BITS / 8)
+            .map(|chunk| {
+                gate.inner_product(
+                    ctx,
+                    chunk
+                        .iter()
+                        .map(|&byte| QuantumCell::Existing(byte))
+                        .collect_vec(),
+                    powers_of_256[..chunk.len()].to_vec(),
+                )
+            })
+            .collect_vec();
+        debug_assert_eq!(limbs.len(), LIMBS);
+        let native = gate.inner_product(
+            ctx,
+            limbs
+                .iter()
+                .map(|&limb| QuantumCell::Existing(limb))
+                .collect_vec(),
+            self.scalar
+                .limb_bases
+                .iter()
+                .map(|&base| QuantumCell::Constant(base))
+                .collect_vec(),
+        );
+
+        CRTInteger::construct(
+            OverflowInteger::construct(limbs, BITS),
+            native,
+            Value::known(BigInt::from(value.clone())),
+        )
+    }
+
+    /// Load a value smaller than 2^256 from its 32 bytes in big-endian.
+    fn load_be_bytes(
+        &self,
+        ctx: &mut Context<Fr>,
+        value: &BigUint,
+    ) -> (CRTInteger<Fr>, Vec<AssignedValue<Fr>>) {
+        let mut bytes_le = value.to_bytes_le();
+        bytes_le.resize(32, 0);
+        let bytes_le = bytes_le
+            .iter()
+            .map(|&byte| self.load_byte(ctx, byte))
+            .collect::<Vec<_>>();
+        let integer = self.compose_le_bytes(ctx, &bytes_le, value);
+
+        (integer, bytes_le.into_iter().rev().collect())
+    }
+
+    /// Load a value smaller than the BLS12-381 scalar modulus from its 32 bytes in big-endian.
+    fn load_canonical_be_bytes(
+        &self,
+        ctx: &mut Context<Fr>,
+        value: &BigUint,
+    ) -> (CRTInteger<Fr>, Vec<AssignedValue<Fr>>) {
+        let (integer, bytes_be) = self.load_be_bytes(ctx, value);
+        self.scalar.enforce_less_than(ctx, &integer);
+
+        (integer, bytes_be)
+    }
+}
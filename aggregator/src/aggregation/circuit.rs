@@ -9,15 +9,13 @@ use itertools::Itertools;
 use rand::Rng;
 use std::{env, fs::File};
 
-#[cfg(not(feature = "disable_proof_aggregation"))]
-use snark_verifier::loader::halo2::halo2_ecc::halo2_base;
-use snark_verifier::pcs::kzg::KzgSuccinctVerifyingKey;
+use snark_verifier::{
+    loader::halo2::halo2_ecc::halo2_base::{self, AssignedValue, Context, ContextParams},
+    pcs::kzg::KzgSuccinctVerifyingKey,
+};
 #[cfg(not(feature = "disable_proof_aggregation"))]
 use snark_verifier::{
-    loader::halo2::{
-        halo2_ecc::halo2_base::{AssignedValue, Context, ContextParams},
-        Halo2Loader,
-    },
+    loader::halo2::Halo2Loader,
     pcs::kzg::{Bdfg21, Kzg},
 };
 #[cfg(not(feature = "disable_proof_aggregation"))]
@@ -26,14 +24,26 @@ use snark_verifier_sdk::{CircuitExt, Snark, SnarkWitness};
 use zkevm_circuits::util::Challenges;
 
 use crate::{
-    batch::BatchHash,
-    constants::{ACC_LEN, DIGEST_LEN, MAX_AGG_SNARKS},
+    batch::{BatchHash, DataAvailability},
+    constants::{ACC_LEN, BLOB_POINT_EVALUATION_LEN, DIGEST_LEN, MAX_AGG_SNARKS},
     core::{assign_batch_hashes, extract_proof_and_instances_with_pairing_check},
-    util::parse_hash_digest_cells,
+    util::{
+        digest_byte_index, parse_blob_hash_digest_cells, parse_blob_hash_preimage_cells,
+        parse_hash_digest_cells,
+    },
     ConfigParams,
 };
 
-use super::AggregationConfig;
+use super::{barycentric::AssignedBarycentricEvaluation, AggregationConfig};
+
+/// The accumulators, the public inputs of the snarks, and in the blob DA mode the barycentric
+/// evaluation, assigned in the aggregation region.
+#[cfg(not(feature = "disable_proof_aggregation"))]
+type AggregationAssignment = (
+    Vec<AssignedValue<Fr>>,
+    Vec<AssignedValue<Fr>>,
+    Option<AssignedBarycentricEvaluation>,
+);
 
 /// Aggregation circuit that does not re-expose any public inputs from aggregated snarks
 #[derive(Clone)]
@@ -45,6 +55,7 @@ pub struct AggregationCircuit {
     // the public instance for this circuit consists of
     // - an accumulator (12 elements)
     // - the batch's public_input_hash (32 elements)
+    // - in the blob DA mode, the challenge point and the blob evaluation (64 elements)
     pub flattened_instances: Vec<Fr>,
     // accumulation scheme proof, private input
     pub as_proof: Value<Vec<u8>>,
@@ -93,12 +104,13 @@ impl AggregationCircuit {
         let (as_proof, acc_instances) =
             extract_proof_and_instances_with_pairing_check(params, snarks_with_padding, rng)?;
 
-        // extract batch's public input hash
+        // extract batch's public input hash, and the blob point evaluation in the blob DA mode
         let public_input_hash = &batch_hash.instances_exclude_acc()[0];
 
         // the public instance for this circuit consists of
         // - an accumulator (12 elements)
         // - the batch's public_input_hash (32 elements)
+        // - in the blob DA mode, the challenge point and the blob evaluation (64 elements)
         let flattened_instances: Vec<Fr> =
             [acc_instances.as_slice(), public_input_hash.as_slice()].concat();
 
@@ -115,6 +127,25 @@ impl AggregationCircuit {
     pub fn as_proof(&self) -> Value<&[u8]> {
         self.as_proof.as_ref().map(Vec::as_slice)
     }
+
+    /// In the blob DA mode, assign the barycentric evaluation of the blob at the challenge point.
+    fn assign_barycentric_evaluation(
+        &self,
+        config: &AggregationConfig,
+        ctx: &mut Context<Fr>,
+    ) -> Option<AssignedBarycentricEvaluation> {
+        match &self.batch_hash.data_availability {
+            DataAvailability::Calldata => None,
+            DataAvailability::Blob {
+                blob,
+                point_evaluation,
+            } => Some(
+                config
+                    .barycentric_config
+                    .assign(ctx, blob, point_evaluation),
+            ),
+        }
+    }
 }
 
 impl Circuit<Fr> for AggregationCircuit {
@@ -161,7 +192,7 @@ impl Circuit<Fr> for AggregationCircuit {
         // Step 1: snark aggregation circuit
         // ==============================================
         #[cfg(not(feature = "disable_proof_aggregation"))]
-        let (accumulator_instances, snark_inputs) = {
+        let (accumulator_instances, snark_inputs, barycentric) = {
             config
                 .range()
                 .load_lookup_table(&mut layouter)
@@ -169,12 +200,12 @@ impl Circuit<Fr> for AggregationCircuit {
 
             let mut first_pass = halo2_base::SKIP_FIRST_PASS;
 
-            let (accumulator_instances, snark_inputs) = layouter.assign_region(
+            let (accumulator_instances, snark_inputs, barycentric) = layouter.assign_region(
                 || "aggregation",
-                |region| -> Result<(Vec<AssignedValue<Fr>>, Vec<AssignedValue<Fr>>), Error> {
+                |region| -> Result<AggregationAssignment, Error> {
                     if first_pass {
                         first_pass = false;
-                        return Ok((vec![], vec![]));
+                        return Ok((vec![], vec![], None));
                     }
 
                     // stores accumulators for all snarks, including the padded ones
@@ -222,16 +253,61 @@ impl Circuit<Fr> for AggregationCircuit {
                             .flat_map(|instance_column| instance_column.iter().skip(ACC_LEN)),
                     );
 
+                    // the barycentric evaluation shares the context of the aggregation, as the
+                    // halo2-lib regions are not measured by the floor planner
+                    let barycentric =
+                        self.assign_barycentric_evaluation(&config, &mut loader.ctx_mut());
+
                     config.range().finalize(&mut loader.ctx_mut());
 
                     loader.ctx_mut().print_stats(&["Range"]);
 
-                    Ok((accumulator_instances, snark_inputs))
+                    Ok((accumulator_instances, snark_inputs, barycentric))
                 },
             )?;
 
             assert_eq!(snark_inputs.len(), MAX_AGG_SNARKS * DIGEST_LEN);
-            (accumulator_instances, snark_inputs)
+            (accumulator_instances, snark_inputs, barycentric)
+        };
+        #[cfg(feature = "disable_proof_aggregation")]
+        let barycentric = {
+            let mut first_pass = halo2_base::SKIP_FIRST_PASS;
+
+            match self.batch_hash.data_availability {
+                DataAvailability::Calldata => None,
+                DataAvailability::Blob { .. } => {
+                    config
+                        .range()
+                        .load_lookup_table(&mut layouter)
+                        .expect("load range lookup table");
+
+                    layouter.assign_region(
+                        || "barycentric evaluation",
+                        |region| -> Result<Option<AssignedBarycentricEvaluation>, Error> {
+                            if first_pass {
+                                first_pass = false;
+                                return Ok(None);
+                            }
+
+                            let mut ctx = Context::new(
+                                region,
+                                ContextParams {
+                                    max_rows: config.flex_gate().max_rows,
+                                    num_context_ids: 1,
+                                    fixed_columns: config.flex_gate().constants.clone(),
+                                },
+                            );
+                            let barycentric = self.assign_barycentric_evaluation(&config, &mut ctx);
+
+                            config.range().finalize(&mut ctx);
+
+                            ctx.print_stats(&["Range"]);
+
+                            Ok(barycentric)
+                        },
+                    )?
+                }
+            }
         };
         end_timer!(timer);
         // ==============================================
@@ -242,7 +318,8 @@ impl Circuit<Fr> for AggregationCircuit {
 
         let timer = start_timer!(|| "load aux table");
 
-        let hash_digest_cells = {
+        let with_blob = self.batch_hash.blob().is_some();
+        let hash_cells = {
             config
                 .keccak_circuit_config
                 .load_aux_tables(&mut layouter)?;
@@ -252,11 +329,12 @@ impl Circuit<Fr> for AggregationCircuit {
             // orders:
             // - batch_public_input_hash
             // - chunk\[i\].piHash for i in \[0, MAX_AGG_SNARKS)
+            // - in the blob DA mode, the blob data hash and the challenge digest
             // - batch_data_hash_preimage
             let preimages = self.batch_hash.extract_hash_preimages();
             assert_eq!(
                 preimages.len(),
-                MAX_AGG_SNARKS + 2,
+                MAX_AGG_SNARKS + 2 + 2 * with_blob as usize,
                 "error extracting preimages"
             );
             end_timer!(timer);
@@ -268,20 +346,21 @@ impl Circuit<Fr> for AggregationCircuit {
                 .iter()
                 .map(|chunk| !chunk.is_padding)
                 .collect::<Vec<_>>();
-            let hash_digest_cells = assign_batch_hashes(
+            let hash_cells = assign_batch_hashes(
                 &config,
                 &mut layouter,
                 challenges,
                 &chunks_are_valid,
                 &preimages,
+                with_blob,
             )
             .map_err(|_e| Error::ConstraintSystemFailure)?;
             end_timer!(timer);
-            hash_digest_cells
+            hash_cells
         };
         // digests
         let (batch_pi_hash_digest, chunk_pi_hash_digests, _potential_batch_data_hash_digest) =
-            parse_hash_digest_cells(&hash_digest_cells);
+            parse_hash_digest_cells(&hash_cells.hash_output_cells, with_blob);
 
         // ==============================================
         // step 3: assert public inputs to the snarks are correct
@@ -343,6 +422,42 @@ impl Circuit<Fr> for AggregationCircuit {
             },
         )?;
 
+        // in the blob DA mode, the barycentric evaluation uses the blob data hashed into the batch
+        // data hash, and the challenge point is reduced from the challenge digest
+        if let Some(barycentric) = &barycentric {
+            let (blob_data, _challenge_preimage) =
+                parse_blob_hash_preimage_cells(&hash_cells.hash_input_cells);
+            let (_blob_data_hash_digest, challenge_digest) =
+                parse_blob_hash_digest_cells(&hash_cells.hash_output_cells);
+
+            let mut first_pass = halo2_base::SKIP_FIRST_PASS;
+            layouter.assign_region(
+                || "blob checks",
+                |mut region| -> Result<(), Error> {
+                    if first_pass {
+                        // this region only use copy constraints and do not affect the shape of
+                        // the layouter
+                        first_pass = false;
+                        return Ok(());
+                    }
+
+                    for (byte, cell) in barycentric.blob_bytes.iter().zip_eq(blob_data) {
+                        region.constrain_equal(byte.cell(), cell.cell())?;
+                    }
+                    for (i, byte) in barycentric.challenge_digest_be.iter().enumerate() {
+                        region.constrain_equal(
+                            byte.cell(),
+                            // in the keccak table, the input and output data have different
+                            // endianess
+                            challenge_digest[digest_byte_index(i)].cell(),
+                        )?;
+                    }
+
+                    Ok(())
+                },
+            )?;
+        }
+
         // ==============================================
         // step 4: assert public inputs to the aggregator circuit are correct
         // ==============================================
@@ -372,6 +487,22 @@ impl Circuit<Fr> for AggregationCircuit {
             }
         }
 
+        // blob point evaluation
+        if let Some(barycentric) = barycentric {
+            for (i, byte) in barycentric
+                .challenge_be
+                .iter()
+                .chain(barycentric.evaluation_be.iter())
+                .enumerate()
+            {
+                layouter.constrain_instance(
+                    byte.cell(),
+                    config.instance,
+                    ACC_LEN + DIGEST_LEN + i,
+                )?;
+            }
+        }
+
         end_timer!(witness_time);
         Ok(())
     }
@@ -381,11 +512,17 @@ impl CircuitExt<Fr> for AggregationCircuit {
     fn num_instance(&self) -> Vec<usize> {
         // 12 elements from accumulator
         // 32 elements from batch's public_input_hash
-        vec![ACC_LEN + DIGEST_LEN]
+        // 64 elements from the blob point evaluation in the blob DA mode
+        let point_evaluation_len = match self.batch_hash.data_availability {
+            DataAvailability::Calldata => 0,
+            DataAvailability::Blob { .. } => BLOB_POINT_EVALUATION_LEN,
+        };
+        vec![ACC_LEN + DIGEST_LEN + point_evaluation_len]
     }
 
     // 12 elements from accumulator
     // 32 elements from batch's public_input_hash
+    // 64 elements from the blob point evaluation in the blob DA mode
     fn instances(&self) -> Vec<Vec<Fr>> {
         vec![self.flattened_instances.clone()]
     }
@@ -19,7 +19,7 @@ use zkevm_circuits::{
 use crate::{
     constants::{BITS, LIMBS},
     param::ConfigParams,
-    RlcConfig,
+    BarycentricEvaluationConfig, RlcConfig,
 };
 
 #[derive(Debug, Clone)]
@@ -33,10 +33,13 @@ pub struct AggregationConfig {
     pub keccak_circuit_config: KeccakCircuitConfig<Fr>,    
     /// RLC config
     pub rlc_config: RlcConfig,
+    /// Barycentric evaluation config for the blob DA mode
+    pub barycentric_config: BarycentricEvaluationConfig,
     /// Instance for public input; stores
     /// - accumulator from aggregation (12 elements)
     /// - batch_public_input_hash (32 elements)
     /// - the number of valid SNARKs (1 element)
+    /// - in the blob DA mode, the challenge point and the blob evaluation (64 elements)
     pub instance: Column<Instance>,
 }
 
@@ -83,6 +86,10 @@ impl AggregationConfig {
             params.degree as usize,
         );
 
+        // the barycentric evaluation shares the range config of the base field
+        let barycentric_config =
+            BarycentricEvaluationConfig::construct(base_field_config.range.clone());
+
         let columns = keccak_circuit_config.cell_manager.columns();
         log::info!("keccak uses {} columns", columns.len(),);
 
@@ -107,6 +114,7 @@ impl AggregationConfig {
         Self {
             base_field_config,
             rlc_config,
+            barycentric_config,
             keccak_circuit_config,
             instance,
         }
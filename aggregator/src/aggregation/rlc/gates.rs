@@ -6,7 +6,14 @@ use halo2_proofs::{
 };
 use zkevm_circuits::util::Challenges;
 
-use crate::{constants::LOG_DEGREE, util::assert_equal};
+use crate::{
+    blob::N_BLOB_BYTES,
+    constants::{
+        BLOB_BATCH_PI_HASH_PREIMAGE_LEN, CHALLENGE_PREIMAGE_LEN, CHUNK_PI_HASH_PREIMAGE_LEN,
+        LOG_DEGREE,
+    },
+    util::assert_equal,
+};
 
 use super::RlcConfig;
 
@@ -32,6 +39,30 @@ impl RlcConfig {
             8,
             || Value::known(Fr::from(1 << 32)),
         )?;
+        region.assign_fixed(
+            || "const 184",
+            self.fixed,
+            9,
+            || Value::known(Fr::from(CHUNK_PI_HASH_PREIMAGE_LEN as u64)),
+        )?;
+        region.assign_fixed(
+            || "const 168",
+            self.fixed,
+            10,
+            || Value::known(Fr::from(BLOB_BATCH_PI_HASH_PREIMAGE_LEN as u64)),
+        )?;
+        region.assign_fixed(
+            || "const 64",
+            self.fixed,
+            11,
+            || Value::known(Fr::from(CHALLENGE_PREIMAGE_LEN as u64)),
+        )?;
+        region.assign_fixed(
+            || "const blob bytes",
+            self.fixed,
+            12,
+            || Value::known(Fr::from(N_BLOB_BYTES as u64)),
+        )?;
         Ok(())
     }
 
@@ -115,6 +146,42 @@ impl RlcConfig {
         }
     }
 
+    #[inline]
+    pub(crate) fn one_hundred_and_eighty_four_cell(&self, region_index: RegionIndex) -> Cell {
+        Cell {
+            region_index,
+            row_offset: 9,
+            column: self.fixed.into(),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn one_hundred_and_sixty_eight_cell(&self, region_index: RegionIndex) -> Cell {
+        Cell {
+            region_index,
+            row_offset: 10,
+            column: self.fixed.into(),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn sixty_four_cell(&self, region_index: RegionIndex) -> Cell {
+        Cell {
+            region_index,
+            row_offset: 11,
+            column: self.fixed.into(),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn blob_bytes_len_cell(&self, region_index: RegionIndex) -> Cell {
+        Cell {
+            region_index,
+            row_offset: 12,
+            column: self.fixed.into(),
+        }
+    }
+
     pub(crate) fn load_private(
         &self,
         region: &mut Region<Fr>,
@@ -1,13 +1,35 @@
 //! This module implements related functions that aggregates public inputs of many chunks into a
 //! single one.
 
-use eth_types::{Field, H256};
+use eth_types::{Field, ToBigEndian, H256};
 use ethers_core::utils::keccak256;
 
-use crate::constants::MAX_AGG_SNARKS;
+use crate::{
+    blob::{challenge_preimage, BlobData, PointEvaluation},
+    constants::MAX_AGG_SNARKS,
+};
 
 use super::chunk::ChunkHash;
 
+#[derive(Default, Debug, Clone)]
+/// How the L2 transactions of a batch are made available on L1.
+pub enum DataAvailability {
+    /// The transactions are posted as calldata, and committed by the batch data hash.
+    #[default]
+    Calldata,
+    /// The transactions are posted in a blob. The keccak of the blob data prefixes the batch data
+    /// hash preimage, and the versioned hash of the blob is appended to the batch public input
+    /// hash preimage. The evaluation of the blob polynomial at the challenge point is proven by
+    /// the aggregation circuit, and exposed with the public input hash, so that it can be checked
+    /// against the blob's versioned hash by the point evaluation precompile.
+    Blob {
+        /// the blob of the batch
+        blob: BlobData,
+        /// the evaluation of the blob at the challenge point
+        point_evaluation: PointEvaluation,
+    },
+}
+
 #[derive(Default, Debug, Clone)]
 /// A batch is a set of MAX_AGG_SNARKS num of continuous chunks
 /// - the first k chunks are from real traces
@@ -16,6 +38,10 @@ use super::chunk::ChunkHash;
 /// - batch_pi_hash   := keccak(chain_id || chunk_0.prev_state_root || chunk_k-1.post_state_root ||
 ///   chunk_k-1.withdraw_root || batch_data_hash)
 /// - batch_data_hash := keccak(chunk_0.data_hash || ... || chunk_k-1.data_hash)
+/// In the blob DA mode, the batch additionally carries the point evaluation of its blob, and
+/// - batch_pi_hash   := keccak(chain_id || chunk_0.prev_state_root || chunk_k-1.post_state_root ||
+///   chunk_k-1.withdraw_root || batch_data_hash || versioned_hash)
+/// - batch_data_hash := keccak(blob_data_hash || chunk_0.data_hash || ... || chunk_k-1.data_hash)
 pub struct BatchHash {
     pub(crate) chain_id: u64,
     // chunks with padding.
@@ -25,6 +51,7 @@ pub struct BatchHash {
     pub(crate) data_hash: H256,
     pub(crate) public_input_hash: H256,
     pub(crate) number_of_valid_chunks: usize,
+    pub(crate) data_availability: DataAvailability,
 }
 
 impl BatchHash {
@@ -83,60 +110,142 @@ impl BatchHash {
                     chunks_with_padding[i + 1].withdraw_root,
                     chunks_with_padding[i].withdraw_root
                 );
+                assert_eq!(
+                    chunks_with_padding[i + 1].start_l1_queue_index,
+                    chunks_with_padding[i].start_l1_queue_index
+                );
+                assert_eq!(
+                    chunks_with_padding[i + 1].end_l1_queue_index,
+                    chunks_with_padding[i].end_l1_queue_index
+                );
+                assert_eq!(
+                    chunks_with_padding[i + 1].skipped_l1_msgs_hash,
+                    chunks_with_padding[i].skipped_l1_msgs_hash
+                );
             } else {
                 assert_eq!(
                     chunks_with_padding[i].post_state_root,
                     chunks_with_padding[i + 1].prev_state_root,
                 );
+                // the chunks consume the L1 message queue contiguously
+                assert_eq!(
+                    chunks_with_padding[i].end_l1_queue_index,
+                    chunks_with_padding[i + 1].start_l1_queue_index,
+                );
             }
         }
 
-        // batch's data hash is build as
-        //  keccak( chunk[0].data_hash || ... || chunk[k-1].data_hash)
-        let preimage = chunks_with_padding
+        let mut batch_hash = Self {
+            chain_id: chunks_with_padding[0].chain_id,
+            chunks_with_padding: chunks_with_padding.try_into().unwrap(), // safe unwrap
+            data_hash: H256::zero(),
+            public_input_hash: H256::zero(),
+            number_of_valid_chunks,
+            data_availability: DataAvailability::Calldata,
+        };
+        batch_hash.data_hash = keccak256(batch_hash.data_hash_preimage()).into();
+        batch_hash.public_input_hash = keccak256(batch_hash.public_input_hash_preimage()).into();
+        batch_hash
+    }
+
+    /// Build Batch hash from an ordered list of #MAX_AGG_SNARKS of chunks, whose L2 transactions
+    /// are posted in the given blob.
+    pub fn construct_with_blob(chunks_with_padding: &[ChunkHash], blob: BlobData) -> Self {
+        let mut batch_hash = Self::construct(chunks_with_padding);
+        assert_eq!(
+            blob.chunk_tx_bytes.len(),
+            batch_hash.number_of_valid_chunks,
+            "blob does not match the valid chunks"
+        );
+
+        // the batch data hash commits the blob data, and the challenge point is derived from the
+        // batch data hash
+        batch_hash.data_availability = DataAvailability::Blob {
+            blob,
+            point_evaluation: PointEvaluation::default(),
+        };
+        batch_hash.data_hash = keccak256(batch_hash.data_hash_preimage()).into();
+        if let DataAvailability::Blob {
+            blob,
+            point_evaluation,
+        } = &mut batch_hash.data_availability
+        {
+            *point_evaluation = blob.point_evaluation(&batch_hash.data_hash);
+            log::trace!("blob point evaluation: {:?}", point_evaluation);
+        }
+        batch_hash.public_input_hash = keccak256(batch_hash.public_input_hash_preimage()).into();
+        batch_hash
+    }
+
+    /// The blob of the batch, if the batch is in the blob DA mode.
+    pub fn blob(&self) -> Option<&BlobData> {
+        match &self.data_availability {
+            DataAvailability::Calldata => None,
+            DataAvailability::Blob { blob, .. } => Some(blob),
+        }
+    }
+
+    /// The point evaluation of the batch's blob, if the batch is in the blob DA mode.
+    pub fn point_evaluation(&self) -> Option<&PointEvaluation> {
+        match &self.data_availability {
+            DataAvailability::Calldata => None,
+            DataAvailability::Blob {
+                point_evaluation, ..
+            } => Some(point_evaluation),
+        }
+    }
+
+    /// The batch data hash preimage
+    ///  (blob_data_hash ||) chunk[0].data_hash || ... || chunk[k-1].data_hash
+    /// where the blob data hash is only present in the blob DA mode.
+    fn data_hash_preimage(&self) -> Vec<u8> {
+        let blob_data_hash = self.blob().map(BlobData::data_hash);
+        blob_data_hash
             .iter()
-            .take(number_of_valid_chunks)
-            .flat_map(|chunk_hash| chunk_hash.data_hash.0.iter())
-            .cloned()
-            .collect::<Vec<_>>();
-        let data_hash = keccak256(preimage);
+            .chain(
+                self.chunks_with_padding
+                    .iter()
+                    .take(self.number_of_valid_chunks)
+                    .map(|chunk| &chunk.data_hash),
+            )
+            .flat_map(|hash| hash.0)
+            .collect()
+    }
 
-        // public input hash is build as
-        //  keccak(
-        //      chain_id ||
-        //      chunk[0].prev_state_root ||
-        //      chunk[k-1].post_state_root ||
-        //      chunk[k-1].withdraw_root ||
-        //      batch_data_hash )
-        let preimage = [
-            chunks_with_padding[0].chain_id.to_be_bytes().as_ref(),
-            chunks_with_padding[0].prev_state_root.as_bytes(),
-            chunks_with_padding[MAX_AGG_SNARKS - 1]
+    /// The batch public input hash preimage
+    ///  chain_id ||
+    ///  chunk[0].prev_state_root ||
+    ///  chunk[k-1].post_state_root ||
+    ///  chunk[k-1].withdraw_root ||
+    ///  batch_data_hash
+    /// followed by the versioned hash of the blob in the blob DA mode.
+    pub(crate) fn public_input_hash_preimage(&self) -> Vec<u8> {
+        let versioned_hash = self
+            .point_evaluation()
+            .map(|point_evaluation| point_evaluation.versioned_hash.as_bytes())
+            .unwrap_or_default();
+        [
+            self.chain_id.to_be_bytes().as_ref(),
+            self.chunks_with_padding[0].prev_state_root.as_bytes(),
+            self.chunks_with_padding[MAX_AGG_SNARKS - 1]
                 .post_state_root
                 .as_bytes(),
-            chunks_with_padding[MAX_AGG_SNARKS - 1]
+            self.chunks_with_padding[MAX_AGG_SNARKS - 1]
                 .withdraw_root
                 .as_bytes(),
-            data_hash.as_slice(),
+            self.data_hash.as_bytes(),
+            versioned_hash,
         ]
-        .concat();
-        let public_input_hash = keccak256(preimage);
-
-        Self {
-            chain_id: chunks_with_padding[0].chain_id,
-            chunks_with_padding: chunks_with_padding.try_into().unwrap(), // safe unwrap
-            data_hash: data_hash.into(),
-            public_input_hash: public_input_hash.into(),
-            number_of_valid_chunks,
-        }
+        .concat()
     }
 
     /// Extract all the hash inputs that will ever be used.
-    /// There are MAX_AGG_SNARKS + 2 hashes.
+    /// There are MAX_AGG_SNARKS + 2 hashes, and MAX_AGG_SNARKS + 4 in the blob DA mode.
     ///
     /// orders:
     /// - batch_public_input_hash
     /// - chunk\[i\].piHash for i in \[0, MAX_AGG_SNARKS)
+    /// - in the blob DA mode, the blob data and the challenge digest preimage
     /// - batch_data_hash_preimage
     pub(crate) fn extract_hash_preimages(&self) -> Vec<Vec<u8>> {
         let mut res = vec![];
@@ -147,27 +256,18 @@ impl BatchHash {
         //      chunk[0].prev_state_root ||
         //      chunk[k-1].post_state_root ||
         //      chunk[k-1].withdraw_root ||
-        //      batch_data_hash )
-        let batch_public_input_hash_preimage = [
-            self.chain_id.to_be_bytes().as_ref(),
-            self.chunks_with_padding[0].prev_state_root.as_bytes(),
-            self.chunks_with_padding[MAX_AGG_SNARKS - 1]
-                .post_state_root
-                .as_bytes(),
-            self.chunks_with_padding[MAX_AGG_SNARKS - 1]
-                .withdraw_root
-                .as_bytes(),
-            self.data_hash.as_bytes(),
-        ]
-        .concat();
-        res.push(batch_public_input_hash_preimage);
+        //      batch_data_hash
+        //      (|| versioned_hash) )
+        res.push(self.public_input_hash_preimage());
 
         // compute piHash for each chunk for i in [0..MAX_AGG_SNARKS)
         // chunk[i].piHash =
         // keccak(
         //        chain id ||
         //        chunk[i].prevStateRoot || chunk[i].postStateRoot || chunk[i].withdrawRoot ||
-        //        chunk[i].datahash)
+        //        chunk[i].datahash ||
+        //        chunk[i].startL1QueueIndex || chunk[i].endL1QueueIndex ||
+        //        chunk[i].skippedL1MsgsHash)
         for chunk in self.chunks_with_padding.iter() {
             let chunk_public_input_hash_preimage = [
                 self.chain_id.to_be_bytes().as_ref(),
@@ -175,31 +275,54 @@ impl BatchHash {
                 chunk.post_state_root.as_bytes(),
                 chunk.withdraw_root.as_bytes(),
                 chunk.data_hash.as_bytes(),
+                chunk.start_l1_queue_index.to_be_bytes().as_ref(),
+                chunk.end_l1_queue_index.to_be_bytes().as_ref(),
+                chunk.skipped_l1_msgs_hash.as_bytes(),
             ]
             .concat();
             res.push(chunk_public_input_hash_preimage)
         }
 
-        // batchDataHash = keccak(chunk[0].dataHash || ... || chunk[k-1].dataHash)
-        let batch_data_hash_preimage = self
-            .chunks_with_padding
-            .iter()
-            .take(self.number_of_valid_chunks)
-            .flat_map(|x| x.data_hash.as_bytes().iter())
-            .cloned()
-            .collect();
-        res.push(batch_data_hash_preimage);
+        // blobDataHash = keccak(blob data)
+        // challengeDigest = keccak(batchDataHash || versionedHash)
+        if let DataAvailability::Blob {
+            blob,
+            point_evaluation,
+        } = &self.data_availability
+        {
+            res.push(blob.to_bytes());
+            res.push(challenge_preimage(
+                &self.data_hash,
+                &point_evaluation.versioned_hash,
+            ));
+        }
+
+        // batchDataHash = keccak((blobDataHash ||) chunk[0].dataHash || ... || chunk[k-1].dataHash)
+        res.push(self.data_hash_preimage());
 
         res
     }
 
     /// Compute the public inputs for this circuit, excluding the accumulator.
-    /// Content: the public_input_hash
+    /// Content: the public_input_hash, followed in the blob DA mode by the challenge point and the
+    /// blob evaluation in big-endian
     pub(crate) fn instances_exclude_acc<F: Field>(&self) -> Vec<Vec<F>> {
+        let point_evaluation_bytes = self
+            .point_evaluation()
+            .map(|point_evaluation| {
+                [
+                    point_evaluation.challenge.to_be_bytes(),
+                    point_evaluation.evaluation.to_be_bytes(),
+                ]
+                .concat()
+            })
+            .unwrap_or_default();
+
         vec![self
             .public_input_hash
             .as_bytes()
             .iter()
+            .chain(point_evaluation_bytes.iter())
             .map(|&x| F::from(x as u64))
             .collect()]
     }
@@ -0,0 +1,258 @@
+//! This module implements the blob used for the blob based data availability of a batch.
+//!
+//! The L2 transactions of the chunks are serialized into a blob of [`BLOB_WIDTH`] field elements
+//! of the BLS12-381 scalar field. The blob is committed on L1 by its KZG versioned hash, and the
+//! aggregation circuit proves the evaluation of the blob polynomial at a challenge point.
+
+use std::{iter, sync::OnceLock};
+
+use c_kzg::{Blob, Bytes32, KzgCommitment, KzgProof, KzgSettings, BYTES_PER_BLOB};
+use eth_types::{ToBigEndian, H256, U256};
+use ethers_core::utils::keccak256;
+use num_bigint::BigUint;
+use revm_primitives::kzg::{G1_POINTS, G2_POINTS};
+use sha2::{Digest, Sha256};
+
+use crate::constants::MAX_AGG_SNARKS;
+
+/// Number of field elements in a blob.
+pub const BLOB_WIDTH: usize = c_kzg::FIELD_ELEMENTS_PER_BLOB;
+
+/// Number of bytes packed into a blob field element. The most significant byte of each field
+/// element is left to zero so that it is always smaller than the BLS12-381 scalar modulus.
+pub const N_BYTES_PER_BLOB_FIELD: usize = 31;
+
+/// Number of bytes that can be stored in a blob.
+pub const N_BLOB_BYTES: usize = BLOB_WIDTH * N_BYTES_PER_BLOB_FIELD;
+
+/// Number of bytes of the blob metadata: the number of valid chunks (2 bytes) followed by the size
+/// of each chunk (4 bytes each).
+pub const N_BLOB_METADATA_BYTES: usize = 2 + 4 * MAX_AGG_SNARKS;
+
+/// The version byte of a KZG versioned hash.
+const VERSIONED_HASH_VERSION_KZG: u8 = 0x01;
+
+/// The scalar field modulus of BLS12-381, in big-endian hex.
+const BLS_MODULUS_HEX: &str = "73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001";
+
+/// The primitive root of unity used to derive the evaluation domain of EIP-4844.
+const PRIMITIVE_ROOT_OF_UNITY: u64 = 7;
+
+/// The scalar field modulus of BLS12-381.
+pub(crate) fn bls_modulus() -> &'static BigUint {
+    static MODULUS: OnceLock<BigUint> = OnceLock::new();
+    MODULUS.get_or_init(|| BigUint::parse_bytes(BLS_MODULUS_HEX.as_bytes(), 16).unwrap())
+}
+
+/// The evaluation domain of a blob: the [`BLOB_WIDTH`]-th roots of unity, in bit-reversed order.
+pub(crate) fn roots_of_unity() -> &'static [BigUint] {
+    static ROOTS: OnceLock<Vec<BigUint>> = OnceLock::new();
+    ROOTS.get_or_init(|| {
+        let modulus = bls_modulus();
+        let log_width = BLOB_WIDTH.trailing_zeros();
+        let omega = BigUint::from(PRIMITIVE_ROOT_OF_UNITY)
+            .modpow(&((modulus - 1u64) / BLOB_WIDTH), modulus);
+        (0..BLOB_WIDTH)
+            .map(|i| {
+                let exponent = i.reverse_bits() >> (usize::BITS - log_width);
+                omega.modpow(&BigUint::from(exponent), modulus)
+            })
+            .collect()
+    })
+}
+
+/// The trusted setup of the Ethereum KZG ceremony, as bundled with revm.
+fn kzg_settings() -> &'static KzgSettings {
+    static SETTINGS: OnceLock<KzgSettings> = OnceLock::new();
+    SETTINGS.get_or_init(|| {
+        KzgSettings::load_trusted_setup(G1_POINTS.as_ref(), G2_POINTS.as_ref())
+            .expect("bundled trusted setup is valid")
+    })
+}
+
+pub(crate) fn u256_to_biguint(value: &U256) -> BigUint {
+    BigUint::from_bytes_be(&value.to_be_bytes())
+}
+
+pub(crate) fn biguint_to_u256(value: &BigUint) -> U256 {
+    U256::from_big_endian(&value.to_bytes_be())
+}
+
+#[derive(Default, Debug, Clone)]
+/// The L2 transaction data of the chunks of a batch, serialized into a blob as
+///   num_valid_chunks (u16) || chunk\[0\].size (u32) || ... || chunk\[MAX_AGG_SNARKS-1\].size ||
+///   chunk\[0\].tx_bytes || ... || chunk\[k-1\].tx_bytes
+/// zero padded to [`N_BLOB_BYTES`], where the tx bytes of a chunk are the signed RLP of its L2
+/// transactions.
+pub struct BlobData {
+    /// the tx bytes of each of the valid chunks
+    pub(crate) chunk_tx_bytes: Vec<Vec<u8>>,
+}
+
+impl BlobData {
+    /// Build the blob data from the tx bytes of the valid chunks of a batch.
+    pub fn new(chunk_tx_bytes: &[Vec<u8>]) -> Self {
+        assert!(
+            chunk_tx_bytes.len() <= MAX_AGG_SNARKS,
+            "a batch has at most {MAX_AGG_SNARKS} chunks"
+        );
+        let n_bytes = chunk_tx_bytes.iter().map(Vec::len).sum::<usize>();
+        assert!(
+            N_BLOB_METADATA_BYTES + n_bytes <= N_BLOB_BYTES,
+            "{n_bytes} bytes of tx data do not fit into a blob"
+        );
+
+        Self {
+            chunk_tx_bytes: chunk_tx_bytes.to_vec(),
+        }
+    }
+
+    /// The serialized blob data, including the metadata and the zero padding.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut chunk_sizes = [0u32; MAX_AGG_SNARKS];
+        for (size, tx_bytes) in chunk_sizes.iter_mut().zip(self.chunk_tx_bytes.iter()) {
+            *size = tx_bytes.len() as u32;
+        }
+
+        let mut bytes = iter::empty()
+            .chain((self.chunk_tx_bytes.len() as u16).to_be_bytes())
+            .chain(chunk_sizes.iter().flat_map(|size| size.to_be_bytes()))
+            .chain(self.chunk_tx_bytes.iter().flatten().cloned())
+            .collect::<Vec<u8>>();
+        bytes.resize(N_BLOB_BYTES, 0);
+        bytes
+    }
+
+    /// The field elements of the blob, i.e. the evaluations of the blob polynomial over the
+    /// evaluation domain.
+    pub fn coefficients(&self) -> Vec<U256> {
+        self.to_bytes()
+            .chunks(N_BYTES_PER_BLOB_FIELD)
+            .map(U256::from_big_endian)
+            .collect()
+    }
+
+    /// The blob in the format of EIP-4844, each field element is 32 bytes in big-endian.
+    pub fn to_blob(&self) -> Blob {
+        let bytes = self
+            .to_bytes()
+            .chunks(N_BYTES_PER_BLOB_FIELD)
+            .flat_map(|chunk| iter::once(0).chain(chunk.iter().cloned()))
+            .collect::<Vec<u8>>();
+        debug_assert_eq!(bytes.len(), BYTES_PER_BLOB);
+        Blob::from_bytes(&bytes).expect("blob has the right size")
+    }
+
+    /// The KZG commitment of the blob.
+    pub fn kzg_commitment(&self) -> KzgCommitment {
+        KzgCommitment::blob_to_kzg_commitment(&self.to_blob(), kzg_settings())
+            .expect("blob is canonical")
+    }
+
+    /// The versioned hash of the blob, i.e.
+    ///   VERSIONED_HASH_VERSION_KZG || sha256(kzg_commitment)\[1..\]
+    pub fn versioned_hash(&self) -> H256 {
+        let mut hash: [u8; 32] = Sha256::digest(self.kzg_commitment().as_slice()).into();
+        hash[0] = VERSIONED_HASH_VERSION_KZG;
+        hash.into()
+    }
+
+    /// The keccak of the serialized blob data, which prefixes the batch data hash preimage in the
+    /// blob DA mode.
+    pub fn data_hash(&self) -> H256 {
+        keccak256(self.to_bytes()).into()
+    }
+
+    /// The challenge point at which the blob polynomial is evaluated:
+    ///   keccak(batch_data_hash || versioned_hash) mod BLS_MODULUS
+    ///
+    /// In the blob DA mode the batch data hash commits the blob data, so the challenge is only
+    /// known once both the blob and its KZG commitment are fixed.
+    pub fn challenge_point(&self, batch_data_hash: &H256) -> U256 {
+        challenge_point(&challenge_digest(batch_data_hash, &self.versioned_hash()))
+    }
+
+    /// Evaluate the blob polynomial at the given point with the barycentric formula
+    ///   p(z) = (z^N - 1) / N * sum_i f_i * w_i / (z - w_i)
+    /// where N is the blob width and w_i is the i-th root of unity in bit-reversed order.
+    pub fn evaluate(&self, z: &U256) -> U256 {
+        let modulus = bls_modulus();
+        let z = u256_to_biguint(z);
+        assert!(&z < modulus, "challenge point is not in the scalar field");
+
+        let coefficients = self.coefficients();
+        let roots = roots_of_unity();
+        if let Some(i) = roots.iter().position(|root| root == &z) {
+            return coefficients[i];
+        }
+
+        let sum = coefficients.iter().zip(roots.iter()).fold(
+            BigUint::from(0u64),
+            |acc, (coefficient, root)| {
+                let denominator = (&z + modulus - root) % modulus;
+                let inverse = denominator.modpow(&(modulus - 2u64), modulus);
+                (acc + u256_to_biguint(coefficient) * root * inverse) % modulus
+            },
+        );
+        let width = BigUint::from(BLOB_WIDTH);
+        let width_inverse = width.modpow(&(modulus - 2u64), modulus);
+        let vanishing = (z.modpow(&width, modulus) + modulus - 1u64) % modulus;
+
+        biguint_to_u256(&(sum * vanishing % modulus * width_inverse % modulus))
+    }
+
+    /// Compute the point evaluation of the blob at the challenge point of the batch.
+    pub fn point_evaluation(&self, batch_data_hash: &H256) -> PointEvaluation {
+        let versioned_hash = self.versioned_hash();
+        let challenge_digest = challenge_digest(batch_data_hash, &versioned_hash);
+        let challenge = challenge_point(&challenge_digest);
+        let evaluation = self.evaluate(&challenge);
+        PointEvaluation {
+            versioned_hash,
+            challenge_digest,
+            challenge,
+            evaluation,
+        }
+    }
+
+    /// The KZG proof of the evaluation of the blob polynomial at the given point, along with the
+    /// evaluation, as consumed by the point evaluation precompile.
+    pub fn kzg_proof(&self, z: &U256) -> (KzgProof, U256) {
+        let (proof, y) = KzgProof::compute_kzg_proof(
+            &self.to_blob(),
+            &Bytes32::new(z.to_be_bytes()),
+            kzg_settings(),
+        )
+        .expect("challenge point is in the scalar field");
+        (proof, U256::from_big_endian(y.as_slice()))
+    }
+}
+
+/// The preimage of the challenge digest: batch_data_hash || versioned_hash.
+pub(crate) fn challenge_preimage(batch_data_hash: &H256, versioned_hash: &H256) -> Vec<u8> {
+    [batch_data_hash.as_bytes(), versioned_hash.as_bytes()].concat()
+}
+
+fn challenge_digest(batch_data_hash: &H256, versioned_hash: &H256) -> U256 {
+    U256::from_big_endian(&keccak256(challenge_preimage(
+        batch_data_hash,
+        versioned_hash,
+    )))
+}
+
+fn challenge_point(challenge_digest: &U256) -> U256 {
+    biguint_to_u256(&(u256_to_biguint(challenge_digest) % bls_modulus()))
+}
+
+#[derive(Default, Debug, Clone, Copy)]
+/// The evaluation of a blob polynomial at the challenge point of a batch.
+pub struct PointEvaluation {
+    /// the versioned hash of the blob
+    pub versioned_hash: H256,
+    /// keccak(batch_data_hash || versioned_hash), reduced into the challenge point
+    pub challenge_digest: U256,
+    /// the challenge point z
+    pub challenge: U256,
+    /// the evaluation y = p(z)
+    pub evaluation: U256,
+}
@@ -1,6 +1,6 @@
 //! This module implements `Chunk` related data types.
 //! A chunk is a list of blocks.
-use eth_types::{ToBigEndian, H256};
+use eth_types::{l2_types::L1QueueRange, ToBigEndian, H256};
 use ethers_core::utils::keccak256;
 use halo2_proofs::halo2curves::bn256::Fr;
 use serde::{Deserialize, Serialize};
@@ -14,6 +14,8 @@ use zkevm_circuits::witness::Block;
 /// - state root after this chunk
 /// - the withdraw root after this chunk
 /// - the data hash of this chunk
+/// - the range of the L1 message queue popped by this chunk, and the hash of its skipped messages
+///   bitmap
 /// - if the chunk is padded (en empty but valid chunk that is padded for aggregation)
 pub struct ChunkHash {
     /// Chain identifier
@@ -26,6 +28,15 @@ pub struct ChunkHash {
     pub withdraw_root: H256,
     /// the data hash of this chunk
     pub data_hash: H256,
+    /// queue index of the first L1 message popped by this chunk
+    #[serde(default)]
+    pub start_l1_queue_index: u64,
+    /// queue index following the last L1 message popped by this chunk
+    #[serde(default)]
+    pub end_l1_queue_index: u64,
+    /// keccak of the bitmap of the L1 messages skipped by this chunk
+    #[serde(default)]
+    pub skipped_l1_msgs_hash: H256,
     /// if the chunk is a padded chunk
     pub is_padding: bool,
 }
@@ -90,16 +101,41 @@ impl ChunkHash {
             .map(|(_, b_ctx)| b_ctx.eth_block.state_root)
             .unwrap_or(H256(block.prev_state_root.to_be_bytes()));
 
+        let l1_queue_range = L1QueueRange::new(
+            block.start_l1_queue_index,
+            block
+                .txs
+                .iter()
+                .filter(|tx| tx.tx_type.is_l1_msg())
+                .map(|tx| tx.nonce),
+            block.circuits_params.max_l1_msgs_popped as u64,
+        )
+        .expect("checked by block_convert");
+
         Self {
             chain_id: block.chain_id,
             prev_state_root: H256(block.prev_state_root.to_be_bytes()),
             post_state_root,
             withdraw_root: H256(block.withdraw_root.to_be_bytes()),
             data_hash,
+            start_l1_queue_index: l1_queue_range.start_queue_index,
+            end_l1_queue_index: l1_queue_range.end_queue_index,
+            skipped_l1_msgs_hash: l1_queue_range.skipped_bitmap_hash(),
             is_padding,
         }
     }
 
+    /// The tx bytes of a chunk, posted in the blob in the blob DA mode: the concatenated signed
+    /// RLP of the chunk's L2 transactions. The L1 messages are already available on L1.
+    pub fn tx_bytes_from_witness_block(block: &Block<Fr>) -> Vec<u8> {
+        block
+            .txs
+            .iter()
+            .filter(|tx| !tx.tx_type.is_l1_msg())
+            .flat_map(|tx| tx.rlp_signed.iter().cloned())
+            .collect()
+    }
+
     /// Sample a chunk hash from random (for testing)
     #[cfg(test)]
     pub(crate) fn mock_random_chunk_hash_for_testing<R: rand::RngCore>(r: &mut R) -> Self {
@@ -111,12 +147,19 @@ impl ChunkHash {
         r.fill_bytes(&mut withdraw_root);
         let mut data_hash = [0u8; 32];
         r.fill_bytes(&mut data_hash);
+        let start_l1_queue_index = r.next_u32() as u64;
+        let end_l1_queue_index = start_l1_queue_index + (r.next_u32() % 16) as u64;
+        let mut skipped_l1_msgs_hash = [0u8; 32];
+        r.fill_bytes(&mut skipped_l1_msgs_hash);
         Self {
             chain_id: 0,
             prev_state_root: prev_state_root.into(),
             post_state_root: post_state_root.into(),
             withdraw_root: withdraw_root.into(),
             data_hash: data_hash.into(),
+            start_l1_queue_index,
+            end_l1_queue_index,
+            skipped_l1_msgs_hash: skipped_l1_msgs_hash.into(),
             is_padding: false,
         }
     }
@@ -134,19 +177,24 @@ impl ChunkHash {
             post_state_root: previous_chunk.post_state_root,
             withdraw_root: previous_chunk.withdraw_root,
             data_hash: previous_chunk.data_hash,
+            start_l1_queue_index: previous_chunk.start_l1_queue_index,
+            end_l1_queue_index: previous_chunk.end_l1_queue_index,
+            skipped_l1_msgs_hash: previous_chunk.skipped_l1_msgs_hash,
             is_padding: true,
         }
     }
 
     /// Public input hash for a given chunk is defined as
-    ///  keccak( chain id || prev state root || post state root || withdraw root || data hash )
+    ///  keccak( chain id || prev state root || post state root || withdraw root || data hash ||
+    ///  start l1 queue index || end l1 queue index || skipped l1 msgs hash )
     pub fn public_input_hash(&self) -> H256 {
         let preimage = self.extract_hash_preimage();
         keccak256::<&[u8]>(preimage.as_ref()).into()
     }
 
     /// Extract the preimage for the hash
-    ///  chain id || prev state root || post state root || withdraw root || data hash
+    ///  chain id || prev state root || post state root || withdraw root || data hash ||
+    ///  start l1 queue index || end l1 queue index || skipped l1 msgs hash
     pub fn extract_hash_preimage(&self) -> Vec<u8> {
         [
             self.chain_id.to_be_bytes().as_ref(),
@@ -154,6 +202,9 @@ impl ChunkHash {
             self.post_state_root.as_bytes(),
             self.withdraw_root.as_bytes(),
             self.data_hash.as_bytes(),
+            self.start_l1_queue_index.to_be_bytes().as_ref(),
+            self.end_l1_queue_index.to_be_bytes().as_ref(),
+            self.skipped_l1_msgs_hash.as_bytes(),
         ]
         .concat()
     }
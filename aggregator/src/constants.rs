@@ -26,11 +26,29 @@ pub(crate) const LOG_DEGREE: u32 = 19;
 // - withdraw_root      32 bytes
 // - chunk_data_hash    32 bytes
 //
+// and for the chunks only, followed by
+// - start_l1_queue_index   8 bytes
+// - end_l1_queue_index     8 bytes
+// - skipped_l1_msgs_hash   32 bytes
+//
+// and for the batch in the blob DA mode only, followed by
+// - versioned_hash         32 bytes
+//
 
 pub(crate) const PREV_STATE_ROOT_INDEX: usize = 8;
 pub(crate) const POST_STATE_ROOT_INDEX: usize = 40;
 pub(crate) const WITHDRAW_ROOT_INDEX: usize = 72;
 pub(crate) const CHUNK_DATA_HASH_INDEX: usize = 104;
+pub(crate) const START_L1_QUEUE_INDEX_INDEX: usize = 136;
+pub(crate) const END_L1_QUEUE_INDEX_INDEX: usize = 144;
+pub(crate) const VERSIONED_HASH_INDEX: usize = 136;
+
+/// Length of a chunk's public input hash preimage
+pub(crate) const CHUNK_PI_HASH_PREIMAGE_LEN: usize = 184;
+/// Length of a batch's public input hash preimage in the blob DA mode
+pub(crate) const BLOB_BATCH_PI_HASH_PREIMAGE_LEN: usize = 168;
+/// Length of the preimage of the challenge digest: batch_data_hash || versioned_hash
+pub(crate) const CHALLENGE_PREIMAGE_LEN: usize = 64;
 
 // ================================
 // aggregator parameters
@@ -39,6 +57,9 @@ pub(crate) const CHUNK_DATA_HASH_INDEX: usize = 104;
 /// An decomposed accumulator consists of 12 field elements
 pub(crate) const ACC_LEN: usize = 12;
 
+/// In the blob DA mode, the challenge point and the blob evaluation are exposed as 32 bytes each
+pub(crate) const BLOB_POINT_EVALUATION_LEN: usize = 64;
+
 /// number of limbs when decomposing a field element in the ECC chip
 pub(crate) const LIMBS: usize = 3;
 /// number of bits in each limb in the ECC chip
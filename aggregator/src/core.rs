@@ -33,13 +33,18 @@ use zkevm_circuits::{
 };
 
 use crate::{
-    constants::{CHAIN_ID_LEN, DIGEST_LEN, INPUT_LEN_PER_ROUND, LOG_DEGREE, MAX_AGG_SNARKS},
+    constants::{
+        CHAIN_ID_LEN, DIGEST_LEN, INPUT_LEN_PER_ROUND, LOG_DEGREE, MAX_AGG_SNARKS,
+        VERSIONED_HASH_INDEX,
+    },
     util::{
-        assert_conditional_equal, assert_equal, assert_exist, get_indices, get_max_keccak_updates,
+        assert_conditional_equal, assert_equal, assert_exist, digest_byte_index,
+        get_blob_data_hash_keccak_updates, get_blob_keccak_updates, get_indices,
+        get_max_keccak_updates, parse_blob_hash_digest_cells, parse_blob_hash_preimage_cells,
         parse_hash_digest_cells, parse_hash_preimage_cells, parse_pi_hash_rlc_cells,
     },
-    AggregationConfig, RlcConfig, BITS, CHUNK_DATA_HASH_INDEX, LIMBS, POST_STATE_ROOT_INDEX,
-    PREV_STATE_ROOT_INDEX, WITHDRAW_ROOT_INDEX,
+    AggregationConfig, RlcConfig, BITS, CHUNK_DATA_HASH_INDEX, END_L1_QUEUE_INDEX_INDEX, LIMBS,
+    POST_STATE_ROOT_INDEX, PREV_STATE_ROOT_INDEX, START_L1_QUEUE_INDEX_INDEX, WITHDRAW_ROOT_INDEX,
 };
 
 /// Subroutine for the witness generations.
@@ -148,11 +153,11 @@ pub fn extract_proof_and_instances_with_pairing_check(
 
 #[derive(Default)]
 pub(crate) struct ExtractedHashCells {
-    hash_input_cells: Vec<AssignedCell<Fr, Fr>>,
-    hash_output_cells: Vec<AssignedCell<Fr, Fr>>,
-    data_rlc_cells: Vec<AssignedCell<Fr, Fr>>,
-    hash_input_len_cells: Vec<AssignedCell<Fr, Fr>>,
-    is_final_cells: Vec<AssignedCell<Fr, Fr>>,
+    pub(crate) hash_input_cells: Vec<AssignedCell<Fr, Fr>>,
+    pub(crate) hash_output_cells: Vec<AssignedCell<Fr, Fr>>,
+    pub(crate) data_rlc_cells: Vec<AssignedCell<Fr, Fr>>,
+    pub(crate) hash_input_len_cells: Vec<AssignedCell<Fr, Fr>>,
+    pub(crate) is_final_cells: Vec<AssignedCell<Fr, Fr>>,
 }
 
 /// Input the hash input bytes,
@@ -168,44 +173,63 @@ pub(crate) struct ExtractedHashCells {
 // 2.2. batch_pi_hash and chunk[MAX_AGG_SNARKS-1] use a same post_state_root
 // 2.3. batch_pi_hash and chunk[MAX_AGG_SNARKS-1] use a same withdraw_root
 // 3. batch_data_hash and chunk[i].pi_hash use a same chunk[i].data_hash when chunk[i] is not padded
-// 4. chunks are continuous: they are linked via the state roots
+// 4. chunks are continuous: they are linked via the state roots and the L1
+// message queue indices
 // 5. batch and all its chunks use a same chain id
 // 6. chunk[i]'s chunk_pi_hash_rlc_cells == chunk[i-1].chunk_pi_hash_rlc_cells when chunk[i] is
 // padded
 // 7. the hash input length are correct
-// - first MAX_AGG_SNARKS + 1 hashes all have 136 bytes input
-// - batch's data_hash length is 32 * number_of_valid_snarks
+// - batch_pi_hash has 136 bytes input, or 168 bytes in the blob DA mode
+// - the next MAX_AGG_SNARKS chunk_pi_hash have 184 bytes input
+// - in the blob DA mode, the blob data hash has N_BLOB_BYTES bytes input and the challenge digest
+// has 64 bytes input
+// - batch's data_hash length is 32 * number_of_data_hashes
 // 8. batch data hash is correct w.r.t. its RLCs
 // 9. is_final_cells are set correctly
+// 10. in the blob DA mode, the blob data hash is the first data hash of the batch data hash
+// 11. in the blob DA mode, the challenge digest hashes the batch data hash and the versioned hash
+// of the batch public input hash
+//
+// where number_of_data_hashes is the number of valid snarks, plus one for the blob data hash in
+// the blob DA mode
 pub(crate) fn assign_batch_hashes(
     config: &AggregationConfig,
     layouter: &mut impl Layouter<Fr>,
     challenges: Challenges<Value<Fr>>,
     chunks_are_valid: &[bool],
     preimages: &[Vec<u8>],
-) -> Result<Vec<AssignedCell<Fr, Fr>>, Error> {
+    with_blob: bool,
+) -> Result<ExtractedHashCells, Error> {
     let extracted_hash_cells = extract_hash_cells(
         &config.keccak_circuit_config,
         layouter,
         challenges,
         preimages,
+        with_blob,
     )?;
     // 2. batch_pi_hash used same roots as chunk_pi_hash
     // 2.1. batch_pi_hash and chunk[0] use a same prev_state_root
     // 2.2. batch_pi_hash and chunk[MAX_AGG_SNARKS-1] use a same post_state_root
     // 2.3. batch_pi_hash and chunk[MAX_AGG_SNARKS-1] use a same withdraw_root
     // 5. batch and all its chunks use a same chain id
-    copy_constraints(layouter, &extracted_hash_cells.hash_input_cells)?;
+    // 10. in the blob DA mode, the blob data hash is the first data hash of the batch data hash
+    // 11. in the blob DA mode, the challenge digest hashes the batch data hash and the versioned
+    // hash of the batch public input hash
+    copy_constraints(layouter, &extracted_hash_cells, with_blob)?;
 
     // 1. batch_data_hash digest is reused for public input hash
     // 3. batch_data_hash and chunk[i].pi_hash use a same chunk[i].data_hash when chunk[i] is not
     // padded
-    // 4. chunks are continuous: they are linked via the state roots
+    // 4. chunks are continuous: they are linked via the state roots and the L1
+    // message queue indices
     // 6. chunk[i]'s chunk_pi_hash_rlc_cells == chunk[i-1].chunk_pi_hash_rlc_cells when chunk[i] is
     // padded
     // 7. the hash input length are correct
-    // - first MAX_AGG_SNARKS + 1 hashes all have 136 bytes input
-    // - batch's data_hash length is 32 * number_of_valid_snarks
+    // - batch_pi_hash has 136 bytes input, or 168 bytes in the blob DA mode
+    // - the next MAX_AGG_SNARKS chunk_pi_hash have 184 bytes input
+    // - in the blob DA mode, the blob data hash has N_BLOB_BYTES bytes input and the challenge
+    // digest has 64 bytes input
+    // - batch's data_hash length is 32 * number_of_data_hashes
     // 8. batch data hash is correct w.r.t. its RLCs
     // 9. is_final_cells are set correctly
     conditional_constraints(
@@ -214,9 +238,10 @@ pub(crate) fn assign_batch_hashes(
         challenges,
         chunks_are_valid,
         &extracted_hash_cells,
+        with_blob,
     )?;
 
-    Ok(extracted_hash_cells.hash_output_cells)
+    Ok(extracted_hash_cells)
 }
 
 pub(crate) fn extract_hash_cells(
@@ -224,10 +249,11 @@ pub(crate) fn extract_hash_cells(
     layouter: &mut impl Layouter<Fr>,
     challenges: Challenges<Value<Fr>>,
     preimages: &[Vec<u8>],
+    with_blob: bool,
 ) -> Result<ExtractedHashCells, Error> {
     let mut is_first_time = true;
     let keccak_capacity = KeccakCircuit::<Fr>::capacity_for_row(1 << LOG_DEGREE);
-    let max_keccak_updates = get_max_keccak_updates(MAX_AGG_SNARKS);
+    let max_keccak_updates = get_max_keccak_updates(MAX_AGG_SNARKS, with_blob);
     let keccak_f_rows = keccak_packed_multi::get_num_rows_per_update();
 
     let timer = start_timer!(|| ("multi keccak").to_string());
@@ -237,20 +263,28 @@ pub(crate) fn extract_hash_cells(
     //      chunk[0].prev_state_root ||
     //      chunk[k-1].post_state_root ||
     //      chunk[k-1].withdraw_root ||
-    //      batch_data_hash)
+    //      batch_data_hash ||
+    //      versioned_hash), where the versioned hash is only present in the blob DA mode
     // (2) chunk[i].piHash preimage =
     //      (chain id ||
     //      chunk[i].prevStateRoot || chunk[i].postStateRoot ||
-    //      chunk[i].withdrawRoot || chunk[i].datahash)
-    // (3) batchDataHash preimage =
-    //      (chunk[0].dataHash || ... || chunk[k-1].dataHash)
+    //      chunk[i].withdrawRoot || chunk[i].datahash ||
+    //      chunk[i].startL1QueueIndex || chunk[i].endL1QueueIndex ||
+    //      chunk[i].skippedL1MsgsHash)
+    // (3) in the blob DA mode, the blob data hash preimage =
+    //      (blob data)
+    //      and the challenge digest preimage =
+    //      (batch_data_hash || versioned_hash)
+    // (4) batchDataHash preimage =
+    //      (blob_data_hash || chunk[0].dataHash || ... || chunk[k-1].dataHash), where the blob
+    //      data hash is only present in the blob DA mode
     // each part of the preimage is mapped to image by Keccak256
     let witness = multi_keccak(preimages, challenges, keccak_capacity)
         .map_err(|e| Error::AssertionFailure(format!("multi keccak assignment failed: {e:?}")))?;
     end_timer!(timer);
 
     // extract the indices of the rows for which the preimage and the digest cells lie in
-    let (preimage_indices, digest_indices) = get_indices(preimages);
+    let (preimage_indices, digest_indices) = get_indices(preimages, with_blob);
 
     let extracted_hash_cells = layouter
         .assign_region(
@@ -316,7 +350,10 @@ pub(crate) fn extract_hash_cells(
                     hash_input_cells.len(),
                     max_keccak_updates * INPUT_LEN_PER_ROUND
                 );
-                assert_eq!(hash_output_cells.len(), (MAX_AGG_SNARKS + 5) * DIGEST_LEN);
+                assert_eq!(
+                    hash_output_cells.len(),
+                    (MAX_AGG_SNARKS + 5 + 2 * with_blob as usize) * DIGEST_LEN
+                );
 
                 keccak_config
                     .keccak_table
@@ -346,10 +383,19 @@ pub(crate) fn extract_hash_cells(
 // 2.2. batch_pi_hash and chunk[MAX_AGG_SNARKS-1] use a same post_state_root
 // 2.3. batch_pi_hash and chunk[MAX_AGG_SNARKS-1] use a same withdraw_root
 // 5. batch and all its chunks use a same chain id
+// 10. in the blob DA mode, the blob data hash is the first data hash of the batch data hash
+// 11. in the blob DA mode, the challenge digest hashes the batch data hash and the versioned hash
+// of the batch public input hash
 fn copy_constraints(
     layouter: &mut impl Layouter<Fr>,
-    hash_input_cells: &[AssignedCell<Fr, Fr>],
+    extracted_hash_cells: &ExtractedHashCells,
+    with_blob: bool,
 ) -> Result<(), Error> {
+    let ExtractedHashCells {
+        hash_input_cells,
+        hash_output_cells,
+        ..
+    } = extracted_hash_cells;
     let mut is_first_time = true;
 
     layouter
@@ -369,8 +415,8 @@ fn copy_constraints(
                 let (
                     batch_pi_hash_preimage,
                     chunk_pi_hash_preimages,
-                    _potential_batch_data_hash_preimage,
-                ) = parse_hash_preimage_cells(hash_input_cells);
+                    potential_batch_data_hash_preimage,
+                ) = parse_hash_preimage_cells(hash_input_cells, with_blob);
 
                 // ====================================================
                 // Constraint the relations between hash preimages
@@ -393,7 +439,10 @@ fn copy_constraints(
                 //        chunk[i].prevStateRoot ||
                 //        chunk[i].postStateRoot ||
                 //        chunk[i].withdrawRoot  ||
-                //        chunk[i].datahash)
+                //        chunk[i].datahash ||
+                //        chunk[i].startL1QueueIndex ||
+                //        chunk[i].endL1QueueIndex ||
+                //        chunk[i].skippedL1MsgsHash)
                 //
                 // PREV_STATE_ROOT_INDEX, POST_STATE_ROOT_INDEX, WITHDRAW_ROOT_INDEX
                 // used below are byte positions for
@@ -472,6 +521,57 @@ fn copy_constraints(
                         region.constrain_equal(lhs.cell(), rhs.cell())?;
                     }
                 }
+
+                if with_blob {
+                    let (blob_data_hash_digest, _challenge_digest) =
+                        parse_blob_hash_digest_cells(hash_output_cells);
+                    let (_blob_data, challenge_preimage) =
+                        parse_blob_hash_preimage_cells(hash_input_cells);
+
+                    // 10 the blob data hash is the first data hash of the batch data hash
+                    //
+                    // batchDataHash =
+                    //   keccak(blob_data_hash || chunk[0].dataHash || ... || chunk[k-1].dataHash)
+                    for i in 0..DIGEST_LEN {
+                        let lhs = &potential_batch_data_hash_preimage[i];
+                        let rhs = &blob_data_hash_digest[digest_byte_index(i)];
+                        // sanity check
+                        assert_equal(
+                            lhs,
+                            rhs,
+                            format!(
+                                "blob data hash does not match batch's: {:?} {:?}",
+                                &lhs.value(),
+                                &rhs.value(),
+                            )
+                            .as_str(),
+                        )?;
+                        region.constrain_equal(lhs.cell(), rhs.cell())?;
+                    }
+
+                    // 11 the challenge digest hashes the batch data hash and the versioned hash
+                    //
+                    // challenge_digest = keccak(batch_data_hash || versioned_hash)
+                    for (i, rhs) in challenge_preimage.iter().enumerate() {
+                        let lhs = if i < DIGEST_LEN {
+                            &batch_pi_hash_preimage[i + CHUNK_DATA_HASH_INDEX]
+                        } else {
+                            &batch_pi_hash_preimage[i - DIGEST_LEN + VERSIONED_HASH_INDEX]
+                        };
+                        // sanity check
+                        assert_equal(
+                            lhs,
+                            rhs,
+                            format!(
+                                "challenge preimage does not match batch's: {:?} {:?}",
+                                &lhs.value(),
+                                &rhs.value(),
+                            )
+                            .as_str(),
+                        )?;
+                        region.constrain_equal(lhs.cell(), rhs.cell())?;
+                    }
+                }
                 Ok(())
             },
         )
@@ -483,12 +583,16 @@ fn copy_constraints(
 // This function asserts the following constraints on the hashes
 // 1. batch_data_hash digest is reused for public input hash
 // 3. batch_data_hash and chunk[i].pi_hash use a same chunk[i].data_hash when chunk[i] is not padded
-// 4. chunks are continuous: they are linked via the state roots
+// 4. chunks are continuous: they are linked via the state roots and the L1
+// message queue indices
 // 6. chunk[i]'s chunk_pi_hash_rlc_cells == chunk[i-1].chunk_pi_hash_rlc_cells when chunk[i] is
 // padded
 // 7. the hash input length are correct
-// - first MAX_AGG_SNARKS + 1 hashes all have 136 bytes input
-// - batch's data_hash length is 32 * number_of_valid_snarks
+// - batch_pi_hash has 136 bytes input, or 168 bytes in the blob DA mode
+// - the next MAX_AGG_SNARKS chunk_pi_hash have 184 bytes input
+// - in the blob DA mode, the blob data hash has N_BLOB_BYTES bytes input and the challenge digest
+// has 64 bytes input
+// - batch's data_hash length is 32 * number_of_data_hashes
 // 8. batch data hash is correct w.r.t. its RLCs
 // 9. is_final_cells are set correctly
 pub(crate) fn conditional_constraints(
@@ -497,8 +601,14 @@ pub(crate) fn conditional_constraints(
     challenges: Challenges<Value<Fr>>,
     chunks_are_valid: &[bool],
     extracted_hash_cells: &ExtractedHashCells,
+    with_blob: bool,
 ) -> Result<(), Error> {
     let mut first_pass = halo2_base::SKIP_FIRST_PASS;
+    // in the blob DA mode, the rounds of the blob data hash and of the challenge digest lie in
+    // between the chunk pi hashes and the batch data hash
+    let blob_data_hash_round = MAX_AGG_SNARKS * 2 + 3;
+    let challenge_digest_round = blob_data_hash_round + get_blob_data_hash_keccak_updates();
+    let data_hash_round = blob_data_hash_round + get_blob_keccak_updates(with_blob);
     let ExtractedHashCells {
         hash_input_cells,
         hash_output_cells,
@@ -535,26 +645,40 @@ pub(crate) fn conditional_constraints(
                     constrain_flags(rlc_config, &mut region, &chunk_is_valid_cells, &mut offset)?;
 
                 log::trace!("number of valid chunks: {:?}", num_valid_snarks.value());
+
+                // in the blob DA mode, the blob data hash is hashed along with the chunks' data
+                // hashes
+                let one = {
+                    let one = rlc_config.load_private(&mut region, &Fr::one(), &mut offset)?;
+                    let one_cell = rlc_config.one_cell(one.cell().region_index);
+                    region.constrain_equal(one_cell, one.cell())?;
+                    one
+                };
+                let num_data_hashes = if with_blob {
+                    rlc_config.add(&mut region, &num_valid_snarks, &one, &mut offset)?
+                } else {
+                    num_valid_snarks.clone()
+                };
                 //
-                // if the num_of_valid_snarks <= 4, which only needs 1 keccak-f round. Therefore
+                // if the num_of_data_hashes <= 4, which only needs 1 keccak-f round. Therefore
                 // the batch's data hash (input, len, data_rlc, output_rlc) are in the first 300
                 // keccak rows;
                 //
-                // else if the num_of_valid_snarks <= 8, which needs
+                // else if the num_of_data_hashes <= 8, which needs
                 // 2 keccak-f rounds. Therefore the batch's data hash (input, len, data_rlc,
                 // output_rlc) are in the 2nd 300 keccak rows;
                 //
                 // else if the
-                // num_of_valid_snarks <= 12, which needs 3 keccak-f rounds. Therefore the batch's
+                // num_of_data_hashes <= 12, which needs 3 keccak-f rounds. Therefore the batch's
                 // data hash (input, len, data_rlc, output_rlc) are in the 3rd 300 keccak rows;
                 //
                 // else if the
-                // num_of_valid_snarks <= 16, which needs 4 keccak-f rounds. Therefore the batch's
+                // num_of_data_hashes <= 16, which needs 4 keccak-f rounds. Therefore the batch's
                 // data hash (input, len, data_rlc, output_rlc) are in the 4th 300 keccak rows;
                 //
                 // the following flag is build to indicate which row the final data_rlc exists
                 //
-                // #valid snarks | offset of data hash | flags
+                // #data hashes  | offset of data hash | flags
                 // 1,2,3,4       | 0                   | 1, 0, 0, 0
                 // 5,6,7,8       | 32                  | 0, 1, 0, 0
                 // 9,10,11,12    | 64                  | 0, 0, 1, 0
@@ -582,21 +706,21 @@ pub(crate) fn conditional_constraints(
 
                 let smaller_or_eq_4 = rlc_config.is_smaller_than(
                     &mut region,
-                    &num_valid_snarks,
+                    &num_data_hashes,
                     &five,
                     &mut offset,
                 )?;
                 let greater_than_4 = rlc_config.not(&mut region, &smaller_or_eq_4, &mut offset)?;
                 let smaller_or_eq_8 = rlc_config.is_smaller_than(
                     &mut region,
-                    &num_valid_snarks,
+                    &num_data_hashes,
                     &nine,
                     &mut offset,
                 )?;
                 let greater_than_8 = rlc_config.not(&mut region, &smaller_or_eq_8, &mut offset)?;
                 let smaller_or_eq_12 = rlc_config.is_smaller_than(
                     &mut region,
-                    &num_valid_snarks,
+                    &num_data_hashes,
                     &thirteen,
                     &mut offset,
                 )?;
@@ -625,14 +749,14 @@ pub(crate) fn conditional_constraints(
                     batch_pi_hash_preimage,
                     chunk_pi_hash_preimages,
                     potential_batch_data_hash_preimage,
-                ) = parse_hash_preimage_cells(hash_input_cells);
+                ) = parse_hash_preimage_cells(hash_input_cells, with_blob);
 
                 // digests
                 let (
                     _batch_pi_hash_digest,
                     _chunk_pi_hash_digests,
                     potential_batch_data_hash_digest,
-                ) = parse_hash_digest_cells(hash_output_cells);
+                ) = parse_hash_digest_cells(hash_output_cells, with_blob);
                 // ====================================================
                 // start the actual statements
                 // ====================================================
@@ -650,12 +774,14 @@ pub(crate) fn conditional_constraints(
                 //      chunk[0].prev_state_root ||
                 //      chunk[k-1].post_state_root ||
                 //      chunk[k-1].withdraw_root ||
-                //      batch_data_hash )
+                //      batch_data_hash [||
+                //      versioned_hash] )
                 //
-                // batchDataHash = keccak(chunk[0].dataHash || ... || chunk[k-1].dataHash)
+                // batchDataHash =
+                //   keccak([blob_data_hash ||] chunk[0].dataHash || ... || chunk[k-1].dataHash)
                 //
                 //
-                // #valid snarks | offset of data hash | flags
+                // #data hashes  | offset of data hash | flags
                 // 1,2,3,4       | 0                   | 1, 0, 0, 0
                 // 5,6,7,8       | 32                  | 0, 1, 0, 0
                 // 9,10,11,12    | 64                  | 0, 0, 1, 0
@@ -717,7 +843,8 @@ pub(crate) fn conditional_constraints(
                 // 3 batch_data_hash and chunk[i].pi_hash use a same chunk[i].data_hash when
                 // chunk[i] is not padded
                 //
-                // batchDataHash = keccak(chunk[0].dataHash || ... || chunk[k-1].dataHash)
+                // batchDataHash =
+                //   keccak([blob_data_hash ||] chunk[0].dataHash || ... || chunk[k-1].dataHash)
                 //
                 // chunk[i].piHash =
                 //     keccak(
@@ -726,16 +853,19 @@ pub(crate) fn conditional_constraints(
                 //        chunk[i].postStateRoot ||
                 //        chunk[i].withdrawRoot  ||
                 //        chunk[i].datahash)
+                //
+                // in the blob DA mode, the chunks' data hashes follow the blob data hash
+                let chunk_data_hash_offset = DIGEST_LEN * with_blob as usize;
                 for i in 0..MAX_AGG_SNARKS {
                     for j in 0..DIGEST_LEN {
                         assert_conditional_equal(
                             &chunk_pi_hash_preimages[i][j + CHUNK_DATA_HASH_INDEX],
-                            &potential_batch_data_hash_preimage[i * DIGEST_LEN + j],
+                            &potential_batch_data_hash_preimage[chunk_data_hash_offset + i * DIGEST_LEN + j],
                             &chunk_is_valid_cells[i],
                             format!(
                                 "chunk_{i}'s data hash does not match batch's: {:?} {:?} {:?}",
                                 &chunk_pi_hash_preimages[i][j + CHUNK_DATA_HASH_INDEX].value(),
-                                &potential_batch_data_hash_preimage[i * DIGEST_LEN + j].value(),
+                                &potential_batch_data_hash_preimage[chunk_data_hash_offset + i * DIGEST_LEN + j].value(),
                                 &chunk_is_valid_cells[i].value()
                             )
                             .as_str(),
@@ -743,7 +873,7 @@ pub(crate) fn conditional_constraints(
                         rlc_config.conditional_enforce_equal(
                             &mut region,
                             &chunk_pi_hash_preimages[i][j + CHUNK_DATA_HASH_INDEX],
-                            &potential_batch_data_hash_preimage[i * DIGEST_LEN + j],
+                            &potential_batch_data_hash_preimage[chunk_data_hash_offset + i * DIGEST_LEN + j],
                             &chunk_is_valid_cells[i],
                             &mut offset,
                         )?;
@@ -776,6 +906,34 @@ pub(crate) fn conditional_constraints(
                     }
                 }
 
+                // 4  __valid__ chunks are continuous: they consume the L1 message queue
+                // contiguously
+                for i in 0..MAX_AGG_SNARKS - 1 {
+                    for j in 0..8 {
+                        // sanity check
+                        assert_conditional_equal(
+                            &chunk_pi_hash_preimages[i + 1][START_L1_QUEUE_INDEX_INDEX + j],
+                            &chunk_pi_hash_preimages[i][END_L1_QUEUE_INDEX_INDEX + j],
+                            &chunk_is_valid_cells[i + 1],
+                            format!(
+                                "chunk_{i} is not continuous in the l1 message queue: {:?} {:?} {:?}",
+                                &chunk_pi_hash_preimages[i + 1][START_L1_QUEUE_INDEX_INDEX + j]
+                                    .value(),
+                                &chunk_pi_hash_preimages[i][END_L1_QUEUE_INDEX_INDEX + j].value(),
+                                &chunk_is_valid_cells[i + 1].value(),
+                            )
+                            .as_str(),
+                        )?;
+                        rlc_config.conditional_enforce_equal(
+                            &mut region,
+                            &chunk_pi_hash_preimages[i + 1][START_L1_QUEUE_INDEX_INDEX + j],
+                            &chunk_pi_hash_preimages[i][END_L1_QUEUE_INDEX_INDEX + j],
+                            &chunk_is_valid_cells[i + 1],
+                            &mut offset,
+                        )?;
+                    }
+                }
+
                 // 6. chunk[i]'s chunk_pi_hash_rlc_cells == chunk[i-1].chunk_pi_hash_rlc_cells when
                 // chunk[i] is padded
                 let chunks_are_padding = chunk_is_valid_cells
@@ -805,84 +963,111 @@ pub(crate) fn conditional_constraints(
                 }
 
                 // 7. the hash input length are correct
-                // - first MAX_AGG_SNARKS + 1 hashes all have 136 bytes input
-                // - batch's data_hash length is 32 * number_of_valid_snarks
-
-                // - first MAX_AGG_SNARKS + 1 hashes all have 136 bytes input
+                // - batch_pi_hash has 136 bytes input, or 168 bytes in the blob DA mode
+                // - the next MAX_AGG_SNARKS chunk_pi_hash have 184 bytes input
+                // - in the blob DA mode, the blob data hash has N_BLOB_BYTES bytes input and the
+                // challenge digest has 64 bytes input
+                // - batch's data_hash length is 32 * number_of_data_hashes
+
+                // - batch_pi_hash has 136 bytes input, or 168 bytes in the blob DA mode
+                // - the next MAX_AGG_SNARKS chunk_pi_hash have 184 bytes input
                 hash_input_len_cells
                     .iter()
                     .skip(1)
                     .take((MAX_AGG_SNARKS + 1) * 2)
                     .chunks(2)
                     .into_iter()
-                    .try_for_each(|chunk| {
+                    .enumerate()
+                    .try_for_each(|(i, chunk)| {
                         let cur_hash_len = chunk.last().unwrap(); // safe unwrap
+                        let region_index = cur_hash_len.cell().region_index;
                         region.constrain_equal(
                             cur_hash_len.cell(),
-                            rlc_config
-                                .one_hundred_and_thirty_six_cell(cur_hash_len.cell().region_index),
+                            if i == 0 && with_blob {
+                                rlc_config.one_hundred_and_sixty_eight_cell(region_index)
+                            } else if i == 0 {
+                                rlc_config.one_hundred_and_thirty_six_cell(region_index)
+                            } else {
+                                rlc_config.one_hundred_and_eighty_four_cell(region_index)
+                            },
                         )
                     })?;
 
-                // - batch's data_hash length is 32 * number_of_valid_snarks
+                // - in the blob DA mode, the blob data hash has N_BLOB_BYTES bytes input and the
+                // challenge digest has 64 bytes input
+                if with_blob {
+                    let blob_data_hash_len =
+                        &hash_input_len_cells[challenge_digest_round - 1];
+                    region.constrain_equal(
+                        blob_data_hash_len.cell(),
+                        rlc_config.blob_bytes_len_cell(blob_data_hash_len.cell().region_index),
+                    )?;
+                    let challenge_digest_len = &hash_input_len_cells[challenge_digest_round];
+                    region.constrain_equal(
+                        challenge_digest_len.cell(),
+                        rlc_config.sixty_four_cell(challenge_digest_len.cell().region_index),
+                    )?;
+                }
+
+                // - batch's data_hash length is 32 * number_of_data_hashes
                 let const32 = rlc_config.load_private(&mut region, &Fr::from(32), &mut offset)?;
                 let const32_cell = rlc_config.thirty_two_cell(const32.cell().region_index);
                 region.constrain_equal(const32.cell(), const32_cell)?;
                 let data_hash_inputs_len =
-                    rlc_config.mul(&mut region, &num_valid_snarks, &const32, &mut offset)?;
+                    rlc_config.mul(&mut region, &num_data_hashes, &const32, &mut offset)?;
 
                 // sanity check
                 assert_exist(
                     &data_hash_inputs_len,
                     &[
-                        hash_input_len_cells[MAX_AGG_SNARKS * 2 + 3].clone(),
-                        hash_input_len_cells[MAX_AGG_SNARKS * 2 + 4].clone(),
-                        hash_input_len_cells[MAX_AGG_SNARKS * 2 + 5].clone(),
-                        hash_input_len_cells[MAX_AGG_SNARKS * 2 + 6].clone(),
+                        hash_input_len_cells[data_hash_round].clone(),
+                        hash_input_len_cells[data_hash_round + 1].clone(),
+                        hash_input_len_cells[data_hash_round + 2].clone(),
+                        hash_input_len_cells[data_hash_round + 3].clone(),
                     ],
                 )?;
 
                 log::trace!("data_hash_inputs: {:?}", data_hash_inputs_len.value());
                 log::trace!(
                     "candidate 1: {:?}",
-                    hash_input_len_cells[MAX_AGG_SNARKS * 2 + 3].value()
+                    hash_input_len_cells[data_hash_round].value()
                 );
                 log::trace!(
                     "candidate 2: {:?}",
-                    hash_input_len_cells[MAX_AGG_SNARKS * 2 + 4].value()
+                    hash_input_len_cells[data_hash_round + 1].value()
                 );
                 log::trace!(
                     "candidate 3: {:?}",
-                    hash_input_len_cells[MAX_AGG_SNARKS * 2 + 5].value()
+                    hash_input_len_cells[data_hash_round + 2].value()
                 );
                 log::trace!(
                     "candidate 4: {:?}",
-                    hash_input_len_cells[MAX_AGG_SNARKS * 2 + 6].value()
+                    hash_input_len_cells[data_hash_round + 3].value()
                 );
 
                 let mut data_hash_inputs_len_rec = rlc_config.mul(
                     &mut region,
-                    &hash_input_len_cells[MAX_AGG_SNARKS * 2 + 3],
+                    &hash_input_len_cells[data_hash_round],
                     &flag1,
                     &mut offset,
                 )?;
                 data_hash_inputs_len_rec = rlc_config.mul_add(
                     &mut region,
-                    &hash_input_len_cells[MAX_AGG_SNARKS * 2 + 4],
+                    &hash_input_len_cells[data_hash_round + 1],
                     &flag2,
                     &data_hash_inputs_len_rec,
                     &mut offset,
                 )?;
                 data_hash_inputs_len_rec = rlc_config.mul_add(
                     &mut region,
-                    &hash_input_len_cells[MAX_AGG_SNARKS * 2 + 5],
+                    &hash_input_len_cells[data_hash_round + 2],
                     &flag3,
                     &data_hash_inputs_len_rec,
                     &mut offset,
                 )?;
                 data_hash_inputs_len_rec = rlc_config.mul_add(
                     &mut region,
-                    &hash_input_len_cells[MAX_AGG_SNARKS * 2 + 6],
+                    &hash_input_len_cells[data_hash_round + 3],
                     &flag4,
                     &data_hash_inputs_len_rec,
                     &mut offset,
@@ -905,19 +1090,22 @@ pub(crate) fn conditional_constraints(
                 )?;
 
                 // 8. batch data hash is correct w.r.t. its RLCs
-                // batchDataHash = keccak(chunk[0].dataHash || ... || chunk[k-1].dataHash)
+                // batchDataHash =
+                //   keccak([blob_data_hash ||] chunk[0].dataHash || ... || chunk[k-1].dataHash)
                 let challenge_cell =
                     rlc_config.read_challenge(&mut region, challenges, &mut offset)?;
 
-                let flags = chunk_is_valid_cells
-                    .iter()
-                    .flat_map(|cell| vec![cell; 32])
+                // the blob data hash is always hashed in the blob DA mode
+                let blob_flags = if with_blob { vec![&one; 32] } else { vec![] };
+                let flags = blob_flags
+                    .into_iter()
+                    .chain(chunk_is_valid_cells.iter().flat_map(|cell| vec![cell; 32]))
                     .cloned()
                     .collect::<Vec<_>>();
 
                 let rlc_cell = rlc_config.rlc_with_flag(
                     &mut region,
-                    potential_batch_data_hash_preimage[..DIGEST_LEN * MAX_AGG_SNARKS].as_ref(),
+                    potential_batch_data_hash_preimage[..flags.len()].as_ref(),
                     &challenge_cell,
                     &flags,
                     &mut offset,
@@ -926,49 +1114,49 @@ pub(crate) fn conditional_constraints(
                 assert_exist(
                     &rlc_cell,
                     &[
-                        data_rlc_cells[MAX_AGG_SNARKS * 2 + 3].clone(),
-                        data_rlc_cells[MAX_AGG_SNARKS * 2 + 4].clone(),
-                        data_rlc_cells[MAX_AGG_SNARKS * 2 + 5].clone(),
-                        data_rlc_cells[MAX_AGG_SNARKS * 2 + 6].clone(),
+                        data_rlc_cells[data_hash_round].clone(),
+                        data_rlc_cells[data_hash_round + 1].clone(),
+                        data_rlc_cells[data_hash_round + 2].clone(),
+                        data_rlc_cells[data_hash_round + 3].clone(),
                     ],
                 )?;
                 log::trace!("rlc from chip {:?}", rlc_cell.value());
                 log::trace!(
                     "rlc from table {:?}",
-                    data_rlc_cells[MAX_AGG_SNARKS * 2 + 3].value()
+                    data_rlc_cells[data_hash_round].value()
                 );
                 log::trace!(
                     "rlc from table {:?}",
-                    data_rlc_cells[MAX_AGG_SNARKS * 2 + 4].value()
+                    data_rlc_cells[data_hash_round + 1].value()
                 );
                 log::trace!(
                     "rlc from table {:?}",
-                    data_rlc_cells[MAX_AGG_SNARKS * 2 + 5].value()
+                    data_rlc_cells[data_hash_round + 2].value()
                 );
 
                 // assertion
                 let t1 = rlc_config.sub(
                     &mut region,
                     &rlc_cell,
-                    &data_rlc_cells[MAX_AGG_SNARKS * 2 + 3],
+                    &data_rlc_cells[data_hash_round],
                     &mut offset,
                 )?;
                 let t2 = rlc_config.sub(
                     &mut region,
                     &rlc_cell,
-                    &data_rlc_cells[MAX_AGG_SNARKS * 2 + 4],
+                    &data_rlc_cells[data_hash_round + 1],
                     &mut offset,
                 )?;
                 let t3 = rlc_config.sub(
                     &mut region,
                     &rlc_cell,
-                    &data_rlc_cells[MAX_AGG_SNARKS * 2 + 5],
+                    &data_rlc_cells[data_hash_round + 2],
                     &mut offset,
                 )?;
                 let t4 = rlc_config.sub(
                     &mut region,
                     &rlc_cell,
-                    &data_rlc_cells[MAX_AGG_SNARKS * 2 + 6],
+                    &data_rlc_cells[data_hash_round + 3],
                     &mut offset,
                 )?;
                 let t1t2 = rlc_config.mul(&mut region, &t1, &t2, &mut offset)?;
@@ -990,14 +1178,20 @@ pub(crate) fn conditional_constraints(
                 // ...
                 // 2*(MAX_AGG_SNARKS) + 1    | 0     | MAX_AGG_SNARKS+1's keccak
                 // 2*(MAX_AGG_SNARKS) + 2    | 1     |   chunk[MAX_AGG_SNARKS].pi_hash use 2 rounds
-                // 2*(MAX_AGG_SNARKS) + 3    | a     | MAX_AGG_SNARKS+2's keccak
-                // 2*(MAX_AGG_SNARKS) + 4    | b     |   batch_data_hash may use 1, 2, 3
-                // 2*(MAX_AGG_SNARKS) + 5    | c     |   or 4 rounds
-                // 2*(MAX_AGG_SNARKS) + 6    | d     |
+                // ...                       | 0     | in the blob DA mode, the blob data hash
+                // c - 1                     | 1     |   uses c - 2*(MAX_AGG_SNARKS) - 3 rounds
+                // c                         | 1     |   and the challenge digest uses 1 round
+                // r                         | a     | MAX_AGG_SNARKS+2's keccak
+                // r + 1                     | b     |   batch_data_hash may use 1, 2, 3
+                // r + 2                     | c     |   or 4 rounds
+                // r + 3                     | d     |
+                //
+                // where c is the challenge digest round, and r is the data hash round, that is
+                // 2*(MAX_AGG_SNARKS) + 3, or c + 1 in the blob DA mode
                 //
                 // so a,b,c are constrained as follows
                 //
-                // #valid snarks | flags        | a | b | c | d
+                // #data hashes  | flags        | a | b | c | d
                 // 1,2,3,4       | 1, 0, 0, 0   | 1 | - | - | -
                 // 5,6,7,8       | 0, 1, 0, 0   | 0 | 1 | - | -
                 // 9,10,11,12    | 0, 0, 1, 0   | 0 | 0 | 1 | -
@@ -1023,12 +1217,31 @@ pub(crate) fn conditional_constraints(
                         rlc_config.one_cell(second_round_cell.cell().region_index),
                     )?;
                 }
+                // in the blob DA mode, the blob data hash and the challenge digest keccaks
+                if with_blob {
+                    for (round, is_final) in is_final_cells
+                        .iter()
+                        .enumerate()
+                        .take(data_hash_round)
+                        .skip(blob_data_hash_round)
+                    {
+                        let region_index = is_final.cell().region_index;
+                        region.constrain_equal(
+                            is_final.cell(),
+                            if round + 1 < challenge_digest_round {
+                                rlc_config.zero_cell(region_index)
+                            } else {
+                                rlc_config.one_cell(region_index)
+                            },
+                        )?;
+                    }
+                }
                 // last keccak
                 // we constrain a * flag1 + b * flag2 + c * flag3 + d * flag4 == 1
-                let a = &is_final_cells[2 * (MAX_AGG_SNARKS) + 3];
-                let b = &is_final_cells[2 * (MAX_AGG_SNARKS) + 4];
-                let c = &is_final_cells[2 * (MAX_AGG_SNARKS) + 5];
-                let d = &is_final_cells[2 * (MAX_AGG_SNARKS) + 6];
+                let a = &is_final_cells[data_hash_round];
+                let b = &is_final_cells[data_hash_round + 1];
+                let c = &is_final_cells[data_hash_round + 2];
+                let d = &is_final_cells[data_hash_round + 3];
                 let mut left = rlc_config.mul(&mut region, a, &flag1, &mut offset)?;
                 left = rlc_config.mul_add(&mut region, b, &flag2, &left, &mut offset)?;
                 left = rlc_config.mul_add(&mut region, c, &flag3, &left, &mut offset)?;
@@ -3,6 +3,8 @@ mod aggregation;
 /// This module implements `Batch` related data types.
 /// A batch is a list of chunk.
 mod batch;
+/// This module implements the blob for the blob based data availability.
+mod blob;
 // This module implements `Chunk` related data types.
 // A chunk is a list of blocks.
 mod chunk;
@@ -22,7 +24,8 @@ mod tests;
 
 pub use self::core::extract_proof_and_instances_with_pairing_check;
 pub use aggregation::*;
-pub use batch::BatchHash;
+pub use batch::{BatchHash, DataAvailability};
+pub use blob::{BlobData, PointEvaluation};
 pub use chunk::ChunkHash;
 pub use compression::*;
 pub use constants::MAX_AGG_SNARKS;
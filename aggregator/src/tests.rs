@@ -1,4 +1,5 @@
 mod aggregation;
+mod blob;
 mod compression;
 mod mock_chunk;
 mod rlc;
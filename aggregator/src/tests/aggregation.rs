@@ -1,14 +1,22 @@
 use std::{fs, path::Path, process};
 
 use ark_std::{end_timer, start_timer, test_rng};
+use eth_types::H256;
+use ethers_core::utils::keccak256;
 use halo2_proofs::{dev::MockProver, halo2curves::bn256::Fr, poly::commitment::Params};
 use itertools::Itertools;
+use rand::RngCore;
 use snark_verifier::loader::halo2::halo2_ecc::halo2_base::utils::fs::gen_srs;
 use snark_verifier_sdk::{gen_pk, gen_snark_shplonk, verify_snark_shplonk, CircuitExt};
 
 use crate::{
-    aggregation::AggregationCircuit, batch::BatchHash, constants::MAX_AGG_SNARKS, layer_0,
-    tests::mock_chunk::MockChunkCircuit, ChunkHash,
+    aggregation::AggregationCircuit,
+    batch::{BatchHash, DataAvailability},
+    blob::BlobData,
+    constants::MAX_AGG_SNARKS,
+    layer_0,
+    tests::mock_chunk::MockChunkCircuit,
+    ChunkHash,
 };
 
 #[test]
@@ -90,32 +98,175 @@ fn test_aggregation_circuit_full() {
     log::trace!("finished verification for circuit");
 }
 
-fn build_new_aggregation_circuit(num_real_chunks: usize) -> AggregationCircuit {
-    // inner circuit: Mock circuit
-    let k0 = 8;
+/// The aggregation circuit must reject a batch whose chunks do not consume the L1 message queue
+/// contiguously, i.e. chunk\[i+1\].start_l1_queue_index != chunk\[i\].end_l1_queue_index.
+#[test]
+fn test_aggregation_circuit_non_contiguous_l1_queue() {
+    let k = 20;
 
     let mut rng = test_rng();
-    let params = gen_srs(k0);
+    let chunks_without_padding = contiguous_chunks(&mut rng, 2);
+    let mut batch_hash = build_batch_hash(&chunks_without_padding);
+
+    // shift the L1 queue range of the second chunk, leaving a gap after the first one. The
+    // batch public input hash does not commit to the queue indices, so only the continuity
+    // check can catch this.
+    let chunk = &mut batch_hash.chunks_with_padding[1];
+    chunk.start_l1_queue_index += 1;
+    chunk.end_l1_queue_index += 1;
+    let padded_chunk = ChunkHash::mock_padded_chunk_hash_for_testing(chunk);
+    for chunk in batch_hash.chunks_with_padding.iter_mut().skip(2) {
+        *chunk = padded_chunk;
+    }
+
+    let circuit = build_aggregation_circuit(batch_hash, rng);
+    let instance = circuit.instances();
+    if let Ok(mock_prover) = MockProver::<Fr>::run(k, &circuit, instance) {
+        assert!(mock_prover.verify_par().is_err());
+    }
+}
+
+#[test]
+fn test_aggregation_circuit_with_blob() {
+    let k = 20;
+
+    let mut rng = test_rng();
+    let chunks_without_padding = contiguous_chunks(&mut rng, 2);
+    let batch_hash = build_batch_hash_with_blob(&chunks_without_padding, &mut rng);
+
+    let circuit = build_aggregation_circuit(batch_hash, rng);
+    let instance = circuit.instances();
+    let mock_prover = MockProver::<Fr>::run(k, &circuit, instance).unwrap();
+    mock_prover.assert_satisfied_par();
+}
+
+/// The aggregation circuit must reject a blob evaluated at a challenge point which is not
+/// derived from the batch data hash and the versioned hash.
+#[test]
+fn test_aggregation_circuit_with_blob_wrong_challenge() {
+    let k = 20;
+
+    let mut rng = test_rng();
+    let chunks_without_padding = contiguous_chunks(&mut rng, 2);
+    let mut batch_hash = build_batch_hash_with_blob(&chunks_without_padding, &mut rng);
+
+    // the evaluation is correct, but at a point chosen by the prover
+    if let DataAvailability::Blob {
+        blob,
+        point_evaluation,
+    } = &mut batch_hash.data_availability
+    {
+        *point_evaluation = blob.point_evaluation(&H256::repeat_byte(0x42));
+    }
+
+    let circuit = build_aggregation_circuit(batch_hash, rng);
+    let instance = circuit.instances();
+    if let Ok(mock_prover) = MockProver::<Fr>::run(k, &circuit, instance) {
+        assert!(mock_prover.verify_par().is_err());
+    }
+}
 
+/// The aggregation circuit must reject a blob which is not committed by the batch data hash.
+#[test]
+fn test_aggregation_circuit_with_blob_not_committed() {
+    let k = 20;
+
+    let mut rng = test_rng();
+    let chunks_without_padding = contiguous_chunks(&mut rng, 2);
+    let mut batch_hash = build_batch_hash_with_blob(&chunks_without_padding, &mut rng);
+
+    // swap the blob for another one, evaluated at a challenge point derived from the batch data
+    // hash, and commit its versioned hash in the batch public input hash
+    let blob = random_blob_data(&mut rng, chunks_without_padding.len());
+    let point_evaluation = blob.point_evaluation(&batch_hash.data_hash);
+    batch_hash.data_availability = DataAvailability::Blob {
+        blob,
+        point_evaluation,
+    };
+    batch_hash.public_input_hash = keccak256(batch_hash.public_input_hash_preimage()).into();
+
+    let circuit = build_aggregation_circuit(batch_hash, rng);
+    let instance = circuit.instances();
+    if let Ok(mock_prover) = MockProver::<Fr>::run(k, &circuit, instance) {
+        assert!(mock_prover.verify_par().is_err());
+    }
+}
+
+fn build_new_aggregation_circuit(num_real_chunks: usize) -> AggregationCircuit {
+    let mut rng = test_rng();
+    let chunks_without_padding = contiguous_chunks(&mut rng, num_real_chunks);
+    build_aggregation_circuit(build_batch_hash(&chunks_without_padding), rng)
+}
+
+/// Random chunks that chain their state roots and L1 message queue ranges.
+fn contiguous_chunks<R: rand::RngCore>(rng: &mut R, num_real_chunks: usize) -> Vec<ChunkHash> {
     let mut chunks_without_padding = (0..num_real_chunks)
-        .map(|_| ChunkHash::mock_random_chunk_hash_for_testing(&mut rng))
+        .map(|_| ChunkHash::mock_random_chunk_hash_for_testing(rng))
         .collect_vec();
     for i in 0..num_real_chunks - 1 {
         chunks_without_padding[i + 1].prev_state_root = chunks_without_padding[i].post_state_root;
+        let num_l1_popped = chunks_without_padding[i + 1].end_l1_queue_index
+            - chunks_without_padding[i + 1].start_l1_queue_index;
+        chunks_without_padding[i + 1].start_l1_queue_index =
+            chunks_without_padding[i].end_l1_queue_index;
+        chunks_without_padding[i + 1].end_l1_queue_index =
+            chunks_without_padding[i + 1].start_l1_queue_index + num_l1_popped;
     }
+    chunks_without_padding
+}
+
+/// Pad the real chunks up to MAX_AGG_SNARKS and build their batch.
+fn build_batch_hash(chunks_without_padding: &[ChunkHash]) -> BatchHash {
+    let num_real_chunks = chunks_without_padding.len();
     let padded_chunk =
         ChunkHash::mock_padded_chunk_hash_for_testing(&chunks_without_padding[num_real_chunks - 1]);
     let chunks_with_padding = [
-        chunks_without_padding,
+        chunks_without_padding.to_vec(),
         vec![padded_chunk; MAX_AGG_SNARKS - num_real_chunks],
     ]
     .concat();
 
+    BatchHash::construct(&chunks_with_padding)
+}
+
+/// Random tx bytes for each of the valid chunks.
+fn random_blob_data<R: RngCore>(rng: &mut R, num_real_chunks: usize) -> BlobData {
+    let chunk_tx_bytes = (0..num_real_chunks)
+        .map(|_| {
+            let mut tx_bytes = vec![0u8; (rng.next_u32() % 1024) as usize];
+            rng.fill_bytes(&mut tx_bytes);
+            tx_bytes
+        })
+        .collect_vec();
+    BlobData::new(&chunk_tx_bytes)
+}
+
+/// Pad the real chunks up to MAX_AGG_SNARKS and build their batch in the blob DA mode.
+fn build_batch_hash_with_blob<R: RngCore>(
+    chunks_without_padding: &[ChunkHash],
+    rng: &mut R,
+) -> BatchHash {
+    let batch_hash = build_batch_hash(chunks_without_padding);
+    let blob = random_blob_data(rng, chunks_without_padding.len());
+    BatchHash::construct_with_blob(&batch_hash.chunks_with_padding, blob)
+}
+
+fn build_aggregation_circuit(
+    batch_hash: BatchHash,
+    rng: impl rand::Rng + Send,
+) -> AggregationCircuit {
+    // inner circuit: Mock circuit
+    let k0 = 8;
+
+    let params = gen_srs(k0);
+    let num_real_chunks = batch_hash.number_of_valid_chunks;
+
     // ==========================
     // real chunks
     // ==========================
     let real_snarks = {
-        let circuits = chunks_with_padding
+        let circuits = batch_hash
+            .chunks_with_padding
             .iter()
             .take(num_real_chunks)
             .map(|&chunk| MockChunkCircuit::new(true, chunk))
@@ -132,11 +283,6 @@ fn build_new_aggregation_circuit(num_real_chunks: usize) -> AggregationCircuit {
     let padded_snarks =
         { vec![real_snarks.last().unwrap().clone(); MAX_AGG_SNARKS - num_real_chunks] };
 
-    // ==========================
-    // batch
-    // ==========================
-    let batch_hash = BatchHash::construct(&chunks_with_padding);
-
     AggregationCircuit::new(
         &params,
         [real_snarks, padded_snarks].concat().as_ref(),
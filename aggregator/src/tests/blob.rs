@@ -0,0 +1,145 @@
+//! Tests the blob and its barycentric evaluation
+
+use ark_std::test_rng;
+use eth_types::{H256, U256};
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner},
+    dev::MockProver,
+    halo2curves::bn256::{Fq, Fr},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+use rand::RngCore;
+use snark_verifier::loader::halo2::halo2_ecc::{
+    fields::fp::{FpConfig, FpStrategy},
+    halo2_base::{utils::modulus, Context, ContextParams, SKIP_FIRST_PASS},
+};
+
+use crate::{
+    aggregation::BarycentricEvaluationConfig,
+    blob::{BlobData, PointEvaluation},
+    constants::{BITS, LIMBS},
+};
+
+fn random_blob_data(num_chunks: usize) -> BlobData {
+    let mut rng = test_rng();
+    let chunk_tx_bytes = (0..num_chunks)
+        .map(|_| {
+            let mut tx_bytes = vec![0u8; (rng.next_u32() % 2048) as usize];
+            rng.fill_bytes(&mut tx_bytes);
+            tx_bytes
+        })
+        .collect::<Vec<_>>();
+    BlobData::new(&chunk_tx_bytes)
+}
+
+#[test]
+fn test_blob_point_evaluation() {
+    let blob = random_blob_data(3);
+    let point_evaluation = blob.point_evaluation(&H256::repeat_byte(0x42));
+
+    // the barycentric evaluation matches the one of the KZG library
+    let (_proof, evaluation) = blob.kzg_proof(&point_evaluation.challenge);
+    assert_eq!(point_evaluation.evaluation, evaluation);
+
+    // the evaluation at a root of unity is the blob field element
+    let z = U256::one();
+    assert_eq!(blob.evaluate(&z), blob.coefficients()[0]);
+}
+
+#[derive(Default, Debug, Clone)]
+struct BarycentricTestCircuit {
+    blob: BlobData,
+    point_evaluation: PointEvaluation,
+}
+
+impl Circuit<Fr> for BarycentricTestCircuit {
+    type Config = BarycentricEvaluationConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let base_field_config = FpConfig::<Fr, Fq>::configure(
+            meta,
+            FpStrategy::Simple,
+            &[16],
+            &[4],
+            1,
+            18,
+            BITS,
+            LIMBS,
+            modulus::<Fq>(),
+            0,
+            19,
+        );
+        BarycentricEvaluationConfig::construct(base_field_config.range)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        config.scalar.range.load_lookup_table(&mut layouter)?;
+
+        let mut first_pass = SKIP_FIRST_PASS;
+        layouter.assign_region(
+            || "barycentric evaluation",
+            |region| -> Result<(), Error> {
+                if first_pass {
+                    first_pass = false;
+                    return Ok(());
+                }
+
+                let mut ctx = Context::new(
+                    region,
+                    ContextParams {
+                        max_rows: config.scalar.range.gate.max_rows,
+                        num_context_ids: 1,
+                        fixed_columns: config.scalar.range.gate.constants.clone(),
+                    },
+                );
+                config.assign(&mut ctx, &self.blob, &self.point_evaluation);
+                config.scalar.range.finalize(&mut ctx);
+
+                Ok(())
+            },
+        )
+    }
+}
+
+#[test]
+fn test_barycentric_evaluation_circuit() {
+    let blob = random_blob_data(2);
+    let point_evaluation = blob.point_evaluation(&H256::repeat_byte(0x42));
+
+    let circuit = BarycentricTestCircuit {
+        blob: blob.clone(),
+        point_evaluation,
+    };
+    let mock_prover = MockProver::<Fr>::run(19, &circuit, vec![]).unwrap();
+    mock_prover.assert_satisfied_par();
+
+    // a wrong evaluation is rejected
+    let circuit = BarycentricTestCircuit {
+        blob: blob.clone(),
+        point_evaluation: PointEvaluation {
+            evaluation: point_evaluation.evaluation + 1,
+            ..point_evaluation
+        },
+    };
+    let mock_prover = MockProver::<Fr>::run(19, &circuit, vec![]).unwrap();
+    assert!(mock_prover.verify_par().is_err());
+
+    // a challenge point which is not the reduced challenge digest is rejected
+    let circuit = BarycentricTestCircuit {
+        blob,
+        point_evaluation: PointEvaluation {
+            challenge_digest: point_evaluation.challenge_digest + 1,
+            ..point_evaluation
+        },
+    };
+    let mock_prover = MockProver::<Fr>::run(19, &circuit, vec![]).unwrap();
+    assert!(mock_prover.verify_par().is_err());
+}
@@ -1,4 +1,7 @@
-use crate::constants::{DIGEST_LEN, INPUT_LEN_PER_ROUND, MAX_AGG_SNARKS};
+use crate::{
+    blob::N_BLOB_BYTES,
+    constants::{CHALLENGE_PREIMAGE_LEN, DIGEST_LEN, INPUT_LEN_PER_ROUND, MAX_AGG_SNARKS},
+};
 use eth_types::Field;
 use halo2_proofs::{circuit::AssignedCell, halo2curves::bn256::Fr, plonk::Error};
 use itertools::Itertools;
@@ -8,25 +11,45 @@ use zkevm_circuits::keccak_circuit::keccak_packed_multi::{
 
 // Calculates the maximum keccak updates (1 absorb, or 1 f-box invoke)
 // needed for the number of snarks
-pub(crate) fn get_max_keccak_updates(max_snarks: usize) -> usize {
+pub(crate) fn get_max_keccak_updates(max_snarks: usize, with_blob: bool) -> usize {
     // The public input hash for the batch is derived from hashing
     // chain_id || chunk_0's prev_state || chunk_k-1's post_state ||
     // chunk_k-1's withdraw_root || batch_data_hash.
     // In total there're 168 bytes. Therefore 2 pi rounds are required.
     let pi_rounds = 2;
     // Hash for each chunk is derived from hashing the chunk's
-    // chain_id || prev_state || post_state || withdraw_root || data_hash
-    // Each chunk hash therefore also requires 2 keccak rounds for 168 bytes.
+    // chain_id || prev_state || post_state || withdraw_root || data_hash ||
+    // start_l1_queue_index || end_l1_queue_index || skipped_l1_msgs_hash
+    // Each chunk hash therefore also requires 2 keccak rounds for 184 bytes.
     let chunk_hash_rounds = 2 * max_snarks;
-    let data_hash_rounds = get_data_hash_keccak_updates(max_snarks);
+    // In the blob DA mode, the blob data and the challenge digest are hashed before the data hash.
+    let blob_rounds = get_blob_keccak_updates(with_blob);
+    // In the blob DA mode, the blob data hash prefixes the batch data hash preimage.
+    let data_hash_rounds = get_data_hash_keccak_updates(max_snarks + with_blob as usize);
+
+    pi_rounds + chunk_hash_rounds + blob_rounds + data_hash_rounds
+}
+
+// Calculates the keccak updates of the blob data hash and of the challenge digest, which are only
+// present in the blob DA mode.
+pub(crate) fn get_blob_keccak_updates(with_blob: bool) -> usize {
+    if with_blob {
+        get_blob_data_hash_keccak_updates() + 1 + CHALLENGE_PREIMAGE_LEN / INPUT_LEN_PER_ROUND
+    } else {
+        0
+    }
+}
 
-    pi_rounds + chunk_hash_rounds + data_hash_rounds
+// The blob data has a fixed length of N_BLOB_BYTES bytes.
+pub(crate) fn get_blob_data_hash_keccak_updates() -> usize {
+    1 + N_BLOB_BYTES / INPUT_LEN_PER_ROUND
 }
-pub(crate) fn get_data_hash_keccak_updates(max_snarks: usize) -> usize {
-    let data_hash_rounds = (32 * max_snarks) / INPUT_LEN_PER_ROUND;
-    // when `32 * max_snarks` happens to match a multiple of 136, a padding round will still be
-    // added
-    let padding_round = if data_hash_rounds * INPUT_LEN_PER_ROUND <= 32 * max_snarks {
+
+pub(crate) fn get_data_hash_keccak_updates(max_data_hashes: usize) -> usize {
+    let data_hash_rounds = (32 * max_data_hashes) / INPUT_LEN_PER_ROUND;
+    // when `32 * max_data_hashes` happens to match a multiple of 136, a padding round will still
+    // be added
+    let padding_round = if data_hash_rounds * INPUT_LEN_PER_ROUND <= 32 * max_data_hashes {
         1
     } else {
         0
@@ -38,7 +61,7 @@ pub(crate) fn get_data_hash_keccak_updates(max_snarks: usize) -> usize {
 /// Return
 /// - the indices of the rows that contain the input preimages
 /// - the indices of the rows that contain the output digest
-pub(crate) fn get_indices(preimages: &[Vec<u8>]) -> (Vec<usize>, Vec<usize>) {
+pub(crate) fn get_indices(preimages: &[Vec<u8>], with_blob: bool) -> (Vec<usize>, Vec<usize>) {
     let mut preimage_indices = vec![];
     let mut digest_indices = vec![];
     let mut round_ctr = 0;
@@ -46,7 +69,8 @@ pub(crate) fn get_indices(preimages: &[Vec<u8>]) -> (Vec<usize>, Vec<usize>) {
     let keccak_f_rows = get_num_rows_per_update();
     let inner_round_rows = get_num_rows_per_round();
 
-    for preimage in preimages.iter().take(MAX_AGG_SNARKS + 1) {
+    // all the hashes but the data hash have a fixed length
+    for preimage in preimages.iter().take(preimages.len() - 1) {
         //  136 = 17 * 8 is the size in bytes of each
         //  input chunk that can be processed by Keccak circuit using absorb
 
@@ -82,7 +106,7 @@ pub(crate) fn get_indices(preimages: &[Vec<u8>]) -> (Vec<usize>, Vec<usize>) {
         }
     }
     // last hash is for data_hash and has various length, so we output all the possible cells
-    for _i in 0..get_data_hash_keccak_updates(MAX_AGG_SNARKS) {
+    for _i in 0..get_data_hash_keccak_updates(MAX_AGG_SNARKS + with_blob as usize) {
         for (j, _) in (0..INPUT_LEN_PER_ROUND).chunks(8).into_iter().enumerate() {
             let inner_offset = round_ctr * keccak_f_rows + (j + 1) * inner_round_rows;
             for k in 0..8 {
@@ -172,6 +196,7 @@ fn is_ascending(a: &[usize]) -> bool {
 #[allow(clippy::type_complexity)]
 pub(crate) fn parse_hash_preimage_cells(
     hash_input_cells: &[AssignedCell<Fr, Fr>],
+    with_blob: bool,
 ) -> (
     &[AssignedCell<Fr, Fr>],
     Vec<&[AssignedCell<Fr, Fr>]>,
@@ -187,8 +212,9 @@ pub(crate) fn parse_hash_preimage_cells(
             &hash_input_cells[INPUT_LEN_PER_ROUND * 2 * (i + 1)..INPUT_LEN_PER_ROUND * 2 * (i + 2)],
         );
     }
-    let potential_batch_data_hash_preimage =
-        &hash_input_cells[INPUT_LEN_PER_ROUND * 2 * (MAX_AGG_SNARKS + 1)..];
+    // in the blob DA mode, the blob hashes lie in between
+    let potential_batch_data_hash_preimage = &hash_input_cells
+        [INPUT_LEN_PER_ROUND * (2 * (MAX_AGG_SNARKS + 1) + get_blob_keccak_updates(with_blob))..];
 
     (
         batch_pi_hash_preimage,
@@ -197,10 +223,27 @@ pub(crate) fn parse_hash_preimage_cells(
     )
 }
 
+/// In the blob DA mode, return the preimage cells of
+/// - the blob data hash: the blob data
+/// - the challenge digest: batch_data_hash || versioned_hash
+#[inline]
+pub(crate) fn parse_blob_hash_preimage_cells(
+    hash_input_cells: &[AssignedCell<Fr, Fr>],
+) -> (&[AssignedCell<Fr, Fr>], &[AssignedCell<Fr, Fr>]) {
+    let blob_data_offset = INPUT_LEN_PER_ROUND * 2 * (MAX_AGG_SNARKS + 1);
+    let challenge_offset =
+        blob_data_offset + INPUT_LEN_PER_ROUND * get_blob_data_hash_keccak_updates();
+    (
+        &hash_input_cells[blob_data_offset..blob_data_offset + N_BLOB_BYTES],
+        &hash_input_cells[challenge_offset..challenge_offset + CHALLENGE_PREIMAGE_LEN],
+    )
+}
+
 #[inline]
 #[allow(clippy::type_complexity)]
 pub(crate) fn parse_hash_digest_cells(
     hash_output_cells: &[AssignedCell<Fr, Fr>],
+    with_blob: bool,
 ) -> (
     &[AssignedCell<Fr, Fr>],
     Vec<&[AssignedCell<Fr, Fr>]>,
@@ -211,7 +254,9 @@ pub(crate) fn parse_hash_digest_cells(
     for i in 0..MAX_AGG_SNARKS {
         chunk_pi_hash_digests.push(&hash_output_cells[DIGEST_LEN * (i + 1)..DIGEST_LEN * (i + 2)]);
     }
-    let potential_batch_data_hash_digest = &hash_output_cells[DIGEST_LEN * (MAX_AGG_SNARKS + 1)..];
+    // in the blob DA mode, the blob data hash and the challenge digest lie in between
+    let potential_batch_data_hash_digest =
+        &hash_output_cells[DIGEST_LEN * (MAX_AGG_SNARKS + 1 + 2 * with_blob as usize)..];
     (
         batch_pi_hash_digest,
         chunk_pi_hash_digests,
@@ -219,6 +264,26 @@ pub(crate) fn parse_hash_digest_cells(
     )
 }
 
+/// In the blob DA mode, return the digest cells of the blob data hash and of the challenge digest
+#[inline]
+pub(crate) fn parse_blob_hash_digest_cells(
+    hash_output_cells: &[AssignedCell<Fr, Fr>],
+) -> (&[AssignedCell<Fr, Fr>], &[AssignedCell<Fr, Fr>]) {
+    let blob_data_hash_offset = DIGEST_LEN * (MAX_AGG_SNARKS + 1);
+    (
+        &hash_output_cells[blob_data_hash_offset..blob_data_hash_offset + DIGEST_LEN],
+        &hash_output_cells
+            [blob_data_hash_offset + DIGEST_LEN..blob_data_hash_offset + 2 * DIGEST_LEN],
+    )
+}
+
+/// The digest cell of the i-th byte of a hash: in the keccak table, the input and output data
+/// have different endianess
+#[inline]
+pub(crate) fn digest_byte_index(i: usize) -> usize {
+    (3 - i / 8) * 8 + i % 8
+}
+
 #[inline]
 pub(crate) fn parse_pi_hash_rlc_cells(
     data_rlc_cells: &[AssignedCell<Fr, Fr>],
@@ -5,6 +5,7 @@ mod access;
 mod block;
 mod call;
 mod execution;
+mod gas_audit;
 mod input_state_ref;
 #[cfg(feature = "scroll")]
 mod l2;
@@ -27,8 +28,9 @@ pub use call::{Call, CallContext, CallKind};
 use core::fmt::Debug;
 use eth_types::{
     self,
-    evm_types::GasCost,
+    evm_types::{GasCost, GasSchedule, GasTable},
     geth_types,
+    l2_types::L1QueueRange,
     sign_types::{pk_bytes_le, pk_bytes_swap_endianness, SignData},
     Address, GethExecTrace, ToBigEndian, ToWord, Word, H256,
 };
@@ -38,6 +40,10 @@ pub use execution::{
     EcAddOp, EcMulOp, EcPairingOp, EcPairingPair, ExecState, ExecStep, ExpEvent, ExpStep,
     NumberOrHash, PrecompileEvent, PrecompileEvents, N_BYTES_PER_PAIR, N_PAIRING_PER_OP, SHA256,
 };
+#[cfg(feature = "test")]
+pub(crate) use gas_audit::expected_gas_cost;
+use gas_audit::GasAuditor;
+pub use gas_audit::{GasAuditError, GasAuditReport, GasMismatch, GasMismatchKind};
 use hex::decode_to_slice;
 
 use eth_types::sign_types::get_dummy_tx;
@@ -47,19 +53,22 @@ use itertools::Itertools;
 use log::warn;
 #[cfg(feature = "scroll")]
 use mpt_zktrie::state::ZktrieState;
+use serde::{Deserialize, Serialize};
 use std::{
     collections::{BTreeMap, HashMap},
     iter,
+    sync::Arc,
 };
 pub use transaction::{
-    Transaction, TransactionContext, TxL1Fee, TX_L1_COMMIT_EXTRA_COST, TX_L1_FEE_PRECISION,
+    CallRwStats, Transaction, TransactionContext, TxL1Fee, TxRwStats, TX_L1_COMMIT_EXTRA_COST,
+    TX_L1_FEE_PRECISION,
 };
 
 #[cfg(feature = "enable-stack")]
 use eth_types::evm_types::OpcodeId;
 
 /// Setup parameters for ECC-related precompile calls.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct PrecompileEcParams {
     /// Maximum number of EcAdd ops supported in one block.
     pub ec_add: usize,
@@ -80,7 +89,7 @@ impl Default for PrecompileEcParams {
 }
 
 /// Circuit Setup Parameters
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct CircuitsParams {
     /// Maximum number of rw operations in the state circuit (RwTable length /
     /// number of rows). This must be at least the number of rw operations
@@ -96,6 +105,10 @@ pub struct CircuitsParams {
     pub max_copy_rows: usize,
     /// Maximum number of inner blocks in a chunk
     pub max_inner_blocks: usize,
+    /// Maximum number of l1 msgs popped by a chunk in the PI circuit, skipped
+    /// ones included. The PI circuit has a row for each of them, rounded up to
+    /// a multiple of 256.
+    pub max_l1_msgs_popped: usize,
     /// Max number of steps that the ExpCircuit can have. Each step is further
     /// expressed in 7 rows
     /// TODO: change this to max_exp_rows too
@@ -125,6 +138,10 @@ pub struct CircuitsParams {
     /// then if there is 1 ecPairing in the input, we will return 500_000 as the "row usage"
     /// for the ec circuit.
     pub max_vertical_circuit_rows: usize,
+    /// Gas costs exposed in the fixed table of the EVM circuit. The gas
+    /// schedule of a block must be the same.
+    #[serde(default)]
+    pub gas_table: GasTable,
 }
 
 impl Default for CircuitsParams {
@@ -135,6 +152,7 @@ impl Default for CircuitsParams {
             max_txs: 1,
             max_calldata: 256,
             max_inner_blocks: 64,
+            max_l1_msgs_popped: 256,
             // TODO: Check whether this value is correct or we should increase/decrease based on
             // this lib tests
             max_copy_rows: 2000,
@@ -147,6 +165,7 @@ impl Default for CircuitsParams {
             max_vertical_circuit_rows: 0,
             max_rlp_rows: 1000,
             max_ec_ops: PrecompileEcParams::default(),
+            gas_table: GasTable::default(),
         }
     }
 }
@@ -169,7 +188,7 @@ impl Default for CircuitsParams {
 /// [`OpcodeId`](crate::evm::OpcodeId)s used in each `ExecTrace` step so that
 /// the State Proof witnesses are already generated on a structured manner and
 /// ready to be added into the State circuit.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct CircuitInputBuilder {
     /// StateDB key-value DB
     pub sdb: StateDB,
@@ -182,6 +201,9 @@ pub struct CircuitInputBuilder {
     #[cfg(feature = "scroll")]
     /// Initial Zktrie Status for a incremental updating
     pub mpt_init_state: Option<ZktrieState>,
+    /// Report of the gas audit, only if enabled with
+    /// [`CircuitInputBuilder::enable_gas_audit`]
+    pub gas_audit: Option<GasAuditReport>,
 }
 
 impl<'a> CircuitInputBuilder {
@@ -195,8 +217,24 @@ impl<'a> CircuitInputBuilder {
             block_ctx: BlockContext::new(),
             #[cfg(feature = "scroll")]
             mpt_init_state: Default::default(),
+            gas_audit: None,
         }
     }
+
+    /// Enable the audit of the gas accounting: the gas of every step of the
+    /// following transactions is recomputed and compared with the trace, and
+    /// the mismatches are collected in [`CircuitInputBuilder::gas_audit`].
+    pub fn enable_gas_audit(mut self) -> Self {
+        self.gas_audit = Some(GasAuditReport::default());
+        self
+    }
+
+    /// Set the gas schedule of the chain, see [`Block::with_gas_schedule`].
+    pub fn with_gas_schedule(mut self, gas_schedule: Arc<dyn GasSchedule>) -> Self {
+        self.block.gas_schedule = Some(gas_schedule);
+        self
+    }
+
     /// Create a new CircuitInputBuilder from the given `eth_block` and
     /// `constants`.
     pub fn new_from_headers(
@@ -492,6 +530,9 @@ impl<'a> CircuitInputBuilder {
             )?;
         }
 
+        // The withdraw root is read in a tx of its own, after the last tx, so
+        // that its MPT update is the last one of the chunk and proves the
+        // withdraw root against the end state root.
         // increase the total rwc by 1
         state.push_op(
             &mut end_block_last,
@@ -501,8 +542,8 @@ impl<'a> CircuitInputBuilder {
                 WITHDRAW_TRIE_ROOT_SLOT,
                 withdraw_root,
                 withdraw_root,
-                dummy_tx_id,
-                withdraw_root_before,
+                dummy_tx_id + 1,
+                withdraw_root,
             ),
         )?;
 
@@ -602,6 +643,10 @@ impl<'a> CircuitInputBuilder {
 
         tx.steps_mut().extend(begin_tx_steps);
 
+        let mut gas_auditor = self
+            .gas_audit
+            .is_some()
+            .then(|| GasAuditor::new(tx_ctx.id()));
         for (index, geth_step) in geth_trace.struct_logs.iter().enumerate() {
             let tx_gas = tx.gas;
             let mut state_ref = self.state_ref(&mut tx, &mut tx_ctx);
@@ -680,13 +725,29 @@ impl<'a> CircuitInputBuilder {
                 state_ref.call(),
                 state_ref.tx.calls()
             );
+            if let Some(gas_auditor) = gas_auditor.as_mut() {
+                gas_auditor.audit_step(&state_ref, &geth_trace.struct_logs, index);
+            }
+            #[cfg(feature = "test")]
+            let fuzz_snapshot = crate::fuzz::StepSnapshot::capture(
+                &state_ref,
+                index,
+                &geth_trace.struct_logs[index..],
+            )?;
             let exec_steps = gen_associated_ops(
                 &geth_step.op,
                 &mut state_ref,
                 &geth_trace.struct_logs[index..],
             )?;
+            #[cfg(feature = "test")]
+            if let Some(snapshot) = fuzz_snapshot {
+                snapshot.check(&state_ref, geth_step);
+            }
             tx.steps_mut().extend(exec_steps);
         }
+        if let (Some(report), Some(gas_auditor)) = (self.gas_audit.as_mut(), gas_auditor) {
+            report.extend(gas_auditor.finish());
+        }
 
         // Generate EndTx step
         log::trace!("gen_end_tx_ops");
@@ -746,7 +807,8 @@ pub fn keccak_inputs(block: &Block, code_db: &CodeDB) -> Result<Vec<Vec<u8>>, Er
         block.withdraw_root,
         &block.headers,
         block.txs(),
-    ));
+        block.circuits_params.max_l1_msgs_popped,
+    )?);
     // Bytecode Circuit
     for _bytecode in code_db.0.values() {
         // keccak_inputs.push(bytecode.clone());
@@ -810,7 +872,8 @@ fn keccak_inputs_pi_circuit(
     withdraw_trie_root: Word,
     block_headers: &BTreeMap<u64, BlockHead>,
     transactions: &[Transaction],
-) -> Vec<Vec<u8>> {
+    max_l1_msgs_popped: usize,
+) -> Result<Vec<Vec<u8>>, Error> {
     let mut total_l1_popped = start_l1_queue_index;
     log::debug!(
         "start_l1_queue_index in keccak_inputs: {}",
@@ -861,15 +924,30 @@ fn keccak_inputs_pi_circuit(
         .last_key_value()
         .map(|(_, blk)| blk.eth_block.state_root)
         .unwrap_or(H256(prev_state_root.to_be_bytes()));
+    let l1_queue_range = L1QueueRange::new(
+        start_l1_queue_index,
+        transactions
+            .iter()
+            .filter(|tx| tx.tx_type.is_l1_msg())
+            .map(|tx| tx.nonce),
+        max_l1_msgs_popped as u64,
+    )?;
     let pi_bytes = iter::empty()
         .chain(chain_id.to_be_bytes())
         .chain(prev_state_root.to_be_bytes())
         .chain(after_state_root.to_fixed_bytes())
         .chain(withdraw_trie_root.to_be_bytes())
         .chain(data_hash.to_fixed_bytes())
+        .chain(l1_queue_range.start_queue_index.to_be_bytes())
+        .chain(l1_queue_range.end_queue_index.to_be_bytes())
+        .chain(l1_queue_range.skipped_bitmap_hash().to_fixed_bytes())
         .collect::<Vec<u8>>();
 
-    vec![data_bytes, pi_bytes]
+    Ok(vec![
+        data_bytes,
+        pi_bytes,
+        l1_queue_range.skipped_bitmap_bytes(),
+    ])
 }
 
 /// Generate the keccak inputs required by the Tx Circuit from the transactions.
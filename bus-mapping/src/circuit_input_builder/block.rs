@@ -9,11 +9,18 @@ use crate::{
     operation::{OperationContainer, RWCounter},
     Error,
 };
-use eth_types::{Address, Hash, ToWord, Word};
-use std::collections::{BTreeMap, HashMap};
+use eth_types::{
+    chain_spec::{ChainRules, ChainSpec},
+    evm_types::GasSchedule,
+    Address, Hash, ToWord, Word,
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
 
 /// Context of a [`Block`] which can mutate in a [`Transaction`].
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct BlockContext {
     /// Used to track the global counter in every operation in the block.
     /// Contains the next available value.
@@ -208,6 +215,10 @@ pub struct Block {
     pub circuits_params: CircuitsParams,
     /// chain id
     pub chain_id: u64,
+    /// Hardfork schedule of the chain
+    pub chain_spec: ChainSpec,
+    /// Gas schedule of the chain, the gas table of the circuits if None
+    pub gas_schedule: Option<Arc<dyn GasSchedule>>,
     /// start_l1_queue_index
     pub start_l1_queue_index: u64,
     /// IO to/from the precompiled contract calls.
@@ -314,6 +325,37 @@ impl Block {
         self.chain_id
     }
 
+    /// Return the rules of the block with number `block_num`, which are the
+    /// ones of its first block if it's not in the chunk.
+    pub fn rules(&self, block_num: u64) -> ChainRules {
+        let timestamp = self
+            .headers
+            .get(&block_num)
+            .or_else(|| self.headers.values().next())
+            .map_or(0, |header| header.timestamp.low_u64());
+        self.chain_spec.rules(block_num, timestamp)
+    }
+
+    /// Set the hardfork schedule of the chain.
+    pub fn with_chain_spec(mut self, chain_spec: ChainSpec) -> Self {
+        self.chain_spec = chain_spec;
+        self
+    }
+
+    /// Return the gas schedule of the chain.
+    pub fn gas_schedule(&self) -> &dyn GasSchedule {
+        self.gas_schedule
+            .as_deref()
+            .unwrap_or(&self.circuits_params.gas_table)
+    }
+
+    /// Set the gas schedule of the chain. The circuits only prove the block
+    /// if it is the gas table of their [`CircuitsParams`].
+    pub fn with_gas_schedule(mut self, gas_schedule: Arc<dyn GasSchedule>) -> Self {
+        self.gas_schedule = Some(gas_schedule);
+        self
+    }
+
     /// Return if the relax mode
     pub fn is_relaxed(&self) -> bool {
         self.relax_mode
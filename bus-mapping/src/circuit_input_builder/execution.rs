@@ -14,6 +14,7 @@ use crate::{
 };
 use eth_types::{
     evm_types::{memory::MemoryWordRange, Gas, GasCost, MemoryAddress, OpcodeId, ProgramCounter},
+    halo2_serde,
     sign_types::SignData,
     Address, Field, GethExecStep, ToLittleEndian, Word, H256, U256,
 };
@@ -27,6 +28,7 @@ use halo2_proofs::{
     },
     plonk::Expression,
 };
+use serde::{Deserialize, Serialize};
 
 /// An execution step of the EVM.
 #[derive(Clone, Debug)]
@@ -204,7 +206,7 @@ impl ExecState {
 }
 
 /// Defines the various source/destination types for a copy event.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum CopyDataType {
     /// When we need to pad the Copy rows of the circuit up to a certain maximum
     /// with rows that are not "useful".
@@ -358,7 +360,7 @@ pub struct CopyStep {
 }
 
 /// Defines an enum type that can hold either a number or a hash value.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum NumberOrHash {
     /// Variant to indicate a number value.
     Number(usize),
@@ -377,7 +379,7 @@ pub enum NumberOrHash {
 ///
 /// Additionally, when the destination is memory, `bytes_write_prev` holds the memory content
 /// *before* the write.
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct CopyBytes {
     /// Represents the list of (bytes, is_code, mask) copied during this copy event
     pub bytes: Vec<(u8, bool, bool)>,
@@ -408,7 +410,7 @@ impl CopyBytes {
 /// Save address, storage_key, storage_key_index and is_warm_prev
 /// to column value_word_rlc, value_word_rlc_prev, value and
 /// value_prev in copy circuit.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct CopyAccessList {
     /// Access list address
     pub address: Address,
@@ -444,7 +446,7 @@ impl CopyAccessList {
 /// Defines a copy event associated with EVM opcodes such as CALLDATACOPY,
 /// CODECOPY, CREATE, etc. More information:
 /// <https://github.com/privacy-scaling-explorations/zkevm-specs/blob/master/specs/copy-proof.md>.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct CopyEvent {
     /// Represents the start address at the source of the copy event.
     pub src_addr: u64,
@@ -846,7 +848,7 @@ where
 }
 
 /// Intermediary multiplication step, representing `a * b == d (mod 2^256)`
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct ExpStep {
     /// First multiplicand.
     pub a: Word,
@@ -867,7 +869,7 @@ impl From<(Word, Word, Word)> for ExpStep {
 }
 
 /// Event representating an exponentiation `a ^ b == d (mod 2^256)`.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ExpEvent {
     /// Base `a` for the exponentiation.
     pub base: Word,
@@ -895,7 +897,7 @@ impl Default for ExpEvent {
 }
 
 /// I/Os from all precompiled contract calls in a block.
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct PrecompileEvents {
     /// All events.
     pub events: Vec<PrecompileEvent>,
@@ -989,7 +991,7 @@ impl PrecompileEvents {
 }
 
 /// I/O from a precompiled contract call.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum PrecompileEvent {
     /// Represents the I/O from Ecrecover call.
     Ecrecover(SignData),
@@ -1012,13 +1014,14 @@ impl Default for PrecompileEvent {
 }
 
 /// EcAdd operation: P + Q = R
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct EcAddOp {
     /// EVM input for first operand to EcAdd.
     pub p: (U256, U256),
     /// EVM input for second operand to EcAdd.
     pub q: (U256, U256),
     /// Addition of the first and second EC points.
+    #[serde(with = "halo2_serde::option_point")]
     pub r: Option<G1Affine>,
 }
 
@@ -1114,13 +1117,15 @@ impl EcAddOp {
 }
 
 /// EcMul operation: s.P = R
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct EcMulOp {
     /// The EVM inputs to the G1 point.
     pub p: (U256, U256),
     /// Scalar.
+    #[serde(with = "halo2_serde::field")]
     pub s: Fr,
     /// Result for s.P = R, that is `None` in the case of an erroneous input.
+    #[serde(with = "halo2_serde::option_point")]
     pub r: Option<G1Affine>,
 }
 
@@ -1230,7 +1235,7 @@ pub const N_PAIRING_PER_OP: usize = 4;
 pub const N_BYTES_PER_PAIR: usize = 192;
 
 /// Pair of (G1, G2).
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct EcPairingPair {
     /// EVM inputs for the G1 point.
     pub g1_point: (U256, U256),
@@ -1332,7 +1337,7 @@ impl EcPairingPair {
 }
 
 /// EcPairing operation
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct EcPairingOp {
     /// tuples of G1 and G2 points supplied to the ECC circuit.
     pub pairs: [EcPairingPair; N_PAIRING_PER_OP],
@@ -1427,7 +1432,7 @@ impl EcPairingOp {
 }
 
 /// Event representating an exponentiation `a ^ b == d (mod m)` in precompile modexp.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct BigModExp {
     /// Base `a` for the exponentiation.
     pub base: Word,
@@ -1451,7 +1456,7 @@ impl Default for BigModExp {
 }
 
 /// Event representating an SHA256 hash in precompile sha256.
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct SHA256 {
     /// input bytes
     pub input: Vec<u8>,
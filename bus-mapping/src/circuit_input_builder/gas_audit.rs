@@ -0,0 +1,891 @@
+//! Opt-in audit of the gas accounting against the trace.
+//!
+//! When enabled with [`CircuitInputBuilder::enable_gas_audit`], the gas of
+//! every step is recomputed from the state tracked by the bus-mapping before
+//! the step (memory size, access list, storage and accounts), independently of
+//! the opcode handlers, and compared with the `gas_cost`, `gas` and `refund`
+//! reported by the tracer. Steps failing with an error are not recomputed, and
+//! steps whose gas the audit fails to recompute are reported as errors instead
+//! of failing the handling of the transaction.
+//!
+//! [`CircuitInputBuilder::enable_gas_audit`]: super::CircuitInputBuilder::enable_gas_audit
+
+use super::CircuitInputStateRef;
+use crate::Error;
+use eth_types::{
+    evm_types::{
+        gas_utils::{eip150_gas, memory_copier_gas_cost, memory_expansion_gas_cost},
+        GasCost, OpcodeId, GAS_STIPEND_CALL_WITH_VALUE, MAX_CODE_SIZE, MAX_EXPANDED_MEMORY_ADDRESS,
+    },
+    Address, GethExecError, GethExecStep, ToAddress, Word,
+};
+use std::fmt;
+
+/// Gas quantity on which the audit and the trace disagree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GasMismatchKind {
+    /// Gas cost of the step
+    GasCost,
+    /// Gas left at the first step of a callee, or at the step of the caller
+    /// following the end of a callee
+    GasLeft,
+    /// Refund counter after the step
+    Refund,
+}
+
+/// A disagreement between the gas expected by the audit and the trace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GasMismatch {
+    /// Kind of the mismatch
+    pub kind: GasMismatchKind,
+    /// Index of the tx
+    pub tx_id: usize,
+    /// Index of the step in the struct logs
+    pub step: usize,
+    /// Opcode of the step
+    pub op: OpcodeId,
+    /// Program counter of the step
+    pub pc: u64,
+    /// Call depth of the step
+    pub depth: u16,
+    /// Value expected by the audit
+    pub expected: u64,
+    /// Value in the trace
+    pub trace: u64,
+}
+
+impl fmt::Display for GasMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:?} mismatch in tx {} at step {} ({:?}, pc {}, depth {}): expected {}, trace {}",
+            self.kind,
+            self.tx_id,
+            self.step,
+            self.op,
+            self.pc,
+            self.depth,
+            self.expected,
+            self.trace
+        )
+    }
+}
+
+/// A step whose gas the audit failed to recompute.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GasAuditError {
+    /// Index of the tx
+    pub tx_id: usize,
+    /// Index of the step in the struct logs
+    pub step: usize,
+    /// Opcode of the step
+    pub op: OpcodeId,
+    /// Program counter of the step
+    pub pc: u64,
+    /// Call depth of the step
+    pub depth: u16,
+    /// Error of the audit
+    pub error: String,
+}
+
+impl fmt::Display for GasAuditError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "audit error in tx {} at step {} ({:?}, pc {}, depth {}): {}",
+            self.tx_id, self.step, self.op, self.pc, self.depth, self.error
+        )
+    }
+}
+
+/// Result of the gas audit of the handled transactions.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GasAuditReport {
+    /// Number of audited steps
+    pub num_steps: usize,
+    /// Disagreements with the trace, in execution order
+    pub mismatches: Vec<GasMismatch>,
+    /// Steps whose gas couldn't be recomputed, in execution order
+    pub errors: Vec<GasAuditError>,
+}
+
+impl GasAuditReport {
+    /// Returns true if the audit agrees with the trace at every step.
+    pub fn is_ok(&self) -> bool {
+        self.mismatches.is_empty() && self.errors.is_empty()
+    }
+
+    pub(crate) fn extend(&mut self, other: GasAuditReport) {
+        self.num_steps += other.num_steps;
+        self.mismatches.extend(other.mismatches);
+        self.errors.extend(other.errors);
+    }
+}
+
+impl fmt::Display for GasAuditReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "gas audit of {} steps: {} mismatches, {} errors",
+            self.num_steps,
+            self.mismatches.len(),
+            self.errors.len()
+        )?;
+        for mismatch in self.mismatches.iter() {
+            writeln!(f, "  {mismatch}")?;
+        }
+        for error in self.errors.iter() {
+            writeln!(f, "  {error}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Gas of a step expected by the audit.
+struct ExpectedGas {
+    /// Gas cost of the step. As in geth, the cost of a call includes the gas
+    /// passed to the callee but not the stipend.
+    cost: u64,
+    /// Gas left at the first step of the callee of a call or create
+    callee_gas: u64,
+    /// Change of the refund counter
+    refund: i64,
+}
+
+/// A call or create being executed.
+struct Frame {
+    /// Gas left to the caller while the callee runs, `None` if unknown
+    caller_gas_left: Option<u64>,
+    is_create: bool,
+}
+
+/// Audits the steps of a transaction, one at a time before they are handled.
+pub(crate) struct GasAuditor {
+    tx_id: usize,
+    frames: Vec<Frame>,
+    report: GasAuditReport,
+}
+
+impl GasAuditor {
+    pub(crate) fn new(tx_id: usize) -> Self {
+        Self {
+            tx_id,
+            frames: Vec::new(),
+            report: GasAuditReport::default(),
+        }
+    }
+
+    /// Audits `geth_steps[index]`, with the state before it. A failure to
+    /// recompute the gas is recorded in the report, and the gas of the step is
+    /// then unknown.
+    pub(crate) fn audit_step(
+        &mut self,
+        state: &CircuitInputStateRef,
+        geth_steps: &[GethExecStep],
+        index: usize,
+    ) {
+        let step = &geth_steps[index];
+        let expected = if step.error.is_none() {
+            let expected = expected_gas(state, step);
+            self.ok_or_record(index, step, expected)
+        } else {
+            None
+        };
+        self.report.num_steps += 1;
+
+        if let Some(expected) = &expected {
+            self.check(
+                GasMismatchKind::GasCost,
+                index,
+                step,
+                expected.cost,
+                step.gas_cost.0,
+            );
+        }
+
+        let Some(next_step) = geth_steps.get(index + 1) else {
+            return;
+        };
+        if next_step.depth > step.depth {
+            // Entering a callee, with the 63/64 rule and the call stipend.
+            let caller_gas_left = expected.as_ref().map(|expected| {
+                self.check(
+                    GasMismatchKind::GasLeft,
+                    index,
+                    step,
+                    expected.callee_gas,
+                    next_step.gas.0,
+                );
+                let gas_left = step.gas.0.saturating_sub(expected.cost);
+                if step.op.is_create() {
+                    gas_left.saturating_sub(expected.callee_gas)
+                } else {
+                    gas_left
+                }
+            });
+            self.frames.push(Frame {
+                caller_gas_left,
+                is_create: step.op.is_create(),
+            });
+        } else if next_step.depth < step.depth {
+            // Returning to the caller, with the gas left by the callee.
+            let Some(frame) = self.frames.pop() else {
+                return;
+            };
+            let returned_gas = returned_gas(state, step, expected.as_ref(), frame.is_create);
+            if let (Some(caller_gas_left), Some(Some(callee_gas_left))) = (
+                frame.caller_gas_left,
+                self.ok_or_record(index, step, returned_gas),
+            ) {
+                self.check(
+                    GasMismatchKind::GasLeft,
+                    index,
+                    step,
+                    caller_gas_left + callee_gas_left,
+                    next_step.gas.0,
+                );
+            }
+        } else if let Some(expected) = &expected {
+            if step.op == OpcodeId::SSTORE {
+                self.check(
+                    GasMismatchKind::Refund,
+                    index,
+                    step,
+                    (step.refund.0 as i64 + expected.refund) as u64,
+                    next_step.refund.0,
+                );
+            }
+        }
+    }
+
+    /// Returns the report of the audited steps.
+    pub(crate) fn finish(self) -> GasAuditReport {
+        self.report
+    }
+
+    fn ok_or_record<T>(
+        &mut self,
+        index: usize,
+        step: &GethExecStep,
+        result: Result<T, Error>,
+    ) -> Option<T> {
+        result
+            .map_err(|error| {
+                self.report.errors.push(GasAuditError {
+                    tx_id: self.tx_id,
+                    step: index,
+                    op: step.op,
+                    pc: step.pc.0 as u64,
+                    depth: step.depth,
+                    error: error.to_string(),
+                })
+            })
+            .ok()
+    }
+
+    fn check(
+        &mut self,
+        kind: GasMismatchKind,
+        index: usize,
+        step: &GethExecStep,
+        expected: u64,
+        trace: u64,
+    ) {
+        if expected != trace {
+            self.report.mismatches.push(GasMismatch {
+                kind,
+                tx_id: self.tx_id,
+                step: index,
+                op: step.op,
+                pc: step.pc.0 as u64,
+                depth: step.depth,
+                expected,
+                trace,
+            });
+        }
+    }
+}
+
+/// Memory size in words after accessing `length` bytes at `offset`, or `None`
+/// if the access is beyond the maximum memory size, which runs out of gas.
+fn next_memory_word_size(curr_memory_word_size: u64, offset: Word, length: Word) -> Option<u64> {
+    if length.is_zero() {
+        return Some(curr_memory_word_size);
+    }
+    let end = offset
+        .checked_add(length)
+        .filter(|end| *end <= Word::from(MAX_EXPANDED_MEMORY_ADDRESS))?;
+    Some(curr_memory_word_size.max((end.as_u64() + 31) / 32))
+}
+
+/// Recomputes the gas cost of a step without error, with the state before it.
+#[cfg(feature = "test")]
+pub(crate) fn expected_gas_cost(
+    state: &CircuitInputStateRef,
+    step: &GethExecStep,
+) -> Result<u64, Error> {
+    expected_gas(state, step).map(|expected| expected.cost)
+}
+
+/// Recomputes the gas of a step without error.
+fn expected_gas(state: &CircuitInputStateRef, step: &GethExecStep) -> Result<ExpectedGas, Error> {
+    let call_ctx = state.call_ctx()?;
+    let stack = &call_ctx.stack;
+    let curr_memory_word_size = call_ctx.memory_word_size();
+    let memory_word_size_after = |offset: Word, length: Word| {
+        next_memory_word_size(curr_memory_word_size, offset, length).ok_or(Error::InternalError(
+            "memory access without out of gas error",
+        ))
+    };
+    let memory_gas = |offset: Word, length: Word| {
+        memory_word_size_after(offset, length)
+            .map(|next| memory_expansion_gas_cost(curr_memory_word_size, next))
+    };
+    let copier_gas = |offset: Word, length: Word, per_word_copy_gas: GasCost| {
+        memory_word_size_after(offset, length).map(|next| {
+            memory_copier_gas_cost(
+                curr_memory_word_size,
+                next,
+                length.as_u64(),
+                per_word_copy_gas.as_u64(),
+            )
+        })
+    };
+    let account_access_gas = |address: &Address| {
+        if state.sdb.check_account_in_access_list(address) {
+            GasCost::WARM_ACCESS.as_u64()
+        } else {
+            GasCost::COLD_ACCOUNT_ACCESS.as_u64()
+        }
+    };
+    let num_words = |length: Word| (length.as_u64() + 31) / 32;
+    let constant_gas = state
+        .block
+        .gas_schedule()
+        .constant_gas_cost(step.op)
+        .as_u64();
+
+    let mut callee_gas = 0;
+    let mut refund = 0;
+    let cost = match step.op {
+        OpcodeId::SHA3 => {
+            constant_gas + copier_gas(stack.last()?, stack.nth_last(1)?, GasCost::COPY_SHA3)?
+        }
+        OpcodeId::CALLDATACOPY | OpcodeId::CODECOPY | OpcodeId::RETURNDATACOPY => {
+            constant_gas + copier_gas(stack.last()?, stack.nth_last(2)?, GasCost::COPY)?
+        }
+        OpcodeId::EXTCODECOPY => {
+            account_access_gas(&stack.last()?.to_address())
+                + copier_gas(stack.nth_last(1)?, stack.nth_last(3)?, GasCost::COPY)?
+        }
+        OpcodeId::BALANCE | OpcodeId::EXTCODESIZE | OpcodeId::EXTCODEHASH => {
+            account_access_gas(&stack.last()?.to_address())
+        }
+        OpcodeId::MLOAD | OpcodeId::MSTORE => constant_gas + memory_gas(stack.last()?, 32.into())?,
+        OpcodeId::MSTORE8 => constant_gas + memory_gas(stack.last()?, 1.into())?,
+        OpcodeId::EXP => {
+            let exponent_byte_size = (stack.nth_last(1)?.bits() as u64 + 7) / 8;
+            constant_gas + GasCost::EXP_BYTE_TIMES.as_u64() * exponent_byte_size
+        }
+        OpcodeId::LOG0 | OpcodeId::LOG1 | OpcodeId::LOG2 | OpcodeId::LOG3 | OpcodeId::LOG4 => {
+            let topic_count = (step.op.as_u8() - OpcodeId::LOG0.as_u8()) as u64;
+            let length = stack.nth_last(1)?;
+            let memory_gas = memory_gas(stack.last()?, length)?;
+            GasCost::LOG.as_u64() * (1 + topic_count) + 8 * length.as_u64() + memory_gas
+        }
+        OpcodeId::RETURN | OpcodeId::REVERT => memory_gas(stack.last()?, stack.nth_last(1)?)?,
+        OpcodeId::CREATE | OpcodeId::CREATE2 => {
+            let length = stack.nth_last(2)?;
+            let memory_gas = memory_gas(stack.nth_last(1)?, length)?;
+            let gas_per_code_word = state.rules().create_gas_per_code_word(step.op);
+            let cost =
+                GasCost::CREATE.as_u64() + memory_gas + gas_per_code_word * num_words(length);
+            // all but one 64th of the gas left is passed to the callee
+            let gas_left = step.gas.0.saturating_sub(cost);
+            callee_gas = gas_left - gas_left / 64;
+            cost
+        }
+        OpcodeId::SLOAD => {
+            let key = stack.last()?;
+            if state
+                .sdb
+                .check_account_storage_in_access_list(&(state.call()?.address, key))
+            {
+                GasCost::WARM_ACCESS.as_u64()
+            } else {
+                GasCost::COLD_SLOAD.as_u64()
+            }
+        }
+        OpcodeId::SSTORE => {
+            let address = state.call()?.address;
+            let key = stack.last()?;
+            let value = stack.nth_last(1)?;
+            let current = *state.sdb.get_storage(&address, &key).1;
+            let original = *state.sdb.get_committed_storage(&address, &key).1;
+            let (cost, sstore_refund) = sstore_gas(original, current, value);
+            refund = sstore_refund;
+            cost + if state
+                .sdb
+                .check_account_storage_in_access_list(&(address, key))
+            {
+                0
+            } else {
+                GasCost::COLD_SLOAD.as_u64()
+            }
+        }
+        OpcodeId::CALL | OpcodeId::CALLCODE | OpcodeId::DELEGATECALL | OpcodeId::STATICCALL => {
+            let has_value = matches!(step.op, OpcodeId::CALL | OpcodeId::CALLCODE);
+            let address = stack.nth_last(1)?.to_address();
+            let (value, args_pos) = if has_value {
+                (stack.nth_last(2)?, 3)
+            } else {
+                (Word::zero(), 2)
+            };
+            let next_memory_word_size =
+                memory_word_size_after(stack.nth_last(args_pos)?, stack.nth_last(args_pos + 1)?)?
+                    .max(memory_word_size_after(
+                        stack.nth_last(args_pos + 2)?,
+                        stack.nth_last(args_pos + 3)?,
+                    )?);
+
+            let transfer_gas = if value.is_zero() {
+                0
+            } else {
+                GasCost::CALL_WITH_VALUE.as_u64()
+                    + if step.op == OpcodeId::CALL && state.sdb.get_account(&address).1.is_empty() {
+                        GasCost::NEW_ACCOUNT.as_u64()
+                    } else {
+                        0
+                    }
+            };
+            let cost = account_access_gas(&address)
+                + transfer_gas
+                + memory_expansion_gas_cost(curr_memory_word_size, next_memory_word_size);
+            let call_gas = eip150_gas(step.gas.0.saturating_sub(cost), stack.last()?);
+            let stipend = if value.is_zero() {
+                0
+            } else {
+                GAS_STIPEND_CALL_WITH_VALUE
+            };
+            callee_gas = call_gas + stipend;
+            cost + call_gas
+        }
+        OpcodeId::SELFDESTRUCT => {
+            let beneficiary = stack.last()?.to_address();
+            let balance = state.sdb.get_balance(&state.call()?.address);
+            GasCost::SELFDESTRUCT.as_u64()
+                + if state.sdb.check_account_in_access_list(&beneficiary) {
+                    0
+                } else {
+                    GasCost::COLD_ACCOUNT_ACCESS.as_u64()
+                }
+                + if !balance.is_zero() && state.sdb.get_account(&beneficiary).1.is_empty() {
+                    GasCost::NEW_ACCOUNT.as_u64()
+                } else {
+                    0
+                }
+        }
+        _ => constant_gas,
+    };
+
+    Ok(ExpectedGas {
+        cost,
+        callee_gas,
+        refund,
+    })
+}
+
+/// Gas cost of SSTORE with a warm slot and change of the refund counter,
+/// following EIP-2200, EIP-2929 and EIP-3529.
+fn sstore_gas(original: Word, current: Word, value: Word) -> (u64, i64) {
+    let clears_schedule = GasCost::SSTORE_CLEARS_SCHEDULE.as_u64() as i64;
+    if current == value {
+        return (GasCost::WARM_ACCESS.as_u64(), 0);
+    }
+    if original == current {
+        if original.is_zero() {
+            return (GasCost::SSTORE_SET.as_u64(), 0);
+        }
+        let refund = if value.is_zero() { clears_schedule } else { 0 };
+        return (GasCost::SSTORE_RESET.as_u64(), refund);
+    }
+
+    let mut refund = 0;
+    if !original.is_zero() {
+        if current.is_zero() {
+            refund -= clears_schedule;
+        } else if value.is_zero() {
+            refund += clears_schedule;
+        }
+    }
+    if original == value {
+        refund += if original.is_zero() {
+            GasCost::SSTORE_SET.as_u64() - GasCost::WARM_ACCESS.as_u64()
+        } else {
+            GasCost::SSTORE_RESET.as_u64() - GasCost::WARM_ACCESS.as_u64()
+        } as i64;
+    }
+    (GasCost::WARM_ACCESS.as_u64(), refund)
+}
+
+/// Gas left by the last step of a callee and returned to the caller, `None` if
+/// unknown.
+fn returned_gas(
+    state: &CircuitInputStateRef,
+    step: &GethExecStep,
+    expected: Option<&ExpectedGas>,
+    is_create: bool,
+) -> Result<Option<u64>, Error> {
+    // Errors other than a revert consume all the gas.
+    if step
+        .error
+        .is_some_and(|error| error != GethExecError::ExecutionReverted)
+    {
+        return Ok(Some(0));
+    }
+    let Some(expected) = expected else {
+        return Ok(None);
+    };
+    let gas_left = step.gas.0.saturating_sub(expected.cost);
+
+    Ok(match step.op {
+        OpcodeId::RETURN if is_create => {
+            // The deployed code is charged per byte, and invalid code consumes
+            // all the gas.
+            let stack = &state.call_ctx()?.stack;
+            let offset = stack.last()?;
+            let length = stack.nth_last(1)?.as_u64();
+            let first_byte = state
+                .call_ctx()?
+                .memory
+                .0
+                .get(offset.low_u64() as usize)
+                .copied();
+            let code_deposit_cost = GasCost::CODE_DEPOSIT_BYTE_COST.as_u64() * length;
+            if length > MAX_CODE_SIZE
+                || (length > 0 && first_byte == Some(0xef))
+                || code_deposit_cost > gas_left
+            {
+                Some(0)
+            } else {
+                Some(gas_left - code_deposit_cost)
+            }
+        }
+        OpcodeId::STOP | OpcodeId::RETURN | OpcodeId::REVERT | OpcodeId::SELFDESTRUCT => {
+            Some(gas_left)
+        }
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod gas_audit_tests {
+    use super::*;
+    use crate::mock::BlockData;
+    use eth_types::{bytecode, geth_types::GethData, Bytecode, ToWord};
+    use mock::{eth, TestContext, MOCK_ACCOUNTS};
+    use pretty_assertions::assert_eq;
+
+    fn gen_block() -> GethData {
+        let callee = bytecode! {
+            // refunded by the reset to the original value
+            PUSH1(1)
+            PUSH1(0)
+            SSTORE
+            PUSH1(0)
+            PUSH1(0)
+            SSTORE
+            CALLER
+            BALANCE
+            POP
+            STOP
+        };
+        let code = bytecode! {
+            // memory expansion
+            PUSH1(0x20)
+            PUSH1(0x40)
+            MSTORE
+            PUSH1(0x40)
+            PUSH1(0)
+            SHA3
+            POP
+            PUSH1(3)
+            PUSH1(2)
+            EXP
+            POP
+            PUSH1(0x40)
+            PUSH1(0x20)
+            LOG0
+            // cold and warm slot, with refunds
+            PUSH1(0)
+            SLOAD
+            POP
+            PUSH1(0)
+            PUSH1(0)
+            SSTORE
+            PUSH1(2)
+            PUSH1(0)
+            SSTORE
+            PUSH1(1)
+            PUSH1(0)
+            SSTORE
+            // cold and warm account
+            PUSH20(MOCK_ACCOUNTS[2].to_word())
+            BALANCE
+            POP
+            PUSH20(MOCK_ACCOUNTS[2].to_word())
+            EXTCODESIZE
+            POP
+            // value transfer to an empty account
+            PUSH1(0)
+            PUSH1(0)
+            PUSH1(0)
+            PUSH1(0)
+            PUSH1(1)
+            PUSH2(0x1234)
+            PUSH2(0xffff)
+            CALL
+            POP
+            // call with the stipend and the 63/64 rule
+            PUSH1(0x20)
+            PUSH1(0x80)
+            PUSH1(0x20)
+            PUSH1(0)
+            PUSH1(1)
+            PUSH20(MOCK_ACCOUNTS[2].to_word())
+            GAS
+            CALL
+            POP
+            // create with a code deposit
+            PUSH5(0x60016000f3u64)
+            PUSH1(0)
+            MSTORE
+            PUSH1(5)
+            PUSH1(27)
+            PUSH1(0)
+            CREATE
+            POP
+            STOP
+        };
+
+        TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(MOCK_ACCOUNTS[0])
+                    .balance(eth(10))
+                    .code(code)
+                    .storage(vec![(Word::zero(), Word::one())].into_iter());
+                accs[1].address(MOCK_ACCOUNTS[1]).balance(eth(10));
+                accs[2]
+                    .address(MOCK_ACCOUNTS[2])
+                    .balance(eth(10))
+                    .code(callee);
+            },
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[1].address)
+                    .to(accs[0].address)
+                    .gas(Word::from(1_000_000u64));
+            },
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap()
+        .into()
+    }
+
+    /// A block whose tx calls `code`, which can call the `callees` at
+    /// `MOCK_ACCOUNTS[2]` and `MOCK_ACCOUNTS[3]`.
+    fn gen_block_with_callees(code: Bytecode, callees: [Bytecode; 2]) -> GethData {
+        let [callee_0, callee_1] = callees;
+        TestContext::<4, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(MOCK_ACCOUNTS[0])
+                    .balance(eth(10))
+                    .code(code);
+                accs[1].address(MOCK_ACCOUNTS[1]).balance(eth(10));
+                accs[2]
+                    .address(MOCK_ACCOUNTS[2])
+                    .balance(eth(10))
+                    .code(callee_0);
+                accs[3]
+                    .address(MOCK_ACCOUNTS[3])
+                    .balance(eth(10))
+                    .code(callee_1);
+            },
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[1].address)
+                    .to(accs[0].address)
+                    .gas(Word::from(1_000_000u64));
+            },
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap()
+        .into()
+    }
+
+    /// Calls the contract at `address` with all the gas and no value.
+    fn call(address: Address) -> Bytecode {
+        bytecode! {
+            PUSH1(0)
+            PUSH1(0)
+            PUSH1(0)
+            PUSH1(0)
+            PUSH1(0)
+            PUSH20(address.to_word())
+            GAS
+            CALL
+            POP
+        }
+    }
+
+    fn assert_audit_ok(block: &GethData) {
+        let report = audit(block);
+        assert!(report.is_ok(), "{report}");
+        assert_eq!(report.num_steps, block.geth_traces[0].struct_logs.len());
+    }
+
+    fn audit(block: &GethData) -> GasAuditReport {
+        let mut builder = BlockData::new_from_geth_data(block.clone())
+            .new_circuit_input_builder()
+            .enable_gas_audit();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        builder.gas_audit.unwrap()
+    }
+
+    #[test]
+    fn gas_audit_matches_trace() {
+        assert_audit_ok(&gen_block());
+    }
+
+    #[test]
+    fn gas_audit_create2_and_extcodecopy() {
+        let code = bytecode! {
+            // cold and warm account, with memory expansion
+            PUSH1(0x40)
+            PUSH1(0)
+            PUSH1(0x20)
+            PUSH20(MOCK_ACCOUNTS[2].to_word())
+            EXTCODECOPY
+            PUSH1(0x20)
+            PUSH1(0)
+            PUSH1(0x60)
+            PUSH20(MOCK_ACCOUNTS[2].to_word())
+            EXTCODECOPY
+            // create2 with a code deposit, then colliding with it
+            PUSH5(0x60016000f3u64)
+            PUSH1(0)
+            MSTORE
+            PUSH1(0xff)
+            PUSH1(5)
+            PUSH1(27)
+            PUSH1(0)
+            CREATE2
+            POP
+            PUSH1(0xff)
+            PUSH1(5)
+            PUSH1(27)
+            PUSH1(0)
+            CREATE2
+            POP
+            STOP
+        };
+        let callee = bytecode! {
+            PUSH1(0x10)
+            STOP
+        };
+        let block = gen_block_with_callees(code, [callee.clone(), callee]);
+        assert_audit_ok(&block);
+    }
+
+    #[test]
+    fn gas_audit_failing_and_reverting_callees() {
+        let mut code = call(MOCK_ACCOUNTS[2]);
+        code.append(&call(MOCK_ACCOUNTS[3]));
+        code.op_stop();
+        // the gas left by the revert is returned to the caller
+        let reverting = bytecode! {
+            PUSH1(0x20)
+            PUSH1(0x20)
+            REVERT
+        };
+        // an invalid jump consumes all the gas
+        let failing = bytecode! {
+            PUSH1(0)
+            JUMP
+        };
+        let block = gen_block_with_callees(code, [reverting, failing]);
+        assert_audit_ok(&block);
+    }
+
+    // SELFDESTRUCT is disabled in scroll
+    #[cfg(not(feature = "scroll"))]
+    #[test]
+    fn gas_audit_selfdestruct() {
+        let mut code = call(MOCK_ACCOUNTS[2]);
+        code.append(&call(MOCK_ACCOUNTS[3]));
+        code.op_stop();
+        // to an empty account with a balance, and to a warm account
+        let to_empty = bytecode! {
+            PUSH20(MOCK_ACCOUNTS[4].to_word())
+            SELFDESTRUCT
+        };
+        let to_caller = bytecode! {
+            CALLER
+            SELFDESTRUCT
+        };
+        let block = gen_block_with_callees(code, [to_empty, to_caller]);
+        assert_audit_ok(&block);
+    }
+
+    #[test]
+    fn gas_audit_report_errors() {
+        let mut report = GasAuditReport {
+            num_steps: 1,
+            ..Default::default()
+        };
+        assert!(report.is_ok());
+        report.errors.push(GasAuditError {
+            tx_id: 1,
+            step: 0,
+            op: OpcodeId::SHA3,
+            pc: 0,
+            depth: 1,
+            error: Error::InternalError("memory access without out of gas error").to_string(),
+        });
+        assert!(!report.is_ok());
+        assert!(report.to_string().contains("1 errors"), "{report}");
+    }
+
+    #[test]
+    fn gas_audit_reports_mismatch() {
+        let mut block = gen_block();
+        let step = block.geth_traces[0]
+            .struct_logs
+            .iter()
+            .position(|step| step.op == OpcodeId::SHA3)
+            .unwrap();
+        let gas_cost = block.geth_traces[0].struct_logs[step].gas_cost.0;
+        block.geth_traces[0].struct_logs[step].gas_cost = GasCost(gas_cost + 1);
+
+        let report = audit(&block);
+        assert_eq!(
+            report.mismatches,
+            vec![GasMismatch {
+                kind: GasMismatchKind::GasCost,
+                tx_id: 1,
+                step,
+                op: OpcodeId::SHA3,
+                pc: block.geth_traces[0].struct_logs[step].pc.0 as u64,
+                depth: 1,
+                expected: gas_cost,
+                trace: gas_cost + 1,
+            }]
+        );
+    }
+}
@@ -25,6 +25,7 @@ use crate::{
 };
 use eth_types::{
     bytecode::BytecodeElement,
+    chain_spec::ChainRules,
     evm_types::{
         gas_utils::memory_expansion_gas_cost,
         memory::{MemoryRange, MemoryWordRange},
@@ -1036,7 +1037,12 @@ impl<'a> CircuitInputStateRef<'a> {
 
     /// Check if address is a precompiled or not.
     pub fn is_precompiled(&self, address: &Address) -> bool {
-        address.0[0..19] == [0u8; 19] && (1..=9).contains(&address.0[19])
+        self.rules().is_precompiled(address)
+    }
+
+    /// Return the rules of the block of the current transaction.
+    pub fn rules(&self) -> ChainRules {
+        self.block.rules(self.tx.block_num)
     }
 
     /// Parse [`Call`] from a *CALL*/CREATE* step without information about success and persistent.
@@ -1620,7 +1626,7 @@ impl<'a> CircuitInputStateRef<'a> {
         step: &GethExecStep,
         next_step: Option<&GethExecStep>,
     ) -> Result<Option<ExecError>, Error> {
-        if matches!(step.op, OpcodeId::INVALID(_)) {
+        if !self.rules().is_opcode_enabled(step.op) {
             return Ok(Some(ExecError::InvalidOpcode));
         }
 
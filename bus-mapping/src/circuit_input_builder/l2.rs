@@ -12,7 +12,7 @@ use eth_types::{
     Address, ToWord, Word, H256,
 };
 use ethers_core::types::Bytes;
-use mpt_zktrie::state::{AccountData, ZktrieState};
+use mpt_zktrie::state::{AccountData, NodeStore, ZktrieState};
 use std::collections::hash_map::{Entry, HashMap};
 
 impl From<&AccountData> for state_db::Account {
@@ -338,6 +338,7 @@ impl CircuitInputBuilder {
             block: block.clone(),
             block_ctx: BlockContext::new(),
             mpt_init_state: Some(mpt_init_state),
+            gas_audit: None,
         }
     }
 
@@ -347,6 +348,19 @@ impl CircuitInputBuilder {
         l2_trace: BlockTrace,
         more: bool,
         light_mode: bool,
+    ) -> Result<Self, Error> {
+        let node_store = (!light_mode).then(NodeStore::in_memory);
+        Self::new_from_l2_trace_with_store(circuits_params, l2_trace, more, node_store)
+    }
+
+    /// Create a new CircuitInputBuilder from the given `l2_trace` and `circuits_params`,
+    /// building the zktrie state on `node_store` so the trie nodes already in the store are
+    /// not imported again. The zktrie state is skipped (light mode) if `node_store` is `None`.
+    pub fn new_from_l2_trace_with_store(
+        circuits_params: CircuitsParams,
+        l2_trace: BlockTrace,
+        more: bool,
+        node_store: Option<NodeStore>,
     ) -> Result<Self, Error> {
         let chain_id = l2_trace.chain_id;
 
@@ -357,9 +371,10 @@ impl CircuitInputBuilder {
             hex::encode(old_root),
         );
 
-        let mpt_init_state = if !light_mode {
-            let mpt_init_state = ZktrieState::from_trace_with_additional(
+        let mpt_init_state = if let Some(node_store) = node_store {
+            let mpt_init_state = ZktrieState::from_trace_with_store(
                 old_root,
+                node_store,
                 Self::collect_account_proofs(&l2_trace.storage_trace),
                 Self::collect_storage_proofs(&l2_trace.storage_trace),
                 l2_trace
@@ -416,6 +431,7 @@ impl CircuitInputBuilder {
             block: builder_block,
             block_ctx: BlockContext::new(),
             mpt_init_state,
+            gas_audit: None,
         };
 
         builder.apply_l2_trace(l2_trace, !more)?;
@@ -426,15 +442,17 @@ impl CircuitInputBuilder {
     pub fn add_more_l2_trace(&mut self, l2_trace: BlockTrace, more: bool) -> Result<(), Error> {
         // update init state new data from storage
         if let Some(mpt_init_state) = &mut self.mpt_init_state {
-            mpt_init_state.update_from_trace(
-                Self::collect_account_proofs(&l2_trace.storage_trace),
-                Self::collect_storage_proofs(&l2_trace.storage_trace),
-                l2_trace
-                    .storage_trace
-                    .deletion_proofs
-                    .iter()
-                    .map(Bytes::as_ref),
-            );
+            mpt_init_state
+                .update_from_trace(
+                    Self::collect_account_proofs(&l2_trace.storage_trace),
+                    Self::collect_storage_proofs(&l2_trace.storage_trace),
+                    l2_trace
+                        .storage_trace
+                        .deletion_proofs
+                        .iter()
+                        .map(Bytes::as_ref),
+                )
+                .map_err(Error::IoError)?;
         }
 
         let new_accounts = ZktrieState::parse_account_from_proofs(
@@ -9,10 +9,14 @@ use crate::{
 use eth_types::{
     evm_types::{gas_utils::tx_data_gas_cost, OpcodeId},
     geth_types,
-    geth_types::{get_rlp_unsigned, TxType},
+    geth_types::{
+        get_blob_versioned_hashes, get_max_fee_per_blob_gas, get_rlp_signed, get_rlp_unsigned,
+        TxType,
+    },
     AccessList, Address, GethExecTrace, Signature, Word, H256,
 };
 use ethers_core::utils::get_contract_address;
+use serde::{Deserialize, Serialize};
 
 /// Precision of transaction L1 fee
 pub const TX_L1_FEE_PRECISION: u64 = 1_000_000_000;
@@ -213,6 +217,10 @@ pub struct Transaction {
     pub l1_fee_committed: TxL1Fee,
     /// EIP2930
     pub access_list: Option<AccessList>,
+    /// EIP4844 max fee per blob gas
+    pub max_fee_per_blob_gas: Word,
+    /// EIP4844 blob versioned hashes
+    pub blob_versioned_hashes: Vec<H256>,
     /// Calls made in the transaction
     pub(crate) calls: Vec<Call>,
     /// Execution steps
@@ -235,6 +243,8 @@ impl From<&Transaction> for geth_types::Transaction {
             s: tx.signature.s,
             gas_fee_cap: Some(tx.gas_fee_cap),
             gas_tip_cap: Some(tx.gas_tip_cap),
+            max_fee_per_blob_gas: tx.tx_type.is_eip4844().then_some(tx.max_fee_per_blob_gas),
+            blob_versioned_hashes: tx.blob_versioned_hashes.clone(),
             rlp_unsigned_bytes: tx.rlp_unsigned_bytes.clone(),
             rlp_bytes: tx.rlp_bytes.clone(),
             tx_type: tx.tx_type,
@@ -272,6 +282,8 @@ impl Transaction {
             l1_fee: Default::default(),
             l1_fee_committed: Default::default(),
             access_list: None,
+            max_fee_per_blob_gas: Word::zero(),
+            blob_versioned_hashes: vec![],
         }
     }
 
@@ -363,7 +375,7 @@ impl Transaction {
             block_num: eth_tx.block_number.unwrap().as_u64(),
             hash: eth_tx.hash,
             tx_type,
-            rlp_bytes: eth_tx.rlp().to_vec(),
+            rlp_bytes: get_rlp_signed(eth_tx),
             rlp_unsigned_bytes: get_rlp_unsigned(eth_tx),
             nonce: eth_tx.nonce.as_u64(),
             gas: eth_tx.gas.as_u64(),
@@ -385,6 +397,8 @@ impl Transaction {
             l1_fee,
             l1_fee_committed,
             access_list: eth_tx.access_list.clone(),
+            max_fee_per_blob_gas: get_max_fee_per_blob_gas(eth_tx).unwrap_or_default(),
+            blob_versioned_hashes: get_blob_versioned_hashes(eth_tx),
         })
     }
 
@@ -439,6 +453,70 @@ impl Transaction {
 
         self.l1_fee.tx_l1_fee(tx_data_gas_cost).0
     }
+
+    /// Count the RW rows of each call of this transaction, and how many of
+    /// them come from reverted calls.
+    pub fn rw_stats(&self) -> TxRwStats {
+        let mut calls = self
+            .calls
+            .iter()
+            .enumerate()
+            .map(|(call_index, call)| CallRwStats {
+                call_index,
+                depth: call.depth,
+                is_reverted: !call.is_persistent,
+                ..Default::default()
+            })
+            .collect::<Vec<_>>();
+        for step in self.steps.iter() {
+            let call = &mut calls[step.call_index];
+            call.rw_rows += step.bus_mapping_instance.len();
+            call.reversible_writes += step.reversible_write_counter_delta;
+        }
+
+        TxRwStats {
+            rw_rows: calls.iter().map(|call| call.rw_rows).sum(),
+            reverted_rw_rows: calls
+                .iter()
+                .skip(1)
+                .filter(|call| call.is_reverted)
+                .map(|call| call.rw_rows)
+                .sum(),
+            calls,
+        }
+    }
+}
+
+/// RW rows of a call, see [`Transaction::rw_stats`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CallRwStats {
+    /// Index of the call in the transaction
+    pub call_index: usize,
+    /// Depth of the call
+    pub depth: usize,
+    /// Whether the call is reverted, by its own failure or by the failure of
+    /// one of its callers
+    pub is_reverted: bool,
+    /// RW rows of the steps of the call, without the ones of its sub-calls.
+    /// For a reverted call, the rows restoring its reversible writes are
+    /// included.
+    pub rw_rows: usize,
+    /// Reversible writes made by the call
+    pub reversible_writes: usize,
+}
+
+/// RW rows of a transaction, see [`Transaction::rw_stats`]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TxRwStats {
+    /// RW rows of the transaction
+    pub rw_rows: usize,
+    /// RW rows of the reverted sub-calls of the transaction, which are proven
+    /// by the state circuit although they don't change the state. The root
+    /// call is not counted even when the transaction fails, since its rows,
+    /// such as the ones paying the fee, are required by any transaction.
+    pub reverted_rw_rows: usize,
+    /// Stats of each call, indexed by call index
+    pub calls: Vec<CallRwStats>,
 }
 
 #[cfg(feature = "test")]
@@ -470,7 +548,7 @@ impl Transaction {
 }
 
 /// Transaction L1 fee for L1GasPriceOracle contract
-#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
 pub struct TxL1Fee {
     /// L1 base fee
     pub base_fee: u64,
@@ -531,3 +609,99 @@ impl TxL1Fee {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::mock::BlockData;
+    use eth_types::{address, bytecode, geth_types::GethData, ToWord, Word};
+    use mock::TestContext;
+
+    #[test]
+    fn rw_stats_of_reverted_call() {
+        let callee_address = address!("0x0000000000000000000000000000000000000020");
+        let callee = bytecode! {
+            PUSH1(1)
+            PUSH1(0)
+            SSTORE
+            PUSH1(0)
+            PUSH1(0)
+            REVERT
+        };
+        let caller = bytecode! {
+            PUSH1(0)
+            PUSH1(0)
+            PUSH1(0)
+            PUSH1(0)
+            PUSH1(0)
+            PUSH20(callee_address.to_word())
+            PUSH2(0xFFFF)
+            CALL
+            STOP
+        };
+        let block: GethData = TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(address!("0x0000000000000000000000000000000000000010"))
+                    .code(caller);
+                accs[1].address(callee_address).code(callee);
+                accs[2]
+                    .address(mock::MOCK_ACCOUNTS[2])
+                    .balance(Word::from(1u64 << 30));
+            },
+            |mut txs, accs| {
+                txs[0].to(accs[0].address).from(accs[2].address);
+            },
+            |block, _tx| block,
+        )
+        .unwrap()
+        .into();
+
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+
+        let stats = builder.block.txs()[0].rw_stats();
+        assert_eq!(stats.calls.len(), 2);
+        let (root, callee) = (&stats.calls[0], &stats.calls[1]);
+        assert!(!root.is_reverted);
+        assert!(callee.is_reverted && callee.depth == 2);
+        // the storage write is reverted
+        assert!(callee.reversible_writes >= 1);
+        assert_eq!(stats.reverted_rw_rows, callee.rw_rows);
+        assert_eq!(stats.rw_rows, root.rw_rows + callee.rw_rows);
+        let total_rws: usize = builder.block.txs()[0]
+            .steps()
+            .iter()
+            .map(|step| step.bus_mapping_instance.len())
+            .sum();
+        assert_eq!(stats.rw_rows, total_rws);
+    }
+
+    #[test]
+    fn rw_stats_of_reverted_tx() {
+        let code = bytecode! {
+            PUSH1(1)
+            PUSH1(0)
+            SSTORE
+            PUSH1(0)
+            PUSH1(0)
+            REVERT
+        };
+        let block: GethData = TestContext::<2, 1>::simple_ctx_with_bytecode(code)
+            .unwrap()
+            .into();
+
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+
+        let stats = builder.block.txs()[0].rw_stats();
+        assert_eq!(stats.calls.len(), 1);
+        assert!(stats.calls[0].is_reverted);
+        assert!(stats.rw_rows > 0);
+        assert_eq!(stats.reverted_rw_rows, 0);
+    }
+}
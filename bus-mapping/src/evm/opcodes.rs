@@ -10,7 +10,9 @@ use crate::{
     Error,
 };
 use core::fmt::Debug;
-use eth_types::{evm_unimplemented, GethExecStep, ToAddress, ToWord, Word};
+use eth_types::{
+    evm_types::is_constant_gas_only, evm_unimplemented, GethExecStep, ToAddress, ToWord, Word,
+};
 
 #[cfg(any(feature = "enable-memory", feature = "enable-stack"))]
 use crate::util::GETH_TRACE_CHECK_LEVEL;
@@ -22,6 +24,7 @@ mod address;
 mod arithmetic;
 mod balance;
 mod begin_end_tx;
+mod blobhash;
 mod blockhash;
 mod calldatacopy;
 mod calldataload;
@@ -84,6 +87,7 @@ use address::Address;
 use arithmetic::ArithmeticOpcode;
 use balance::Balance;
 use begin_end_tx::{gen_begin_tx_steps, gen_end_tx_steps};
+use blobhash::BlobHash;
 use blockhash::Blockhash;
 use calldatacopy::Calldatacopy;
 use calldataload::Calldataload;
@@ -227,6 +231,7 @@ fn fn_gen_associated_ops(opcode_id: &OpcodeId) -> FnGenAssociatedOps {
         OpcodeId::CHAINID => GetBlockHeaderField::<{ OpcodeId::CHAINID }>::gen_associated_ops,
         OpcodeId::SELFBALANCE => Selfbalance::gen_associated_ops,
         OpcodeId::BASEFEE => GetBlockHeaderField::<{ OpcodeId::BASEFEE }>::gen_associated_ops,
+        OpcodeId::BLOBHASH => BlobHash::gen_associated_ops,
         OpcodeId::POP => StackPopOnlyOpcode::<1>::gen_associated_ops,
         OpcodeId::MLOAD => Mload::gen_associated_ops,
         OpcodeId::MSTORE => Mstore::<false>::gen_associated_ops,
@@ -511,6 +516,16 @@ pub fn gen_associated_ops(
             return Ok(vec![exec_step]);
         }
     }
+    // the circuits charge the constant gas cost of the gas schedule of the
+    // block, reject a trace disagreeing with it
+    if is_constant_gas_only(geth_step.op)
+        && geth_step.gas_cost != state.block.gas_schedule().constant_gas_cost(geth_step.op)
+    {
+        return Err(Error::InvalidGethExecStep(
+            "gas cost differs from the gas schedule",
+            Box::new(geth_step.clone()),
+        ));
+    }
     // if no errors, continue as normal
     let fn_gen_associated_ops = fn_gen_associated_ops(opcode_id);
     fn_gen_associated_ops(state, geth_steps)
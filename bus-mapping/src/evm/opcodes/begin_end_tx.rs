@@ -160,19 +160,16 @@ pub fn gen_begin_tx_steps(state: &mut CircuitInputStateRef) -> Result<Vec<ExecSt
     }
 
     // Add caller, callee and coinbase (only for Shanghai) to access list.
-    #[cfg(feature = "shanghai")]
-    let accessed_addresses = [
-        call.caller_address,
-        call.address,
-        state
-            .block
-            .headers
-            .get(&state.tx.block_num)
-            .unwrap()
-            .coinbase,
-    ];
-    #[cfg(not(feature = "shanghai"))]
-    let accessed_addresses = [call.caller_address, call.address];
+    let rules = state.rules();
+    let coinbase = state
+        .block
+        .headers
+        .get(&state.tx.block_num)
+        .unwrap()
+        .coinbase;
+    let accessed_addresses = [call.caller_address, call.address]
+        .into_iter()
+        .chain(rules.is_coinbase_warm().then_some(coinbase));
     for address in accessed_addresses {
         let is_warm_prev = !state.sdb.add_account_to_access_list(address);
         state.tx_access_list_account_write(
@@ -185,14 +182,11 @@ pub fn gen_begin_tx_steps(state: &mut CircuitInputStateRef) -> Result<Vec<ExecSt
     }
 
     // Calculate gas cost of init code only for EIP-3860 of Shanghai.
-    #[cfg(feature = "shanghai")]
     let init_code_gas_cost = if state.tx.is_create() {
-        (state.tx.input.len() as u64 + 31) / 32 * eth_types::evm_types::INIT_CODE_WORD_GAS
+        (state.tx.input.len() as u64 + 31) / 32 * rules.init_code_word_gas()
     } else {
         0
     };
-    #[cfg(not(feature = "shanghai"))]
-    let init_code_gas_cost = 0;
 
     // Calculate intrinsic gas cost
     let call_data_gas_cost = tx_data_gas_cost(&state.tx.input);
@@ -423,6 +417,7 @@ pub fn gen_begin_tx_steps(state: &mut CircuitInputStateRef) -> Result<Vec<ExecSt
 
             let precompile_call: PrecompileCalls = call.address.0[19].into();
             let (result, precompile_call_gas_cost, has_oog_err) = execute_precompiled(
+                &rules,
                 &precompile_call.into(),
                 &state.tx.input,
                 exec_step.gas_left.0 - exec_step.gas_cost.as_u64(),
@@ -784,12 +779,15 @@ fn gen_tx_l1_fee_ops(
 }
 
 // Add two copy-events for tx access-list addresses and storage keys for
-// EIP-1559 and EIP-2930.
+// EIP-1559, EIP-2930 and EIP-4844.
 fn gen_tx_access_list_ops(
     state: &mut CircuitInputStateRef,
     exec_step: &mut ExecStep,
 ) -> Result<(), Error> {
-    if !(state.tx.tx_type.is_eip1559() || state.tx.tx_type.is_eip2930()) {
+    if !(state.tx.tx_type.is_eip1559()
+        || state.tx.tx_type.is_eip2930()
+        || state.tx.tx_type.is_eip4844())
+    {
         return Ok(());
     }
 
@@ -0,0 +1,46 @@
+use super::Opcode;
+use crate::{
+    circuit_input_builder::{CircuitInputStateRef, ExecStep},
+    operation::CallContextField,
+    Error,
+};
+use eth_types::{GethExecStep, ToWord, Word};
+
+/// Placeholder structure used to implement [`Opcode`] trait over it
+/// corresponding to the [`OpcodeId::BLOBHASH`](crate::evm::OpcodeId::BLOBHASH) `OpcodeId`.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct BlobHash;
+
+impl Opcode for BlobHash {
+    fn gen_associated_ops(
+        state: &mut CircuitInputStateRef,
+        geth_steps: &[GethExecStep],
+    ) -> Result<Vec<ExecStep>, Error> {
+        let geth_step = &geth_steps[0];
+        let mut exec_step = state.new_step(geth_step)?;
+
+        let index = state.stack_pop(&mut exec_step)?;
+        #[cfg(feature = "enable-stack")]
+        assert_eq!(index, geth_step.stack.last()?);
+
+        // CallContext read of the TxId
+        let tx_id = state.tx_ctx.id();
+        state.call_context_read(
+            &mut exec_step,
+            state.call()?.call_id,
+            CallContextField::TxId,
+            tx_id.into(),
+        )?;
+
+        // Out of range indices yield zero
+        let blob_hash = usize::try_from(index)
+            .ok()
+            .and_then(|index| state.tx.blob_versioned_hashes.get(index))
+            .map_or_else(Word::zero, |hash| hash.to_word());
+        #[cfg(feature = "enable-stack")]
+        assert_eq!(blob_hash, geth_steps[1].stack.last()?);
+        state.stack_push(&mut exec_step, blob_hash)?;
+
+        Ok(vec![exec_step])
+    }
+}
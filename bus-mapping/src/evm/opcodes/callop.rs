@@ -291,6 +291,7 @@ impl<const N_ARGS: usize> Opcode for CallOpcode<N_ARGS> {
                 // get the result of the precompile call.
                 // For failed call, it will cost all gas provided.
                 let (result, precompile_call_gas_cost, has_oog_err) = execute_precompiled(
+                    &state.rules(),
                     &code_address,
                     if args_length != 0 {
                         let caller_memory = &state.caller_ctx()?.memory;
@@ -21,3 +21,92 @@ impl Opcode for Push0 {
         Ok(vec![exec_step])
     }
 }
+
+#[cfg(test)]
+mod push0_tests {
+    use crate::{
+        circuit_input_builder::ExecState, error::ExecError, evm::OpcodeId, mock::BlockData,
+        operation::RW,
+    };
+    use eth_types::{
+        bytecode,
+        chain_spec::{ChainSpec, ForkCondition, Hardfork},
+        evm_types::StackAddress,
+        geth_types::GethData,
+        Word,
+    };
+    use mock::{
+        test_ctx::{helpers::*, LoggerConfig},
+        ChunkTestContext, MOCK_COINBASE,
+    };
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn push0_across_shanghai_fork() {
+        let code = bytecode! {
+            PUSH0
+            STOP
+        };
+        let block_number = 0xcafeu64;
+        // Shanghai activates at the second block of the chunk.
+        let chain_spec = ChainSpec::london()
+            .with_scroll(cfg!(feature = "scroll"))
+            .with_fork(Hardfork::Shanghai, ForkCondition::Block(block_number + 1));
+
+        let blocks: Vec<GethData> = ChunkTestContext::<2>::new_with_chain_spec(
+            chain_spec,
+            None,
+            account_0_code_account_1_no_code(code),
+            &[1, 1],
+            |txs, accs| {
+                for tx in txs.into_iter().flatten() {
+                    tx.from(accs[1].address).to(accs[0].address);
+                }
+            },
+            |idx, block, _txs| {
+                block
+                    .number(block_number + idx as u64)
+                    .author(*MOCK_COINBASE)
+            },
+            LoggerConfig::default(),
+        )
+        .unwrap()
+        .into();
+
+        let builder = BlockData::handle_chunk(&blocks, Default::default()).unwrap();
+        let txs = builder.block.txs();
+
+        // Before Shanghai, PUSH0 is an invalid opcode.
+        let step = txs[0]
+            .steps()
+            .iter()
+            .find(|step| step.error.is_some())
+            .unwrap();
+        assert_eq!(step.exec_state, ExecState::Op(OpcodeId::INVALID(0x5f)));
+        assert_eq!(step.error, Some(ExecError::InvalidOpcode));
+
+        // From Shanghai, PUSH0 pushes a zero.
+        let step = txs[1]
+            .steps()
+            .iter()
+            .find(|step| step.exec_state == ExecState::Op(OpcodeId::PUSH0))
+            .unwrap();
+        assert_eq!(step.error, None);
+        let op = &builder.block.container.stack[step.bus_mapping_instance[0].as_usize()];
+        assert_eq!(
+            (op.rw(), op.op().address, op.op().value),
+            (RW::WRITE, StackAddress(1023usize), Word::zero())
+        );
+
+        // Only the transactions from Shanghai warm the coinbase.
+        let coinbase_warmed_by = builder
+            .block
+            .container
+            .tx_access_list_account
+            .iter()
+            .filter(|op| op.op().address == *MOCK_COINBASE)
+            .map(|op| op.op().tx_id)
+            .collect::<Vec<_>>();
+        assert_eq!(coinbase_warmed_by, vec![txs[1].id()]);
+    }
+}
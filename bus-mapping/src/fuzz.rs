@@ -0,0 +1,163 @@
+//! Differential fuzzing of the bus-mapping against the tracer.
+//!
+//! Random [`Program`]s, made of arithmetic, memory, storage, calls, creates,
+//! reverts and precompile calls, are run through the tracer and the
+//! [`CircuitInputBuilder`](crate::circuit_input_builder::CircuitInputBuilder).
+//! At every step, the stack, memory, storage and refund tracked by the
+//! bus-mapping are compared with the trace, as well as the gas cost recomputed
+//! from its state and the gas left after the step. Stack and memory are only
+//! compared when the `enable-stack` and `enable-memory` features are on, and
+//! storage when `enable-storage` is on.
+//!
+//! Failing programs are minimised before being reported. Run with
+//! ```sh
+//! FUZZ_SEED=1 FUZZ_ITERATIONS=1000 cargo test -p bus-mapping \
+//!     --features enable-stack,enable-memory fuzz_bus_mapping -- --ignored
+//! ```
+
+mod checker;
+mod program;
+
+pub(crate) use checker::StepSnapshot;
+pub use checker::{check_program, Mismatch, MismatchKind};
+pub use program::{FuzzOp, Program, ProgramGenerator, NUM_CONTRACTS};
+
+use crate::Error;
+use std::fmt;
+
+/// A program on which the bus-mapping and the trace disagree.
+#[derive(Debug, Clone)]
+pub struct Counterexample {
+    /// Seed of the generator
+    pub seed: u64,
+    /// Index of the program generated from the seed
+    pub iteration: usize,
+    /// Minimised program
+    pub program: Program,
+    /// Disagreements on the minimised program
+    pub mismatches: Vec<Mismatch>,
+}
+
+impl fmt::Display for Counterexample {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "counterexample for seed {} at iteration {}:",
+            self.seed, self.iteration
+        )?;
+        for mismatch in self.mismatches.iter() {
+            writeln!(f, "  {mismatch}")?;
+        }
+        for (i, code) in self.program.bytecodes().iter().enumerate() {
+            writeln!(f, "contract {i}:")?;
+            writeln!(f, "{}", code.disasm())?;
+        }
+        Ok(())
+    }
+}
+
+/// Greedily removes ops from `program` as long as `still_fails` holds for the
+/// result. The returned program fails, and removing any single op from it
+/// makes it pass.
+pub fn minimise(program: Program, mut still_fails: impl FnMut(&Program) -> bool) -> Program {
+    let mut program = program;
+    let mut n = 0;
+    while n < program.len() {
+        match program.without(n) {
+            Some(candidate) if still_fails(&candidate) => program = candidate,
+            _ => n += 1,
+        }
+    }
+    program
+}
+
+/// Checks `iterations` programs generated from `seed`, and returns the first
+/// failing one, minimised. Programs on which the tracer fails are skipped.
+pub fn fuzz(seed: u64, iterations: usize) -> Result<Option<Counterexample>, Error> {
+    let mut generator = ProgramGenerator::new(seed);
+    for iteration in 0..iterations {
+        let program = generator.generate();
+        let mismatches = match check_program(&program) {
+            Ok(mismatches) => mismatches,
+            Err(err) => {
+                log::debug!("skip program {iteration}, tracer failed: {err:?}");
+                continue;
+            }
+        };
+        let Some(first) = mismatches.first() else {
+            continue;
+        };
+
+        let kind = first.kind;
+        let program = minimise(program, |candidate| {
+            check_program(candidate)
+                .map(|mismatches| mismatches.iter().any(|m| m.kind == kind))
+                .unwrap_or(false)
+        });
+        let mismatches = check_program(&program)?;
+        return Ok(Some(Counterexample {
+            seed,
+            iteration,
+            program,
+            mismatches,
+        }));
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::read_env_var;
+
+    #[test]
+    fn generator_is_deterministic() {
+        let mut a = ProgramGenerator::new(42);
+        let mut b = ProgramGenerator::new(42);
+        for _ in 0..16 {
+            assert_eq!(a.generate(), b.generate());
+        }
+    }
+
+    #[test]
+    fn minimise_keeps_failing_op() {
+        let program = (0..8)
+            .map(|seed| ProgramGenerator::new(seed).generate())
+            .find(|program| {
+                program
+                    .contracts
+                    .iter()
+                    .flatten()
+                    .any(|op| matches!(op, FuzzOp::SStore { .. }))
+            })
+            .expect("no program with SSTORE");
+
+        let minimised = minimise(program, |candidate| {
+            candidate
+                .contracts
+                .iter()
+                .flatten()
+                .any(|op| matches!(op, FuzzOp::SStore { .. }))
+        });
+        assert_eq!(minimised.len(), 1);
+    }
+
+    #[test]
+    fn stop_only_program_matches() {
+        let program = Program {
+            contracts: vec![vec![]; NUM_CONTRACTS],
+        };
+        let mismatches = check_program(&program).unwrap();
+        assert!(mismatches.is_empty(), "{mismatches:?}");
+    }
+
+    #[ignore = "long running, configure with FUZZ_SEED and FUZZ_ITERATIONS"]
+    #[test]
+    fn fuzz_bus_mapping() {
+        let seed = read_env_var("FUZZ_SEED", 0u64);
+        let iterations = read_env_var("FUZZ_ITERATIONS", 100usize);
+        if let Some(counterexample) = fuzz(seed, iterations).unwrap() {
+            panic!("{counterexample}");
+        }
+    }
+}
@@ -0,0 +1,326 @@
+//! Comparison of the state tracked by the bus-mapping with the trace.
+
+use super::Program;
+use crate::{
+    circuit_input_builder::{expected_gas_cost, CircuitInputStateRef},
+    mock::BlockData,
+    Error,
+};
+use eth_types::{evm_types::OpcodeId, geth_types::GethData, Address, GethExecStep, Word};
+use std::{cell::RefCell, fmt};
+
+thread_local! {
+    /// Mismatches found while a [`check_program`] is running on this thread.
+    static MISMATCHES: RefCell<Option<Vec<Mismatch>>> = RefCell::new(None);
+}
+
+/// Part of the state on which the bus-mapping and the trace disagree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MismatchKind {
+    /// Stack before the step
+    Stack,
+    /// Memory before the step
+    Memory,
+    /// Storage slot read or written by SLOAD / SSTORE, after the step
+    Storage,
+    /// Gas left after the step, i.e. the gas before it minus its recomputed
+    /// cost
+    GasLeft,
+    /// Gas cost of the step, recomputed from the state of the bus-mapping
+    GasCost,
+    /// Refund counter before the step
+    Refund,
+    /// The bus-mapping failed to handle the trace
+    Error,
+}
+
+/// A disagreement between the bus-mapping and the trace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    /// Kind of the mismatch
+    pub kind: MismatchKind,
+    /// Index of the tx
+    pub tx_id: usize,
+    /// Index of the step in the struct logs, if any
+    pub step: Option<usize>,
+    /// Opcode of the step
+    pub op: Option<OpcodeId>,
+    /// Program counter of the step
+    pub pc: u64,
+    /// Value in the trace
+    pub trace: String,
+    /// Value in the bus-mapping
+    pub bus_mapping: String,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} mismatch in tx {}", self.kind, self.tx_id)?;
+        if let (Some(step), Some(op)) = (self.step, self.op) {
+            write!(f, " at step {step} ({op:?}, pc {})", self.pc)?;
+        }
+        write!(
+            f,
+            ": trace {}, bus-mapping {}",
+            self.trace, self.bus_mapping
+        )
+    }
+}
+
+/// Runs `program` through the tracer and the bus-mapping, and returns their
+/// disagreements. Fails if the tracer fails.
+pub fn check_program(program: &Program) -> Result<Vec<Mismatch>, Error> {
+    let block: GethData = program.test_context()?.into();
+    let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+
+    MISMATCHES.with(|mismatches| *mismatches.borrow_mut() = Some(vec![]));
+    let result = builder.handle_block(&block.eth_block, &block.geth_traces);
+    let mut mismatches = MISMATCHES
+        .with(|mismatches| mismatches.borrow_mut().take())
+        .unwrap_or_default();
+    if let Err(err) = result {
+        mismatches.push(Mismatch {
+            kind: MismatchKind::Error,
+            tx_id: builder.block.txs.len() + 1,
+            step: None,
+            op: None,
+            pc: 0,
+            trace: "ok".to_string(),
+            bus_mapping: format!("{err:?}"),
+        });
+    }
+    Ok(mismatches)
+}
+
+/// Mismatches of a step found before it is handled, and the state needed to
+/// compare the storage after it. Captured only while a [`check_program`] is
+/// running.
+pub(crate) struct StepSnapshot {
+    index: usize,
+    found: Vec<Mismatch>,
+    #[cfg_attr(not(feature = "enable-storage"), allow(dead_code))]
+    address: Address,
+    #[cfg_attr(not(feature = "enable-storage"), allow(dead_code))]
+    stack_top: Option<Word>,
+}
+
+impl StepSnapshot {
+    /// Compares the state before `geth_steps[0]`, the step at `index` in the
+    /// struct logs, with the trace. This runs before `gen_associated_ops`,
+    /// which resets the stack and memory to the ones of the trace when they
+    /// differ.
+    pub(crate) fn capture(
+        state: &CircuitInputStateRef,
+        index: usize,
+        geth_steps: &[GethExecStep],
+    ) -> Result<Option<Self>, Error> {
+        if MISMATCHES.with(|mismatches| mismatches.borrow().is_none()) {
+            return Ok(None);
+        }
+        let geth_step = &geth_steps[0];
+        let call_ctx = state.call_ctx()?;
+        let mut found = vec![];
+        let mut mismatch = |kind, trace: String, bus_mapping: String| {
+            found.push(step_mismatch(
+                kind,
+                state,
+                index,
+                geth_step,
+                trace,
+                bus_mapping,
+            ))
+        };
+
+        #[cfg(feature = "enable-stack")]
+        if call_ctx.stack != geth_step.stack {
+            mismatch(
+                MismatchKind::Stack,
+                format!("{:?}", geth_step.stack),
+                format!("{:?}", call_ctx.stack),
+            );
+        }
+        #[cfg(feature = "enable-memory")]
+        if call_ctx.memory != geth_step.memory {
+            mismatch(
+                MismatchKind::Memory,
+                format!("{:?}", geth_step.memory),
+                format!("{:?}", call_ctx.memory),
+            );
+        }
+        let refund = state.sdb.refund();
+        if refund != geth_step.refund.0 {
+            mismatch(
+                MismatchKind::Refund,
+                geth_step.refund.0.to_string(),
+                refund.to_string(),
+            );
+        }
+        if geth_step.error.is_none() {
+            match expected_gas_cost(state, geth_step) {
+                Ok(cost) => {
+                    for (kind, trace, bus_mapping) in gas_mismatches(geth_steps, cost) {
+                        mismatch(kind, trace, bus_mapping);
+                    }
+                }
+                Err(err) => mismatch(
+                    MismatchKind::GasCost,
+                    geth_step.gas_cost.0.to_string(),
+                    format!("{err:?}"),
+                ),
+            }
+        }
+
+        Ok(Some(Self {
+            index,
+            found,
+            address: state.call()?.address,
+            stack_top: call_ctx.stack.last().ok(),
+        }))
+    }
+
+    /// Compares the state after the step with the trace, and records the
+    /// mismatches of the step.
+    #[cfg_attr(not(feature = "enable-storage"), allow(unused_variables, unused_mut))]
+    pub(crate) fn check(self, state: &CircuitInputStateRef, geth_step: &GethExecStep) {
+        let mut found = self.found;
+        // geth reports the slot accessed by the step, with its value after it
+        #[cfg(feature = "enable-storage")]
+        if matches!(geth_step.op, OpcodeId::SLOAD | OpcodeId::SSTORE) && geth_step.error.is_none() {
+            if let Some(key) = self.stack_top {
+                let value = state.sdb.get_storage(&self.address, &key).1;
+                if geth_step.storage.get(&key) != Some(value) {
+                    found.push(step_mismatch(
+                        MismatchKind::Storage,
+                        state,
+                        self.index,
+                        geth_step,
+                        format!("{key:?}: {:?}", geth_step.storage.get(&key)),
+                        format!("{key:?}: {value:?}"),
+                    ));
+                }
+            }
+        }
+        MISMATCHES.with(|mismatches| {
+            if let Some(mismatches) = mismatches.borrow_mut().as_mut() {
+                mismatches.extend(found);
+            }
+        });
+    }
+}
+
+fn step_mismatch(
+    kind: MismatchKind,
+    state: &CircuitInputStateRef,
+    index: usize,
+    geth_step: &GethExecStep,
+    trace: String,
+    bus_mapping: String,
+) -> Mismatch {
+    Mismatch {
+        kind,
+        tx_id: state.tx_ctx.id(),
+        step: Some(index),
+        op: Some(geth_step.op),
+        pc: geth_step.pc.0 as u64,
+        trace,
+        bus_mapping,
+    }
+}
+
+/// Gas cost of `geth_steps[0]` and gas left after it on which the cost
+/// recomputed by the bus-mapping and the trace disagree. The gas left is only
+/// checked when the next step is in the same call and the step isn't a call or
+/// a create, which get back the gas left by their callee.
+fn gas_mismatches(
+    geth_steps: &[GethExecStep],
+    expected_cost: u64,
+) -> Vec<(MismatchKind, String, String)> {
+    let geth_step = &geth_steps[0];
+    let mut found = vec![];
+    if expected_cost != geth_step.gas_cost.0 {
+        found.push((
+            MismatchKind::GasCost,
+            geth_step.gas_cost.0.to_string(),
+            expected_cost.to_string(),
+        ));
+    }
+    let next_step = geth_steps.get(1).filter(|next_step| {
+        next_step.depth == geth_step.depth && !geth_step.op.is_call_or_create()
+    });
+    if let Some(next_step) = next_step {
+        let gas_left = geth_step.gas.0.checked_sub(expected_cost);
+        if gas_left != Some(next_step.gas.0) {
+            found.push((
+                MismatchKind::GasLeft,
+                next_step.gas.0.to_string(),
+                gas_left.map_or_else(|| "out of gas".to_string(), |gas| gas.to_string()),
+            ));
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eth_types::evm_types::{Gas, GasCost, ProgramCounter};
+
+    fn geth_step(op: OpcodeId, gas: u64, gas_cost: u64, depth: u16) -> GethExecStep {
+        GethExecStep {
+            pc: ProgramCounter(0),
+            op,
+            gas: Gas(gas),
+            gas_cost: GasCost(gas_cost),
+            refund: Gas(0),
+            depth,
+            error: None,
+            #[cfg(feature = "enable-stack")]
+            stack: eth_types::evm_types::Stack::new(),
+            #[cfg(feature = "enable-memory")]
+            memory: eth_types::evm_types::Memory::new(),
+            #[cfg(feature = "enable-storage")]
+            storage: Default::default(),
+        }
+    }
+
+    #[test]
+    fn gas_cost_and_gas_left_mismatches() {
+        let steps = [
+            geth_step(OpcodeId::ADD, 100, 3, 1),
+            geth_step(OpcodeId::STOP, 97, 0, 1),
+        ];
+        assert!(gas_mismatches(&steps, 3).is_empty());
+        assert_eq!(
+            gas_mismatches(&steps, 5),
+            vec![
+                (MismatchKind::GasCost, "3".to_string(), "5".to_string()),
+                (MismatchKind::GasLeft, "97".to_string(), "95".to_string()),
+            ]
+        );
+
+        // the trace deducts another gas than the cost it reports
+        let steps = [
+            geth_step(OpcodeId::ADD, 100, 3, 1),
+            geth_step(OpcodeId::STOP, 90, 0, 1),
+        ];
+        assert_eq!(
+            gas_mismatches(&steps, 3),
+            vec![(MismatchKind::GasLeft, "90".to_string(), "97".to_string())]
+        );
+    }
+
+    #[test]
+    fn gas_left_of_calls_and_returns_is_not_checked() {
+        // a call to an account without code gets back the gas passed to it
+        let steps = [
+            geth_step(OpcodeId::CALL, 10_000, 2_600, 1),
+            geth_step(OpcodeId::POP, 9_900, 2, 1),
+        ];
+        assert!(gas_mismatches(&steps, 2_600).is_empty());
+        let steps = [
+            geth_step(OpcodeId::STOP, 1_000, 0, 2),
+            geth_step(OpcodeId::POP, 9_000, 2, 1),
+        ];
+        assert!(gas_mismatches(&steps, 0).is_empty());
+    }
+}
@@ -0,0 +1,461 @@
+//! Random programs for the differential fuzzer.
+
+use eth_types::{evm_types::OpcodeId, Address, Bytecode, Word};
+use ethers_signers::Signer;
+use mock::{eth, TestContext, MOCK_ACCOUNTS, MOCK_WALLETS};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+/// Number of contracts of a program, the tx calls the first one.
+pub const NUM_CONTRACTS: usize = 3;
+
+/// Memory offset where the init code of a CREATE / CREATE2 is written.
+const INIT_CODE_OFFSET: u64 = 0x200;
+
+const BINARY_OPS: [OpcodeId; 20] = [
+    OpcodeId::ADD,
+    OpcodeId::MUL,
+    OpcodeId::SUB,
+    OpcodeId::DIV,
+    OpcodeId::SDIV,
+    OpcodeId::MOD,
+    OpcodeId::SMOD,
+    OpcodeId::EXP,
+    OpcodeId::SIGNEXTEND,
+    OpcodeId::LT,
+    OpcodeId::GT,
+    OpcodeId::SLT,
+    OpcodeId::SGT,
+    OpcodeId::EQ,
+    OpcodeId::AND,
+    OpcodeId::OR,
+    OpcodeId::XOR,
+    OpcodeId::BYTE,
+    OpcodeId::SHL,
+    OpcodeId::SAR,
+];
+
+const CALL_OPS: [OpcodeId; 4] = [
+    OpcodeId::CALL,
+    OpcodeId::CALLCODE,
+    OpcodeId::DELEGATECALL,
+    OpcodeId::STATICCALL,
+];
+
+/// A stack neutral piece of a program, so that any of them can be removed
+/// while minimising a counterexample.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FuzzOp {
+    /// Binary arithmetic, comparison or bitwise operation, whose result is
+    /// popped
+    Binary {
+        /// opcode
+        op: OpcodeId,
+        /// first operand, on top of the stack
+        a: Word,
+        /// second operand
+        b: Word,
+    },
+    /// MSTORE
+    MStore {
+        /// memory offset
+        offset: u64,
+        /// stored word
+        value: Word,
+    },
+    /// MLOAD, whose result is popped
+    MLoad {
+        /// memory offset
+        offset: u64,
+    },
+    /// SSTORE
+    SStore {
+        /// storage key
+        key: Word,
+        /// stored value
+        value: Word,
+    },
+    /// SLOAD, whose result is popped
+    SLoad {
+        /// storage key
+        key: Word,
+    },
+    /// SHA3, whose result is popped
+    Sha3 {
+        /// memory offset
+        offset: u64,
+        /// hashed length
+        size: u64,
+    },
+    /// CALL, CALLCODE, DELEGATECALL or STATICCALL to a contract of the program
+    Call {
+        /// opcode
+        op: OpcodeId,
+        /// index of the callee in [`Program::contracts`]
+        callee: usize,
+        /// gas passed to the callee
+        gas: u64,
+        /// transferred value, ignored by DELEGATECALL and STATICCALL
+        value: Word,
+        /// memory offset and length of the call data
+        args: (u64, u64),
+        /// memory offset and length of the return data
+        ret: (u64, u64),
+    },
+    /// STATICCALL to a precompile
+    Precompile {
+        /// precompile address
+        address: u8,
+        /// gas passed to the precompile
+        gas: u64,
+        /// memory offset and length of the input
+        args: (u64, u64),
+        /// memory offset and length of the output
+        ret: (u64, u64),
+    },
+    /// CREATE, or CREATE2 with a salt, of a contract with empty code
+    Create {
+        /// CREATE2 salt
+        salt: Option<Word>,
+        /// transferred value
+        value: Word,
+        /// init code, before its final RETURN or REVERT
+        init: Vec<FuzzOp>,
+        /// end the init code with REVERT instead of RETURN
+        revert: bool,
+    },
+    /// REVERT, which ends the current call
+    Revert {
+        /// memory offset
+        offset: u64,
+        /// returned length
+        size: u64,
+    },
+    /// RETURN, which ends the current call
+    Return {
+        /// memory offset
+        offset: u64,
+        /// returned length
+        size: u64,
+    },
+}
+
+impl FuzzOp {
+    fn append_to(&self, code: &mut Bytecode) {
+        match self {
+            FuzzOp::Binary { op, a, b } => {
+                code.op_push32(*b).op_push32(*a).write_op(*op).op_pop();
+            }
+            FuzzOp::MStore { offset, value } => {
+                code.op_mstore(*offset, *value);
+            }
+            FuzzOp::MLoad { offset } => {
+                code.op_mload(*offset).op_pop();
+            }
+            FuzzOp::SStore { key, value } => {
+                code.op_sstore(*key, *value);
+            }
+            FuzzOp::SLoad { key } => {
+                code.op_sload(*key).op_pop();
+            }
+            FuzzOp::Sha3 { offset, size } => {
+                code.op_sha3(*offset, *size).op_pop();
+            }
+            FuzzOp::Call {
+                op,
+                callee,
+                gas,
+                value,
+                args,
+                ret,
+            } => {
+                let address = MOCK_ACCOUNTS[*callee];
+                match op {
+                    OpcodeId::CALL => {
+                        code.op_call(*gas, address, *value, args.0, args.1, ret.0, ret.1)
+                    }
+                    OpcodeId::CALLCODE => {
+                        code.op_callcode(*gas, address, *value, args.0, args.1, ret.0, ret.1)
+                    }
+                    OpcodeId::DELEGATECALL => {
+                        code.op_delegatecall(*gas, address, args.0, args.1, ret.0, ret.1)
+                    }
+                    _ => code.op_staticcall(*gas, address, args.0, args.1, ret.0, ret.1),
+                }
+                .op_pop();
+            }
+            FuzzOp::Precompile {
+                address,
+                gas,
+                args,
+                ret,
+            } => {
+                let address = Address::from_low_u64_be(*address as u64);
+                code.op_staticcall(*gas, address, args.0, args.1, ret.0, ret.1)
+                    .op_pop();
+            }
+            FuzzOp::Create {
+                salt,
+                value,
+                init,
+                revert,
+            } => {
+                let mut init_code = Bytecode::default();
+                init.iter().for_each(|op| op.append_to(&mut init_code));
+                if *revert {
+                    init_code.op_revert(0, 0);
+                } else {
+                    init_code.op_return(0, 0);
+                }
+                let init_code = init_code.code();
+                for (i, chunk) in init_code.chunks(32).enumerate() {
+                    let mut word = [0u8; 32];
+                    word[..chunk.len()].copy_from_slice(chunk);
+                    code.op_mstore(
+                        INIT_CODE_OFFSET + 32 * i as u64,
+                        Word::from_big_endian(&word),
+                    );
+                }
+                match salt {
+                    Some(salt) => code.op_create2(*value, INIT_CODE_OFFSET, init_code.len(), *salt),
+                    None => code.op_create(*value, INIT_CODE_OFFSET, init_code.len()),
+                }
+                .op_pop();
+            }
+            FuzzOp::Revert { offset, size } => {
+                code.op_revert(*offset, *size);
+            }
+            FuzzOp::Return { offset, size } => {
+                code.op_return(*offset, *size);
+            }
+        }
+    }
+
+    /// Number of ops of this op, including itself.
+    fn len(&self) -> usize {
+        match self {
+            FuzzOp::Create { init, .. } => 1 + init.iter().map(FuzzOp::len).sum::<usize>(),
+            _ => 1,
+        }
+    }
+}
+
+/// Removes the `n`-th op of `ops`, in pre-order.
+fn remove_nth(ops: &mut Vec<FuzzOp>, mut n: usize) -> bool {
+    for i in 0..ops.len() {
+        if n == 0 {
+            ops.remove(i);
+            return true;
+        }
+        n -= 1;
+        let len = ops[i].len() - 1;
+        if n < len {
+            if let FuzzOp::Create { init, .. } = &mut ops[i] {
+                return remove_nth(init, n);
+            }
+        }
+        n -= len;
+    }
+    false
+}
+
+/// Program executed by the fuzzer, made of the code of [`NUM_CONTRACTS`]
+/// contracts.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Program {
+    /// Ops of each contract
+    pub contracts: Vec<Vec<FuzzOp>>,
+}
+
+impl Program {
+    /// Bytecode of every contract.
+    pub fn bytecodes(&self) -> Vec<Bytecode> {
+        self.contracts
+            .iter()
+            .map(|ops| {
+                let mut code = Bytecode::default();
+                ops.iter().for_each(|op| op.append_to(&mut code));
+                code.op_stop();
+                code
+            })
+            .collect()
+    }
+
+    /// Number of ops of the program, including the ones of init codes.
+    pub fn len(&self) -> usize {
+        self.contracts.iter().flatten().map(FuzzOp::len).sum()
+    }
+
+    /// Returns true if the program has no op.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the program without its `n`-th op, in pre-order.
+    pub fn without(&self, mut n: usize) -> Option<Self> {
+        let mut program = self.clone();
+        for ops in program.contracts.iter_mut() {
+            let len = ops.iter().map(FuzzOp::len).sum::<usize>();
+            if n < len {
+                return remove_nth(ops, n).then_some(program);
+            }
+            n -= len;
+        }
+        None
+    }
+
+    /// Test context with the contracts of the program deployed at
+    /// [`MOCK_ACCOUNTS`], and a tx from [`MOCK_WALLETS`]`[0]` calling the
+    /// first one.
+    pub fn test_context(&self) -> Result<TestContext<{ NUM_CONTRACTS + 1 }, 1>, eth_types::Error> {
+        let bytecodes = self.bytecodes();
+        TestContext::new(
+            None,
+            |accs| {
+                for (i, code) in bytecodes.into_iter().enumerate() {
+                    accs[i]
+                        .address(MOCK_ACCOUNTS[i])
+                        .balance(eth(10))
+                        .code(code);
+                }
+                accs[NUM_CONTRACTS]
+                    .address(MOCK_WALLETS[0].address())
+                    .balance(eth(10));
+            },
+            |mut txs, accs| {
+                txs[0]
+                    .from(MOCK_WALLETS[0].clone())
+                    .to(accs[0].address)
+                    .gas(Word::from(1_000_000));
+            },
+            |block, _txs| block,
+        )
+    }
+}
+
+/// Generates random [`Program`]s, deterministically from a seed.
+#[derive(Debug)]
+pub struct ProgramGenerator {
+    rng: StdRng,
+    /// Maximum number of ops per contract
+    pub max_ops: usize,
+}
+
+impl ProgramGenerator {
+    /// Create a new generator from `seed`.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+            max_ops: 16,
+        }
+    }
+
+    /// Generate a new program.
+    pub fn generate(&mut self) -> Program {
+        let contracts = (0..NUM_CONTRACTS)
+            .map(|_| {
+                let len = self.rng.gen_range(0..=self.max_ops);
+                self.gen_ops(len, true)
+            })
+            .collect();
+        Program { contracts }
+    }
+
+    fn gen_ops(&mut self, len: usize, with_create: bool) -> Vec<FuzzOp> {
+        (0..len).map(|_| self.gen_op(with_create)).collect()
+    }
+
+    fn gen_op(&mut self, with_create: bool) -> FuzzOp {
+        match self.rng.gen_range(0..if with_create { 12 } else { 11 }) {
+            0 | 1 => FuzzOp::Binary {
+                op: *BINARY_OPS.choose(&mut self.rng).unwrap(),
+                a: self.gen_word(),
+                b: self.gen_word(),
+            },
+            2 => FuzzOp::MStore {
+                offset: self.gen_offset(),
+                value: self.gen_word(),
+            },
+            3 => FuzzOp::MLoad {
+                offset: self.gen_offset(),
+            },
+            4 => FuzzOp::SStore {
+                key: self.gen_key(),
+                value: self.gen_word(),
+            },
+            5 => FuzzOp::SLoad {
+                key: self.gen_key(),
+            },
+            6 => FuzzOp::Sha3 {
+                offset: self.gen_offset(),
+                size: self.gen_size(),
+            },
+            7 | 8 => FuzzOp::Call {
+                op: *CALL_OPS.choose(&mut self.rng).unwrap(),
+                callee: self.rng.gen_range(0..NUM_CONTRACTS),
+                gas: self.gen_gas(),
+                value: Word::from(self.rng.gen_range(0..2u64)),
+                args: (self.gen_offset(), self.gen_size()),
+                ret: (self.gen_offset(), self.gen_size()),
+            },
+            9 => FuzzOp::Precompile {
+                address: self.rng.gen_range(1..=9),
+                gas: self.gen_gas(),
+                args: (self.gen_offset(), self.gen_size()),
+                ret: (self.gen_offset(), self.gen_size()),
+            },
+            10 => {
+                if self.rng.gen_bool(0.5) {
+                    FuzzOp::Revert {
+                        offset: self.gen_offset(),
+                        size: self.gen_size(),
+                    }
+                } else {
+                    FuzzOp::Return {
+                        offset: self.gen_offset(),
+                        size: self.gen_size(),
+                    }
+                }
+            }
+            _ => {
+                let len = self.rng.gen_range(0..=self.max_ops / 4);
+                FuzzOp::Create {
+                    // a few salts only, to hit address collisions
+                    salt: self
+                        .rng
+                        .gen_bool(0.5)
+                        .then(|| Word::from(self.rng.gen_range(0..2u64))),
+                    value: Word::from(self.rng.gen_range(0..2u64)),
+                    init: self.gen_ops(len, false),
+                    revert: self.rng.gen_bool(0.25),
+                }
+            }
+        }
+    }
+
+    fn gen_word(&mut self) -> Word {
+        match self.rng.gen_range(0..4) {
+            0 => Word::from(self.rng.gen_range(0..=256u64)),
+            1 => *[Word::zero(), Word::one(), Word::MAX, Word::one() << 255]
+                .choose(&mut self.rng)
+                .unwrap(),
+            _ => Word::from_big_endian(&self.rng.gen::<[u8; 32]>()),
+        }
+    }
+
+    fn gen_key(&mut self) -> Word {
+        // a few keys only, to read back written slots
+        Word::from(self.rng.gen_range(0..4u64))
+    }
+
+    fn gen_offset(&mut self) -> u64 {
+        self.rng.gen_range(0..0x100)
+    }
+
+    fn gen_size(&mut self) -> u64 {
+        self.rng.gen_range(0..0x80)
+    }
+
+    fn gen_gas(&mut self) -> u64 {
+        self.rng.gen_range(0..50_000)
+    }
+}
@@ -229,6 +229,8 @@ pub mod circuit_input_builder;
 pub mod error;
 pub mod evm;
 pub mod exec_trace;
+#[cfg(feature = "test")]
+pub mod fuzz;
 pub mod l2_predeployed;
 pub mod mock;
 pub mod operation;
@@ -3,8 +3,9 @@
 use crate::{
     circuit_input_builder::{AccessSet, Block, BlockHead, CircuitInputBuilder, CircuitsParams},
     state_db::{self, CodeDB, StateDB},
+    Error,
 };
-use eth_types::{geth_types::GethData, ToWord, Word, H256};
+use eth_types::{chain_spec::ChainSpec, geth_types::GethData, ToWord, Word, H256};
 use ethers_core::utils::keccak256;
 
 const MOCK_OLD_STATE_ROOT: u64 = 0xcafeu64;
@@ -28,6 +29,8 @@ pub struct BlockData {
     pub geth_traces: Vec<eth_types::GethExecTrace>,
     /// Circuits setup parameters
     pub circuits_params: CircuitsParams,
+    /// Hardfork schedule of the chain
+    pub chain_spec: ChainSpec,
 }
 
 impl BlockData {
@@ -40,7 +43,8 @@ impl BlockData {
                     .unwrap(),
             ],
             Default::default(),
-        );
+        )
+        .with_chain_spec(self.chain_spec.clone());
         // FIXME: better fetch a real state root instead of a mock one
         block.prev_state_root = MOCK_OLD_STATE_ROOT.into();
         block.circuits_params = self.circuits_params;
@@ -90,6 +94,7 @@ impl BlockData {
             eth_block: geth_data.eth_block,
             geth_traces: geth_data.geth_traces,
             circuits_params,
+            chain_spec: geth_data.chain_spec,
         }
     }
 
@@ -97,6 +102,54 @@ impl BlockData {
     pub fn new_from_geth_data(geth_data: GethData) -> Self {
         Self::new_from_geth_data_with_params(geth_data, CircuitsParams::default())
     }
+
+    /// Generate a new CircuitInputBuilder from the Geth data of the
+    /// consecutive blocks of a chunk, and handle all of them. The accounts of
+    /// the first block are the state before the chunk.
+    pub fn handle_chunk(
+        blocks: &[GethData],
+        circuits_params: CircuitsParams,
+    ) -> Result<CircuitInputBuilder, Error> {
+        let first = blocks.first().expect("a chunk has at least one block");
+        // The accesses of all the blocks are needed to initialize the state.
+        let geth_data = GethData {
+            geth_traces: blocks
+                .iter()
+                .flat_map(|block| block.geth_traces.clone())
+                .collect(),
+            ..first.clone()
+        };
+        let mut builder = Self::new_from_geth_data_with_params(geth_data, circuits_params)
+            .new_circuit_input_builder();
+
+        #[cfg(feature = "scroll")]
+        {
+            builder.block.start_l1_queue_index = first.block_trace.start_l1_queue_index;
+        }
+        builder.block.headers.clear();
+        for block in blocks {
+            #[cfg(feature = "scroll")]
+            let header = BlockHead::new_with_l1_queue_index(
+                block.chain_id,
+                block.block_trace.start_l1_queue_index,
+                block.history_hashes.clone(),
+                &block.eth_block,
+            )?;
+            #[cfg(not(feature = "scroll"))]
+            let header = BlockHead::new(
+                block.chain_id,
+                block.history_hashes.clone(),
+                &block.eth_block,
+            )?;
+            builder.block.headers.insert(header.number.as_u64(), header);
+        }
+        for (idx, block) in blocks.iter().enumerate() {
+            let is_last = idx + 1 == blocks.len();
+            builder.handle_block_inner(&block.eth_block, &block.geth_traces, is_last, is_last)?;
+        }
+
+        Ok(builder)
+    }
 }
 
 #[cfg(test)]
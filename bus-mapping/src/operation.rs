@@ -2,13 +2,17 @@
 //! - Define the internals of a [`MemoryOp`], [`StackOp`] and [`StorageOp`].
 //! - Define the actual operation types and a wrapper over them (the [`Operation`] enum).
 //! - Define structures that interact with operations such as [`OperationContainer`].
+//! - Query the operations of an [`OperationContainer`] with a [`RwFilter`].
 pub(crate) mod container;
+pub(crate) mod query;
 
 pub use container::OperationContainer;
 pub use eth_types::evm_types::{MemoryAddress, StackAddress};
+pub use query::{RwAttrs, RwFilter, TypedOp};
 
 use core::{cmp::Ordering, fmt, fmt::Debug};
 use eth_types::{Address, Word};
+use serde::{Deserialize, Serialize};
 use std::mem::swap;
 
 /// Marker that defines whether an Operation performs a `READ` or a `WRITE`.
@@ -35,7 +39,7 @@ impl RW {
 /// Wrapper type over `usize` which represents the global counter. The purpose
 /// of the `RWCounter` is to enforce that each Opcode/Instruction and Operation
 /// is unique and just executed once.
-#[derive(Clone, Copy, Eq, PartialEq, PartialOrd, Ord)]
+#[derive(Clone, Copy, Eq, PartialEq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct RWCounter(pub usize);
 
 impl fmt::Debug for RWCounter {
@@ -0,0 +1,443 @@
+//! Typed queries over the operations stored in an [`OperationContainer`].
+
+use super::{
+    AccountOp, CallContextOp, MemoryOp, Op, Operation, OperationContainer, RWCounter, StackOp,
+    StartOp, StorageOp, Target, TxAccessListAccountOp, TxAccessListAccountStorageOp, TxLogOp,
+    TxReceiptOp, TxRefundOp,
+};
+use crate::exec_trace::OperationRef;
+use eth_types::{Address, Word};
+use std::ops::{Bound, RangeBounds};
+
+/// The attributes of a read-write operation that a [`RwFilter`] can select
+/// on. Attributes which don't apply to the operation kind are `None`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RwAttrs {
+    /// Kind of the operation.
+    pub target: Target,
+    /// Global counter of the operation.
+    pub rw_counter: usize,
+    /// Account address of account, storage and access list operations.
+    pub address: Option<Address>,
+    /// Storage key of storage and storage access list operations.
+    pub storage_key: Option<Word>,
+    /// Call id of call context, stack and memory operations.
+    pub call_id: Option<usize>,
+    /// Transaction id of transaction scoped operations.
+    pub tx_id: Option<usize>,
+}
+
+impl RwAttrs {
+    /// Create the attributes of an operation which has no address, storage
+    /// key, call id or tx id.
+    pub fn new(target: Target, rw_counter: usize) -> Self {
+        Self {
+            target,
+            rw_counter,
+            address: None,
+            storage_key: None,
+            call_id: None,
+            tx_id: None,
+        }
+    }
+}
+
+/// A filter over read-write operations, built by chaining the criteria to
+/// match. An operation is selected when it matches all the criteria set on the
+/// filter; an operation lacking a queried attribute (e.g. a stack operation
+/// queried by storage key) is never selected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RwFilter {
+    target: Option<Target>,
+    address: Option<Address>,
+    storage_key: Option<Word>,
+    call_id: Option<usize>,
+    tx_id: Option<usize>,
+    rw_counter: (Bound<usize>, Bound<usize>),
+}
+
+impl Default for RwFilter {
+    fn default() -> Self {
+        Self {
+            target: None,
+            address: None,
+            storage_key: None,
+            call_id: None,
+            tx_id: None,
+            rw_counter: (Bound::Unbounded, Bound::Unbounded),
+        }
+    }
+}
+
+impl RwFilter {
+    /// Create a filter selecting every operation.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only select operations of the given kind.
+    pub fn target(mut self, target: Target) -> Self {
+        self.target = Some(target);
+        self
+    }
+
+    /// Only select operations on the given account address.
+    pub fn address(mut self, address: Address) -> Self {
+        self.address = Some(address);
+        self
+    }
+
+    /// Only select operations on the given storage key.
+    pub fn storage_key(mut self, storage_key: Word) -> Self {
+        self.storage_key = Some(storage_key);
+        self
+    }
+
+    /// Only select operations of the given call.
+    pub fn call_id(mut self, call_id: usize) -> Self {
+        self.call_id = Some(call_id);
+        self
+    }
+
+    /// Only select operations of the given transaction.
+    pub fn tx_id(mut self, tx_id: usize) -> Self {
+        self.tx_id = Some(tx_id);
+        self
+    }
+
+    /// Only select operations whose rw counter lies in `range`.
+    pub fn rw_counter_range(mut self, range: impl RangeBounds<usize>) -> Self {
+        self.rw_counter = (range.start_bound().cloned(), range.end_bound().cloned());
+        self
+    }
+
+    /// Return the operation kind this filter is restricted to, if any.
+    pub fn target_filter(&self) -> Option<Target> {
+        self.target
+    }
+
+    /// Returns true if an operation with the given attributes is selected.
+    pub fn matches(&self, attrs: &RwAttrs) -> bool {
+        fn matches_opt<T: PartialEq>(filter: &Option<T>, value: &Option<T>) -> bool {
+            filter.is_none() || (value.is_some() && filter == value)
+        }
+
+        self.target.map_or(true, |target| target == attrs.target)
+            && self.rw_counter.contains(&attrs.rw_counter)
+            && matches_opt(&self.address, &attrs.address)
+            && matches_opt(&self.storage_key, &attrs.storage_key)
+            && matches_opt(&self.call_id, &attrs.call_id)
+            && matches_opt(&self.tx_id, &attrs.tx_id)
+    }
+}
+
+/// An [`Op`] kind which is stored in its own vector of the
+/// [`OperationContainer`], and can therefore be queried as a typed view.
+pub trait TypedOp: Op {
+    /// Kind of the operation.
+    const TARGET: Target;
+
+    /// Return the operations of this kind stored in the container.
+    fn select(container: &OperationContainer) -> &[Operation<Self>];
+
+    /// Return the attributes of this operation performed at `rwc`.
+    fn attrs(&self, rwc: RWCounter) -> RwAttrs;
+}
+
+impl TypedOp for StartOp {
+    const TARGET: Target = Target::Start;
+
+    fn select(container: &OperationContainer) -> &[Operation<Self>] {
+        &container.start
+    }
+
+    fn attrs(&self, rwc: RWCounter) -> RwAttrs {
+        RwAttrs::new(Self::TARGET, rwc.0)
+    }
+}
+
+impl TypedOp for MemoryOp {
+    const TARGET: Target = Target::Memory;
+
+    fn select(container: &OperationContainer) -> &[Operation<Self>] {
+        &container.memory
+    }
+
+    fn attrs(&self, rwc: RWCounter) -> RwAttrs {
+        RwAttrs {
+            call_id: Some(self.call_id),
+            ..RwAttrs::new(Self::TARGET, rwc.0)
+        }
+    }
+}
+
+impl TypedOp for StackOp {
+    const TARGET: Target = Target::Stack;
+
+    fn select(container: &OperationContainer) -> &[Operation<Self>] {
+        &container.stack
+    }
+
+    fn attrs(&self, rwc: RWCounter) -> RwAttrs {
+        RwAttrs {
+            call_id: Some(self.call_id),
+            ..RwAttrs::new(Self::TARGET, rwc.0)
+        }
+    }
+}
+
+impl TypedOp for StorageOp {
+    const TARGET: Target = Target::Storage;
+
+    fn select(container: &OperationContainer) -> &[Operation<Self>] {
+        &container.storage
+    }
+
+    fn attrs(&self, rwc: RWCounter) -> RwAttrs {
+        RwAttrs {
+            address: Some(self.address),
+            storage_key: Some(self.key),
+            tx_id: Some(self.tx_id),
+            ..RwAttrs::new(Self::TARGET, rwc.0)
+        }
+    }
+}
+
+impl TypedOp for TxAccessListAccountOp {
+    const TARGET: Target = Target::TxAccessListAccount;
+
+    fn select(container: &OperationContainer) -> &[Operation<Self>] {
+        &container.tx_access_list_account
+    }
+
+    fn attrs(&self, rwc: RWCounter) -> RwAttrs {
+        RwAttrs {
+            address: Some(self.address),
+            tx_id: Some(self.tx_id),
+            ..RwAttrs::new(Self::TARGET, rwc.0)
+        }
+    }
+}
+
+impl TypedOp for TxAccessListAccountStorageOp {
+    const TARGET: Target = Target::TxAccessListAccountStorage;
+
+    fn select(container: &OperationContainer) -> &[Operation<Self>] {
+        &container.tx_access_list_account_storage
+    }
+
+    fn attrs(&self, rwc: RWCounter) -> RwAttrs {
+        RwAttrs {
+            address: Some(self.address),
+            storage_key: Some(self.key),
+            tx_id: Some(self.tx_id),
+            ..RwAttrs::new(Self::TARGET, rwc.0)
+        }
+    }
+}
+
+impl TypedOp for TxRefundOp {
+    const TARGET: Target = Target::TxRefund;
+
+    fn select(container: &OperationContainer) -> &[Operation<Self>] {
+        &container.tx_refund
+    }
+
+    fn attrs(&self, rwc: RWCounter) -> RwAttrs {
+        RwAttrs {
+            tx_id: Some(self.tx_id),
+            ..RwAttrs::new(Self::TARGET, rwc.0)
+        }
+    }
+}
+
+impl TypedOp for AccountOp {
+    const TARGET: Target = Target::Account;
+
+    fn select(container: &OperationContainer) -> &[Operation<Self>] {
+        &container.account
+    }
+
+    fn attrs(&self, rwc: RWCounter) -> RwAttrs {
+        RwAttrs {
+            address: Some(self.address),
+            ..RwAttrs::new(Self::TARGET, rwc.0)
+        }
+    }
+}
+
+impl TypedOp for CallContextOp {
+    const TARGET: Target = Target::CallContext;
+
+    fn select(container: &OperationContainer) -> &[Operation<Self>] {
+        &container.call_context
+    }
+
+    fn attrs(&self, rwc: RWCounter) -> RwAttrs {
+        RwAttrs {
+            call_id: Some(self.call_id),
+            ..RwAttrs::new(Self::TARGET, rwc.0)
+        }
+    }
+}
+
+impl TypedOp for TxReceiptOp {
+    const TARGET: Target = Target::TxReceipt;
+
+    fn select(container: &OperationContainer) -> &[Operation<Self>] {
+        &container.tx_receipt
+    }
+
+    fn attrs(&self, rwc: RWCounter) -> RwAttrs {
+        RwAttrs {
+            tx_id: Some(self.tx_id),
+            ..RwAttrs::new(Self::TARGET, rwc.0)
+        }
+    }
+}
+
+impl TypedOp for TxLogOp {
+    const TARGET: Target = Target::TxLog;
+
+    fn select(container: &OperationContainer) -> &[Operation<Self>] {
+        &container.tx_log
+    }
+
+    fn attrs(&self, rwc: RWCounter) -> RwAttrs {
+        RwAttrs {
+            tx_id: Some(self.tx_id),
+            ..RwAttrs::new(Self::TARGET, rwc.0)
+        }
+    }
+}
+
+impl<T: TypedOp> Operation<T> {
+    /// Return the attributes of this `Operation` used by [`RwFilter`].
+    pub fn attrs(&self) -> RwAttrs {
+        self.op().attrs(self.rwc())
+    }
+}
+
+impl OperationContainer {
+    /// Returns the operations of kind `T` selected by `filter`, in insertion
+    /// order.
+    pub fn query<'a, T: TypedOp + 'a>(
+        &'a self,
+        filter: &'a RwFilter,
+    ) -> impl Iterator<Item = &'a Operation<T>> + 'a {
+        T::select(self)
+            .iter()
+            .filter(move |op| filter.matches(&op.attrs()))
+    }
+
+    /// Returns references to the operations of any kind selected by `filter`,
+    /// ordered by rw counter.
+    pub fn query_refs(&self, filter: &RwFilter) -> Vec<OperationRef> {
+        fn collect<T: TypedOp>(
+            container: &OperationContainer,
+            filter: &RwFilter,
+            refs: &mut Vec<(RWCounter, OperationRef)>,
+        ) {
+            if filter
+                .target_filter()
+                .map_or(true, |target| target == T::TARGET)
+            {
+                refs.extend(
+                    T::select(container)
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, op)| filter.matches(&op.attrs()))
+                        .map(|(idx, op)| (op.rwc(), OperationRef::from((T::TARGET, idx)))),
+                );
+            }
+        }
+
+        let mut refs = Vec::new();
+        collect::<StartOp>(self, filter, &mut refs);
+        collect::<MemoryOp>(self, filter, &mut refs);
+        collect::<StackOp>(self, filter, &mut refs);
+        collect::<StorageOp>(self, filter, &mut refs);
+        collect::<TxAccessListAccountOp>(self, filter, &mut refs);
+        collect::<TxAccessListAccountStorageOp>(self, filter, &mut refs);
+        collect::<TxRefundOp>(self, filter, &mut refs);
+        collect::<AccountOp>(self, filter, &mut refs);
+        collect::<CallContextOp>(self, filter, &mut refs);
+        collect::<TxReceiptOp>(self, filter, &mut refs);
+        collect::<TxLogOp>(self, filter, &mut refs);
+        refs.sort_by_key(|(rwc, _)| *rwc);
+
+        refs.into_iter().map(|(_, op_ref)| op_ref).collect()
+    }
+}
+
+#[cfg(test)]
+mod query_test {
+    use super::*;
+    use crate::operation::{AccountField, RW};
+    use eth_types::evm_types::StackAddress;
+
+    #[test]
+    fn operation_container_query() {
+        let mut rwc = RWCounter::default();
+        let mut container = OperationContainer::default();
+        let addr_a = Address::repeat_byte(0xaa);
+        let addr_b = Address::repeat_byte(0xbb);
+
+        container.insert(Operation::new(
+            rwc.inc_pre(),
+            RW::WRITE,
+            StackOp::new(1, StackAddress(1023), Word::from(0x100)),
+        ));
+        for (address, key) in [(addr_a, 1u64), (addr_b, 1), (addr_a, 2)] {
+            container.insert(Operation::new(
+                rwc.inc_pre(),
+                RW::WRITE,
+                StorageOp::new(
+                    address,
+                    Word::from(key),
+                    Word::from(0x1),
+                    Word::zero(),
+                    1,
+                    Word::zero(),
+                ),
+            ));
+        }
+        container.insert(Operation::new(
+            rwc.inc_pre(),
+            RW::READ,
+            AccountOp::new(addr_a, AccountField::Nonce, Word::one(), Word::one()),
+        ));
+
+        let filter = RwFilter::new().address(addr_a);
+        let storage_keys = container
+            .query::<StorageOp>(&filter)
+            .map(|op| op.op().key.as_u64())
+            .collect::<Vec<_>>();
+        assert_eq!(storage_keys, vec![1, 2]);
+        assert_eq!(
+            container.query_refs(&filter),
+            vec![
+                OperationRef::from((Target::Storage, 0)),
+                OperationRef::from((Target::Storage, 2)),
+                OperationRef::from((Target::Account, 0)),
+            ]
+        );
+
+        // a stack operation has no storage key, so it's never selected by one
+        let filter = RwFilter::new().storage_key(Word::one());
+        assert_eq!(container.query::<StackOp>(&filter).count(), 0);
+        assert_eq!(container.query::<StorageOp>(&filter).count(), 2);
+
+        let filter = RwFilter::new()
+            .rw_counter_range(2..4)
+            .target(Target::Storage);
+        assert_eq!(
+            container.query_refs(&filter),
+            vec![
+                OperationRef::from((Target::Storage, 0)),
+                OperationRef::from((Target::Storage, 1)),
+            ]
+        );
+        assert_eq!(container.query::<AccountOp>(&filter).count(), 0);
+    }
+}
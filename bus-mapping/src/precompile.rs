@@ -1,7 +1,8 @@
 //! precompile helpers
 
-use eth_types::{evm_types::GasCost, Address, ToBigEndian, Word};
+use eth_types::{chain_spec::ChainRules, evm_types::GasCost, Address, ToBigEndian, Word};
 use revm_precompile::{Precompile, PrecompileError, Precompiles};
+use serde::{Deserialize, Serialize};
 use strum_macros::EnumIter;
 
 use crate::circuit_input_builder::{EcMulOp, EcPairingOp, N_BYTES_PER_PAIR, N_PAIRING_PER_OP};
@@ -14,6 +15,7 @@ pub fn is_precompiled(address: &Address) -> bool {
 }
 
 pub(crate) fn execute_precompiled(
+    rules: &ChainRules,
     address: &Address,
     input: &[u8],
     gas: u64,
@@ -30,13 +32,12 @@ pub(crate) fn execute_precompiled(
     );
     let (return_data, gas_cost, is_oog, is_ok) = match precompile_fn(input, gas) {
         Ok((gas_cost, return_value)) => {
-            if cfg!(feature = "scroll") {
+            if !rules.is_precompile_enabled(address) {
+                (vec![], gas, false, false)
+            } else if rules.scroll {
                 // Revm behavior is different from scroll evm,
                 // so we need to override the behavior of invalid input
                 match PrecompileCalls::from(address.0[19]) {
-                    PrecompileCalls::Blake2F | PrecompileCalls::Ripemd160 => {
-                        (vec![], gas, false, false)
-                    }
                     PrecompileCalls::Bn128Pairing => {
                         if input.len() > N_PAIRING_PER_OP * N_BYTES_PER_PAIR {
                             (vec![], gas, false, false)
@@ -171,7 +172,7 @@ impl PrecompileCalls {
 }
 
 /// Auxiliary data for Ecrecover
-#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct EcrecoverAuxData {
     /// Keccak hash of the message being signed.
     pub msg_hash: Word,
@@ -233,7 +234,7 @@ pub const MODEXP_SIZE_LIMIT: usize = 32;
 pub const MODEXP_INPUT_LIMIT: usize = 192;
 
 /// Auxiliary data for Modexp
-#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ModExpAuxData {
     /// The specified len of inputs: [base, exp, modulus]
     pub input_lens: [Word; 3],
@@ -334,7 +335,7 @@ impl ModExpAuxData {
 }
 
 /// Auxiliary data for EcAdd, i.e. P + Q = R
-#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct EcAddAuxData {
     /// x co-ordinate of the first point.
     pub p_x: Word,
@@ -379,7 +380,7 @@ impl EcAddAuxData {
 }
 
 /// Auxiliary data for EcMul, i.e. s * P = R
-#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct EcMulAuxData {
     /// x co-ordinate of the point.
     pub p_x: Word,
@@ -426,11 +427,11 @@ impl EcMulAuxData {
 }
 
 /// Auxiliary data for EcPairing.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct EcPairingAuxData(pub EcPairingOp);
 
 /// Erroneous bytes passed to the EcPairing precompile call.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum EcPairingError {
     /// the calldatalength passed to EcPairing precompile call is expected to be:
     /// 1. len(input) <= 768
@@ -439,7 +440,7 @@ pub enum EcPairingError {
 }
 
 /// Auxiliary data attached to an internal state for precompile verification.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PrecompileAuxData {
     /// Base precompile (used for SHA256, RIPEMD-160 and BLAKE2F).
     Base {
@@ -165,6 +165,11 @@ impl StateDB {
         }
     }
 
+    /// Iterate over all the accounts in current state db, with their addresses.
+    pub fn iter_accounts(&self) -> impl Iterator<Item = (&Address, &Account)> {
+        self.state.iter()
+    }
+
     /// List all account addresses in current state db
     pub fn list_accounts(&self) {
         let addrs: BTreeSet<_> = self.state.keys().collect();
@@ -43,6 +43,7 @@ mod tests {
         const MAX_TXS: usize = 10;
         const MAX_CALLDATA: usize = 128;
         const MAX_INNER_BLOCKS: usize = 64;
+        const MAX_L1_MSGS_POPPED: usize = 256;
 
         let degree: u32 = var("DEGREE")
             .unwrap_or_else(|_| "19".to_string())
@@ -51,7 +52,14 @@ mod tests {
 
         let block = generate_block::<MAX_TXS, MAX_CALLDATA>();
         let circuit = PiTestCircuit::<Fr, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS>(
-            PiCircuit::<Fr>::new(MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS, &block),
+            PiCircuit::<Fr>::new(
+                MAX_TXS,
+                MAX_CALLDATA,
+                MAX_INNER_BLOCKS,
+                MAX_L1_MSGS_POPPED,
+                &block,
+            ),
+            block.mpt_updates.clone(),
         );
 
         let public_inputs = circuit.0.instance();
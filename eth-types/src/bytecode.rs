@@ -3,11 +3,17 @@
 use crate::{evm_types::OpcodeId, Bytes, ToWord, Word};
 use std::{collections::HashMap, str::FromStr};
 
+mod assembler;
+
+pub use assembler::{Assembler, SourceLocation, SourceMap};
+
 /// Error type for Bytecode related failures
 #[derive(Debug)]
 pub enum Error {
     /// Serde de/serialization error.
     InvalidAsmError(String),
+    /// Error assembling a source, at the given location.
+    AssemblyError(SourceLocation, String),
 }
 
 /// Helper struct that represents a single element in a bytecode.
@@ -32,6 +38,7 @@ pub struct Bytecode {
     pub code: Vec<BytecodeElement>,
     num_opcodes: usize,
     markers: HashMap<String, usize>,
+    source_map: SourceMap,
 }
 
 impl From<Bytecode> for Bytes {
@@ -57,9 +64,15 @@ impl Bytecode {
                 .collect(),
             markers: HashMap::new(),
             num_opcodes: 0,
+            source_map: SourceMap::default(),
         }
     }
 
+    /// Assemble multi-line EVM assembly, see [`Assembler`] for the syntax.
+    pub fn from_asm(source: &str) -> Result<Self, Error> {
+        Assembler::default().assemble(source)
+    }
+
     /// Get the code
     pub fn code(&self) -> Vec<u8> {
         self.code.iter().map(|b| b.value).collect()
@@ -77,6 +90,7 @@ impl Bytecode {
 
     /// Append
     pub fn append(&mut self, other: &Bytecode) {
+        self.source_map.append(&other.source_map, self.code.len());
         self.code.extend_from_slice(&other.code);
         for (key, val) in other.markers.iter() {
             self.insert_marker(key, self.num_opcodes + val);
@@ -154,12 +168,27 @@ impl Bytecode {
         self
     }
 
-    /// Generate the diassembly
+    /// Get the source location of the opcode at `pc`, if it was assembled from a source.
+    pub fn source_location(&self, pc: usize) -> Option<&SourceLocation> {
+        self.source_map.location(pc)
+    }
+
+    /// Get the source map of the assembled opcodes.
+    pub fn source_map(&self) -> &SourceMap {
+        &self.source_map
+    }
+
+    /// Generate the diassembly, annotated with the source location of the assembled opcodes
     pub fn disasm(&self) -> String {
         let mut asm = String::new();
+        let mut pc = 0;
         for op in self.iter() {
             asm.push_str(&op.to_string());
+            if let Some(location) = self.source_location(pc) {
+                asm.push_str(&format!(" // {location}"));
+            }
             asm.push('\n');
+            pc += 1 + op.opcode().data_len();
         }
         asm
     }
@@ -231,7 +260,7 @@ impl FromStr for OpcodeWithData {
                 return Err(err());
             }
 
-            // Parse `PUSH0` below only for shanghai (otherwise as an invalid opcode).
+            // `PUSH0` has no value, it's parsed below as a plain opcode.
             if n > 0 {
                 let value = if n_value[1].starts_with("0x") {
                     Word::from_str_radix(&n_value[1][2..], 16)
@@ -595,9 +624,8 @@ mod tests {
         assert_eq!(code.code, code2.code);
     }
 
-    #[cfg(feature = "shanghai")]
     #[test]
-    fn test_asm_disasm_for_shanghai() {
+    fn test_asm_disasm_push0() {
         let code = bytecode! {
             PUSH0
             POP
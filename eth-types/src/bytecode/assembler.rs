@@ -0,0 +1,486 @@
+//! A two-pass EVM assembler with labels, constants and included snippets.
+//!
+//! The source is a sequence of whitespace separated tokens, where `;` and `//` start a comment
+//! running to the end of the line:
+//!
+//! ```text
+//! .const SLOT 0x01        ; a named constant
+//! .include prelude        ; a snippet registered with `Assembler::with_snippet`
+//!     PUSH SLOT           ; `PUSH` is sized to fit its operand
+//!     SLOAD
+//!     PUSH @end           ; the address of a label, resolved once the code is laid out
+//!     JUMPI
+//!     PUSH2 0xffff        ; explicitly sized push
+//!     PUSH1(0)            ; the syntax of `Bytecode::disasm` is accepted as well
+//!     SSTORE
+//! end:
+//!     JUMPDEST
+//! ```
+//!
+//! Labels are recorded as markers of the assembled [`Bytecode`] and every opcode keeps the
+//! [`SourceLocation`] it was assembled from in the [`SourceMap`] of the bytecode.
+
+use super::{Bytecode, Error};
+use crate::{evm_types::OpcodeId, Word};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    str::FromStr,
+};
+
+/// Name of the source passed to [`Assembler::assemble`].
+const MAIN_SOURCE: &str = "<main>";
+
+/// Maximum nesting depth of included snippets, which rejects recursive includes.
+const MAX_INCLUDE_DEPTH: usize = 16;
+
+/// The location in the assembly source of an assembled opcode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceLocation {
+    /// The source name, `<main>` or the name of an included snippet.
+    pub source: String,
+    /// The 1-based line number in the source.
+    pub line: usize,
+    /// The source line, without comment.
+    pub text: String,
+}
+
+impl fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.source, self.line, self.text)
+    }
+}
+
+/// Map from the program counter of the assembled opcodes to their source location.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SourceMap(BTreeMap<usize, (usize, SourceLocation)>);
+
+impl SourceMap {
+    /// Get the source location of the opcode at `pc`, push data is mapped to its push.
+    pub fn location(&self, pc: usize) -> Option<&SourceLocation> {
+        self.0
+            .range(..=pc)
+            .next_back()
+            .filter(|(start, (len, _))| pc < *start + len)
+            .map(|(_, (_, location))| location)
+    }
+
+    /// Iterate over the program counter and source location of the assembled opcodes.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &SourceLocation)> {
+        self.0.iter().map(|(pc, (_, location))| (*pc, location))
+    }
+
+    /// Returns `true` if the map has no source location.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub(super) fn insert(&mut self, pc: usize, len: usize, location: SourceLocation) {
+        self.0.insert(pc, (len, location));
+    }
+
+    /// Append the source map of a bytecode appended at `offset`.
+    pub(super) fn append(&mut self, other: &SourceMap, offset: usize) {
+        for (pc, entry) in other.0.iter() {
+            self.0.insert(offset + pc, entry.clone());
+        }
+    }
+}
+
+/// Assembler of multi-line EVM assembly, see the [module documentation](self) for the syntax.
+#[derive(Debug, Default, Clone)]
+pub struct Assembler {
+    constants: HashMap<String, Word>,
+    snippets: HashMap<String, String>,
+}
+
+impl Assembler {
+    /// Define a constant visible to all sources.
+    pub fn with_constant(mut self, name: &str, value: Word) -> Self {
+        self.constants.insert(name.to_string(), value);
+        self
+    }
+
+    /// Register a snippet that can be included with `.include <name>`.
+    pub fn with_snippet(mut self, name: &str, source: &str) -> Self {
+        self.snippets.insert(name.to_string(), source.to_string());
+        self
+    }
+
+    /// Assemble the source into bytecode.
+    pub fn assemble(&self, source: &str) -> Result<Bytecode, Error> {
+        let mut parser = Parser {
+            assembler: self,
+            constants: self.constants.clone(),
+            items: vec![],
+        };
+        parser.parse(MAIN_SOURCE, source, 0)?;
+
+        let mut items = parser.items;
+        let labels = layout(&mut items)?;
+
+        let mut code = Bytecode::default();
+        for (item, location) in items {
+            let pc = code.code.len();
+            match item {
+                Item::Op(op) => {
+                    code.write_op(op);
+                }
+                Item::Push { size, operand, .. } => {
+                    let value = match operand {
+                        Operand::Value(value) => value,
+                        Operand::Label(label) => Word::from(labels[&label] as u64),
+                    };
+                    if byte_len(value) > size as usize {
+                        return Err(Error::AssemblyError(
+                            location,
+                            format!("value {value:#x} too big for PUSH{size}"),
+                        ));
+                    }
+                    code.push(size, value);
+                }
+                Item::Label(label) => {
+                    let pos = code.num_opcodes;
+                    code.insert_marker(&label, pos);
+                    continue;
+                }
+            }
+            let len = code.code.len() - pc;
+            code.source_map.insert(pc, len, location);
+        }
+
+        Ok(code)
+    }
+}
+
+enum Operand {
+    Value(Word),
+    Label(String),
+}
+
+enum Item {
+    Op(OpcodeId),
+    /// A push with data, `fixed` is `false` if its size is chosen by the assembler.
+    Push {
+        size: u8,
+        fixed: bool,
+        operand: Operand,
+    },
+    Label(String),
+}
+
+struct Parser<'a> {
+    assembler: &'a Assembler,
+    constants: HashMap<String, Word>,
+    items: Vec<(Item, SourceLocation)>,
+}
+
+impl Parser<'_> {
+    fn parse(&mut self, name: &str, source: &str, depth: usize) -> Result<(), Error> {
+        for (index, line) in source.lines().enumerate() {
+            let text = line
+                .split(';')
+                .next()
+                .and_then(|code| code.split("//").next())
+                .unwrap_or_default()
+                .trim();
+            let location = SourceLocation {
+                source: name.to_string(),
+                line: index + 1,
+                text: text.to_string(),
+            };
+            let err = |message: String| Error::AssemblyError(location.clone(), message);
+
+            let mut tokens = text.split_whitespace();
+            while let Some(token) = tokens.next() {
+                let mut operand = |what: &str| {
+                    tokens
+                        .next()
+                        .ok_or_else(|| err(format!("missing {what} after {token}")))
+                };
+
+                if token == ".const" {
+                    let constant = operand("name")?;
+                    let value = self.value(operand("value")?).map_err(err)?;
+                    if self.constants.insert(constant.to_string(), value).is_some() {
+                        return Err(err(format!("constant {constant} already defined")));
+                    }
+                } else if token == ".include" {
+                    let snippet = operand("snippet name")?;
+                    let assembler = self.assembler;
+                    let snippet_source = assembler
+                        .snippets
+                        .get(snippet)
+                        .ok_or_else(|| err(format!("unknown snippet {snippet}")))?;
+                    if depth == MAX_INCLUDE_DEPTH {
+                        return Err(err(format!("snippets nested too deeply at {snippet}")));
+                    }
+                    self.parse(snippet, snippet_source, depth + 1)?;
+                } else if let Some(label) = token.strip_suffix(':') {
+                    if !is_identifier(label) {
+                        return Err(err(format!("invalid label {label}")));
+                    }
+                    if self.has_label(label) {
+                        return Err(err(format!("label {label} already defined")));
+                    }
+                    self.items
+                        .push((Item::Label(label.to_string()), location.clone()));
+                } else if let Some((mnemonic, value)) = token.split_once('(') {
+                    // `PUSHn(value)`, as printed by `Bytecode::disasm`
+                    let value = value
+                        .strip_suffix(')')
+                        .ok_or_else(|| err(format!("unclosed parenthesis in {token}")))?;
+                    let item = self.push(mnemonic, value).map_err(err)?;
+                    self.items.push((item, location.clone()));
+                } else if token.to_uppercase().starts_with("PUSH") && !is_push0(token) {
+                    let value = operand("operand")?;
+                    let item = self.push(token, value).map_err(err)?;
+                    self.items.push((item, location.clone()));
+                } else {
+                    let op = OpcodeId::from_str(&token.to_uppercase())
+                        .map_err(|_| err(format!("unknown opcode {token}")))?;
+                    self.items.push((Item::Op(op), location.clone()));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn has_label(&self, label: &str) -> bool {
+        self.items
+            .iter()
+            .any(|(item, _)| matches!(item, Item::Label(l) if l == label))
+    }
+
+    /// Parse a push with data, `PUSH` picks the smallest size fitting the operand.
+    fn push(&self, mnemonic: &str, operand: &str) -> Result<Item, String> {
+        let size = mnemonic
+            .to_uppercase()
+            .strip_prefix("PUSH")
+            .map(|n| {
+                if n.is_empty() {
+                    Ok(None)
+                } else {
+                    n.parse::<u8>().map(Some)
+                }
+            })
+            .and_then(Result::ok)
+            .ok_or_else(|| format!("unknown push {mnemonic}"))?;
+        if let Some(size) = size {
+            if !(1..=32).contains(&size) {
+                return Err(format!("invalid push size {size}"));
+            }
+        }
+
+        let operand = match operand.strip_prefix('@') {
+            Some(label) if is_identifier(label) => Operand::Label(label.to_string()),
+            Some(label) => return Err(format!("invalid label {label}")),
+            None => Operand::Value(self.value(operand)?),
+        };
+        Ok(match (size, operand) {
+            (Some(size), operand) => Item::Push {
+                size,
+                fixed: true,
+                operand,
+            },
+            (None, Operand::Value(value)) => Item::Push {
+                size: byte_len(value).max(1) as u8,
+                fixed: true,
+                operand: Operand::Value(value),
+            },
+            // The size of a label push is only known after the layout.
+            (None, operand) => Item::Push {
+                size: 1,
+                fixed: false,
+                operand,
+            },
+        })
+    }
+
+    /// Parse a decimal or `0x` prefixed hex literal, or a constant.
+    fn value(&self, token: &str) -> Result<Word, String> {
+        if let Some(hex) = token.strip_prefix("0x") {
+            Word::from_str_radix(hex, 16).map_err(|_| format!("invalid hex literal {token}"))
+        } else if token.starts_with(|c: char| c.is_ascii_digit()) {
+            Word::from_dec_str(token).map_err(|_| format!("invalid literal {token}"))
+        } else {
+            self.constants
+                .get(token)
+                .cloned()
+                .ok_or_else(|| format!("unknown constant {token}"))
+        }
+    }
+}
+
+/// Lay out the items and return the program counter of each label. The label pushes sized by the
+/// assembler start as `PUSH1` and grow until all the label addresses fit, which terminates as the
+/// sizes only increase.
+fn layout(items: &mut [(Item, SourceLocation)]) -> Result<HashMap<String, usize>, Error> {
+    loop {
+        let mut labels = HashMap::new();
+        let mut pc = 0;
+        for (item, _) in items.iter() {
+            match item {
+                Item::Op(_) => pc += 1,
+                Item::Push { size, .. } => pc += 1 + *size as usize,
+                Item::Label(label) => {
+                    labels.insert(label.clone(), pc);
+                }
+            }
+        }
+
+        let mut resized = false;
+        for (item, location) in items.iter_mut() {
+            if let Item::Push {
+                size,
+                fixed,
+                operand: Operand::Label(label),
+            } = item
+            {
+                let pc = *labels.get(label).ok_or_else(|| {
+                    Error::AssemblyError(location.clone(), format!("unknown label {label}"))
+                })?;
+                let needed = byte_len(Word::from(pc as u64)).max(1) as u8;
+                if !*fixed && needed > *size {
+                    *size = needed;
+                    resized = true;
+                }
+            }
+        }
+
+        if !resized {
+            return Ok(labels);
+        }
+    }
+}
+
+/// Number of bytes needed to represent the value.
+fn byte_len(value: Word) -> usize {
+    value.bits().div_ceil(8)
+}
+
+fn is_push0(token: &str) -> bool {
+    token.eq_ignore_ascii_case("PUSH0")
+}
+
+fn is_identifier(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bytecode;
+
+    #[test]
+    fn test_assemble_labels() {
+        let code = Bytecode::from_asm(
+            r#"
+            PUSH1 0x01
+            PUSH @end       ; forward jump
+            JUMPI
+            PUSH2(0x1234)
+            POP
+            end:
+            JUMPDEST
+            STOP
+            "#,
+        )
+        .unwrap();
+
+        let expected = bytecode! {
+            PUSH1(0x01)
+            PUSH1(0x09)
+            JUMPI
+            PUSH2(0x1234)
+            POP
+            JUMPDEST
+            STOP
+        };
+        assert_eq!(code.code, expected.code);
+        assert_eq!(code.get_pos("end"), 5);
+    }
+
+    #[test]
+    fn test_assemble_resizes_label_pushes() {
+        // 300 bytes of code before the label, the jump needs a PUSH2
+        let source = format!(
+            "PUSH @target\nJUMP\n{}\ntarget: JUMPDEST",
+            "PUSH1 0x00 POP\n".repeat(100)
+        );
+        let code = Bytecode::from_asm(&source).unwrap();
+
+        assert_eq!(code.code()[..3], [OpcodeId::PUSH2.as_u8(), 0x01, 0x30]);
+        assert_eq!(code.code()[0x130], OpcodeId::JUMPDEST.as_u8());
+    }
+
+    #[test]
+    fn test_assemble_constants_and_snippets() {
+        let assembler = Assembler::default()
+            .with_constant("OFFSET", Word::from(0x40))
+            .with_snippet(
+                "store",
+                ".const VALUE 0x80\nPUSH VALUE\nPUSH OFFSET\nMSTORE",
+            );
+        let code = assembler.assemble(".include store\nPUSH VALUE").unwrap();
+
+        let mut expected = Bytecode::default();
+        expected.setup_state();
+        expected.op_push1(0x80);
+        assert_eq!(code.code, expected.code);
+    }
+
+    #[test]
+    fn test_source_map() {
+        let assembler = Assembler::default().with_snippet("stop", "\nSTOP // halt");
+        let code = assembler
+            .assemble("PUSH2 0x0102 ; a push\n\n.include stop")
+            .unwrap();
+
+        let push = code.source_location(2).unwrap();
+        assert_eq!((push.source.as_str(), push.line), ("<main>", 1));
+        assert_eq!(push.text, "PUSH2 0x0102");
+        assert_eq!(code.source_location(3).unwrap().to_string(), "stop:2: STOP");
+        assert!(code.source_location(4).is_none());
+
+        assert_eq!(
+            code.disasm(),
+            "PUSH2(258) // <main>:1: PUSH2 0x0102\nSTOP // stop:2: STOP\n"
+        );
+        // the annotated disassembly assembles back to the same code
+        assert_eq!(Bytecode::from_asm(&code.disasm()).unwrap().code, code.code);
+    }
+
+    #[test]
+    fn test_source_map_append() {
+        let mut code = bytecode! { PUSH1(0x01) };
+        code.append(&Bytecode::from_asm("POP\nSTOP").unwrap());
+
+        assert!(code.source_location(0).is_none());
+        assert_eq!(code.source_location(3).unwrap().line, 2);
+    }
+
+    #[test]
+    fn test_assemble_errors() {
+        let error_line = |source: &str| match Bytecode::from_asm(source) {
+            Err(Error::AssemblyError(location, _)) => location.line,
+            _ => panic!("expected an assembly error"),
+        };
+
+        assert_eq!(error_line("STOP\nFOO"), 2);
+        assert_eq!(error_line("PUSH @missing\nJUMP"), 1);
+        assert_eq!(error_line("a:\nSTOP\na:"), 3);
+        assert_eq!(error_line("PUSH1 0x100"), 1);
+        assert_eq!(error_line("STOP\n\nPUSH UNKNOWN"), 3);
+        assert_eq!(error_line("PUSH"), 1);
+        assert!(Assembler::default()
+            .with_snippet("loop", ".include loop")
+            .assemble(".include loop")
+            .is_err());
+    }
+}
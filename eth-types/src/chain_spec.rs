@@ -0,0 +1,283 @@
+//! Hardfork schedule of a chain, selecting at runtime the rules that apply to
+//! each block.
+//!
+//! The default [`ChainSpec`] follows the `shanghai` and `scroll` cargo
+//! features, so that builds relying on them keep their behaviour. Hardforks
+//! are selected at runtime, but [`ChainSpec::scroll`] must match the `scroll`
+//! feature of the build: the L1 messages and the code hash of accounts are
+//! still compiled in.
+
+use crate::{
+    evm_types::{
+        GasCost, OpcodeId, INIT_CODE_WORD_GAS, MAX_CODE_SIZE, MAX_EXPANDED_MEMORY_ADDRESS,
+    },
+    Address,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Hardforks following London, in activation order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Hardfork {
+    /// London, the base of the supported forks
+    London,
+    /// Shanghai: PUSH0 (EIP-3855), warm coinbase (EIP-3651) and limited and
+    /// metered init code (EIP-3860)
+    Shanghai,
+    /// Cancun: BLOBHASH (EIP-4844). The other opcodes of Cancun aren't
+    /// supported by the circuits.
+    Cancun,
+}
+
+/// Condition activating a hardfork.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ForkCondition {
+    /// Active from the block with this number
+    Block(u64),
+    /// Active from the first block with at least this timestamp
+    Timestamp(u64),
+    /// Never active
+    Never,
+}
+
+impl ForkCondition {
+    /// Returns true if the condition holds for the block with `number` and
+    /// `timestamp`.
+    pub fn is_active(&self, number: u64, timestamp: u64) -> bool {
+        match self {
+            Self::Block(block) => number >= *block,
+            Self::Timestamp(time) => timestamp >= *time,
+            Self::Never => false,
+        }
+    }
+}
+
+/// The hardfork schedule of a chain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainSpec {
+    /// Whether the chain is Scroll: L1 messages, no SELFDESTRUCT nor BASEFEE,
+    /// and a restricted set of precompiles
+    pub scroll: bool,
+    /// Activation of the hardforks after London
+    pub forks: BTreeMap<Hardfork, ForkCondition>,
+}
+
+impl Default for ChainSpec {
+    fn default() -> Self {
+        if cfg!(feature = "shanghai") {
+            Self::shanghai()
+        } else {
+            Self::london()
+        }
+        .with_scroll(cfg!(feature = "scroll"))
+    }
+}
+
+impl ChainSpec {
+    /// A chain staying on London.
+    pub fn london() -> Self {
+        Self {
+            scroll: false,
+            forks: BTreeMap::new(),
+        }
+    }
+
+    /// A chain on Shanghai from genesis.
+    pub fn shanghai() -> Self {
+        Self::london().with_fork(Hardfork::Shanghai, ForkCondition::Block(0))
+    }
+
+    /// A chain on Cancun from genesis.
+    pub fn cancun() -> Self {
+        Self::shanghai().with_fork(Hardfork::Cancun, ForkCondition::Block(0))
+    }
+
+    /// Set whether the chain is Scroll.
+    pub fn with_scroll(mut self, scroll: bool) -> Self {
+        self.scroll = scroll;
+        self
+    }
+
+    /// Set the activation condition of `hardfork`.
+    pub fn with_fork(mut self, hardfork: Hardfork, condition: ForkCondition) -> Self {
+        self.forks.insert(hardfork, condition);
+        self
+    }
+
+    /// Returns the activation condition of `hardfork`.
+    pub fn fork_condition(&self, hardfork: Hardfork) -> ForkCondition {
+        match hardfork {
+            Hardfork::London => ForkCondition::Block(0),
+            _ => self
+                .forks
+                .get(&hardfork)
+                .copied()
+                .unwrap_or(ForkCondition::Never),
+        }
+    }
+
+    /// Returns the latest hardfork active at the block with `number` and
+    /// `timestamp`.
+    pub fn hardfork_at(&self, number: u64, timestamp: u64) -> Hardfork {
+        self.forks
+            .iter()
+            .rev()
+            .find(|(_, condition)| condition.is_active(number, timestamp))
+            .map_or(Hardfork::London, |(hardfork, _)| *hardfork)
+    }
+
+    /// Returns the rules of the block with `number` and `timestamp`.
+    pub fn rules(&self, number: u64, timestamp: u64) -> ChainRules {
+        ChainRules {
+            hardfork: self.hardfork_at(number, timestamp),
+            scroll: self.scroll,
+        }
+    }
+}
+
+/// The rules applying to a block, as selected by a [`ChainSpec`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainRules {
+    /// Active hardfork
+    pub hardfork: Hardfork,
+    /// Whether the chain is Scroll
+    pub scroll: bool,
+}
+
+impl Default for ChainRules {
+    fn default() -> Self {
+        ChainSpec::default().rules(0, 0)
+    }
+}
+
+impl ChainRules {
+    /// Returns true if Shanghai is active.
+    pub fn is_shanghai(&self) -> bool {
+        self.hardfork >= Hardfork::Shanghai
+    }
+
+    /// Returns true if Cancun is active.
+    pub fn is_cancun(&self) -> bool {
+        self.hardfork >= Hardfork::Cancun
+    }
+
+    /// Returns true if `op` is defined, executing an undefined opcode fails
+    /// with an invalid opcode error.
+    pub fn is_opcode_enabled(&self, op: OpcodeId) -> bool {
+        match op {
+            OpcodeId::PUSH0 => self.is_shanghai(),
+            OpcodeId::BLOBHASH => self.is_cancun() && !self.scroll,
+            OpcodeId::BASEFEE | OpcodeId::SELFDESTRUCT => !self.scroll,
+            OpcodeId::INVALID(_) => false,
+            _ => true,
+        }
+    }
+
+    /// Returns the opcodes undefined under these rules, as invalid opcodes.
+    pub fn invalid_opcodes(&self) -> Vec<OpcodeId> {
+        (u8::MIN..=u8::MAX)
+            .filter(|byte| !self.is_opcode_enabled(OpcodeId::from(*byte)))
+            .map(OpcodeId::INVALID)
+            .collect()
+    }
+
+    /// Returns true if the coinbase is warm at the beginning of a transaction
+    /// (EIP-3651).
+    pub fn is_coinbase_warm(&self) -> bool {
+        self.is_shanghai()
+    }
+
+    /// Gas paid per word of init code by a creation transaction (EIP-3860).
+    pub fn init_code_word_gas(&self) -> u64 {
+        if self.is_shanghai() {
+            INIT_CODE_WORD_GAS
+        } else {
+            0
+        }
+    }
+
+    /// Gas paid per word of init code by CREATE and CREATE2, which also pays
+    /// for hashing it.
+    pub fn create_gas_per_code_word(&self, op: OpcodeId) -> u64 {
+        let hash_gas = if op == OpcodeId::CREATE2 {
+            GasCost::COPY_SHA3.as_u64()
+        } else {
+            0
+        };
+        self.init_code_word_gas() + hash_gas
+    }
+
+    /// Maximum size of init code (EIP-3860), or of the memory before Shanghai.
+    pub fn max_init_code_size(&self) -> u64 {
+        if self.is_shanghai() {
+            2 * MAX_CODE_SIZE
+        } else {
+            MAX_EXPANDED_MEMORY_ADDRESS
+        }
+    }
+
+    /// Returns true if `address` is a precompiled contract.
+    pub fn is_precompiled(&self, address: &Address) -> bool {
+        address.0[0..19] == [0u8; 19] && (1..=9).contains(&address.0[19])
+    }
+
+    /// Returns true if calls to the precompiled contract at `address` are
+    /// supported. Scroll keeps RIPEMD-160 and BLAKE2F as precompiles whose
+    /// calls always fail.
+    pub fn is_precompile_enabled(&self, address: &Address) -> bool {
+        self.is_precompiled(address) && !(self.scroll && matches!(address.0[19], 0x03 | 0x09))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hardfork_schedule() {
+        let spec = ChainSpec::london().with_fork(Hardfork::Shanghai, ForkCondition::Timestamp(100));
+        assert_eq!(spec.hardfork_at(5, 99), Hardfork::London);
+        assert_eq!(spec.hardfork_at(5, 100), Hardfork::Shanghai);
+        assert_eq!(
+            spec.fork_condition(Hardfork::London),
+            ForkCondition::Block(0)
+        );
+
+        let spec = ChainSpec::london().with_fork(Hardfork::Shanghai, ForkCondition::Block(10));
+        assert!(!spec.rules(9, u64::MAX).is_shanghai());
+        assert!(spec.rules(10, 0).is_shanghai());
+        assert_eq!(
+            ChainSpec::london().hardfork_at(u64::MAX, u64::MAX),
+            Hardfork::London
+        );
+    }
+
+    #[test]
+    fn rules() {
+        let london = ChainSpec::london().rules(0, 0);
+        let shanghai = ChainSpec::shanghai().rules(0, 0);
+        assert!(!london.is_opcode_enabled(OpcodeId::PUSH0));
+        assert!(shanghai.is_opcode_enabled(OpcodeId::PUSH0));
+        assert!(london.invalid_opcodes().contains(&OpcodeId::INVALID(0x5f)));
+        assert!(!shanghai
+            .invalid_opcodes()
+            .contains(&OpcodeId::INVALID(0x5f)));
+        let cancun = ChainSpec::cancun().rules(0, 0);
+        assert!(cancun.is_shanghai());
+        assert!(!shanghai.is_opcode_enabled(OpcodeId::BLOBHASH));
+        assert!(cancun.is_opcode_enabled(OpcodeId::BLOBHASH));
+        assert!(!ChainSpec::cancun()
+            .with_scroll(true)
+            .rules(0, 0)
+            .is_opcode_enabled(OpcodeId::BLOBHASH));
+        assert_eq!(london.create_gas_per_code_word(OpcodeId::CREATE), 0);
+        assert_eq!(shanghai.create_gas_per_code_word(OpcodeId::CREATE2), 8);
+
+        let scroll = ChainSpec::shanghai().with_scroll(true).rules(0, 0);
+        assert!(!scroll.is_opcode_enabled(OpcodeId::SELFDESTRUCT));
+        let ripemd160 = Address::from_low_u64_be(3);
+        assert!(scroll.is_precompiled(&ripemd160));
+        assert!(!scroll.is_precompile_enabled(&ripemd160));
+        assert!(shanghai.is_precompile_enabled(&ripemd160));
+    }
+}
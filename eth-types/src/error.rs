@@ -33,6 +33,11 @@ pub enum Error {
     WordToMemAddr,
     /// Signature parsing error.
     Signature,
+    /// An L1 message included in a chunk has a queue index below the first
+    /// one popped by the chunk.
+    L1QueueIndexBeforeStart(u64),
+    /// A chunk pops more L1 messages, skipped ones included, than supported.
+    TooManyL1MsgsPopped(u64),
 }
 
 impl Display for Error {
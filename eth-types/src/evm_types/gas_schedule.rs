@@ -0,0 +1,166 @@
+//! Gas schedule of the EVM, letting a chain reprice the opcodes.
+
+use super::{GasCost, OpcodeId};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt::Debug};
+
+/// Gas costs that witness generation and the EVM circuit take from the chain
+/// instead of the constants of [`GasCost`].
+///
+/// Only the constant gas cost of the opcodes can be repriced. The dynamic gas
+/// costs (memory expansion and copy, account and storage accesses, SSTORE,
+/// logs, calls and creations), the precompile gas costs and the intrinsic gas
+/// of the transactions keep the values of [`GasCost`], and so do the constant
+/// gas costs of the opcodes that are an account access, creation or self
+/// destruct cost.
+pub trait GasSchedule: Debug + Send + Sync {
+    /// Constant gas cost of `opcode`, charged before its dynamic gas cost.
+    fn constant_gas_cost(&self, opcode: OpcodeId) -> GasCost {
+        opcode.constant_gas_cost()
+    }
+}
+
+/// Whether the gas cost of `opcode` is its constant gas cost alone, when it
+/// doesn't fail.
+pub fn is_constant_gas_only(opcode: OpcodeId) -> bool {
+    opcode.constant_gas_cost().0 > 0
+        && !matches!(
+            opcode,
+            OpcodeId::SHA3
+                | OpcodeId::CALLDATACOPY
+                | OpcodeId::CODECOPY
+                | OpcodeId::RETURNDATACOPY
+                | OpcodeId::EXTCODECOPY
+                | OpcodeId::BALANCE
+                | OpcodeId::EXTCODESIZE
+                | OpcodeId::EXTCODEHASH
+                | OpcodeId::MLOAD
+                | OpcodeId::MSTORE
+                | OpcodeId::MSTORE8
+                | OpcodeId::EXP
+                | OpcodeId::CREATE
+                | OpcodeId::CREATE2
+                | OpcodeId::CALL
+                | OpcodeId::CALLCODE
+                | OpcodeId::DELEGATECALL
+                | OpcodeId::STATICCALL
+                | OpcodeId::SELFDESTRUCT
+        )
+}
+
+/// The gas schedule of Ethereum.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DefaultGasSchedule;
+
+impl GasSchedule for DefaultGasSchedule {}
+
+/// A gas schedule repricing some opcodes of the [`DefaultGasSchedule`].
+///
+/// It is a parameter of the circuits, so it is `Copy` and keeps the costs in
+/// an array indexed by the opcode.
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "GasTableRepr", into = "GasTableRepr")]
+pub struct GasTable {
+    constant_gas_costs: [Option<GasCost>; 256],
+}
+
+impl Default for GasTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GasTable {
+    /// Gas table repricing no opcode.
+    pub const fn new() -> Self {
+        Self {
+            constant_gas_costs: [None; 256],
+        }
+    }
+
+    /// Reprice the constant gas cost of `opcode`.
+    pub fn with_constant_gas_cost(mut self, opcode: OpcodeId, gas_cost: GasCost) -> Self {
+        self.constant_gas_costs[opcode.as_u8() as usize] = Some(gas_cost);
+        self
+    }
+
+    /// Constant gas costs replacing the default ones, ordered by opcode.
+    pub fn constant_gas_costs(&self) -> impl Iterator<Item = (OpcodeId, GasCost)> + '_ {
+        self.constant_gas_costs
+            .iter()
+            .enumerate()
+            .filter_map(|(opcode, gas_cost)| {
+                gas_cost.map(|gas_cost| (OpcodeId::from(opcode as u8), gas_cost))
+            })
+    }
+}
+
+impl GasSchedule for GasTable {
+    fn constant_gas_cost(&self, opcode: OpcodeId) -> GasCost {
+        self.constant_gas_costs[opcode.as_u8() as usize]
+            .unwrap_or_else(|| opcode.constant_gas_cost())
+    }
+}
+
+impl Debug for GasTable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GasTable")
+            .field(
+                "constant_gas_costs",
+                &GasTableRepr::from(*self).constant_gas_costs,
+            )
+            .finish()
+    }
+}
+
+/// Serialized form of a [`GasTable`].
+#[derive(Clone, Serialize, Deserialize)]
+struct GasTableRepr {
+    constant_gas_costs: BTreeMap<OpcodeId, GasCost>,
+}
+
+impl From<GasTable> for GasTableRepr {
+    fn from(gas_table: GasTable) -> Self {
+        Self {
+            constant_gas_costs: gas_table.constant_gas_costs().collect(),
+        }
+    }
+}
+
+impl From<GasTableRepr> for GasTable {
+    fn from(repr: GasTableRepr) -> Self {
+        repr.constant_gas_costs
+            .into_iter()
+            .fold(Self::default(), |gas_table, (opcode, gas_cost)| {
+                gas_table.with_constant_gas_cost(opcode, gas_cost)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gas_table_reprices() {
+        let table = GasTable::default().with_constant_gas_cost(OpcodeId::ADD, GasCost(5));
+        assert_eq!(table.constant_gas_cost(OpcodeId::ADD), GasCost(5));
+        assert_eq!(
+            table.constant_gas_cost(OpcodeId::MUL),
+            DefaultGasSchedule.constant_gas_cost(OpcodeId::MUL)
+        );
+
+        let json = serde_json::to_string(&table).unwrap();
+        assert_eq!(json, r#"{"constant_gas_costs":{"ADD":5}}"#);
+        assert_eq!(serde_json::from_str::<GasTable>(&json).unwrap(), table);
+    }
+
+    #[test]
+    fn constant_gas_only_opcodes() {
+        assert!(is_constant_gas_only(OpcodeId::ADD));
+        assert!(is_constant_gas_only(OpcodeId::PUSH1));
+        assert!(!is_constant_gas_only(OpcodeId::STOP));
+        assert!(!is_constant_gas_only(OpcodeId::MSTORE));
+        assert!(!is_constant_gas_only(OpcodeId::SSTORE));
+    }
+}
@@ -282,6 +282,8 @@ pub enum OpcodeId {
     SELFBALANCE,
     /// `BASEFEE`
     BASEFEE,
+    /// `BLOBHASH`
+    BLOBHASH,
     /// `SLOAD`
     SLOAD,
     /// `SSTORE`
@@ -318,16 +320,10 @@ pub enum OpcodeId {
 }
 
 impl OpcodeId {
-    #[cfg(feature = "shanghai")]
     /// Returns `true` if the `OpcodeId` is a `PUSHn` (including `PUSH0`).
     pub fn is_push(&self) -> bool {
         self.as_u8() >= Self::PUSH0.as_u8() && self.as_u8() <= Self::PUSH32.as_u8()
     }
-    #[cfg(not(feature = "shanghai"))]
-    /// Returns `true` if the `OpcodeId` is a `PUSHn`.
-    pub fn is_push(&self) -> bool {
-        self.as_u8() >= Self::PUSH1.as_u8() && self.as_u8() <= Self::PUSH32.as_u8()
-    }
 
     /// Returns `true` if the `OpcodeId` is a `PUSH1` .. `PUSH32` (excluding `PUSH0`).
     pub fn is_push_with_data(&self) -> bool {
@@ -515,6 +511,7 @@ impl OpcodeId {
             OpcodeId::CHAINID => 0x46u8,
             OpcodeId::SELFBALANCE => 0x47u8,
             OpcodeId::BASEFEE => 0x48u8,
+            OpcodeId::BLOBHASH => 0x49u8,
             OpcodeId::SLOAD => 0x54u8,
             OpcodeId::SSTORE => 0x55u8,
             OpcodeId::GAS => 0x5au8,
@@ -593,6 +590,7 @@ impl OpcodeId {
             OpcodeId::CHAINID => GasCost::QUICK,
             OpcodeId::SELFBALANCE => GasCost::FAST,
             OpcodeId::BASEFEE => GasCost::QUICK,
+            OpcodeId::BLOBHASH => GasCost::FASTEST,
             OpcodeId::POP => GasCost::QUICK,
             OpcodeId::MLOAD => GasCost::FASTEST,
             OpcodeId::MSTORE => GasCost::FASTEST,
@@ -757,6 +755,7 @@ impl OpcodeId {
             OpcodeId::CHAINID => (1, 1024),
             OpcodeId::SELFBALANCE => (1, 1024),
             OpcodeId::BASEFEE => (1, 1024),
+            OpcodeId::BLOBHASH => (0, 1023),
             OpcodeId::POP => (0, 1023),
             OpcodeId::MLOAD => (0, 1023),
             OpcodeId::MSTORE => (0, 1022),
@@ -970,7 +969,6 @@ impl From<u8> for OpcodeId {
             0x58u8 => OpcodeId::PC,
             0x59u8 => OpcodeId::MSIZE,
             0x5bu8 => OpcodeId::JUMPDEST,
-            #[cfg(feature = "shanghai")]
             0x5fu8 => OpcodeId::PUSH0,
             0x60u8 => OpcodeId::PUSH1,
             0x61u8 => OpcodeId::PUSH2,
@@ -1061,6 +1059,8 @@ impl From<u8> for OpcodeId {
             0x47u8 => OpcodeId::SELFBALANCE,
             #[cfg(not(feature = "scroll"))]
             0x48u8 => OpcodeId::BASEFEE,
+            #[cfg(not(feature = "scroll"))]
+            0x49u8 => OpcodeId::BLOBHASH,
             0x54u8 => OpcodeId::SLOAD,
             0x55u8 => OpcodeId::SSTORE,
             0x5au8 => OpcodeId::GAS,
@@ -1127,10 +1127,7 @@ impl FromStr for OpcodeId {
             "PC" => OpcodeId::PC,
             "MSIZE" => OpcodeId::MSIZE,
             "JUMPDEST" => OpcodeId::JUMPDEST,
-            #[cfg(feature = "shanghai")]
             "PUSH0" => OpcodeId::PUSH0,
-            #[cfg(not(feature = "shanghai"))]
-            "PUSH0" => OpcodeId::INVALID(0x5f),
             "PUSH1" => OpcodeId::PUSH1,
             "PUSH2" => OpcodeId::PUSH2,
             "PUSH3" => OpcodeId::PUSH3,
@@ -1240,6 +1237,10 @@ impl FromStr for OpcodeId {
             "BASEFEE" => OpcodeId::BASEFEE,
             #[cfg(feature = "scroll")]
             "BASEFEE" => OpcodeId::INVALID(0x48),
+            #[cfg(not(feature = "scroll"))]
+            "BLOBHASH" => OpcodeId::BLOBHASH,
+            #[cfg(feature = "scroll")]
+            "BLOBHASH" => OpcodeId::INVALID(0x49),
             "TLOAD" => OpcodeId::INVALID(0xb3),
             "TSTORE" => OpcodeId::INVALID(0xb4),
             _ => {
@@ -1282,13 +1283,7 @@ mod opcode_ids_tests {
 
     #[test]
     fn push_n() {
-        #[cfg(feature = "shanghai")]
         assert!(matches!(OpcodeId::push_n(0), Ok(OpcodeId::PUSH0)));
-        #[cfg(not(feature = "shanghai"))]
-        assert!(matches!(
-            OpcodeId::push_n(0),
-            Err(Error::InvalidOpConversion)
-        ));
         assert!(matches!(OpcodeId::push_n(1), Ok(OpcodeId::PUSH1)));
         assert!(matches!(OpcodeId::push_n(10), Ok(OpcodeId::PUSH10)));
         assert!(matches!(
@@ -1299,16 +1294,30 @@ mod opcode_ids_tests {
 
     #[test]
     fn postfix() {
-        #[cfg(feature = "shanghai")]
         assert_eq!(OpcodeId::PUSH0.postfix(), Some(0));
-        #[cfg(not(feature = "shanghai"))]
-        assert_eq!(OpcodeId::PUSH0.postfix(), None);
         assert_eq!(OpcodeId::PUSH1.postfix(), Some(1));
         assert_eq!(OpcodeId::PUSH10.postfix(), Some(10));
         assert_eq!(OpcodeId::LOG2.postfix(), Some(2));
         assert_eq!(OpcodeId::CALLCODE.postfix(), None);
     }
 
+    #[test]
+    fn blobhash() {
+        #[cfg(not(feature = "scroll"))]
+        {
+            assert_eq!(OpcodeId::from(0x49u8), OpcodeId::BLOBHASH);
+            assert_eq!(OpcodeId::from_str("BLOBHASH").unwrap(), OpcodeId::BLOBHASH);
+        }
+        #[cfg(feature = "scroll")]
+        {
+            assert_eq!(OpcodeId::from(0x49u8), OpcodeId::INVALID(0x49));
+            assert_eq!(
+                OpcodeId::from_str("BLOBHASH").unwrap(),
+                OpcodeId::INVALID(0x49)
+            );
+        }
+    }
+
     #[test]
     fn data_len() {
         assert_eq!(OpcodeId::PUSH0.data_len(), 0);
@@ -3,25 +3,29 @@
 #[cfg(feature = "scroll")]
 use crate::l2_types::BlockTrace;
 use crate::{
+    chain_spec::ChainSpec,
     sign_types::{biguint_to_32bytes_le, ct_option_ok_or, recover_pk2, SignData, SECP256K1_Q},
     AccessList, Address, Block, Bytes, Error, GethExecTrace, Hash, ToBigEndian, ToLittleEndian,
     Word, U64,
 };
-use ethers_core::types::{
-    transaction::eip2718::TypedTransaction, Eip1559TransactionRequest, Eip2930TransactionRequest,
-    NameOrAddress, TransactionRequest, H256,
+use ethers_core::{
+    types::{
+        transaction::eip2718::TypedTransaction, Eip1559TransactionRequest,
+        Eip2930TransactionRequest, NameOrAddress, OtherFields, TransactionRequest, H256,
+    },
+    utils::rlp::RlpStream,
 };
 use halo2_proofs::halo2curves::{group::ff::PrimeField, secp256k1::Fq};
 use num::Integer;
 use num_bigint::BigUint;
-use serde::{Serialize, Serializer};
+use serde::{Deserialize, Serialize, Serializer};
 use serde_with::serde_as;
 use sha3::{Digest, Keccak256};
 use std::collections::HashMap;
 use strum_macros::EnumIter;
 
 /// Tx type
-#[derive(Default, Debug, Copy, Clone, EnumIter, Serialize, PartialEq, Eq)]
+#[derive(Default, Debug, Copy, Clone, EnumIter, Serialize, Deserialize, PartialEq, Eq)]
 pub enum TxType {
     /// EIP 155 tx
     #[default]
@@ -34,6 +38,8 @@ pub enum TxType {
     Eip2930,
     /// L1 Message tx
     L1Msg,
+    /// EIP 4844 (blob-carrying) tx
+    Eip4844,
 }
 
 impl From<TxType> for usize {
@@ -74,11 +80,17 @@ impl TxType {
         matches!(*self, TxType::Eip2930)
     }
 
+    /// If this type is Eip4844 or not
+    pub fn is_eip4844(&self) -> bool {
+        matches!(*self, TxType::Eip4844)
+    }
+
     /// Get the type of transaction
     pub fn get_tx_type(tx: &crate::Transaction) -> Self {
         match tx.transaction_type {
             Some(x) if x == U64::from(1) => Self::Eip2930,
             Some(x) if x == U64::from(2) => Self::Eip1559,
+            Some(x) if x == U64::from(3) => Self::Eip4844,
             Some(x) if x == U64::from(0x7e) => Self::L1Msg,
             _ => {
                 if cfg!(feature = "scroll") {
@@ -116,6 +128,10 @@ impl TxType {
                 assert!(v <= 1);
                 v
             }
+            TxType::Eip4844 => {
+                assert!(v <= 1);
+                v
+            }
             TxType::L1Msg => {
                 unreachable!("L1 msg does not have signature")
             }
@@ -151,6 +167,7 @@ pub fn get_rlp_unsigned(tx: &crate::Transaction) -> Vec<u8> {
             let typed_tx: TypedTransaction = tx.into();
             typed_tx.rlp().to_vec()
         }
+        TxType::Eip4844 => get_eip4844_rlp(tx, false),
         TxType::L1Msg => {
             // L1 msg does not have signature
             vec![]
@@ -158,6 +175,65 @@ pub fn get_rlp_unsigned(tx: &crate::Transaction) -> Vec<u8> {
     }
 }
 
+/// Get the signed RLP bytes of the tx
+pub fn get_rlp_signed(tx: &crate::Transaction) -> Vec<u8> {
+    match TxType::get_tx_type(tx) {
+        TxType::Eip4844 => get_eip4844_rlp(tx, true),
+        _ => tx.rlp().to_vec(),
+    }
+}
+
+/// Key of the max fee per blob gas in the extra fields of a json tx
+pub const MAX_FEE_PER_BLOB_GAS_KEY: &str = "maxFeePerBlobGas";
+/// Key of the blob versioned hashes in the extra fields of a json tx
+pub const BLOB_VERSIONED_HASHES_KEY: &str = "blobVersionedHashes";
+
+/// Get the max fee per blob gas of an EIP-4844 tx
+pub fn get_max_fee_per_blob_gas(tx: &crate::Transaction) -> Option<Word> {
+    tx.other
+        .get_deserialized(MAX_FEE_PER_BLOB_GAS_KEY)
+        .and_then(Result::ok)
+}
+
+/// Get the versioned hashes of the blobs carried by an EIP-4844 tx
+pub fn get_blob_versioned_hashes(tx: &crate::Transaction) -> Vec<H256> {
+    tx.other
+        .get_deserialized(BLOB_VERSIONED_HASHES_KEY)
+        .and_then(Result::ok)
+        .unwrap_or_default()
+}
+
+// ethers-core does not support EIP-4844 txs yet, so we encode them by hand:
+// 0x03 || rlp([chain_id, nonce, max_priority_fee_per_gas, max_fee_per_gas, gas_limit, to,
+// value, data, access_list, max_fee_per_blob_gas, blob_versioned_hashes(, y_parity, r, s)])
+fn get_eip4844_rlp(tx: &crate::Transaction, signed: bool) -> Vec<u8> {
+    let mut stream = RlpStream::new();
+    stream.begin_list(if signed { 14 } else { 11 });
+    stream.append(&tx.chain_id.unwrap_or_default());
+    stream.append(&tx.nonce);
+    stream.append(&tx.max_priority_fee_per_gas.unwrap_or_default());
+    stream.append(&tx.max_fee_per_gas.unwrap_or_default());
+    stream.append(&tx.gas);
+    match tx.to {
+        Some(to) => stream.append(&to),
+        None => stream.append_empty_data(),
+    };
+    stream.append(&tx.value);
+    stream.append(&tx.input.to_vec());
+    stream.append(&tx.access_list.clone().unwrap_or_default());
+    stream.append(&get_max_fee_per_blob_gas(tx).unwrap_or_default());
+    stream.append_list(&get_blob_versioned_hashes(tx));
+    if signed {
+        stream.append(&tx.v);
+        stream.append(&tx.r);
+        stream.append(&tx.s);
+    }
+
+    let mut rlp = vec![0x03];
+    rlp.extend_from_slice(&stream.out());
+    rlp
+}
+
 /// Definition of all of the data related to an account.
 #[serde_as]
 #[derive(PartialEq, Eq, Debug, Default, Clone, Serialize)]
@@ -277,6 +353,10 @@ pub struct Transaction {
     pub call_data: Bytes,
     /// Access list
     pub access_list: Option<AccessList>,
+    /// Max fee per blob gas (EIP-4844 only)
+    pub max_fee_per_blob_gas: Option<Word>,
+    /// Versioned hashes of the blobs carried by the tx (EIP-4844 only)
+    pub blob_versioned_hashes: Vec<H256>,
 
     /// "v" value of the transaction signature
     pub v: u64,
@@ -296,6 +376,19 @@ pub struct Transaction {
 
 impl From<&Transaction> for crate::Transaction {
     fn from(tx: &Transaction) -> crate::Transaction {
+        let mut other = OtherFields::default();
+        if let Some(max_fee_per_blob_gas) = tx.max_fee_per_blob_gas {
+            other.insert(
+                MAX_FEE_PER_BLOB_GAS_KEY.to_string(),
+                serde_json::to_value(max_fee_per_blob_gas).unwrap(),
+            );
+        }
+        if !tx.blob_versioned_hashes.is_empty() {
+            other.insert(
+                BLOB_VERSIONED_HASHES_KEY.to_string(),
+                serde_json::to_value(&tx.blob_versioned_hashes).unwrap(),
+            );
+        }
         crate::Transaction {
             from: tx.from,
             to: tx.to,
@@ -311,6 +404,7 @@ impl From<&Transaction> for crate::Transaction {
             r: tx.r,
             s: tx.s,
             hash: tx.hash,
+            other,
             ..Default::default()
         }
     }
@@ -330,10 +424,12 @@ impl From<&crate::Transaction> for Transaction {
             gas_fee_cap: tx.max_fee_per_gas,
             call_data: tx.input.clone(),
             access_list: tx.access_list.clone(),
+            max_fee_per_blob_gas: get_max_fee_per_blob_gas(tx),
+            blob_versioned_hashes: get_blob_versioned_hashes(tx),
             v: tx.v.as_u64(),
             r: tx.r,
             s: tx.s,
-            rlp_bytes: tx.rlp().to_vec(),
+            rlp_bytes: get_rlp_signed(tx),
             rlp_unsigned_bytes: get_rlp_unsigned(tx),
             hash: tx.hash,
         }
@@ -398,6 +494,8 @@ pub struct GethData {
     pub geth_traces: Vec<GethExecTrace>,
     /// Accounts
     pub accounts: Vec<Account>,
+    /// Hardfork schedule the block was traced with
+    pub chain_spec: ChainSpec,
     /// block trace
     #[cfg(feature = "scroll")]
     pub block_trace: BlockTrace,
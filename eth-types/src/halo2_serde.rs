@@ -0,0 +1,127 @@
+//! Serde helpers for halo2 field elements and curve points, which don't
+//! implement serde themselves. Values are (de)serialized as the hex string of
+//! their canonical byte representation, and are meant to be used as
+//! `#[serde(with = "eth_types::halo2_serde::field")]`.
+
+use ethers_core::types::Bytes;
+use serde::de::Error;
+
+fn repr_from_bytes<R: Default + AsMut<[u8]>, E: Error>(bytes: &[u8]) -> Result<R, E> {
+    let mut repr = R::default();
+    if repr.as_mut().len() != bytes.len() {
+        return Err(E::invalid_length(
+            bytes.len(),
+            &format!("{} bytes", repr.as_mut().len()).as_str(),
+        ));
+    }
+    repr.as_mut().copy_from_slice(bytes);
+    Ok(repr)
+}
+
+/// (De)serialize a prime field element.
+pub mod field {
+    use super::{repr_from_bytes, Bytes};
+    use halo2_proofs::halo2curves::ff::PrimeField;
+    use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+    /// Serialize a field element as the hex string of its repr.
+    pub fn serialize<F: PrimeField, S: Serializer>(
+        value: &F,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        Bytes::from(value.to_repr().as_ref().to_vec()).serialize(serializer)
+    }
+
+    /// Deserialize a field element from the hex string of its repr.
+    pub fn deserialize<'de, F: PrimeField, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<F, D::Error> {
+        let bytes = Bytes::deserialize(deserializer)?;
+        let repr = repr_from_bytes::<F::Repr, D::Error>(&bytes)?;
+        Option::from(F::from_repr(repr)).ok_or_else(|| D::Error::custom("invalid field element"))
+    }
+}
+
+/// (De)serialize a curve point.
+pub mod point {
+    use super::{repr_from_bytes, Bytes};
+    use halo2_proofs::halo2curves::group::GroupEncoding;
+    use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+    /// Serialize a curve point as the hex string of its compressed encoding.
+    pub fn serialize<G: GroupEncoding, S: Serializer>(
+        value: &G,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        Bytes::from(value.to_bytes().as_ref().to_vec()).serialize(serializer)
+    }
+
+    /// Deserialize a curve point from the hex string of its compressed
+    /// encoding.
+    pub fn deserialize<'de, G: GroupEncoding, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<G, D::Error> {
+        let bytes = Bytes::deserialize(deserializer)?;
+        let repr = repr_from_bytes::<G::Repr, D::Error>(&bytes)?;
+        Option::from(G::from_bytes(&repr)).ok_or_else(|| D::Error::custom("invalid curve point"))
+    }
+}
+
+/// (De)serialize an optional curve point.
+pub mod option_point {
+    use halo2_proofs::halo2curves::group::GroupEncoding;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(bound = "")]
+    struct Point<G: GroupEncoding>(#[serde(with = "super::point")] G);
+
+    /// Serialize an optional curve point, `None` being `null`.
+    pub fn serialize<G: GroupEncoding + Copy, S: Serializer>(
+        value: &Option<G>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        value.map(Point).serialize(serializer)
+    }
+
+    /// Deserialize an optional curve point, `null` being `None`.
+    pub fn deserialize<'de, G: GroupEncoding, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<G>, D::Error> {
+        Ok(Option::<Point<G>>::deserialize(deserializer)?.map(|point| point.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::halo2curves::{
+        bn256::{Fr, G1Affine},
+        group::prime::PrimeCurveAffine,
+    };
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Values {
+        #[serde(with = "super::field")]
+        scalar: Fr,
+        #[serde(with = "super::point")]
+        point: G1Affine,
+        #[serde(with = "super::option_point")]
+        some_point: Option<G1Affine>,
+        #[serde(with = "super::option_point")]
+        none_point: Option<G1Affine>,
+    }
+
+    #[test]
+    fn halo2_serde_roundtrip() {
+        let values = Values {
+            scalar: Fr::from(0xdeadbeefu64),
+            point: G1Affine::generator(),
+            some_point: Some(G1Affine::identity()),
+            none_point: None,
+        };
+        let json = serde_json::to_string(&values).unwrap();
+        assert_eq!(serde_json::from_str::<Values>(&json).unwrap(), values);
+        assert!(serde_json::from_str::<Values>(&json.replace("\"0x", "\"0x00")).is_err());
+    }
+}
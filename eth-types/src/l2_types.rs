@@ -2,12 +2,16 @@
 
 use crate::{
     evm_types::{Gas, GasCost, OpcodeId, ProgramCounter},
-    Block, GethCallTrace, GethExecError, GethExecStep, GethExecTrace, GethPrestateTrace, Hash,
-    Transaction, Word, H256,
+    geth_types::{BLOB_VERSIONED_HASHES_KEY, MAX_FEE_PER_BLOB_GAS_KEY},
+    Block, Error, GethCallTrace, GethExecError, GethExecStep, GethExecTrace, GethPrestateTrace,
+    Hash, ToBigEndian, Transaction, Word, H256,
+};
+use ethers_core::{
+    types::{Address, Bytes, OtherFields, U256, U64},
+    utils::keccak256,
 };
-use ethers_core::types::{Address, Bytes, U256, U64};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 
 #[cfg(feature = "enable-memory")]
 use crate::evm_types::Memory;
@@ -111,6 +115,20 @@ pub struct TransactionTrace {
     pub r: U256,
     /// signature s
     pub s: U256,
+    /// max fee per blob gas (EIP-4844 only)
+    #[serde(
+        default,
+        rename = "maxFeePerBlobGas",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub max_fee_per_blob_gas: Option<U256>,
+    /// blob versioned hashes (EIP-4844 only)
+    #[serde(
+        default,
+        rename = "blobVersionedHashes",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub blob_versioned_hashes: Vec<H256>,
 }
 
 impl TransactionTrace {
@@ -121,6 +139,19 @@ impl TransactionTrace {
         block_number: Option<U64>,
         transaction_index: Option<U64>,
     ) -> Transaction {
+        let mut other = OtherFields::default();
+        if let Some(max_fee_per_blob_gas) = self.max_fee_per_blob_gas {
+            other.insert(
+                MAX_FEE_PER_BLOB_GAS_KEY.to_string(),
+                serde_json::to_value(max_fee_per_blob_gas).unwrap(),
+            );
+        }
+        if !self.blob_versioned_hashes.is_empty() {
+            other.insert(
+                BLOB_VERSIONED_HASHES_KEY.to_string(),
+                serde_json::to_value(&self.blob_versioned_hashes).unwrap(),
+            );
+        }
         Transaction {
             hash: self.tx_hash,
             nonce: U256::from(self.nonce),
@@ -141,7 +172,7 @@ impl TransactionTrace {
             max_priority_fee_per_gas: None,
             max_fee_per_gas: None,
             chain_id: Some(self.chain_id),
-            other: Default::default(),
+            other,
         }
     }
 }
@@ -251,6 +282,61 @@ pub struct ExecStep {
     pub extra_data: Option<ExtraData>,
 }
 
+impl ExecStep {
+    /// Creates a step without stack, memory, storage nor extra data.
+    pub fn new(
+        pc: u64,
+        op: OpcodeId,
+        gas: u64,
+        gas_cost: u64,
+        refund: u64,
+        depth: isize,
+        error: Option<GethExecError>,
+    ) -> Self {
+        Self {
+            pc,
+            op,
+            gas,
+            gas_cost,
+            refund,
+            depth,
+            error,
+            #[cfg(feature = "enable-stack")]
+            stack: None,
+            #[cfg(feature = "enable-memory")]
+            memory: None,
+            #[cfg(feature = "enable-storage")]
+            storage: None,
+            extra_data: None,
+        }
+    }
+
+    /// Sets the stack, memory and storage captured at the step. Like in
+    /// deserialized traces, each one is only kept with the matching
+    /// `enable-stack`, `enable-memory` and `enable-storage` feature.
+    #[allow(unused_variables, unused_mut)]
+    pub fn with_snapshots(
+        mut self,
+        stack: Option<Vec<Word>>,
+        memory: Option<Vec<Word>>,
+        storage: Option<HashMap<Word, Word>>,
+    ) -> Self {
+        #[cfg(feature = "enable-stack")]
+        {
+            self.stack = stack;
+        }
+        #[cfg(feature = "enable-memory")]
+        {
+            self.memory = memory;
+        }
+        #[cfg(feature = "enable-storage")]
+        {
+            self.storage = storage;
+        }
+        self
+    }
+}
+
 impl From<ExecStep> for GethExecStep {
     fn from(e: ExecStep) -> Self {
         GethExecStep {
@@ -317,3 +403,136 @@ pub struct StorageProofWrapper {
     pub key: Option<U256>,
     pub value: Option<U256>,
 }
+
+/// The range of the L1 message queue popped by a chunk, together with the bitmap
+/// of the popped messages that are skipped, i.e. not included as txs of the chunk.
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq, Eq)]
+pub struct L1QueueRange {
+    /// Queue index of the first message popped by the chunk
+    pub start_queue_index: u64,
+    /// Queue index following the last message popped by the chunk
+    pub end_queue_index: u64,
+    /// Bit `i % 256` of the `i / 256`-th word is set if the message with queue
+    /// index `start_queue_index + i` is skipped
+    pub skipped_bitmap: Vec<Word>,
+}
+
+impl L1QueueRange {
+    /// Build the range popped by a chunk starting at `start_queue_index`, from
+    /// the queue indices of the L1 messages included in the chunk. At most
+    /// `max_num_popped` messages can be popped, skipped ones included.
+    pub fn new(
+        start_queue_index: u64,
+        included: impl IntoIterator<Item = u64>,
+        max_num_popped: u64,
+    ) -> Result<Self, Error> {
+        let included = included.into_iter().collect::<BTreeSet<_>>();
+        if let Some(&queue_index) = included.first().filter(|&&first| first < start_queue_index) {
+            return Err(Error::L1QueueIndexBeforeStart(queue_index));
+        }
+        let end_queue_index = included
+            .last()
+            .map_or(start_queue_index, |max_queue_index| max_queue_index + 1);
+
+        let num_popped = end_queue_index - start_queue_index;
+        if num_popped > max_num_popped {
+            return Err(Error::TooManyL1MsgsPopped(num_popped));
+        }
+        let num_popped = num_popped as usize;
+        let mut skipped_bitmap = vec![Word::zero(); (num_popped + 255) / 256];
+        for i in 0..num_popped {
+            if !included.contains(&(start_queue_index + i as u64)) {
+                skipped_bitmap[i / 256] |= Word::one() << (i % 256);
+            }
+        }
+
+        Ok(Self {
+            start_queue_index,
+            end_queue_index,
+            skipped_bitmap,
+        })
+    }
+
+    /// Number of messages popped from the queue, skipped ones included
+    pub fn num_popped(&self) -> u64 {
+        self.end_queue_index.saturating_sub(self.start_queue_index)
+    }
+
+    /// Whether the `i`-th popped message is skipped
+    pub fn is_skipped(&self, i: usize) -> bool {
+        self.skipped_bitmap
+            .get(i / 256)
+            .is_some_and(|word| word.bit(i % 256))
+    }
+
+    /// The big-endian words of the skipped bitmap, as the
+    /// `skippedL1MessageBitmap` of the ScrollChain contract, so that the bit of
+    /// the `i`-th popped message is bit `i % 8` of byte `31 - i % 256 / 8` of
+    /// the `i / 256`-th word
+    pub fn skipped_bitmap_bytes(&self) -> Vec<u8> {
+        self.skipped_bitmap
+            .iter()
+            .flat_map(|word| word.to_be_bytes())
+            .collect()
+    }
+
+    /// Keccak of the skipped bitmap bytes
+    pub fn skipped_bitmap_hash(&self) -> H256 {
+        H256(keccak256(self.skipped_bitmap_bytes()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn l1_queue_range_skipped_bitmap() {
+        let range = L1QueueRange::new(10, [11, 13], 256).unwrap();
+        assert_eq!(range.end_queue_index, 14);
+        assert_eq!(range.num_popped(), 4);
+        // queue indices 10 and 12 are skipped
+        assert_eq!(range.skipped_bitmap, vec![Word::from(0b0101)]);
+        assert!(range.is_skipped(0) && !range.is_skipped(1) && range.is_skipped(2));
+        // a big-endian uint256 word, as in the skippedL1MessageBitmap of ScrollChain
+        let bytes = range.skipped_bitmap_bytes();
+        assert_eq!(bytes.len(), 32);
+        assert_eq!(bytes[31], 0b0101);
+        assert!(bytes[..31].iter().all(|&byte| byte == 0));
+
+        let range = L1QueueRange::new(0, [300], 512).unwrap();
+        assert_eq!(range.skipped_bitmap.len(), 2);
+        assert_eq!(range.skipped_bitmap[0], Word::MAX);
+        assert_eq!(range.skipped_bitmap[1], (Word::one() << 44) - 1);
+        let bytes = range.skipped_bitmap_bytes();
+        assert_eq!(bytes[32..58], [0; 26]);
+        assert_eq!(bytes[58..], [0x0f, 0xff, 0xff, 0xff, 0xff, 0xff]);
+
+        let empty = L1QueueRange::new(10, [], 0).unwrap();
+        assert_eq!(empty.end_queue_index, 10);
+        assert!(empty.skipped_bitmap.is_empty());
+        assert_eq!(empty.skipped_bitmap_hash(), H256(keccak256([])));
+    }
+
+    #[test]
+    fn l1_queue_range_invalid() {
+        assert!(matches!(
+            L1QueueRange::new(10, [9, 11], 256),
+            Err(Error::L1QueueIndexBeforeStart(9))
+        ));
+        // a gap of skipped msgs larger than the bound
+        assert!(matches!(
+            L1QueueRange::new(10, [10, 300], 256),
+            Err(Error::TooManyL1MsgsPopped(291))
+        ));
+        assert!(L1QueueRange::new(10, [10, 265], 256).is_ok());
+
+        // a range tampered with after being built doesn't underflow
+        let range = L1QueueRange {
+            start_queue_index: 10,
+            end_queue_index: 9,
+            skipped_bitmap: vec![],
+        };
+        assert_eq!(range.num_popped(), 0);
+    }
+}
@@ -23,8 +23,10 @@ pub mod macros;
 pub mod error;
 #[macro_use]
 pub mod bytecode;
+pub mod chain_spec;
 pub mod evm_types;
 pub mod geth_types;
+pub mod halo2_serde;
 pub mod l2_types;
 pub mod sign_types;
 
@@ -746,18 +748,25 @@ pub struct GethPrestateTrace {
 /// using callTracer
 #[derive(Deserialize, Serialize, Clone, Debug, Eq, PartialEq)]
 pub struct GethCallTrace {
+    /// sub calls
     #[serde(default)]
-    calls: Vec<GethCallTrace>,
-    error: Option<String>,
-    from: Address,
+    pub calls: Vec<GethCallTrace>,
+    /// error of the call
+    pub error: Option<String>,
+    /// caller
+    pub from: Address,
     // gas: U256,
+    /// gas used
     #[serde(rename = "gasUsed")]
-    gas_used: U256,
+    pub gas_used: U256,
     // input: Bytes,
-    output: Option<Bytes>,
-    to: Option<Address>,
+    /// output of the call
+    pub output: Option<Bytes>,
+    /// callee
+    pub to: Option<Address>,
+    /// call type, the name of the call opcode
     #[serde(rename = "type")]
-    call_type: String,
+    pub call_type: String,
     // value: U256,
 }
 
@@ -3,7 +3,7 @@
 use crate::{
     address,
     geth_types::{Transaction, TxType},
-    word, Error, Word, H256,
+    halo2_serde, word, Error, Word, H256,
 };
 use ethers_core::{
     k256::{
@@ -28,6 +28,7 @@ use halo2_proofs::{
     },
 };
 use num_bigint::BigUint;
+use serde::{Deserialize, Serialize};
 use sha3::digest::generic_array::GenericArray;
 use std::sync::LazyLock;
 use subtle::CtOption;
@@ -54,19 +55,48 @@ pub fn sign(randomness: Fq, sk: Fq, msg_hash: Fq) -> (Fq, Fq, u8) {
 
 /// Signature data required by the SignVerify Chip as input to verify a
 /// signature.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SignData {
     /// Secp256k1 signature point (r, s, v)
     /// v must be 0 or 1
+    #[serde(with = "serde_signature")]
     pub signature: (Fq, Fq, u8),
     /// Secp256k1 public key
+    #[serde(with = "halo2_serde::point")]
     pub pk: Secp256k1Affine,
     /// Message being hashed before signing.
     pub msg: Bytes,
     /// Hash of the message that is being signed
+    #[serde(with = "halo2_serde::field")]
     pub msg_hash: Fq,
 }
 
+mod serde_signature {
+    use super::{halo2_serde, Fq};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize, Deserialize)]
+    struct Signature(
+        #[serde(with = "halo2_serde::field")] Fq,
+        #[serde(with = "halo2_serde::field")] Fq,
+        u8,
+    );
+
+    pub(super) fn serialize<S: Serializer>(
+        signature: &(Fq, Fq, u8),
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        Signature(signature.0, signature.1, signature.2).serialize(serializer)
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<(Fq, Fq, u8), D::Error> {
+        let Signature(r, s, v) = Signature::deserialize(deserializer)?;
+        Ok((r, s, v))
+    }
+}
+
 /// Generate a dummy pre-eip155 tx in which
 /// (nonce=0, gas=0, gas_price=0, to=0, value=0, data="")
 /// using the dummy private key = 1
@@ -1,14 +1,39 @@
 //! This module generates traces by connecting to an external tracer
+//!
+//! Two backends are available:
+//! - `geth-tracer` (default): the go tracers of `geth-utils`, called through cgo.
+//! - `native-tracer`: an in-process tracer built on revm, which doesn't need a Go toolchain. It
+//!   takes precedence when both features are enabled. With `scroll`, its storage traces are read
+//!   from an in-memory zktrie of the state.
+//!
+//! `mock`, `bus-mapping`, `zkevm-circuits`, `prover` and `testool` forward both features, with
+//! `geth-tracer` in their defaults.
+
+#[cfg(not(any(feature = "geth-tracer", feature = "native-tracer")))]
+compile_error!("one of the `geth-tracer` and `native-tracer` features must be enabled");
+
+#[cfg(feature = "native-tracer")]
+mod native;
 
 #[cfg(feature = "scroll")]
 use eth_types::l2_types::BlockTrace;
 use eth_types::{
+    chain_spec::ChainSpec,
     geth_types::{Account, BlockConstants, Transaction},
     Address, Error, GethExecTrace, Word,
 };
+#[cfg(feature = "geth-tracer")]
+use serde::de::DeserializeOwned;
 use serde::Serialize;
 use std::collections::BTreeMap;
 
+/// Traces of a block, as given by the tracer backends.
+#[cfg(not(feature = "scroll"))]
+pub(crate) type TracerOutput = Vec<GethExecTrace>;
+/// Traces of a block, as given by the tracer backends.
+#[cfg(feature = "scroll")]
+pub(crate) type TracerOutput = BlockTrace;
+
 /// Configuration structure for `geth_utlis::trace`
 #[derive(Debug, Default, Clone, Serialize)]
 pub struct TraceConfig {
@@ -83,6 +108,8 @@ pub struct ChainConfig {
     /// Shanghai switch time (nil = no fork, 0 = already on shanghai)
     /// Scroll EVM use the name `ShanghaiBlock` instead
     pub shanghai_time: Option<u64>,
+    /// Cancun switch time (nil = no fork, 0 = already on cancun)
+    pub cancun_time: Option<u64>,
     /// TerminalTotalDifficulty is the amount of total difficulty reached by
     /// the network that triggers the consensus upgrade.
     pub terminal_total_difficulty: Option<u64>,
@@ -98,33 +125,140 @@ impl ChainConfig {
         Self {
             archimedes_block: None,
             shanghai_time: Some(0),
+            cancun_time: None,
             terminal_total_difficulty: Some(0),
             terminal_total_difficulty_passed: true,
         }
     }
+
+    /// Create a chain config for Cancun fork.
+    pub fn cancun() -> Self {
+        Self {
+            cancun_time: Some(0),
+            ..Self::shanghai()
+        }
+    }
+
+    /// Create the chain config of `block` under the hardfork schedule of
+    /// `chain_spec`, or None if it's on London, which is the default of the
+    /// tracer.
+    ///
+    /// The tracer schedules forks by timestamp, so the fork active at `block`
+    /// is resolved here and set from genesis. Fails if `chain_spec` isn't on
+    /// the chain the tracer was built for.
+    pub fn from_chain_spec(
+        chain_spec: &ChainSpec,
+        block: &BlockConstants,
+    ) -> Result<Option<Self>, Error> {
+        if chain_spec.scroll != cfg!(feature = "scroll") {
+            return Err(Error::TracingError(format!(
+                "the tracer can't trace a chain with scroll = {}",
+                chain_spec.scroll
+            )));
+        }
+        let timestamp = u64::try_from(block.timestamp).map_err(|_| {
+            Error::TracingError(format!("block timestamp {} overflows", block.timestamp))
+        })?;
+        let rules = chain_spec.rules(block.number.as_u64(), timestamp);
+        Ok(if rules.is_cancun() {
+            Some(Self::cancun())
+        } else {
+            rules.is_shanghai().then(Self::shanghai)
+        })
+    }
 }
 
-/// Creates a trace for the specified config
-#[cfg(not(feature = "scroll"))]
-pub fn trace(config: &TraceConfig) -> Result<Vec<GethExecTrace>, Error> {
+/// Runs the selected tracer backend.
+#[cfg(feature = "native-tracer")]
+fn create_trace(config: &TraceConfig) -> Result<TracerOutput, Error> {
+    let trace_config = &serde_json::to_string_pretty(&config).unwrap();
+    log::trace!("trace config: {}", trace_config);
+    native::trace(config)
+}
+
+/// Runs the selected tracer backend.
+#[cfg(all(feature = "geth-tracer", not(feature = "native-tracer")))]
+fn create_trace(config: &TraceConfig) -> Result<TracerOutput, Error> {
     let trace_config = &serde_json::to_string_pretty(&config).unwrap();
     log::trace!("trace config: {}", trace_config);
-    // Get the trace
-    let trace_string = geth_utils::trace(trace_config).map_err(|error| match error {
+    parse_trace(&geth_trace(trace_config)?)
+}
+
+/// Runs the selected tracer backend on the consecutive blocks of a chunk.
+#[cfg(feature = "native-tracer")]
+fn create_trace_chunk(configs: &[TraceConfig]) -> Result<Vec<TracerOutput>, Error> {
+    let trace_configs = &serde_json::to_string_pretty(&configs).unwrap();
+    log::trace!("trace configs: {}", trace_configs);
+    native::trace_chunk(configs)
+}
+
+/// Runs the selected tracer backend on the consecutive blocks of a chunk.
+#[cfg(all(feature = "geth-tracer", not(feature = "native-tracer")))]
+fn create_trace_chunk(configs: &[TraceConfig]) -> Result<Vec<TracerOutput>, Error> {
+    let trace_configs = &serde_json::to_string_pretty(&configs).unwrap();
+    log::trace!("trace configs: {}", trace_configs);
+    let traces = geth_utils::trace_chunk(trace_configs).map_err(|error| match error {
         geth_utils::Error::TracingError(error) => Error::TracingError(error),
     })?;
+    parse_trace(&traces)
+}
 
+/// Runs the Go tracer, which returns the trace as a JSON string.
+#[cfg(feature = "geth-tracer")]
+fn geth_trace(trace_config: &str) -> Result<String, Error> {
+    geth_utils::trace(trace_config).map_err(|error| match error {
+        geth_utils::Error::TracingError(error) => Error::TracingError(error),
+    })
+}
+
+/// Creates the traces of `config` with the Go tracer and with the native
+/// one, in this order, to check that the backends agree.
+#[cfg(all(feature = "geth-tracer", feature = "native-tracer"))]
+fn trace_with_both_backends(config: &TraceConfig) -> Result<[TracerOutput; 2], Error> {
+    let trace_config = &serde_json::to_string_pretty(&config).unwrap();
+    Ok([
+        parse_trace(&geth_trace(trace_config)?)?,
+        native::trace(config)?,
+    ])
+}
+
+#[cfg(feature = "geth-tracer")]
+fn parse_trace<T: DeserializeOwned>(trace_string: &str) -> Result<T, Error> {
     log::trace!("trace: {}", trace_string);
 
-    let mut deserializer = serde_json::Deserializer::from_str(&trace_string);
+    let mut deserializer = serde_json::Deserializer::from_str(trace_string);
     deserializer.disable_recursion_limit();
     let deserializer = serde_stacker::Deserializer::new(&mut deserializer);
     serde::Deserialize::deserialize(deserializer).map_err(Error::SerdeError)
 }
 
-/// Creates a l2-trace for the specified config
+/// Creates a trace for the specified config
+#[cfg(not(feature = "scroll"))]
+pub fn trace(config: &TraceConfig) -> Result<Vec<GethExecTrace>, Error> {
+    create_trace(config)
+}
+
+/// Creates the traces of `config` with the Go tracer and with the native
+/// one, in this order.
+#[cfg(all(
+    feature = "geth-tracer",
+    feature = "native-tracer",
+    not(feature = "scroll")
+))]
+pub fn trace_both(config: &TraceConfig) -> Result<[Vec<GethExecTrace>; 2], Error> {
+    trace_with_both_backends(config)
+}
+
+/// Creates the traces of the consecutive blocks of a chunk, each block being
+/// executed on top of the state left by the previous one. The accounts of a
+/// config are written to the state before its block is executed.
+#[cfg(not(feature = "scroll"))]
+pub fn trace_chunk(configs: &[TraceConfig]) -> Result<Vec<Vec<GethExecTrace>>, Error> {
+    create_trace_chunk(configs)
+}
+
 #[cfg(feature = "scroll")]
-pub fn l2trace(config: &TraceConfig) -> Result<BlockTrace, Error> {
+fn l2_config(config: &TraceConfig) -> TraceConfig {
     let mut l2_config = config.clone();
     if let Some(chain_config) = l2_config.chain_config.as_mut() {
         chain_config.archimedes_block = Some(0);
@@ -132,23 +266,33 @@ pub fn l2trace(config: &TraceConfig) -> Result<BlockTrace, Error> {
         l2_config.chain_config = Some(ChainConfig {
             archimedes_block: Some(0),
             shanghai_time: None,
+            cancun_time: None,
             terminal_total_difficulty: None,
             terminal_total_difficulty_passed: false,
         });
     }
-    let trace_config = &serde_json::to_string_pretty(&l2_config).unwrap();
-    log::trace!("trace config: {}", trace_config);
-    // Get the trace
-    let trace_string = geth_utils::trace(trace_config).map_err(|error| match error {
-        geth_utils::Error::TracingError(error) => Error::TracingError(error),
-    })?;
+    l2_config
+}
 
-    log::trace!("trace: {}", trace_string);
+/// Creates a l2-trace for the specified config
+#[cfg(feature = "scroll")]
+pub fn l2trace(config: &TraceConfig) -> Result<BlockTrace, Error> {
+    create_trace(&l2_config(config))
+}
 
-    let mut deserializer = serde_json::Deserializer::from_str(&trace_string);
-    deserializer.disable_recursion_limit();
-    let deserializer = serde_stacker::Deserializer::new(&mut deserializer);
-    serde::Deserialize::deserialize(deserializer).map_err(Error::SerdeError)
+/// Creates the l2-traces of `config` with the Go tracer and with the native
+/// one, in this order.
+#[cfg(all(feature = "geth-tracer", feature = "native-tracer", feature = "scroll"))]
+pub fn l2trace_both(config: &TraceConfig) -> Result<[BlockTrace; 2], Error> {
+    trace_with_both_backends(&l2_config(config))
+}
+
+/// Creates the l2-traces of the consecutive blocks of a chunk, see
+/// [`trace_chunk`].
+#[cfg(feature = "scroll")]
+pub fn l2trace_chunk(configs: &[TraceConfig]) -> Result<Vec<BlockTrace>, Error> {
+    let l2_configs = configs.iter().map(l2_config).collect::<Vec<_>>();
+    create_trace_chunk(&l2_configs)
 }
 
 #[cfg(feature = "scroll")]
@@ -161,3 +305,57 @@ pub fn trace(config: &TraceConfig) -> Result<Vec<GethExecTrace>, Error> {
         .map(From::from)
         .collect::<Vec<_>>())
 }
+
+/// Creates the traces of the consecutive blocks of a chunk, each block being
+/// executed on top of the state left by the previous one. The accounts of a
+/// config are written to the state before its block is executed.
+#[cfg(feature = "scroll")]
+pub fn trace_chunk(configs: &[TraceConfig]) -> Result<Vec<Vec<GethExecTrace>>, Error> {
+    Ok(l2trace_chunk(configs)?
+        .into_iter()
+        .map(|block_trace| {
+            block_trace
+                .execution_results
+                .into_iter()
+                .map(From::from)
+                .collect::<Vec<_>>()
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eth_types::chain_spec::{ForkCondition, Hardfork};
+
+    #[test]
+    fn chain_config_from_chain_spec() {
+        let spec = ChainSpec::london()
+            .with_scroll(cfg!(feature = "scroll"))
+            .with_fork(Hardfork::Shanghai, ForkCondition::Block(10));
+        let block = |number: u64| BlockConstants {
+            number: number.into(),
+            ..Default::default()
+        };
+        assert!(ChainConfig::from_chain_spec(&spec, &block(9))
+            .unwrap()
+            .is_none());
+        let config = ChainConfig::from_chain_spec(&spec, &block(10))
+            .unwrap()
+            .unwrap();
+        assert_eq!(config.shanghai_time, Some(0));
+        assert_eq!(config.cancun_time, None);
+
+        let spec = spec.with_fork(Hardfork::Cancun, ForkCondition::Block(20));
+        let config = ChainConfig::from_chain_spec(&spec, &block(20))
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            (config.shanghai_time, config.cancun_time),
+            (Some(0), Some(0))
+        );
+
+        let other_chain = spec.with_scroll(!cfg!(feature = "scroll"));
+        assert!(ChainConfig::from_chain_spec(&other_chain, &block(10)).is_err());
+    }
+}
@@ -0,0 +1,688 @@
+//! Scroll flavour of the native tracer, producing the same `BlockTrace` as
+//! `l2geth/trace.go`.
+//!
+//! The storage traces of the block and of its transactions are read from an
+//! in-memory [`ZkTrie`] of the state, so their roots and proofs are the ones
+//! l2geth would give for the same state.
+
+use super::{
+    address, apply_error, b256, call_trace, capture_config, check_txs_gas_limit, eth_address,
+    exec_step, insert_accounts, insert_block_hashes, load_code,
+    logger::TxTracer,
+    prestate, return_value, transact, tx_env, u256, word,
+    zktrie::{account_key, Leaf, ZkTrie},
+};
+use crate::TraceConfig;
+use eth_types::{
+    evm_types::{block_utils::calculate_block_hash, gas_utils::tx_data_gas_cost},
+    geth_types::{Transaction, TxType},
+    l2_types::{
+        self, AccountProofWrapper, AccountTrieProofs, BlockTrace, EthBlock, ExtraData,
+        StorageTrace, StorageTrieProofs, TransactionTrace,
+    },
+    Error, Word, H256, KECCAK_CODE_HASH_EMPTY, POSEIDON_CODE_HASH_EMPTY, U64,
+};
+use halo2_proofs::halo2curves::{bn256::Fr, group::ff::PrimeField};
+use hash_circuit::hash::{Hashable, MessageHashable, HASHABLE_DOMAIN_SPEC};
+use revm::{
+    db::{AccountState, CacheDB, EmptyDB},
+    handler::register::EvmHandler,
+    interpreter::{
+        instructions::control,
+        opcode::{self, InstructionTables},
+    },
+    precompile::{Precompile, PrecompileResult, Precompiles},
+    primitives::{
+        db::Database, keccak256, Address, Bytes, ExecutionResult, PrecompileError, SpecId, B256,
+        U256,
+    },
+    ContextPrecompile, DatabaseCommit, DatabaseRef,
+};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    sync::Arc,
+};
+
+/// L1GasPriceOracle predeployed address
+const L1_GAS_PRICE_ORACLE: Address = Address::new([
+    0x53, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x02,
+]);
+/// Slots of the l1 base fee, overhead and scalar in L1GasPriceOracle
+const L1_FEE_SLOTS: [u64; 3] = [1, 2, 3];
+const TX_L1_COMMIT_EXTRA_COST: u64 = 64;
+const TX_L1_FEE_PRECISION: u128 = 1_000_000_000;
+const L1_MSG_TX_TYPE: u8 = 0x7e;
+
+const POSEIDON_HASH_BYTES_IN_FIELD: usize = 31;
+
+/// Traces the block of `config` on top of the state of `db`, the same as
+/// `geth_utils::trace` gives with the `scroll` feature.
+pub(crate) fn trace_block(
+    db: &mut CacheDB<EmptyDB>,
+    config: &TraceConfig,
+) -> Result<BlockTrace, Error> {
+    check_txs_gas_limit(config)?;
+
+    insert_accounts(db, config);
+    // BLOCKHASH of scroll doesn't depend on the history
+    insert_block_hashes(db, config, |n| {
+        Some(b256(
+            &calculate_block_hash(config.chain_id, Word::from(n)).1,
+        ))
+    });
+
+    let coinbase = address(&config.block_constants.coinbase);
+    let mut code_hashes = HashMap::new();
+    let trie_before = StateTrie::new(db, &mut code_hashes);
+    let mut tx_trie_before = trie_before.clone();
+    // every tx reads the l1 fee and pays the coinbase
+    let mut always_touched = Touched::new();
+    always_touched.entry(coinbase).or_default();
+    always_touched
+        .entry(L1_GAS_PRICE_ORACLE)
+        .or_default()
+        .extend(L1_FEE_SLOTS.map(U256::from));
+    let mut touched = always_touched.clone();
+    let mut tx_storage_traces = Vec::with_capacity(config.transactions.len());
+
+    let mut transactions = Vec::with_capacity(config.transactions.len());
+    let mut execution_results = Vec::with_capacity(config.transactions.len());
+    for (idx, tx) in config.transactions.iter().enumerate() {
+        // without a signature, a tx can only be handled as a l1 message
+        let is_l1_msg = tx.tx_type.is_l1_msg() || tx.r.is_zero();
+        if !is_l1_msg && tx.tx_type != TxType::Eip155 {
+            return Err(Error::TracingError(format!(
+                "not implement tx type [{:?}]",
+                tx.tx_type
+            )));
+        }
+
+        let mut env = tx_env(config, tx);
+        // DIFFICULTY is always 0 on scroll
+        env.block.prevrandao = Some(B256::ZERO);
+        let gas_price = if is_l1_msg {
+            Word::zero()
+        } else {
+            tx.gas_price.unwrap_or_default()
+        };
+        env.tx.gas_price = u256(&gas_price);
+        env.tx.gas_priority_fee = None;
+        env.tx.access_list.clear();
+        env.cfg.disable_base_fee = gas_price.is_zero();
+        if is_l1_msg {
+            // the nonce of a l1 message is its queue index
+            env.tx.nonce = None;
+        }
+
+        let sender = env.tx.caller;
+        let receiver = tx.to.as_ref().map(address);
+        let from = account_wrapper(db, sender, &mut code_hashes);
+        let to = receiver.map(|receiver| account_wrapper(db, receiver, &mut code_hashes));
+        let byte_code = receiver.map(|receiver| {
+            let info = db
+                .basic_ref(receiver)
+                .expect("in-memory db is infallible")
+                .unwrap_or_default();
+            load_code(db, &info)
+        });
+        let code_hash = to.as_ref().and_then(|to| to.poseidon_code_hash);
+
+        let l1_fee = if is_l1_msg { 0 } else { l1_fee(db, tx) };
+        if l1_fee > 0 {
+            let account = db.load_account(sender).expect("in-memory db is infallible");
+            if account.info.balance < U256::from(l1_fee) {
+                return Err(apply_error(
+                    idx,
+                    format!("insufficient funds for l1 fee: address {sender}"),
+                ));
+            }
+            account.info.balance -= U256::from(l1_fee);
+        }
+
+        let mut tracer = TxTracer::new(capture_config(&config.logger_config, true));
+        let result = transact(db, env, SpecId::SHANGHAI, &mut tracer)
+            .map_err(|err| apply_error(idx, err))?;
+        let mut prestate = prestate(db, &result.state);
+        if l1_fee > 0 {
+            // the l1 fee is charged as part of the tx
+            if let Some(balance) = prestate
+                .get_mut(&eth_address(sender))
+                .and_then(|account| account.balance.as_mut())
+            {
+                *balance += Word::from(l1_fee);
+            }
+        }
+        let mut tx_touched = always_touched.clone();
+        tx_touched.entry(sender).or_default();
+        if let Some(receiver) = receiver {
+            tx_touched.entry(receiver).or_default();
+        }
+        for (addr, account) in &result.state {
+            tx_touched
+                .entry(*addr)
+                .or_default()
+                .extend(account.storage.keys().copied());
+        }
+        db.commit(result.state);
+        if l1_fee > 0 {
+            add_balance(db, coinbase, U256::from(l1_fee));
+        }
+        let tx_trie_after = StateTrie::new(db, &mut code_hashes);
+        tx_storage_traces.push(tx_trie_before.storage_trace(&tx_trie_after, &tx_touched));
+        tx_trie_before = tx_trie_after;
+        for (addr, slots) in tx_touched {
+            touched.entry(addr).or_default().extend(slots);
+        }
+
+        let created = tracer
+            .root
+            .as_ref()
+            .filter(|_| receiver.is_none())
+            .and_then(|root| root.to);
+        let account_after = [Some(sender), receiver.or(created), Some(coinbase)]
+            .into_iter()
+            .flatten()
+            .map(|addr| account_wrapper(db, addr, &mut code_hashes))
+            .collect::<Vec<_>>();
+        let account_created = created.map(|created| account_wrapper(db, created, &mut code_hashes));
+        let code_hash = code_hash.or_else(|| {
+            account_created
+                .as_ref()
+                .and_then(|account| account.poseidon_code_hash)
+        });
+
+        let exec_steps = tracer
+            .logs
+            .iter()
+            .map(|log| {
+                let mut step = exec_step(log);
+                if let Some(callee) = &log.callee {
+                    let (keccak_code_hash, poseidon_code_hash) =
+                        code_hash_pair(&mut code_hashes, &callee.code);
+                    let proof = AccountProofWrapper {
+                        address: Some(eth_address(callee.address)),
+                        nonce: Some(callee.nonce),
+                        balance: Some(word(callee.balance)),
+                        keccak_code_hash: Some(keccak_code_hash),
+                        poseidon_code_hash: Some(poseidon_code_hash),
+                        storage: None,
+                    };
+                    let code = eth_types::Bytes::from(callee.code.to_vec());
+                    // the builder reads the callee at index 0 or 1, depending
+                    // on the kind of tx
+                    step.extra_data = Some(ExtraData {
+                        code_list: Some(vec![code.clone(), code]),
+                        proof_list: Some(vec![proof.clone(), proof]),
+                    });
+                }
+                step
+            })
+            .collect::<Vec<_>>();
+
+        let gas = match result.result {
+            // l1 messages don't get a refund
+            ExecutionResult::Success {
+                gas_used,
+                gas_refunded,
+                ..
+            } if is_l1_msg => gas_used + gas_refunded,
+            _ => result.result.gas_used(),
+        };
+        execution_results.push(l2_types::ExecutionResult {
+            l1_fee: Word::from(l1_fee),
+            gas,
+            failed: !result.result.is_success(),
+            return_value: return_value(&result.result),
+            from: Some(from),
+            to,
+            account_after,
+            account_created,
+            code_hash,
+            byte_code: byte_code.map(|code| format!("0x{}", hex::encode(code))),
+            exec_steps,
+            call_trace: call_trace(&tracer, &result.result)?,
+            prestate,
+        });
+        transactions.push(transaction_trace(config, tx, is_l1_msg, gas_price));
+    }
+
+    let mut storage_trace = trie_before.storage_trace(&tx_trie_before, &touched);
+    // slots written and deleted within the block are only in the traces of
+    // their txs
+    for deletion_proof in tx_storage_traces
+        .iter()
+        .flat_map(|trace| &trace.deletion_proofs)
+    {
+        if !storage_trace.deletion_proofs.contains(deletion_proof) {
+            storage_trace.deletion_proofs.push(deletion_proof.clone());
+        }
+    }
+    let block_constants = &config.block_constants;
+    let header = EthBlock {
+        number: Some(block_constants.number),
+        timestamp: block_constants.timestamp,
+        gas_limit: block_constants.gas_limit,
+        base_fee_per_gas: Some(block_constants.base_fee),
+        parent_hash: config
+            .history_hashes
+            .last()
+            .map(H256::from_uint)
+            .unwrap_or_else(|| H256::from_uint(&block_constants.difficulty)),
+        author: Some(block_constants.coinbase),
+        state_root: storage_trace.root_after,
+        ..Default::default()
+    };
+
+    Ok(BlockTrace {
+        chain_id: config.chain_id,
+        coinbase: account_wrapper(db, coinbase, &mut code_hashes),
+        header,
+        transactions,
+        execution_results,
+        storage_trace,
+        tx_storage_trace: tx_storage_traces,
+        start_l1_queue_index: config.l1_queue_index,
+    })
+}
+
+/// Applies the EVM differences of scroll: BASEFEE and SELFDESTRUCT are
+/// disabled, as well as the RIPEMD-160 and blake2f precompiles, and modexp
+/// and ecPairing only accept limited inputs.
+pub(crate) fn handle_register<EXT, DB: Database>(handler: &mut EvmHandler<'_, EXT, DB>) {
+    if let Some(InstructionTables::Plain(mut table)) = handler.take_instruction_table() {
+        table[opcode::BASEFEE as usize] = control::unknown;
+        table[opcode::SELFDESTRUCT as usize] = control::unknown;
+        handler.set_instruction_table(InstructionTables::Plain(table));
+    }
+
+    let load_precompiles = handler.pre_execution.load_precompiles.clone();
+    handler.pre_execution.load_precompiles = Arc::new(move || {
+        let mut precompiles = load_precompiles();
+        precompiles.extend([
+            (Address::with_last_byte(3), standard(disabled)),
+            (Address::with_last_byte(5), standard(modexp)),
+            (Address::with_last_byte(8), standard(ec_pairing)),
+            (Address::with_last_byte(9), standard(disabled)),
+        ]);
+        precompiles
+    });
+}
+
+fn standard<DB: Database>(run: fn(&Bytes, u64) -> PrecompileResult) -> ContextPrecompile<DB> {
+    ContextPrecompile::Ordinary(Precompile::Standard(run))
+}
+
+fn standard_precompile(address: Address) -> fn(&Bytes, u64) -> PrecompileResult {
+    match Precompiles::berlin().get(&address) {
+        Some(Precompile::Standard(run)) => *run,
+        _ => unreachable!("{address} is a standard precompile"),
+    }
+}
+
+fn disabled(_input: &Bytes, _gas_limit: u64) -> PrecompileResult {
+    Err(PrecompileError::other("precompile is disabled"))
+}
+
+fn modexp(input: &Bytes, gas_limit: u64) -> PrecompileResult {
+    // base, exponent and modulus are limited to 32 bytes
+    let mut lengths = [0u8; 96];
+    let len = input.len().min(96);
+    lengths[..len].copy_from_slice(&input[..len]);
+    if lengths
+        .chunks(32)
+        .any(|length| U256::from_be_slice(length) > U256::from(32))
+    {
+        return Err(PrecompileError::other(
+            "modexp input is limited to 32 bytes",
+        ));
+    }
+    standard_precompile(Address::with_last_byte(5))(input, gas_limit)
+}
+
+fn ec_pairing(input: &Bytes, gas_limit: u64) -> PrecompileResult {
+    // at most 4 pairs
+    if input.len() > 4 * 192 {
+        return Err(PrecompileError::Bn128PairLength);
+    }
+    standard_precompile(Address::with_last_byte(8))(input, gas_limit)
+}
+
+fn add_balance(db: &mut CacheDB<EmptyDB>, addr: Address, amount: U256) {
+    let account = db.load_account(addr).expect("in-memory db is infallible");
+    if account.account_state == AccountState::NotExisting {
+        account.account_state = AccountState::Touched;
+    }
+    account.info.balance += amount;
+}
+
+/// L1 data fee of a tx, according to the L1GasPriceOracle.
+fn l1_fee(db: &CacheDB<EmptyDB>, tx: &Transaction) -> u64 {
+    let [base_fee, fee_overhead, fee_scalar] = L1_FEE_SLOTS.map(|slot| {
+        db.storage_ref(L1_GAS_PRICE_ORACLE, U256::from(slot))
+            .expect("in-memory db is infallible")
+            .saturating_to::<u64>()
+    });
+    let tx_l1_gas = tx_data_gas_cost(&tx.rlp_bytes) + fee_overhead + TX_L1_COMMIT_EXTRA_COST;
+    (fee_scalar as u128 * base_fee as u128 * tx_l1_gas as u128 / TX_L1_FEE_PRECISION) as u64
+}
+
+fn transaction_trace(
+    config: &TraceConfig,
+    tx: &Transaction,
+    is_l1_msg: bool,
+    gas_price: Word,
+) -> TransactionTrace {
+    TransactionTrace {
+        tx_hash: tx.hash,
+        type_: if is_l1_msg { L1_MSG_TX_TYPE } else { 0 },
+        nonce: tx.nonce.as_u64(),
+        gas: tx.gas_limit.as_u64(),
+        gas_price,
+        from: tx.from,
+        to: tx.to,
+        chain_id: Word::from(config.chain_id),
+        value: tx.value,
+        data: tx.call_data.clone(),
+        is_create: tx.to.is_none(),
+        v: U64::from(tx.v),
+        r: tx.r,
+        s: tx.s,
+        max_fee_per_blob_gas: tx.max_fee_per_blob_gas,
+        blob_versioned_hashes: tx.blob_versioned_hashes.clone(),
+    }
+}
+
+/// Poseidon code hash, the same as `bus_mapping::util::hash_code_poseidon`.
+fn hash_code_poseidon(code: &[u8]) -> H256 {
+    let msgs = code
+        .chunks(POSEIDON_HASH_BYTES_IN_FIELD)
+        .map(|chunk| {
+            // pad to bytes_in_field
+            let mut field = [0u8; POSEIDON_HASH_BYTES_IN_FIELD];
+            field[..chunk.len()].copy_from_slice(chunk);
+            let mut buf = [0u8; 32];
+            Word::from_big_endian(&field).to_little_endian(&mut buf);
+            Fr::from_bytes(&buf).unwrap()
+        })
+        .collect::<Vec<_>>();
+
+    let h = if msgs.is_empty() {
+        // the empty code hash is overlapped with simple hash on [0, 0]
+        Fr::hash_with_domain([Fr::zero(), Fr::zero()], Fr::zero())
+    } else {
+        Fr::hash_msg(&msgs, Some(code.len() as u128 * HASHABLE_DOMAIN_SPEC))
+    };
+
+    let mut buf = [0u8; 32];
+    Word::from_little_endian(h.to_repr().as_ref()).to_big_endian(&mut buf);
+    H256::from(buf)
+}
+
+/// Keccak and poseidon hashes of `code`, poseidon hashes are cached by the
+/// keccak hash.
+fn code_hash_pair(code_hashes: &mut HashMap<H256, H256>, code: &[u8]) -> (H256, H256) {
+    if code.is_empty() {
+        return (*KECCAK_CODE_HASH_EMPTY, *POSEIDON_CODE_HASH_EMPTY);
+    }
+    let keccak_code_hash = H256::from_slice(keccak256(code).as_slice());
+    let poseidon_code_hash = *code_hashes
+        .entry(keccak_code_hash)
+        .or_insert_with(|| hash_code_poseidon(code));
+    (keccak_code_hash, poseidon_code_hash)
+}
+
+fn account_wrapper(
+    db: &CacheDB<EmptyDB>,
+    addr: Address,
+    code_hashes: &mut HashMap<H256, H256>,
+) -> AccountProofWrapper {
+    let info = db
+        .basic_ref(addr)
+        .expect("in-memory db is infallible")
+        .unwrap_or_default();
+    let (keccak_code_hash, poseidon_code_hash) = code_hash_pair(code_hashes, &load_code(db, &info));
+    AccountProofWrapper {
+        address: Some(eth_address(addr)),
+        nonce: Some(info.nonce),
+        balance: Some(word(info.balance)),
+        keccak_code_hash: Some(keccak_code_hash),
+        poseidon_code_hash: Some(poseidon_code_hash),
+        storage: None,
+    }
+}
+
+/// Storage slots touched per account.
+type Touched = BTreeMap<Address, BTreeSet<U256>>;
+
+/// Zktries of the accounts and of their storage.
+#[derive(Debug, Clone)]
+struct StateTrie {
+    accounts: ZkTrie,
+    storages: HashMap<Address, ZkTrie>,
+}
+
+impl StateTrie {
+    fn new(db: &CacheDB<EmptyDB>, code_hashes: &mut HashMap<H256, H256>) -> Self {
+        let mut leaves = vec![];
+        let mut storages = HashMap::new();
+        for (addr, account) in &db.accounts {
+            let storage = ZkTrie::new(
+                account
+                    .storage
+                    .iter()
+                    .filter(|(_, value)| !value.is_zero())
+                    .map(|(slot, value)| Leaf::storage(slot.to_be_bytes(), value.to_be_bytes())),
+            );
+            let info = &account.info;
+            let code = load_code(db, info);
+            // empty accounts are deleted from the state
+            if info.nonce == 0 && info.balance.is_zero() && code.is_empty() && storage.is_empty() {
+                continue;
+            }
+
+            let (keccak_code_hash, poseidon_code_hash) = code_hash_pair(code_hashes, &code);
+            let mut field0 = [0u8; 32];
+            field0[16..24].copy_from_slice(&(code.len() as u64).to_be_bytes());
+            field0[24..].copy_from_slice(&info.nonce.to_be_bytes());
+            leaves.push(Leaf::account(
+                addr,
+                [
+                    field0,
+                    info.balance.to_be_bytes(),
+                    storage.root().0,
+                    keccak_code_hash.0,
+                    poseidon_code_hash.0,
+                ],
+            ));
+            storages.insert(*addr, storage);
+        }
+        Self {
+            accounts: ZkTrie::new(leaves),
+            storages,
+        }
+    }
+
+    fn storage(&self, addr: &Address) -> ZkTrie {
+        self.storages
+            .get(addr)
+            .cloned()
+            .unwrap_or_else(|| ZkTrie::new([]))
+    }
+
+    /// Storage trace of going from this state to `after`, with the proofs of
+    /// the `touched` accounts and slots in this state.
+    fn storage_trace(&self, after: &Self, touched: &Touched) -> StorageTrace {
+        let mut proofs = AccountTrieProofs::new();
+        let mut storage_proofs = StorageTrieProofs::new();
+        let mut deletion_proofs = vec![];
+        for (addr, slots) in touched {
+            proofs.insert(eth_address(*addr), self.accounts.prove(&account_key(addr)));
+            if slots.is_empty() {
+                continue;
+            }
+            let storage = self.storage(addr);
+            let storage_after = after.storage(addr);
+            let account_storage_proofs = slots
+                .iter()
+                .map(|slot| {
+                    let key = slot.to_be_bytes();
+                    if storage.contains(&key) && !storage_after.contains(&key) {
+                        deletion_proofs.extend(storage.deletion_proof(&key));
+                    }
+                    (word(*slot), storage.prove(&key))
+                })
+                .collect();
+            storage_proofs.insert(eth_address(*addr), account_storage_proofs);
+        }
+        StorageTrace {
+            root_before: self.accounts.root(),
+            root_after: after.accounts.root(),
+            proofs: Some(proofs),
+            storage_proofs,
+            deletion_proofs,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        super::tests::{config, contract, contract_account},
+        *,
+    };
+    use eth_types::bytecode;
+
+    fn slot(n: u64) -> [u8; 32] {
+        U256::from(n).to_be_bytes()
+    }
+
+    fn l2trace(config: &TraceConfig) -> BlockTrace {
+        crate::l2trace(config).unwrap()
+    }
+
+    #[test]
+    fn storage_traces() {
+        // clears the slot 1, the slot 2 is kept
+        let code = bytecode! {
+            PUSH1(0x00)
+            PUSH1(0x01)
+            SSTORE
+            STOP
+        };
+        let mut account = contract_account(contract(), code);
+        account.storage = [
+            (Word::from(1), Word::from(7)),
+            (Word::from(2), Word::from(9)),
+        ]
+        .into();
+        let block_trace = l2trace(&config([account]));
+
+        let storage_trace = &block_trace.storage_trace;
+        assert_ne!(storage_trace.root_before, storage_trace.root_after);
+        assert_eq!(block_trace.header.state_root, storage_trace.root_after);
+        assert_eq!(block_trace.tx_storage_trace.len(), 1);
+        let tx_storage_trace = &block_trace.tx_storage_trace[0];
+        assert_eq!(tx_storage_trace.root_before, storage_trace.root_before);
+        assert_eq!(tx_storage_trace.root_after, storage_trace.root_after);
+
+        // the account leaf commits to the storage root before the tx
+        let storage = ZkTrie::new([
+            Leaf::storage(slot(1), slot(7)),
+            Leaf::storage(slot(2), slot(9)),
+        ]);
+        let account_proof = &storage_trace.proofs.as_ref().unwrap()[&contract()];
+        let leaf = &account_proof[account_proof.len() - 2];
+        let storage_root_offset = 1 + 32 + 4 + 2 * 32;
+        assert_eq!(
+            &leaf[storage_root_offset..storage_root_offset + 32],
+            storage.root().as_bytes()
+        );
+
+        let storage_proofs = &storage_trace.storage_proofs[&contract()];
+        assert_eq!(storage_proofs[&Word::from(1)], storage.prove(&slot(1)));
+        // the deleted leaf is replaced by its sibling
+        assert_eq!(
+            storage_trace.deletion_proofs,
+            vec![storage.deletion_proof(&slot(1)).unwrap()]
+        );
+        assert_eq!(
+            tx_storage_trace.deletion_proofs,
+            storage_trace.deletion_proofs
+        );
+    }
+
+    #[test]
+    fn tx_storage_traces_are_chained() {
+        let code = bytecode! {
+            PUSH1(0x00)
+            SLOAD
+            PUSH1(0x01)
+            ADD
+            PUSH1(0x00)
+            SSTORE
+            STOP
+        };
+        let mut config = config([contract_account(contract(), code)]);
+        config.transactions.push(config.transactions[0].clone());
+        let block_trace = l2trace(&config);
+
+        let roots = block_trace
+            .tx_storage_trace
+            .iter()
+            .map(|trace| (trace.root_before, trace.root_after))
+            .collect::<Vec<_>>();
+        assert_eq!(roots.len(), 2);
+        assert_eq!(roots[0].1, roots[1].0);
+        assert_ne!(roots[0].0, roots[0].1);
+        assert_ne!(roots[1].0, roots[1].1);
+        assert_eq!(block_trace.storage_trace.root_before, roots[0].0);
+        assert_eq!(block_trace.storage_trace.root_after, roots[1].1);
+
+        // the slot is proven against the state before each tx
+        let slot_proof =
+            |trace: &StorageTrace| trace.storage_proofs[&contract()][&Word::zero()].clone();
+        assert_eq!(
+            slot_proof(&block_trace.tx_storage_trace[0]),
+            ZkTrie::new([]).prove(&slot(0))
+        );
+        assert_eq!(
+            slot_proof(&block_trace.tx_storage_trace[1]),
+            ZkTrie::new([Leaf::storage(slot(0), slot(1))]).prove(&slot(0))
+        );
+        assert_eq!(
+            slot_proof(&block_trace.storage_trace),
+            slot_proof(&block_trace.tx_storage_trace[0])
+        );
+    }
+
+    #[test]
+    fn limited_precompiles() {
+        let mut input = vec![0u8; 96];
+        input[31] = 33;
+        assert!(modexp(&input.into(), u64::MAX).is_err());
+        let mut input = vec![0u8; 99];
+        input[31] = 1;
+        input[63] = 1;
+        input[95] = 1;
+        assert!(modexp(&input.into(), u64::MAX).is_ok());
+
+        assert!(ec_pairing(&Bytes::from(vec![0u8; 5 * 192]), u64::MAX).is_err());
+        assert!(ec_pairing(&Bytes::new(), u64::MAX).is_ok());
+        assert!(disabled(&Bytes::new(), u64::MAX).is_err());
+    }
+
+    #[test]
+    fn poseidon_code_hash() {
+        let (keccak_code_hash, poseidon_code_hash) = code_hash_pair(&mut HashMap::new(), &[]);
+        assert_eq!(keccak_code_hash, *KECCAK_CODE_HASH_EMPTY);
+        assert_eq!(poseidon_code_hash, *POSEIDON_CODE_HASH_EMPTY);
+        assert_eq!(hash_code_poseidon(&[]), *POSEIDON_CODE_HASH_EMPTY);
+
+        let mut code_hashes = HashMap::new();
+        let code = [0x60, 0x00, 0x00];
+        let (keccak_code_hash, poseidon_code_hash) = code_hash_pair(&mut code_hashes, &code);
+        assert_eq!(code_hashes[&keccak_code_hash], poseidon_code_hash);
+        assert_eq!(hash_code_poseidon(&code), poseidon_code_hash);
+        assert_ne!(poseidon_code_hash, *POSEIDON_CODE_HASH_EMPTY);
+    }
+}
@@ -0,0 +1,581 @@
+//! revm [`Inspector`] collecting the same data as geth's `StructLogger` and
+//! `callTracer`.
+
+use revm::{
+    interpreter::{
+        opcode, CallInputs, CallOutcome, CallScheme, CreateInputs, CreateOutcome,
+        InstructionResult, Interpreter,
+    },
+    primitives::{db::Database, AccountInfo, Address, Bytes, CreateScheme, U256},
+    EvmContext, Inspector,
+};
+use std::collections::{BTreeMap, HashMap};
+
+/// Which parts of the machine state are captured on every step.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct CaptureConfig {
+    pub(crate) memory: bool,
+    pub(crate) stack: bool,
+    pub(crate) storage: bool,
+    /// Capture the callee of CALL* / EXTCODESIZE / EXTCODECOPY steps, which
+    /// the l2 trace reports in `extraData`.
+    pub(crate) callee: bool,
+}
+
+/// One step of the struct logger, before the opcode is executed.
+#[derive(Debug, Clone)]
+pub(crate) struct StructLog {
+    pub(crate) pc: u64,
+    pub(crate) op: u8,
+    pub(crate) gas: u64,
+    pub(crate) gas_cost: u64,
+    pub(crate) depth: u64,
+    pub(crate) refund: u64,
+    /// Error raised before the opcode could run (stack check or gas), which
+    /// is the only kind of error geth attaches to a struct log.
+    pub(crate) error: Option<InstructionResult>,
+    pub(crate) stack_len: usize,
+    pub(crate) stack: Option<Vec<U256>>,
+    pub(crate) memory: Option<Vec<u8>>,
+    pub(crate) storage: Option<BTreeMap<U256, U256>>,
+    /// Account targeted by a CALL* / EXTCODESIZE / EXTCODECOPY step.
+    pub(crate) callee: Option<Callee>,
+}
+
+/// State of the account a step reads the code of, before the step runs.
+#[derive(Debug, Clone)]
+#[cfg_attr(not(feature = "scroll"), allow(dead_code))]
+pub(crate) struct Callee {
+    pub(crate) address: Address,
+    pub(crate) nonce: u64,
+    pub(crate) balance: U256,
+    pub(crate) code: Bytes,
+}
+
+/// Kind of a call frame, named like the opcode that opened it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CallKind {
+    Call,
+    CallCode,
+    DelegateCall,
+    StaticCall,
+    Create,
+    Create2,
+}
+
+impl CallKind {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            CallKind::Call => "CALL",
+            CallKind::CallCode => "CALLCODE",
+            CallKind::DelegateCall => "DELEGATECALL",
+            CallKind::StaticCall => "STATICCALL",
+            CallKind::Create => "CREATE",
+            CallKind::Create2 => "CREATE2",
+        }
+    }
+}
+
+/// A frame of the call tracer.
+#[derive(Debug, Clone)]
+pub(crate) struct CallFrame {
+    pub(crate) kind: CallKind,
+    pub(crate) from: Address,
+    pub(crate) to: Option<Address>,
+    pub(crate) gas: u64,
+    pub(crate) gas_used: u64,
+    /// Return data, only set for successful or reverted frames.
+    pub(crate) output: Option<Bytes>,
+    pub(crate) result: InstructionResult,
+    pub(crate) calls: Vec<CallFrame>,
+    /// Address whose storage the frame runs against.
+    context: Address,
+}
+
+/// Inspector recording the struct logs and the call tree of one transaction.
+#[derive(Debug, Default)]
+pub(crate) struct TxTracer {
+    config: CaptureConfig,
+    pub(crate) logs: Vec<StructLog>,
+    /// Root frame, available once the transaction has been executed.
+    pub(crate) root: Option<CallFrame>,
+    call_stack: Vec<CallFrame>,
+    /// Index of the log of the step being executed and the gas before it.
+    pending: Option<(usize, u64)>,
+    /// Refund counter of every frame on the call stack, geth reports their sum.
+    refunds: Vec<i64>,
+    /// Storage seen by SLOAD / SSTORE per contract, as geth's struct logger.
+    storage: HashMap<Address, BTreeMap<U256, U256>>,
+}
+
+impl TxTracer {
+    pub(crate) fn new(config: CaptureConfig) -> Self {
+        Self {
+            config,
+            ..Default::default()
+        }
+    }
+
+    fn enter(&mut self, mut frame: CallFrame) {
+        if let Some(parent) = self.call_stack.last() {
+            frame.from = parent.context;
+        }
+        self.call_stack.push(frame);
+    }
+
+    fn exit(
+        &mut self,
+        result: InstructionResult,
+        gas_remaining: u64,
+        output: &Bytes,
+        address: Option<Address>,
+    ) {
+        let Some(mut frame) = self.call_stack.pop() else {
+            return;
+        };
+        frame.result = result;
+        frame.gas_used = frame.gas.saturating_sub(gas_remaining);
+        if result.is_ok() || result == InstructionResult::Revert {
+            frame.output = Some(output.clone());
+        }
+        if address.is_some() {
+            frame.to = address;
+        }
+        match self.call_stack.last_mut() {
+            Some(parent) => {
+                // geth does not report the frames which fail before they are
+                // entered.
+                let not_entered = matches!(
+                    result,
+                    InstructionResult::CallTooDeep
+                        | InstructionResult::OutOfFunds
+                        | InstructionResult::CreateCollision
+                        | InstructionResult::NonceOverflow
+                );
+                if !not_entered {
+                    parent.calls.push(frame);
+                }
+            }
+            None => self.root = Some(frame),
+        }
+    }
+}
+
+fn load_storage<DB: Database>(context: &mut EvmContext<DB>, address: Address, key: U256) -> U256 {
+    // read without touching the journal, so that the access list is kept as is
+    if let Some(slot) = context
+        .journaled_state
+        .state
+        .get(&address)
+        .and_then(|account| account.storage.get(&key))
+    {
+        return slot.present_value;
+    }
+    context.db.storage(address, key).unwrap_or_default()
+}
+
+fn load_callee<DB: Database>(context: &mut EvmContext<DB>, address: Address) -> Callee {
+    let info = match context.journaled_state.state.get(&address) {
+        Some(account) => account.info.clone(),
+        None => context
+            .db
+            .basic(address)
+            .ok()
+            .flatten()
+            .unwrap_or_else(AccountInfo::default),
+    };
+    let code = match info.code {
+        Some(code) => code.original_bytes(),
+        None => context
+            .db
+            .code_by_hash(info.code_hash)
+            .map(|code| code.original_bytes())
+            .unwrap_or_default(),
+    };
+    Callee {
+        address,
+        nonce: info.nonce,
+        balance: info.balance,
+        code,
+    }
+}
+
+fn is_pre_execution_error(result: InstructionResult) -> bool {
+    matches!(
+        result,
+        InstructionResult::StackUnderflow
+            | InstructionResult::StackOverflow
+            | InstructionResult::OutOfGas
+            | InstructionResult::MemoryOOG
+            | InstructionResult::MemoryLimitOOG
+            | InstructionResult::InvalidOperandOOG
+    )
+}
+
+impl<DB: Database> Inspector<DB> for TxTracer {
+    fn step(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) {
+        let depth = context.journaled_state.depth();
+        let op = interp.current_opcode();
+        let stack = interp.stack.data();
+
+        self.refunds.truncate(depth as usize);
+        self.refunds.resize(depth as usize, 0);
+        if let Some(refund) = self.refunds.last_mut() {
+            *refund = interp.gas.refunded();
+        }
+        let refund = self.refunds.iter().sum::<i64>().max(0) as u64;
+
+        let storage = if self.config.storage && matches!(op, opcode::SLOAD | opcode::SSTORE) {
+            let address = interp.contract.address;
+            let entry = match (op, stack.len()) {
+                (opcode::SLOAD, 1..) => {
+                    let key = stack[stack.len() - 1];
+                    Some((key, load_storage(context, address, key)))
+                }
+                (opcode::SSTORE, 2..) => Some((stack[stack.len() - 1], stack[stack.len() - 2])),
+                _ => None,
+            };
+            entry.map(|(key, value)| {
+                let storage = self.storage.entry(address).or_default();
+                storage.insert(key, value);
+                storage.clone()
+            })
+        } else {
+            None
+        };
+
+        let callee_pos = match op {
+            opcode::CALL | opcode::CALLCODE | opcode::DELEGATECALL | opcode::STATICCALL => Some(1),
+            opcode::EXTCODESIZE | opcode::EXTCODECOPY => Some(0),
+            _ => None,
+        };
+        let callee = callee_pos
+            .filter(|pos| self.config.callee && *pos < stack.len())
+            .map(|pos| Address::from_word(stack[stack.len() - 1 - pos].to_be_bytes().into()))
+            .map(|address| load_callee(context, address));
+
+        self.pending = Some((self.logs.len(), interp.gas.remaining()));
+        self.logs.push(StructLog {
+            pc: interp.program_counter() as u64,
+            op,
+            gas: interp.gas.remaining(),
+            gas_cost: 0,
+            depth,
+            refund,
+            error: None,
+            stack_len: stack.len(),
+            stack: self.config.stack.then(|| stack.clone()),
+            memory: self
+                .config
+                .memory
+                .then(|| interp.shared_memory.context_memory().to_vec()),
+            storage,
+            callee,
+        });
+    }
+
+    fn step_end(&mut self, interp: &mut Interpreter, _context: &mut EvmContext<DB>) {
+        let Some((index, gas_before)) = self.pending.take() else {
+            return;
+        };
+        let log = &mut self.logs[index];
+        log.gas_cost = gas_before.saturating_sub(interp.gas.remaining());
+        if is_pre_execution_error(interp.instruction_result) {
+            log.error = Some(interp.instruction_result);
+        }
+    }
+
+    fn call(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        inputs: &mut CallInputs,
+    ) -> Option<CallOutcome> {
+        let kind = match inputs.context.scheme {
+            CallScheme::Call => CallKind::Call,
+            CallScheme::CallCode => CallKind::CallCode,
+            CallScheme::DelegateCall => CallKind::DelegateCall,
+            CallScheme::StaticCall => CallKind::StaticCall,
+        };
+        self.enter(CallFrame {
+            kind,
+            from: inputs.context.caller,
+            to: Some(inputs.contract),
+            gas: inputs.gas_limit,
+            gas_used: 0,
+            output: None,
+            result: InstructionResult::Continue,
+            calls: vec![],
+            context: inputs.context.address,
+        });
+        None
+    }
+
+    fn call_end(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        _inputs: &CallInputs,
+        outcome: CallOutcome,
+    ) -> CallOutcome {
+        self.exit(
+            outcome.result.result,
+            outcome.result.gas.remaining(),
+            &outcome.result.output,
+            None,
+        );
+        outcome
+    }
+
+    fn create(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &mut CreateInputs,
+    ) -> Option<CreateOutcome> {
+        // revm charges the gas passed to the new frame as part of the CREATE
+        // step, geth does not include it in the step cost.
+        if context.journaled_state.depth() > 0 {
+            if let Some(log) = self.logs.last_mut() {
+                if matches!(log.op, opcode::CREATE | opcode::CREATE2) {
+                    log.gas_cost = log.gas_cost.saturating_sub(inputs.gas_limit);
+                }
+            }
+        }
+
+        let nonce = context
+            .journaled_state
+            .state
+            .get(&inputs.caller)
+            .map(|account| account.info.nonce)
+            .unwrap_or_default();
+        let address = inputs.created_address(nonce);
+        let kind = match inputs.scheme {
+            CreateScheme::Create => CallKind::Create,
+            CreateScheme::Create2 { .. } => CallKind::Create2,
+        };
+        self.enter(CallFrame {
+            kind,
+            from: inputs.caller,
+            to: Some(address),
+            gas: inputs.gas_limit,
+            gas_used: 0,
+            output: None,
+            result: InstructionResult::Continue,
+            calls: vec![],
+            context: address,
+        });
+        None
+    }
+
+    fn create_end(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        _inputs: &CreateInputs,
+        outcome: CreateOutcome,
+    ) -> CreateOutcome {
+        self.exit(
+            outcome.result.result,
+            outcome.result.gas.remaining(),
+            &outcome.result.output,
+            outcome.address,
+        );
+        outcome
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        super::{
+            address, insert_accounts,
+            tests::{config, contract, contract_account},
+            transact, tx_env,
+        },
+        *,
+    };
+    use crate::TraceConfig;
+    use eth_types::{address, bytecode, Bytecode, ToWord, Word};
+    use revm::{
+        db::{CacheDB, EmptyDB},
+        primitives::{SpecId, B256},
+    };
+    use std::collections::BTreeSet;
+
+    fn callee() -> eth_types::Address {
+        address!("0x000000000000000000000000000000000000beef")
+    }
+
+    fn run(config: &TraceConfig, capture: CaptureConfig) -> TxTracer {
+        let mut db = CacheDB::new(EmptyDB::default());
+        insert_accounts(&mut db, config);
+        let mut env = tx_env(config, &config.transactions[0]);
+        env.block.prevrandao = Some(B256::ZERO);
+        let mut tracer = TxTracer::new(capture);
+        transact(&db, env, SpecId::SHANGHAI, &mut tracer).unwrap();
+        tracer
+    }
+
+    fn call(op: &str, address: eth_types::Address) -> Bytecode {
+        let mut code = bytecode! {
+            PUSH1(0x00) // retLength
+            PUSH1(0x00) // retOffset
+            PUSH1(0x00) // argsLength
+            PUSH1(0x00) // argsOffset
+        };
+        if matches!(op, "CALL" | "CALLCODE") {
+            code.push(1, Word::zero()); // value
+        }
+        code.push(32, address.to_word());
+        code.push(3, Word::from(0x10000)); // gas
+        code.append_asm(op).unwrap();
+        code
+    }
+
+    #[test]
+    fn call_tree() {
+        let mut code = call("CALL", callee());
+        code.append(&call("STATICCALL", callee()));
+        // fails in a static call
+        let writing = bytecode! {
+            PUSH1(0x01)
+            PUSH1(0x00)
+            SSTORE
+        };
+        let tracer = run(
+            &config([
+                contract_account(contract(), code),
+                contract_account(callee(), writing),
+            ]),
+            CaptureConfig::default(),
+        );
+
+        let root = tracer.root.unwrap();
+        assert_eq!(root.kind, CallKind::Call);
+        assert_eq!(root.result, InstructionResult::Stop);
+        let kinds = root
+            .calls
+            .iter()
+            .map(|frame| (frame.kind, frame.result))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![
+                (CallKind::Call, InstructionResult::Stop),
+                (
+                    CallKind::StaticCall,
+                    InstructionResult::StateChangeDuringStaticCall
+                ),
+            ]
+        );
+        for frame in &root.calls {
+            assert_eq!(frame.from, address(&contract()));
+            assert_eq!(frame.to, Some(address(&callee())));
+            assert_eq!(frame.gas, 0x10000);
+        }
+        assert!(root.calls[1].output.is_none());
+
+        let depths = tracer
+            .logs
+            .iter()
+            .map(|log| log.depth)
+            .collect::<BTreeSet<_>>();
+        assert_eq!(depths, BTreeSet::from([1, 2]));
+    }
+
+    #[test]
+    fn create_cost_excludes_the_gas_of_the_frame() {
+        let code = bytecode! {
+            PUSH1(0x00) // size
+            PUSH1(0x00) // offset
+            PUSH1(0x00) // value
+            CREATE
+            STOP
+        };
+        let tracer = run(
+            &config([contract_account(contract(), code)]),
+            CaptureConfig::default(),
+        );
+        let create = tracer
+            .logs
+            .iter()
+            .find(|log| log.op == opcode::CREATE)
+            .unwrap();
+        assert_eq!(create.gas_cost, 32000);
+
+        let root = tracer.root.unwrap();
+        assert_eq!(root.calls.len(), 1);
+        let frame = &root.calls[0];
+        assert_eq!(frame.kind, CallKind::Create);
+        assert_eq!(frame.from, address(&contract()));
+        assert_eq!(frame.to, Some(address(&contract()).create(0)));
+    }
+
+    #[test]
+    fn storage_and_refund() {
+        let code = bytecode! {
+            PUSH1(0x01)
+            SLOAD
+            PUSH1(0x00)
+            PUSH1(0x01)
+            SSTORE
+            STOP
+        };
+        let mut account = contract_account(contract(), code);
+        account.storage.insert(Word::one(), Word::from(7));
+        let tracer = run(
+            &config([account]),
+            CaptureConfig {
+                stack: true,
+                storage: true,
+                ..Default::default()
+            },
+        );
+
+        let logs = &tracer.logs;
+        let slot = U256::from(1);
+        assert_eq!(
+            logs[1].storage,
+            Some(BTreeMap::from([(slot, U256::from(7))]))
+        );
+        assert_eq!(logs[4].storage, Some(BTreeMap::from([(slot, U256::ZERO)])));
+        assert_eq!(logs[4].stack, Some(vec![U256::from(7), U256::ZERO, slot]));
+        assert!(logs[0].storage.is_none() && logs[0].memory.is_none());
+        // clearing a slot refunds 4800 since London
+        assert_eq!(logs[4].refund, 0);
+        assert_eq!(logs[5].refund, 4800);
+    }
+
+    #[test]
+    fn callee_of_extcodesize() {
+        let code = bytecode! {
+            PUSH32(callee().to_word())
+            EXTCODESIZE
+            STOP
+        };
+        let callee_code = bytecode! { STOP };
+        let config = config([
+            contract_account(contract(), code),
+            contract_account(callee(), callee_code.clone()),
+        ]);
+
+        let tracer = run(&config, CaptureConfig::default());
+        assert!(tracer.logs.iter().all(|log| log.callee.is_none()));
+
+        let tracer = run(
+            &config,
+            CaptureConfig {
+                callee: true,
+                ..Default::default()
+            },
+        );
+        let callee_of = |op| {
+            tracer
+                .logs
+                .iter()
+                .find(|log| log.op == op)
+                .and_then(|log| log.callee.clone())
+        };
+        let target = callee_of(opcode::EXTCODESIZE).unwrap();
+        assert_eq!(target.address, address(&callee()));
+        assert_eq!(target.code.to_vec(), callee_code.code());
+        assert!(callee_of(opcode::STOP).is_none());
+    }
+}
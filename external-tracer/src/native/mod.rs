@@ -0,0 +1,617 @@
+//! In-process tracer backend built on [revm](https://github.com/bluealloy/revm).
+//!
+//! It executes the transactions of a [`TraceConfig`] and builds the same
+//! traces as the Go tracer of `geth-utils`, directly as the typed traces of
+//! `eth-types` rather than through their JSON form. Building it doesn't need a
+//! Go toolchain.
+
+#[cfg(feature = "scroll")]
+mod l2;
+mod logger;
+#[cfg(feature = "scroll")]
+mod zktrie;
+
+use crate::{LoggerConfig, TraceConfig, TracerOutput};
+#[cfg(not(feature = "scroll"))]
+use eth_types::{evm_types::Gas, GethExecStep, GethExecTrace};
+use eth_types::{
+    evm_types::OpcodeId, geth_types::Transaction, l2_types::ExecStep, Error, GethCallTrace,
+    GethExecError, GethPrestateTrace, Word,
+};
+use logger::{CallFrame, CaptureConfig, StructLog, TxTracer};
+use revm::{
+    db::{CacheDB, EmptyDB},
+    inspector_handle_register,
+    interpreter::InstructionResult,
+    primitives::{
+        AccountInfo, Address, Bytecode, Bytes, Env, ExecutionResult, ResultAndState, SpecId, State,
+        TransactTo, TxEnv, B256, U256,
+    },
+    DatabaseCommit, DatabaseRef, Evm,
+};
+use std::collections::HashMap;
+
+#[cfg(feature = "scroll")]
+use l2::trace_block;
+
+/// Creates the trace of `config`, the same as `geth_utils::trace` gives.
+pub(crate) fn trace(config: &TraceConfig) -> Result<TracerOutput, Error> {
+    let mut db = CacheDB::new(EmptyDB::default());
+    trace_block(&mut db, config)
+}
+
+/// Creates the traces of the consecutive blocks of a chunk, the same as
+/// `geth_utils::trace_chunk` gives. Each block is executed on top of the state
+/// left by the previous one.
+pub(crate) fn trace_chunk(configs: &[TraceConfig]) -> Result<Vec<TracerOutput>, Error> {
+    let mut db = CacheDB::new(EmptyDB::default());
+    configs
+        .iter()
+        .map(|config| trace_block(&mut db, config))
+        .collect()
+}
+
+/// Traces the block of `config` on top of the state of `db`.
+#[cfg(not(feature = "scroll"))]
+fn trace_block(
+    db: &mut CacheDB<EmptyDB>,
+    config: &TraceConfig,
+) -> Result<Vec<GethExecTrace>, Error> {
+    check_txs_gas_limit(config)?;
+
+    insert_accounts(db, config);
+    insert_block_hashes(db, config, |n| {
+        // history_hashes[len - 1] is the hash of the parent block
+        let index = (config.history_hashes.len() as u64 + n)
+            .checked_sub(config.block_constants.number.as_u64())?;
+        config.history_hashes.get(index as usize).map(b256)
+    });
+    let timestamp = config.block_constants.timestamp.as_u64();
+    let is_active = |time: Option<u64>| time.is_some_and(|time| time <= timestamp);
+    let spec_id = match config.chain_config.as_ref() {
+        Some(c) if is_active(c.cancun_time) => SpecId::CANCUN,
+        Some(c) if is_active(c.shanghai_time) => SpecId::SHANGHAI,
+        _ => SpecId::MERGE,
+    };
+
+    let mut results = Vec::with_capacity(config.transactions.len());
+    for (idx, tx) in config.transactions.iter().enumerate() {
+        let mut env = tx_env(config, tx);
+        env.block.prevrandao = Some(b256(&config.block_constants.difficulty));
+
+        let mut tracer = TxTracer::new(capture_config(&config.logger_config, false));
+        let ResultAndState { result, state } =
+            transact(db, env, spec_id, &mut tracer).map_err(|err| apply_error(idx, err))?;
+        let prestate = prestate(db, &state);
+        db.commit(state);
+
+        results.push(GethExecTrace {
+            l1_fee: 0,
+            gas: Gas(result.gas_used()),
+            failed: !result.is_success(),
+            return_value: return_value(&result),
+            struct_logs: tracer
+                .logs
+                .iter()
+                .map(|log| GethExecStep::from(exec_step(log)))
+                .collect(),
+            account_after: vec![],
+            prestate,
+            call_trace: call_trace(&tracer, &result)?,
+        });
+    }
+
+    Ok(results)
+}
+
+fn address(address: &eth_types::Address) -> Address {
+    Address::from_slice(address.as_bytes())
+}
+
+fn u256(word: &Word) -> U256 {
+    let mut bytes = [0u8; 32];
+    word.to_big_endian(&mut bytes);
+    U256::from_be_bytes(bytes)
+}
+
+fn b256(word: &Word) -> B256 {
+    B256::from(u256(word).to_be_bytes::<32>())
+}
+
+fn word(value: U256) -> Word {
+    Word::from_big_endian(&value.to_be_bytes::<32>())
+}
+
+fn eth_address(address: Address) -> eth_types::Address {
+    eth_types::Address::from_slice(address.as_slice())
+}
+
+fn apply_error(idx: usize, err: String) -> Error {
+    Error::TracingError(format!("Failed to apply config.Transactions[{idx}]: {err}"))
+}
+
+fn check_txs_gas_limit(config: &TraceConfig) -> Result<(), Error> {
+    let txs_gas_limit = config
+        .transactions
+        .iter()
+        .map(|tx| tx.gas_limit.as_u64())
+        .sum::<u64>();
+    let block_gas_limit = config.block_constants.gas_limit.as_u64();
+    if txs_gas_limit > block_gas_limit {
+        return Err(Error::TracingError(format!(
+            "txs total gas: {txs_gas_limit} Exceeds block gas limit: {block_gas_limit}"
+        )));
+    }
+    Ok(())
+}
+
+fn capture_config(logger_config: &LoggerConfig, callee: bool) -> CaptureConfig {
+    CaptureConfig {
+        memory: logger_config.enable_memory,
+        stack: !logger_config.disable_stack,
+        storage: !logger_config.disable_storage,
+        callee,
+    }
+}
+
+/// Writes the accounts of `config` to the state.
+fn insert_accounts(db: &mut CacheDB<EmptyDB>, config: &TraceConfig) {
+    for (addr, account) in &config.accounts {
+        let code = Bytecode::new_raw(Bytes::copy_from_slice(&account.code));
+        db.insert_account_info(
+            address(addr),
+            AccountInfo {
+                balance: u256(&account.balance),
+                nonce: account.nonce.as_u64(),
+                code_hash: code.hash_slow(),
+                code: Some(code),
+            },
+        );
+        for (key, value) in &account.storage {
+            db.insert_account_storage(address(addr), u256(key), u256(value))
+                .expect("in-memory db is infallible");
+        }
+    }
+}
+
+/// Fills the hashes of the 256 most recent blocks, the only ones `BLOCKHASH`
+/// can access.
+fn insert_block_hashes(
+    db: &mut CacheDB<EmptyDB>,
+    config: &TraceConfig,
+    block_hash: impl Fn(u64) -> Option<B256>,
+) {
+    let number = config.block_constants.number.as_u64();
+    for n in number.saturating_sub(256)..number {
+        db.block_hashes
+            .insert(U256::from(n), block_hash(n).unwrap_or_default());
+    }
+}
+
+fn tx_env(config: &TraceConfig, tx: &Transaction) -> Env {
+    let block_constants = &config.block_constants;
+    let mut env = Env::default();
+    env.cfg.chain_id = config.chain_id;
+    env.block.number = U256::from(block_constants.number.as_u64());
+    env.block.coinbase = address(&block_constants.coinbase);
+    env.block.timestamp = u256(&block_constants.timestamp);
+    env.block.gas_limit = u256(&block_constants.gas_limit);
+    env.block.basefee = u256(&block_constants.base_fee);
+    env.block.difficulty = u256(&block_constants.difficulty);
+
+    // Same as the Go tracer: the fee caps default to the gas price, and the
+    // base fee check is skipped for txs without fees.
+    let gas_fee_cap = tx.gas_fee_cap.or(tx.gas_price).unwrap_or_default();
+    let gas_tip_cap = tx.gas_tip_cap.or(tx.gas_price).unwrap_or_default();
+    env.cfg.disable_base_fee = gas_fee_cap.is_zero() && gas_tip_cap.is_zero();
+
+    env.tx = TxEnv {
+        caller: address(&tx.from),
+        gas_limit: tx.gas_limit.as_u64(),
+        gas_price: u256(&gas_fee_cap),
+        gas_priority_fee: Some(u256(&gas_tip_cap)),
+        transact_to: match &tx.to {
+            Some(to) => TransactTo::call(address(to)),
+            None => TransactTo::create(),
+        },
+        value: u256(&tx.value),
+        data: Bytes::copy_from_slice(&tx.call_data),
+        nonce: Some(tx.nonce.as_u64()),
+        access_list: tx
+            .access_list
+            .iter()
+            .flat_map(|access_list| access_list.0.iter())
+            .map(|item| {
+                (
+                    address(&item.address),
+                    item.storage_keys
+                        .iter()
+                        .map(|key| U256::from_be_bytes(key.to_fixed_bytes()))
+                        .collect(),
+                )
+            })
+            .collect(),
+        max_fee_per_blob_gas: tx.max_fee_per_blob_gas.as_ref().map(u256),
+        blob_hashes: tx
+            .blob_versioned_hashes
+            .iter()
+            .map(|hash| B256::from(hash.to_fixed_bytes()))
+            .collect(),
+        ..Default::default()
+    };
+    env
+}
+
+/// Executes the tx of `env` on top of `db` without committing its changes.
+fn transact(
+    db: &CacheDB<EmptyDB>,
+    env: Env,
+    spec_id: SpecId,
+    tracer: &mut TxTracer,
+) -> Result<ResultAndState, String> {
+    let builder = Evm::builder()
+        .with_ref_db(db)
+        .with_external_context(tracer)
+        .with_env(Box::new(env))
+        .with_spec_id(spec_id);
+    #[cfg(feature = "scroll")]
+    let builder = builder.append_handler_register(l2::handle_register);
+    let mut evm = builder
+        .append_handler_register(inspector_handle_register)
+        .build();
+    evm.transact().map_err(|err| err.to_string())
+}
+
+fn return_value(result: &ExecutionResult) -> String {
+    hex::encode(result.output().cloned().unwrap_or_default())
+}
+
+/// Accounts and storage slots touched by a tx, with their values before it,
+/// as geth's `prestateTracer` reports them.
+fn prestate(
+    db: &CacheDB<EmptyDB>,
+    state: &State,
+) -> HashMap<eth_types::Address, GethPrestateTrace> {
+    state
+        .iter()
+        .map(|(addr, account)| {
+            let info = db
+                .basic_ref(*addr)
+                .expect("in-memory db is infallible")
+                .unwrap_or_default();
+            let code = load_code(db, &info);
+            let storage = (!account.storage.is_empty()).then(|| {
+                account
+                    .storage
+                    .keys()
+                    .map(|key| {
+                        let value = db
+                            .storage_ref(*addr, *key)
+                            .expect("in-memory db is infallible");
+                        (word(*key), word(value))
+                    })
+                    .collect()
+            });
+            let prestate = GethPrestateTrace {
+                balance: Some(word(info.balance)),
+                nonce: Some(info.nonce),
+                // always reported, unlike in geth, as `flatten_trace` needs
+                // the code of every callee
+                code: Some(code.to_vec().into()),
+                storage,
+            };
+            (eth_address(*addr), prestate)
+        })
+        .collect()
+}
+
+fn load_code(db: &CacheDB<EmptyDB>, info: &AccountInfo) -> Bytes {
+    match &info.code {
+        Some(code) => code.original_bytes(),
+        None => db
+            .code_by_hash_ref(info.code_hash)
+            .map(|code| code.original_bytes())
+            .unwrap_or_default(),
+    }
+}
+
+/// Converts the result of an instruction or a call to the error reported by
+/// geth.
+fn exec_error(result: InstructionResult, op: u8, stack_len: usize) -> Option<GethExecError> {
+    let (min_stack_ptr, max_stack_ptr) = OpcodeId::from(op).valid_stack_ptr_range();
+    let stack_len = stack_len as u64;
+    Some(match result {
+        InstructionResult::OutOfGas
+        | InstructionResult::MemoryOOG
+        | InstructionResult::MemoryLimitOOG
+        | InstructionResult::PrecompileOOG => GethExecError::OutOfGas,
+        InstructionResult::InvalidOperandOOG => GethExecError::GasUintOverflow,
+        InstructionResult::StackUnderflow => GethExecError::StackUnderflow {
+            stack_len,
+            required: 1024 - max_stack_ptr as u64,
+        },
+        InstructionResult::StackOverflow => GethExecError::StackOverflow {
+            stack_len,
+            limit: 1024 - min_stack_ptr as u64,
+        },
+        InstructionResult::Revert => GethExecError::ExecutionReverted,
+        InstructionResult::InvalidJump => GethExecError::InvalidJump,
+        InstructionResult::CallNotAllowedInsideStatic
+        | InstructionResult::StateChangeDuringStaticCall => GethExecError::WriteProtection,
+        InstructionResult::OutOfOffset => GethExecError::ReturnDataOutOfBounds,
+        InstructionResult::OpcodeNotFound
+        | InstructionResult::InvalidFEOpcode
+        | InstructionResult::NotActivated => GethExecError::InvalidOpcode(OpcodeId::from(op)),
+        InstructionResult::CallTooDeep => GethExecError::Depth,
+        InstructionResult::OutOfFunds => GethExecError::InsufficientBalance,
+        InstructionResult::CreateCollision => GethExecError::ContractAddressCollision,
+        InstructionResult::NonceOverflow => GethExecError::NonceUintOverflow,
+        InstructionResult::CreateContractSizeLimit => GethExecError::MaxCodeSizeExceeded,
+        InstructionResult::CreateContractStartingWithEF => GethExecError::InvalidCode,
+        InstructionResult::CreateInitCodeSizeLimit => GethExecError::MaxInitCodeSizeExceeded,
+        _ => return None,
+    })
+}
+
+/// Converts a step to the struct log geth's `FormatLogs` reports.
+fn exec_step(log: &StructLog) -> ExecStep {
+    let error = log
+        .error
+        .and_then(|result| exec_error(result, log.op, log.stack_len));
+    ExecStep::new(
+        log.pc,
+        OpcodeId::from(log.op),
+        log.gas,
+        log.gas_cost,
+        log.refund,
+        log.depth as isize,
+        error,
+    )
+    .with_snapshots(
+        log.stack
+            .as_ref()
+            .map(|stack| stack.iter().map(|value| word(*value)).collect()),
+        log.memory
+            .as_ref()
+            .map(|memory| memory.chunks(32).map(Word::from_big_endian).collect()),
+        log.storage.as_ref().map(|storage| {
+            storage
+                .iter()
+                .map(|(key, value)| (word(*key), word(*value)))
+                .collect()
+        }),
+    )
+}
+
+/// Builds the call tree geth's `callTracer` reports. The root frame reports
+/// the gas used by the whole tx.
+fn call_trace(tracer: &TxTracer, result: &ExecutionResult) -> Result<GethCallTrace, Error> {
+    let root = tracer
+        .root
+        .as_ref()
+        .ok_or_else(|| Error::TracingError("the tx has no call frame".to_string()))?;
+    Ok(GethCallTrace {
+        gas_used: Word::from(result.gas_used()),
+        ..call_frame(root)
+    })
+}
+
+fn call_frame(frame: &CallFrame) -> GethCallTrace {
+    let error = (!frame.result.is_ok()).then(|| {
+        exec_error(frame.result, 0, 0)
+            .map(|error| error.error().to_string())
+            .unwrap_or_else(|| format!("{:?}", frame.result))
+    });
+    GethCallTrace {
+        calls: frame.calls.iter().map(call_frame).collect(),
+        error,
+        from: eth_address(frame.from),
+        gas_used: Word::from(frame.gas_used),
+        output: frame.output.as_ref().map(|output| output.to_vec().into()),
+        to: frame.to.map(eth_address),
+        call_type: frame.kind.as_str().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eth_types::{address, bytecode, geth_types::Account, Bytecode, ToWord};
+
+    pub(super) fn contract() -> eth_types::Address {
+        address!("0x000000000000000000000000000000000000cafe")
+    }
+
+    pub(super) fn contract_account(address: eth_types::Address, code: Bytecode) -> Account {
+        Account {
+            address,
+            code: code.code().into(),
+            ..Default::default()
+        }
+    }
+
+    /// A block with a single tx, from a funded account to [`contract`], on top
+    /// of `accounts`.
+    pub(super) fn config(accounts: impl IntoIterator<Item = Account>) -> TraceConfig {
+        let caller = address!("0x00000000000000000000000000000000000c0ffe");
+        let mut accounts = accounts
+            .into_iter()
+            .map(|account| (account.address, account))
+            .collect::<std::collections::BTreeMap<_, _>>();
+        accounts.insert(
+            caller,
+            Account {
+                address: caller,
+                balance: Word::from(10).pow(18.into()),
+                ..Default::default()
+            },
+        );
+        TraceConfig {
+            chain_id: 1338,
+            block_constants: eth_types::geth_types::BlockConstants {
+                number: 1.into(),
+                gas_limit: 1_000_000.into(),
+                ..Default::default()
+            },
+            accounts,
+            transactions: vec![Transaction {
+                from: caller,
+                to: Some(contract()),
+                gas_limit: 100_000.into(),
+                ..Default::default()
+            }],
+            logger_config: LoggerConfig {
+                enable_memory: true,
+                disable_stack: false,
+                disable_storage: false,
+                enable_return_data: true,
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn trace_gas_and_return_value() {
+        let code = bytecode! {
+            PUSH1(0x2a)
+            PUSH1(0x00)
+            SSTORE
+            PUSH1(0x00)
+            SLOAD
+            PUSH1(0x00)
+            MSTORE
+            PUSH1(0x20)
+            PUSH1(0x00)
+            RETURN
+        };
+        let traces = crate::trace(&config([contract_account(contract(), code)])).unwrap();
+        assert_eq!(traces.len(), 1);
+        let trace = &traces[0];
+        assert!(!trace.failed);
+        assert_eq!(trace.return_value, format!("{:064x}", 0x2a));
+
+        let ops = trace
+            .struct_logs
+            .iter()
+            .map(|step| step.op)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ops,
+            vec![
+                OpcodeId::PUSH1,
+                OpcodeId::PUSH1,
+                OpcodeId::SSTORE,
+                OpcodeId::PUSH1,
+                OpcodeId::SLOAD,
+                OpcodeId::PUSH1,
+                OpcodeId::MSTORE,
+                OpcodeId::PUSH1,
+                OpcodeId::PUSH1,
+                OpcodeId::RETURN,
+            ]
+        );
+        // cold SSTORE of a new value, warm SLOAD, MSTORE expanding 1 word
+        let gas_costs = trace
+            .struct_logs
+            .iter()
+            .map(|step| step.gas_cost.0)
+            .collect::<Vec<_>>();
+        assert_eq!(gas_costs, vec![3, 3, 22100, 3, 100, 3, 6, 3, 3, 0]);
+        assert_eq!(trace.struct_logs[0].gas.0, 100_000 - 21_000);
+        assert_eq!(trace.gas.0, 21_000 + gas_costs.iter().sum::<u64>());
+        assert!(trace
+            .struct_logs
+            .iter()
+            .all(|step| step.depth == 1 && step.error.is_none()));
+    }
+
+    #[test]
+    fn trace_chunk_keeps_the_state() {
+        let code = bytecode! {
+            PUSH1(0x00)
+            SLOAD
+            PUSH1(0x01)
+            ADD
+            PUSH1(0x00)
+            SSTORE
+            STOP
+        };
+        let first = config([contract_account(contract(), code)]);
+        let mut second = first.clone();
+        second.block_constants.number = 2.into();
+        second.transactions[0].nonce = Word::one();
+        // the accounts of a block don't reset the storage
+        second.accounts.clear();
+
+        let traces = crate::trace_chunk(&[first, second]).unwrap();
+        assert_eq!(traces.len(), 2);
+        let sstore_cost = |traces: &[eth_types::GethExecTrace]| {
+            traces[0]
+                .struct_logs
+                .iter()
+                .find(|step| step.op == OpcodeId::SSTORE)
+                .map(|step| step.gas_cost.0)
+                .unwrap()
+        };
+        // 0 -> 1, then 1 -> 2
+        assert_eq!(sstore_cost(&traces[0]), 22100);
+        assert_eq!(sstore_cost(&traces[1]), 5000);
+    }
+
+    #[test]
+    fn trace_errors() {
+        let mut over_gas_limit = config([contract_account(contract(), bytecode! { STOP })]);
+        over_gas_limit.block_constants.gas_limit = 50_000.into();
+        assert!(matches!(
+            crate::trace(&over_gas_limit),
+            Err(Error::TracingError(_))
+        ));
+
+        let code = bytecode! {
+            PUSH1(0x01)
+            ADD
+        };
+        let traces = crate::trace(&config([contract_account(contract(), code)])).unwrap();
+        let trace = &traces[0];
+        assert!(trace.failed);
+        assert_eq!(trace.gas.0, 100_000);
+        assert_eq!(
+            trace.struct_logs.last().unwrap().error,
+            Some(GethExecError::StackUnderflow {
+                stack_len: 1,
+                required: 2
+            })
+        );
+    }
+
+    #[test]
+    fn geth_errors() {
+        assert_eq!(
+            exec_error(InstructionResult::StackOverflow, 0x60, 1024),
+            Some(GethExecError::StackOverflow {
+                stack_len: 1024,
+                limit: 1023
+            })
+        );
+        assert_eq!(
+            exec_error(InstructionResult::MemoryOOG, 0x52, 2),
+            Some(GethExecError::OutOfGas)
+        );
+        assert_eq!(
+            exec_error(InstructionResult::OpcodeNotFound, 0x0c, 0),
+            Some(GethExecError::InvalidOpcode(OpcodeId::from(0x0c)))
+        );
+        assert_eq!(exec_error(InstructionResult::Stop, 0x00, 0), None);
+        assert_eq!(exec_error(InstructionResult::Return, 0xf3, 2), None);
+    }
+
+    #[test]
+    fn word_conversions() {
+        let value = Word::from_big_endian(&[0xab; 32]);
+        assert_eq!(word(u256(&value)), value);
+        assert_eq!(b256(&value), B256::from([0xab; 32]));
+        let addr = contract();
+        assert_eq!(eth_address(address(&addr)), addr);
+        assert_eq!(addr.to_word(), Word::from(0xcafe));
+    }
+}
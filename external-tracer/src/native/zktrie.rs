@@ -0,0 +1,449 @@
+//! In-memory zktrie, giving the same roots and proofs as the zktrie of l2geth
+//! for the same set of leaves.
+//!
+//! A zktrie is canonical: a leaf sits at the shallowest depth at which the
+//! path of its key is unique, so the trie is rebuilt from its leaves instead
+//! of being updated in place.
+
+use eth_types::{Bytes, H256};
+use halo2_proofs::halo2curves::{bn256::Fr, group::ff::PrimeField};
+use hash_circuit::hash::Hashable;
+
+const NODE_TYPE_LEAF: u8 = 4;
+const NODE_TYPE_EMPTY: u8 = 5;
+/// Branch whose children are both terminal (leaf or empty), +1 if the right
+/// child is a branch, +2 if the left one is.
+const NODE_TYPE_BRANCH_0: u8 = 6;
+const SECURE_HASH_DOMAIN: u64 = 512;
+/// Domain of hashing n elements is n * HASH_DOMAIN_ELEMS_BASE
+const HASH_DOMAIN_ELEMS_BASE: u64 = 256;
+/// The node appended by zktrie at the end of a proof
+const MAGIC_NODE_BYTES: &[u8] = b"THIS IS SOME MAGIC BYTES FOR SMT m1rRXgP2xpDI";
+
+/// A key and its values.
+#[derive(Debug, Clone)]
+pub(crate) struct Leaf {
+    node_key: Fr,
+    key_preimage: [u8; 32],
+    /// Bit i is set if the i-th value is hashed as 32 bytes instead of being
+    /// read as a field element.
+    compressed_flags: u32,
+    values: Vec<[u8; 32]>,
+}
+
+impl Leaf {
+    /// Leaf of an account in the state trie, the values are the nonce and
+    /// code size, the balance, the storage root, the keccak code hash and the
+    /// poseidon code hash.
+    pub(crate) fn account(address: &[u8; 20], values: [[u8; 32]; 5]) -> Self {
+        Self::new(account_key(address), 1 << 3, values.to_vec())
+    }
+
+    /// Leaf of a slot in a storage trie.
+    pub(crate) fn storage(slot: [u8; 32], value: [u8; 32]) -> Self {
+        Self::new(slot, 1, vec![value])
+    }
+
+    fn new(key_preimage: [u8; 32], compressed_flags: u32, values: Vec<[u8; 32]>) -> Self {
+        Self {
+            node_key: secure_key(&key_preimage),
+            key_preimage,
+            compressed_flags,
+            values,
+        }
+    }
+
+    fn hash(&self) -> Fr {
+        let elems = self
+            .values
+            .iter()
+            .enumerate()
+            .map(|(i, value)| {
+                if self.compressed_flags & (1 << i) != 0 {
+                    secure_key(value)
+                } else {
+                    field_from_bytes(value)
+                }
+            })
+            .collect::<Vec<_>>();
+        let value_hash = match elems.as_slice() {
+            [elem] => *elem,
+            [fst, snd, rest @ ..] => {
+                let domain = Fr::from(elems.len() as u64 * HASH_DOMAIN_ELEMS_BASE);
+                hash_elems_with_domain(domain, *fst, *snd, rest)
+            }
+            [] => unreachable!("a leaf has values"),
+        };
+        Fr::hash_with_domain([self.node_key, value_hash], Fr::from(NODE_TYPE_LEAF as u64))
+    }
+
+    fn bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![NODE_TYPE_LEAF];
+        bytes.extend(field_to_bytes(&self.node_key));
+        bytes.extend((self.values.len() as u32 | self.compressed_flags << 8).to_le_bytes());
+        for value in &self.values {
+            bytes.extend(value);
+        }
+        bytes.push(self.key_preimage.len() as u8);
+        bytes.extend(self.key_preimage);
+        bytes
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    Empty,
+    Leaf {
+        leaf: Leaf,
+        hash: Fr,
+    },
+    Branch {
+        left: Box<Node>,
+        right: Box<Node>,
+        hash: Fr,
+    },
+}
+
+impl Node {
+    fn build(mut leaves: Vec<Leaf>, depth: usize) -> Self {
+        if leaves.len() <= 1 {
+            return leaves.pop().map_or(Self::Empty, |leaf| Self::Leaf {
+                hash: leaf.hash(),
+                leaf,
+            });
+        }
+        assert!(depth < Fr::NUM_BITS as usize, "duplicated keys in zktrie");
+        let (right, left) = leaves
+            .into_iter()
+            .partition(|leaf| path_bit(&leaf.node_key, depth));
+        let left = Self::build(left, depth + 1);
+        let right = Self::build(right, depth + 1);
+        let hash = Fr::hash_with_domain(
+            [left.hash(), right.hash()],
+            Fr::from(branch_type(&left, &right) as u64),
+        );
+        Self::Branch {
+            left: Box::new(left),
+            right: Box::new(right),
+            hash,
+        }
+    }
+
+    fn hash(&self) -> Fr {
+        match self {
+            Self::Empty => Fr::zero(),
+            Self::Leaf { hash, .. } | Self::Branch { hash, .. } => *hash,
+        }
+    }
+
+    fn bytes(&self) -> Vec<u8> {
+        match self {
+            Self::Empty => vec![NODE_TYPE_EMPTY],
+            Self::Leaf { leaf, .. } => leaf.bytes(),
+            Self::Branch { left, right, .. } => {
+                let mut bytes = vec![branch_type(left, right)];
+                bytes.extend(field_to_bytes(&left.hash()));
+                bytes.extend(field_to_bytes(&right.hash()));
+                bytes
+            }
+        }
+    }
+}
+
+fn branch_type(left: &Node, right: &Node) -> u8 {
+    let is_branch = |node: &Node| matches!(node, Node::Branch { .. }) as u8;
+    NODE_TYPE_BRANCH_0 + 2 * is_branch(left) + is_branch(right)
+}
+
+/// A zktrie built from a set of leaves with distinct keys.
+#[derive(Debug, Clone)]
+pub(crate) struct ZkTrie {
+    root: Node,
+}
+
+impl ZkTrie {
+    pub(crate) fn new(leaves: impl IntoIterator<Item = Leaf>) -> Self {
+        Self {
+            root: Node::build(leaves.into_iter().collect(), 0),
+        }
+    }
+
+    pub(crate) fn root(&self) -> H256 {
+        H256(field_to_bytes(&self.root.hash()))
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        matches!(self.root, Node::Empty)
+    }
+
+    /// Whether `key_preimage` has a leaf in the trie.
+    pub(crate) fn contains(&self, key_preimage: &[u8; 32]) -> bool {
+        let node_key = secure_key(key_preimage);
+        match self.path(&node_key).last() {
+            Some(Node::Leaf { leaf, .. }) => leaf.node_key == node_key,
+            _ => false,
+        }
+    }
+
+    /// Nodes from the root to the leaf of `key_preimage`, or to the empty node
+    /// or the leaf of another key where its path ends, followed by the magic
+    /// node, as returned by `eth_getProof`.
+    pub(crate) fn prove(&self, key_preimage: &[u8; 32]) -> Vec<Bytes> {
+        self.path(&secure_key(key_preimage))
+            .into_iter()
+            .map(|node| Bytes::from(node.bytes()))
+            .chain([Bytes::from(MAGIC_NODE_BYTES.to_vec())])
+            .collect()
+    }
+
+    /// The sibling of the leaf of `key_preimage` if it is a leaf, which takes
+    /// the place of their parent once the key is deleted. l2geth reports it
+    /// in the deletion proofs, as it is not part of the proof of the key.
+    pub(crate) fn deletion_proof(&self, key_preimage: &[u8; 32]) -> Option<Bytes> {
+        let node_key = secure_key(key_preimage);
+        let path = self.path(&node_key);
+        match path.as_slice() {
+            [.., Node::Branch { left, right, .. }, Node::Leaf { leaf, .. }]
+                if leaf.node_key == node_key =>
+            {
+                let sibling = if path_bit(&node_key, path.len() - 2) {
+                    left
+                } else {
+                    right
+                };
+                matches!(**sibling, Node::Leaf { .. }).then(|| Bytes::from(sibling.bytes()))
+            }
+            _ => None,
+        }
+    }
+
+    fn path(&self, node_key: &Fr) -> Vec<&Node> {
+        let mut node = &self.root;
+        let mut path = vec![node];
+        while let Node::Branch { left, right, .. } = node {
+            node = if path_bit(node_key, path.len() - 1) {
+                right.as_ref()
+            } else {
+                left.as_ref()
+            };
+            path.push(node);
+        }
+        path
+    }
+}
+
+/// Key preimage of an account, the address padded on the right.
+pub(crate) fn account_key(address: &[u8; 20]) -> [u8; 32] {
+    let mut key_preimage = [0u8; 32];
+    key_preimage[..20].copy_from_slice(address);
+    key_preimage
+}
+
+/// Key of `key_preimage` in the zktrie, as `zktrie::state::builder::hash_byte32`.
+pub(crate) fn secure_key(key_preimage: &[u8; 32]) -> Fr {
+    let high = Fr::from_u128(u128::from_be_bytes(key_preimage[..16].try_into().unwrap()));
+    let low = Fr::from_u128(u128::from_be_bytes(key_preimage[16..].try_into().unwrap()));
+    Fr::hash_with_domain([high, low], Fr::from(SECURE_HASH_DOMAIN))
+}
+
+/// The path of a key goes right at `depth` if the bit is set, starting from
+/// the least significant bit.
+fn path_bit(node_key: &Fr, depth: usize) -> bool {
+    (node_key.to_repr().as_ref()[depth / 8] >> (depth % 8)) & 1 == 1
+}
+
+/// Hashes and field values are big endian in the nodes.
+fn field_to_bytes(field: &Fr) -> [u8; 32] {
+    let mut bytes = field.to_repr();
+    bytes.reverse();
+    bytes
+}
+
+fn field_from_bytes(bytes: &[u8; 32]) -> Fr {
+    let mut repr = *bytes;
+    repr.reverse();
+    Fr::from_repr(repr).expect("uncompressed values are field elements")
+}
+
+fn hash_elems_with_domain(domain: Fr, fst: Fr, snd: Fr, elems: &[Fr]) -> Fr {
+    let base = Fr::hash_with_domain([fst, snd], domain);
+    match elems {
+        [] => base,
+        [elem] => Fr::hash_with_domain([base, *elem], domain),
+        _ => {
+            let tmp = elems
+                .chunks(2)
+                .map(|pair| match pair {
+                    [a, b] => Fr::hash_with_domain([*a, *b], domain),
+                    [a] => *a,
+                    _ => unreachable!("chunks of 2"),
+                })
+                .collect::<Vec<_>>();
+            hash_elems_with_domain(domain, base, tmp[0], &tmp[1..])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    // proofs of the account 0x66613a02924ca4171675f3793b4eb3908a480d55 and of
+    // its storage slot 0, traced by l2geth
+    const ACCOUNT_PROOF: [&str; 6] = [
+        "0917e72849d9c0d67bb31746101cf4895de34892b24d1486daa024a660abc37d860ddffa0c24af819b6e3c1a8b94699fedcdc77656184edc5a39eb81ca0bed790a",
+        "0927fb0f5d23170a387eba5ab2e6d4353fd2ec8ab81022f981548d9acdc07c637a2048ec88c007fbe8be0b597adcb2ce40b5f4581e0cc058d67e8e12528d3e6917",
+        "0921b2b32fa1ee730a507859d58adc1e3f03eac97c1c38ffd8bd1e5e940233fa1301e6296bc35577d87cbfd3bc018c967217ed782d80e3ac023a5f9266f48e3e0a",
+        "0715c17bf538c62ca0e099396928857a0be6d6dca109eba6c338d62c199410a9bf2fe42431a564bcfd3b464819934c1dca3aa031fd49ed4c7c9724e991dc38ed15",
+        "08205a520af27de994c9eeef1ab0a6145e1f0f90e59aefdb73a3674484bc6a8d3f2671e331081527a983a7610159b13af138a164596c8280d1553b6578f7759e88",
+        "04072e5dd3f196958c52140078f2407e11cf80f414c2a3ae3f3bd641c92f7d693a050800000000000000000000000000000000000000000000000000e4000000000000000100000000000000000000000000000000000000000000000000000000000000000d24d8b8043e372c52c8e686ff0998ec5f85e26a5f882c5b9026925a1d23f4fc1f5b06141eb3afb77b6dc6873756a9fdc88f90c2b1937e4b523358664a3f894108cc5fe38e7c93fa53a7f1da1e46529e3e1aff66ef93e078a014794238d5fe332066613a02924ca4171675f3793b4eb3908a480d55000000000000000000000000",
+    ];
+    const STATE_ROOT: &str = "0x24c368802ea77a0d8d49d8ccce69cdb7aead98533c77aebbd7605358a592f3aa";
+    const STORAGE_LEAF: &str = "041d3c5f8c36e5da873d45bfa1d2399a572ac77493ec089cbf88a37b9e9442842201010000000000000000000000000000000000000000000000000000000000000000000a200000000000000000000000000000000000000000000000000000000000000000";
+    const STORAGE_ROOT: &str = "0x0d24d8b8043e372c52c8e686ff0998ec5f85e26a5f882c5b9026925a1d23f4fc";
+
+    fn word(value: u64) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        bytes[24..].copy_from_slice(&value.to_be_bytes());
+        bytes
+    }
+
+    fn hash(hex: &str) -> [u8; 32] {
+        H256::from_str(hex).unwrap().0
+    }
+
+    fn account_leaf() -> Leaf {
+        let address = hex::decode("66613a02924ca4171675f3793b4eb3908a480d55").unwrap();
+        let mut field0 = word(1);
+        field0[16..24].copy_from_slice(&0xe4u64.to_be_bytes());
+        Leaf::account(
+            &address.try_into().unwrap(),
+            [
+                field0,
+                word(0),
+                hash(STORAGE_ROOT),
+                hash("0x1f5b06141eb3afb77b6dc6873756a9fdc88f90c2b1937e4b523358664a3f8941"),
+                hash("0x08cc5fe38e7c93fa53a7f1da1e46529e3e1aff66ef93e078a014794238d5fe33"),
+            ],
+        )
+    }
+
+    #[test]
+    fn storage_trie_of_one_slot() {
+        let trie = ZkTrie::new([Leaf::storage(word(0), word(0x0a))]);
+        assert_eq!(trie.root(), H256::from_str(STORAGE_ROOT).unwrap());
+        assert_eq!(
+            trie.prove(&word(0)),
+            vec![
+                Bytes::from(hex::decode(STORAGE_LEAF).unwrap()),
+                Bytes::from(MAGIC_NODE_BYTES.to_vec()),
+            ]
+        );
+        assert!(trie.contains(&word(0)));
+        assert!(!trie.contains(&word(1)));
+        assert_eq!(trie.deletion_proof(&word(0)), None);
+    }
+
+    #[test]
+    fn empty_trie() {
+        let trie = ZkTrie::new([]);
+        assert_eq!(trie.root(), H256::zero());
+        assert_eq!(
+            trie.prove(&word(0)),
+            vec![
+                Bytes::from(vec![NODE_TYPE_EMPTY]),
+                Bytes::from(MAGIC_NODE_BYTES.to_vec()),
+            ]
+        );
+    }
+
+    #[test]
+    fn account_leaf_matches_l2geth() {
+        let leaf = account_leaf();
+        let proof = ACCOUNT_PROOF.map(|node| hex::decode(node).unwrap());
+        assert_eq!(leaf.bytes(), proof[5]);
+
+        // fold the leaf hash up through the branches of the proof
+        let mut hash = leaf.hash();
+        for (depth, node) in proof[..5].iter().enumerate().rev() {
+            let left = field_from_bytes(node[1..33].try_into().unwrap());
+            let right = field_from_bytes(node[33..65].try_into().unwrap());
+            let (left, right) = if path_bit(&leaf.node_key, depth) {
+                assert_eq!(right, hash);
+                (left, hash)
+            } else {
+                assert_eq!(left, hash);
+                (hash, right)
+            };
+            hash = Fr::hash_with_domain([left, right], Fr::from(node[0] as u64));
+        }
+        assert_eq!(
+            H256(field_to_bytes(&hash)),
+            H256::from_str(STATE_ROOT).unwrap()
+        );
+    }
+
+    #[test]
+    fn proofs_of_many_leaves() {
+        let leaves = (0..16)
+            .map(|slot| Leaf::storage(word(slot), word(slot + 1)))
+            .collect::<Vec<_>>();
+        let trie = ZkTrie::new(leaves.clone());
+        let root = trie.root();
+
+        for leaf in &leaves {
+            let proof = trie.prove(&leaf.key_preimage);
+            let (magic, nodes) = proof.split_last().unwrap();
+            assert_eq!(magic.as_ref(), MAGIC_NODE_BYTES);
+            assert_eq!(nodes.last().unwrap().as_ref(), leaf.bytes());
+            // every branch commits to the next node of the path
+            let mut expected = root.0;
+            for (depth, node) in nodes.iter().enumerate() {
+                let node = node.as_ref();
+                if node[0] == NODE_TYPE_LEAF {
+                    assert_eq!(field_to_bytes(&leaf.hash()), expected);
+                    break;
+                }
+                let left: [u8; 32] = node[1..33].try_into().unwrap();
+                let right: [u8; 32] = node[33..65].try_into().unwrap();
+                let hash = Fr::hash_with_domain(
+                    [field_from_bytes(&left), field_from_bytes(&right)],
+                    Fr::from(node[0] as u64),
+                );
+                assert_eq!(field_to_bytes(&hash), expected);
+                expected = if path_bit(&leaf.node_key, depth) {
+                    right
+                } else {
+                    left
+                };
+            }
+        }
+
+        // the root does not depend on the order of the leaves
+        let reversed = ZkTrie::new(leaves.iter().rev().cloned());
+        assert_eq!(reversed.root(), root);
+        // deleting a leaf gives the trie of the other leaves
+        let others = ZkTrie::new(leaves[1..].iter().cloned());
+        assert_ne!(others.root(), root);
+        assert!(!others.contains(&word(0)));
+    }
+
+    #[test]
+    fn deletion_proof_is_the_sibling_leaf() {
+        let leaves = [
+            Leaf::storage(word(1), word(1)),
+            Leaf::storage(word(2), word(2)),
+        ];
+        let trie = ZkTrie::new(leaves.clone());
+        assert_eq!(
+            trie.deletion_proof(&word(1)),
+            Some(Bytes::from(leaves[1].bytes()))
+        );
+        assert_eq!(
+            trie.deletion_proof(&word(2)),
+            Some(Bytes::from(leaves[0].bytes()))
+        );
+        assert_eq!(trie.deletion_proof(&word(3)), None);
+    }
+}
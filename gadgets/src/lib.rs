@@ -21,6 +21,7 @@ pub mod less_than;
 pub mod monotone;
 pub mod mul_add;
 pub mod range;
+pub mod range_table;
 pub mod util;
 
 use eth_types::Field;
@@ -0,0 +1,361 @@
+//! # Range table mod
+//! RangeTableChip is a lookup table of the values in [0, 1 << N_BITS), which
+//! can be shared by the range checks of several (sub-)circuits: it is
+//! configured and loaded once, while its [`RangeTableConfig`] is copied into
+//! the config of each circuit that uses it. A table of N_BITS can range check
+//! any width up to N_BITS bits.
+//!
+//! DecompositionChip decomposes a value into little-endian limbs of N_BITS
+//! bits, each of them range checked against a shared range table.
+use crate::util::Expr;
+use eth_types::Field;
+use halo2_proofs::{
+    circuit::{AssignedCell, Chip, Layouter, Region, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, TableColumn, VirtualCells},
+    poly::Rotation,
+};
+use std::marker::PhantomData;
+
+/// Config of a lookup table of the values in [0, 1 << N_BITS).
+#[derive(Clone, Copy, Debug)]
+pub struct RangeTableConfig<const N_BITS: usize> {
+    /// The table column
+    pub table: TableColumn,
+}
+
+impl<const N_BITS: usize> RangeTableConfig<N_BITS> {
+    /// Check that the expression is in [0, 1 << N_BITS).
+    pub fn range_check<F: Field>(
+        &self,
+        meta: &mut ConstraintSystem<F>,
+        name: &'static str,
+        expr_fn: impl FnOnce(&mut VirtualCells<'_, F>) -> Expression<F>,
+    ) {
+        meta.lookup(name, |meta| vec![(expr_fn(meta), self.table)]);
+    }
+
+    /// Check that the expression is in [0, 1 << n_bits), for n_bits <=
+    /// N_BITS. A narrower range is checked by also looking up the expression
+    /// shifted by N_BITS - n_bits bits, which is in the table only if the
+    /// expression has at most n_bits bits.
+    pub fn range_check_bits<F: Field>(
+        &self,
+        meta: &mut ConstraintSystem<F>,
+        name: &'static str,
+        n_bits: usize,
+        expr_fn: impl Fn(&mut VirtualCells<'_, F>) -> Expression<F>,
+    ) {
+        assert!(
+            n_bits <= N_BITS,
+            "can't check {n_bits} bits with a table of {N_BITS} bits"
+        );
+        self.range_check(meta, name, &expr_fn);
+        if n_bits < N_BITS {
+            self.range_check(meta, name, |meta| {
+                expr_fn(meta) * Expression::Constant(F::from(1u64 << (N_BITS - n_bits)))
+            });
+        }
+    }
+}
+
+/// Chip of a lookup table of the values in [0, 1 << N_BITS).
+#[derive(Clone, Debug)]
+pub struct RangeTableChip<F, const N_BITS: usize> {
+    config: RangeTableConfig<N_BITS>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field, const N_BITS: usize> RangeTableChip<F, N_BITS> {
+    /// Configures the range table.
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> RangeTableConfig<N_BITS> {
+        assert!(N_BITS < 32, "range table of {N_BITS} bits is too large");
+        let table = meta.lookup_table_column();
+        meta.annotate_lookup_column(table, || format!("range table [0, {})", 1u64 << N_BITS));
+        RangeTableConfig { table }
+    }
+
+    /// Constructs a range table chip.
+    pub fn construct(config: RangeTableConfig<N_BITS>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Assigns the values of the table. A shared table must only be loaded
+    /// once, by the circuit which configured it.
+    pub fn load(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        layouter.assign_table(
+            || format!("range table [0, {})", 1u64 << N_BITS),
+            |mut table| {
+                for i in 0..(1 << N_BITS) {
+                    table.assign_cell(
+                        || format!("range at offset = {i}"),
+                        self.config.table,
+                        i,
+                        || Value::known(F::from(i as u64)),
+                    )?;
+                }
+
+                Ok(())
+            },
+        )
+    }
+}
+
+impl<F: Field, const N_BITS: usize> Chip<F> for RangeTableChip<F, N_BITS> {
+    type Config = RangeTableConfig<N_BITS>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+/// Config of the decomposition of a value into N_LIMBS little-endian limbs of
+/// N_BITS bits.
+#[derive(Clone, Copy, Debug)]
+pub struct DecompositionConfig<const N_LIMBS: usize, const N_BITS: usize> {
+    /// The limbs, the least significant one first.
+    pub limbs: [Column<Advice>; N_LIMBS],
+    /// The range table of the limbs.
+    pub range_table: RangeTableConfig<N_BITS>,
+}
+
+impl<const N_LIMBS: usize, const N_BITS: usize> DecompositionConfig<N_LIMBS, N_BITS> {
+    /// The expressions of the limbs at the current row.
+    pub fn limbs<F: Field>(&self, meta: &mut VirtualCells<'_, F>) -> [Expression<F>; N_LIMBS] {
+        self.limbs
+            .map(|limb| meta.query_advice(limb, Rotation::cur()))
+    }
+}
+
+/// Chip that decomposes a value into N_LIMBS little-endian limbs of N_BITS
+/// bits, so that the value is range checked to N_LIMBS * N_BITS bits.
+#[derive(Clone, Debug)]
+pub struct DecompositionChip<F, const N_LIMBS: usize, const N_BITS: usize> {
+    config: DecompositionConfig<N_LIMBS, N_BITS>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field, const N_LIMBS: usize, const N_BITS: usize> DecompositionChip<F, N_LIMBS, N_BITS> {
+    /// Configures the decomposition of `value` when q_enable is on, the limbs
+    /// being range checked with `range_table`.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        q_enable: impl FnOnce(&mut VirtualCells<'_, F>) -> Expression<F> + Clone,
+        value: impl FnOnce(&mut VirtualCells<'_, F>) -> Expression<F>,
+        range_table: RangeTableConfig<N_BITS>,
+    ) -> DecompositionConfig<N_LIMBS, N_BITS> {
+        assert!(
+            N_LIMBS * N_BITS <= F::CAPACITY as usize,
+            "{N_LIMBS} limbs of {N_BITS} bits overflow the field"
+        );
+        let config = DecompositionConfig {
+            limbs: [(); N_LIMBS].map(|_| meta.advice_column()),
+            range_table,
+        };
+
+        meta.create_gate("decomposition", |meta| {
+            let q_enable = q_enable.clone()(meta);
+            let value = value(meta);
+            let composed = config
+                .limbs(meta)
+                .into_iter()
+                .rev()
+                .fold(0.expr(), |acc, limb| {
+                    acc * Expression::Constant(F::from(1u64 << N_BITS)) + limb
+                });
+            vec![q_enable * (value - composed)]
+        });
+
+        for limb in config.limbs {
+            range_table.range_check(meta, "decomposition limb range check", |meta| {
+                q_enable.clone()(meta) * meta.query_advice(limb, Rotation::cur())
+            });
+        }
+
+        config
+    }
+
+    /// Constructs a decomposition chip.
+    pub fn construct(config: DecompositionConfig<N_LIMBS, N_BITS>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Assigns the limbs of the value at the offset, returning their cells.
+    pub fn assign(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        value: Value<F>,
+    ) -> Result<[AssignedCell<F, F>; N_LIMBS], Error> {
+        let limbs = value.map(Self::decompose);
+        let mut cells = Vec::with_capacity(N_LIMBS);
+        for (idx, column) in self.config.limbs.into_iter().enumerate() {
+            cells.push(region.assign_advice(
+                || format!("decomposition limb[{idx}]"),
+                column,
+                offset,
+                || limbs.map(|limbs| F::from(limbs[idx])),
+            )?);
+        }
+
+        Ok(cells.try_into().unwrap())
+    }
+
+    /// The little-endian limbs of the value, truncated to N_LIMBS * N_BITS
+    /// bits.
+    pub fn decompose(value: F) -> [u64; N_LIMBS] {
+        let repr = value.to_repr();
+        let bit = |i: usize| ((repr[i / 8] >> (i % 8)) & 1) as u64;
+        std::array::from_fn(|limb| {
+            (0..N_BITS)
+                .rev()
+                .fold(0, |acc, i| acc << 1 | bit(limb * N_BITS + i))
+        })
+    }
+}
+
+impl<F: Field, const N_LIMBS: usize, const N_BITS: usize> Chip<F>
+    for DecompositionChip<F, N_LIMBS, N_BITS>
+{
+    type Config = DecompositionConfig<N_LIMBS, N_BITS>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{DecompositionChip, DecompositionConfig, RangeTableChip, RangeTableConfig};
+    use eth_types::Field;
+    use halo2_proofs::{
+        circuit::{Layouter, SimpleFloorPlanner, Value},
+        dev::MockProver,
+        halo2curves::bn256::Fr as Fp,
+        plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Selector},
+        poly::Rotation,
+    };
+    use std::marker::PhantomData;
+
+    #[derive(Clone, Debug)]
+    struct TestCircuitConfig {
+        q_enable: Selector,
+        value: Column<Advice>,
+        range_table: RangeTableConfig<8>,
+        decomposition: DecompositionConfig<4, 8>,
+    }
+
+    #[derive(Default)]
+    struct TestCircuit<F: Field> {
+        values: Vec<u64>,
+        _marker: PhantomData<F>,
+    }
+
+    impl<F: Field> Circuit<F> for TestCircuit<F> {
+        type Config = TestCircuitConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+        #[cfg(feature = "circuit-params")]
+        type Params = ();
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let q_enable = meta.complex_selector();
+            let value = meta.advice_column();
+            let range_table = RangeTableChip::<F, 8>::configure(meta);
+
+            // u32 value, with a u4 top limb
+            let decomposition = DecompositionChip::configure(
+                meta,
+                |meta| meta.query_selector(q_enable),
+                |meta| meta.query_advice(value, Rotation::cur()),
+                range_table,
+            );
+            range_table.range_check_bits(meta, "top limb range check", 4, |meta| {
+                meta.query_selector(q_enable)
+                    * meta.query_advice(decomposition.limbs[3], Rotation::cur())
+            });
+
+            Self::Config {
+                q_enable,
+                value,
+                range_table,
+                decomposition,
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            RangeTableChip::construct(config.range_table).load(&mut layouter)?;
+            let decomposition_chip = DecompositionChip::construct(config.decomposition);
+
+            layouter.assign_region(
+                || "witness",
+                |mut region| {
+                    for (offset, value) in self.values.iter().enumerate() {
+                        let value = Value::known(F::from(*value));
+                        config.q_enable.enable(&mut region, offset)?;
+                        region.assign_advice(|| "value", config.value, offset, || value)?;
+                        decomposition_chip.assign(&mut region, offset, value)?;
+                    }
+
+                    Ok(())
+                },
+            )
+        }
+    }
+
+    fn try_test_circuit(values: Vec<u64>, ok: bool) {
+        let circuit = TestCircuit::<Fp> {
+            values,
+            _marker: PhantomData,
+        };
+        let prover = MockProver::<Fp>::run(9, &circuit, vec![]).unwrap();
+        assert_eq!(prover.verify_par().is_ok(), ok);
+    }
+
+    #[test]
+    fn decomposition_in_range() {
+        try_test_circuit(vec![0, 1, 0xff, 0x100, 0x0fff_ffff], true);
+    }
+
+    #[test]
+    fn decomposition_out_of_range() {
+        // the top limb overflows 4 bits
+        try_test_circuit(vec![0x1000_0000], false);
+        // the limbs can't compose the value
+        try_test_circuit(vec![1 << 32], false);
+    }
+
+    #[test]
+    fn decompose_limbs() {
+        assert_eq!(
+            DecompositionChip::<Fp, 4, 8>::decompose(Fp::from(0x0102_0304)),
+            [4, 3, 2, 1]
+        );
+        assert_eq!(
+            DecompositionChip::<Fp, 3, 10>::decompose(Fp::from(0b11_0000000010_1111111111)),
+            [0x3ff, 2, 3]
+        );
+    }
+}
@@ -34,6 +34,30 @@ func CreateTrace(configStr *C.char) *C.char {
 	return C.CString(string(bytes))
 }
 
+// CreateTraceChunk traces the consecutive blocks of a chunk, the config is a
+// JSON array of trace configs.
+//
+//export CreateTraceChunk
+func CreateTraceChunk(configStr *C.char) *C.char {
+	var configs []TraceConfig
+	err := json.Unmarshal([]byte(C.GoString(configStr)), &configs)
+	if err != nil {
+		return C.CString(fmt.Sprintf("Failed to unmarshal configs, err: %v", err))
+	}
+
+	traces, err := TraceChunk(configs)
+	if err != nil {
+		return C.CString(fmt.Sprintf("Failed to run TraceChunk, err: %v", err))
+	}
+
+	bytes, err := json.MarshalIndent(traces, "", "  ")
+	if err != nil {
+		return C.CString(fmt.Sprintf("Failed to marshal traces, err: %v", err))
+	}
+
+	return C.CString(string(bytes))
+}
+
 //export FreeString
 func FreeString(str *C.char) {
 	C.free(unsafe.Pointer(str))
@@ -119,6 +119,8 @@ type Transaction struct {
 		// <https://github.com/gakonst/ethers-rs/blob/88095ba47eb6a3507f0db1767353b387b27a6e98/ethers-core/src/types/transaction/eip2930.rs#L75>
 		StorageKeys []common.Hash `json:"storageKeys"`
 	} `json:"access_list"`
+	BlobGasFeeCap *hexutil.Big  `json:"max_fee_per_blob_gas"`
+	BlobHashes    []common.Hash `json:"blob_versioned_hashes"`
 }
 
 type TraceConfig struct {
@@ -143,6 +145,33 @@ func toBigInt(value *hexutil.Big) *big.Int {
 }
 
 func Trace(config TraceConfig) ([]*ExecutionResult, error) {
+	executionResults, err := TraceChunk([]TraceConfig{config})
+	if err != nil {
+		return nil, err
+	}
+	return executionResults[0], nil
+}
+
+// TraceChunk traces consecutive blocks, each block being executed on top of
+// the state left by the previous one. The accounts of a config are written to
+// the state before its block is executed.
+func TraceChunk(configs []TraceConfig) ([][]*ExecutionResult, error) {
+	stateDB, _ := state.New(common.Hash{}, state.NewDatabase(rawdb.NewMemoryDatabase()), nil)
+	executionResults := make([][]*ExecutionResult, len(configs))
+	for i, config := range configs {
+		blockResults, err := traceBlock(config, stateDB)
+		if err != nil {
+			if len(configs) == 1 {
+				return nil, err
+			}
+			return nil, fmt.Errorf("Failed to trace block %d: %w", i, err)
+		}
+		executionResults[i] = blockResults
+	}
+	return executionResults, nil
+}
+
+func traceBlock(config TraceConfig, stateDB *state.StateDB) ([]*ExecutionResult, error) {
 	chainConfig := params.ChainConfig{
 		ChainID:             new(big.Int).SetUint64(config.ChainID),
 		HomesteadBlock:      big.NewInt(0),
@@ -197,6 +226,8 @@ func Trace(config TraceConfig) ([]*ExecutionResult, error) {
 			GasTipCap:         toBigInt(tx.GasTipCap),
 			Data:              tx.CallData,
 			AccessList:        txAccessList,
+			BlobGasFeeCap:     toBigInt(tx.BlobGasFeeCap),
+			BlobHashes:        tx.BlobHashes,
 			SkipAccountChecks: false,
 		}
 
@@ -226,11 +257,12 @@ func Trace(config TraceConfig) ([]*ExecutionResult, error) {
 		Difficulty:  toBigInt(config.Block.Difficulty),
 		Random:      &randao,
 		BaseFee:     toBigInt(config.Block.BaseFee),
+		// The minimum blob base fee, for a zero excess blob gas
+		BlobBaseFee: big.NewInt(1),
 		GasLimit:    blockGasLimit,
 	}
 
 	// Setup state db with accounts from argument
-	stateDB, _ := state.New(common.Hash{}, state.NewDatabase(rawdb.NewMemoryDatabase()), nil)
 	for address, account := range config.Accounts {
 		stateDB.SetNonce(address, uint64(account.Nonce))
 		stateDB.SetCode(address, account.Code)
@@ -137,6 +137,40 @@ func transferTxs(txs []Transaction) types.Transactions {
 }
 
 func Trace(config TraceConfig) (*types.BlockTrace, error) {
+	traces, err := TraceChunk([]TraceConfig{config})
+	if err != nil {
+		return nil, err
+	}
+	return traces[0], nil
+}
+
+// TraceChunk traces consecutive blocks, each block being executed on top of
+// the state left by the previous one. The accounts of a config are written to
+// the state before its block is executed.
+func TraceChunk(configs []TraceConfig) ([]*types.BlockTrace, error) {
+	trieCfg := &trie.Config{Zktrie: true}
+	db := state.NewDatabaseWithConfig(rawdb.NewMemoryDatabase(), trieCfg)
+	root := common.Hash{}
+	traces := make([]*types.BlockTrace, len(configs))
+	for i, config := range configs {
+		stateDB, err := state.New(root, db, nil)
+		if err != nil {
+			return nil, err
+		}
+		trace, err := traceBlock(config, stateDB)
+		if err != nil {
+			if len(configs) == 1 {
+				return nil, err
+			}
+			return nil, fmt.Errorf("Failed to trace block %d: %w", i, err)
+		}
+		traces[i] = trace
+		root = trace.StorageTrace.RootAfter
+	}
+	return traces, nil
+}
+
+func traceBlock(config TraceConfig, stateDB *state.StateDB) (*types.BlockTrace, error) {
 
 	chainConfig := params.ChainConfig{
 		ChainID:             new(big.Int).SetUint64(config.ChainID),
@@ -215,12 +249,7 @@ func Trace(config TraceConfig) (*types.BlockTrace, error) {
 	}
 	block := types.NewBlockWithHeader(header).WithBody(txs, nil)
 
-	trieCfg := &trie.Config{Zktrie: true}
 	// Setup state db with accounts from argument
-	stateDB, _ := state.New(
-		common.Hash{},
-		state.NewDatabaseWithConfig(rawdb.NewMemoryDatabase(), trieCfg),
-		nil)
 	for address, account := range config.Accounts {
 		stateDB.SetNonce(address, uint64(account.Nonce))
 		stateDB.SetCode(address, account.Code)
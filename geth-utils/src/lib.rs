@@ -8,6 +8,7 @@ use std::{
 
 extern "C" {
     fn CreateTrace(str: *const c_char) -> *const c_char;
+    fn CreateTraceChunk(str: *const c_char) -> *const c_char;
     fn FreeString(str: *const c_char);
     #[cfg(feature = "scroll")]
     fn CreateL2Trace(str: *const c_char) -> *const c_char;
@@ -16,37 +17,29 @@ extern "C" {
 /// Creates the l2 trace
 #[cfg(feature = "scroll")]
 pub fn l2trace(config: &str) -> Result<String, Error> {
-    // Create a string we can pass into Go
-    let c_config = CString::new(config).expect("invalid config");
-
-    // Generate the trace externally
-    let result = unsafe { CreateL2Trace(c_config.as_ptr()) };
-
-    // Convert the returned string to something we can use in Rust again.
-    // Also make sure the returned data is copied to rust managed memory.
-    let c_result = unsafe { CStr::from_ptr(result) };
-    let result = c_result
-        .to_str()
-        .expect("Error translating EVM trace from library")
-        .to_string();
-
-    // We can now free the returned string (memory managed by Go)
-    unsafe { FreeString(c_result.as_ptr()) };
-
-    // Return the trace
-    match result.is_empty() || result.starts_with("Failed") {
-        true => Err(Error::TracingError(result)),
-        false => Ok(result),
-    }
+    call_go(CreateL2Trace, config)
 }
 
 /// Creates the trace
 pub fn trace(config: &str) -> Result<String, Error> {
+    call_go(CreateTrace, config)
+}
+
+/// Creates the traces of the consecutive blocks of a chunk, from a JSON array
+/// of configs.
+pub fn trace_chunk(configs: &str) -> Result<String, Error> {
+    call_go(CreateTraceChunk, configs)
+}
+
+fn call_go(
+    create: unsafe extern "C" fn(*const c_char) -> *const c_char,
+    config: &str,
+) -> Result<String, Error> {
     // Create a string we can pass into Go
     let c_config = CString::new(config).expect("invalid config");
 
     // Generate the trace externally
-    let result = unsafe { CreateTrace(c_config.as_ptr()) };
+    let result = unsafe { create(c_config.as_ptr()) };
 
     // Convert the returned string to something we can use in Rust again.
     // Also make sure the returned data is copied to rust managed memory.
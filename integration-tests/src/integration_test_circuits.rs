@@ -5,7 +5,7 @@ use bus_mapping::{
     },
     mock::BlockData,
 };
-use eth_types::geth_types::GethData;
+use eth_types::{evm_types::GasTable, geth_types::GethData};
 use halo2_proofs::{
     dev::{CellValue, MockProver},
     halo2curves::bn256::{Bn256, Fr, G1Affine},
@@ -85,6 +85,7 @@ const CIRCUITS_PARAMS: CircuitsParams = CircuitsParams {
     max_calldata: MAX_CALLDATA,
     max_mpt_rows: MAX_CALLDATA,
     max_inner_blocks: 64,
+    max_l1_msgs_popped: 256,
     max_bytecode: MAX_BYTECODE,
     max_copy_rows: MAX_COPY_ROWS,
     max_evm_rows: MAX_EVM_ROWS,
@@ -98,6 +99,7 @@ const CIRCUITS_PARAMS: CircuitsParams = CircuitsParams {
         ec_mul: MAX_EC_MUL,
         ec_pairing: MAX_EC_PAIRING,
     },
+    gas_table: GasTable::new(),
 };
 
 const EVM_CIRCUIT_DEGREE: u32 = 18;
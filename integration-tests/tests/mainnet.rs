@@ -3,7 +3,7 @@ use bus_mapping::{
     util::read_env_var,
     Error::JSONRpcError,
 };
-use eth_types::H256;
+use eth_types::{evm_types::GasTable, H256};
 use halo2_proofs::{
     circuit::Value,
     dev::{MockProver, VerifyFailure},
@@ -32,6 +32,7 @@ const CIRCUITS_PARAMS: CircuitsParams = CircuitsParams {
     max_txs: 20,
     max_calldata: 30000,
     max_inner_blocks: 64,
+    max_l1_msgs_popped: 256,
     max_bytecode: 30000,
     max_mpt_rows: 30000,
     max_keccak_rows: 0,
@@ -45,6 +46,7 @@ const CIRCUITS_PARAMS: CircuitsParams = CircuitsParams {
         ec_mul: 10,
         ec_pairing: 4,
     },
+    gas_table: GasTable::new(),
 };
 
 #[tokio::test]
@@ -3,9 +3,15 @@
 /// The State is a 5x5 matrix of 64 bit lanes.
 pub type State = [[u64; 5]; 5];
 
-/// The number of next_inputs that are used inside the `absorb` circuit.
+/// The number of lanes of the state absorbed from each block of input.
 pub const NEXT_INPUTS_LANES: usize = 17;
 
+/// The rate of Keccak-256 in bytes, the size of each absorbed block.
+pub const RATE: usize = NEXT_INPUTS_LANES * 8;
+
+/// The length of a Keccak-256 digest in bytes.
+pub const DIGEST_LEN: usize = 32;
+
 /// The number of rounds for the 1600 bits permutation used in Keccak-256. See [here](https://github.com/Legrandin/pycryptodome/blob/016252bde04456614b68d4e4e8798bc124d91e7a/src/keccak.c#L230)
 pub const PERMUTATION: usize = 24;
 
@@ -46,4 +52,5 @@ pub static ROTATION_CONSTANTS: [[u32; 5]; 5] = [
     [27, 20, 39, 8, 14],
 ];
 
+/// The size of a lane in bits.
 pub const LANE_SIZE: u32 = 64;
@@ -0,0 +1,277 @@
+//! Streaming Keccak-256 hasher, optionally recording the per-round trace of
+//! the permutation.
+
+use crate::{
+    common::*,
+    permutation::{keccak_f, round},
+};
+
+/// The trace of the absorption of a block of [`RATE`] bytes, following the
+/// layout of the keccak circuit: one state per round of the permutation plus
+/// the state after the last round.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlockTrace {
+    /// The state before the absorption of the block.
+    pub state_in: State,
+    /// The lanes of the (padded) block, XORed into the state at `(x, y)`
+    /// with `x + 5 * y` being the index of the lane.
+    pub lanes: [u64; NEXT_INPUTS_LANES],
+    /// The number of bytes of the block coming from the input, the rest
+    /// being padding. Only the last block has padding.
+    pub num_data_bytes: usize,
+    /// `rounds[i]` is the state at the start of the i-th round, so
+    /// `rounds[0]` is the state right after the absorption and
+    /// `rounds[PERMUTATION]` the state after the permutation.
+    pub rounds: [State; PERMUTATION + 1],
+}
+
+/// Keccak-256 hasher absorbing the input by blocks of [`RATE`] bytes as it is
+/// fed with [`Keccak::update`].
+#[derive(Clone, Debug)]
+pub struct Keccak {
+    state: State,
+    /// The input not absorbed yet, always shorter than a block.
+    buffer: Vec<u8>,
+    /// The trace of the absorbed blocks, if recorded.
+    blocks: Option<Vec<BlockTrace>>,
+}
+
+impl Default for Keccak {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Keccak {
+    /// Creates a hasher with the empty input.
+    pub fn new() -> Self {
+        Self {
+            state: [[0; 5]; 5],
+            buffer: Vec::with_capacity(RATE),
+            blocks: None,
+        }
+    }
+
+    /// Creates a hasher recording the [`BlockTrace`] of every absorbed block.
+    pub fn with_trace() -> Self {
+        Self {
+            blocks: Some(Vec::new()),
+            ..Self::new()
+        }
+    }
+
+    /// Feeds the hasher with more input, absorbing all the completed blocks.
+    pub fn update(&mut self, mut input: &[u8]) {
+        if !self.buffer.is_empty() {
+            let len = (RATE - self.buffer.len()).min(input.len());
+            self.buffer.extend_from_slice(&input[..len]);
+            input = &input[len..];
+            if self.buffer.len() < RATE {
+                return;
+            }
+            let block = std::mem::take(&mut self.buffer);
+            self.absorb(&block, RATE);
+            self.buffer = block;
+            self.buffer.clear();
+        }
+
+        let mut blocks = input.chunks_exact(RATE);
+        for block in &mut blocks {
+            self.absorb(block, RATE);
+        }
+        self.buffer.extend_from_slice(blocks.remainder());
+    }
+
+    /// Pads the remaining input and returns the digest.
+    pub fn finalize(self) -> [u8; DIGEST_LEN] {
+        self.finalize_with_trace().0
+    }
+
+    /// Pads the remaining input and returns the digest along with the trace
+    /// of all the absorbed blocks, which is empty unless the hasher was
+    /// created with [`Keccak::with_trace`].
+    pub fn finalize_with_trace(mut self) -> ([u8; DIGEST_LEN], Vec<BlockTrace>) {
+        // pad10*1, the last block has at least one byte of padding.
+        let num_data_bytes = self.buffer.len();
+        let mut block = std::mem::take(&mut self.buffer);
+        block.resize(RATE, 0);
+        block[num_data_bytes] ^= 0x01;
+        block[RATE - 1] ^= 0x80;
+        self.absorb(&block, num_data_bytes);
+
+        let mut digest = [0u8; DIGEST_LEN];
+        for (bytes, lane) in digest.chunks_exact_mut(8).zip(self.state.iter()) {
+            bytes.copy_from_slice(&lane[0].to_le_bytes());
+        }
+        (digest, self.blocks.unwrap_or_default())
+    }
+
+    /// Absorbs a padded block and applies the permutation.
+    fn absorb(&mut self, block: &[u8], num_data_bytes: usize) {
+        debug_assert_eq!(block.len(), RATE);
+
+        let state_in = self.state;
+        let lanes: [u64; NEXT_INPUTS_LANES] = std::array::from_fn(|i| {
+            u64::from_le_bytes(block[i * 8..(i + 1) * 8].try_into().unwrap())
+        });
+        for (i, lane) in lanes.iter().enumerate() {
+            self.state[i % 5][i / 5] ^= lane;
+        }
+
+        match self.blocks.as_mut() {
+            Some(blocks) => {
+                let mut rounds = [self.state; PERMUTATION + 1];
+                for (i, rc) in ROUND_CONSTANTS.iter().enumerate() {
+                    self.state = round(self.state, *rc);
+                    rounds[i + 1] = self.state;
+                }
+                blocks.push(BlockTrace {
+                    state_in,
+                    lanes,
+                    num_data_bytes,
+                    rounds,
+                });
+            }
+            None => keccak_f(&mut self.state),
+        }
+    }
+}
+
+/// Returns the Keccak-256 digest of `input`.
+pub fn keccak256(input: &[u8]) -> [u8; DIGEST_LEN] {
+    let mut hasher = Keccak::new();
+    hasher.update(input);
+    hasher.finalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use rand::{Rng, RngCore};
+    use sha3::{Digest, Keccak256};
+
+    /// Hashes `msg` at once and byte by byte, checking both agree.
+    fn hash(msg: &[u8]) -> [u8; DIGEST_LEN] {
+        let a = keccak256(msg);
+
+        let mut keccak = Keccak::new();
+        for byte in msg {
+            keccak.update(&[*byte]);
+        }
+        assert_eq!(a, keccak.finalize());
+
+        a
+    }
+
+    #[test]
+    fn test_empty_input() {
+        let output = [
+            197, 210, 70, 1, 134, 247, 35, 60, 146, 126, 125, 178, 220, 199, 3, 192, 229, 0, 182,
+            83, 202, 130, 39, 59, 123, 250, 216, 4, 93, 133, 164, 112,
+        ];
+        assert_eq!(hash(&[]), output);
+    }
+
+    #[test]
+    fn test_short_input() {
+        let output = [
+            56, 209, 138, 203, 103, 210, 92, 139, 185, 148, 39, 100, 182, 47, 24, 225, 112, 84,
+            246, 106, 129, 123, 212, 41, 84, 35, 173, 249, 237, 152, 135, 62,
+        ];
+        assert_eq!(hash(&[102, 111, 111, 98, 97, 114]), output);
+    }
+
+    #[test]
+    fn test_long_input() {
+        let input = b"Alice was beginning to get very tired of sitting by her sister on the \
+            bank, and of having nothing to do: once or twice she had peeped into the book her \
+            sister was reading, but it had no pictures or conversations in it, and what is the \
+            use of a book, thought Alice without pictures or conversations?";
+        let output = [
+            60, 227, 142, 8, 143, 135, 108, 85, 13, 254, 190, 58, 30, 106, 153, 194, 188, 6, 208,
+            49, 16, 102, 150, 120, 100, 130, 224, 177, 64, 98, 53, 252,
+        ];
+        assert_eq!(hash(input), output);
+    }
+
+    #[test]
+    fn test_block_boundaries() {
+        let mut rng = rand::thread_rng();
+        for len in [
+            1,
+            RATE - 1,
+            RATE,
+            RATE + 1,
+            2 * RATE - 1,
+            2 * RATE,
+            5 * RATE + 7,
+        ] {
+            let mut input = vec![0u8; len];
+            rng.fill_bytes(&mut input);
+            assert_eq!(hash(&input), Keccak256::digest(&input).as_slice());
+        }
+    }
+
+    #[test]
+    fn test_random_updates() {
+        let mut rng = rand::thread_rng();
+        let mut input = vec![0u8; 4 * RATE + 42];
+        rng.fill_bytes(&mut input);
+
+        for _ in 0..16 {
+            let mut keccak = Keccak::new();
+            let mut rest = input.as_slice();
+            while !rest.is_empty() {
+                let (chunk, tail) = rest.split_at(rng.gen_range(0..=rest.len().min(2 * RATE)));
+                keccak.update(chunk);
+                rest = tail;
+            }
+            assert_eq!(keccak.finalize(), keccak256(&input));
+        }
+    }
+
+    #[test]
+    fn test_trace() {
+        let mut rng = rand::thread_rng();
+        for len in [0, RATE - 1, RATE, 3 * RATE + 5] {
+            let mut input = vec![0u8; len];
+            rng.fill_bytes(&mut input);
+
+            let mut keccak = Keccak::with_trace();
+            keccak.update(&input);
+            let (digest, blocks) = keccak.finalize_with_trace();
+            assert_eq!(digest, keccak256(&input));
+            assert_eq!(blocks.len(), len / RATE + 1);
+
+            let mut state = [[0; 5]; 5];
+            for (idx, block) in blocks.iter().enumerate() {
+                let is_final_block = idx == blocks.len() - 1;
+                let num_data_bytes = if is_final_block { len % RATE } else { RATE };
+                assert_eq!(block.num_data_bytes, num_data_bytes);
+
+                // the data of the block followed by the padding
+                let bytes = block
+                    .lanes
+                    .iter()
+                    .flat_map(|lane| lane.to_le_bytes())
+                    .collect::<Vec<_>>();
+                assert_eq!(
+                    bytes[..num_data_bytes],
+                    input[idx * RATE..idx * RATE + num_data_bytes]
+                );
+
+                // the trace chains the absorption and the rounds
+                assert_eq!(block.state_in, state);
+                for (i, lane) in block.lanes.iter().enumerate() {
+                    state[i % 5][i / 5] ^= lane;
+                }
+                assert_eq!(block.rounds[0], state);
+                for (i, rc) in ROUND_CONSTANTS.iter().enumerate() {
+                    state = round(state, *rc);
+                    assert_eq!(block.rounds[i + 1], state);
+                }
+            }
+        }
+    }
+}
@@ -1,10 +1,11 @@
-// Leave here until #105 uses all the functions that now are
-// just used in tests
+//! Reference implementation of the Keccak-256 hash function.
+//!
+//! The [`Keccak`] hasher can record the state of the permutation at every
+//! round, which is the witness laid out by the keccak circuit. The circuit
+//! checks its own witness generation against it, so both stay in lock-step.
 
-pub mod arith_helpers;
 pub mod common;
-pub mod gate_helpers;
-// We build arith module to get test cases for the circuit
-pub mod keccak_arith;
-// We build plain module for the purpose of reviewing the circuit
-pub mod plain;
+pub mod hasher;
+pub mod permutation;
+
+pub use hasher::{keccak256, BlockTrace, Keccak};
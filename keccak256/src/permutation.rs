@@ -0,0 +1,65 @@
+//! The Keccak-f\[1600\] permutation and the steps of its rounds.
+
+use crate::common::*;
+use itertools::Itertools;
+
+/// Applies the 24 rounds of the permutation to the state.
+pub fn keccak_f(a: &mut State) {
+    for rc in ROUND_CONSTANTS.iter() {
+        *a = round(*a, *rc);
+    }
+}
+
+/// A single round of the permutation, with the round constant `rc`.
+pub fn round(a: State, rc: u64) -> State {
+    iota(chi(pi(rho(theta(a)))), rc)
+}
+
+/// XORs each lane with the parities of two neighbouring columns.
+pub fn theta(a: State) -> State {
+    let mut c: [u64; 5] = [0; 5];
+    let mut out: State = [[0; 5]; 5];
+
+    for x in 0..5 {
+        c[x] = a[x][0] ^ a[x][1] ^ a[x][2] ^ a[x][3] ^ a[x][4];
+    }
+
+    for (x, y) in (0..5).cartesian_product(0..5) {
+        out[x][y] = a[x][y] ^ c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1);
+    }
+    out
+}
+
+/// Rotates each lane by its rotation offset.
+pub fn rho(a: State) -> State {
+    let mut out: State = [[0; 5]; 5];
+    for (x, y) in (0..5).cartesian_product(0..5) {
+        out[x][y] = a[x][y].rotate_left(ROTATION_CONSTANTS[x][y]);
+    }
+    out
+}
+
+/// Moves the lane at `(x, y)` to `(y, 2x + 3y)`.
+pub fn pi(a: State) -> State {
+    let mut out: State = [[0; 5]; 5];
+    for (x, y) in (0..5).cartesian_product(0..5) {
+        out[y][(2 * x + 3 * y) % 5] = a[x][y];
+    }
+    out
+}
+
+/// The only non-linear step, combining each lane with the next two of its row.
+pub fn chi(a: State) -> State {
+    let mut out: State = [[0; 5]; 5];
+    for (x, y) in (0..5).cartesian_product(0..5) {
+        out[x][y] = a[x][y] ^ (!a[(x + 1) % 5][y] & a[(x + 2) % 5][y]);
+    }
+    out
+}
+
+/// XORs the round constant into the first lane.
+pub fn iota(a: State, rc: u64) -> State {
+    let mut out = a;
+    out[0][0] ^= rc;
+    out
+}
@@ -23,7 +23,7 @@ pub struct MockBlock {
     number: U64,
     gas_used: Word,
     gas_limit: Word,
-    base_fee_per_gas: Word,
+    pub(crate) base_fee_per_gas: Word,
     extra_data: Bytes,
     logs_bloom: Option<Bloom>,
     timestamp: Word,
@@ -8,12 +8,14 @@ use rand_chacha::ChaCha20Rng;
 use std::sync::LazyLock;
 mod account;
 mod block;
+#[cfg(feature = "proptest")]
+pub mod strategy;
 pub mod test_ctx;
 mod transaction;
 
 pub(crate) use account::MockAccount;
 pub(crate) use block::MockBlock;
-pub use test_ctx::TestContext;
+pub use test_ctx::{ChunkTestContext, TestContext};
 pub use transaction::{AddrOrWallet, MockTransaction, CORRECT_MOCK_TXS};
 
 /// Mock block gas limit
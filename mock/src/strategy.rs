@@ -0,0 +1,422 @@
+//! [`proptest`] strategies to generate random but valid mock accounts,
+//! transactions and blocks.
+//!
+//! The generated transactions are signed, and [`test_ctx_inputs`] generates
+//! whole blocks which are internally consistent: the senders can afford their
+//! transactions, whose nonces are correlative and whose gas covers their
+//! intrinsic gas. All the values, including the keys of the wallets, are
+//! derived from the proptest rng, so that a failing case is reproducible and
+//! can be shrunk.
+//!
+//! ## Example
+//! ```rust,no_run
+//! use mock::strategy::test_ctx_inputs;
+//! use proptest::{
+//!     strategy::{Strategy, ValueTree},
+//!     test_runner::TestRunner,
+//! };
+//!
+//! let mut runner = TestRunner::deterministic();
+//! let inputs = test_ctx_inputs::<3, 2>()
+//!     .new_tree(&mut runner)
+//!     .unwrap()
+//!     .current();
+//! let ctx = inputs.test_ctx().unwrap();
+//! assert_eq!(ctx.eth_block.transactions.len(), 2);
+//! ```
+
+use crate::{gwei, MockAccount, MockBlock, MockTransaction, TestContext, MOCK_CODES};
+use eth_types::{
+    chain_spec::ChainRules,
+    evm_types::{
+        gas_utils::{tx_access_list_gas_cost, tx_data_gas_cost},
+        GasCost,
+    },
+    AccessList, AccessListItem, Address, Bytes, Error, Hash, Word,
+};
+use ethers_core::types::{
+    transaction::eip2718::TypedTransaction, Eip1559TransactionRequest, TransactionRequest,
+};
+use ethers_signers::{LocalWallet, Signer};
+use itertools::Itertools;
+use proptest::{
+    collection::{hash_map, vec},
+    option,
+    prelude::*,
+    sample::select,
+};
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+
+/// Max length of the call data of a transaction.
+pub const MAX_CALLDATA_LEN: usize = 1 << 15;
+/// Max length of the call data of a contract creation, i.e. its init code.
+pub const MAX_INIT_CODE_LEN: usize = 1 << 10;
+/// Max gas given to a transaction on top of its intrinsic gas.
+pub const MAX_EXTRA_GAS: u64 = 1_000_000;
+
+/// Strategy of a [`Word`], biased towards the edge values.
+pub fn word() -> impl Strategy<Value = Word> {
+    prop_oneof![
+        Just(Word::zero()),
+        Just(Word::one()),
+        Just(Word::MAX),
+        any::<u64>().prop_map(Word::from),
+        any::<[u8; 32]>().prop_map(|bytes| Word::from_big_endian(&bytes)),
+    ]
+}
+
+/// Strategy of an [`Address`].
+pub fn address() -> impl Strategy<Value = Address> {
+    any::<[u8; 20]>().prop_map(Address::from)
+}
+
+/// Strategy of a [`Hash`].
+pub fn hash() -> impl Strategy<Value = Hash> {
+    any::<[u8; 32]>().prop_map(Hash::from)
+}
+
+/// Strategy of a [`LocalWallet`], whose key is derived from a generated seed.
+pub fn wallet() -> impl Strategy<Value = LocalWallet> {
+    any::<[u8; 32]>().prop_map(|seed| LocalWallet::new(&mut ChaCha20Rng::from_seed(seed)))
+}
+
+/// Strategy of an account balance.
+pub fn balance() -> impl Strategy<Value = Word> {
+    prop_oneof![
+        Just(Word::zero()),
+        any::<u64>().prop_map(Word::from),
+        any::<u128>().prop_map(Word::from),
+    ]
+}
+
+/// Strategy of an account nonce, including the max nonce `u64::MAX`.
+pub fn nonce() -> impl Strategy<Value = u64> {
+    prop_oneof![Just(0u64), 0..1024u64, Just(u64::MAX)]
+}
+
+/// Strategy of an account code: either empty, one of [`MOCK_CODES`] or random
+/// bytes.
+pub fn code() -> impl Strategy<Value = Bytes> {
+    prop_oneof![
+        Just(Bytes::default()),
+        select(MOCK_CODES.clone()),
+        vec(any::<u8>(), 1..=256).prop_map(Bytes::from),
+    ]
+}
+
+/// Strategy of the call data of a transaction: either empty, short or up to
+/// `max_len` bytes, mostly zeros.
+pub fn calldata(max_len: usize) -> impl Strategy<Value = Bytes> {
+    prop_oneof![
+        Just(Bytes::default()),
+        vec(any::<u8>(), 1..=max_len.min(64)).prop_map(Bytes::from),
+        vec(prop_oneof![3 => Just(0u8), 1 => any::<u8>()], 1..=max_len).prop_map(Bytes::from),
+    ]
+}
+
+/// Strategy of an EIP-2930 access list.
+pub fn access_list() -> impl Strategy<Value = AccessList> {
+    vec((address(), vec(hash(), 0..4)), 0..4).prop_map(|items| {
+        AccessList(
+            items
+                .into_iter()
+                .map(|(address, storage_keys)| AccessListItem {
+                    address,
+                    storage_keys,
+                })
+                .collect(),
+        )
+    })
+}
+
+prop_compose! {
+    /// Strategy of an account with an arbitrary nonce, balance, code and
+    /// storage.
+    pub fn account()(
+        address in address(),
+        nonce in nonce(),
+        balance in balance(),
+        code in code(),
+        storage in hash_map(
+            word(),
+            word().prop_filter("empty slot", |value| !value.is_zero()),
+            0..4,
+        ),
+    ) -> MockAccount {
+        MockAccount::default()
+            .address(address)
+            .nonce(nonce.into())
+            .balance(balance)
+            .code(code)
+            .storage(storage.into_iter())
+            .build()
+    }
+}
+
+prop_compose! {
+    /// Strategy of a block header, without transactions.
+    pub fn block()(
+        number in prop_oneof![Just(0u64), 1..=u64::from(u32::MAX)],
+        hash in hash(),
+        parent_hash in hash(),
+        author in address(),
+        timestamp in prop_oneof![Just(0u64), any::<u32>().prop_map(u64::from)],
+        base_fee in prop_oneof![Just(Word::zero()), (1..=100u64).prop_map(gwei)],
+    ) -> MockBlock {
+        MockBlock::default()
+            .number(number)
+            .hash(hash)
+            .parent_hash(parent_hash)
+            .author(author)
+            .timestamp(timestamp.into())
+            .base_fee_per_gas(base_fee)
+            .build()
+    }
+}
+
+/// The parameters of a transaction which do not depend on its sender, its
+/// receiver nor its block.
+#[derive(Debug, Clone)]
+struct TxParams {
+    tx_type: u64,
+    value: Word,
+    input: Bytes,
+    access_list: AccessList,
+    extra_gas: u64,
+    priority_fee: Word,
+    fee_cap_margin: Word,
+}
+
+prop_compose! {
+    fn tx_params(is_create: bool)(
+        tx_type in 0..=2u64,
+        value in prop_oneof![Just(Word::zero()), (1..=1_000_000u64).prop_map(gwei)],
+        input in calldata(if is_create { MAX_INIT_CODE_LEN } else { MAX_CALLDATA_LEN }),
+        access_list in access_list(),
+        extra_gas in prop_oneof![Just(0u64), 0..=MAX_EXTRA_GAS],
+        priority_fee in prop_oneof![Just(Word::zero()), (1..=10u64).prop_map(gwei)],
+        fee_cap_margin in prop_oneof![Just(Word::zero()), (1..=10u64).prop_map(gwei)],
+    ) -> TxParams {
+        TxParams {
+            tx_type,
+            value,
+            input,
+            access_list,
+            extra_gas,
+            priority_fee,
+            fee_cap_margin,
+        }
+    }
+}
+
+impl TxParams {
+    /// Build and sign the transaction, whose fees are valid under the base fee.
+    fn build(
+        self,
+        from: &LocalWallet,
+        to: Option<Address>,
+        nonce: u64,
+        base_fee: Word,
+    ) -> MockTransaction {
+        let access_list = if self.tx_type == 0 {
+            AccessList::default()
+        } else {
+            self.access_list
+        };
+        let mut intrinsic_gas =
+            tx_data_gas_cost(&self.input) + tx_access_list_gas_cost(&Some(access_list.clone()));
+        intrinsic_gas += if to.is_some() {
+            GasCost::TX.as_u64()
+        } else {
+            GasCost::CREATION_TX.as_u64()
+                + self.input.len().div_ceil(32) as u64 * ChainRules::default().init_code_word_gas()
+        };
+
+        let mut tx = MockTransaction::default();
+        tx.from(from.clone())
+            .nonce(nonce.into())
+            .value(self.value)
+            .gas((intrinsic_gas + self.extra_gas).into())
+            .input(self.input)
+            .transaction_type(self.tx_type)
+            .access_list(access_list);
+        if let Some(to) = to {
+            tx.to(to);
+        }
+        if self.tx_type == 2 {
+            let max_fee_per_gas = base_fee + self.fee_cap_margin;
+            tx.max_fee_per_gas(max_fee_per_gas)
+                .max_priority_fee_per_gas(self.priority_fee.min(max_fee_per_gas));
+        } else {
+            tx.gas_price(base_fee + self.priority_fee);
+        }
+        // `MockTransaction::build` signs the legacy tx, so the typed txs are
+        // signed here.
+        let sig = from
+            .clone()
+            .with_chain_id(tx.chain_id)
+            .sign_transaction_sync(&typed_transaction(&tx, to))
+            .expect("sign mock tx");
+        tx.sig_data((sig.v, sig.r, sig.s)).build()
+    }
+}
+
+/// The typed transaction of `tx`, as signed by its sender.
+fn typed_transaction(tx: &MockTransaction, to: Option<Address>) -> TypedTransaction {
+    match tx.transaction_type.as_u64() {
+        2 => {
+            let request = Eip1559TransactionRequest::new()
+                .chain_id(tx.chain_id)
+                .from(tx.from.address())
+                .nonce(tx.nonce)
+                .value(tx.value)
+                .data(tx.input.clone())
+                .gas(tx.gas)
+                .access_list(tx.access_list.clone())
+                .max_priority_fee_per_gas(tx.max_priority_fee_per_gas)
+                .max_fee_per_gas(tx.max_fee_per_gas);
+            match to {
+                Some(to) => request.to(to).into(),
+                None => request.into(),
+            }
+        }
+        tx_type => {
+            let request = TransactionRequest::new()
+                .chain_id(tx.chain_id)
+                .from(tx.from.address())
+                .nonce(tx.nonce)
+                .value(tx.value)
+                .data(tx.input.clone())
+                .gas(tx.gas)
+                .gas_price(tx.gas_price.unwrap_or_default());
+            let request = match to {
+                Some(to) => request.to(to),
+                None => request,
+            };
+            if tx_type == 1 {
+                request.with_access_list(tx.access_list.clone()).into()
+            } else {
+                request.into()
+            }
+        }
+    }
+}
+
+/// The max amount of wei that a transaction can cost to its sender.
+fn max_cost(tx: &MockTransaction) -> Word {
+    tx.gas * tx.gas_price.unwrap_or(tx.max_fee_per_gas) + tx.value
+}
+
+/// Strategy of a transaction of any of the legacy, EIP-2930 and EIP-1559
+/// types, signed by `from` and whose fees are valid under `base_fee`. The
+/// receiver is either a random address or none for a contract creation.
+pub fn transaction(
+    from: LocalWallet,
+    nonce: u64,
+    base_fee: Word,
+) -> impl Strategy<Value = MockTransaction> {
+    option::of(address())
+        .prop_flat_map(|to| (Just(to), tx_params(to.is_none())))
+        .prop_map(move |(to, params)| params.build(&from, to, nonce, base_fee))
+}
+
+/// The accounts, transactions and block generated by [`test_ctx_inputs`] to
+/// build a [`TestContext`].
+#[derive(Debug, Clone)]
+pub struct TestContextInputs<const NACC: usize, const NTX: usize> {
+    /// Accounts, the senders of the transactions being the first ones
+    pub accounts: [MockAccount; NACC],
+    /// Signed transactions, with their index in the block set
+    pub transactions: [MockTransaction; NTX],
+    /// Block header
+    pub block: MockBlock,
+}
+
+impl<const NACC: usize, const NTX: usize> TestContextInputs<NACC, NTX> {
+    /// Trace the transactions of the block over the state of the accounts.
+    pub fn test_ctx(self) -> Result<TestContext<NACC, NTX>, Error> {
+        let Self {
+            accounts,
+            transactions,
+            block,
+        } = self;
+        TestContext::new(
+            None,
+            |accs| {
+                for (acc, account) in accs.into_iter().zip(accounts) {
+                    *acc = account;
+                }
+            },
+            |txs, _accs| {
+                for (tx, transaction) in txs.into_iter().zip(transactions) {
+                    *tx = transaction;
+                }
+            },
+            |mock_block, _txs| {
+                let transactions = std::mem::take(&mut mock_block.transactions);
+                *mock_block = block;
+                mock_block.transactions(transactions)
+            },
+        )
+    }
+}
+
+/// Strategy of the inputs of a [`TestContext`] with `NACC` accounts and `NTX`
+/// transactions. The transactions are sent by the first accounts, which are
+/// funded to afford all of them, and are addressed to any of the accounts or
+/// create a contract.
+pub fn test_ctx_inputs<const NACC: usize, const NTX: usize>(
+) -> impl Strategy<Value = TestContextInputs<NACC, NTX>> {
+    assert!(NACC > 0, "the transactions need a sender account");
+
+    (
+        block(),
+        vec(account(), NACC),
+        vec(wallet(), NACC),
+        vec(
+            (0..NACC, option::of(0..NACC))
+                .prop_flat_map(|(from, to)| (Just(from), Just(to), tx_params(to.is_none()))),
+            NTX,
+        ),
+    )
+        .prop_map(|(block, mut accounts, wallets, txs)| {
+            let num_senders = txs.iter().map(|(from, _, _)| from + 1).max().unwrap_or(0);
+            for (account, wallet) in accounts.iter_mut().zip(wallets.iter()).take(num_senders) {
+                // leave room for the nonces of the transactions, which must
+                // be lower than the max nonce
+                let nonce = account.nonce.min((u64::MAX - 1 - NTX as u64).into());
+                // EIP-3607: the senders can't have code
+                account
+                    .address(wallet.address())
+                    .nonce(nonce)
+                    .code(Bytes::default());
+            }
+
+            let mut nonces = accounts
+                .iter()
+                .map(|account| account.nonce.as_u64())
+                .collect_vec();
+            let transactions = txs
+                .into_iter()
+                .enumerate()
+                .map(|(idx, (from, to, params))| {
+                    let to = to.map(|to| accounts[to].address);
+                    let mut tx =
+                        params.build(&wallets[from], to, nonces[from], block.base_fee_per_gas);
+                    tx.transaction_idx(idx as u64);
+                    nonces[from] += 1;
+
+                    let balance = accounts[from].balance.saturating_add(max_cost(&tx));
+                    accounts[from].balance(balance);
+                    tx
+                })
+                .collect_vec();
+
+            TestContextInputs {
+                accounts: accounts.try_into().expect("Mismatched acc len"),
+                transactions: transactions.try_into().expect("Mismatched tx len"),
+                block,
+            }
+        })
+}
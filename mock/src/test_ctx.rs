@@ -4,6 +4,7 @@ use crate::{eth, MockAccount, MockBlock, MockTransaction, MOCK_WALLETS};
 #[cfg(feature = "scroll")]
 use eth_types::l2_types::BlockTrace;
 use eth_types::{
+    chain_spec::ChainSpec,
     geth_types::{Account, BlockConstants, GethData},
     BigEndianHash, Block, Bytecode, Error, Transaction, Word, H256,
 };
@@ -15,6 +16,11 @@ use external_tracer::TraceConfig;
 use helpers::*;
 use itertools::Itertools;
 
+mod chunk;
+#[cfg(all(test, feature = "geth-tracer", feature = "native-tracer"))]
+mod differential_tests;
+
+pub use chunk::ChunkTestContext;
 pub use external_tracer::LoggerConfig;
 
 /// TestContext is a type that contains all the information from a block
@@ -94,6 +100,8 @@ pub struct TestContext<const NACC: usize, const NTX: usize> {
     pub eth_block: eth_types::Block<eth_types::Transaction>,
     /// Execution Trace from geth
     pub geth_traces: Vec<eth_types::GethExecTrace>,
+    /// Hardfork schedule of the chain
+    pub chain_spec: ChainSpec,
     #[cfg(feature = "scroll")]
     block_trace: BlockTrace,
 }
@@ -106,6 +114,7 @@ impl<const NACC: usize, const NTX: usize> From<TestContext<NACC, NTX>> for GethD
             eth_block: ctx.eth_block,
             geth_traces: ctx.geth_traces.to_vec(),
             accounts: ctx.accounts.into(),
+            chain_spec: ctx.chain_spec,
             #[cfg(feature = "scroll")]
             block_trace: ctx.block_trace,
         }
@@ -120,6 +129,31 @@ impl<const NACC: usize, const NTX: usize> TestContext<NACC, NTX> {
         func_block: Fb,
         logger_config: LoggerConfig,
     ) -> Result<Self, Error>
+    where
+        FTx: FnOnce(Vec<&mut MockTransaction>, [MockAccount; NACC]),
+        Fb: FnOnce(&mut MockBlock, Vec<MockTransaction>) -> &mut MockBlock,
+        FAcc: FnOnce([&mut MockAccount; NACC]),
+    {
+        Self::new_with_chain_spec(
+            ChainSpec::default(),
+            history_hashes,
+            acc_fns,
+            func_tx,
+            func_block,
+            logger_config,
+        )
+    }
+
+    /// Create a new TestContext as [`TestContext::new_with_logger_config`],
+    /// tracing the block under the rules `chain_spec` selects for it.
+    pub fn new_with_chain_spec<FAcc, FTx, Fb>(
+        chain_spec: ChainSpec,
+        history_hashes: Option<Vec<Word>>,
+        acc_fns: FAcc,
+        func_tx: FTx,
+        func_block: Fb,
+        logger_config: LoggerConfig,
+    ) -> Result<Self, Error>
     where
         FTx: FnOnce(Vec<&mut MockTransaction>, [MockAccount; NACC]),
         Fb: FnOnce(&mut MockBlock, Vec<MockTransaction>) -> &mut MockBlock,
@@ -183,6 +217,7 @@ impl<const NACC: usize, const NTX: usize> TestContext<NACC, NTX> {
             .expect("Mismatched acc len");
 
         let trace_config = gen_trace_config(
+            &chain_spec,
             chain_id,
             block.clone(),
             accounts.to_vec(),
@@ -210,6 +245,7 @@ impl<const NACC: usize, const NTX: usize> TestContext<NACC, NTX> {
             history_hashes: history_hashes.unwrap_or_default(),
             eth_block: block,
             geth_traces,
+            chain_spec,
             #[cfg(feature = "scroll")]
             block_trace,
         })
@@ -265,18 +301,21 @@ impl<const NACC: usize, const NTX: usize> TestContext<NACC, NTX> {
 }
 
 /// Generates config to generating execution traces for the transactions included in the provided
-/// Block
+/// Block, under the rules `chain_spec` selects for it
 pub fn gen_trace_config(
+    chain_spec: &ChainSpec,
     chain_id: u64,
     block: Block<Transaction>,
     accounts: Vec<Account>,
     history_hashes: Option<Vec<Word>>,
     logger_config: LoggerConfig,
 ) -> Result<TraceConfig, Error> {
+    let block_constants = BlockConstants::try_from(&block)?;
     Ok(TraceConfig {
         chain_id,
         history_hashes: history_hashes.unwrap_or_default(),
-        block_constants: BlockConstants::try_from(&block)?,
+        chain_config: external_tracer::ChainConfig::from_chain_spec(chain_spec, &block_constants)?,
+        block_constants,
         accounts: accounts
             .iter()
             .map(|account| (account.address, account.clone()))
@@ -287,10 +326,6 @@ pub fn gen_trace_config(
             .map(eth_types::geth_types::Transaction::from)
             .collect(),
         logger_config,
-        #[cfg(feature = "shanghai")]
-        chain_config: Some(external_tracer::ChainConfig::shanghai()),
-        #[cfg(not(feature = "shanghai"))]
-        chain_config: None,
         #[cfg(feature = "scroll")]
         l1_queue_index: 0,
     })
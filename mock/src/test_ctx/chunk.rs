@@ -0,0 +1,343 @@
+//! Mock types and functions to generate the multi-block Test enviroments of a
+//! chunk
+
+use super::{gen_trace_config, LoggerConfig};
+use crate::{MockAccount, MockBlock, MockTransaction};
+use eth_types::{
+    chain_spec::ChainSpec,
+    geth_types::{Account, GethData},
+    BigEndianHash, Block, Error, GethExecTrace, Transaction, Word, H256,
+};
+#[cfg(feature = "scroll")]
+use eth_types::{geth_types::TxType, l2_types::BlockTrace};
+#[cfg(feature = "scroll")]
+use external_tracer::l2trace_chunk;
+#[cfg(not(feature = "scroll"))]
+use external_tracer::trace_chunk;
+use itertools::Itertools;
+
+/// Number of the most recent block hashes accessible with `BLOCKHASH`.
+const NUM_HISTORY_HASHES: usize = 256;
+
+/// ChunkTestContext is the multi-block counterpart of
+/// [`TestContext`](super::TestContext): it contains the information of the
+/// consecutive blocks of a chunk, each block being executed on top of the
+/// state left by the previous one.
+///
+/// By default, the blocks are numbered consecutively after the first one and
+/// each block is the child of the previous one. The hashes of the previous
+/// blocks of the chunk are appended to the history hashes of the following
+/// ones, so the block numbers must stay consecutive when overridden. The
+/// nonces of the transactions are correlative across the whole chunk, while
+/// their indexes are correlative within each block.
+///
+/// The context converts into the [`GethData`] of each block, where only the
+/// first block has accounts: they are the state before the chunk.
+///
+/// ## Example
+/// ```rust
+/// use eth_types::{bytecode, geth_types::GethData, H256};
+/// use mock::test_ctx::{helpers::*, ChunkTestContext};
+///
+/// let code = bytecode! {
+///     NUMBER
+///     BLOCKHASH
+///     POP
+///     STOP
+/// };
+///
+/// // Two blocks with 1 and 2 txs calling the code.
+/// let ctx = ChunkTestContext::<2>::new(
+///     None,
+///     account_0_code_account_1_no_code(code),
+///     &[1, 2],
+///     |blocks_txs, accs| {
+///         for tx in blocks_txs.into_iter().flatten() {
+///             tx.from(accs[1].address).to(accs[0].address);
+///         }
+///     },
+///     |idx, block, _txs| block.hash(H256::from_low_u64_be(idx as u64 + 1)),
+/// )
+/// .unwrap();
+///
+/// let blocks: Vec<GethData> = ctx.into();
+/// assert_eq!(blocks.len(), 2);
+/// assert_eq!(blocks[1].history_hashes, vec![1.into()]);
+/// ```
+#[derive(Debug)]
+pub struct ChunkTestContext<const NACC: usize> {
+    /// chain id
+    pub chain_id: u64,
+    /// Account list, the state before the first block
+    pub accounts: [Account; NACC],
+    /// history hashes contains most recent 256 block hashes before the chunk,
+    /// where the lastest one is at history_hashes[history_hashes.len() - 1].
+    pub history_hashes: Vec<Word>,
+    /// Blocks of the chunk
+    pub eth_blocks: Vec<Block<Transaction>>,
+    /// Execution Trace from geth, of each block
+    pub geth_traces: Vec<Vec<GethExecTrace>>,
+    /// Hardfork schedule of the chain
+    pub chain_spec: ChainSpec,
+    block_history_hashes: Vec<Vec<Word>>,
+    #[cfg(feature = "scroll")]
+    block_traces: Vec<BlockTrace>,
+}
+
+impl<const NACC: usize> From<ChunkTestContext<NACC>> for Vec<GethData> {
+    fn from(ctx: ChunkTestContext<NACC>) -> Vec<GethData> {
+        #[cfg(feature = "scroll")]
+        let block_traces = ctx.block_traces;
+        #[cfg(not(feature = "scroll"))]
+        let block_traces = vec![(); ctx.eth_blocks.len()];
+
+        ctx.eth_blocks
+            .into_iter()
+            .zip_eq(ctx.geth_traces)
+            .zip_eq(ctx.block_history_hashes)
+            .zip_eq(block_traces)
+            .enumerate()
+            .map(
+                |(idx, (((eth_block, geth_traces), history_hashes), _block_trace))| GethData {
+                    chain_id: ctx.chain_id,
+                    history_hashes,
+                    eth_block,
+                    geth_traces,
+                    accounts: if idx == 0 {
+                        ctx.accounts.to_vec()
+                    } else {
+                        vec![]
+                    },
+                    chain_spec: ctx.chain_spec.clone(),
+                    #[cfg(feature = "scroll")]
+                    block_trace: _block_trace,
+                },
+            )
+            .collect()
+    }
+}
+
+impl<const NACC: usize> ChunkTestContext<NACC> {
+    /// Create a new ChunkTestContext with `num_txs.len()` blocks, which
+    /// starts with `NACC` default accounts and `num_txs[i]` default
+    /// transactions in the i-th block. As in
+    /// [`TestContext::new`](super::TestContext::new), `acc_fns` is then
+    /// applied to the accounts, `func_tx` to the transactions of all the
+    /// blocks and `func_block` to each block along with its index. Finally the
+    /// blocks are traced one after the other.
+    pub fn new_with_logger_config<FAcc, FTx, Fb>(
+        history_hashes: Option<Vec<Word>>,
+        acc_fns: FAcc,
+        num_txs: &[usize],
+        func_tx: FTx,
+        func_block: Fb,
+        logger_config: LoggerConfig,
+    ) -> Result<Self, Error>
+    where
+        FTx: FnOnce(Vec<Vec<&mut MockTransaction>>, [MockAccount; NACC]),
+        Fb: FnMut(usize, &mut MockBlock, Vec<MockTransaction>) -> &mut MockBlock,
+        FAcc: FnOnce([&mut MockAccount; NACC]),
+    {
+        Self::new_with_chain_spec(
+            ChainSpec::default(),
+            history_hashes,
+            acc_fns,
+            num_txs,
+            func_tx,
+            func_block,
+            logger_config,
+        )
+    }
+
+    /// Create a new ChunkTestContext as
+    /// [`ChunkTestContext::new_with_logger_config`], tracing each block under
+    /// the rules `chain_spec` selects for it, so that the chunk can cross a
+    /// fork boundary.
+    pub fn new_with_chain_spec<FAcc, FTx, Fb>(
+        chain_spec: ChainSpec,
+        history_hashes: Option<Vec<Word>>,
+        acc_fns: FAcc,
+        num_txs: &[usize],
+        func_tx: FTx,
+        mut func_block: Fb,
+        logger_config: LoggerConfig,
+    ) -> Result<Self, Error>
+    where
+        FTx: FnOnce(Vec<Vec<&mut MockTransaction>>, [MockAccount; NACC]),
+        Fb: FnMut(usize, &mut MockBlock, Vec<MockTransaction>) -> &mut MockBlock,
+        FAcc: FnOnce([&mut MockAccount; NACC]),
+    {
+        assert!(!num_txs.is_empty(), "a chunk has at least one block");
+
+        let mut accounts: Vec<MockAccount> = vec![MockAccount::default(); NACC];
+        // Build Accounts modifiers
+        let account_refs = accounts
+            .iter_mut()
+            .collect_vec()
+            .try_into()
+            .expect("Mismatched len err");
+        acc_fns(account_refs);
+        let accounts: [MockAccount; NACC] = accounts
+            .iter_mut()
+            .map(|acc| acc.build())
+            .collect_vec()
+            .try_into()
+            .expect("Mismatched acc len");
+
+        let mut transactions = num_txs
+            .iter()
+            .map(|num_txs| vec![MockTransaction::default(); *num_txs])
+            .collect_vec();
+        // By default, the nonces of the transactions are correlative across the
+        // chunk, and their TxIndex within their block.
+        for (nonce, tx) in transactions.iter_mut().flatten().enumerate().skip(1) {
+            tx.nonce(Word::from(nonce as u64));
+        }
+        for txs in transactions.iter_mut() {
+            for (idx, tx) in txs.iter_mut().enumerate().skip(1) {
+                tx.transaction_idx(idx as u64);
+            }
+        }
+        let tx_refs = transactions
+            .iter_mut()
+            .map(|txs| txs.iter_mut().collect())
+            .collect();
+
+        // Build Tx modifiers.
+        func_tx(tx_refs, accounts.clone());
+
+        let accounts: [Account; NACC] = accounts
+            .iter()
+            .cloned()
+            .map(Account::from)
+            .collect_vec()
+            .try_into()
+            .expect("Mismatched acc len");
+        let history_hashes = history_hashes.unwrap_or_default();
+
+        let mut chain_id = 0;
+        let mut eth_blocks: Vec<Block<Transaction>> = Vec::with_capacity(num_txs.len());
+        let mut block_history_hashes = Vec::with_capacity(num_txs.len());
+        let mut trace_configs = Vec::with_capacity(num_txs.len());
+        #[cfg(feature = "scroll")]
+        let mut l1_queue_index = 0;
+        for (idx, transactions) in transactions.iter_mut().enumerate() {
+            let transactions: Vec<MockTransaction> =
+                transactions.iter_mut().map(|tx| tx.build()).collect();
+
+            let history_hashes = history_hashes
+                .iter()
+                .copied()
+                .chain(
+                    eth_blocks
+                        .iter()
+                        .map(|block| block.hash.unwrap_or_default().into_uint()),
+                )
+                .collect_vec();
+            let history_hashes =
+                history_hashes[history_hashes.len().saturating_sub(NUM_HISTORY_HASHES)..].to_vec();
+
+            // Build Block modifiers
+            let mut block = MockBlock::default();
+            let parent_hash = history_hashes.last().copied().unwrap_or_default();
+            block.parent_hash(H256::from_uint(&parent_hash));
+            if let Some(parent) = eth_blocks.last() {
+                block.number(parent.number.unwrap_or_default().as_u64() + 1);
+            }
+            block.transactions.extend_from_slice(&transactions);
+            func_block(idx, &mut block, transactions).build();
+
+            chain_id = block.chain_id;
+            let block = Block::<Transaction>::from(block);
+            // The state of the following blocks is the one left by the
+            // previous blocks.
+            let block_accounts = if idx == 0 { accounts.to_vec() } else { vec![] };
+            #[allow(unused_mut)]
+            let mut trace_config = gen_trace_config(
+                &chain_spec,
+                chain_id,
+                block.clone(),
+                block_accounts,
+                Some(history_hashes.clone()),
+                logger_config.clone(),
+            )?;
+            #[cfg(feature = "scroll")]
+            {
+                trace_config.l1_queue_index = l1_queue_index;
+                // the nonce of a l1 message is its queue index
+                l1_queue_index = block
+                    .transactions
+                    .iter()
+                    .filter(|tx| TxType::get_tx_type(tx).is_l1_msg())
+                    .map(|tx| tx.nonce.as_u64() + 1)
+                    .max()
+                    .unwrap_or(l1_queue_index);
+            }
+
+            trace_configs.push(trace_config);
+            block_history_hashes.push(history_hashes);
+            eth_blocks.push(block);
+        }
+
+        #[cfg(feature = "scroll")]
+        let block_traces = l2trace_chunk(&trace_configs)?;
+
+        #[cfg(feature = "scroll")]
+        let geth_traces = block_traces
+            .iter()
+            .map(|block_trace| {
+                block_trace
+                    .execution_results
+                    .clone()
+                    .into_iter()
+                    .map(From::from)
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        #[cfg(not(feature = "scroll"))]
+        let geth_traces = trace_chunk(&trace_configs)?;
+
+        Ok(Self {
+            chain_id,
+            accounts,
+            history_hashes,
+            eth_blocks,
+            geth_traces,
+            chain_spec,
+            block_history_hashes,
+            #[cfg(feature = "scroll")]
+            block_traces,
+        })
+    }
+
+    /// Create a new ChunkTestContext with the default [`LoggerConfig`], see
+    /// [`ChunkTestContext::new_with_logger_config`].
+    pub fn new<FAcc, FTx, Fb>(
+        history_hashes: Option<Vec<Word>>,
+        acc_fns: FAcc,
+        num_txs: &[usize],
+        func_tx: FTx,
+        func_block: Fb,
+    ) -> Result<Self, Error>
+    where
+        FTx: FnOnce(Vec<Vec<&mut MockTransaction>>, [MockAccount; NACC]),
+        Fb: FnMut(usize, &mut MockBlock, Vec<MockTransaction>) -> &mut MockBlock,
+        FAcc: FnOnce([&mut MockAccount; NACC]),
+    {
+        Self::new_with_logger_config(
+            history_hashes,
+            acc_fns,
+            num_txs,
+            func_tx,
+            func_block,
+            LoggerConfig::default(),
+        )
+    }
+
+    /// obtain the full l2 block traces of the chunk
+    #[cfg(feature = "scroll")]
+    pub fn l2_traces(&self) -> &[BlockTrace] {
+        &self.block_traces
+    }
+}
@@ -0,0 +1,315 @@
+//! Traces the mock fixtures with the Go tracer of `geth-utils` and with the
+//! native one, and checks that the traces are the same.
+
+use super::{gen_trace_config, helpers::*, LoggerConfig, TestContext};
+use crate::{eth, MOCK_ACCOUNTS};
+use eth_types::{bytecode, word, Bytecode, GethExecTrace, ToWord, Word};
+
+/// Captures everything the tracers can capture.
+fn logger_config() -> LoggerConfig {
+    LoggerConfig {
+        enable_memory: true,
+        disable_stack: false,
+        disable_storage: false,
+        enable_return_data: true,
+    }
+}
+
+/// The native tracer always reports the code of the prestate accounts, geth
+/// only does for contracts.
+fn align_prestate(geth: &[GethExecTrace], native: &mut [GethExecTrace]) {
+    for (geth, native) in geth.iter().zip(native) {
+        for (addr, account) in native.prestate.iter_mut() {
+            if geth
+                .prestate
+                .get(addr)
+                .is_some_and(|account| account.code.is_none())
+            {
+                account.code = None;
+            }
+        }
+    }
+}
+
+fn check_backends<const NACC: usize, const NTX: usize>(ctx: TestContext<NACC, NTX>) {
+    let config = gen_trace_config(
+        &ctx.chain_spec,
+        ctx.chain_id,
+        ctx.eth_block.clone(),
+        ctx.accounts.to_vec(),
+        Some(ctx.history_hashes.clone()),
+        logger_config(),
+    )
+    .unwrap();
+
+    #[cfg(not(feature = "scroll"))]
+    {
+        let [geth, mut native] = external_tracer::trace_both(&config).unwrap();
+        align_prestate(&geth, &mut native);
+        assert_eq!(geth, native);
+    }
+
+    #[cfg(feature = "scroll")]
+    {
+        use eth_types::l2_types::StorageTrace;
+
+        let [geth, native] = external_tracer::l2trace_both(&config).unwrap();
+        let exec_traces = |block_trace: &eth_types::l2_types::BlockTrace| {
+            block_trace
+                .execution_results
+                .iter()
+                .cloned()
+                .map(GethExecTrace::from)
+                .collect::<Vec<_>>()
+        };
+        let mut native_exec_traces = exec_traces(&native);
+        align_prestate(&exec_traces(&geth), &mut native_exec_traces);
+        assert_eq!(exec_traces(&geth), native_exec_traces);
+
+        let check_storage_trace = |geth: &StorageTrace, native: &StorageTrace| {
+            assert_eq!(geth.root_before, native.root_before);
+            assert_eq!(geth.root_after, native.root_after);
+            assert_eq!(geth.proofs, native.proofs);
+            assert_eq!(geth.storage_proofs, native.storage_proofs);
+            let mut deletion_proofs =
+                [&geth.deletion_proofs, &native.deletion_proofs].map(|proofs| {
+                    proofs
+                        .iter()
+                        .map(|proof| proof.to_vec())
+                        .collect::<Vec<_>>()
+                });
+            deletion_proofs.iter_mut().for_each(|proofs| proofs.sort());
+            assert_eq!(deletion_proofs[0], deletion_proofs[1]);
+        };
+        check_storage_trace(&geth.storage_trace, &native.storage_trace);
+        assert_eq!(geth.tx_storage_trace.len(), native.tx_storage_trace.len());
+        for (geth, native) in geth.tx_storage_trace.iter().zip(&native.tx_storage_trace) {
+            check_storage_trace(geth, native);
+        }
+        assert_eq!(geth.header.state_root, native.header.state_root);
+    }
+}
+
+#[test]
+fn storage_and_memory() {
+    let code = bytecode! {
+        PUSH1(0x2a)
+        PUSH1(0x00)
+        SSTORE
+        PUSH1(0x00)
+        SLOAD
+        PUSH1(0x01)
+        SLOAD
+        ADD
+        PUSH1(0x40)
+        MSTORE
+        // clear the slot 2
+        PUSH1(0x00)
+        PUSH1(0x02)
+        SSTORE
+        PUSH1(0x20)
+        PUSH1(0x40)
+        RETURN
+    };
+    let storage = [(word!("0x1"), word!("0x7")), (word!("0x2"), word!("0x9"))];
+    let ctx = TestContext::<2, 1>::new_with_logger_config(
+        None,
+        |accs| {
+            accs[0]
+                .address(MOCK_ACCOUNTS[0])
+                .balance(eth(10))
+                .code(code)
+                .storage(storage.into_iter());
+            accs[1].address(MOCK_ACCOUNTS[1]).balance(eth(10));
+        },
+        tx_from_1_to_0,
+        |block, _txs| block,
+        logger_config(),
+    )
+    .unwrap();
+    check_backends(ctx);
+}
+
+#[test]
+fn nested_calls() {
+    // the callee reverts after writing to its storage
+    let callee = bytecode! {
+        PUSH1(0x01)
+        PUSH1(0x00)
+        SSTORE
+        PUSH1(0x00)
+        PUSH1(0x00)
+        REVERT
+    };
+    let mut caller = Bytecode::default();
+    for op in ["CALL", "STATICCALL", "DELEGATECALL", "CALLCODE"] {
+        caller.append(&bytecode! {
+            PUSH1(0x00) // retLength
+            PUSH1(0x00) // retOffset
+            PUSH1(0x00) // argsLength
+            PUSH1(0x00) // argsOffset
+        });
+        if matches!(op, "CALL" | "CALLCODE") {
+            caller.push(1, Word::zero()); // value
+        }
+        caller.push(32, MOCK_ACCOUNTS[1].to_word());
+        caller.push(3, Word::from(0x10000)); // gas
+        caller.append_asm(op).unwrap();
+        caller.append(&bytecode! { POP });
+    }
+    caller.append(&bytecode! {
+        PUSH32(MOCK_ACCOUNTS[1].to_word())
+        EXTCODESIZE
+        STOP
+    });
+
+    let ctx = TestContext::<3, 1>::new_with_logger_config(
+        None,
+        |accs| {
+            accs[0]
+                .address(MOCK_ACCOUNTS[0])
+                .balance(eth(10))
+                .code(caller);
+            accs[1]
+                .address(MOCK_ACCOUNTS[1])
+                .balance(eth(10))
+                .code(callee);
+            accs[2].address(MOCK_ACCOUNTS[2]).balance(eth(10));
+        },
+        |mut txs, accs| {
+            txs[0].from(accs[2].address).to(accs[0].address);
+        },
+        |block, _txs| block,
+        logger_config(),
+    )
+    .unwrap();
+    check_backends(ctx);
+}
+
+#[test]
+fn create_and_create2() {
+    // init code returning the 1 byte code 0x00
+    let init_code = bytecode! {
+        PUSH1(0x01)
+        PUSH1(0x00)
+        RETURN
+    }
+    .code();
+    // the init code is right aligned in the first word of the memory
+    let size = Word::from(init_code.len());
+    let offset = Word::from(32 - init_code.len());
+    let code = bytecode! {
+        PUSH32(Word::from_big_endian(&init_code))
+        PUSH1(0x00)
+        MSTORE
+        PUSH1(size) // size
+        PUSH1(offset) // offset
+        PUSH1(0x00) // value
+        CREATE
+        POP
+        PUSH1(0x2a) // salt
+        PUSH1(size) // size
+        PUSH1(offset) // offset
+        PUSH1(0x00) // value
+        CREATE2
+        STOP
+    };
+    let ctx = TestContext::<2, 1>::new_with_logger_config(
+        None,
+        account_0_code_account_1_no_code(code),
+        tx_from_1_to_0,
+        |block, _txs| block,
+        logger_config(),
+    )
+    .unwrap();
+    check_backends(ctx);
+}
+
+#[test]
+fn errors() {
+    // out of gas in a call, then an invalid jump in the root frame
+    let callee = bytecode! {
+        JUMPDEST
+        PUSH1(0x00)
+        JUMP
+    };
+    let code = bytecode! {
+        PUSH1(0x00)
+        PUSH1(0x00)
+        PUSH1(0x00)
+        PUSH1(0x00)
+        PUSH1(0x00)
+        PUSH32(MOCK_ACCOUNTS[1].to_word())
+        PUSH2(0x1000)
+        CALL
+        PUSH1(0xff)
+        JUMP
+    };
+    let ctx = TestContext::<3, 1>::new_with_logger_config(
+        None,
+        |accs| {
+            accs[0]
+                .address(MOCK_ACCOUNTS[0])
+                .balance(eth(10))
+                .code(code);
+            accs[1]
+                .address(MOCK_ACCOUNTS[1])
+                .balance(eth(10))
+                .code(callee);
+            accs[2].address(MOCK_ACCOUNTS[2]).balance(eth(10));
+        },
+        |mut txs, accs| {
+            txs[0].from(accs[2].address).to(accs[0].address);
+        },
+        |block, _txs| block,
+        logger_config(),
+    )
+    .unwrap();
+    check_backends(ctx);
+}
+
+#[test]
+fn transfers_in_one_block() {
+    let ctx = TestContext::<3, 3>::new_with_logger_config(
+        None,
+        |accs| {
+            accs[0].address(MOCK_ACCOUNTS[0]).balance(eth(10));
+            accs[1].address(MOCK_ACCOUNTS[1]).balance(eth(10));
+            // an account without code nor balance
+            accs[2].address(MOCK_ACCOUNTS[2]);
+        },
+        |mut txs, accs| {
+            txs[0]
+                .from(accs[0].address)
+                .to(accs[1].address)
+                .value(eth(1));
+            txs[1]
+                .from(accs[1].address)
+                .to(accs[2].address)
+                .value(eth(2))
+                .nonce(Word::zero());
+            txs[2]
+                .from(accs[0].address)
+                .to(accs[2].address)
+                .nonce(Word::one());
+        },
+        |block, _txs| block,
+        logger_config(),
+    )
+    .unwrap();
+    check_backends(ctx);
+}
+
+#[test]
+fn simple_ctx() {
+    let code = bytecode! {
+        CALLER
+        BALANCE
+        ORIGIN
+        EXTCODEHASH
+        NUMBER
+        BLOCKHASH
+        STOP
+    };
+    check_backends(TestContext::<2, 1>::simple_ctx_with_bytecode(code).unwrap());
+}
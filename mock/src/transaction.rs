@@ -2,12 +2,16 @@
 
 use super::{MOCK_ACCOUNTS, MOCK_CHAIN_ID};
 use eth_types::{
-    geth_types::Transaction as GethTransaction, word, AccessList, Address, Bytes, Hash,
-    Transaction, Word, U64,
+    geth_types::{
+        get_rlp_signed, get_rlp_unsigned, Transaction as GethTransaction,
+        BLOB_VERSIONED_HASHES_KEY, MAX_FEE_PER_BLOB_GAS_KEY,
+    },
+    word, AccessList, Address, Bytes, Hash, Transaction, Word, U64,
 };
 use ethers_core::{
     rand::{CryptoRng, RngCore},
     types::{OtherFields, TransactionRequest},
+    utils::keccak256,
 };
 use ethers_signers::{LocalWallet, Signer};
 use rand::SeedableRng;
@@ -159,6 +163,8 @@ pub struct MockTransaction {
     pub access_list: AccessList,
     pub max_priority_fee_per_gas: Word,
     pub max_fee_per_gas: Word,
+    pub max_fee_per_blob_gas: Word,
+    pub blob_versioned_hashes: Vec<Hash>,
     pub chain_id: u64,
 }
 
@@ -184,6 +190,8 @@ impl Default for MockTransaction {
             access_list: AccessList::default(),
             max_priority_fee_per_gas: Word::zero(),
             max_fee_per_gas: Word::zero(),
+            max_fee_per_blob_gas: Word::zero(),
+            blob_versioned_hashes: vec![],
             chain_id: MOCK_CHAIN_ID,
         }
     }
@@ -191,6 +199,17 @@ impl Default for MockTransaction {
 
 impl From<MockTransaction> for Transaction {
     fn from(mock: MockTransaction) -> Self {
+        let mut other = OtherFields::default();
+        if mock.is_eip4844() {
+            other.insert(
+                MAX_FEE_PER_BLOB_GAS_KEY.to_string(),
+                serde_json::to_value(mock.max_fee_per_blob_gas).unwrap(),
+            );
+            other.insert(
+                BLOB_VERSIONED_HASHES_KEY.to_string(),
+                serde_json::to_value(&mock.blob_versioned_hashes).unwrap(),
+            );
+        }
         Transaction {
             hash: mock.hash.unwrap_or_default(),
             nonce: mock.nonce,
@@ -211,7 +230,7 @@ impl From<MockTransaction> for Transaction {
             max_priority_fee_per_gas: Some(mock.max_priority_fee_per_gas),
             max_fee_per_gas: Some(mock.max_fee_per_gas),
             chain_id: Some(mock.chain_id.into()),
-            other: OtherFields::default(),
+            other,
         }
     }
 }
@@ -322,6 +341,18 @@ impl MockTransaction {
         self
     }
 
+    /// Set max_fee_per_blob_gas field for the MockTransaction.
+    pub fn max_fee_per_blob_gas(&mut self, max_fee_per_blob_gas: Word) -> &mut Self {
+        self.max_fee_per_blob_gas = max_fee_per_blob_gas;
+        self
+    }
+
+    /// Set blob_versioned_hashes field for the MockTransaction.
+    pub fn blob_versioned_hashes(&mut self, blob_versioned_hashes: Vec<Hash>) -> &mut Self {
+        self.blob_versioned_hashes = blob_versioned_hashes;
+        self
+    }
+
     /// Set chain_id field for the MockTransaction.
     pub fn chain_id(&mut self, chain_id: u64) -> &mut Self {
         self.chain_id = chain_id;
@@ -354,12 +385,21 @@ impl MockTransaction {
             (None, None, None) => {
                 // Compute sig params and set them in case we have a wallet as `from` attr.
                 if self.from.is_wallet() && self.hash.is_none() {
-                    let sig = self
-                        .from
-                        .as_wallet()
-                        .with_chain_id(self.chain_id)
-                        .sign_transaction_sync(&tx.into())
-                        .expect("sign mock tx");
+                    let wallet = self.from.as_wallet().with_chain_id(self.chain_id);
+                    let sig = if self.is_eip4844() {
+                        // ethers-core can't sign an EIP-4844 tx, so we sign its unsigned rlp
+                        let rlp_unsigned = get_rlp_unsigned(&Transaction::from(self.to_owned()));
+                        let mut sig = wallet
+                            .sign_hash(Hash::from(keccak256(rlp_unsigned)))
+                            .expect("sign mock tx");
+                        // y parity of a typed tx
+                        sig.v -= 27;
+                        sig
+                    } else {
+                        wallet
+                            .sign_transaction_sync(&tx.into())
+                            .expect("sign mock tx")
+                    };
                     // Set sig parameters
                     self.sig_data((sig.v, sig.r, sig.s));
                 }
@@ -373,9 +413,18 @@ impl MockTransaction {
             let tmp_tx = Transaction::from(self.to_owned());
             // FIXME: Note that tmp_tx does not have sigs if self.from.is_wallet() = false.
             //  This means tmp_tx.hash() is not correct.
-            self.hash(tmp_tx.hash());
+            let hash = if self.is_eip4844() {
+                Hash::from(keccak256(get_rlp_signed(&tmp_tx)))
+            } else {
+                tmp_tx.hash()
+            };
+            self.hash(hash);
         }
 
         self.to_owned()
     }
+
+    fn is_eip4844(&self) -> bool {
+        self.transaction_type == U64::from(3)
+    }
 }
@@ -194,6 +194,9 @@ fn check_chunk_hashes(name: &str, chunk_hashes_proofs: &[(ChunkHash, ChunkProof)
             compare_field!(name, idx, post_state_root, in_arg, in_proof);
             compare_field!(name, idx, withdraw_root, in_arg, in_proof);
             compare_field!(name, idx, data_hash, in_arg, in_proof);
+            compare_field!(name, idx, start_l1_queue_index, in_arg, in_proof);
+            compare_field!(name, idx, end_l1_queue_index, in_arg, in_proof);
+            compare_field!(name, idx, skipped_l1_msgs_hash, in_arg, in_proof);
         }
     }
 
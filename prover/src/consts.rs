@@ -8,5 +8,7 @@ pub static CHUNK_PROTOCOL_FILENAME: LazyLock<String> =
     LazyLock::new(|| read_env_var("CHUNK_PROTOCOL_FILENAME", "chunk.protocol".to_string()));
 pub static CHUNK_VK_FILENAME: LazyLock<String> =
     LazyLock::new(|| read_env_var("CHUNK_VK_FILENAME", "chunk_vk.vkey".to_string()));
+pub static DUMP_WITNESS_BLOCK: LazyLock<bool> =
+    LazyLock::new(|| read_env_var("DUMP_WITNESS_BLOCK", false));
 pub static DEPLOYMENT_CODE_FILENAME: LazyLock<String> =
     LazyLock::new(|| read_env_var("DEPLOYMENT_CODE_FILENAME", "evm_verifier.bin".to_string()));
@@ -1,6 +1,7 @@
 use super::Prover;
 use crate::{
     config::INNER_DEGREE,
+    io::load_witness_block,
     utils::metric_of_witness_block,
     zkevm::circuit::{block_traces_to_witness_block, TargetCircuit},
 };
@@ -19,6 +20,12 @@ impl<C: TargetCircuit> Prover<C> {
         Self::mock_prove_witness_block(&witness_block)
     }
 
+    /// Mock prove a witness block dumped by the chunk prover.
+    pub fn mock_prove_dumped_witness_block(file_path: &str) -> anyhow::Result<()> {
+        let witness_block = load_witness_block(file_path)?;
+        Self::mock_prove_witness_block(&witness_block)
+    }
+
     pub fn mock_prove_witness_block(witness_block: &Block<Fr>) -> anyhow::Result<()> {
         log::info!(
             "mock proving batch, batch metric {:?}",
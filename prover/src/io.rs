@@ -1,3 +1,4 @@
+use crate::WitnessBlock;
 use anyhow;
 use halo2_proofs::{
     halo2curves::bn256::{Fq, Fr, G1Affine},
@@ -9,7 +10,7 @@ use snark_verifier::util::arithmetic::PrimeField;
 use snark_verifier_sdk::Snark;
 use std::{
     fs::File,
-    io::{Cursor, Read, Write},
+    io::{BufReader, Cursor, Read, Write},
     path::{Path, PathBuf},
 };
 
@@ -173,6 +174,21 @@ pub fn load_snark(file_path: &str) -> anyhow::Result<Option<Snark>> {
     Ok(Some(snark))
 }
 
+pub fn write_witness_block(file_path: &str, witness_block: &WitnessBlock) -> anyhow::Result<()> {
+    let mut fd = std::fs::File::create(file_path)?;
+    serde_json::to_writer(&mut fd, witness_block)?;
+    Ok(())
+}
+
+pub fn load_witness_block(file_path: &str) -> anyhow::Result<WitnessBlock> {
+    let fd = File::open(file_path)?;
+    let mut deserializer = serde_json::Deserializer::from_reader(BufReader::new(fd));
+    deserializer.disable_recursion_limit();
+    let deserializer = serde_stacker::Deserializer::new(&mut deserializer);
+    let witness_block = serde::Deserialize::deserialize(deserializer)?;
+    Ok(witness_block)
+}
+
 pub fn load_instances(buf: &[u8]) -> Vec<Vec<Vec<Fr>>> {
     let instances: Vec<Vec<Vec<Vec<u8>>>> = serde_json::from_reader(buf).unwrap();
     instances
@@ -200,3 +216,72 @@ pub fn load_instances_flat(buf: &[u8]) -> Vec<Vec<Vec<Fr>>> {
 
     vec![vec![ret]]
 }
+
+#[cfg(all(test, feature = "scroll"))]
+mod tests {
+    use super::*;
+    use crate::{
+        inner::Prover,
+        zkevm::circuit::{block_traces_to_witness_block, SuperCircuit},
+    };
+    use eth_types::{bytecode, Word};
+    use mock::{test_ctx::helpers::account_0_code_account_1_no_code, TestContext};
+
+    fn witness_block() -> WitnessBlock {
+        let code = bytecode! {
+            PUSH1(0x01)
+            PUSH1(0x00)
+            SSTORE
+            PUSH1(0x20)
+            PUSH1(0x00)
+            SHA3
+            STOP
+        };
+        let trace = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(code),
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[1].address)
+                    .to(accs[0].address)
+                    .gas(Word::from(1_000_000u64));
+            },
+            |block, _tx| block,
+        )
+        .unwrap()
+        .l2_trace()
+        .clone();
+        block_traces_to_witness_block(vec![trace]).unwrap()
+    }
+
+    fn dump(name: &str, witness_block: &WitnessBlock) -> String {
+        let path = std::env::temp_dir().join(format!("witness_block_{name}.json"));
+        let path = path.to_str().unwrap().to_string();
+        write_witness_block(&path, witness_block).unwrap();
+        path
+    }
+
+    #[test]
+    fn witness_block_roundtrip() {
+        let witness_block = witness_block();
+        let path = dump("roundtrip", &witness_block);
+        let loaded = load_witness_block(&path).unwrap();
+        assert_eq!(
+            serde_json::to_value(&loaded).unwrap(),
+            serde_json::to_value(&witness_block).unwrap()
+        );
+        assert_eq!(loaded.rws.0, witness_block.rws.0);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    // High memory usage test, mock proves the super circuit at INNER_DEGREE.
+    #[ignore]
+    #[test]
+    fn replay_dumped_witness_block() {
+        let witness_block = witness_block();
+        let path = dump("replay", &witness_block);
+        Prover::<SuperCircuit>::mock_prove_witness_block(&witness_block).unwrap();
+        Prover::<SuperCircuit>::mock_prove_dumped_witness_block(&path).unwrap();
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -11,10 +11,14 @@ use bus_mapping::{
     circuit_input_builder::{self, CircuitInputBuilder},
     state_db::{CodeDB, StateDB},
 };
-use eth_types::{l2_types::BlockTrace, ToWord, H256};
+use eth_types::{
+    l2_types::{BlockTrace, EthBlock, StorageTrace},
+    ToWord, H256,
+};
 use itertools::Itertools;
-use mpt_zktrie::state::ZktrieState;
+use mpt_zktrie::state::{NodeStore, ZktrieState};
 use serde_derive::{Deserialize, Serialize};
+use zkevm_circuits::super_circuit::SubcircuitRowUsage;
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct SubCircuitRowUsage {
@@ -37,6 +41,34 @@ impl Default for RowUsage {
 
 const NORMALIZED_ROW_LIMIT: usize = 1_000_000;
 
+/// Number of zktrie nodes above which the node store of the checker is cleared on reset.
+const MAX_NODE_STORE_NODES: usize = 1 << 22;
+
+/// Name of the entry of the row usage details counting the RW rows of the reverted calls. They are
+/// already counted in the rows of the sub circuits using the RW table, this is reported so that
+/// they can be priced separately.
+pub const REVERTED_RW_ROWS: &str = "reverted_rw";
+
+/// Rows available to each entry of the row usage details, with the confidence of the estimation.
+const ROW_LIMITS: [(&str, usize, f32); 16] = [
+    ("evm", MAX_RWS, 0.95),
+    ("state", MAX_RWS, 0.95),
+    ("bytecode", MAX_BYTECODE, 0.95),
+    ("copy", MAX_RWS, 0.95),
+    ("keccak", MAX_KECCAK_ROWS, 0.95),
+    ("sha256", MAX_VERTICAL_ROWS, 0.95),
+    ("tx", MAX_VERTICAL_ROWS, 0.95),
+    ("rlp", MAX_CALLDATA, 0.95),
+    ("exp", 7 * MAX_EXP_STEPS, 0.95),
+    ("mod_exp", MAX_KECCAK_ROWS, 0.95),
+    ("pi", MAX_RWS, 0.95),
+    ("poseidon", MAX_POSEIDON_ROWS, 0.95),
+    ("sig", MAX_VERTICAL_ROWS, 0.95),
+    ("ecc", MAX_VERTICAL_ROWS, 1.0),
+    ("mpt", MAX_MPT_ROWS, 0.95),
+    (REVERTED_RW_ROWS, MAX_RWS, 0.95),
+];
+
 impl RowUsage {
     pub fn new() -> Self {
         Self {
@@ -47,30 +79,20 @@ impl RowUsage {
     }
     // We treat 1M as 100%
     pub fn normalize(&self) -> Self {
-        let real_available_rows = [
-            (MAX_RWS, 0.95),           // evm
-            (MAX_RWS, 0.95),           // state
-            (MAX_BYTECODE, 0.95),      // bytecode
-            (MAX_RWS, 0.95),           // copy
-            (MAX_KECCAK_ROWS, 0.95),   // keccak
-            (MAX_VERTICAL_ROWS, 0.95), // tx
-            (MAX_CALLDATA, 0.95),      // rlp
-            (7 * MAX_EXP_STEPS, 0.95), // exp
-            (MAX_KECCAK_ROWS, 0.95),   // modexp
-            (MAX_RWS, 0.95),           // pi
-            (MAX_POSEIDON_ROWS, 0.95), // poseidon
-            (MAX_VERTICAL_ROWS, 0.95), // sig
-            (MAX_VERTICAL_ROWS, 1.0),  // ecc
-            (MAX_MPT_ROWS, 0.95),      // mpt
-        ]
-        .map(|(limit, confidence)| (limit as f32 * confidence) as usize);
+        let real_available_rows = |name: &str| {
+            let (_, limit, confidence) = ROW_LIMITS
+                .iter()
+                .find(|(limit_name, _, _)| *limit_name == name)
+                .unwrap_or_else(|| panic!("no row limit for {name}"));
+            (*limit as f32 * confidence) as usize
+        };
         let details = self
             .row_usage_details
             .iter()
-            .zip_eq(real_available_rows.iter())
-            .map(|(x, limit)| SubCircuitRowUsage {
+            .map(|x| SubCircuitRowUsage {
                 name: x.name.clone(),
-                row_number: (1_000_000u64 * (x.row_number as u64) / (*limit as u64)) as usize,
+                row_number: (1_000_000u64 * (x.row_number as u64)
+                    / (real_available_rows(&x.name) as u64)) as usize,
             })
             .collect_vec();
         log::debug!(
@@ -119,7 +141,20 @@ pub struct CircuitCapacityChecker {
     pub light_mode: bool,
     pub acc_row_usage: RowUsage,
     pub row_usages: Vec<RowUsage>,
+    /// When "tx_breakdown" enabled, the traces are handled tx by tx to record the row usage of
+    /// each of their txs in `tx_row_usages`, at the cost of a row estimation of the txs handled
+    /// so far after each tx, which grows quadratically with the number of txs of a trace.
+    pub tx_breakdown: bool,
+    /// Row usage of each tx of the estimated traces, only recorded with "tx_breakdown". A tx is
+    /// charged the rows added to the row usage of its trace by the tx, including its share of the
+    /// MPT updates and of the trie hashes, so the row usages of the txs of a trace sum to its row
+    /// usage in `row_usages`.
+    pub tx_row_usages: Vec<RowUsage>,
     pub builder_ctx: Option<(CodeDB, StateDB, Option<ZktrieState>)>,
+    /// Store of the zktrie nodes kept across blocks (and resets) when "light_mode" is disabled,
+    /// so the same proof nodes are only imported once. It is cleared on reset once it holds more
+    /// than `MAX_NODE_STORE_NODES` nodes.
+    pub node_store: NodeStore,
 }
 
 impl Default for CircuitCapacityChecker {
@@ -134,18 +169,34 @@ impl CircuitCapacityChecker {
         Self {
             acc_row_usage: RowUsage::new(),
             row_usages: Vec::new(),
+            tx_breakdown: false,
+            tx_row_usages: Vec::new(),
             light_mode: true,
             builder_ctx: None,
+            node_store: NodeStore::in_memory(),
         }
     }
     pub fn reset(&mut self) {
         self.builder_ctx = None;
         self.acc_row_usage = RowUsage::new();
         self.row_usages = Vec::new();
+        self.tx_row_usages = Vec::new();
+        if self.node_store.len() > MAX_NODE_STORE_NODES {
+            log::debug!("clear zktrie node store of {} nodes", self.node_store.len());
+            if let Err(err) = self.node_store.clear() {
+                log::error!("fail to clear zktrie node store: {err}");
+            }
+        }
     }
     pub fn set_light_mode(&mut self, light_mode: bool) {
         self.light_mode = light_mode;
     }
+    pub fn set_node_store(&mut self, node_store: NodeStore) {
+        self.node_store = node_store;
+    }
+    pub fn set_tx_breakdown(&mut self, tx_breakdown: bool) {
+        self.tx_breakdown = tx_breakdown;
+    }
     pub fn get_tx_num(&self) -> usize {
         self.row_usages.len()
     }
@@ -156,10 +207,31 @@ impl CircuitCapacityChecker {
             self.acc_row_usage.clone()
         }
     }
+    pub fn get_tx_row_usages(&self, normalize: bool) -> Vec<RowUsage> {
+        self.tx_row_usages
+            .iter()
+            .map(|row_usage| {
+                if normalize {
+                    row_usage.normalize()
+                } else {
+                    row_usage.clone()
+                }
+            })
+            .collect()
+    }
     pub fn estimate_circuit_capacity(
         &mut self,
         trace: BlockTrace,
     ) -> Result<RowUsage, anyhow::Error> {
+        let mut traces = if self.tx_breakdown {
+            split_trace_by_tx(trace)
+        } else {
+            vec![trace]
+        }
+        .into_iter()
+        .peekable();
+        let trace = traces.next().expect("at least one trace");
+        let more = traces.peek().is_some();
         let (mut estimate_builder, codedb_prev) =
             if let Some((code_db, sdb, mpt_state)) = self.builder_ctx.take() {
                 // here we create a new builder for another (sealed) witness block
@@ -190,36 +262,77 @@ impl CircuitCapacityChecker {
                 } else {
                     CircuitInputBuilder::new(sdb, CodeDB::new(), &builder_block)
                 };
-                builder.add_more_l2_trace(trace, false)?;
+                builder.add_more_l2_trace(trace, more)?;
                 (builder, Some(code_db))
             } else {
                 (
-                    CircuitInputBuilder::new_from_l2_trace(
+                    CircuitInputBuilder::new_from_l2_trace_with_store(
                         get_super_circuit_params(),
                         trace,
-                        false,
-                        self.light_mode,
+                        more,
+                        (!self.light_mode).then(|| self.node_store.clone()),
                     )?,
                     None,
                 )
             };
-        let witness_block =
-            block_traces_to_witness_block_with_updated_state(vec![], &mut estimate_builder)?;
-        let mut rows = calculate_row_usage_of_witness_block(&witness_block)?;
-
         let mut code_db = codedb_prev.unwrap_or_else(CodeDB::new);
-        // merge current codes with previous , and dedup bytecode row usage
-        // for bytecode circuit / poseidon circuit
-        for (hash, bytes) in estimate_builder.code_db.0 {
-            let bytes_len = bytes.len();
-            // code for current run has been evaluated in previous
-            if code_db.0.insert(hash, bytes).is_some() {
-                assert_eq!(rows[2].name, "bytecode");
-                rows[2].row_num_real -= bytes_len + 1;
-                assert_eq!(rows[10].name, "poseidon");
-                rows[10].row_num_real -= bytes_len / (31 * 2) * 9;
+        // With "tx_breakdown", the row usage of the txs handled so far is estimated after each tx
+        // on a finalized copy of the builder, as the row usage of the trace is on the builder
+        // after the last tx, and each tx is charged the rows added since the previous one.
+        let mut prev_rows: Option<Vec<SubcircuitRowUsage>> = None;
+        let rows = loop {
+            let mut rows = if traces.peek().is_some() {
+                builder_row_usage(&mut estimate_builder.clone())?
+            } else {
+                builder_row_usage(&mut estimate_builder)?
+            };
+            // dedup bytecode row usage for bytecode circuit / poseidon circuit with the codes
+            // evaluated in previous runs
+            let row_idx = |name: &str| rows.iter().position(|x| x.name == name).unwrap();
+            let (bytecode, poseidon) = (row_idx("bytecode"), row_idx("poseidon"));
+            for (hash, bytes) in &estimate_builder.code_db.0 {
+                if code_db.0.contains_key(hash) {
+                    rows[bytecode].row_num_real -= bytes.len() + 1;
+                    rows[poseidon].row_num_real -= bytes.len() / (31 * 2) * 9;
+                }
+            }
+            let reverted_rw_rows = estimate_builder
+                .block
+                .txs()
+                .iter()
+                .map(|tx| tx.rw_stats().reverted_rw_rows)
+                .sum();
+            rows.push(SubcircuitRowUsage {
+                name: REVERTED_RW_ROWS.to_string(),
+                row_num_real: reverted_rw_rows,
+                row_num_total: reverted_rw_rows,
+            });
+            if self.tx_breakdown && !estimate_builder.block.txs().is_empty() {
+                let prev_row_num = |idx: usize| {
+                    prev_rows
+                        .as_ref()
+                        .map_or(0, |prev_rows| prev_rows[idx].row_num_real)
+                };
+                let row_usage_details = rows
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, x)| SubCircuitRowUsage {
+                        name: x.name.clone(),
+                        row_number: x.row_num_real.saturating_sub(prev_row_num(idx)),
+                    })
+                    .collect_vec();
+                self.tx_row_usages
+                    .push(RowUsage::from_row_usage_details(row_usage_details));
             }
-        }
+
+            let Some(trace) = traces.next() else {
+                break rows;
+            };
+            estimate_builder.add_more_l2_trace(trace, traces.peek().is_some())?;
+            prev_rows = Some(rows);
+        };
+        // merge current codes with previous
+        code_db.0.extend(estimate_builder.code_db.0);
 
         let row_usage_details: Vec<SubCircuitRowUsage> = rows
             .into_iter()
@@ -240,3 +353,124 @@ impl CircuitCapacityChecker {
         Ok(self.acc_row_usage.normalize())
     }
 }
+
+/// Row usage of the txs handled so far by `builder`, which is finalized.
+fn builder_row_usage(
+    builder: &mut CircuitInputBuilder,
+) -> Result<Vec<SubcircuitRowUsage>, anyhow::Error> {
+    let witness_block = block_traces_to_witness_block_with_updated_state(vec![], builder)?;
+    calculate_row_usage_of_witness_block(&witness_block)
+}
+
+/// Split `trace` into traces of its block with a single tx each. The storage proofs of the block
+/// are only carried by the first one, the builder keeps the state they prove for the next txs.
+fn split_trace_by_tx(trace: BlockTrace) -> Vec<BlockTrace> {
+    if trace.transactions.len() <= 1 {
+        return vec![trace];
+    }
+    let storage_trace = StorageTrace {
+        proofs: None,
+        storage_proofs: Default::default(),
+        deletion_proofs: vec![],
+        ..trace.storage_trace.clone()
+    };
+    let block = BlockTrace {
+        header: EthBlock {
+            transactions: vec![],
+            ..trace.header
+        },
+        transactions: vec![],
+        execution_results: vec![],
+        storage_trace,
+        tx_storage_trace: vec![],
+        ..trace
+    };
+    let mut first_storage_trace = Some(trace.storage_trace);
+    let mut tx_storage_traces = trace.tx_storage_trace.into_iter();
+    trace
+        .transactions
+        .into_iter()
+        .zip_eq(trace.execution_results)
+        .map(|(tx, execution_result)| {
+            let block = block.clone();
+            BlockTrace {
+                transactions: vec![tx],
+                execution_results: vec![execution_result],
+                storage_trace: first_storage_trace.take().unwrap_or(block.storage_trace),
+                tx_storage_trace: tx_storage_traces.next().into_iter().collect(),
+                ..block
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_by_name() {
+        let row_usage = RowUsage::from_row_usage_details(
+            [("poseidon", MAX_POSEIDON_ROWS), (REVERTED_RW_ROWS, 950)]
+                .map(|(name, row_number)| SubCircuitRowUsage {
+                    name: name.to_string(),
+                    row_number,
+                })
+                .to_vec(),
+        )
+        .normalize();
+        assert_eq!(row_usage.row_usage_details[0].row_number, 1_052_631);
+        assert_eq!(row_usage.row_usage_details[1].row_number, 1_000);
+        assert_eq!(row_usage.row_number, 1_052_631);
+        assert!(!row_usage.is_ok);
+    }
+
+    #[cfg(feature = "scroll")]
+    #[test]
+    fn tx_row_usages_sum_to_trace_row_usage() {
+        use eth_types::{bytecode, Word};
+        use mock::{test_ctx::helpers::account_0_code_account_1_no_code, TestContext};
+
+        let code = bytecode! {
+            PUSH1(0x01)
+            PUSH1(0x00)
+            SSTORE
+            STOP
+        };
+        let trace = TestContext::<2, 3>::new(
+            None,
+            account_0_code_account_1_no_code(code),
+            |txs, accs| {
+                for (nonce, tx) in txs.into_iter().enumerate() {
+                    tx.from(accs[1].address)
+                        .to(accs[0].address)
+                        .nonce(Word::from(nonce));
+                }
+            },
+            |block, _tx| block,
+        )
+        .unwrap()
+        .l2_trace()
+        .clone();
+
+        let mut checker = CircuitCapacityChecker::new();
+        checker.set_tx_breakdown(true);
+        checker.estimate_circuit_capacity(trace).unwrap();
+
+        let tx_row_usages = checker.get_tx_row_usages(false);
+        assert_eq!(tx_row_usages.len(), 3);
+        let mut sum = RowUsage::new();
+        for tx_row_usage in &tx_row_usages {
+            sum.add(tx_row_usage);
+        }
+        let row_usage = &checker.row_usages[0];
+        for (tx_sum, trace) in sum
+            .row_usage_details
+            .iter()
+            .zip_eq(&row_usage.row_usage_details)
+        {
+            assert_eq!(tx_sum.name, trace.name);
+            assert_eq!(tx_sum.row_number, trace.row_number, "{}", trace.name);
+        }
+    }
+}
@@ -5,7 +5,7 @@ use bus_mapping::{
     circuit_input_builder::{self, CircuitInputBuilder, CircuitsParams, PrecompileEcParams},
     state_db::{CodeDB, StateDB},
 };
-use eth_types::{l2_types::BlockTrace, ToWord, H256};
+use eth_types::{evm_types::GasTable, l2_types::BlockTrace, ToWord, H256};
 use halo2_proofs::halo2curves::bn256::Fr;
 use itertools::Itertools;
 use mpt_zktrie::state::{ZkTrieHash, ZktrieState};
@@ -22,6 +22,7 @@ static AUTO_TRUNCATE: LazyLock<bool> = LazyLock::new(|| read_env_var("AUTO_TRUNC
 ////// params for degree = 20 ////////////
 pub const MAX_TXS: usize = 100;
 pub const MAX_INNER_BLOCKS: usize = 100;
+pub const MAX_L1_MSGS_POPPED: usize = 1024;
 pub const MAX_EXP_STEPS: usize = 10_000;
 pub const MAX_CALLDATA: usize = 350_000;
 pub const MAX_RLP_ROWS: usize = 800_000;
@@ -45,6 +46,7 @@ pub fn get_super_circuit_params() -> CircuitsParams {
         max_calldata: MAX_CALLDATA,
         max_bytecode: MAX_BYTECODE,
         max_inner_blocks: MAX_INNER_BLOCKS,
+        max_l1_msgs_popped: MAX_L1_MSGS_POPPED,
         max_keccak_rows: MAX_KECCAK_ROWS,
         max_poseidon_rows: MAX_POSEIDON_ROWS,
         max_vertical_circuit_rows: MAX_VERTICAL_ROWS,
@@ -56,6 +58,7 @@ pub fn get_super_circuit_params() -> CircuitsParams {
             ec_mul: MAX_PRECOMPILE_EC_MUL,
             ec_pairing: MAX_PRECOMPILE_EC_PAIRING,
         },
+        gas_table: GasTable::default(),
     }
 }
 
@@ -73,15 +76,15 @@ pub fn calculate_row_usage_of_witness_block(
     let mut rows = <super::SuperCircuit as TargetCircuit>::Inner::min_num_rows_block_subcircuits(
         witness_block,
     );
-    assert_eq!(rows[10].name, "poseidon");
-    assert_eq!(rows[13].name, "mpt");
+    let row_idx = |name: &str| rows.iter().position(|x| x.name == name).unwrap();
+    let (poseidon, mpt) = (row_idx("poseidon"), row_idx("mpt"));
     // empirical estimation is each row in mpt cost 1.5 hash (aka 12 rows)
-    let mpt_poseidon_rows = rows[13].row_num_real * 12;
+    let mpt_poseidon_rows = rows[mpt].row_num_real * 12;
     if witness_block.mpt_updates.smt_traces.is_empty() {
-        rows[10].row_num_real += mpt_poseidon_rows;
+        rows[poseidon].row_num_real += mpt_poseidon_rows;
         log::debug!("calculate_row_usage_of_witness_block light mode, adding {mpt_poseidon_rows} poseidon rows");
     } else {
-        //rows[10].row_num_real += mpt_poseidon_rows;
+        //rows[poseidon].row_num_real += mpt_poseidon_rows;
         log::debug!("calculate_row_usage_of_witness_block normal mode, skip adding {mpt_poseidon_rows} poseidon rows");
     }
 
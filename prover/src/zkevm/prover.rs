@@ -1,10 +1,10 @@
 use crate::{
     common,
     config::{LayerId, ZKEVM_DEGREES},
-    consts::CHUNK_VK_FILENAME,
-    io::try_to_read,
+    consts::{CHUNK_VK_FILENAME, DUMP_WITNESS_BLOCK},
+    io::{load_witness_block, try_to_read, write_witness_block},
     utils::chunk_trace_to_witness_block,
-    ChunkProof,
+    ChunkProof, WitnessBlock,
 };
 use aggregator::ChunkHash;
 use anyhow::Result;
@@ -51,6 +51,31 @@ impl Prover {
         let witness_block = chunk_trace_to_witness_block(chunk_trace)?;
         log::info!("Got witness block");
 
+        self.gen_chunk_proof_by_witness_block(witness_block, name, inner_id, output_dir)
+    }
+
+    /// Replay the proving of a chunk from a witness block dumped by a previous
+    /// run with `DUMP_WITNESS_BLOCK` set.
+    pub fn replay_chunk_proof(
+        &mut self,
+        witness_block_path: &str,
+        name: Option<&str>,
+        inner_id: Option<&str>,
+        output_dir: Option<&str>,
+    ) -> Result<ChunkProof> {
+        let witness_block = load_witness_block(witness_block_path)?;
+        log::info!("Loaded witness block from {witness_block_path}");
+
+        self.gen_chunk_proof_by_witness_block(witness_block, name, inner_id, output_dir)
+    }
+
+    fn gen_chunk_proof_by_witness_block(
+        &mut self,
+        witness_block: WitnessBlock,
+        name: Option<&str>,
+        inner_id: Option<&str>,
+        output_dir: Option<&str>,
+    ) -> Result<ChunkProof> {
         let name = name.map_or_else(
             || {
                 witness_block
@@ -64,6 +89,17 @@ impl Prover {
             |name| name.to_string(),
         );
 
+        if *DUMP_WITNESS_BLOCK {
+            match output_dir {
+                Some(output_dir) => {
+                    let path = format!("{output_dir}/witness_block_{name}.json");
+                    write_witness_block(&path, &witness_block)?;
+                    log::info!("Dumped witness block to {path}");
+                }
+                None => log::warn!("DUMP_WITNESS_BLOCK is set without an output dir, skip dumping"),
+            }
+        }
+
         let snark = self.inner.load_or_gen_final_chunk_snark(
             &name,
             &witness_block,
@@ -0,0 +1,399 @@
+use super::{
+    receipt,
+    spec::{BlockHeader, BlockTest, TestBlock},
+};
+use crate::{
+    config::TestSuite,
+    statetest::{
+        check_geth_traces, get_params_for_sub_circuit_test, get_params_for_super_circuit_test,
+        mock_prove, CircuitsConfig, StateTestError, StateTestResult, MAX_TXS,
+    },
+    utils::ETH_CHAIN_ID,
+};
+use bus_mapping::circuit_input_builder::{self, CircuitsParams};
+use eth_types::{
+    chain_spec::ChainSpec, geth_types, geth_types::Account, Address, GethExecTrace, Transaction,
+    H256, U256, U64,
+};
+use external_tracer::{LoggerConfig, TraceConfig};
+use halo2_proofs::halo2curves::bn256::Fr;
+use std::collections::BTreeMap;
+use thiserror::Error;
+use zkevm_circuits::{
+    super_circuit::params::min_circuits_params, test_util::CircuitTestBuilder, witness::Block,
+};
+
+/// Number of recent block hashes available to the `BLOCKHASH` opcode.
+const NUM_HISTORY_HASHES: usize = 256;
+
+#[derive(PartialEq, Eq, Error, Debug)]
+pub enum BlockTestError {
+    #[error("BlockHashMismatch(block:{number}, expected:{expected:?}, found:{found:?})")]
+    BlockHashMismatch {
+        number: u64,
+        expected: H256,
+        found: H256,
+    },
+    #[error("ParentHashMismatch(block:{number}, expected:{expected:?}, found:{found:?})")]
+    ParentHashMismatch {
+        number: u64,
+        expected: H256,
+        found: H256,
+    },
+    #[error("ReceiptTrieMismatch(block:{number}, expected:{expected:?}, found:{found:?})")]
+    ReceiptTrieMismatch {
+        number: u64,
+        expected: H256,
+        found: H256,
+    },
+    #[error("GasUsedMismatch(block:{number}, expected:{expected}, found:{found})")]
+    GasUsedMismatch {
+        number: u64,
+        expected: u64,
+        found: u64,
+    },
+    #[error("LastBlockHashMismatch(expected:{expected:?}, found:{found:?})")]
+    LastBlockHashMismatch { expected: H256, found: H256 },
+    #[error("SkipTestNoPostState")]
+    SkipTestNoPostState,
+    #[error("SkipTestUnsupportedNetwork({0})")]
+    SkipTestUnsupportedNetwork(String),
+    #[error(transparent)]
+    State(#[from] StateTestError),
+}
+
+impl BlockTestError {
+    pub fn is_skip(&self) -> bool {
+        match self {
+            BlockTestError::SkipTestNoPostState | BlockTestError::SkipTestUnsupportedNetwork(_) => {
+                true
+            }
+            BlockTestError::State(err) => err.is_skip(),
+            _ => false,
+        }
+    }
+}
+
+/// Compare the state after the last block with the fixture `postState`.
+fn check_post_state(
+    state: &BTreeMap<Address, Account>,
+    post: &StateTestResult,
+) -> Result<(), StateTestError> {
+    log::trace!("check post state");
+    for (address, expected) in post {
+        let actual = state.get(address).cloned().unwrap_or_default();
+
+        if expected.balance.map(|v| v == actual.balance) == Some(false) {
+            log::error!("balance mismatch, expected {expected:?} actual {actual:?}");
+            return Err(StateTestError::BalanceMismatch {
+                expected: expected.balance.unwrap(),
+                found: actual.balance,
+            });
+        }
+
+        if expected.nonce.map(|v| v == actual.nonce) == Some(false) {
+            log::error!("nonce mismatch, expected {expected:?} actual {actual:?}");
+            return Err(StateTestError::NonceMismatch {
+                expected: expected.nonce.unwrap(),
+                found: actual.nonce,
+            });
+        }
+
+        if let Some(expected_code) = &expected.code {
+            if expected_code != &actual.code {
+                return Err(StateTestError::CodeMismatch {
+                    expected: expected_code.clone(),
+                    found: actual.code,
+                });
+            }
+        }
+
+        for (slot, expected_value) in &expected.storage {
+            let actual_value = actual.storage.get(slot).cloned().unwrap_or_else(U256::zero);
+            if expected_value != &actual_value {
+                log::error!(
+                    "StorageMismatch address {address:?}, expected {expected:?} actual {actual:?}"
+                );
+                return Err(StateTestError::StorageMismatch {
+                    slot: *slot,
+                    expected: *expected_value,
+                    found: actual_value,
+                });
+            }
+        }
+    }
+    log::trace!("check post state done");
+    Ok(())
+}
+
+fn into_traceconfig(
+    header: &BlockHeader,
+    transactions: &[Transaction],
+    state: &BTreeMap<Address, Account>,
+    history_hashes: &[U256],
+    chain_spec: &ChainSpec,
+) -> Result<TraceConfig, StateTestError> {
+    let block_constants = geth_types::BlockConstants {
+        coinbase: header.coinbase,
+        timestamp: U256::from(header.timestamp),
+        number: U64::from(header.number),
+        // after the merge, `DIFFICULTY` returns `PREVRANDAO`, which is
+        // stored in the header `mixHash`.
+        difficulty: if header.difficulty.is_zero() {
+            U256::from_big_endian(header.mix_hash.as_bytes())
+        } else {
+            header.difficulty
+        },
+        gas_limit: U256::from(header.gas_limit),
+        base_fee: header.base_fee_per_gas.unwrap_or_default(),
+    };
+    let chain_config = external_tracer::ChainConfig::from_chain_spec(chain_spec, &block_constants)
+        .map_err(|err| StateTestError::CircuitInput(err.to_string()))?;
+    Ok(TraceConfig {
+        chain_id: ETH_CHAIN_ID,
+        history_hashes: history_hashes.to_vec(),
+        chain_config,
+        block_constants,
+        transactions: transactions
+            .iter()
+            .map(geth_types::Transaction::from)
+            .collect(),
+        accounts: state.clone(),
+        logger_config: LoggerConfig {
+            enable_memory: cfg!(feature = "enable-memory")
+                && bus_mapping::util::GETH_TRACE_CHECK_LEVEL.should_check(),
+            disable_stack: !cfg!(feature = "enable-stack")
+                && bus_mapping::util::GETH_TRACE_CHECK_LEVEL.should_check(),
+            disable_storage: !cfg!(feature = "enable-storage"),
+            ..Default::default()
+        },
+    })
+}
+
+/// Check the receipts trie of the block against the receipts rebuilt from the
+/// traces of its txs and the logs of the witness.
+fn check_receipt_trie(
+    header: &BlockHeader,
+    transactions: &[Transaction],
+    geth_traces: &[GethExecTrace],
+    block: &circuit_input_builder::Block,
+) -> Result<(), BlockTestError> {
+    let receipts = receipt::receipts(transactions, geth_traces, block);
+    let found = receipt::ordered_trie_root(receipts.iter().map(receipt::Receipt::encode));
+    if found != header.receipt_trie {
+        return Err(BlockTestError::ReceiptTrieMismatch {
+            number: header.number,
+            expected: header.receipt_trie,
+            found,
+        });
+    }
+    Ok(())
+}
+
+/// Execute one block on top of `state`, run the circuits on its witness and
+/// return the state after the block.
+fn run_block(
+    test_id: &str,
+    header: &BlockHeader,
+    transactions: &[Transaction],
+    state: BTreeMap<Address, Account>,
+    history_hashes: &[U256],
+    chain_spec: &ChainSpec,
+    suite: &TestSuite,
+    circuits_config: &CircuitsConfig,
+) -> Result<BTreeMap<Address, Account>, BlockTestError> {
+    let trace_config = into_traceconfig(header, transactions, &state, history_hashes, chain_spec)?;
+
+    let geth_traces = external_tracer::trace(&trace_config)
+        .map_err(|err| StateTestError::CircuitInput(err.to_string()))?;
+    for geth_trace in &geth_traces {
+        check_geth_traces(
+            std::slice::from_ref(geth_trace),
+            suite,
+            circuits_config.verbose,
+        )?;
+    }
+
+    let gas_used = geth_traces.iter().map(|trace| trace.gas.0).sum::<u64>();
+    if gas_used != header.gas_used {
+        return Err(BlockTestError::GasUsedMismatch {
+            number: header.number,
+            expected: header.gas_used,
+            found: gas_used,
+        });
+    }
+
+    let eth_block = eth_types::Block {
+        author: Some(header.coinbase),
+        timestamp: U256::from(header.timestamp),
+        number: Some(U64::from(header.number)),
+        difficulty: trace_config.block_constants.difficulty,
+        gas_limit: U256::from(header.gas_limit),
+        gas_used: U256::from(header.gas_used),
+        base_fee_per_gas: header.base_fee_per_gas,
+        transactions: transactions.to_vec(),
+        parent_hash: header.parent_hash,
+        hash: Some(header.hash),
+        ..eth_types::Block::default()
+    };
+
+    let geth_data = geth_types::GethData {
+        chain_id: trace_config.chain_id,
+        history_hashes: trace_config.history_hashes.clone(),
+        geth_traces: geth_traces.clone(),
+        accounts: trace_config.accounts.values().cloned().collect(),
+        eth_block: eth_block.clone(),
+        chain_spec: chain_spec.clone(),
+    };
+
+    let circuits_params = if circuits_config.super_circuit {
+        get_params_for_super_circuit_test()
+    } else {
+        CircuitsParams {
+            max_txs: MAX_TXS,
+            ..get_params_for_sub_circuit_test()
+        }
+    };
+    let block_data =
+        bus_mapping::mock::BlockData::new_from_geth_data_with_params(geth_data, circuits_params);
+    let mut builder = block_data.new_circuit_input_builder();
+    builder
+        .handle_block(&eth_block, &geth_traces)
+        .map_err(|err| StateTestError::CircuitInput(err.to_string()))?;
+    check_receipt_trie(header, transactions, &geth_traces, &builder.block)?;
+
+    let mut witness_block: Block<Fr> =
+        zkevm_circuits::evm_circuit::witness::block_convert(&builder.block, &builder.code_db)
+            .unwrap();
+    if circuits_config.auto_params {
+        witness_block.circuits_params = min_circuits_params(&witness_block);
+    }
+    if circuits_config.super_circuit {
+        mock_prove(&format!("{test_id}#{}", header.number), &witness_block);
+    } else {
+        CircuitTestBuilder::<1, 1>::new_from_block(witness_block)
+            .copy_checks(None)
+            .run();
+    }
+
+    // Accounts not touched by the block are carried over unchanged, touched
+    // ones are replaced by the state bus-mapping ends up with.
+    let mut state = state;
+    for (address, account) in builder.sdb.iter_accounts() {
+        if account.is_empty() {
+            state.remove(address);
+            continue;
+        }
+        let code = builder
+            .code_db
+            .0
+            .get(&account.code_hash)
+            .cloned()
+            .unwrap_or_default();
+        state.insert(
+            *address,
+            Account {
+                address: *address,
+                nonce: account.nonce,
+                balance: account.balance,
+                code: code.into(),
+                storage: account
+                    .storage
+                    .iter()
+                    .filter(|(_, value)| !value.is_zero())
+                    .map(|(key, value)| (*key, *value))
+                    .collect(),
+            },
+        );
+    }
+    Ok(state)
+}
+
+pub fn run_block_test(
+    bt: BlockTest,
+    suite: TestSuite,
+    circuits_config: CircuitsConfig,
+) -> Result<(), BlockTestError> {
+    let test_id = bt.id.clone();
+    log::info!("{test_id}: run-block-test BEGIN - {circuits_config:?}");
+
+    let chain_spec = bt
+        .chain_spec
+        .ok_or(BlockTestError::SkipTestUnsupportedNetwork(bt.network))?;
+    let post = bt.post.ok_or(BlockTestError::SkipTestNoPostState)?;
+
+    let mut state = bt.pre;
+    let mut last_hash = bt.genesis.hash;
+    let mut history_hashes = vec![U256::from_big_endian(last_hash.as_bytes())];
+
+    for block in &bt.blocks {
+        let (header, transactions, withdrawals) = match block {
+            TestBlock::Valid {
+                header,
+                transactions,
+                withdrawals,
+            } => (header, transactions, withdrawals),
+            TestBlock::Invalid => continue,
+        };
+
+        let found = header.compute_hash();
+        if found != header.hash {
+            return Err(BlockTestError::BlockHashMismatch {
+                number: header.number,
+                expected: header.hash,
+                found,
+            });
+        }
+        if header.parent_hash != last_hash {
+            return Err(BlockTestError::ParentHashMismatch {
+                number: header.number,
+                expected: last_hash,
+                found: header.parent_hash,
+            });
+        }
+
+        log::debug!(
+            "{test_id}: block {} with {} txs",
+            header.number,
+            transactions.len()
+        );
+        state = run_block(
+            &test_id,
+            header,
+            transactions,
+            state,
+            &history_hashes,
+            &chain_spec,
+            &suite,
+            &circuits_config,
+        )?;
+
+        // withdrawals are processed after the txs and are not part of the
+        // execution trace, so they are applied directly to the state.
+        for (address, amount) in withdrawals {
+            let account = state.entry(*address).or_insert_with(|| Account {
+                address: *address,
+                ..Default::default()
+            });
+            account.balance += *amount;
+        }
+
+        last_hash = header.hash;
+        history_hashes.push(U256::from_big_endian(last_hash.as_bytes()));
+        if history_hashes.len() > NUM_HISTORY_HASHES {
+            history_hashes.remove(0);
+        }
+    }
+
+    if last_hash != bt.last_block_hash {
+        return Err(BlockTestError::LastBlockHashMismatch {
+            expected: bt.last_block_hash,
+            found: last_hash,
+        });
+    }
+
+    check_post_state(&state, &post)?;
+
+    log::info!("{test_id}: run-block-test END");
+    Ok(())
+}
@@ -0,0 +1,553 @@
+use super::spec::{BlockHeader, BlockTest, TestBlock};
+use crate::{
+    statetest::{parse, AccountMatch},
+    utils::{MainnetFork, TEST_FORK},
+};
+use anyhow::{Context, Result};
+use eth_types::{
+    chain_spec::{ChainSpec, ForkCondition, Hardfork},
+    geth_types::Account,
+    AccessList, AccessListItem, Address, Transaction, U256, U64,
+};
+use serde::Deserialize;
+use std::{
+    collections::{BTreeMap, HashMap},
+    str::FromStr,
+};
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonBlockHeader {
+    base_fee_per_gas: Option<String>,
+    bloom: String,
+    coinbase: String,
+    difficulty: String,
+    extra_data: String,
+    gas_limit: String,
+    gas_used: String,
+    hash: String,
+    mix_hash: String,
+    nonce: String,
+    number: String,
+    parent_hash: String,
+    receipt_trie: String,
+    state_root: String,
+    timestamp: String,
+    transactions_trie: String,
+    uncle_hash: String,
+    withdrawals_root: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonAccessListItem {
+    address: String,
+    storage_keys: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonTransaction {
+    #[serde(rename = "type")]
+    tx_type: Option<String>,
+    chain_id: Option<String>,
+    data: String,
+    gas_limit: String,
+    gas_price: Option<String>,
+    max_fee_per_gas: Option<String>,
+    max_priority_fee_per_gas: Option<String>,
+    access_list: Option<Vec<JsonAccessListItem>>,
+    nonce: String,
+    r: String,
+    s: String,
+    v: String,
+    sender: String,
+    to: String,
+    value: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct JsonWithdrawal {
+    address: String,
+    amount: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonBlock {
+    block_header: Option<JsonBlockHeader>,
+    #[serde(default)]
+    transactions: Vec<JsonTransaction>,
+    #[serde(default)]
+    withdrawals: Vec<JsonWithdrawal>,
+    expect_exception: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct JsonAccount {
+    balance: String,
+    code: String,
+    nonce: String,
+    storage: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonBlockTest {
+    network: String,
+    genesis_block_header: JsonBlockHeader,
+    pre: HashMap<String, JsonAccount>,
+    post_state: Option<HashMap<String, JsonAccount>>,
+    #[serde(rename = "lastblockhash")]
+    last_block_hash: String,
+    blocks: Vec<JsonBlock>,
+}
+
+/// Loads `BlockTest` vectors from an ethereum/tests `BlockchainTests` fixture.
+pub struct JsonBlockTestBuilder;
+
+impl JsonBlockTestBuilder {
+    /// generates `BlockTest` vectors from a `BlockchainTests` json fixture,
+    /// skipping the ones that do not target the fork we are testing against.
+    pub fn load_json(path: &str, source: &str) -> Result<Vec<BlockTest>> {
+        let mut block_tests = Vec::new();
+        let tests: HashMap<String, JsonBlockTest> = serde_json::from_str(source)?;
+
+        for (test_name, test) in tests {
+            // transitions the circuits can not run are kept to be reported as
+            // skipped, other forks than the tested one are not part of the suite
+            let chain_spec = Self::parse_network(&test.network);
+            if chain_spec.is_none() && !test.network.contains("To") {
+                continue;
+            }
+
+            let genesis = Self::parse_header(&test.genesis_block_header)?;
+            let pre = Self::parse_accounts(&test.pre)?;
+            let post = test
+                .post_state
+                .as_ref()
+                .map(Self::parse_accounts_post)
+                .transpose()?;
+
+            let blocks = test
+                .blocks
+                .iter()
+                .map(Self::parse_block)
+                .collect::<Result<_>>()
+                .with_context(|| format!("parsing blocks of {test_name}"))?;
+
+            block_tests.push(BlockTest {
+                path: path.to_string(),
+                id: test_name,
+                network: test.network.clone(),
+                chain_spec,
+                genesis,
+                pre,
+                post,
+                last_block_hash: parse::parse_hash(&test.last_block_hash)?,
+                blocks,
+            });
+        }
+
+        Ok(block_tests)
+    }
+
+    /// Maps a network to the chain spec running it: the tested fork, or a
+    /// transition between the supported forks such as
+    /// `ParisToShanghaiAtTime15k`, activated at a timestamp (`AtTime15k`) or
+    /// at a block number (`At5`).
+    fn parse_network(network: &str) -> Option<ChainSpec> {
+        let Some((from, to)) = network.split_once("To") else {
+            return (MainnetFork::from_str(network).ok()? == TEST_FORK).then(ChainSpec::default);
+        };
+        let hardfork = |name: &str| match MainnetFork::from_str(name).ok()? {
+            MainnetFork::Merge => Some(Hardfork::London),
+            MainnetFork::Shanghai => Some(Hardfork::Shanghai),
+            _ => None,
+        };
+        let (to, activation) = to.split_once("At")?;
+        let (from, to) = (hardfork(from)?, hardfork(to)?);
+        if from >= to {
+            return None;
+        }
+        let parse_count = |count: &str| match count.strip_suffix('k') {
+            Some(thousands) => thousands.parse::<u64>().ok().map(|n| n * 1000),
+            None => count.parse::<u64>().ok(),
+        };
+        let condition = match activation.strip_prefix("Time") {
+            Some(time) => ForkCondition::Timestamp(parse_count(time)?),
+            None => ForkCondition::Block(parse_count(activation)?),
+        };
+        let chain_spec = if from == Hardfork::Shanghai {
+            ChainSpec::shanghai()
+        } else {
+            ChainSpec::london()
+        };
+        Some(
+            chain_spec
+                .with_fork(to, condition)
+                .with_scroll(cfg!(feature = "scroll")),
+        )
+    }
+
+    fn parse_block(block: &JsonBlock) -> Result<TestBlock> {
+        // invalid blocks are only described by their rlp, they must be rejected
+        // and are never imported into the chain
+        let header = match (&block.block_header, &block.expect_exception) {
+            (Some(header), None) => header,
+            _ => return Ok(TestBlock::Invalid),
+        };
+        let header = Self::parse_header(header)?;
+        let transactions = block
+            .transactions
+            .iter()
+            .enumerate()
+            .map(|(index, tx)| Self::parse_transaction(&header, index, tx))
+            .collect::<Result<_>>()?;
+        // withdrawal amounts are denominated in gwei
+        let withdrawals = block
+            .withdrawals
+            .iter()
+            .map(|w| {
+                Ok((
+                    parse::parse_address(&w.address)?,
+                    parse::parse_u256(&w.amount)? * U256::exp10(9),
+                ))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(TestBlock::Valid {
+            header,
+            transactions,
+            withdrawals,
+        })
+    }
+
+    fn parse_header(header: &JsonBlockHeader) -> Result<BlockHeader> {
+        Ok(BlockHeader {
+            parent_hash: parse::parse_hash(&header.parent_hash)?,
+            uncle_hash: parse::parse_hash(&header.uncle_hash)?,
+            coinbase: parse::parse_address(&header.coinbase)?,
+            state_root: parse::parse_hash(&header.state_root)?,
+            transactions_trie: parse::parse_hash(&header.transactions_trie)?,
+            receipt_trie: parse::parse_hash(&header.receipt_trie)?,
+            bloom: parse::parse_bytes(&header.bloom)?,
+            difficulty: parse::parse_u256(&header.difficulty)?,
+            number: parse::parse_u64(&header.number)?,
+            gas_limit: parse::parse_u64(&header.gas_limit)?,
+            gas_used: parse::parse_u64(&header.gas_used)?,
+            timestamp: parse::parse_u64(&header.timestamp)?,
+            extra_data: parse::parse_bytes(&header.extra_data)?,
+            mix_hash: parse::parse_hash(&header.mix_hash)?,
+            nonce: parse::parse_bytes(&header.nonce)?,
+            base_fee_per_gas: header
+                .base_fee_per_gas
+                .as_ref()
+                .map(|v| parse::parse_u256(v))
+                .transpose()?,
+            withdrawals_root: header
+                .withdrawals_root
+                .as_ref()
+                .map(|v| parse::parse_hash(v))
+                .transpose()?,
+            hash: parse::parse_hash(&header.hash)?,
+        })
+    }
+
+    fn parse_transaction(
+        header: &BlockHeader,
+        index: usize,
+        tx: &JsonTransaction,
+    ) -> Result<Transaction> {
+        let access_list = tx
+            .access_list
+            .as_ref()
+            .map(|items| {
+                items
+                    .iter()
+                    .map(|item| {
+                        Ok(AccessListItem {
+                            address: parse::parse_address(&item.address)?,
+                            storage_keys: item
+                                .storage_keys
+                                .iter()
+                                .map(|key| parse::parse_hash(key))
+                                .collect::<Result<_>>()?,
+                        })
+                    })
+                    .collect::<Result<Vec<_>>>()
+                    .map(AccessList)
+            })
+            .transpose()?;
+
+        let mut tx = Transaction {
+            transaction_type: tx
+                .tx_type
+                .as_ref()
+                .map(|v| parse::parse_u64(v).map(U64::from))
+                .transpose()?
+                .filter(|tx_type| !tx_type.is_zero()),
+            chain_id: tx
+                .chain_id
+                .as_ref()
+                .map(|v| parse::parse_u256(v))
+                .transpose()?,
+            from: parse::parse_address(&tx.sender)?,
+            to: parse::parse_to_address(&tx.to)?,
+            nonce: parse::parse_u256(&tx.nonce)?,
+            gas: parse::parse_u256(&tx.gas_limit)?,
+            gas_price: tx
+                .gas_price
+                .as_ref()
+                .map(|v| parse::parse_u256(v))
+                .transpose()?,
+            max_fee_per_gas: tx
+                .max_fee_per_gas
+                .as_ref()
+                .map(|v| parse::parse_u256(v))
+                .transpose()?,
+            max_priority_fee_per_gas: tx
+                .max_priority_fee_per_gas
+                .as_ref()
+                .map(|v| parse::parse_u256(v))
+                .transpose()?,
+            value: parse::parse_u256(&tx.value)?,
+            input: parse::parse_bytes(&tx.data)?,
+            access_list,
+            v: U64::from(parse::parse_u64(&tx.v)?),
+            r: parse::parse_u256(&tx.r)?,
+            s: parse::parse_u256(&tx.s)?,
+            block_number: Some(U64::from(header.number)),
+            transaction_index: Some(U64::from(index)),
+            ..Transaction::default()
+        };
+        // For EIP-1559 txs the effective gas price is
+        // `min(max_priority_fee_per_gas + base_fee, max_fee_per_gas)`.
+        if tx.gas_price.is_none() {
+            if let (Some(fee_cap), Some(tip_cap), Some(base_fee)) = (
+                tx.max_fee_per_gas,
+                tx.max_priority_fee_per_gas,
+                header.base_fee_per_gas,
+            ) {
+                tx.gas_price = Some(fee_cap.min(tip_cap + base_fee));
+            }
+        }
+        tx.hash = tx.hash();
+
+        Ok(tx)
+    }
+
+    /// parse a vector of address=>(storage,balance,code,nonce) entry
+    fn parse_accounts(
+        accounts: &HashMap<String, JsonAccount>,
+    ) -> Result<BTreeMap<Address, Account>> {
+        let mut parsed = BTreeMap::new();
+        for (address, acc) in accounts {
+            let address = parse::parse_address(address)?;
+            let mut storage = HashMap::new();
+            for (k, v) in &acc.storage {
+                storage.insert(parse::parse_u256(k)?, parse::parse_u256(v)?);
+            }
+            parsed.insert(
+                address,
+                Account {
+                    address,
+                    balance: parse::parse_u256(&acc.balance)?,
+                    nonce: parse::parse_u256(&acc.nonce)?,
+                    code: parse::parse_bytes(&acc.code)?,
+                    storage,
+                },
+            );
+        }
+        Ok(parsed)
+    }
+
+    /// post state in fixtures is complete, so every field is checked
+    fn parse_accounts_post(
+        accounts: &HashMap<String, JsonAccount>,
+    ) -> Result<HashMap<Address, AccountMatch>> {
+        Ok(Self::parse_accounts(accounts)?
+            .into_iter()
+            .map(|(address, acc)| {
+                (
+                    address,
+                    AccountMatch {
+                        address,
+                        balance: Some(acc.balance),
+                        code: Some(acc.code),
+                        nonce: Some(acc.nonce),
+                        storage: acc.storage,
+                    },
+                )
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use eth_types::{address, H256};
+    use serde_json::{json, Value};
+
+    const MAINNET_GENESIS_HASH: &str =
+        "0xd4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3";
+
+    fn mainnet_genesis_header() -> Value {
+        json!({
+            "bloom": format!("0x{}", "00".repeat(256)),
+            "coinbase": "0x0000000000000000000000000000000000000000",
+            "difficulty": "0x0400000000",
+            "extraData": "0x11bbe8db4e347b4e8c937c1c8370e4b5ed33adb3db69cbdb7a38e1e50b1b82fa",
+            "gasLimit": "0x1388",
+            "gasUsed": "0x00",
+            "hash": MAINNET_GENESIS_HASH,
+            "mixHash": "0x0000000000000000000000000000000000000000000000000000000000000000",
+            "nonce": "0x0000000000000042",
+            "number": "0x00",
+            "parentHash": "0x0000000000000000000000000000000000000000000000000000000000000000",
+            "receiptTrie": "0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421",
+            "stateRoot": "0xd7f8974fb5ac78d9ac099b9ad5018bedc2ce0a72dad1827a1709da30580f0544",
+            "timestamp": "0x00",
+            "transactionsTrie": "0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421",
+            "uncleHash": "0x1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347"
+        })
+    }
+
+    fn block_test(network: &str) -> Value {
+        let mut header = mainnet_genesis_header();
+        header["number"] = json!("0x01");
+        header["parentHash"] = json!(MAINNET_GENESIS_HASH);
+        header["baseFeePerGas"] = json!("0x0a");
+        header["withdrawalsRoot"] =
+            json!("0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421");
+        json!({
+            "network": network,
+            "genesisBlockHeader": mainnet_genesis_header(),
+            "pre": {
+                "0xa94f5374fce5edbc8e2a8697c15331677e6ebf0b": {
+                    "balance": "0x3b9aca00",
+                    "code": "0x",
+                    "nonce": "0x00",
+                    "storage": {}
+                }
+            },
+            "postState": {
+                "0x095e7baea6a6c7c4c2dfeb977efac326af552d87": {
+                    "balance": "0x01",
+                    "code": "0x600160005500",
+                    "nonce": "0x00",
+                    "storage": { "0x00": "0x01" }
+                }
+            },
+            "lastblockhash": MAINNET_GENESIS_HASH,
+            "blocks": [
+                {
+                    "blockHeader": header,
+                    "transactions": [
+                        {
+                            "type": "0x02",
+                            "chainId": "0x01",
+                            "data": "0x",
+                            "gasLimit": "0x5208",
+                            "maxFeePerGas": "0x14",
+                            "maxPriorityFeePerGas": "0x02",
+                            "accessList": [],
+                            "nonce": "0x00",
+                            "r": "0x01",
+                            "s": "0x02",
+                            "v": "0x01",
+                            "sender": "0xa94f5374fce5edbc8e2a8697c15331677e6ebf0b",
+                            "to": "0x095e7baea6a6c7c4c2dfeb977efac326af552d87",
+                            "value": "0x01"
+                        }
+                    ],
+                    "withdrawals": [
+                        {
+                            "index": "0x00",
+                            "validatorIndex": "0x00",
+                            "address": "0x095e7baea6a6c7c4c2dfeb977efac326af552d87",
+                            "amount": "0x02"
+                        }
+                    ]
+                },
+                {
+                    "expectException": "TransactionException.INTRINSIC_GAS_TOO_LOW",
+                    "rlp": "0xc0"
+                }
+            ]
+        })
+    }
+
+    #[test]
+    fn parse_block_test() -> Result<()> {
+        let network = if cfg!(feature = "shanghai") {
+            "Shanghai"
+        } else {
+            "Paris"
+        };
+        let source = json!({
+            "block_test": block_test(network),
+            "transition": block_test("ParisToShanghaiAtTime15k"),
+            "unsupported_transition": block_test("BerlinToLondonAt5"),
+            "other_fork": block_test("Berlin"),
+        })
+        .to_string();
+
+        // tests of other forks are dropped, transitions are kept
+        let mut tests = JsonBlockTestBuilder::load_json("test_path", &source)?;
+        tests.sort_by(|a, b| a.id.cmp(&b.id));
+        assert_eq!(tests.len(), 3);
+
+        let transition = &tests[1];
+        assert_eq!(transition.id, "transition");
+        let chain_spec = transition.chain_spec.as_ref().unwrap();
+        assert_eq!(chain_spec.rules(1, 14_999).hardfork, Hardfork::London);
+        assert_eq!(chain_spec.rules(1, 15_000).hardfork, Hardfork::Shanghai);
+        assert_eq!(chain_spec.scroll, cfg!(feature = "scroll"));
+        // the circuits can not run it, it is reported as skipped
+        assert_eq!(tests[2].id, "unsupported_transition");
+        assert_eq!(tests[2].chain_spec, None);
+
+        let test = &tests[0];
+        assert_eq!(test.id, "block_test");
+        assert_eq!(test.chain_spec, Some(ChainSpec::default()));
+        assert_eq!(test.genesis.hash, H256::from_str(MAINNET_GENESIS_HASH)?);
+        assert_eq!(test.genesis.compute_hash(), test.genesis.hash);
+        assert_eq!(test.pre.len(), 1);
+        let post = test.post.as_ref().unwrap();
+        let account = &post[&address!("0x095e7baea6a6c7c4c2dfeb977efac326af552d87")];
+        assert_eq!(account.storage[&U256::zero()], U256::one());
+
+        let TestBlock::Valid {
+            header,
+            transactions,
+            withdrawals,
+        } = &test.blocks[0]
+        else {
+            panic!("the first block is valid");
+        };
+        assert_eq!(header.number, 1);
+        assert_eq!(header.base_fee_per_gas, Some(U256::from(10)));
+        assert!(header.withdrawals_root.is_some());
+
+        let tx = &transactions[0];
+        assert_eq!(tx.transaction_type, Some(U64::from(2)));
+        // min(max_priority_fee_per_gas + base_fee, max_fee_per_gas)
+        assert_eq!(tx.gas_price, Some(U256::from(12)));
+        assert_eq!(tx.block_number, Some(U64::from(1)));
+        assert_eq!(tx.transaction_index, Some(U64::zero()));
+        assert_eq!(tx.hash, tx.hash());
+        // withdrawal amounts are converted from gwei to wei
+        assert_eq!(
+            withdrawals,
+            &vec![(
+                address!("0x095e7baea6a6c7c4c2dfeb977efac326af552d87"),
+                U256::from(2_000_000_000u64)
+            )]
+        );
+
+        assert_eq!(test.blocks[1], TestBlock::Invalid);
+        Ok(())
+    }
+}
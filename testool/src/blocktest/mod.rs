@@ -0,0 +1,10 @@
+mod executor;
+mod json;
+mod receipt;
+pub mod spec;
+mod suite;
+
+pub use executor::{run_block_test, BlockTestError};
+pub use json::JsonBlockTestBuilder;
+pub use spec::{BlockHeader, BlockTest, TestBlock};
+pub use suite::{load_blocktests_suite, run_blocktests_suite};
@@ -0,0 +1,291 @@
+use bus_mapping::{circuit_input_builder::Block, operation::TxLogField};
+use eth_types::{Address, GethExecTrace, ToAddress, ToBigEndian, Transaction, H256};
+use ethers_core::utils::{
+    keccak256,
+    rlp::{self, RlpStream},
+};
+use std::collections::BTreeMap;
+
+/// Root of an empty trie, i.e. the keccak of the rlp of an empty string.
+pub const EMPTY_TRIE_ROOT: H256 = H256([
+    0x56, 0xe8, 0x1f, 0x17, 0x1b, 0xcc, 0x55, 0xa6, 0xff, 0x83, 0x45, 0xe6, 0x92, 0xc0, 0xf8, 0x6e,
+    0x5b, 0x48, 0xe0, 0x1b, 0x99, 0x6c, 0xad, 0xc0, 0x01, 0x62, 0x2f, 0xb5, 0xe3, 0x63, 0xb4, 0x21,
+]);
+
+/// A log emitted by a tx.
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct Log {
+    pub address: Address,
+    pub topics: Vec<H256>,
+    pub data: Vec<u8>,
+}
+
+/// The receipt of a tx.
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct Receipt {
+    /// EIP-2718 type of the tx, `None` for legacy txs
+    pub tx_type: Option<u8>,
+    pub success: bool,
+    pub cumulative_gas_used: u64,
+    pub logs: Vec<Log>,
+}
+
+impl Receipt {
+    /// The bloom filter of the logs.
+    pub fn bloom(&self) -> [u8; 256] {
+        let mut bloom = [0u8; 256];
+        let items = self.logs.iter().flat_map(|log| {
+            std::iter::once(keccak256(log.address))
+                .chain(log.topics.iter().map(|topic| keccak256(topic)))
+        });
+        for hash in items {
+            for i in 0..3 {
+                let bit = (usize::from(hash[2 * i]) << 8 | usize::from(hash[2 * i + 1])) & 2047;
+                bloom[255 - bit / 8] |= 1 << (bit % 8);
+            }
+        }
+        bloom
+    }
+
+    /// The encoding of the receipt in the receipts trie: the rlp of
+    /// `[status, cumulative_gas_used, bloom, logs]`, prefixed by the tx type for
+    /// typed txs.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut stream = RlpStream::new_list(4);
+        stream
+            .append(&u8::from(self.success))
+            .append(&self.cumulative_gas_used)
+            .append(&self.bloom().to_vec());
+        stream.begin_list(self.logs.len());
+        for log in &self.logs {
+            stream
+                .begin_list(3)
+                .append(&log.address)
+                .append_list::<H256, _>(&log.topics)
+                .append(&log.data);
+        }
+        self.tx_type
+            .into_iter()
+            .chain(stream.out().iter().copied())
+            .collect()
+    }
+}
+
+/// Rebuild the receipts of the `transactions` of a block from their traces and
+/// the `TxLog` operations of the block witness.
+pub fn receipts(
+    transactions: &[Transaction],
+    geth_traces: &[GethExecTrace],
+    block: &Block,
+) -> Vec<Receipt> {
+    // logs by tx id (starting at 1) and log id
+    let mut logs: BTreeMap<(usize, usize), Log> = BTreeMap::new();
+    let mut topics: BTreeMap<(usize, usize), BTreeMap<usize, H256>> = BTreeMap::new();
+    let mut data: BTreeMap<(usize, usize), BTreeMap<usize, u8>> = BTreeMap::new();
+    for op in &block.container.tx_log {
+        let op = op.op();
+        let id = (op.tx_id, op.log_id);
+        logs.entry(id).or_default();
+        match op.field {
+            TxLogField::Address => logs.get_mut(&id).unwrap().address = op.value.to_address(),
+            TxLogField::Topic => {
+                topics
+                    .entry(id)
+                    .or_default()
+                    .insert(op.index, H256(op.value.to_be_bytes()));
+            }
+            TxLogField::Data => {
+                data.entry(id)
+                    .or_default()
+                    .insert(op.index, op.value.low_u64() as u8);
+            }
+        }
+    }
+    for (id, log) in logs.iter_mut() {
+        log.topics = topics
+            .remove(id)
+            .unwrap_or_default()
+            .into_values()
+            .collect();
+        log.data = data.remove(id).unwrap_or_default().into_values().collect();
+    }
+
+    let mut cumulative_gas_used = 0;
+    transactions
+        .iter()
+        .zip(geth_traces)
+        .enumerate()
+        .map(|(index, (tx, trace))| {
+            cumulative_gas_used += trace.gas.0;
+            Receipt {
+                tx_type: tx.transaction_type.map(|tx_type| tx_type.as_u64() as u8),
+                success: !trace.failed,
+                cumulative_gas_used,
+                logs: logs
+                    .range((index + 1, 0)..(index + 2, 0))
+                    .map(|(_, log)| log.clone())
+                    .collect(),
+            }
+        })
+        .collect()
+}
+
+/// Root of the trie holding `values` at the keys `rlp(index)`, as the txs and
+/// receipts tries of a block.
+pub fn ordered_trie_root(values: impl IntoIterator<Item = Vec<u8>>) -> H256 {
+    trie_root(
+        values
+            .into_iter()
+            .enumerate()
+            .map(|(index, value)| (rlp::encode(&(index as u64)).to_vec(), value)),
+    )
+}
+
+/// Root of the trie holding the `(key, value)` items.
+pub fn trie_root(items: impl IntoIterator<Item = (Vec<u8>, Vec<u8>)>) -> H256 {
+    let mut items: Vec<_> = items
+        .into_iter()
+        .map(|(key, value)| (nibbles(&key), value))
+        .collect();
+    if items.is_empty() {
+        return EMPTY_TRIE_ROOT;
+    }
+    items.sort();
+    H256(keccak256(encode_node(&items, 0)))
+}
+
+/// Encode the node holding the `items`, sorted by path, below `depth` nibbles.
+fn encode_node(items: &[(Vec<u8>, Vec<u8>)], depth: usize) -> Vec<u8> {
+    if let [(path, value)] = items {
+        let mut stream = RlpStream::new_list(2);
+        stream
+            .append(&encode_path(&path[depth..], true))
+            .append(value);
+        return stream.out().to_vec();
+    }
+
+    let first = &items[0].0;
+    let prefix = items
+        .iter()
+        .map(|(path, _)| common_prefix(&first[depth..], &path[depth..]))
+        .min()
+        .unwrap_or_default();
+    if prefix > 0 {
+        let mut stream = RlpStream::new_list(2);
+        stream.append(&encode_path(&first[depth..depth + prefix], false));
+        append_child(&mut stream, encode_node(items, depth + prefix));
+        return stream.out().to_vec();
+    }
+
+    // a key ending at the branch is the shortest one, so it comes first
+    let (value, items) = match items.split_first() {
+        Some(((path, value), rest)) if path.len() == depth => (Some(value), rest),
+        _ => (None, items),
+    };
+    let mut stream = RlpStream::new_list(17);
+    for nibble in 0..16 {
+        let children: Vec<_> = items
+            .iter()
+            .filter(|(path, _)| path[depth] == nibble)
+            .cloned()
+            .collect();
+        if children.is_empty() {
+            stream.append_empty_data();
+        } else {
+            append_child(&mut stream, encode_node(&children, depth + 1));
+        }
+    }
+    match value {
+        Some(value) => stream.append(value),
+        None => stream.append_empty_data(),
+    };
+    stream.out().to_vec()
+}
+
+/// Nodes shorter than a hash are embedded in their parent.
+fn append_child(stream: &mut RlpStream, node: Vec<u8>) {
+    if node.len() < 32 {
+        stream.append_raw(&node, 1);
+    } else {
+        stream.append(&H256(keccak256(&node)));
+    }
+}
+
+fn nibbles(bytes: &[u8]) -> Vec<u8> {
+    bytes
+        .iter()
+        .flat_map(|byte| [byte >> 4, byte & 0xf])
+        .collect()
+}
+
+fn common_prefix(a: &[u8], b: &[u8]) -> usize {
+    a.iter().zip(b).take_while(|(a, b)| a == b).count()
+}
+
+/// Hex prefix encoding of a path
+fn encode_path(path: &[u8], is_leaf: bool) -> Vec<u8> {
+    let flag = if is_leaf { 2 } else { 0 };
+    let (first, rest) = if path.len() % 2 == 1 {
+        ((flag + 1) << 4 | path[0], &path[1..])
+    } else {
+        (flag << 4, path)
+    };
+    std::iter::once(first)
+        .chain(rest.chunks(2).map(|pair| pair[0] << 4 | pair[1]))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn empty_trie_root() {
+        assert_eq!(ordered_trie_root(Vec::new()), EMPTY_TRIE_ROOT);
+    }
+
+    #[test]
+    fn trie_root_with_branch_value() {
+        // the "puppy" vector of the ethereum/tests trie tests
+        let items = [
+            ("doe", "reindeer"),
+            ("dog", "puppy"),
+            ("dogglesworth", "cat"),
+        ]
+        .map(|(key, value)| (key.as_bytes().to_vec(), value.as_bytes().to_vec()));
+        assert_eq!(
+            trie_root(items),
+            H256::from_str("0x8aad789dff2f538bca5d8ea56e8abe10f4c7ba3a5dea95fea4cd6e7c3a1168d3")
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn receipt_bloom_and_encoding() {
+        let receipt = Receipt {
+            tx_type: Some(2),
+            success: true,
+            cumulative_gas_used: 21000,
+            logs: vec![Log {
+                address: Address::repeat_byte(0x11),
+                topics: vec![H256::repeat_byte(0x22)],
+                data: vec![1, 2, 3],
+            }],
+        };
+        // 3 bits per address and topic
+        let bits = receipt
+            .bloom()
+            .iter()
+            .map(|byte| byte.count_ones())
+            .sum::<u32>();
+        assert!((1..=6).contains(&bits));
+        let encoded = receipt.encode();
+        assert_eq!(encoded[0], 2);
+        let legacy = Receipt {
+            tx_type: None,
+            ..receipt
+        };
+        assert_eq!(&legacy.encode()[..], &encoded[1..]);
+    }
+}
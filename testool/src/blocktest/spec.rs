@@ -0,0 +1,187 @@
+use crate::statetest::StateTestResult;
+use eth_types::{
+    chain_spec::ChainSpec, geth_types::Account, Address, Bytes, Transaction, H256, U256,
+};
+use ethers_core::utils::{keccak256, rlp::RlpStream};
+use std::collections::BTreeMap;
+
+/// Header of a block in a `BlockchainTests` fixture.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct BlockHeader {
+    pub parent_hash: H256,
+    pub uncle_hash: H256,
+    pub coinbase: Address,
+    pub state_root: H256,
+    pub transactions_trie: H256,
+    pub receipt_trie: H256,
+    pub bloom: Bytes,
+    pub difficulty: U256,
+    pub number: u64,
+    pub gas_limit: u64,
+    pub gas_used: u64,
+    pub timestamp: u64,
+    pub extra_data: Bytes,
+    pub mix_hash: H256,
+    pub nonce: Bytes,
+    /// Only present after London
+    pub base_fee_per_gas: Option<U256>,
+    /// Only present after Shanghai
+    pub withdrawals_root: Option<H256>,
+    /// Hash of the header, as declared in the fixture
+    pub hash: H256,
+}
+
+impl BlockHeader {
+    /// Compute the hash of the header, i.e. the keccak of its rlp encoding.
+    pub fn compute_hash(&self) -> H256 {
+        let fields = 15
+            + usize::from(self.base_fee_per_gas.is_some())
+            + usize::from(self.withdrawals_root.is_some());
+        let mut stream = RlpStream::new_list(fields);
+        stream
+            .append(&self.parent_hash)
+            .append(&self.uncle_hash)
+            .append(&self.coinbase)
+            .append(&self.state_root)
+            .append(&self.transactions_trie)
+            .append(&self.receipt_trie)
+            .append(&self.bloom)
+            .append(&self.difficulty)
+            .append(&self.number)
+            .append(&self.gas_limit)
+            .append(&self.gas_used)
+            .append(&self.timestamp)
+            .append(&self.extra_data)
+            .append(&self.mix_hash)
+            .append(&self.nonce);
+        if let Some(base_fee) = &self.base_fee_per_gas {
+            stream.append(base_fee);
+        }
+        if let Some(withdrawals_root) = &self.withdrawals_root {
+            stream.append(withdrawals_root);
+        }
+        H256(keccak256(stream.out()))
+    }
+}
+
+/// A block of a `BlockchainTests` fixture.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum TestBlock {
+    /// A block that is expected to be imported.
+    Valid {
+        header: BlockHeader,
+        transactions: Vec<Transaction>,
+        /// Withdrawals as `(address, amount in wei)`
+        withdrawals: Vec<(Address, U256)>,
+    },
+    /// A block that is expected to be rejected by the client. It does not
+    /// change the chain, so it is not executed.
+    Invalid,
+}
+
+/// A test of the `BlockchainTests` suite: a chain of blocks applied on top of
+/// a genesis state.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct BlockTest {
+    pub path: String,
+    pub id: String,
+    pub network: String,
+    /// Hardfork schedule of the network, `None` if the network is a
+    /// transition the circuits can not run.
+    pub chain_spec: Option<ChainSpec>,
+    pub genesis: BlockHeader,
+    pub pre: BTreeMap<Address, Account>,
+    /// Expected post state. Big fixtures only provide the state root, in
+    /// which case this is `None`.
+    pub post: Option<StateTestResult>,
+    pub last_block_hash: H256,
+    pub blocks: Vec<TestBlock>,
+}
+
+impl std::fmt::Display for BlockTest {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        use prettytable::Table;
+        let mut table = Table::new();
+        table.add_row(row!["id", self.id]);
+        table.add_row(row!["path", self.path]);
+        table.add_row(row!["network", self.network]);
+        table.add_row(row!["genesis", format!("{:?}", self.genesis.hash)]);
+        for (idx, block) in self.blocks.iter().enumerate() {
+            match block {
+                TestBlock::Valid {
+                    header,
+                    transactions,
+                    ..
+                } => table.add_row(row![
+                    format!("block[{idx}]"),
+                    format!(
+                        "number {} hash {:?} txs {}",
+                        header.number,
+                        header.hash,
+                        transactions.len()
+                    )
+                ]),
+                TestBlock::Invalid => table.add_row(row![format!("block[{idx}]"), "invalid"]),
+            };
+        }
+        table.add_row(row!["lastblockhash", format!("{:?}", self.last_block_hash)]);
+        write!(f, "{table}")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::str::FromStr;
+
+    fn mainnet_genesis_header() -> BlockHeader {
+        let hash = |s: &str| H256::from_str(s).unwrap();
+        let empty_trie_root =
+            hash("0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421");
+        BlockHeader {
+            parent_hash: H256::zero(),
+            uncle_hash: hash("0x1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347"),
+            coinbase: Address::zero(),
+            state_root: hash("0xd7f8974fb5ac78d9ac099b9ad5018bedc2ce0a72dad1827a1709da30580f0544"),
+            transactions_trie: empty_trie_root,
+            receipt_trie: empty_trie_root,
+            bloom: vec![0; 256].into(),
+            difficulty: U256::from(0x400000000u64),
+            number: 0,
+            gas_limit: 5000,
+            gas_used: 0,
+            timestamp: 0,
+            extra_data: Bytes::from_str(
+                "0x11bbe8db4e347b4e8c937c1c8370e4b5ed33adb3db69cbdb7a38e1e50b1b82fa",
+            )
+            .unwrap(),
+            mix_hash: H256::zero(),
+            nonce: vec![0, 0, 0, 0, 0, 0, 0, 0x42].into(),
+            base_fee_per_gas: None,
+            withdrawals_root: None,
+            hash: hash("0xd4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3"),
+        }
+    }
+
+    #[test]
+    fn compute_mainnet_genesis_hash() {
+        let header = mainnet_genesis_header();
+        assert_eq!(header.compute_hash(), header.hash);
+    }
+
+    #[test]
+    fn compute_hash_with_optional_fields() {
+        // the optional fields are appended to the rlp list
+        let header = mainnet_genesis_header();
+        let london = BlockHeader {
+            base_fee_per_gas: Some(U256::from(7)),
+            ..header.clone()
+        };
+        let shanghai = BlockHeader {
+            withdrawals_root: Some(header.transactions_trie),
+            ..london.clone()
+        };
+        assert_ne!(london.compute_hash(), header.hash);
+        assert_ne!(shanghai.compute_hash(), london.compute_hash());
+    }
+}
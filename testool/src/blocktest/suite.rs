@@ -0,0 +1,120 @@
+use super::{executor::run_block_test, BlockTest, JsonBlockTestBuilder};
+use crate::{
+    config::{Config, TestSuite},
+    statetest::{CircuitsConfig, ResultInfo, ResultLevel, Results},
+};
+use anyhow::{Context, Result};
+use rayon::prelude::*;
+use std::{
+    panic::AssertUnwindSafe,
+    sync::{Arc, RwLock},
+};
+
+pub fn load_blocktests_suite(suite: &TestSuite, config: Config) -> Result<Vec<BlockTest>> {
+    let skip_paths: Vec<&String> = config.skip_paths.iter().flat_map(|t| &t.paths).collect();
+    let skip_tests: Vec<&String> = config.skip_tests.iter().flat_map(|t| &t.tests).collect();
+
+    let tcs = glob::glob(&suite.path)
+        .context("failed to read glob")?
+        .filter_map(|v| v.ok())
+        .filter(|f| {
+            !skip_paths
+                .iter()
+                .any(|e| f.as_path().to_string_lossy().contains(*e))
+        })
+        .filter(|f| f.extension().map_or(false, |ext| ext == "json"))
+        .par_bridge()
+        .map(|file| {
+            let path = file.as_path().to_string_lossy();
+            let src = std::fs::read_to_string(&file)?;
+            log::debug!(target: "testool", "Reading file {:?}", file);
+            let mut tcs = JsonBlockTestBuilder::load_json(&path, &src)
+                .with_context(|| format!("fail to load {path:?}"))?;
+            tcs.retain(|v| !skip_tests.contains(&&v.id) && suite.allowed(&v.id));
+            Ok(tcs)
+        })
+        .collect::<Result<Vec<Vec<BlockTest>>>>()?
+        .into_iter()
+        .flatten()
+        .collect::<Vec<BlockTest>>();
+    Ok(tcs)
+}
+
+pub fn run_blocktests_suite(
+    tcs: Vec<BlockTest>,
+    circuits_config: &CircuitsConfig,
+    suite: &TestSuite,
+    results: &mut Results,
+) -> Result<()> {
+    // Filter already cached entries
+    let all_test_count = tcs.len();
+    let tcs: Vec<BlockTest> = tcs
+        .into_iter()
+        .filter(|t| !results.contains(&format!("{}#{}", t.id, t.path)))
+        .collect();
+
+    log::info!(
+        "{} test results cached, {} remaining",
+        all_test_count - tcs.len(),
+        tcs.len()
+    );
+
+    let results = Arc::new(RwLock::from(results));
+
+    let run_block_test_case = |tc: &BlockTest| {
+        let (test_id, path) = (tc.id.clone(), tc.path.clone());
+        std::panic::set_hook(Box::new(|_info| {}));
+
+        log::debug!(target: "testool", "🐕 running block test {}#{}...", test_id, path);
+        let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+            run_block_test(tc.clone(), suite.clone(), circuits_config.clone())
+        }));
+
+        let (level, details) = match result {
+            Ok(Ok(())) => (ResultLevel::Success, String::default()),
+            Ok(Err(err)) => (
+                if err.is_skip() {
+                    ResultLevel::Ignored
+                } else {
+                    ResultLevel::Fail
+                },
+                err.to_string(),
+            ),
+            Err(err) => {
+                let panic_err = if let Some(s) = err.downcast_ref::<String>() {
+                    s.to_string()
+                } else if let Some(s) = err.downcast_ref::<&str>() {
+                    s.to_string()
+                } else {
+                    "unable to get panic info".into()
+                };
+                let level = if panic_err.contains("circuit was not satisfied") {
+                    ResultLevel::Fail
+                } else if panic_err.contains("evm_unimplemented") {
+                    ResultLevel::Ignored
+                } else {
+                    ResultLevel::Panic
+                };
+                (level, panic_err)
+            }
+        };
+
+        results
+            .write()
+            .unwrap()
+            .insert(ResultInfo {
+                test_id,
+                level,
+                details,
+                path,
+            })
+            .unwrap();
+    };
+
+    if circuits_config.super_circuit {
+        tcs.iter().for_each(run_block_test_case);
+    } else {
+        tcs.par_iter().for_each(run_block_test_case);
+    }
+    Ok(())
+}
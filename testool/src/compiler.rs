@@ -2,7 +2,7 @@
 
 use anyhow::{bail, Context, Result};
 use eth_types::{bytecode, Bytecode, Bytes, H256};
-use keccak256::plain::Keccak;
+use keccak256::keccak256;
 use serde::{Deserialize, Serialize};
 use std::{
     collections::HashMap,
@@ -61,9 +61,7 @@ impl Cache {
     }
 
     fn hash(src: &str) -> H256 {
-        let mut hash = Keccak::default();
-        hash.update(src.as_bytes());
-        H256::from_slice(&hash.digest())
+        H256(keccak256(src.as_bytes()))
     }
 }
 
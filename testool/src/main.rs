@@ -2,6 +2,8 @@
 
 /// Execute the bytecode from an empty state and run the EVM and State circuits
 mod abi;
+#[cfg(not(feature = "scroll"))]
+mod blocktest;
 mod compiler;
 mod config;
 mod statetest;
@@ -88,6 +90,16 @@ struct Args {
     #[clap(long)]
     exclude_test_ids: Option<String>,
 
+    /// Treat the suite path as `BlockchainTests` fixtures instead of
+    /// `GeneralStateTests` fillers
+    #[clap(long)]
+    blocktest: bool,
+
+    /// Size the sub circuits params to each witness block instead of using
+    /// the fixed ones. Not supported with the super circuit
+    #[clap(long)]
+    auto_params: bool,
+
     /// Verbose
     #[clap(short, long)]
     v: bool,
@@ -135,7 +147,7 @@ fn run_single_test(test: StateTest, circuits_config: CircuitsConfig) -> Result<(
     log::info!("run single test {}", &test);
     let circuits_config = CircuitsConfig {
         verbose: true,
-        super_circuit: circuits_config.super_circuit,
+        ..circuits_config
     };
     //let trace = geth_trace(test.clone())?;
     //crate::utils::print_trace(trace)?;
@@ -146,6 +158,35 @@ fn run_single_test(test: StateTest, circuits_config: CircuitsConfig) -> Result<(
     Ok(())
 }
 
+#[cfg(not(feature = "scroll"))]
+fn run_blocktests(suite_name: &str, config: Config, circuits_config: CircuitsConfig) -> Result<()> {
+    let suite = config.suite(suite_name)?.clone();
+    let block_tests = blocktest::load_blocktests_suite(&suite, config)?;
+    log::info!(
+        "{} block tests collected in {}",
+        block_tests.len(),
+        suite.path
+    );
+
+    let mut results = Results::default();
+    log::info!("Executing...");
+    blocktest::run_blocktests_suite(block_tests, &circuits_config, &suite, &mut results)?;
+    let success = results.success();
+
+    log::info!("Generating report...");
+    results.report(None).print_tty()?;
+
+    if !success {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+#[cfg(feature = "scroll")]
+fn run_blocktests(_: &str, _: Config, _: CircuitsConfig) -> Result<()> {
+    bail!("BlockchainTests are not supported with the `scroll` feature")
+}
+
 fn go() -> Result<()> {
     //  RAYON_NUM_THREADS=1 RUST_BACKTRACE=1 cargo run -- --path
     // "tests/src/GeneralStateTestsFiller/**/" --skip-state-circuit
@@ -156,6 +197,11 @@ fn go() -> Result<()> {
     if args.circuits == Some(Circuits::sc) {
         circuits_config.super_circuit = true;
     }
+    if args.auto_params && circuits_config.super_circuit {
+        // the super circuit params are fixed by its const generics
+        bail!("--auto-params only applies to the sub circuits, not to the super circuit");
+    }
+    circuits_config.auto_params = args.auto_params;
 
     if let Some(oneliner) = &args.oneliner {
         let test = StateTest::parse_oneline_spec(oneliner)?;
@@ -168,6 +214,10 @@ fn go() -> Result<()> {
     env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
 
     log::info!("Using suite '{}'", args.suite);
+    if args.blocktest {
+        return run_blocktests(&args.suite, config, circuits_config);
+    }
+
     log::info!("Parsing and compliling tests...");
     let compiler = Compiler::new(true, Some(PathBuf::from(CODEHASH_FILE)))?;
     let suite = config.suite(&args.suite)?.clone();
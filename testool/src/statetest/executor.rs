@@ -4,7 +4,10 @@ use bus_mapping::{
     circuit_input_builder::{CircuitInputBuilder, CircuitsParams, PrecompileEcParams},
     state_db::CodeDB,
 };
-use eth_types::{geth_types, Address, Bytes, GethExecTrace, ToBigEndian, ToWord, H256, U256, U64};
+use eth_types::{
+    chain_spec::ChainSpec, evm_types::GasTable, geth_types, Address, Bytes, GethExecTrace,
+    ToBigEndian, ToWord, H256, U256, U64,
+};
 use ethers_core::utils::keccak256;
 use ethers_signers::LocalWallet;
 use external_tracer::{LoggerConfig, TraceConfig};
@@ -13,9 +16,14 @@ use itertools::Itertools;
 use std::{collections::HashMap, env, str::FromStr, sync::LazyLock};
 use thiserror::Error;
 use zkevm_circuits::{
-    bytecode_circuit::circuit::BytecodeCircuit, ecc_circuit::EccCircuit,
-    modexp_circuit::ModExpCircuit, sig_circuit::SigCircuit, super_circuit::SuperCircuit,
-    test_util::CircuitTestBuilder, util::SubCircuit, witness::Block,
+    bytecode_circuit::circuit::BytecodeCircuit,
+    ecc_circuit::EccCircuit,
+    modexp_circuit::ModExpCircuit,
+    sig_circuit::SigCircuit,
+    super_circuit::{params::min_circuits_params, SuperCircuit},
+    test_util::CircuitTestBuilder,
+    util::SubCircuit,
+    witness::Block,
 };
 
 /// Read env var with default value
@@ -81,6 +89,9 @@ impl StateTestError {
 pub struct CircuitsConfig {
     pub super_circuit: bool,
     pub verbose: bool,
+    /// Size the sub circuits params to the witness block, not supported with
+    /// the super circuit
+    pub auto_params: bool,
 }
 
 fn check_post(
@@ -151,20 +162,26 @@ fn into_traceconfig(st: StateTest) -> (String, TraceConfig, StateTestResult) {
     let rlp_signed = tx.rlp_signed(&sig).to_vec();
     let tx_hash = keccak256(tx.rlp_signed(&sig));
     let accounts = st.pre;
+    let block_constants = geth_types::BlockConstants {
+        coinbase: st.env.current_coinbase,
+        timestamp: U256::from(st.env.current_timestamp),
+        number: U64::from(st.env.current_number),
+        difficulty: st.env.current_difficulty,
+        gas_limit: U256::from(st.env.current_gas_limit),
+        base_fee: st.env.current_base_fee,
+    };
 
     (
         st.id,
         TraceConfig {
             chain_id: ETH_CHAIN_ID,
             history_hashes: vec![U256::from_big_endian(st.env.previous_hash.as_bytes())],
-            block_constants: geth_types::BlockConstants {
-                coinbase: st.env.current_coinbase,
-                timestamp: U256::from(st.env.current_timestamp),
-                number: U64::from(st.env.current_number),
-                difficulty: st.env.current_difficulty,
-                gas_limit: U256::from(st.env.current_gas_limit),
-                base_fee: st.env.current_base_fee,
-            },
+            chain_config: external_tracer::ChainConfig::from_chain_spec(
+                &ChainSpec::default(),
+                &block_constants,
+            )
+            .expect("the default chain spec is on the chain of the tracer"),
+            block_constants,
 
             transactions: vec![geth_types::Transaction {
                 tx_type,
@@ -178,6 +195,8 @@ fn into_traceconfig(st: StateTest) -> (String, TraceConfig, StateTestResult) {
                 gas_tip_cap: st.max_priority_fee_per_gas,
                 call_data: st.data,
                 access_list: st.access_list,
+                max_fee_per_blob_gas: None,
+                blob_versioned_hashes: vec![],
                 v,
                 r: sig.r,
                 s: sig.s,
@@ -194,10 +213,6 @@ fn into_traceconfig(st: StateTest) -> (String, TraceConfig, StateTestResult) {
                 disable_storage: !cfg!(feature = "enable-storage"),
                 ..Default::default()
             },
-            #[cfg(feature = "shanghai")]
-            chain_config: Some(external_tracer::ChainConfig::shanghai()),
-            #[cfg(not(feature = "shanghai"))]
-            chain_config: None,
             #[cfg(feature = "scroll")]
             l1_queue_index: 0,
         },
@@ -216,7 +231,7 @@ pub fn geth_trace(st: StateTest) -> Result<GethExecTrace, StateTestError> {
 }
 */
 
-fn check_geth_traces(
+pub(crate) fn check_geth_traces(
     geth_traces: &[GethExecTrace],
     suite: &TestSuite,
     verbose: bool,
@@ -404,6 +419,7 @@ fn trace_config_to_witness_block_l1(
         geth_traces: geth_traces.clone(),
         accounts: trace_config.accounts.values().cloned().collect(),
         eth_block: eth_block.clone(),
+        chain_spec: ChainSpec::default(),
     };
 
     let block_data =
@@ -423,6 +439,7 @@ fn trace_config_to_witness_block_l1(
 ////// params for degree = 20 ////////////
 pub const MAX_TXS: usize = 100;
 pub const MAX_INNER_BLOCKS: usize = 100;
+pub const MAX_L1_MSGS_POPPED: usize = 1024;
 pub const MAX_EXP_STEPS: usize = 10_000;
 pub const MAX_CALLDATA: usize = 350_000;
 pub const MAX_RLP_ROWS: usize = 800_000;
@@ -465,6 +482,7 @@ fn get_params_for_super_circuit_test_l2() -> CircuitsParams {
         max_calldata: MAX_CALLDATA,
         max_bytecode: MAX_BYTECODE,
         max_inner_blocks: MAX_INNER_BLOCKS,
+        max_l1_msgs_popped: MAX_L1_MSGS_POPPED,
         max_keccak_rows: MAX_KECCAK_ROWS,
         max_poseidon_rows: MAX_POSEIDON_ROWS,
         max_vertical_circuit_rows: MAX_VERTICAL_ROWS,
@@ -476,10 +494,11 @@ fn get_params_for_super_circuit_test_l2() -> CircuitsParams {
             ec_mul: MAX_PRECOMPILE_EC_MUL,
             ec_pairing: MAX_PRECOMPILE_EC_PAIRING,
         },
+        gas_table: GasTable::default(),
     }
 }
 
-fn get_params_for_super_circuit_test() -> CircuitsParams {
+pub(crate) fn get_params_for_super_circuit_test() -> CircuitsParams {
     CircuitsParams {
         max_txs: MAX_TXS,
         max_calldata: MAX_CALLDATA,
@@ -493,16 +512,18 @@ fn get_params_for_super_circuit_test() -> CircuitsParams {
         max_poseidon_rows: 0,
         max_vertical_circuit_rows: 0,
         max_inner_blocks: 64,
+        max_l1_msgs_popped: 256,
         max_rlp_rows: 512,
         max_ec_ops: PrecompileEcParams {
             ec_add: 50,
             ec_mul: 50,
             ec_pairing: 2,
         },
+        gas_table: GasTable::default(),
     }
 }
 
-fn get_params_for_sub_circuit_test() -> CircuitsParams {
+pub(crate) fn get_params_for_sub_circuit_test() -> CircuitsParams {
     CircuitsParams {
         max_txs: 1,
         max_rws: 0,      // dynamic
@@ -516,12 +537,14 @@ fn get_params_for_sub_circuit_test() -> CircuitsParams {
         max_poseidon_rows: 0,
         max_vertical_circuit_rows: MAX_VERTICAL_ROWS, // is it good?
         max_inner_blocks: 64,
+        max_l1_msgs_popped: 256,
         max_rlp_rows: 6000,
         max_ec_ops: PrecompileEcParams {
             ec_add: 50,
             ec_mul: 50,
             ec_pairing: 2,
         },
+        gas_table: GasTable::default(),
     }
 }
 
@@ -592,10 +615,14 @@ pub fn run_test(
         circuits_config.verbose,
     )?;
 
-    let (witness_block, mut builder) = match result {
+    let (mut witness_block, mut builder) = match result {
         Some((witness_block, builder)) => (witness_block, builder),
         None => return Ok(()),
     };
+    // rejected with the super circuit, whose params are fixed by `ScrollSuperCircuit`
+    if circuits_config.auto_params {
+        witness_block.circuits_params = min_circuits_params(&witness_block);
+    }
 
     log::debug!("witness_block created");
     //builder.sdb.list_accounts();
@@ -741,7 +768,7 @@ fn set_env_coinbase(coinbase: &Address) -> String {
 }
 
 #[cfg(not(any(feature = "inner-prove", feature = "chunk-prove")))]
-fn mock_prove(test_id: &str, witness_block: &Block<Fr>) {
+pub(crate) fn mock_prove(test_id: &str, witness_block: &Block<Fr>) {
     log::info!("{test_id}: mock-prove BEGIN");
     // TODO: do we need to automatically adjust this k?
     let k = 20;
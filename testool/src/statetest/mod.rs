@@ -1,17 +1,19 @@
 mod executor;
 mod json;
-mod parse;
+pub mod parse;
 mod results;
 pub mod spec;
 mod suite;
 mod yaml;
 
-pub use executor::{run_test, CircuitsConfig};
+#[cfg(not(feature = "scroll"))]
+pub(crate) use executor::{
+    check_geth_traces, get_params_for_sub_circuit_test, get_params_for_super_circuit_test,
+    mock_prove, MAX_TXS,
+};
+pub use executor::{run_test, CircuitsConfig, StateTestError};
 pub use json::JsonStateTestBuilder;
-pub use results::{ResultLevel, Results};
+pub use results::{ResultInfo, ResultLevel, Results};
 pub use spec::{AccountMatch, StateTest, StateTestResult};
 pub use suite::{load_statetests_suite, run_statetests_suite};
 pub use yaml::YamlStateTestBuilder;
-
-#[cfg(test)]
-pub use executor::StateTestError;
@@ -43,6 +43,7 @@ impl FromStr for MainnetFork {
         Ok(match s {
             "Shanghai" => Self::Shanghai,
             "Merge" => Self::Merge,
+            "Paris" => Self::Merge,
             "Gray Glacier" => Self::GrayGlacier,
             "Arrow Glacier" => Self::ArrowGlacier,
             "Altair" => Self::Altair,
@@ -28,7 +28,7 @@ use crate::{
     util::{SubCircuit, SubCircuitConfig},
 };
 use bus_mapping::evm::OpcodeId;
-use eth_types::Field;
+use eth_types::{chain_spec::ChainRules, evm_types::GasTable, Field};
 use execution::ExecutionConfig;
 use itertools::Itertools;
 use strum::IntoEnumIterator;
@@ -179,12 +179,18 @@ impl<F: Field> EvmCircuitConfig<F> {
         &self,
         layouter: &mut impl Layouter<F>,
         fixed_table_tags: Vec<FixedTableTag>,
+        gas_table: &GasTable,
+        rules: &ChainRules,
     ) -> Result<(), Error> {
         layouter.assign_region(
             || "fixed table",
             |mut region| {
                 for (offset, row) in std::iter::once([F::zero(); 4])
-                    .chain(fixed_table_tags.iter().flat_map(|tag| tag.build()))
+                    .chain(
+                        fixed_table_tags
+                            .iter()
+                            .flat_map(|tag| tag.build(gas_table, rules)),
+                    )
                     .enumerate()
                 {
                     for (column, value) in self.fixed_table.iter().zip_eq(row) {
@@ -289,7 +295,9 @@ impl<F: Field> EvmCircuit<F> {
     }
 }
 
-const FIXED_TABLE_ROWS_NO_BITWISE: usize = 3647;
+// Rows of the fixed table under the rules of Cancun, which define the most
+// opcodes.
+const FIXED_TABLE_ROWS_NO_BITWISE: usize = 3649;
 const FIXED_TABLE_ROWS: usize = FIXED_TABLE_ROWS_NO_BITWISE + 3 * 65536;
 
 impl<F: Field> SubCircuit<F> for EvmCircuit<F> {
@@ -332,7 +340,12 @@ impl<F: Field> SubCircuit<F> for EvmCircuit<F> {
     ) -> Result<(), Error> {
         let block = self.block.as_ref().unwrap();
 
-        config.load_fixed_table(layouter, self.fixed_table_tags.clone())?;
+        config.load_fixed_table(
+            layouter,
+            self.fixed_table_tags.clone(),
+            &block.circuits_params.gas_table,
+            &block.rules,
+        )?;
         config.load_byte_table(layouter)?;
         config.pow_of_rand_table.assign(layouter, challenges)?;
         let export = config.execution.assign_block(layouter, block, challenges)?;
@@ -575,7 +588,13 @@ mod evm_circuit_stats {
     };
     use bus_mapping::{circuit_input_builder::CircuitsParams, mock::BlockData};
     use cli_table::{print_stdout, Cell, Style, Table};
-    use eth_types::{bytecode, evm_types::OpcodeId, geth_types::GethData, ToWord};
+    use eth_types::{
+        bytecode,
+        chain_spec::{ChainRules, ChainSpec},
+        evm_types::{GasTable, OpcodeId},
+        geth_types::GethData,
+        ToWord,
+    };
     use halo2_proofs::{
         dev::MockProver,
         halo2curves::bn256::Fr,
@@ -593,34 +612,41 @@ mod evm_circuit_stats {
 
     #[test]
     fn test_fixed_table_rows() {
-        let row_num_by_tags = |tags: Vec<FixedTableTag>| -> usize {
+        let row_num = |tags: Vec<FixedTableTag>, rules: &ChainRules| -> usize {
             tags.iter()
                 .map(|tag| {
-                    let count = tag.build::<Fr>().count();
+                    let count = tag.build::<Fr>(&GasTable::default(), rules).count();
                     log::debug!("fixed tab {tag:?} needs {count} rows");
                     count
                 })
                 .sum::<usize>()
         };
+        let no_bitwise = FixedTableTag::iter()
+            .filter(|t| {
+                !matches!(
+                    t,
+                    FixedTableTag::BitwiseAnd
+                        | FixedTableTag::BitwiseOr
+                        | FixedTableTag::BitwiseXor
+                )
+            })
+            .collect_vec();
+        let cancun = ChainSpec::cancun()
+            .with_scroll(cfg!(feature = "scroll"))
+            .rules(0, 0);
         assert_eq!(
             FIXED_TABLE_ROWS,
-            row_num_by_tags(FixedTableTag::iter().collect_vec())
+            row_num(FixedTableTag::iter().collect_vec(), &cancun)
         );
         assert_eq!(
             FIXED_TABLE_ROWS_NO_BITWISE,
-            row_num_by_tags(
-                FixedTableTag::iter()
-                    .filter(|t| {
-                        !matches!(
-                            t,
-                            FixedTableTag::BitwiseAnd
-                                | FixedTableTag::BitwiseOr
-                                | FixedTableTag::BitwiseXor
-                        )
-                    })
-                    .collect_vec()
-            )
+            row_num(no_bitwise.clone(), &cancun)
         );
+        // The earlier hardforks define fewer opcodes.
+        for chain_spec in [ChainSpec::london(), ChainSpec::shanghai()] {
+            let rules = chain_spec.with_scroll(cfg!(feature = "scroll")).rules(0, 0);
+            assert!(row_num(no_bitwise.clone(), &rules) <= FIXED_TABLE_ROWS_NO_BITWISE);
+        }
     }
 
     #[test]
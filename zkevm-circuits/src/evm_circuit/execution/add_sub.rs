@@ -59,7 +59,7 @@ impl<F: Field> ExecutionGadget<F> for AddSubGadget<F> {
             rw_counter: Delta(3.expr()),
             program_counter: Delta(1.expr()),
             stack_pointer: Delta(1.expr()),
-            gas_left: Delta(-OpcodeId::ADD.constant_gas_cost().expr()),
+            gas_left: cb.gas_left_transition(opcode.expr(), 0.expr()),
             ..StepStateTransition::default()
         };
         let same_context = SameContextGadget::construct(cb, opcode, step_state_transition);
@@ -104,8 +104,18 @@ impl<F: Field> ExecutionGadget<F> for AddSubGadget<F> {
 
 #[cfg(test)]
 mod test {
-    use crate::{evm_circuit::test::rand_word, test_util::CircuitTestBuilder};
-    use eth_types::{bytecode, evm_types::OpcodeId, Word};
+    use crate::{
+        evm_circuit::test::rand_word, test_util::CircuitTestBuilder, witness::block_convert,
+    };
+    use bus_mapping::{circuit_input_builder::CircuitsParams, mock::BlockData};
+    use eth_types::{
+        bytecode,
+        evm_types::{GasCost, GasSchedule, GasTable, OpcodeId},
+        geth_types::GethData,
+        Word,
+    };
+    use halo2_proofs::halo2curves::bn256::Fr;
+    use std::sync::Arc;
 
     use mock::TestContext;
 
@@ -136,4 +146,69 @@ mod test {
         test_ok(OpcodeId::ADD, a, b);
         test_ok(OpcodeId::SUB, a, b);
     }
+
+    #[test]
+    fn add_gadget_repriced() {
+        const ADD_GAS: u64 = 5;
+        let bytecode = bytecode! {
+            PUSH32(0x030201)
+            PUSH32(0x060504)
+            ADD
+            STOP
+        };
+        let mut block: GethData = TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode)
+            .unwrap()
+            .into();
+
+        // the trace of a chain charging ADD_GAS for ADD
+        let trace = &mut block.geth_traces[0];
+        let extra_gas = ADD_GAS - OpcodeId::ADD.constant_gas_cost().0;
+        let add_step = trace
+            .struct_logs
+            .iter()
+            .position(|step| step.op == OpcodeId::ADD)
+            .unwrap();
+        trace.struct_logs[add_step].gas_cost = GasCost(ADD_GAS);
+        for step in trace.struct_logs[add_step + 1..].iter_mut() {
+            step.gas.0 -= extra_gas;
+        }
+        trace.gas.0 += extra_gas;
+        trace.account_after.clear();
+
+        // the trace disagrees with the default gas schedule
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        assert!(builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .is_err());
+
+        // the circuits disagree with the gas schedule of the chain
+        let gas_table = GasTable::default().with_constant_gas_cost(OpcodeId::ADD, GasCost(ADD_GAS));
+        let mut builder = BlockData::new_from_geth_data(block.clone())
+            .new_circuit_input_builder()
+            .with_gas_schedule(Arc::new(gas_table));
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        assert!(block_convert::<Fr>(&builder.block, &builder.code_db).is_err());
+
+        let circuits_params = CircuitsParams {
+            gas_table,
+            ..Default::default()
+        };
+        let mut builder = BlockData::new_from_geth_data_with_params(block.clone(), circuits_params)
+            .new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        let block = block_convert::<Fr>(&builder.block, &builder.code_db).unwrap();
+        assert_eq!(
+            block
+                .circuits_params
+                .gas_table
+                .constant_gas_cost(OpcodeId::ADD),
+            GasCost(ADD_GAS)
+        );
+
+        CircuitTestBuilder::<2, 1>::new_from_block(block).run();
+    }
 }
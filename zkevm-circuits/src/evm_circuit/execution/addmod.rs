@@ -19,7 +19,6 @@ use crate::{
     util::Expr,
 };
 
-use bus_mapping::evm::OpcodeId;
 use eth_types::{Field, ToLittleEndian, U256, U512};
 use halo2_proofs::plonk::Error;
 
@@ -114,7 +113,7 @@ impl<F: Field> ExecutionGadget<F> for AddModGadget<F> {
             rw_counter: Delta(4.expr()),
             program_counter: Delta(1.expr()),
             stack_pointer: Delta(2.expr()),
-            gas_left: Delta(-OpcodeId::ADDMOD.constant_gas_cost().expr()),
+            gas_left: cb.gas_left_transition(opcode.expr(), 0.expr()),
             ..StepStateTransition::default()
         };
         let same_context = SameContextGadget::construct(cb, opcode, step_state_transition);
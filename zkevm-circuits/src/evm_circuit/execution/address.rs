@@ -13,7 +13,6 @@ use crate::{
     table::CallContextFieldTag,
     util::Expr,
 };
-use bus_mapping::evm::OpcodeId;
 use eth_types::{Field, ToAddress, ToLittleEndian};
 use halo2_proofs::plonk::Error;
 use std::convert::TryInto;
@@ -42,15 +41,15 @@ impl<F: Field> ExecutionGadget<F> for AddressGadget<F> {
 
         cb.stack_push(address.expr());
 
+        let opcode = cb.query_cell();
         let step_state_transition = StepStateTransition {
             rw_counter: Delta(2.expr()),
             program_counter: Delta(1.expr()),
             stack_pointer: Delta((-1).expr()),
-            gas_left: Delta(-OpcodeId::ADDRESS.constant_gas_cost().expr()),
+            gas_left: cb.gas_left_transition(opcode.expr(), 0.expr()),
             ..Default::default()
         };
 
-        let opcode = cb.query_cell();
         let same_context = SameContextGadget::construct(cb, opcode, step_state_transition);
 
         Self {
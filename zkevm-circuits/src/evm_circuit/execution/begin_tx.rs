@@ -6,8 +6,8 @@ use crate::{
         util::{
             and,
             common_gadget::{
-                TransferGadgetInfo, TransferWithGasFeeGadget, TxAccessListGadget, TxEip1559Gadget,
-                TxL1FeeGadget, TxL1MsgGadget,
+                ChainRulesGadget, TransferGadgetInfo, TransferWithGasFeeGadget, TxAccessListGadget,
+                TxEip1559Gadget, TxL1FeeGadget, TxL1MsgGadget,
             },
             constraint_builder::{
                 ConstrainBuilderCommon, EVMConstraintBuilder, ReversionInfo, StepStateTransition,
@@ -38,12 +38,6 @@ use ethers_core::utils::{get_contract_address, keccak256, rlp::RlpStream};
 use gadgets::util::{expr_from_bytes, not, select, Expr};
 use halo2_proofs::{circuit::Value, plonk::Error};
 
-// For Shanghai, EIP-3651 (Warm COINBASE) adds 1 write op for coinbase.
-#[cfg(feature = "shanghai")]
-const SHANGHAI_RW_DELTA: u8 = 1;
-#[cfg(not(feature = "shanghai"))]
-const SHANGHAI_RW_DELTA: u8 = 0;
-
 const PRECOMPILE_COUNT: usize = 9;
 
 #[derive(Clone, Debug)]
@@ -100,6 +94,7 @@ pub(crate) struct BeginTxGadget<F> {
     are_precompile_warm: [Cell<F>; PRECOMPILE_COUNT],
     // EIP-3651 (Warm COINBASE) for Shanghai
     coinbase: Cell<F>,
+    chain_rules: ChainRulesGadget<F>,
     tx_l1_fee: TxL1FeeGadget<F>,
     tx_l1_msg: TxL1MsgGadget<F>,
     tx_access_list: TxAccessListGadget<F>,
@@ -251,18 +246,16 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
         // Use intrinsic gas
         // TODO2: contrain calling precompile directly
 
+        let chain_rules = ChainRulesGadget::construct(cb);
         let intrinsic_gas_cost = cb.query_cell();
         cb.condition(not::expr(is_precompile.expr()), |cb| {
-            // Calculate gas cost of init code only for EIP-3860 of Shanghai.
-            #[cfg(feature = "shanghai")]
+            // Calculate gas cost of init code, which is only paid from Shanghai
+            // (EIP-3860).
             let init_code_gas_cost = select::expr(
                 tx_is_create.expr(),
-                tx_call_data_word_length.quotient().expr()
-                    * eth_types::evm_types::INIT_CODE_WORD_GAS.expr(),
+                tx_call_data_word_length.quotient().expr() * chain_rules.init_code_word_gas(),
                 0.expr(),
             );
-            #[cfg(not(feature = "shanghai"))]
-            let init_code_gas_cost = 0.expr();
 
             cb.require_equal(
                 "calculate intrinsic gas cost",
@@ -313,14 +306,15 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             coinbase.expr(),
         );
 
-        #[cfg(feature = "shanghai")]
-        cb.account_access_list_write(
-            tx_id.expr(),
-            coinbase.expr(),
-            1.expr(),
-            is_coinbase_warm.expr(),
-            None,
-        ); // rwc_delta += 1
+        cb.condition(chain_rules.is_coinbase_warm(), |cb| {
+            cb.account_access_list_write(
+                tx_id.expr(),
+                coinbase.expr(),
+                1.expr(),
+                is_coinbase_warm.expr(),
+                None,
+            )
+        }); // rwc_delta += chain_rules.is_coinbase_warm()
 
         let account_code_hash = cb.query_cell_phase2();
         let account_code_hash_is_empty =
@@ -503,7 +497,7 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                         + l1_rw_delta.expr()
                         + transfer_with_gas_fee.rw_delta()
                         + tx_access_list.rw_delta_expr()
-                        + SHANGHAI_RW_DELTA.expr()
+                        + chain_rules.is_coinbase_warm()
                         + PRECOMPILE_COUNT.expr(),
                 ),
                 call_id: To(call_id.expr()),
@@ -636,7 +630,7 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                             + l1_rw_delta.expr()
                             + transfer_with_gas_fee.rw_delta()
                             + tx_access_list.rw_delta_expr()
-                            + SHANGHAI_RW_DELTA.expr()
+                            + chain_rules.is_coinbase_warm()
                             + PRECOMPILE_COUNT.expr(),
                     ),
                     call_id: To(call_id.expr()),
@@ -698,7 +692,7 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                             + l1_rw_delta.expr()
                             + transfer_with_gas_fee.rw_delta()
                             + tx_access_list.rw_delta_expr()
-                            + SHANGHAI_RW_DELTA.expr()
+                            + chain_rules.is_coinbase_warm()
                             + PRECOMPILE_COUNT.expr(),
                     ),
                     call_id: To(call_id.expr()),
@@ -772,7 +766,7 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                             + l1_rw_delta.expr()
                             + transfer_with_gas_fee.rw_delta()
                             + tx_access_list.rw_delta_expr()
-                            + SHANGHAI_RW_DELTA.expr()
+                            + chain_rules.is_coinbase_warm()
                             + PRECOMPILE_COUNT.expr(),
                     ),
                     call_id: To(call_id.expr()),
@@ -833,6 +827,7 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             is_coinbase_warm,
             are_precompile_warm,
             coinbase,
+            chain_rules,
             tx_l1_fee,
             tx_l1_msg,
             tx_access_list,
@@ -930,10 +925,11 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
         let is_caller_warm = rws.next().tx_access_list_value_pair().1;
         let is_callee_warm = rws.next().tx_access_list_value_pair().1;
 
-        #[cfg(feature = "shanghai")]
-        let is_coinbase_warm = rws.next().tx_access_list_value_pair().1;
-        #[cfg(not(feature = "shanghai"))]
-        let is_coinbase_warm = false;
+        let is_coinbase_warm = if block.rules.is_coinbase_warm() {
+            rws.next().tx_access_list_value_pair().1
+        } else {
+            false
+        };
 
         let account_code_hash = rws.next().account_codehash_pair().1;
         let transfer_assign_result = self.transfer_with_gas_fee.assign_from_rws(
@@ -1185,6 +1181,7 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             cell.assign(region, offset, Value::known(F::from(val)))?;
         }
 
+        self.chain_rules.assign(region, offset, &block.rules)?;
         self.coinbase.assign(
             region,
             offset,
@@ -1247,7 +1244,8 @@ mod test {
         self, address, bytecode, evm_types::GasCost, word, Address, Bytecode, Hash, Word, U256,
     };
     use ethers_core::{types::Bytes, utils::get_contract_address};
-    use mock::{eth, gwei, MockTransaction, TestContext, MOCK_ACCOUNTS};
+    use mock::{eth, gwei, strategy::test_ctx_inputs, MockTransaction, TestContext, MOCK_ACCOUNTS};
+    use proptest::prelude::*;
 
     fn gas(call_data: &[u8]) -> Word {
         Word::from(
@@ -1392,6 +1390,17 @@ mod test {
         }
     }
 
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(4))]
+
+        #[test]
+        fn begin_tx_gadget_proptest(inputs in test_ctx_inputs::<3, 2>()) {
+            CircuitTestBuilder::new_from_test_ctx(inputs.test_ctx().unwrap())
+                .auto_params()
+                .run();
+        }
+    }
+
     #[test]
     fn begin_tx_no_code() {
         let ctx = TestContext::<2, 1>::new(
@@ -64,7 +64,7 @@ impl<F: Field> ExecutionGadget<F> for BitwiseGadget<F> {
             rw_counter: Delta(3.expr()),
             program_counter: Delta(1.expr()),
             stack_pointer: Delta(1.expr()),
-            gas_left: Delta(-OpcodeId::AND.constant_gas_cost().expr()),
+            gas_left: cb.gas_left_transition(opcode.expr(), 0.expr()),
             ..Default::default()
         };
         let same_context = SameContextGadget::construct(cb, opcode, step_state_transition);
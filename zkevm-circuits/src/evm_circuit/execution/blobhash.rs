@@ -0,0 +1,228 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::N_BYTES_U64,
+        step::ExecutionState,
+        util::{
+            common_gadget::{SameContextGadget, WordByteCapGadget},
+            constraint_builder::{
+                ConstrainBuilderCommon, EVMConstraintBuilder, StepStateTransition,
+                Transition::Delta,
+            },
+            not, CachedRegion, Cell,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    table::{CallContextFieldTag, TxContextFieldTag},
+    util::Expr,
+};
+use eth_types::Field;
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+#[derive(Clone, Debug)]
+pub(crate) struct BlobHashGadget<F> {
+    same_context: SameContextGadget<F>,
+    tx_id: Cell<F>,
+    /// Number of blob versioned hashes of the tx.
+    blob_hashes_len: Cell<F>,
+    /// The index of the blob versioned hash to load. It is valid if within
+    /// range of Uint64 and less than blob_hashes_len.
+    index: WordByteCapGadget<F, N_BYTES_U64>,
+    /// The blob versioned hash pushed to stack, zero if index is out of range.
+    blob_hash: Cell<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for BlobHashGadget<F> {
+    const NAME: &'static str = "BLOBHASH";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::BLOBHASH;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let opcode = cb.query_cell();
+
+        let blob_hashes_len = cb.query_cell();
+        let blob_hash = cb.query_cell_phase2();
+
+        let index = WordByteCapGadget::construct(cb, blob_hashes_len.expr());
+        cb.stack_pop(index.original_word());
+
+        // Lookup in call_ctx the TxId
+        let tx_id = cb.call_context(None, CallContextFieldTag::TxId);
+        // Lookup the number of blob versioned hashes in tx table
+        cb.tx_context_lookup(
+            tx_id.expr(),
+            TxContextFieldTag::BlobVersionedHashesLen,
+            None,
+            blob_hashes_len.expr(),
+        );
+
+        cb.condition(index.lt_cap(), |cb| {
+            // blob hash index starts from 1 in tx table
+            cb.tx_context_lookup(
+                tx_id.expr(),
+                TxContextFieldTag::BlobVersionedHash,
+                Some(index.valid_value() + 1.expr()),
+                blob_hash.expr(),
+            );
+        });
+        cb.condition(not::expr(index.lt_cap()), |cb| {
+            cb.require_zero("blob_hash == 0 if index is out of range", blob_hash.expr());
+        });
+
+        // Push the value to the stack
+        cb.stack_push(blob_hash.expr());
+
+        // State transition
+        let step_state_transition = StepStateTransition {
+            rw_counter: Delta(3.expr()),
+            program_counter: Delta(1.expr()),
+            stack_pointer: Delta(0.expr()),
+            gas_left: cb.gas_left_transition(opcode.expr(), 0.expr()),
+            ..Default::default()
+        };
+        let same_context = SameContextGadget::construct(cb, opcode, step_state_transition);
+
+        Self {
+            same_context,
+            tx_id,
+            blob_hashes_len,
+            index,
+            blob_hash,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        tx: &Transaction,
+        _: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        self.same_context.assign_exec_step(region, offset, step)?;
+
+        let blob_hashes_len = tx.blob_versioned_hashes.len() as u64;
+        let index = block.rws[step.rw_indices[0]].stack_value();
+        let blob_hash = block.rws[step.rw_indices[2]].stack_value();
+
+        self.tx_id
+            .assign(region, offset, Value::known(F::from(tx.id as u64)))?;
+        self.blob_hashes_len
+            .assign(region, offset, Value::known(F::from(blob_hashes_len)))?;
+        self.index
+            .assign(region, offset, index, F::from(blob_hashes_len))?;
+        self.blob_hash
+            .assign(region, offset, region.word_rlc(blob_hash))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        evm_circuit::{step::ExecutionState, witness::Rw},
+        test_util::CircuitTestBuilder,
+    };
+    use eth_types::{bytecode, chain_spec::ChainSpec, Word, H256};
+    use mock::{
+        test_ctx::{helpers::account_0_code_wallet_0_no_code, LoggerConfig},
+        TestContext, MOCK_WALLETS,
+    };
+
+    /// A tx calling BLOBHASH with `index`, of type 3 if it has blob versioned hashes.
+    fn test_ctx(index: Word, blob_versioned_hashes: Vec<H256>) -> TestContext<2, 1> {
+        let bytecode = bytecode! {
+            PUSH32(index)
+            BLOBHASH
+            STOP
+        };
+
+        TestContext::<2, 1>::new_with_chain_spec(
+            ChainSpec::cancun(),
+            None,
+            account_0_code_wallet_0_no_code(bytecode),
+            |mut txs, accs| {
+                txs[0].from(MOCK_WALLETS[0].clone()).to(accs[0].address);
+                if !blob_versioned_hashes.is_empty() {
+                    txs[0]
+                        .transaction_type(3)
+                        .max_fee_per_blob_gas(Word::one())
+                        .blob_versioned_hashes(blob_versioned_hashes);
+                }
+            },
+            |block, _tx| block,
+            LoggerConfig::default(),
+        )
+        .unwrap()
+    }
+
+    fn test_ok(index: Word, blob_versioned_hashes: Vec<H256>) {
+        CircuitTestBuilder::new_from_test_ctx(test_ctx(index, blob_versioned_hashes)).run();
+    }
+
+    /// Versioned hashes of KZG commitments start with the version byte 0x01.
+    fn blob_versioned_hashes() -> Vec<H256> {
+        (2..=3)
+            .map(|byte| {
+                let mut hash = H256::repeat_byte(byte);
+                hash.0[0] = 0x01;
+                hash
+            })
+            .collect()
+    }
+
+    // A tx without blobs has no versioned hashes, so that BLOBHASH pushes a
+    // zero for any index.
+    #[cfg(not(feature = "scroll"))]
+    #[test]
+    fn blobhash_gadget_no_blobs() {
+        test_ok(Word::zero(), vec![]);
+        test_ok(Word::one(), vec![]);
+        test_ok(Word::from(u64::MAX), vec![]);
+        test_ok(Word::MAX, vec![]);
+    }
+
+    #[cfg(not(feature = "scroll"))]
+    #[test]
+    fn blobhash_gadget_blob_tx() {
+        // in range
+        test_ok(Word::zero(), blob_versioned_hashes());
+        test_ok(Word::one(), blob_versioned_hashes());
+        // out of range
+        test_ok(Word::from(2), blob_versioned_hashes());
+        test_ok(Word::from(u64::MAX), blob_versioned_hashes());
+        test_ok(Word::MAX, blob_versioned_hashes());
+    }
+
+    #[cfg(not(feature = "scroll"))]
+    #[test]
+    fn blobhash_gadget_wrong_blob_hash() {
+        // the hash of another index, and a non zero hash of an out of range index
+        for (index, wrong_blob_hash) in [
+            (Word::zero(), blob_versioned_hashes()[1]),
+            (Word::from(2), blob_versioned_hashes()[0]),
+        ] {
+            CircuitTestBuilder::new_from_test_ctx(test_ctx(index, blob_versioned_hashes()))
+                .block_modifier(Box::new(move |block| {
+                    let step = block.txs[0]
+                        .steps
+                        .iter()
+                        .find(|step| step.execution_state == ExecutionState::BLOBHASH)
+                        .unwrap();
+                    // the stack push of the blob hash
+                    let (tag, idx) = step.rw_indices[2];
+                    if let Rw::Stack { value, .. } = &mut block.rws.0.get_mut(&tag).unwrap()[idx] {
+                        *value = Word::from_big_endian(wrong_blob_hash.as_bytes());
+                    }
+                }))
+                .evm_checks(Some(Box::new(|prover, gate_rows, lookup_rows| {
+                    assert!(prover
+                        .verify_at_rows_par(gate_rows.iter().cloned(), lookup_rows.iter().cloned())
+                        .is_err())
+                })))
+                .run();
+        }
+    }
+}
@@ -49,7 +49,7 @@ impl<F: Field, const N_BYTES: usize> BlockCtxGadget<F, N_BYTES> {
             rw_counter: Delta(1.expr()),
             program_counter: Delta(1.expr()),
             stack_pointer: Delta((-1).expr()),
-            gas_left: Delta(-OpcodeId::TIMESTAMP.constant_gas_cost().expr()),
+            gas_left: cb.gas_left_transition(opcode.expr(), 0.expr()),
             ..Default::default()
         };
         let same_context = SameContextGadget::construct(cb, opcode, step_state_transition);
@@ -217,7 +217,7 @@ impl<F: Field> ExecutionGadget<F> for DifficultyGadget<F> {
             rw_counter: Delta(1.expr()),
             program_counter: Delta(1.expr()),
             stack_pointer: Delta((-1).expr()),
-            gas_left: Delta(-OpcodeId::DIFFICULTY.constant_gas_cost().expr()),
+            gas_left: cb.gas_left_transition(opcode.expr(), 0.expr()),
             ..Default::default()
         };
         let same_context = SameContextGadget::construct(cb, opcode, step_state_transition);
@@ -19,7 +19,6 @@ use crate::{
     table::BlockContextFieldTag,
     util::Expr,
 };
-use bus_mapping::evm::OpcodeId;
 use eth_types::{
     evm_types::block_utils::{is_valid_block_number, NUM_PREV_BLOCK_ALLOWED},
     Field, ToScalar,
@@ -119,14 +118,14 @@ impl<F: Field> ExecutionGadget<F> for BlockHashGadget<F> {
 
         cb.stack_push(block_hash.expr());
 
+        let opcode = cb.query_cell();
         let step_state_transition = StepStateTransition {
             rw_counter: Delta(2.expr()),
             program_counter: Delta(1.expr()),
-            gas_left: Delta(-OpcodeId::BLOCKHASH.constant_gas_cost().expr()),
+            gas_left: cb.gas_left_transition(opcode.expr(), 0.expr()),
             ..Default::default()
         };
 
-        let opcode = cb.query_cell();
         let same_context = SameContextGadget::construct(cb, opcode, step_state_transition);
         Self {
             same_context,
@@ -196,8 +195,8 @@ impl<F: Field> ExecutionGadget<F> for BlockHashGadget<F> {
 #[cfg(test)]
 mod test {
     use crate::test_util::CircuitTestBuilder;
-    use eth_types::{bytecode, U256};
-    use mock::test_ctx::{helpers::*, TestContext};
+    use eth_types::{bytecode, H256, U256};
+    use mock::test_ctx::{helpers::*, ChunkTestContext, TestContext};
 
     fn test_ok(block_number: U256, current_block_number: u64) {
         let code = bytecode! {
@@ -251,4 +250,36 @@ mod test {
     fn blockhash_gadget_block_number_overflow() {
         test_ok(U256::MAX, 0xcafeu64);
     }
+
+    #[test]
+    fn blockhash_gadget_chunk() {
+        // query the hash of the parent block, which is in the same chunk from
+        // the second block onwards
+        let code = bytecode! {
+            PUSH1(1)
+            NUMBER
+            SUB
+            BLOCKHASH
+            STOP
+        };
+        let history_hashes = (0..5).map(|i| U256::from(0xbeefcafeu64 + i)).collect();
+        let ctx = ChunkTestContext::<2>::new(
+            Some(history_hashes),
+            account_0_code_account_1_no_code(code),
+            &[1, 1, 1],
+            |blocks_txs, accs| {
+                for tx in blocks_txs.into_iter().flatten() {
+                    tx.from(accs[1].address).to(accs[0].address);
+                }
+            },
+            |idx, block, _txs| {
+                block
+                    .number(5 + idx as u64)
+                    .hash(H256::from_low_u64_be(0xcafe + idx as u64))
+            },
+        )
+        .unwrap();
+
+        CircuitTestBuilder::<2, 3>::new_from_chunk_ctx(ctx).run()
+    }
 }
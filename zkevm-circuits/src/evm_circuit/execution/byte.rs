@@ -13,7 +13,6 @@ use crate::{
     util::Expr,
 };
 use array_init::array_init;
-use bus_mapping::evm::OpcodeId;
 use eth_types::{Field, ToLittleEndian};
 use halo2_proofs::plonk::Error;
 
@@ -70,14 +69,14 @@ impl<F: Field> ExecutionGadget<F> for ByteGadget<F> {
         cb.stack_push(selected_byte);
 
         // State transition
+        let opcode = cb.query_cell();
         let step_state_transition = StepStateTransition {
             rw_counter: Delta(3.expr()),
             program_counter: Delta(1.expr()),
             stack_pointer: Delta(1.expr()),
-            gas_left: Delta(-OpcodeId::BYTE.constant_gas_cost().expr()),
+            gas_left: cb.gas_left_transition(opcode.expr(), 0.expr()),
             ..Default::default()
         };
-        let opcode = cb.query_cell();
         let same_context = SameContextGadget::construct(cb, opcode, step_state_transition);
 
         Self {
@@ -20,7 +20,7 @@ use crate::{
     table::CallContextFieldTag,
     util::Expr,
 };
-use bus_mapping::{circuit_input_builder::CopyDataType, evm::OpcodeId};
+use bus_mapping::circuit_input_builder::CopyDataType;
 use eth_types::{evm_types::GasCost, Field, ToScalar};
 use halo2_proofs::{circuit::Value, plonk::Error};
 
@@ -147,9 +147,7 @@ impl<F: Field> ExecutionGadget<F> for CallDataCopyGadget<F> {
             rw_counter: Delta(cb.rw_counter_offset()),
             program_counter: Delta(1.expr()),
             stack_pointer: Delta(3.expr()),
-            gas_left: Delta(
-                -(OpcodeId::CALLDATACOPY.constant_gas_cost().expr() + memory_copier_gas.gas_cost()),
-            ),
+            gas_left: cb.gas_left_transition(opcode.expr(), memory_copier_gas.gas_cost()),
             memory_word_size: To(memory_expansion.next_memory_word_size()),
             ..Default::default()
         };
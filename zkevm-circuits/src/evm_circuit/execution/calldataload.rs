@@ -1,5 +1,4 @@
 use array_init::array_init;
-use bus_mapping::evm::OpcodeId;
 use eth_types::{Field, ToLittleEndian};
 use halo2_proofs::{
     circuit::Value,
@@ -237,7 +236,7 @@ impl<F: Field> ExecutionGadget<F> for CallDataLoadGadget<F> {
             rw_counter: Delta(cb.rw_counter_offset()),
             program_counter: Delta(1.expr()),
             stack_pointer: Delta(0.expr()),
-            gas_left: Delta(-OpcodeId::CALLDATALOAD.constant_gas_cost().expr()),
+            gas_left: cb.gas_left_transition(opcode.expr(), 0.expr()),
             ..Default::default()
         };
 
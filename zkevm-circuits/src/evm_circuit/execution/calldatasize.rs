@@ -13,7 +13,6 @@ use crate::{
     table::CallContextFieldTag,
     util::Expr,
 };
-use bus_mapping::evm::OpcodeId;
 use eth_types::{Field, ToLittleEndian};
 use halo2_proofs::plonk::Error;
 
@@ -47,7 +46,7 @@ impl<F: Field> ExecutionGadget<F> for CallDataSizeGadget<F> {
             rw_counter: Delta(2.expr()),
             program_counter: Delta(1.expr()),
             stack_pointer: Delta((-1).expr()),
-            gas_left: Delta(-OpcodeId::CALLDATASIZE.constant_gas_cost().expr()),
+            gas_left: cb.gas_left_transition(opcode.expr(), 0.expr()),
             ..Default::default()
         };
 
@@ -13,7 +13,6 @@ use crate::{
     table::CallContextFieldTag,
     util::Expr,
 };
-use bus_mapping::evm::OpcodeId;
 use eth_types::{Field, ToLittleEndian};
 use halo2_proofs::plonk::Error;
 
@@ -49,7 +48,7 @@ impl<F: Field> ExecutionGadget<F> for CallerGadget<F> {
             rw_counter: Delta(2.expr()),
             program_counter: Delta(1.expr()),
             stack_pointer: Delta((-1).expr()),
-            gas_left: Delta(-OpcodeId::CALLER.constant_gas_cost().expr()),
+            gas_left: cb.gas_left_transition(opcode.expr(), 0.expr()),
             ..Default::default()
         };
         let same_context = SameContextGadget::construct(cb, opcode, step_state_transition);
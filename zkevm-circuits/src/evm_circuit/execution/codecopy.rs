@@ -1,4 +1,4 @@
-use bus_mapping::{circuit_input_builder::CopyDataType, evm::OpcodeId};
+use bus_mapping::circuit_input_builder::CopyDataType;
 use eth_types::{evm_types::GasCost, Field, ToScalar};
 use halo2_proofs::{circuit::Value, plonk::Error};
 
@@ -119,9 +119,7 @@ impl<F: Field> ExecutionGadget<F> for CodeCopyGadget<F> {
             program_counter: Transition::Delta(1.expr()),
             stack_pointer: Transition::Delta(3.expr()),
             memory_word_size: Transition::To(memory_expansion.next_memory_word_size()),
-            gas_left: Transition::Delta(
-                -OpcodeId::CODECOPY.constant_gas_cost().expr() - memory_copier_gas.gas_cost(),
-            ),
+            gas_left: cb.gas_left_transition(opcode.expr(), memory_copier_gas.gas_cost()),
             ..Default::default()
         };
         let same_context = SameContextGadget::construct(cb, opcode, step_state_transition);
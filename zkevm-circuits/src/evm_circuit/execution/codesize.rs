@@ -1,5 +1,4 @@
 use array_init::array_init;
-use bus_mapping::evm::OpcodeId;
 use eth_types::Field;
 use halo2_proofs::{circuit::Value, plonk::Error};
 
@@ -50,7 +49,7 @@ impl<F: Field> ExecutionGadget<F> for CodesizeGadget<F> {
         cb.stack_push(cb.word_rlc(codesize_bytes.clone().map(|c| c.expr())));
 
         let step_state_transition = StepStateTransition {
-            gas_left: Transition::Delta(-OpcodeId::CODESIZE.constant_gas_cost().expr()),
+            gas_left: cb.gas_left_transition(opcode.expr(), 0.expr()),
             rw_counter: Transition::Delta(1.expr()),
             program_counter: Transition::Delta(1.expr()),
             stack_pointer: Transition::Delta((-1).expr()),
@@ -7,7 +7,7 @@ use crate::{
         },
         step::ExecutionState,
         util::{
-            common_gadget::{get_copy_bytes, TransferGadget},
+            common_gadget::{get_copy_bytes, ChainRulesGadget, TransferGadget},
             constraint_builder::{
                 ConstrainBuilderCommon, EVMConstraintBuilder, ReversionInfo, StepStateTransition,
                 Transition::{Delta, To},
@@ -27,8 +27,8 @@ use crate::{
 };
 use bus_mapping::{circuit_input_builder::CopyDataType, evm::OpcodeId, state_db::CodeDB};
 use eth_types::{
-    evm_types::{GasCost, CREATE2_GAS_PER_CODE_WORD, CREATE_GAS_PER_CODE_WORD, MAX_INIT_CODE_SIZE},
-    Field, ToBigEndian, ToLittleEndian, ToScalar, ToWord, H256, KECCAK_CODE_HASH_EMPTY, U256,
+    evm_types::GasCost, Field, ToBigEndian, ToLittleEndian, ToScalar, ToWord, H256,
+    KECCAK_CODE_HASH_EMPTY, U256,
 };
 use ethers_core::utils::keccak256;
 use gadgets::util::{and, expr_from_bytes};
@@ -62,6 +62,7 @@ pub(crate) struct CreateGadget<F, const IS_CREATE2: bool, const S: ExecutionStat
     // (maximum init code size) if Shanghai, otherwise should be less than or
     // equal to 0x1FFFFFFFE0 (maximum value of offset + size).
     init_code_size_not_overflow: LtGadget<F, { N_BYTES_MEMORY_ADDRESS }>,
+    chain_rules: ChainRulesGadget<F>,
     init_code_rlc: Cell<F>,
     keccak_output: Word<F>,
 
@@ -138,8 +139,12 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
 
         let init_code =
             MemoryAddressGadget::construct(cb, init_code_memory_offset, init_code_length);
-        let init_code_size_not_overflow =
-            LtGadget::construct(cb, init_code.length(), MAX_INIT_CODE_SIZE.expr() + 1.expr());
+        let chain_rules = ChainRulesGadget::construct(cb);
+        let init_code_size_not_overflow = LtGadget::construct(
+            cb,
+            init_code.length(),
+            chain_rules.max_init_code_size() + 1.expr(),
+        );
 
         // Init code size overflow is checked before ErrDepth, ErrInsufficientBalance,
         // ErrNonceUintOverflow and ErrContractAddressCollision.
@@ -237,13 +242,14 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
             init_code.length() + (N_BYTES_WORD - 1).expr(),
             N_BYTES_WORD as u64,
         );
+        // CREATE2 also pays for hashing the init code.
         let keccak_gas_cost = init_code_word_size.quotient()
-            * if IS_CREATE2 {
-                CREATE2_GAS_PER_CODE_WORD
-            } else {
-                CREATE_GAS_PER_CODE_WORD
-            }
-            .expr();
+            * (chain_rules.init_code_word_gas()
+                + if IS_CREATE2 {
+                    GasCost::COPY_SHA3.expr()
+                } else {
+                    0.expr()
+                });
 
         let gas_cost = GasCost::CREATE.expr() + memory_expansion.gas_cost() + keccak_gas_cost;
         let gas_remaining = cb.curr.state.gas_left.expr() - gas_cost.clone();
@@ -547,6 +553,7 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
             gas_left,
             init_code_word_size,
             init_code_size_not_overflow,
+            chain_rules,
             create,
             caller_balance,
             is_depth_in_range,
@@ -687,19 +694,16 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
             offset,
             (31u64 + init_code_length.as_u64()).into(),
         )?;
+        self.chain_rules.assign(region, offset, &block.rules)?;
         self.init_code_size_not_overflow.assign(
             region,
             offset,
             F::from(init_code_length.as_u64()),
-            F::from(MAX_INIT_CODE_SIZE + 1),
+            F::from(block.rules.max_init_code_size() + 1),
         )?;
 
         let keccak_gas_cost = u64::try_from(init_code_word_size).unwrap()
-            * if IS_CREATE2 {
-                CREATE2_GAS_PER_CODE_WORD
-            } else {
-                CREATE_GAS_PER_CODE_WORD
-            };
+            * block.rules.create_gas_per_code_word(opcode);
         let gas_left =
             step.gas_left - GasCost::CREATE.as_u64() - memory_expansion_gas_cost - keccak_gas_cost;
         self.gas_left.assign(region, offset, gas_left.into())?;
@@ -33,7 +33,6 @@ pub(crate) struct EndBlockGadget<F> {
     max_rws: Cell<F>,
     max_txs: Cell<F>,
     phase2_withdraw_root: Cell<F>,
-    phase2_withdraw_root_prev: Cell<F>,
     pub withdraw_root_assigned: Mutex<Option<AssignedCell>>,
 }
 
@@ -49,7 +48,6 @@ impl<F: Clone> Clone for EndBlockGadget<F> {
             max_rws: self.max_rws.clone(),
             max_txs: self.max_txs.clone(),
             phase2_withdraw_root: self.phase2_withdraw_root.clone(),
-            phase2_withdraw_root_prev: self.phase2_withdraw_root_prev.clone(),
         }
     }
 }
@@ -67,7 +65,6 @@ impl<F: Field> ExecutionGadget<F> for EndBlockGadget<F> {
         let total_txs = cb.query_cell();
         let total_txs_is_max_txs = IsEqualGadget::construct(cb, total_txs.expr(), max_txs.expr());
         let phase2_withdraw_root = cb.query_copy_cell_phase2();
-        let phase2_withdraw_root_prev = cb.query_cell_phase2();
         // Note that rw_counter starts at 1
         let is_empty_block =
             IsZeroGadget::construct(cb, cb.curr.state.rw_counter.clone().expr() - 1.expr());
@@ -92,15 +89,16 @@ impl<F: Field> ExecutionGadget<F> for EndBlockGadget<F> {
         let mut withdraw_trie_root_slot_le = [0u8; 32];
         WITHDRAW_TRIE_ROOT_SLOT.to_little_endian(withdraw_trie_root_slot_le.as_mut_slice());
 
-        // 1.1 constraint withdraw_root
+        // 1.1 constraint withdraw_root, read in a pseudo tx following the last
+        // tx so that its MPT update is the last one, against the end state root
         cb.account_storage_read(
             Expression::Constant(MESSAGE_QUEUE.to_scalar().expect(
                 "unexpected Address for message_queue precompile -> Scalar conversion failure",
             )),
             cb.word_rlc(withdraw_trie_root_slot_le.map(|byte| byte.expr())),
             phase2_withdraw_root.expr(),
-            total_txs.expr(),
-            phase2_withdraw_root_prev.expr(),
+            total_txs.expr() + 1.expr(),
+            phase2_withdraw_root.expr(),
         );
 
         // 2. If total_txs == max_txs, we know we have covered all txs from the
@@ -151,7 +149,6 @@ impl<F: Field> ExecutionGadget<F> for EndBlockGadget<F> {
             max_txs,
             max_rws,
             phase2_withdraw_root,
-            phase2_withdraw_root_prev,
             total_txs,
             total_txs_is_max_txs,
             is_empty_block,
@@ -186,19 +183,9 @@ impl<F: Field> ExecutionGadget<F> for EndBlockGadget<F> {
             offset,
             region.word_rlc(block.withdraw_root),
         )?;
-        let _withdraw_root_prev = self.phase2_withdraw_root_prev.assign(
-            region,
-            offset,
-            region.word_rlc(block.prev_withdraw_root),
-        )?;
         if let Some(cell) = withdraw_root {
             *self.withdraw_root_assigned.lock().unwrap() = Some(cell.cell());
         }
-        // TODO: now we do not export withdraw_root_prev for we have only one
-        // phase2 cell which is enabled for copy constraint
-        // self.withdraw_root_prev_assigned
-        //     .borrow_mut()
-        //     .replace(withdraw_root_prev.cell());
 
         // When rw_indices is not empty, we're at the last row (at a fixed offset),
         // where we need to access the max_rws and max_txs constant.
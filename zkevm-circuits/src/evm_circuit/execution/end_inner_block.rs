@@ -143,3 +143,78 @@ impl<F: Field> ExecutionGadget<F> for EndInnerBlockGadget<F> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::CircuitTestBuilder;
+    use eth_types::{bytecode, Word};
+    use mock::{test_ctx::helpers::*, ChunkTestContext};
+
+    /// A chunk of 3 blocks with 1, 0 and 2 txs calling a contract.
+    fn chunk_ctx() -> ChunkTestContext<2> {
+        let code = bytecode! {
+            NUMBER
+            POP
+            STOP
+        };
+        ChunkTestContext::<2>::new(
+            None,
+            account_0_code_account_1_no_code(code),
+            &[1, 0, 2],
+            |blocks_txs, accs| {
+                for tx in blocks_txs.into_iter().flatten() {
+                    tx.from(accs[1].address)
+                        .to(accs[0].address)
+                        .gas(Word::from(100_000u64));
+                }
+            },
+            |idx, block, _txs| block.number(0xcafe + idx as u64),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn end_inner_block_chunk() {
+        CircuitTestBuilder::<2, 3>::new_from_chunk_ctx(chunk_ctx()).run();
+    }
+
+    #[cfg(feature = "scroll")]
+    #[test]
+    fn end_inner_block_chunk_l1_queue_index() {
+        use bus_mapping::{circuit_input_builder::CircuitsParams, mock::BlockData};
+        use eth_types::geth_types::GethData;
+        use itertools::Itertools;
+
+        // the unsigned txs are l1 messages, whose nonce is their queue index
+        let ctx = chunk_ctx();
+        assert_eq!(
+            ctx.l2_traces()
+                .iter()
+                .map(|block_trace| block_trace.start_l1_queue_index)
+                .collect_vec(),
+            [0, 1, 1]
+        );
+
+        let blocks: Vec<GethData> = ctx.into();
+        let builder = BlockData::handle_chunk(&blocks, CircuitsParams::default()).unwrap();
+        assert_eq!(builder.block.start_l1_queue_index, 0);
+        assert_eq!(
+            builder
+                .block
+                .headers
+                .values()
+                .map(|header| header.start_l1_queue_index)
+                .collect_vec(),
+            [0, 1, 1]
+        );
+        assert_eq!(
+            builder
+                .block
+                .txs
+                .iter()
+                .map(|tx| (tx.block_num, tx.tx_type.is_l1_msg(), tx.nonce))
+                .collect_vec(),
+            [(0xcafe, true, 0), (0xcb00, true, 1), (0xcb00, true, 2)]
+        );
+    }
+}
@@ -71,16 +71,22 @@ impl<F: Field> ExecutionGadget<F> for ErrorInvalidOpcodeGadget<F> {
 #[cfg(test)]
 mod test {
     use crate::{evm_circuit::test::rand_bytes, test_util::CircuitTestBuilder};
-    use eth_types::{bytecode::Bytecode, Word};
-    use mock::{generate_mock_call_bytecode, MockCallBytecodeParams, TestContext};
+    use eth_types::{bytecode::Bytecode, chain_spec::ChainSpec, Word};
+    use mock::{
+        generate_mock_call_bytecode,
+        test_ctx::{helpers::account_0_code_wallet_0_no_code, LoggerConfig},
+        MockCallBytecodeParams, TestContext, MOCK_WALLETS,
+    };
 
     #[cfg(feature = "scroll")]
     use eth_types::address;
 
-    static TESTING_INVALID_CODES: [&[u8]; 6] = [
+    static TESTING_INVALID_CODES: [&[u8]; 7] = [
         // Single invalid opcode
         &[0x0e],
         &[0x4f],
+        // BLOBHASH before Cancun
+        &[0x49],
         &[0xa5],
         &[0xf6],
         &[0xfe],
@@ -127,14 +133,26 @@ mod test {
         test_internal_ok(0x20, 0x30, &[fe_opcode]);
     }
 
-    #[cfg(not(feature = "shanghai"))]
     #[test]
-    fn invalid_opcode_push0_for_not_shanghai() {
+    fn invalid_opcode_push0_before_shanghai() {
         // Should test with logs in `assign_exec_step`, otherwise it could also
         // pass (since PushGadget).
-        let push0 = 0x5f;
-        test_root_ok(&[push0]);
-        test_internal_ok(0x20, 0x00, &[push0]);
+        let mut code = Bytecode::default();
+        code.write(0x5f, true);
+
+        let ctx = TestContext::<2, 1>::new_with_chain_spec(
+            ChainSpec::london().with_scroll(cfg!(feature = "scroll")),
+            None,
+            account_0_code_wallet_0_no_code(code),
+            |mut txs, accs| {
+                txs[0].from(MOCK_WALLETS[0].clone()).to(accs[0].address);
+            },
+            |block, _tx| block,
+            LoggerConfig::default(),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
     }
 
     // for scroll feature, treat selfdestruct_opcode as invalidcode. even this test construct oog
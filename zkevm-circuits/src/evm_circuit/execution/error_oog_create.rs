@@ -4,7 +4,7 @@ use crate::{
         param::{N_BYTES_GAS, N_BYTES_MEMORY_ADDRESS, N_BYTES_MEMORY_WORD_SIZE},
         step::ExecutionState,
         util::{
-            common_gadget::CommonErrorGadget,
+            common_gadget::{ChainRulesGadget, CommonErrorGadget},
             constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
             math_gadget::{LtGadget, PairSelectGadget},
             memory_gadget::{
@@ -17,9 +17,7 @@ use crate::{
     witness::{Block, Call, ExecStep, Transaction},
 };
 use eth_types::{
-    evm_types::{
-        GasCost, OpcodeId, CREATE2_GAS_PER_CODE_WORD, CREATE_GAS_PER_CODE_WORD, MAX_INIT_CODE_SIZE,
-    },
+    evm_types::{GasCost, OpcodeId},
     Field, ToLittleEndian, U256,
 };
 use gadgets::util::Expr;
@@ -39,6 +37,7 @@ pub(crate) struct ErrorOOGCreateGadget<F> {
     // 0x1FFFFFFFE0 (maximum value of offset + size).
     // Uint64 overflow is checked in `memory_address` (offset + length).
     init_code_size_overflow: LtGadget<F, { N_BYTES_MEMORY_ADDRESS }>,
+    chain_rules: ChainRulesGadget<F>,
     insufficient_gas: LtGadget<F, N_BYTES_GAS>,
     common_error_gadget: CommonErrorGadget<F>,
 }
@@ -68,18 +67,20 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGCreateGadget<F> {
         cb.stack_pop(memory_address.length_rlc());
         cb.condition(is_create2.expr().0, |cb| cb.stack_pop(salt.expr()));
 
-        let init_code_size_overflow =
-            LtGadget::construct(cb, MAX_INIT_CODE_SIZE.expr(), memory_address.length());
+        let chain_rules = ChainRulesGadget::construct(cb);
+        let init_code_size_overflow = LtGadget::construct(
+            cb,
+            chain_rules.max_init_code_size(),
+            memory_address.length(),
+        );
 
         let minimum_word_size = MemoryWordSizeGadget::construct(cb, memory_address.length());
         let memory_expansion = MemoryExpansionGadget::construct(cb, [memory_address.end_offset()]);
 
+        // CREATE2 also pays for hashing the init code.
         let keccak_gas_cost = minimum_word_size.expr()
-            * select::expr(
-                is_create2.expr().0,
-                CREATE2_GAS_PER_CODE_WORD.expr(),
-                CREATE_GAS_PER_CODE_WORD.expr(),
-            );
+            * (chain_rules.init_code_word_gas()
+                + select::expr(is_create2.expr().0, GasCost::COPY_SHA3.expr(), 0.expr()));
         let gas_cost = GasCost::CREATE.expr() + memory_expansion.gas_cost() + keccak_gas_cost;
         let insufficient_gas = LtGadget::construct(cb, cb.curr.state.gas_left.expr(), gas_cost);
 
@@ -108,6 +109,7 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGCreateGadget<F> {
             memory_address,
             memory_expansion,
             init_code_size_overflow,
+            chain_rules,
             insufficient_gas,
             common_error_gadget,
         }
@@ -166,19 +168,15 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGCreateGadget<F> {
             .assign(region, offset, step.memory_word_size(), [memory_address])?
             .1;
 
+        self.chain_rules.assign(region, offset, &block.rules)?;
         self.init_code_size_overflow.assign(
             region,
             offset,
-            F::from(MAX_INIT_CODE_SIZE),
+            F::from(block.rules.max_init_code_size()),
             F::from(init_code_size),
         )?;
 
-        let keccak_gas_cost = minimum_word_size
-            * if is_create2 {
-                CREATE2_GAS_PER_CODE_WORD
-            } else {
-                CREATE_GAS_PER_CODE_WORD
-            };
+        let keccak_gas_cost = minimum_word_size * block.rules.create_gas_per_code_word(opcode);
         self.insufficient_gas.assign(
             region,
             offset,
@@ -203,7 +201,7 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGCreateGadget<F> {
 mod tests {
     use super::*;
     use crate::test_util::CircuitTestBuilder;
-    use eth_types::{bytecode, word, Bytecode, ToWord};
+    use eth_types::{bytecode, chain_spec::ChainRules, word, Bytecode, ToWord};
     use mock::{
         eth,
         test_ctx::{helpers::account_0_code_account_1_no_code, LoggerConfig},
@@ -301,15 +299,14 @@ mod tests {
     #[test]
     fn test_oog_create_max_init_code_size() {
         for is_create2 in [true, false] {
-            // For Shanghai, MAX_INIT_CODE_SIZE is 49152, it is constrained by
-            // `init_code_size_overflow`.
-            // For not Shanghai, MAX_INIT_CODE_SIZE is 0x1FFFFFFFE0, it is
-            // constrained by `memory_address.overflow()`
-            // (and `init_code_size_overflow`).
+            // For Shanghai, the maximum init code size is 49152, it is
+            // constrained by `init_code_size_overflow`.
+            // For not Shanghai, it is 0x1FFFFFFFE0, it is constrained by
+            // `memory_address.overflow()` (and `init_code_size_overflow`).
             let case = TestCase::new(
                 is_create2,
                 U256::zero(),
-                (MAX_INIT_CODE_SIZE + 1).into(),
+                (ChainRules::default().max_init_code_size() + 1).into(),
                 MOCK_BLOCK_GAS_LIMIT,
             );
 
@@ -14,7 +14,7 @@ use crate::{
     util::Expr,
 };
 use eth_types::{
-    evm_types::{GasCost, OpcodeId},
+    evm_types::{GasCost, GasSchedule, OpcodeId},
     Field, ToLittleEndian,
 };
 use halo2_proofs::{circuit::Value, plonk::Error};
@@ -27,6 +27,7 @@ pub(crate) struct ErrorOOGExpGadget<F> {
     base: Word<F>,
     exponent: Word<F>,
     exponent_byte_size: ByteSizeGadget<F>,
+    constant_gas_cost: Cell<F>,
     insufficient_gas_cost: LtGadget<F, N_BYTES_GAS>,
     common_error_gadget: CommonErrorGadget<F>,
 }
@@ -61,14 +62,16 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGExpGadget<F> {
                 .unwrap(),
         );
 
+        let constant_gas_cost = cb.query_cell();
+        cb.constant_gas_lookup(opcode.expr(), constant_gas_cost.expr());
+
         let insufficient_gas_cost = LtGadget::construct(
             cb,
             cb.curr.state.gas_left.expr(),
-            // static_gas = 10
+            // static_gas = 10 by default
             // dynamic_gas = exponent_byte_size * 50
             // gas_cost = dynamic_gas + static_gas
-            exponent_byte_size.size() * GasCost::EXP_BYTE_TIMES.0.expr()
-                + OpcodeId::EXP.constant_gas_cost().expr(),
+            exponent_byte_size.size() * GasCost::EXP_BYTE_TIMES.0.expr() + constant_gas_cost.expr(),
         );
 
         cb.require_equal(
@@ -83,6 +86,7 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGExpGadget<F> {
             base,
             exponent,
             exponent_byte_size,
+            constant_gas_cost,
             insufficient_gas_cost,
             common_error_gadget,
         }
@@ -113,6 +117,13 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGExpGadget<F> {
             .assign(region, offset, Some(exponent.to_le_bytes()))?;
         self.exponent_byte_size
             .assign(region, offset, ByteOrWord::Word(exponent))?;
+        self.constant_gas_cost.assign(
+            region,
+            offset,
+            Value::known(F::from(
+                block.circuits_params.gas_table.constant_gas_cost(opcode).0,
+            )),
+        )?;
         self.insufficient_gas_cost.assign_value(
             region,
             offset,
@@ -20,7 +20,7 @@ use crate::{
     util::Expr,
 };
 use eth_types::{
-    evm_types::{GasCost, OpcodeId},
+    evm_types::{GasCost, GasSchedule, OpcodeId},
     Field, ToLittleEndian, U256,
 };
 use halo2_proofs::{circuit::Value, plonk::Error};
@@ -42,6 +42,8 @@ pub(crate) struct ErrorOOGMemoryCopyGadget<F> {
     dst_memory_addr: MemoryExpandedAddressGadget<F>,
     memory_expansion: MemoryExpansionGadget<F, 1, N_BYTES_MEMORY_WORD_SIZE>,
     memory_copier_gas: MemoryCopierGasGadget<F, { GasCost::COPY }>,
+    /// Constant gas cost of the opcode in the gas schedule
+    opcode_gas_cost: Cell<F>,
     insufficient_gas: LtGadget<F, N_BYTES_GAS>,
     is_extcodecopy: IsZeroGadget<F>,
     common_error_gadget: CommonErrorGadget<F>,
@@ -100,6 +102,9 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGMemoryCopyGadget<F> {
             memory_expansion.gas_cost(),
         );
 
+        let opcode_gas_cost = cb.query_cell();
+        cb.constant_gas_lookup(opcode.expr(), opcode_gas_cost.expr());
+
         let constant_gas_cost = select::expr(
             is_extcodecopy.expr(),
             // According to EIP-2929, EXTCODECOPY constant gas cost is different for cold and warm
@@ -109,8 +114,7 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGMemoryCopyGadget<F> {
                 GasCost::WARM_ACCESS.expr(),
                 GasCost::COLD_ACCOUNT_ACCESS.expr(),
             ),
-            // Constant gas cost is same for CALLDATACOPY, CODECOPY and RETURNDATACOPY.
-            OpcodeId::CALLDATACOPY.constant_gas_cost().expr(),
+            opcode_gas_cost.expr(),
         );
 
         let insufficient_gas = LtGadget::construct(
@@ -142,6 +146,7 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGMemoryCopyGadget<F> {
             dst_memory_addr,
             memory_expansion,
             memory_copier_gas,
+            opcode_gas_cost,
             insufficient_gas,
             is_extcodecopy,
             common_error_gadget,
@@ -202,6 +207,9 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGMemoryCopyGadget<F> {
             MemoryExpandedAddressGadget::<F>::length_value(dst_offset, copy_size),
             memory_expansion_cost,
         )?;
+        let opcode_gas_cost = block.circuits_params.gas_table.constant_gas_cost(opcode);
+        self.opcode_gas_cost
+            .assign(region, offset, Value::known(F::from(opcode_gas_cost.0)))?;
         let constant_gas_cost = if is_extcodecopy {
             if is_warm {
                 GasCost::WARM_ACCESS
@@ -209,7 +217,7 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGMemoryCopyGadget<F> {
                 GasCost::COLD_ACCOUNT_ACCESS
             }
         } else {
-            GasCost::FASTEST
+            opcode_gas_cost
         };
         self.insufficient_gas.assign_value(
             region,
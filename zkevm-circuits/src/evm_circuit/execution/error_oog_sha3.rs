@@ -18,7 +18,7 @@ use crate::{
     util::Expr,
 };
 use eth_types::{
-    evm_types::{GasCost, OpcodeId},
+    evm_types::{GasCost, GasSchedule, OpcodeId},
     Field,
 };
 use halo2_proofs::{circuit::Value, plonk::Error};
@@ -31,6 +31,7 @@ pub(crate) struct ErrorOOGSha3Gadget<F> {
     memory_address: MemoryExpandedAddressGadget<F>,
     memory_expansion: MemoryExpansionGadget<F, 1, N_BYTES_MEMORY_WORD_SIZE>,
     memory_copier_gas: MemoryCopierGasGadget<F, { GasCost::COPY_SHA3 }>,
+    constant_gas_cost: Cell<F>,
     insufficient_gas: LtGadget<F, N_BYTES_GAS>,
     common_error_gadget: CommonErrorGadget<F>,
 }
@@ -59,10 +60,13 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGSha3Gadget<F> {
             memory_expansion.gas_cost(),
         );
 
+        let constant_gas_cost = cb.query_cell();
+        cb.constant_gas_lookup(opcode.expr(), constant_gas_cost.expr());
+
         let insufficient_gas = LtGadget::construct(
             cb,
             cb.curr.state.gas_left.expr(),
-            OpcodeId::SHA3.constant_gas_cost().expr() + memory_copier_gas.gas_cost(),
+            constant_gas_cost.expr() + memory_copier_gas.gas_cost(),
         );
 
         cb.require_equal(
@@ -78,6 +82,7 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGSha3Gadget<F> {
             memory_address,
             memory_expansion,
             memory_copier_gas,
+            constant_gas_cost,
             insufficient_gas,
             common_error_gadget,
         }
@@ -119,13 +124,14 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGSha3Gadget<F> {
             MemoryExpandedAddressGadget::<F>::length_value(memory_offset, memory_length),
             memory_expansion_cost,
         )?;
+        let constant_gas_cost = block.circuits_params.gas_table.constant_gas_cost(opcode).0;
+        self.constant_gas_cost
+            .assign(region, offset, Value::known(F::from(constant_gas_cost)))?;
         self.insufficient_gas.assign_value(
             region,
             offset,
             Value::known(F::from(step.gas_left)),
-            Value::known(F::from(
-                OpcodeId::SHA3.constant_gas_cost().0 + memory_copier_gas,
-            )),
+            Value::known(F::from(constant_gas_cost + memory_copier_gas)),
         )?;
 
         self.common_error_gadget
@@ -20,7 +20,10 @@ use crate::{
     table::CallContextFieldTag,
     util::Expr,
 };
-use eth_types::{evm_types::OpcodeId, Field};
+use eth_types::{
+    evm_types::{GasSchedule, OpcodeId},
+    Field,
+};
 use halo2_proofs::{circuit::Value, plonk::Error};
 
 #[derive(Clone, Debug)]
@@ -28,6 +31,7 @@ pub(crate) struct ErrorOOGStaticMemoryGadget<F> {
     opcode: Cell<F>,
     memory_address: MemoryExpandedAddressGadget<F>,
     memory_expansion: MemoryExpansionGadget<F, 1, N_BYTES_MEMORY_WORD_SIZE>,
+    constant_gas_cost: Cell<F>,
     insufficient_gas: LtGadget<F, N_BYTES_GAS>,
     is_mload: IsEqualGadget<F>,
     is_mstore8: IsEqualGadget<F>,
@@ -75,12 +79,15 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGStaticMemoryGadget<F> {
         // Get the next memory size and the gas cost for this memory access
         let memory_expansion = MemoryExpansionGadget::construct(cb, [memory_address.end_offset()]);
 
+        let constant_gas_cost = cb.query_cell();
+        cb.constant_gas_lookup(opcode.expr(), constant_gas_cost.expr());
+
         // Check if the amount of gas available is less than the amount of gas
         // required
         let insufficient_gas = LtGadget::construct(
             cb,
             cb.curr.state.gas_left.expr(),
-            OpcodeId::MLOAD.constant_gas_cost().expr() + memory_expansion.gas_cost(),
+            constant_gas_cost.expr() + memory_expansion.gas_cost(),
         );
 
         cb.require_equal(
@@ -124,6 +131,7 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGStaticMemoryGadget<F> {
             opcode,
             memory_address,
             memory_expansion,
+            constant_gas_cost,
             insufficient_gas,
             is_mload,
             is_mstore8,
@@ -179,13 +187,17 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGStaticMemoryGadget<F> {
             .assign(region, offset, step.memory_word_size(), [memory_address])?
             .1;
 
+        let constant_gas_cost = block.circuits_params.gas_table.constant_gas_cost(opcode).0;
+        self.constant_gas_cost
+            .assign(region, offset, Value::known(F::from(constant_gas_cost)))?;
+
         // Gas insufficient check
         // Get `gas_available` variable here once it's available
         self.insufficient_gas.assign(
             region,
             offset,
             F::from(step.gas_left),
-            F::from(OpcodeId::MLOAD.constant_gas_cost().0 + memory_expansion_cost),
+            F::from(constant_gas_cost + memory_expansion_cost),
         )?;
 
         self.rw_counter_end_of_reversion.assign(
@@ -1,4 +1,3 @@
-use bus_mapping::evm::OpcodeId;
 use eth_types::{evm_types::GasCost, Field, ToLittleEndian, ToScalar};
 use gadgets::util::{and, not, split_u256, Expr};
 use halo2_proofs::plonk::Error;
@@ -159,10 +158,8 @@ impl<F: Field> ExecutionGadget<F> for ExponentiationGadget<F> {
             rw_counter: Transition::Delta(3.expr()), // 2 stack pops, 1 stack push
             program_counter: Transition::Delta(1.expr()),
             stack_pointer: Transition::Delta(1.expr()),
-            gas_left: Transition::Delta(
-                // gas_cost = static_gas (10) + dynamic_gas
-                -OpcodeId::EXP.constant_gas_cost().expr() - dynamic_gas_cost,
-            ),
+            // gas_cost = static_gas (10) + dynamic_gas
+            gas_left: cb.gas_left_transition(opcode.expr(), dynamic_gas_cost),
             ..Default::default()
         };
         let same_context = SameContextGadget::construct(cb, opcode, step_state_transition);
@@ -15,7 +15,7 @@ use crate::{
     },
     util::Expr,
 };
-use eth_types::{evm_types::OpcodeId, Field};
+use eth_types::Field;
 use halo2_proofs::plonk::Error;
 
 #[derive(Clone, Debug)]
@@ -34,24 +34,25 @@ impl<F: Field> ExecutionGadget<F> for GasGadget<F> {
         let gas_left = cb.query_word_rlc();
 
         // The `gas_left` in the current state has to be deducted by the gas
-        // used by the `GAS` opcode itself.
+        // used by the `GAS` opcode itself, which is the gas left of the next
+        // state.
         cb.require_equal(
             "Constraint: gas left equal to stack value",
             from_bytes::expr(&gas_left.cells),
-            cb.curr.state.gas_left.expr() - OpcodeId::GAS.constant_gas_cost().expr(),
+            cb.next.state.gas_left.expr(),
         );
 
         // Construct the value and push it to stack.
         cb.stack_push(gas_left.expr());
 
+        let opcode = cb.query_cell();
         let step_state_transition = StepStateTransition {
             rw_counter: Delta(1.expr()),
             program_counter: Delta(1.expr()),
             stack_pointer: Delta((-1).expr()),
-            gas_left: Delta(-OpcodeId::GAS.constant_gas_cost().expr()),
+            gas_left: cb.gas_left_transition(opcode.expr(), 0.expr()),
             ..Default::default()
         };
-        let opcode = cb.query_cell();
         let same_context = SameContextGadget::construct(cb, opcode, step_state_transition);
 
         Self {
@@ -76,11 +77,7 @@ impl<F: Field> ExecutionGadget<F> for GasGadget<F> {
         self.gas_left.assign(
             region,
             offset,
-            Some(
-                step.gas_left
-                    .saturating_sub(OpcodeId::GAS.constant_gas_cost().as_u64())
-                    .to_le_bytes(),
-            ),
+            Some(step.gas_left.saturating_sub(step.gas_cost).to_le_bytes()),
         )?;
 
         Ok(())
@@ -12,7 +12,6 @@ use crate::{
     table::{CallContextFieldTag, TxContextFieldTag},
     util::Expr,
 };
-use bus_mapping::evm::OpcodeId;
 use eth_types::Field;
 use halo2_proofs::{circuit::Value, plonk::Error};
 
@@ -51,7 +50,7 @@ impl<F: Field> ExecutionGadget<F> for GasPriceGadget<F> {
             rw_counter: Delta(2u64.expr()),
             program_counter: Delta(1u64.expr()),
             stack_pointer: Delta((-1i32).expr()),
-            gas_left: Delta(-OpcodeId::GASPRICE.constant_gas_cost().expr()),
+            gas_left: cb.gas_left_transition(opcode.expr(), 0.expr()),
             ..Default::default()
         };
         let same_context = SameContextGadget::construct(cb, opcode, step_state_transition);
@@ -48,7 +48,7 @@ impl<F: Field> ExecutionGadget<F> for JumpGadget<F> {
             rw_counter: Delta(1.expr()),
             program_counter: To(from_bytes::expr(&destination.cells)),
             stack_pointer: Delta(1.expr()),
-            gas_left: Delta(-OpcodeId::JUMP.constant_gas_cost().expr()),
+            gas_left: cb.gas_left_transition(opcode.expr(), 0.expr()),
             ..Default::default()
         };
         let same_context = SameContextGadget::construct(cb, opcode, step_state_transition);
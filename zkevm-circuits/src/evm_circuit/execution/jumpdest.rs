@@ -11,7 +11,6 @@ use crate::{
     },
     util::Expr,
 };
-use bus_mapping::evm::OpcodeId;
 use eth_types::Field;
 use halo2_proofs::plonk::Error;
 
@@ -27,12 +26,12 @@ impl<F: Field> ExecutionGadget<F> for JumpdestGadget<F> {
 
     fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
         // State transition
+        let opcode = cb.query_cell();
         let step_state_transition = StepStateTransition {
             program_counter: Delta(1.expr()),
-            gas_left: Delta(-OpcodeId::JUMPDEST.constant_gas_cost().expr()),
+            gas_left: cb.gas_left_transition(opcode.expr(), 0.expr()),
             ..Default::default()
         };
-        let opcode = cb.query_cell();
         let same_context = SameContextGadget::construct(cb, opcode, step_state_transition);
 
         Self { same_context }
@@ -69,7 +69,7 @@ impl<F: Field> ExecutionGadget<F> for JumpiGadget<F> {
             rw_counter: Delta(2.expr()),
             program_counter: To(next_program_counter),
             stack_pointer: Delta(2.expr()),
-            gas_left: Delta(-OpcodeId::JUMPI.constant_gas_cost().expr()),
+            gas_left: cb.gas_left_transition(opcode.expr(), 0.expr()),
             ..Default::default()
         };
         let same_context = SameContextGadget::construct(cb, opcode, step_state_transition);
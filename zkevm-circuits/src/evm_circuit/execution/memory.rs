@@ -131,13 +131,12 @@ impl<F: Field> ExecutionGadget<F> for MemoryGadget<F> {
         // - `program_counter` needs to be increased by 1
         // - `stack_pointer` needs to be increased by 2 when is_store, otherwise to be same
         // - `memory_size` needs to be set to `next_memory_size`
-        let gas_cost = OpcodeId::MLOAD.constant_gas_cost().expr() + memory_expansion.gas_cost();
         let step_state_transition = StepStateTransition {
             //TODO: update rw_counter
             rw_counter: Delta(4.expr() - is_mstore8.expr()),
             program_counter: Delta(1.expr()),
             stack_pointer: Delta(is_store * 2.expr()),
-            gas_left: Delta(-gas_cost),
+            gas_left: cb.gas_left_transition(opcode.expr(), memory_expansion.gas_cost()),
             memory_word_size: To(memory_expansion.next_memory_word_size()),
             ..Default::default()
         };
@@ -5,8 +5,7 @@ use crate::{
         util::{
             common_gadget::SameContextGadget,
             constraint_builder::{EVMConstraintBuilder, StepStateTransition, Transition::Delta},
-            math_gadget::IsZeroGadget,
-            rlc, select, CachedRegion, Cell,
+            rlc, CachedRegion, Cell,
         },
         witness::{Block, Call, ExecStep, Transaction},
     },
@@ -18,7 +17,6 @@ use halo2_proofs::{circuit::Value, plonk::Error};
 #[derive(Clone, Debug)]
 pub(crate) struct PushGadget<F> {
     same_context: SameContextGadget<F>,
-    is_push0: IsZeroGadget<F>,
     value: Cell<F>,
 }
 
@@ -30,8 +28,6 @@ impl<F: Field> ExecutionGadget<F> for PushGadget<F> {
     fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
         let opcode = cb.query_cell();
 
-        let is_push0 = IsZeroGadget::construct(cb, opcode.expr() - OpcodeId::PUSH0.expr());
-
         let value = cb.query_cell_phase2();
         cb.stack_push(value.expr());
 
@@ -40,11 +36,7 @@ impl<F: Field> ExecutionGadget<F> for PushGadget<F> {
             rw_counter: Delta(1.expr()),
             program_counter: Delta(opcode.expr() - (OpcodeId::PUSH0.as_u64() - 1).expr()),
             stack_pointer: Delta((-1).expr()),
-            gas_left: Delta(select::expr(
-                is_push0.expr(),
-                -OpcodeId::PUSH0.constant_gas_cost().expr(),
-                -OpcodeId::PUSH1.constant_gas_cost().expr(),
-            )),
+            gas_left: cb.gas_left_transition(opcode.expr(), 0.expr()),
             ..Default::default()
         };
         let same_context =
@@ -52,7 +44,6 @@ impl<F: Field> ExecutionGadget<F> for PushGadget<F> {
 
         Self {
             same_context,
-            is_push0,
             value,
         }
     }
@@ -69,12 +60,6 @@ impl<F: Field> ExecutionGadget<F> for PushGadget<F> {
         self.same_context.assign_exec_step(region, offset, step)?;
 
         let opcode = step.opcode.unwrap();
-        self.is_push0.assign(
-            region,
-            offset,
-            F::from(opcode.as_u64() - OpcodeId::PUSH0.as_u64()),
-        )?;
-
         let value_rlc = if opcode.is_push_with_data() {
             let value = block.rws[step.rw_indices[0]].stack_value();
             region
@@ -93,8 +78,11 @@ impl<F: Field> ExecutionGadget<F> for PushGadget<F> {
 #[cfg(test)]
 mod test {
     use crate::{evm_circuit::test::rand_bytes, test_util::CircuitTestBuilder};
-    use eth_types::{bytecode, evm_types::OpcodeId};
-    use mock::TestContext;
+    use eth_types::{bytecode, chain_spec::ChainSpec, evm_types::OpcodeId};
+    use mock::{
+        test_ctx::{helpers::account_0_code_wallet_0_no_code, LoggerConfig},
+        TestContext, MOCK_WALLETS,
+    };
 
     fn test_ok(opcode: OpcodeId, bytes: &[u8]) {
         let mut bytecode = bytecode! {
@@ -105,15 +93,23 @@ mod test {
         }
         bytecode.op_stop();
 
-        CircuitTestBuilder::new_from_test_ctx(
-            TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode).unwrap(),
+        let ctx = TestContext::<2, 1>::new_with_chain_spec(
+            ChainSpec::shanghai().with_scroll(cfg!(feature = "scroll")),
+            None,
+            account_0_code_wallet_0_no_code(bytecode),
+            |mut txs, accs| {
+                txs[0].from(MOCK_WALLETS[0].clone()).to(accs[0].address);
+            },
+            |block, _tx| block,
+            LoggerConfig::default(),
         )
-        .run();
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
     }
 
     #[test]
     fn push_gadget_simple() {
-        #[cfg(feature = "shanghai")]
         test_ok(OpcodeId::PUSH0, &[]);
         test_ok(OpcodeId::PUSH1, &[1]);
         test_ok(OpcodeId::PUSH2, &[1, 2]);
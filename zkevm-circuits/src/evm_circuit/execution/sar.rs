@@ -19,7 +19,6 @@ use crate::{
     util::Expr,
 };
 use array_init::array_init;
-use bus_mapping::evm::OpcodeId;
 use eth_types::{Field, ToLittleEndian};
 use halo2_proofs::{circuit::Value, plonk::Error};
 
@@ -225,7 +224,7 @@ impl<F: Field> ExecutionGadget<F> for SarGadget<F> {
             rw_counter: Delta(3.expr()),
             program_counter: Delta(1.expr()),
             stack_pointer: Delta(1.expr()),
-            gas_left: Delta(-OpcodeId::SAR.constant_gas_cost().expr()),
+            gas_left: cb.gas_left_transition(opcode.expr(), 0.expr()),
             ..Default::default()
         };
 
@@ -47,7 +47,7 @@ impl<F: Field> ExecutionGadget<F> for SwapGadget<F> {
         let step_state_transition = StepStateTransition {
             rw_counter: Delta(4.expr()),
             program_counter: Delta(1.expr()),
-            gas_left: Delta(-OpcodeId::SWAP1.constant_gas_cost().expr()),
+            gas_left: cb.gas_left_transition(opcode.expr(), 0.expr()),
             ..Default::default()
         };
         let same_context = SameContextGadget::construct(cb, opcode, step_state_transition);
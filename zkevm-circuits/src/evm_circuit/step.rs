@@ -9,11 +9,12 @@ use crate::{
     witness::Transaction,
 };
 use bus_mapping::{evm::OpcodeId, precompile::PrecompileCalls};
-use eth_types::{evm_types::GasCost, Field};
+use eth_types::{chain_spec::ChainRules, evm_types::GasCost, Field};
 use halo2_proofs::{
     circuit::Value,
     plonk::{Advice, Column, ConstraintSystem, Error, Expression},
 };
+use serde::{Deserialize, Serialize};
 use std::{fmt::Display, iter, marker::ConstParamTy};
 use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
@@ -35,7 +36,9 @@ impl From<PrecompileCalls> for ExecutionState {
 }
 
 #[allow(non_camel_case_types)]
-#[derive(ConstParamTy, Clone, Copy, Debug, PartialEq, Eq, Hash, EnumIter)]
+#[derive(
+    ConstParamTy, Clone, Copy, Debug, PartialEq, Eq, Hash, EnumIter, Serialize, Deserialize,
+)]
 pub enum ExecutionState {
     // Internal state
     BeginTx,
@@ -84,6 +87,7 @@ pub enum ExecutionState {
     DIFFICULTY, // DIFFICULTY
     CHAINID,
     SELFBALANCE,
+    BLOBHASH,
     POP,
     MEMORY, // MLOAD, MSTORE, MSTORE8
     SLOAD,
@@ -222,10 +226,12 @@ impl ExecutionState {
             || self.halts_in_exception()
     }
 
-    pub(crate) fn responsible_opcodes(&self) -> Vec<ResponsibleOp> {
+    /// Opcodes the execution state is responsible for under `rules`.
+    pub(crate) fn responsible_opcodes(&self, rules: &ChainRules) -> Vec<ResponsibleOp> {
         if matches!(self, Self::ErrorStack) {
             return OpcodeId::valid_opcodes()
                 .into_iter()
+                .filter(|op| rules.is_opcode_enabled(*op))
                 .flat_map(|op| {
                     op.invalid_stack_ptrs()
                         .into_iter()
@@ -282,6 +288,7 @@ impl ExecutionState {
             Self::DIFFICULTY => vec![OpcodeId::DIFFICULTY],
             Self::CHAINID => vec![OpcodeId::CHAINID],
             Self::SELFBALANCE => vec![OpcodeId::SELFBALANCE],
+            Self::BLOBHASH => vec![OpcodeId::BLOBHASH],
             Self::POP => vec![OpcodeId::POP],
             Self::MEMORY => {
                 vec![OpcodeId::MLOAD, OpcodeId::MSTORE, OpcodeId::MSTORE8]
@@ -382,10 +389,11 @@ impl ExecutionState {
             ],
             Self::RETURN_REVERT => vec![OpcodeId::RETURN, OpcodeId::REVERT],
             Self::SELFDESTRUCT => vec![OpcodeId::SELFDESTRUCT],
-            Self::ErrorInvalidOpcode => OpcodeId::invalid_opcodes(),
+            Self::ErrorInvalidOpcode => rules.invalid_opcodes(),
             _ => vec![],
         }
         .into_iter()
+        .filter(|op| matches!(op, OpcodeId::INVALID(_)) || rules.is_opcode_enabled(*op))
         .map(Into::into)
         .collect()
     }
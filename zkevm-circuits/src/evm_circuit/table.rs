@@ -4,7 +4,11 @@ use crate::{
     impl_expr,
 };
 use bus_mapping::{evm::OpcodeId, precompile::PrecompileCalls};
-use eth_types::Field;
+use eth_types::{
+    chain_spec::ChainRules,
+    evm_types::{GasSchedule, GasTable},
+    Field,
+};
 use gadgets::util::Expr;
 use halo2_proofs::plonk::Expression;
 use strum::IntoEnumIterator;
@@ -32,11 +36,18 @@ pub enum FixedTableTag {
     Pow2,
     ConstantGasCost,
     PrecompileInfo,
+    ChainRules,
 }
 impl_expr!(FixedTableTag);
 
 impl FixedTableTag {
-    pub fn build<F: Field>(&self) -> Box<dyn Iterator<Item = [F; 4]>> {
+    /// Rows of the tag, with the constant gas costs of `gas_table` and the
+    /// opcodes and parameters defined by `rules`.
+    pub fn build<F: Field>(
+        &self,
+        gas_table: &GasTable,
+        rules: &ChainRules,
+    ) -> Box<dyn Iterator<Item = [F; 4]>> {
         let tag = F::from(*self as u64);
         match self {
             Self::Zero => Box::new((0..1).map(move |_| [tag, F::zero(), F::zero(), F::zero()])),
@@ -91,8 +102,9 @@ impl FixedTableTag {
                 (0..256).map(move |rhs| [tag, F::from(lhs), F::from(rhs), F::from(lhs ^ rhs)])
             })),
             Self::ResponsibleOpcode => {
+                let rules = *rules;
                 Box::new(ExecutionState::iter().flat_map(move |execution_state| {
-                    execution_state.responsible_opcodes().into_iter().map(
+                    execution_state.responsible_opcodes(&rules).into_iter().map(
                         move |responsible_opcode| {
                             let (op, aux) = match responsible_opcode {
                                 ResponsibleOp::Op(op) => (op, F::zero()),
@@ -118,18 +130,14 @@ impl FixedTableTag {
                 };
                 [tag, F::from(value), pow_lo, pow_hi]
             })),
-            Self::ConstantGasCost => Box::new(
-                OpcodeId::iter()
-                    .filter(move |opcode| opcode.constant_gas_cost().0 > 0)
-                    .map(move |opcode| {
-                        [
-                            tag,
-                            F::from(opcode.as_u64()),
-                            F::from(opcode.constant_gas_cost().0),
-                            F::zero(),
-                        ]
-                    }),
-            ),
+            Self::ConstantGasCost => {
+                let gas_table = *gas_table;
+                Box::new(OpcodeId::iter().filter_map(move |opcode| {
+                    let gas_cost = gas_table.constant_gas_cost(opcode).0;
+                    (gas_cost > 0)
+                        .then(|| [tag, F::from(opcode.as_u64()), F::from(gas_cost), F::zero()])
+                }))
+            }
             Self::PrecompileInfo => Box::new(PrecompileCalls::iter().map(move |precompile| {
                 [
                     tag,
@@ -141,6 +149,12 @@ impl FixedTableTag {
                     F::from(precompile.base_gas_cost().0),
                 ]
             })),
+            Self::ChainRules => Box::new(std::iter::once([
+                tag,
+                F::from(rules.is_coinbase_warm()),
+                F::from(rules.init_code_word_gas()),
+                F::from(rules.max_init_code_size()),
+            ])),
         }
     }
 }
@@ -24,7 +24,9 @@ use crate::{
     witness::{Block, Call, ExecStep},
 };
 use either::Either;
-use eth_types::{evm_types::GasCost, Field, ToLittleEndian, ToScalar, U256};
+use eth_types::{
+    chain_spec::ChainRules, evm_types::GasCost, Field, ToLittleEndian, ToScalar, U256,
+};
 use gadgets::util::{select, sum};
 use halo2_proofs::{
     circuit::Value,
@@ -1587,6 +1589,79 @@ impl<F: Field> CommonErrorGadget<F> {
     }
 }
 
+/// Rules of the hardfork the block is proven under, read from the
+/// [`FixedTableTag::ChainRules`] row of the fixed table.
+#[derive(Clone, Debug)]
+pub(crate) struct ChainRulesGadget<F> {
+    is_coinbase_warm: Cell<F>,
+    init_code_word_gas: Cell<F>,
+    max_init_code_size: Cell<F>,
+}
+
+impl<F: Field> ChainRulesGadget<F> {
+    pub(crate) fn construct(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let is_coinbase_warm = cb.query_cell();
+        let init_code_word_gas = cb.query_cell();
+        let max_init_code_size = cb.query_cell();
+        cb.add_lookup(
+            "Chain rules lookup",
+            Lookup::Fixed {
+                tag: FixedTableTag::ChainRules.expr(),
+                values: [
+                    is_coinbase_warm.expr(),
+                    init_code_word_gas.expr(),
+                    max_init_code_size.expr(),
+                ],
+            },
+        );
+
+        Self {
+            is_coinbase_warm,
+            init_code_word_gas,
+            max_init_code_size,
+        }
+    }
+
+    pub(crate) fn assign(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        rules: &ChainRules,
+    ) -> Result<(), Error> {
+        self.is_coinbase_warm.assign(
+            region,
+            offset,
+            Value::known(F::from(rules.is_coinbase_warm())),
+        )?;
+        self.init_code_word_gas.assign(
+            region,
+            offset,
+            Value::known(F::from(rules.init_code_word_gas())),
+        )?;
+        self.max_init_code_size.assign(
+            region,
+            offset,
+            Value::known(F::from(rules.max_init_code_size())),
+        )?;
+        Ok(())
+    }
+
+    /// Whether the coinbase is warm at the beginning of a tx (EIP-3651).
+    pub(crate) fn is_coinbase_warm(&self) -> Expression<F> {
+        self.is_coinbase_warm.expr()
+    }
+
+    /// Gas paid per word of init code (EIP-3860).
+    pub(crate) fn init_code_word_gas(&self) -> Expression<F> {
+        self.init_code_word_gas.expr()
+    }
+
+    /// Maximum size of init code (EIP-3860).
+    pub(crate) fn max_init_code_size(&self) -> Expression<F> {
+        self.max_init_code_size.expr()
+    }
+}
+
 /// Check if the passed in word is within the specified byte range
 /// (not overflow) and less than a maximum cap.
 #[derive(Clone, Debug)]
@@ -25,6 +25,7 @@ use halo2_proofs::{
 pub(crate) struct TxAccessListGadget<F> {
     is_eip1559_tx: IsEqualGadget<F>,
     is_eip2930_tx: IsEqualGadget<F>,
+    is_eip4844_tx: IsEqualGadget<F>,
     is_address_len_zero: IsZeroGadget<F>,
     is_storage_key_len_zero: IsZeroGadget<F>,
     address_len: Cell<F>,
@@ -37,11 +38,12 @@ impl<F: Field> TxAccessListGadget<F> {
         tx_id: Expression<F>,
         tx_type: Expression<F>,
     ) -> Self {
-        let [is_eip1559_tx, is_eip2930_tx] = [TxType::Eip1559, TxType::Eip2930]
-            .map(|val| IsEqualGadget::construct(cb, tx_type.expr(), (val as u64).expr()));
+        let [is_eip1559_tx, is_eip2930_tx, is_eip4844_tx] =
+            [TxType::Eip1559, TxType::Eip2930, TxType::Eip4844]
+                .map(|val| IsEqualGadget::construct(cb, tx_type.expr(), (val as u64).expr()));
 
         let (address_len, storage_key_len, is_address_len_zero, is_storage_key_len_zero) = cb.condition(
-            or::expr([is_eip1559_tx.expr(), is_eip2930_tx.expr()]),
+            or::expr([is_eip1559_tx.expr(), is_eip2930_tx.expr(), is_eip4844_tx.expr()]),
             |cb| {
                 let [(address_len, is_address_len_zero), (storage_key_len, is_storage_key_len_zero)] = [
                     TxFieldTag::AccessListAddressesLen,
@@ -93,6 +95,7 @@ impl<F: Field> TxAccessListGadget<F> {
         Self {
             is_eip1559_tx,
             is_eip2930_tx,
+            is_eip4844_tx,
             is_address_len_zero,
             is_storage_key_len_zero,
             address_len,
@@ -118,6 +121,12 @@ impl<F: Field> TxAccessListGadget<F> {
             F::from(tx.tx_type as u64),
             F::from(TxType::Eip2930 as u64),
         )?;
+        self.is_eip4844_tx.assign(
+            region,
+            offset,
+            F::from(tx.tx_type as u64),
+            F::from(TxType::Eip4844 as u64),
+        )?;
 
         let (address_len, storage_key_len) = access_list_size(&tx.access_list);
 
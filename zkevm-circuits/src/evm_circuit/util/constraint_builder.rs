@@ -627,6 +627,18 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         );
     }
 
+    /// Gas left transition of a step consuming the constant gas cost of
+    /// `opcode` in the gas schedule of the fixed table plus `dynamic_gas_cost`.
+    pub(crate) fn gas_left_transition(
+        &mut self,
+        opcode: Expression<F>,
+        dynamic_gas_cost: Expression<F>,
+    ) -> Transition<Expression<F>> {
+        let gas_cost = self.curr.state.gas_left.expr() - self.next.state.gas_left.expr();
+        self.constant_gas_lookup(opcode, gas_cost - dynamic_gas_cost);
+        Transition::Any
+    }
+
     // Opcode
 
     pub(crate) fn opcode_lookup(&mut self, opcode: Expression<F>, is_code: Expression<F>) {
@@ -31,7 +31,7 @@ use halo2_proofs::plonk::SecondPhase;
 #[cfg(not(feature = "onephase"))]
 use halo2_proofs::plonk::ThirdPhase;
 
-use eth_types::{Field, Word, U256};
+use eth_types::{chain_spec::ChainRules, evm_types::GasTable, Field, Word, U256};
 pub(crate) use halo2_proofs::circuit::{Layouter, Value};
 use halo2_proofs::{
     circuit::SimpleFloorPlanner,
@@ -249,7 +249,9 @@ impl<F: Field, G: MathGadgetContainer<F>> Circuit<F> for UnitTestMathGadgetBaseC
                                         | FixedTableTag::Range1024
                                 )
                             })
-                            .flat_map(|tag| tag.build()),
+                            .flat_map(|tag| {
+                                tag.build(&GasTable::default(), &ChainRules::default())
+                            }),
                     )
                     .enumerate()
                 {
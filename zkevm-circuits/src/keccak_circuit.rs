@@ -1007,6 +1007,13 @@ pub struct KeccakCircuit<F: Field> {
     _marker: PhantomData<F>,
 }
 
+/// Rows of the keccak circuit hashing an input of `num_bytes` bytes.
+/// The padding always adds a byte, so an input takes `num_bytes / RATE + 1`
+/// keccak_f's.
+pub(crate) fn keccak_num_rows(num_bytes: usize) -> usize {
+    (num_bytes / RATE + 1) * (NUM_ROUNDS + 1) * get_num_rows_per_round()
+}
+
 impl<F: Field> SubCircuit<F> for KeccakCircuit<F> {
     type Config = KeccakCircuitConfig<F>;
 
@@ -1026,7 +1033,6 @@ impl<F: Field> SubCircuit<F> for KeccakCircuit<F> {
 
     /// Return the minimum number of rows required to prove the block
     fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
-        let rows_per_chunk = (NUM_ROUNDS + 1) * get_num_rows_per_round();
         let aux_tables_rows = [
             normalize_table_size(6),
             normalize_table_size(4),
@@ -1037,9 +1043,10 @@ impl<F: Field> SubCircuit<F> for KeccakCircuit<F> {
             block
                 .keccak_inputs
                 .iter()
-                .map(|bytes| (bytes.len() as f64 / 136.0).ceil() as usize * rows_per_chunk)
+                .map(|bytes| keccak_num_rows(bytes.len()))
                 .sum::<usize>()
-                + get_num_rows_per_round(), // reserved for first 12 dummy rows
+                // 2 keccak_f's are unusable, see `capacity_for_row`
+                + 2 * (NUM_ROUNDS + 1) * get_num_rows_per_round(),
             max(
                 block.circuits_params.max_keccak_rows,
                 *(aux_tables_rows.iter().max().unwrap()),
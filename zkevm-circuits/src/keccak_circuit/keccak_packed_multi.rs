@@ -5,6 +5,7 @@ use halo2_proofs::{
     circuit::Value,
     plonk::{Error, Expression},
 };
+use keccak256::{common::State, Keccak};
 use log::{debug, trace};
 use rayon::{iter::IntoParallelRefIterator, prelude::ParallelIterator};
 use std::{env::var, vec};
@@ -544,30 +545,28 @@ pub(crate) fn keccak<F: Field>(
     bytes: &[u8],
     challenges: Challenges<Value<F>>,
 ) {
-    let mut bits = into_bits(bytes);
+    // The padded blocks and the state at each round, which the witness below
+    // must follow.
+    let mut hasher = Keccak::with_trace();
+    hasher.update(bytes);
+    let (_, blocks) = hasher.finalize_with_trace();
+    let pack_state = |state: &State| state.map(|lanes| lanes.map(pack_u64::<F>));
+
     let mut s = [[F::zero(); 5]; 5];
     let absorb_positions = get_absorb_positions();
-    let num_bytes_in_last_block = bytes.len() % RATE;
     let two = F::from(2u64);
 
-    // Padding
-    bits.push(1);
-    while (bits.len() + 1) % RATE_IN_BITS != 0 {
-        bits.push(0);
-    }
-    bits.push(1);
-
     let mut length = 0usize;
     let mut data_rlc = Value::known(F::zero());
-    let chunks = bits.chunks(RATE_IN_BITS);
-    let num_chunks = chunks.len();
-    for (idx, chunk) in chunks.enumerate() {
+    let num_chunks = blocks.len();
+    for (idx, block) in blocks.iter().enumerate() {
         let is_final_block = idx == num_chunks - 1;
+        debug_assert_eq!(s, pack_state(&block.state_in));
 
         let mut absorb_rows = Vec::new();
         // Absorb
         for (idx, &(i, j)) in absorb_positions.iter().enumerate() {
-            let absorb = pack(&chunk[idx * 64..(idx + 1) * 64]);
+            let absorb = pack_u64(block.lanes[idx]);
             let from = s[i][j];
             s[i][j] = field_xor(s[i][j], absorb);
             absorb_rows.push(AbsorbData {
@@ -595,6 +594,7 @@ pub(crate) fn keccak<F: Field>(
             }
 
             // State data
+            debug_assert_eq!(s, pack_state(&block.rounds[round]));
             for s in &s {
                 for s in s {
                     let cell = cell_manager.query_cell_value();
@@ -651,7 +651,7 @@ pub(crate) fn keccak<F: Field>(
                 let mut paddings = Vec::new();
                 for (padding_idx, is_padding) in is_paddings.iter_mut().enumerate() {
                     let byte_idx = round * 8 + padding_idx;
-                    let padding = if is_final_block && byte_idx >= num_bytes_in_last_block {
+                    let padding = if byte_idx >= block.num_data_bytes {
                         true
                     } else {
                         length += 1;
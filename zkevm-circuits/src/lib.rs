@@ -46,8 +46,7 @@ pub mod table;
 #[cfg(any(feature = "test", test))]
 pub mod test_util;
 
-#[cfg(any(feature = "test", test))]
-mod stats;
+pub mod stats;
 
 pub mod tx_circuit;
 pub mod util;
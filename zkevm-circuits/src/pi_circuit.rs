@@ -9,9 +9,17 @@ mod test;
 
 use std::{cell::RefCell, collections::BTreeMap, iter, marker::PhantomData, str::FromStr};
 
-use crate::{evm_circuit::util::constraint_builder::ConstrainBuilderCommon, table::KeccakTable};
-use bus_mapping::circuit_input_builder::get_dummy_tx_hash;
-use eth_types::{Address, Field, Hash, ToBigEndian, ToWord, Word, H256};
+use crate::{
+    evm_circuit::util::{constraint_builder::ConstrainBuilderCommon, rlc},
+    table::{KeccakTable, MPTProofType, MptTable},
+};
+use bus_mapping::{
+    circuit_input_builder::get_dummy_tx_hash,
+    l2_predeployed::message_queue::{ADDRESS as MESSAGE_QUEUE, WITHDRAW_TRIE_ROOT_SLOT},
+};
+use eth_types::{
+    l2_types::L1QueueRange, Address, Field, Hash, ToBigEndian, ToScalar, ToWord, Word, H256,
+};
 use ethers_core::utils::keccak256;
 use halo2_proofs::plonk::{Assigned, Expression, Fixed, Instance};
 
@@ -28,15 +36,23 @@ use crate::{
     evm_circuit::{util::constraint_builder::BaseConstraintBuilder, EvmCircuitExports},
     pi_circuit::param::{
         BASE_FEE_OFFSET, BLOCK_HEADER_BYTES_NUM, BLOCK_LEN, BLOCK_NUM_OFFSET, BYTE_POW_BASE,
-        CHAIN_ID_OFFSET, GAS_LIMIT_OFFSET, KECCAK_DIGEST_SIZE, RPI_CELL_IDX,
+        CHAIN_ID_OFFSET, GAS_LIMIT_OFFSET, KECCAK_DIGEST_SIZE, N_L1_MSGS_PER_WORD, RPI_CELL_IDX,
         RPI_LENGTH_ACC_CELL_IDX, RPI_RLC_ACC_CELL_IDX, TIMESTAMP_OFFSET,
     },
     state_circuit::StateCircuitExports,
-    tx_circuit::{CHAIN_ID_OFFSET as CHAIN_ID_OFFSET_IN_TX, TX_HASH_OFFSET, TX_LEN},
+    table::TxFieldTag,
+    tx_circuit::{
+        TxCircuitExports, CHAIN_ID_OFFSET as CHAIN_ID_OFFSET_IN_TX, NONCE_OFFSET, TX_HASH_OFFSET,
+        TX_LEN, TX_TYPE_OFFSET,
+    },
     witness::{self, Block, BlockContext, BlockContexts, Transaction},
 };
 use bus_mapping::util::read_env_var;
-use gadgets::util::{and, not, select, Expr};
+use eth_types::geth_types::TxType;
+use gadgets::{
+    is_equal::{IsEqualChip, IsEqualConfig, IsEqualInstruction},
+    util::{and, not, select, Expr},
+};
 use halo2_proofs::{
     circuit::{AssignedCell, Layouter, Region, Value},
     plonk::{Advice, Column, ConstraintSystem, Error, Selector},
@@ -79,6 +95,8 @@ pub struct PublicData {
     pub chain_id: u64,
     /// Start L1 QueueIndex
     pub start_l1_queue_index: u64,
+    /// The range of the L1 message queue popped by the chunk
+    pub l1_queue_range: L1QueueRange,
     /// Block Transactions
     pub transactions: Vec<Transaction>,
     /// Block contexts
@@ -95,6 +113,8 @@ pub struct PublicData {
     pub max_calldata: usize,
     /// Max number of supported inner blocks in a chunk
     pub max_inner_blocks: usize,
+    /// Max number of l1 msgs popped by the chunk, skipped ones included
+    pub max_l1_msgs_popped: usize,
 }
 
 impl PublicData {
@@ -195,6 +215,7 @@ impl PublicData {
     }
 
     fn pi_bytes(&self, data_hash: H256) -> Vec<u8> {
+        let l1_queue_range = &self.l1_queue_range;
         iter::empty()
             .chain(self.chain_id.to_be_bytes())
             // state roots
@@ -203,6 +224,10 @@ impl PublicData {
             .chain(self.withdraw_trie_root.to_fixed_bytes())
             // data hash
             .chain(data_hash.to_fixed_bytes())
+            // l1 message queue
+            .chain(l1_queue_range.start_queue_index.to_be_bytes())
+            .chain(l1_queue_range.end_queue_index.to_be_bytes())
+            .chain(l1_queue_range.skipped_bitmap_hash().to_fixed_bytes())
             .collect::<Vec<u8>>()
     }
 
@@ -274,7 +299,7 @@ impl PublicData {
     }
 
     fn pi_bytes_end_offset(&self) -> usize {
-        self.pi_bytes_start_offset() + N_BYTES_U64 + N_BYTES_WORD * 4
+        self.pi_bytes_start_offset() + N_BYTES_U64 * 3 + N_BYTES_WORD * 5
     }
 
     fn pi_hash_start_offset(&self) -> usize {
@@ -297,6 +322,26 @@ impl PublicData {
     fn constants_end_offset(&self) -> usize {
         self.constants_start_offset() + N_BYTES_ACCOUNT_ADDRESS + N_BYTES_WORD
     }
+
+    fn l1_queue_start_offset(&self) -> usize {
+        // the l1 msg queue has its own columns, except for the keccak lookup of the skipped bitmap
+        // on its last row. Hence we start assigning it from the row after the constants.
+        self.constants_end_offset() + 1
+    }
+
+    fn l1_queue_end_offset(&self) -> usize {
+        // a row for each of the msgs that can be popped and the last row, or a row for each tx.
+        self.l1_queue_start_offset()
+            + (l1_queue_capacity(self.max_l1_msgs_popped) + 1).max(self.max_txs)
+    }
+}
+
+/// Number of msgs of the l1 queue rows, i.e. `max_l1_msgs_popped` rounded up to whole words of the
+/// skipped bitmap, and at least one word so that the range is constrained.
+fn l1_queue_capacity(max_l1_msgs_popped: usize) -> usize {
+    max_l1_msgs_popped
+        .max(1)
+        .next_multiple_of(N_L1_MSGS_PER_WORD)
 }
 
 impl BlockContext {
@@ -363,6 +408,37 @@ pub struct PiCircuitConfig<F: Field> {
     is_rlc_keccak: Column<Fixed>,
     q_keccak: Selector,
 
+    // lookup of (next_state_root, withdraw_trie_root) into the mpt table
+    q_withdraw_root: Selector,
+    withdraw_root_lookup: [Column<Advice>; 2],
+    withdraw_root_proof_type: Column<Advice>,
+
+    // columns for the l1 msg queue range popped by the chunk, one row per popped msg
+    q_l1_queue: Column<Fixed>,
+    q_l1_queue_first: Column<Fixed>,
+    l1_queue_index: Column<Advice>,
+    l1_end_queue_index: Column<Advice>,
+    l1_is_popped: Column<Advice>,
+    l1_is_skipped: Column<Advice>,
+    l1_is_included: Column<Advice>,
+    l1_tx_id: Column<Advice>,
+    // columns for the skipped bitmap of the popped msgs
+    l1_bit_weight: Column<Fixed>,
+    q_l1_byte_end: Column<Fixed>,
+    q_l1_word_start: Column<Fixed>,
+    l1_word_in_use: Column<Advice>,
+    l1_byte_acc: Column<Advice>,
+    l1_byte_weight: Column<Advice>,
+    l1_word_rlc: Column<Advice>,
+    l1_bitmap_rlc: Column<Advice>,
+    l1_bitmap_len: Column<Advice>,
+    // columns for the l1 msgs in the tx table, one row per tx
+    q_l1_tx: Column<Fixed>,
+    l1_tx_type: Column<Advice>,
+    l1_tx_nonce: Column<Advice>,
+    l1_tx_is_l1_msg: Column<Advice>,
+    l1_tx_type_is_l1_msg: IsEqualConfig<F>,
+
     // 32 big-endian bytes of pi_hash
     pi: Column<Instance>,
 
@@ -370,6 +446,7 @@ pub struct PiCircuitConfig<F: Field> {
     block_table: BlockTable,
     tx_table: TxTable,
     keccak_table: KeccakTable,
+    mpt_table: MptTable,
 
     _marker: PhantomData<F>,
 }
@@ -382,6 +459,8 @@ pub struct PiCircuitConfigArgs<F: Field> {
     pub block_table: BlockTable,
     /// Keccak Table
     pub keccak_table: KeccakTable,
+    /// MPT Table
+    pub mpt_table: MptTable,
     /// Challenges
     pub challenges: Challenges<Expression<F>>,
 }
@@ -396,6 +475,7 @@ impl<F: Field> SubCircuitConfig<F> for PiCircuitConfig<F> {
             block_table,
             tx_table,
             keccak_table,
+            mpt_table,
             challenges,
         }: Self::ConfigArgs,
     ) -> Self {
@@ -447,10 +527,40 @@ impl<F: Field> SubCircuitConfig<F> for PiCircuitConfig<F> {
         let is_rlc_keccak = meta.fixed_column();
         let q_keccak = meta.complex_selector();
 
+        let q_withdraw_root = meta.complex_selector();
+        // (next_state_root, withdraw_trie_root)
+        let withdraw_root_lookup = [
+            meta.advice_column_in(SecondPhase),
+            meta.advice_column_in(SecondPhase),
+        ];
+        let withdraw_root_proof_type = meta.advice_column();
+
         let q_block_tag = meta.fixed_column();
         let cum_num_txs = meta.advice_column();
         let is_block_num_txs = meta.fixed_column();
 
+        let q_l1_queue = meta.fixed_column();
+        let q_l1_queue_first = meta.fixed_column();
+        let l1_queue_index = meta.advice_column();
+        let l1_end_queue_index = meta.advice_column();
+        let l1_is_popped = meta.advice_column();
+        let l1_is_skipped = meta.advice_column();
+        let l1_is_included = meta.advice_column();
+        let l1_tx_id = meta.advice_column();
+        let l1_bit_weight = meta.fixed_column();
+        let q_l1_byte_end = meta.fixed_column();
+        let q_l1_word_start = meta.fixed_column();
+        let l1_word_in_use = meta.advice_column();
+        let l1_byte_acc = meta.advice_column();
+        let l1_byte_weight = meta.advice_column_in(SecondPhase);
+        let l1_word_rlc = meta.advice_column_in(SecondPhase);
+        let l1_bitmap_rlc = meta.advice_column_in(SecondPhase);
+        let l1_bitmap_len = meta.advice_column();
+        let q_l1_tx = meta.fixed_column();
+        let l1_tx_type = meta.advice_column();
+        let l1_tx_nonce = meta.advice_column();
+        let l1_tx_is_l1_msg = meta.advice_column();
+
         meta.enable_constant(constant);
         meta.enable_equality(rpi_bytes);
         meta.enable_equality(rpi_bytes_acc);
@@ -458,11 +568,25 @@ impl<F: Field> SubCircuitConfig<F> for PiCircuitConfig<F> {
         meta.enable_equality(rpi_length_acc);
         meta.enable_equality(rpi_rlc_acc);
         meta.enable_equality(real_rpi);
+        for column in withdraw_root_lookup {
+            meta.enable_equality(column);
+        }
         meta.enable_equality(block_table.value); // copy block to rpi
         meta.enable_equality(block_table.index);
         meta.enable_equality(tx_table.value); // copy tx hashes to rpi
         meta.enable_equality(cum_num_txs);
         meta.enable_equality(pi);
+        for column in [
+            l1_queue_index,
+            l1_end_queue_index,
+            l1_is_popped,
+            l1_bitmap_rlc,
+            l1_bitmap_len,
+            l1_tx_type,
+            l1_tx_nonce,
+        ] {
+            meta.enable_equality(column);
+        }
 
         // 1. constrain rpi_bytes, rpi_bytes_acc, and rpi for each field
         meta.create_gate(
@@ -629,8 +753,11 @@ impl<F: Field> SubCircuitConfig<F> for PiCircuitConfig<F> {
         // prev_state_root  |   ..    |     ..    |      ...      |     ...     |      ...       |
         // after_state_root |   ..    |     ..    |      ...      |     ...     |      ...       |
         // withdraw_root    |   ..    |     ..    |      ...      |     ...     |      ...       |
-        // data hash        |  dh_rlc |     ..    |      ...      |  pi_bs_rlc  |      136       |
-        // q_keccak = 1     |pi_bs_rlc|     ..    |      ...      | pi_hash_rlc |      136       |
+        // data hash        |  dh_rlc |     ..    |      ...      |     ...     |      136       |
+        // start_l1_index   |   ..    |     ..    |      ...      |     ...     |      ...       |
+        // end_l1_index     |   ..    |     ..    |      ...      |     ...     |      ...       |
+        // skipped_l1_hash  |   ..    |     ..    |      ...      |  pi_bs_rlc  |      184       |
+        // q_keccak = 1     |pi_bs_rlc|     ..    |      ...      | pi_hash_rlc |      184       |
         //   pi hash        |   hi    |     ..    |      ...      |     ...     |       16       |
         //                  |   lo    |     ..    |      ...      | pi_hash_rlc |       32       |
         meta.lookup_any("keccak(rpi)", |meta| {
@@ -657,6 +784,62 @@ impl<F: Field> SubCircuitConfig<F> for PiCircuitConfig<F> {
                 .collect()
         });
 
+        // The withdraw root is the storage of the message queue contract in the
+        // state after the chunk. The EndBlock step reads it after the last tx, so its
+        // MPT update is the last one of the chunk, and its new root is the end state root.
+        meta.create_gate("withdraw root proof type", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+            let proof_type = meta.query_advice(withdraw_root_proof_type, Rotation::cur());
+            cb.require_zero(
+                "proof_type is StorageChanged or StorageDoesNotExist",
+                (proof_type.expr() - (MPTProofType::StorageChanged as u64).expr())
+                    * (proof_type - (MPTProofType::StorageDoesNotExist as u64).expr()),
+            );
+            cb.gate(meta.query_selector(q_withdraw_root))
+        });
+        meta.lookup_any(
+            "withdraw root is message queue storage at end state root",
+            |meta| {
+                let q_withdraw_root = meta.query_selector(q_withdraw_root);
+                let mut withdraw_trie_root_slot_le = [0u8; 32];
+                WITHDRAW_TRIE_ROOT_SLOT.to_little_endian(&mut withdraw_trie_root_slot_le);
+                let [state_root, withdraw_root] =
+                    withdraw_root_lookup.map(|column| meta.query_advice(column, Rotation::cur()));
+
+                vec![
+                    (
+                        q_withdraw_root.expr(),
+                        meta.query_fixed(mpt_table.q_enable, Rotation::cur()),
+                    ),
+                    (
+                        q_withdraw_root.expr() * MESSAGE_QUEUE.to_scalar().unwrap().expr(),
+                        meta.query_advice(mpt_table.address, Rotation::cur()),
+                    ),
+                    (
+                        q_withdraw_root.expr()
+                            * rlc::expr(
+                                &withdraw_trie_root_slot_le.map(|byte| byte.expr()),
+                                challenges.evm_word(),
+                            ),
+                        meta.query_advice(mpt_table.storage_key, Rotation::cur()),
+                    ),
+                    (
+                        q_withdraw_root.expr()
+                            * meta.query_advice(withdraw_root_proof_type, Rotation::cur()),
+                        meta.query_advice(mpt_table.proof_type, Rotation::cur()),
+                    ),
+                    (
+                        q_withdraw_root.expr() * state_root,
+                        meta.query_advice(mpt_table.new_root, Rotation::cur()),
+                    ),
+                    (
+                        q_withdraw_root * withdraw_root,
+                        meta.query_advice(mpt_table.new_value, Rotation::cur()),
+                    ),
+                ]
+            },
+        );
+
         // 3. constrain block_table
         meta.create_gate(
             "cum_num_txs::next == cum_num_txs::cur + (block_table.tag == NumTxs) ? block_table.value : 0",
@@ -689,10 +872,256 @@ impl<F: Field> SubCircuitConfig<F> for PiCircuitConfig<F> {
             }
         );
 
+        // 4. constrain the l1 msg queue range popped by the chunk
+        //
+        // The layout has a row for each of the first max_l1_msgs_popped queue indices from
+        // start_l1_queue_index, rounded up to whole words of the skipped bitmap, and a last row
+        // with is_popped = 0.
+        // | queue_index | is_popped | is_skipped | bit_weight | byte_acc |  bitmap_rlc  |
+        // |    start    |     1     |     1      |     1      |    1     |      0       |
+        // |   start+1   |     1     |     0      |     2      |    1     |      0       |
+        // |    ...      |    ...    |    ...     |    ...     |   ...    |     ...      |
+        // |   start+7   |     1     |     0      |    128     | byte_0   |    byte_0    |
+        // |    ...      |    ...    |    ...     |    ...     |   ...    |     ...      |
+        // |  end - 1    |     1     |     0      |    ...     |   ...    |     ...      |
+        // |    end      |     0     |     0      |    ...     |   ...    |     ...      |
+        // The popped msgs which are not skipped are looked up in the tx table as l1 msgs, and
+        // the l1 msgs of the tx table are looked up in the included rows. The bits of the
+        // skipped bitmap are accumulated into big-endian uint256 words, as the
+        // skippedL1MessageBitmap of ScrollChain, of which the rlc is looked up in the keccak table
+        // along with skipped_bitmap_hash.
+        meta.create_gate("l1 msg queue range", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let is_popped = meta.query_advice(l1_is_popped, Rotation::cur());
+            let is_popped_next = meta.query_advice(l1_is_popped, Rotation::next());
+            let is_skipped = meta.query_advice(l1_is_skipped, Rotation::cur());
+            let queue_index = meta.query_advice(l1_queue_index, Rotation::cur());
+            let end_queue_index = meta.query_advice(l1_end_queue_index, Rotation::cur());
+
+            cb.require_boolean("is_popped is boolean", is_popped.expr());
+            cb.require_boolean("is_skipped is boolean", is_skipped.expr());
+            cb.condition(is_skipped.expr(), |cb| {
+                cb.require_equal("only popped msgs are skipped", is_popped.expr(), 1.expr());
+            });
+            cb.require_equal(
+                "is_included = is_popped - is_skipped",
+                meta.query_advice(l1_is_included, Rotation::cur()),
+                is_popped.expr() - is_skipped.expr(),
+            );
+            cb.condition(not::expr(is_popped.expr()), |cb| {
+                cb.require_zero(
+                    "is_popped' == false if is_popped is false",
+                    is_popped_next.expr(),
+                );
+            });
+            cb.require_equal(
+                "queue_index' = queue_index + 1",
+                meta.query_advice(l1_queue_index, Rotation::next()),
+                queue_index.expr() + 1.expr(),
+            );
+            cb.require_equal(
+                "end_queue_index' = end_queue_index",
+                meta.query_advice(l1_end_queue_index, Rotation::next()),
+                end_queue_index.expr(),
+            );
+
+            // the last popped msg ends the range, and it's always included
+            cb.condition(
+                and::expr([is_popped.expr(), not::expr(is_popped_next.expr())]),
+                |cb| {
+                    cb.require_equal(
+                        "end_queue_index = queue_index + 1 for the last popped msg",
+                        end_queue_index.expr(),
+                        queue_index.expr() + 1.expr(),
+                    );
+                    cb.require_zero("the last popped msg is not skipped", is_skipped.expr());
+                },
+            );
+            cb.condition(
+                and::expr([
+                    meta.query_fixed(q_l1_queue_first, Rotation::cur()),
+                    not::expr(is_popped.expr()),
+                ]),
+                |cb| {
+                    cb.require_equal(
+                        "end_queue_index = start_queue_index if no msg is popped",
+                        end_queue_index.expr(),
+                        queue_index.expr(),
+                    );
+                },
+            );
+
+            // a word of the skipped bitmap is in use if its first msg is popped
+            let word_in_use = meta.query_advice(l1_word_in_use, Rotation::cur());
+            let word_in_use_next = meta.query_advice(l1_word_in_use, Rotation::next());
+            cb.condition(meta.query_fixed(q_l1_word_start, Rotation::cur()), |cb| {
+                cb.require_equal(
+                    "word_in_use = is_popped for the first msg of a word",
+                    word_in_use.expr(),
+                    is_popped.expr(),
+                );
+            });
+            cb.condition(
+                not::expr(meta.query_fixed(q_l1_word_start, Rotation::next())),
+                |cb| {
+                    cb.require_equal(
+                        "word_in_use' = word_in_use within a word",
+                        word_in_use_next.expr(),
+                        word_in_use.expr(),
+                    );
+                },
+            );
+
+            // the bits of a byte are accumulated in little-endian order
+            let byte_acc = meta.query_advice(l1_byte_acc, Rotation::cur());
+            let byte_acc_next = meta.query_advice(l1_byte_acc, Rotation::next());
+            let is_byte_end = meta.query_fixed(q_l1_byte_end, Rotation::cur());
+            cb.condition(meta.query_fixed(q_l1_queue_first, Rotation::cur()), |cb| {
+                cb.require_equal(
+                    "byte_acc = is_skipped * bit_weight for the first msg",
+                    byte_acc.expr(),
+                    is_skipped.expr() * meta.query_fixed(l1_bit_weight, Rotation::cur()),
+                );
+            });
+            cb.require_equal(
+                "byte_acc' = (is_byte_end ? 0 : byte_acc) + is_skipped' * bit_weight'",
+                byte_acc_next.expr(),
+                select::expr(is_byte_end, 0.expr(), byte_acc)
+                    + meta.query_advice(l1_is_skipped, Rotation::next())
+                        * meta.query_fixed(l1_bit_weight, Rotation::next()),
+            );
+
+            // the bytes of a word are big-endian, so the k-th byte from its first msg has the
+            // weight r^k in the rlc of the word
+            let byte_weight = meta.query_advice(l1_byte_weight, Rotation::cur());
+            let word_rlc = meta.query_advice(l1_word_rlc, Rotation::cur());
+            let is_word_start_next = meta.query_fixed(q_l1_word_start, Rotation::next());
+            cb.condition(meta.query_fixed(q_l1_queue_first, Rotation::cur()), |cb| {
+                cb.require_equal(
+                    "byte_weight = 1 for the first msg",
+                    byte_weight.expr(),
+                    1.expr(),
+                );
+                cb.require_zero("word_rlc = 0 for the first msg", word_rlc.expr());
+            });
+            let byte_weight_next = meta.query_advice(l1_byte_weight, Rotation::next());
+            cb.require_equal(
+                "byte_weight' = is_word_start' ? 1 : (is_byte_end ? byte_weight * r : byte_weight)",
+                byte_weight_next.expr(),
+                select::expr(
+                    is_word_start_next.expr(),
+                    1.expr(),
+                    select::expr(
+                        meta.query_fixed(q_l1_byte_end, Rotation::cur()),
+                        byte_weight.expr() * challenges.keccak_input(),
+                        byte_weight.expr(),
+                    ),
+                ),
+            );
+            cb.require_equal(
+                "word_rlc' = (is_word_start' ? 0 : word_rlc) + is_byte_end' * byte_acc' * byte_weight'",
+                meta.query_advice(l1_word_rlc, Rotation::next()),
+                select::expr(is_word_start_next.expr(), 0.expr(), word_rlc.expr())
+                    + meta.query_fixed(q_l1_byte_end, Rotation::next())
+                        * byte_acc_next
+                        * byte_weight_next,
+            );
+
+            // the words in use are the input of skipped_bitmap_hash, each is added to the rlc of
+            // the bitmap on its last msg, where byte_weight * r = r^32
+            let bitmap_rlc = meta.query_advice(l1_bitmap_rlc, Rotation::cur());
+            let bitmap_len = meta.query_advice(l1_bitmap_len, Rotation::cur());
+            let is_input_word = and::expr([is_word_start_next, word_in_use]);
+            cb.require_equal(
+                "bitmap_rlc' = is_input_word ? bitmap_rlc * r^32 + word_rlc : bitmap_rlc",
+                meta.query_advice(l1_bitmap_rlc, Rotation::next()),
+                select::expr(
+                    is_input_word.expr(),
+                    bitmap_rlc.expr() * byte_weight * challenges.keccak_input() + word_rlc,
+                    bitmap_rlc,
+                ),
+            );
+            cb.require_equal(
+                "bitmap_len' = bitmap_len + is_input_word * 32",
+                meta.query_advice(l1_bitmap_len, Rotation::next()),
+                bitmap_len + is_input_word * N_BYTES_WORD.expr(),
+            );
+
+            cb.gate(meta.query_fixed(q_l1_queue, Rotation::cur()))
+        });
+
+        meta.lookup_any("included l1 msg in tx table", |meta| {
+            let enable = meta.query_fixed(q_l1_queue, Rotation::cur())
+                * meta.query_advice(l1_is_included, Rotation::cur());
+            let tx_id = meta.query_advice(l1_tx_id, Rotation::cur());
+
+            vec![
+                1.expr(),
+                tx_id,
+                TxFieldTag::Nonce.expr(),
+                0.expr(),
+                meta.query_advice(l1_queue_index, Rotation::cur()),
+            ]
+            .into_iter()
+            .zip(tx_table.table_exprs(meta))
+            .map(|(arg, table)| (enable.expr() * arg, table))
+            .collect()
+        });
+        meta.lookup_any("included l1 msg is an l1 msg tx", |meta| {
+            let enable = meta.query_fixed(q_l1_queue, Rotation::cur())
+                * meta.query_advice(l1_is_included, Rotation::cur());
+            let tx_id = meta.query_advice(l1_tx_id, Rotation::cur());
+
+            vec![
+                1.expr(),
+                tx_id,
+                TxFieldTag::TxType.expr(),
+                0.expr(),
+                (TxType::L1Msg as u64).expr(),
+            ]
+            .into_iter()
+            .zip(tx_table.table_exprs(meta))
+            .map(|(arg, table)| (enable.expr() * arg, table))
+            .collect()
+        });
+
+        // tx_type and nonce of each tx are copied from the tx table
+        let l1_tx_type_is_l1_msg = IsEqualChip::configure(
+            meta,
+            |meta| meta.query_fixed(q_l1_tx, Rotation::cur()),
+            |meta| meta.query_advice(l1_tx_type, Rotation::cur()),
+            |_| (TxType::L1Msg as u64).expr(),
+        );
+        meta.create_gate("is_l1_msg = tx_type == L1Msg", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+            cb.require_equal(
+                "is_l1_msg = tx_type == L1Msg",
+                meta.query_advice(l1_tx_is_l1_msg, Rotation::cur()),
+                l1_tx_type_is_l1_msg.expr(),
+            );
+            cb.gate(meta.query_fixed(q_l1_tx, Rotation::cur()))
+        });
+        meta.lookup_any("l1 msg tx is popped and included", |meta| {
+            let is_l1_msg = meta.query_advice(l1_tx_is_l1_msg, Rotation::cur());
+            let nonce = meta.query_advice(l1_tx_nonce, Rotation::cur());
+            let q_l1_queue = meta.query_fixed(q_l1_queue, Rotation::cur());
+            let is_included = meta.query_advice(l1_is_included, Rotation::cur());
+
+            vec![
+                (is_l1_msg.expr(), q_l1_queue.expr() * is_included),
+                (
+                    is_l1_msg * nonce,
+                    q_l1_queue * meta.query_advice(l1_queue_index, Rotation::cur()),
+                ),
+            ]
+        });
+
         Self {
             block_table,
             tx_table,
             keccak_table,
+            mpt_table,
             constant,
             raw_public_inputs: rpi,
             rpi_field_bytes: rpi_bytes,
@@ -707,6 +1136,31 @@ impl<F: Field> SubCircuitConfig<F> for PiCircuitConfig<F> {
             q_not_end,
             is_rlc_keccak,
             q_keccak,
+            q_withdraw_root,
+            withdraw_root_lookup,
+            withdraw_root_proof_type,
+            q_l1_queue,
+            q_l1_queue_first,
+            l1_queue_index,
+            l1_end_queue_index,
+            l1_is_popped,
+            l1_is_skipped,
+            l1_is_included,
+            l1_tx_id,
+            l1_bit_weight,
+            q_l1_byte_end,
+            q_l1_word_start,
+            l1_word_in_use,
+            l1_byte_acc,
+            l1_byte_weight,
+            l1_word_rlc,
+            l1_bitmap_rlc,
+            l1_bitmap_len,
+            q_l1_tx,
+            l1_tx_type,
+            l1_tx_nonce,
+            l1_tx_is_l1_msg,
+            l1_tx_type_is_l1_msg,
             cum_num_txs,
             q_block_tag,
             is_block_num_txs,
@@ -725,6 +1179,7 @@ struct Connections<F: Field> {
     start_state_root: AssignedCell<F, F>,
     end_state_root: AssignedCell<F, F>,
     withdraw_root: AssignedCell<F, F>,
+    start_l1_queue_index: AssignedCell<F, F>,
 }
 
 impl<F: Field> PiCircuitConfig<F> {
@@ -756,13 +1211,16 @@ impl<F: Field> PiCircuitConfig<F> {
     /// |          |------------------------|--------------------------|
     /// |          | rlc(data_bytes)        | <- q_keccak == 1         |
     /// |----------|------------------------|--------------------------|
-    /// |          | rpi initialise         |                          |
+    /// |          | rpi initialise         | <- q_withdraw_root == 1  |
     /// |          | chain_id               |                          |
     /// | *PART 2* | prev_state_root        |                          |
     /// |          | next_state_root        |                          |
     /// | ASSIGN   | withdraw_trie_root     |                          |
     /// | PI       | data_hash              |                          |
-    /// | BYTES    |------------------------|--------------------------|
+    /// | BYTES    | start_l1_queue_index   |                          |
+    /// |          | end_l1_queue_index     |                          |
+    /// |          | skipped_l1_msgs_hash   |                          |
+    /// |          |------------------------|--------------------------|
     /// |          | rlc(pi_bytes)          | <- q_keccak == 1         |
     /// |----------|------------------------|--------------------------|
     /// | *PART 3* | rpi initialise         |                          |
@@ -773,6 +1231,12 @@ impl<F: Field> PiCircuitConfig<F> {
     /// | ASSIGN   | coinbase               |                          |
     /// | CONSTS   | difficulty             |                          |
     /// |----------|------------------------|--------------------------|
+    /// |          | start_l1_queue_index   | <- q_l1_queue == 1       |
+    /// | *PART 5* | ...                    |                          |
+    /// | ASSIGN   | end_l1_queue_index     |                          |
+    /// | L1 QUEUE | ...                    |                          |
+    /// |          | rlc(skipped_bitmap)    | <- q_keccak == 1         |
+    /// |----------|------------------------|--------------------------|
     ///
     /// Where each one of the rows above, i.e. block\[0\].number, block\[0\].timestamp, ...,
     /// pi_hash_lo, coinbase, difficulty are assigned using the assign_field method.
@@ -782,6 +1246,14 @@ impl<F: Field> PiCircuitConfig<F> {
     /// of the public input hash, hence we need 16 rows to assign the pi_hash_lo field. In
     /// addition, for those rows represent the field, the `q_field_step` fixed column is enabled.
     ///
+    /// The end state root and withdraw root are also copied to the first row of PART 2, and looked
+    /// up in the mpt table as the storage of the message queue contract in the end state.
+    ///
+    /// PART 5 has a row for each queue index from start_l1_queue_index, in its own columns. The
+    /// popped msgs end at end_l1_queue_index, and their skipped bits are hashed to
+    /// skipped_l1_msgs_hash. The tx type and nonce of each tx are copied to the same rows, so
+    /// that the l1 msgs of the tx table are exactly the popped msgs that are not skipped.
+    ///
     /// Since we already know the maximum number of blocks and txs that we will assign in this
     /// layout, all the `q_*` columns are fixed. For blocks and txs, we pad the remaining layout
     /// with a padded field and mark it by the `is_rpi_padding` identifier.
@@ -805,7 +1277,7 @@ impl<F: Field> PiCircuitConfig<F> {
         debug_assert_eq!(offset, public_data.pi_bytes_start_offset());
 
         // 2. Assign public input bytes.
-        let (offset, pi_hash_rlc_cell, connections) = self.assign_pi_bytes(
+        let (offset, pi_hash_rlc_cell, connections, l1_queue_cells) = self.assign_pi_bytes(
             region,
             offset,
             public_data,
@@ -826,6 +1298,17 @@ impl<F: Field> PiCircuitConfig<F> {
             self.assign_constants(region, offset, public_data, block_value_cells, challenges)?;
         debug_assert_eq!(offset, public_data.constants_end_offset() + 1);
 
+        // 5. Assign the l1 msg queue range and its skipped bitmap.
+        let offset = self.assign_l1_queue(
+            region,
+            offset,
+            public_data,
+            &l1_queue_cells,
+            tx_value_cells,
+            challenges,
+        )?;
+        debug_assert_eq!(offset, public_data.l1_queue_end_offset());
+
         Ok((pi_hash_cells, connections))
     }
 
@@ -1031,7 +1514,16 @@ impl<F: Field> PiCircuitConfig<F> {
         tx_value_cells: &[AssignedCell<F, F>],
         data_hash_rlc_cell: &AssignedCell<F, F>,
         challenges: &Challenges<Value<F>>,
-    ) -> Result<(usize, AssignedCell<F, F>, Connections<F>), Error> {
+    ) -> Result<
+        (
+            usize,
+            AssignedCell<F, F>,
+            Connections<F>,
+            Vec<AssignedCell<F, F>>,
+        ),
+        Error,
+    > {
+        let withdraw_root_offset = offset;
         let (mut offset, mut rpi_rlc_acc, mut rpi_length) = self.assign_rlc_init(region, offset)?;
 
         // Enable RLC accumulator consistency check throughout the above rows.
@@ -1077,15 +1569,8 @@ impl<F: Field> PiCircuitConfig<F> {
                 tx_value_cells[tx_id * TX_LEN + CHAIN_ID_OFFSET_IN_TX - 1].cell(),
             )?;
         }
-        // connections to be done with other sub-circuits.
-        let connections = Connections {
-            start_state_root: rpi_cells[1].clone(),
-            end_state_root: rpi_cells[2].clone(),
-            withdraw_root: rpi_cells[3].clone(),
-        };
-
         // Assign data_hash
-        (offset, _, _, cells) = self.assign_field(
+        (offset, rpi_rlc_acc, rpi_length, cells) = self.assign_field(
             region,
             offset,
             &public_data.get_data_hash().to_fixed_bytes(),
@@ -1096,12 +1581,45 @@ impl<F: Field> PiCircuitConfig<F> {
             challenges,
         )?;
         let data_hash_cell = cells[RPI_CELL_IDX].clone();
-        let pi_bytes_rlc = cells[RPI_RLC_ACC_CELL_IDX].clone();
-        let pi_bytes_length = cells[RPI_LENGTH_ACC_CELL_IDX].clone();
 
         // Copy data_hash value we collected from assigning data bytes.
         region.constrain_equal(data_hash_rlc_cell.cell(), data_hash_cell.cell())?;
 
+        // Assign [start_l1_queue_index, end_l1_queue_index, skipped_l1_msgs_hash].
+        let l1_queue_range = &public_data.l1_queue_range;
+        let mut l1_queue_cells = vec![];
+        for value_be_bytes in [
+            l1_queue_range.start_queue_index.to_be_bytes().to_vec(),
+            l1_queue_range.end_queue_index.to_be_bytes().to_vec(),
+            l1_queue_range
+                .skipped_bitmap_hash()
+                .to_fixed_bytes()
+                .to_vec(),
+        ] {
+            (offset, rpi_rlc_acc, rpi_length, cells) = self.assign_field(
+                region,
+                offset,
+                &value_be_bytes,
+                RpiFieldType::DefaultType,
+                false, // no padding in this case
+                rpi_rlc_acc,
+                rpi_length,
+                challenges,
+            )?;
+            l1_queue_cells.push(cells[RPI_CELL_IDX].clone());
+        }
+        let pi_bytes_rlc = cells[RPI_RLC_ACC_CELL_IDX].clone();
+
+        // connections to be done with other sub-circuits.
+        let connections = Connections {
+            start_state_root: rpi_cells[1].clone(),
+            end_state_root: rpi_cells[2].clone(),
+            withdraw_root: rpi_cells[3].clone(),
+            start_l1_queue_index: l1_queue_cells[0].clone(),
+        };
+        self.assign_withdraw_root_lookup(region, withdraw_root_offset, public_data, &connections)?;
+        let pi_bytes_length = cells[RPI_LENGTH_ACC_CELL_IDX].clone();
+
         // Assign row for validating lookup to check:
         // pi_hash == keccak256(rlc(pi_bytes))
         pi_bytes_rlc.copy_advice(
@@ -1125,7 +1643,7 @@ impl<F: Field> PiCircuitConfig<F> {
         };
         self.q_keccak.enable(region, offset)?;
 
-        Ok((offset + 1, pi_hash_rlc_cell, connections))
+        Ok((offset + 1, pi_hash_rlc_cell, connections, l1_queue_cells))
     }
 
     /// Assign the (hi, lo) decomposition of pi_hash.
@@ -1231,6 +1749,262 @@ impl<F: Field> PiCircuitConfig<F> {
         Ok(offset)
     }
 
+    /// Assign the l1 msg queue range popped by the chunk, i.e. the rows from start_l1_queue_index
+    /// to end_l1_queue_index, and the skipped bitmap of which keccak256 is skipped_l1_msgs_hash.
+    fn assign_l1_queue(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        public_data: &PublicData,
+        l1_queue_cells: &[AssignedCell<F, F>],
+        tx_value_cells: &[AssignedCell<F, F>],
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<usize, Error> {
+        let l1_queue_range = &public_data.l1_queue_range;
+        let num_popped = l1_queue_range.num_popped() as usize;
+        let capacity = l1_queue_capacity(public_data.max_l1_msgs_popped);
+        if num_popped > capacity {
+            log::error!("{num_popped} l1 msgs popped, at most {capacity} are supported");
+            return Err(Error::Synthesis);
+        }
+        let num_bitmap_words = l1_queue_range.skipped_bitmap.len();
+        // tx.nonce alias for queue_index for l1 msg tx
+        let l1_msg_tx_ids = public_data
+            .transactions
+            .iter()
+            .filter(|tx| tx.tx_type.is_l1_msg())
+            .map(|tx| (tx.nonce, tx.id))
+            .collect::<BTreeMap<_, _>>();
+
+        let mut byte_acc = 0;
+        let mut byte_weight = Value::known(F::one());
+        let mut word_rlc = Value::known(F::zero());
+        let mut bitmap_rlc = Value::known(F::zero());
+        let mut bitmap_len = 0;
+        let mut bitmap_cells = None;
+        for i in 0..=capacity {
+            let row_offset = offset + i;
+            let queue_index = l1_queue_range.start_queue_index + i as u64;
+            let is_popped = i < num_popped;
+            let is_skipped = is_popped && l1_queue_range.is_skipped(i);
+            let is_included = is_popped && !is_skipped;
+            let tx_id = match l1_msg_tx_ids.get(&queue_index) {
+                Some(&tx_id) if is_included => tx_id,
+                _ => 0,
+            };
+            let word_in_use = i / 256 < num_bitmap_words;
+            let bit_weight = 1u64 << (i % 8);
+            let (is_byte_end, is_word_start) = (i % 8 == 7, i % 256 == 0);
+
+            if i > 0 && is_word_start {
+                // the previous word is complete, and byte_weight * r = r^32
+                if (i - 1) / 256 < num_bitmap_words {
+                    bitmap_rlc = bitmap_rlc * byte_weight * challenges.keccak_input() + word_rlc;
+                    bitmap_len += N_BYTES_WORD as u64;
+                }
+                byte_weight = Value::known(F::one());
+                word_rlc = Value::known(F::zero());
+            } else if i % 8 == 0 && i > 0 {
+                byte_weight = byte_weight * challenges.keccak_input();
+            }
+            if i % 8 == 0 {
+                byte_acc = 0;
+            }
+            byte_acc += is_skipped as u64 * bit_weight;
+            if is_byte_end {
+                word_rlc = word_rlc + byte_weight * Value::known(F::from(byte_acc));
+            }
+
+            if i < capacity {
+                region.assign_fixed(
+                    || "q_l1_queue",
+                    self.q_l1_queue,
+                    row_offset,
+                    || Value::known(F::one()),
+                )?;
+            }
+            for (col_anno, col, col_val) in [
+                ("q_l1_queue_first", self.q_l1_queue_first, i == 0),
+                ("q_l1_byte_end", self.q_l1_byte_end, is_byte_end),
+                ("q_l1_word_start", self.q_l1_word_start, is_word_start),
+            ] {
+                region.assign_fixed(
+                    || col_anno,
+                    col,
+                    row_offset,
+                    || Value::known(F::from(col_val as u64)),
+                )?;
+            }
+            region.assign_fixed(
+                || "l1_bit_weight",
+                self.l1_bit_weight,
+                row_offset,
+                || Value::known(F::from(bit_weight)),
+            )?;
+
+            if i == 0 {
+                l1_queue_cells[0].copy_advice(
+                    || "start_l1_queue_index",
+                    region,
+                    self.l1_queue_index,
+                    row_offset,
+                )?;
+                l1_queue_cells[1].copy_advice(
+                    || "end_l1_queue_index",
+                    region,
+                    self.l1_end_queue_index,
+                    row_offset,
+                )?;
+            } else {
+                for (col_anno, col, col_val) in [
+                    ("l1_queue_index", self.l1_queue_index, queue_index),
+                    (
+                        "l1_end_queue_index",
+                        self.l1_end_queue_index,
+                        l1_queue_range.end_queue_index,
+                    ),
+                ] {
+                    region.assign_advice(
+                        || col_anno,
+                        col,
+                        row_offset,
+                        || Value::known(F::from(col_val)),
+                    )?;
+                }
+            }
+            if i == capacity {
+                region.assign_advice_from_constant(
+                    || "l1_is_popped",
+                    self.l1_is_popped,
+                    row_offset,
+                    F::zero(),
+                )?;
+            } else {
+                region.assign_advice(
+                    || "l1_is_popped",
+                    self.l1_is_popped,
+                    row_offset,
+                    || Value::known(F::from(is_popped as u64)),
+                )?;
+            }
+            for (col_anno, col, col_val) in [
+                ("l1_is_skipped", self.l1_is_skipped, is_skipped as u64),
+                ("l1_is_included", self.l1_is_included, is_included as u64),
+                ("l1_tx_id", self.l1_tx_id, tx_id as u64),
+                ("l1_word_in_use", self.l1_word_in_use, word_in_use as u64),
+                ("l1_byte_acc", self.l1_byte_acc, byte_acc),
+            ] {
+                region.assign_advice(
+                    || col_anno,
+                    col,
+                    row_offset,
+                    || Value::known(F::from(col_val)),
+                )?;
+            }
+            for (col_anno, col, col_val) in [
+                ("l1_byte_weight", self.l1_byte_weight, byte_weight),
+                ("l1_word_rlc", self.l1_word_rlc, word_rlc),
+            ] {
+                region.assign_advice(|| col_anno, col, row_offset, || col_val)?;
+            }
+            if i == 0 {
+                region.assign_advice_from_constant(
+                    || "l1_bitmap_rlc",
+                    self.l1_bitmap_rlc,
+                    row_offset,
+                    F::zero(),
+                )?;
+                region.assign_advice_from_constant(
+                    || "l1_bitmap_len",
+                    self.l1_bitmap_len,
+                    row_offset,
+                    F::zero(),
+                )?;
+            } else {
+                let bitmap_rlc_cell = region.assign_advice(
+                    || "l1_bitmap_rlc",
+                    self.l1_bitmap_rlc,
+                    row_offset,
+                    || bitmap_rlc,
+                )?;
+                let bitmap_len_cell = region.assign_advice(
+                    || "l1_bitmap_len",
+                    self.l1_bitmap_len,
+                    row_offset,
+                    || Value::known(F::from(bitmap_len)),
+                )?;
+                bitmap_cells = Some((bitmap_rlc_cell, bitmap_len_cell));
+            }
+        }
+
+        // Assign row for validating lookup to check:
+        // skipped_l1_msgs_hash == keccak256(rlc(skipped_bitmap))
+        let keccak_offset = offset + capacity;
+        let (bitmap_rlc_cell, bitmap_len_cell) = bitmap_cells.unwrap();
+        bitmap_rlc_cell.copy_advice(
+            || "skipped_bitmap_rlc in the rpi col",
+            region,
+            self.raw_public_inputs,
+            keccak_offset,
+        )?;
+        bitmap_len_cell.copy_advice(
+            || "skipped_bitmap_length in the rpi_length_acc col",
+            region,
+            self.rpi_length_acc,
+            keccak_offset,
+        )?;
+        l1_queue_cells[2].copy_advice(
+            || "skipped_l1_msgs_hash_rlc",
+            region,
+            self.rpi_rlc_acc,
+            keccak_offset,
+        )?;
+        self.q_keccak.enable(region, keccak_offset)?;
+
+        // Copy the tx type and nonce of each tx from the tx table.
+        let tx_type_is_l1_msg = IsEqualChip::construct(self.l1_tx_type_is_l1_msg.clone());
+        for tx_id in 0..public_data.max_txs {
+            let row_offset = offset + tx_id;
+            region.assign_fixed(
+                || "q_l1_tx",
+                self.q_l1_tx,
+                row_offset,
+                || Value::known(F::one()),
+            )?;
+            let tx_type_cell = tx_value_cells[tx_id * TX_LEN + TX_TYPE_OFFSET - 1].copy_advice(
+                || "tx_type",
+                region,
+                self.l1_tx_type,
+                row_offset,
+            )?;
+            tx_value_cells[tx_id * TX_LEN + NONCE_OFFSET - 1].copy_advice(
+                || "tx_nonce",
+                region,
+                self.l1_tx_nonce,
+                row_offset,
+            )?;
+            let l1_msg_tx_type = F::from(TxType::L1Msg as u64);
+            region.assign_advice(
+                || "l1_tx_is_l1_msg",
+                self.l1_tx_is_l1_msg,
+                row_offset,
+                || {
+                    tx_type_cell
+                        .value()
+                        .map(|&tx_type| F::from((tx_type == l1_msg_tx_type) as u64))
+                },
+            )?;
+            tx_type_is_l1_msg.assign(
+                region,
+                row_offset,
+                tx_type_cell.value().copied(),
+                Value::known(l1_msg_tx_type),
+            )?;
+        }
+
+        Ok(offset + (capacity + 1).max(public_data.max_txs))
+    }
+
     /// Initialise the RLC computation at the row with the given offset. Returns the offset at the
     /// next row.
     fn assign_rlc_init(
@@ -1253,6 +2027,42 @@ impl<F: Field> PiCircuitConfig<F> {
         Ok((offset + 1, Value::known(F::zero()), Value::known(F::zero())))
     }
 
+    /// Copy the end state root and the withdraw root to the row with the given offset, where they
+    /// are looked up in the mpt table as the message queue storage after the chunk.
+    fn assign_withdraw_root_lookup(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        public_data: &PublicData,
+        connections: &Connections<F>,
+    ) -> Result<(), Error> {
+        self.q_withdraw_root.enable(region, offset)?;
+        connections.end_state_root.copy_advice(
+            || "withdraw root lookup: state_root",
+            region,
+            self.withdraw_root_lookup[0],
+            offset,
+        )?;
+        connections.withdraw_root.copy_advice(
+            || "withdraw root lookup: withdraw_root",
+            region,
+            self.withdraw_root_lookup[1],
+            offset,
+        )?;
+        let proof_type = if public_data.withdraw_trie_root.is_zero() {
+            MPTProofType::StorageDoesNotExist
+        } else {
+            MPTProofType::StorageChanged
+        };
+        region.assign_advice(
+            || "withdraw root lookup: proof_type",
+            self.withdraw_root_proof_type,
+            offset,
+            || Value::known(F::from(proof_type as u64)),
+        )?;
+        Ok(())
+    }
+
     /// Assign a variable length field (in its big-endian byte representation to the PI circuit.
     #[allow(clippy::too_many_arguments)]
     #[allow(clippy::type_complexity)]
@@ -1619,12 +2429,30 @@ pub struct PiCircuit<F: Field> {
     tx_value_cells: RefCell<Option<Vec<AssignedCell<F, F>>>>,
 }
 
+/// The l1 msg queue range popped by the txs of `block`, of at most `max_l1_msgs_popped` msgs.
+pub(crate) fn l1_queue_range<F: Field>(
+    block: &Block<F>,
+    max_l1_msgs_popped: usize,
+) -> Result<L1QueueRange, eth_types::Error> {
+    L1QueueRange::new(
+        block.start_l1_queue_index,
+        block
+            .txs
+            .iter()
+            .filter(|tx| tx.tx_type.is_l1_msg())
+            // tx.nonce alias for queue_index for l1 msg tx
+            .map(|tx| tx.nonce),
+        max_l1_msgs_popped as u64,
+    )
+}
+
 impl<F: Field> PiCircuit<F> {
     /// Creates a new PiCircuit
     pub fn new(
         max_txs: usize,
         max_calldata: usize,
         max_inner_blocks: usize,
+        max_l1_msgs_popped: usize,
         block: &Block<F>,
     ) -> Self {
         let chain_id = block.chain_id;
@@ -1641,12 +2469,24 @@ impl<F: Field> PiCircuit<F> {
                 next_state_root
             );
         }
+        // rejected by block_convert. With an empty range the l1 msgs of the block are not popped,
+        // so the circuit is not satisfied.
+        let l1_queue_range = l1_queue_range(block, max_l1_msgs_popped).unwrap_or_else(|err| {
+            log::error!("invalid l1 msg queue range: {err}");
+            L1QueueRange {
+                start_queue_index: block.start_l1_queue_index,
+                end_queue_index: block.start_l1_queue_index,
+                skipped_bitmap: vec![],
+            }
+        });
         let public_data = PublicData {
             max_txs,
             max_calldata,
             max_inner_blocks,
+            max_l1_msgs_popped,
             chain_id,
             start_l1_queue_index: block.start_l1_queue_index,
+            l1_queue_range,
             transactions: block.txs.clone(),
             block_ctxs: block.context.clone(),
             prev_state_root: H256(block.mpt_updates.old_root().to_be_bytes()),
@@ -1678,6 +2518,7 @@ impl<F: Field> PiCircuit<F> {
         layouter: &mut impl Layouter<F>,
         state_roots: Option<&StateCircuitExports<Assigned<F>>>,
         withdraw_roots: Option<&EvmCircuitExports<Assigned<F>>>,
+        l1_queue: Option<&TxCircuitExports<Assigned<F>>>,
     ) -> Result<(), Error> {
         let local_conn = self
             .connections
@@ -1721,6 +2562,20 @@ impl<F: Field> PiCircuit<F> {
                     log::warn!("withdraw roots are not set, skip connection with evm circuit");
                 }
 
+                if let Some(l1_queue) = l1_queue {
+                    log::debug!(
+                        "constrain_equal of start l1 queue index: {:?} <-> {:?}",
+                        &local_conn.start_l1_queue_index,
+                        &l1_queue.start_l1_queue_index
+                    );
+                    region.constrain_equal(
+                        local_conn.start_l1_queue_index.cell(),
+                        l1_queue.start_l1_queue_index.0,
+                    )?;
+                } else {
+                    log::warn!("start l1 queue index is not set, skip connection with tx circuit");
+                }
+
                 Ok(())
             },
         )
@@ -1735,6 +2590,7 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
             block.circuits_params.max_txs,
             block.circuits_params.max_calldata,
             block.circuits_params.max_inner_blocks,
+            block.circuits_params.max_l1_msgs_popped,
             block,
         )
     }
@@ -1751,12 +2607,15 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
             + 1 // for pi bytes start row
             + N_BYTES_U64 // chain_id
             + 4 * KECCAK_DIGEST_SIZE // state_roots & data hash
+            + 2 * N_BYTES_U64 // start & end l1 queue index
+            + KECCAK_DIGEST_SIZE // skipped l1 msgs hash
             + 1 // for pi hash row
             + 1 // for pi hash bytes start row
             + KECCAK_DIGEST_SIZE // pi hash bytes
             + 1 // for coinbase & difficulty start row
             + N_BYTES_ACCOUNT_ADDRESS
-            + N_BYTES_WORD;
+            + N_BYTES_WORD
+            + (l1_queue_capacity(block.circuits_params.max_l1_msgs_popped) + 1).max(max_txs); // for l1 msg queue rows
 
         (
             (tx_usage * block.circuits_params.max_vertical_circuit_rows as f32).ceil() as usize,
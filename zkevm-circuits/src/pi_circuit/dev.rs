@@ -1,4 +1,9 @@
 use super::*;
+use crate::witness::MptUpdates;
+
+/// Rows of the mpt table loaded by [`PiTestCircuit`], fixed so that its layout
+/// doesn't depend on the block.
+const MAX_MPT_ROWS: usize = 256;
 
 // We define the PiTestCircuit as a wrapper over PiCircuit extended to take the
 // generic const parameters MAX_TXS and MAX_CALLDATA.  This is necessary because
@@ -6,36 +11,42 @@ use super::*;
 // any circuit parameters, and the PiCircuit defines gates that use rotations
 // that depend on MAX_TXS and MAX_CALLDATA, so these two values are required
 // during the configuration.
-/// Test Circuit for PiCircuit
+/// Test Circuit for PiCircuit, with the mpt updates of the block loaded in the
+/// mpt table
 #[derive(Clone)]
 pub struct PiTestCircuit<
     F: Field,
     const MAX_TXS: usize,
     const MAX_CALLDATA: usize,
     const MAX_INNER_BLOCKS: usize,
->(pub PiCircuit<F>);
+>(pub PiCircuit<F>, pub MptUpdates);
 
 impl<F: Field, const MAX_TXS: usize, const MAX_CALLDATA: usize, const MAX_INNER_BLOCKS: usize>
     Default for PiTestCircuit<F, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS>
 {
     fn default() -> Self {
-        Self(PiCircuit::<F> {
-            public_data: PublicData {
-                max_txs: MAX_TXS,
-                max_calldata: MAX_CALLDATA,
-                max_inner_blocks: MAX_INNER_BLOCKS,
-                chain_id: 0,
-                start_l1_queue_index: 0,
-                transactions: vec![],
-                prev_state_root: H256::zero(),
-                next_state_root: H256::zero(),
-                withdraw_trie_root: H256::zero(),
-                block_ctxs: Default::default(),
+        Self(
+            PiCircuit::<F> {
+                public_data: PublicData {
+                    max_txs: MAX_TXS,
+                    max_calldata: MAX_CALLDATA,
+                    max_inner_blocks: MAX_INNER_BLOCKS,
+                    max_l1_msgs_popped: 0,
+                    chain_id: 0,
+                    start_l1_queue_index: 0,
+                    l1_queue_range: Default::default(),
+                    transactions: vec![],
+                    prev_state_root: H256::zero(),
+                    next_state_root: H256::zero(),
+                    withdraw_trie_root: H256::zero(),
+                    block_ctxs: Default::default(),
+                },
+                connections: Default::default(),
+                tx_value_cells: Default::default(),
+                _marker: PhantomData,
             },
-            connections: Default::default(),
-            tx_value_cells: Default::default(),
-            _marker: PhantomData,
-        })
+            MptUpdates::default(),
+        )
     }
 }
 
@@ -48,7 +59,7 @@ impl<F: Field, const MAX_TXS: usize, const MAX_CALLDATA: usize, const MAX_INNER_
         assert_eq!(block.circuits_params.max_txs, MAX_TXS);
         assert_eq!(block.circuits_params.max_calldata, MAX_CALLDATA);
 
-        Self(PiCircuit::new_from_block(block))
+        Self(PiCircuit::new_from_block(block), block.mpt_updates.clone())
     }
 
     /// Compute the public inputs for this circuit.
@@ -89,6 +100,7 @@ impl<F: Field, const MAX_TXS: usize, const MAX_CALLDATA: usize, const MAX_INNER_
         let block_table = BlockTable::construct(meta);
         let tx_table = TxTable::construct(meta);
         let keccak_table = KeccakTable::construct(meta);
+        let mpt_table = MptTable::construct(meta);
         let challenges = Challenges::construct(meta);
         let challenge_exprs = challenges.exprs(meta);
         (
@@ -98,6 +110,7 @@ impl<F: Field, const MAX_TXS: usize, const MAX_CALLDATA: usize, const MAX_INNER_
                     block_table,
                     keccak_table,
                     tx_table,
+                    mpt_table,
                     challenges: challenge_exprs,
                 },
             ),
@@ -127,9 +140,18 @@ impl<F: Field, const MAX_TXS: usize, const MAX_CALLDATA: usize, const MAX_INNER_
             .0
             .public_data
             .pi_bytes(self.0.public_data.get_data_hash());
+        let skipped_bitmap_bytes = self.0.public_data.l1_queue_range.skipped_bitmap_bytes();
+        config.keccak_table.dev_load(
+            &mut layouter,
+            vec![&data_bytes, &pi_bytes, &skipped_bitmap_bytes],
+            &challenges,
+        )?;
+
+        // assign the mpt updates of the block, the last one of which is the withdraw root
+        // storage at the end state root
         config
-            .keccak_table
-            .dev_load(&mut layouter, vec![&data_bytes, &pi_bytes], &challenges)?;
+            .mpt_table
+            .load(&mut layouter, &self.1, MAX_MPT_ROWS, challenges.evm_word())?;
 
         self.0.import_tx_values(tx_value_cells);
         self.0.synthesize_sub(&config, &challenges, &mut layouter)?;
@@ -21,3 +21,6 @@ pub(super) const BASE_FEE_OFFSET: usize = 5;
 pub(super) const CHAIN_ID_OFFSET: usize = 6;
 // pub(super) const CUM_NUM_TXS_OFFSET: usize = 8;
 pub(super) const NUM_ALL_TXS_OFFSET: usize = 9;
+
+/// Number of l1 msgs of a word of the skipped bitmap
+pub(super) const N_L1_MSGS_PER_WORD: usize = 256;
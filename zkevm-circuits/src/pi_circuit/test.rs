@@ -16,8 +16,14 @@ use std::env::set_var;
 
 use crate::{super_circuit::test::block_2tx, witness::block_convert};
 use bus_mapping::{circuit_input_builder::CircuitsParams, mock::BlockData};
-use eth_types::{bytecode, geth_types::GethData};
+use eth_types::{
+    bytecode,
+    geth_types::{Account, GethData, TxType},
+};
 use mock::{test_ctx::helpers::account_0_code_account_1_no_code, TestContext};
+use std::collections::HashMap;
+
+const MAX_L1_MSGS_POPPED: usize = 256;
 
 // #[test]
 // fn pi_circuit_unusable_rows() {
@@ -34,12 +40,16 @@ fn run<F: Field, const MAX_TXS: usize, const MAX_CALLDATA: usize, const MAX_INNE
     k: u32,
     block: Block<F>,
 ) -> Result<(), Vec<VerifyFailure>> {
-    let circuit = PiTestCircuit::<F, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS>(PiCircuit::new(
-        MAX_TXS,
-        MAX_CALLDATA,
-        MAX_INNER_BLOCKS,
-        &block,
-    ));
+    let circuit = PiTestCircuit::<F, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS>(
+        PiCircuit::new(
+            MAX_TXS,
+            MAX_CALLDATA,
+            MAX_INNER_BLOCKS,
+            MAX_L1_MSGS_POPPED,
+            &block,
+        ),
+        block.mpt_updates.clone(),
+    );
     let public_inputs = circuit.0.instance();
 
     let prover = match MockProver::run(k, &circuit, public_inputs) {
@@ -53,7 +63,25 @@ fn run<F: Field, const MAX_TXS: usize, const MAX_CALLDATA: usize, const MAX_INNE
 fn block_1tx() -> Block<Fr> {
     use crate::super_circuit::test::block_1tx;
 
-    let block = block_1tx();
+    witness_block(block_1tx())
+}
+
+const WITHDRAW_ROOT: Word = Word([1, 2, 3, 4]);
+
+fn block_1tx_with_withdraw_root() -> Block<Fr> {
+    use crate::super_circuit::test::block_1tx;
+
+    let mut block = block_1tx();
+    block.accounts.push(Account {
+        address: *MESSAGE_QUEUE,
+        nonce: Word::one(),
+        storage: HashMap::from([(WITHDRAW_TRIE_ROOT_SLOT, WITHDRAW_ROOT)]),
+        ..Default::default()
+    });
+    witness_block(block)
+}
+
+fn witness_block(block: GethData) -> Block<Fr> {
     let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
     builder
         .handle_block(&block.eth_block, &block.geth_traces)
@@ -77,6 +105,15 @@ fn block_2txs() -> Block<Fr> {
     block_convert(&builder.block, &builder.code_db).unwrap()
 }
 
+fn block_2txs_with_l1_msg() -> Block<Fr> {
+    // the first tx is the l1 msg with queue index 12, the msgs 10 and 11 before it are skipped
+    let mut block = block_2txs();
+    block.start_l1_queue_index = 10;
+    block.txs[0].tx_type = TxType::L1Msg;
+    block.txs[0].nonce = 12;
+    block
+}
+
 fn empty_block() -> Block<Fr> {
     Block::<Fr> {
         txs: vec![],
@@ -85,6 +122,13 @@ fn empty_block() -> Block<Fr> {
     }
 }
 
+fn set_block_constants() {
+    let mut difficulty_be_bytes = [0u8; 32];
+    MOCK_DIFFICULTY.to_big_endian(&mut difficulty_be_bytes);
+    set_var("DIFFICULTY", hex::encode(difficulty_be_bytes));
+    set_var("COINBASE", "0x0000000000000000000000000000000000000000");
+}
+
 #[cfg(feature = "scroll")]
 #[test]
 fn serial_test_simple_pi() {
@@ -92,10 +136,7 @@ fn serial_test_simple_pi() {
     const MAX_CALLDATA: usize = 20;
     const MAX_INNER_BLOCKS: usize = 4;
 
-    let mut difficulty_be_bytes = [0u8; 32];
-    MOCK_DIFFICULTY.to_big_endian(&mut difficulty_be_bytes);
-    set_var("DIFFICULTY", hex::encode(difficulty_be_bytes));
-    set_var("COINBASE", "0x0000000000000000000000000000000000000000");
+    set_block_constants();
 
     let block = block_1tx();
 
@@ -114,21 +155,29 @@ fn run_size_check<
 >(
     blocks: [Block<F>; 2],
 ) {
-    let circuit = PiTestCircuit::<F, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS>(PiCircuit::new(
-        MAX_TXS,
-        MAX_CALLDATA,
-        MAX_INNER_BLOCKS,
-        &blocks[0],
-    ));
+    let circuit = PiTestCircuit::<F, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS>(
+        PiCircuit::new(
+            MAX_TXS,
+            MAX_CALLDATA,
+            MAX_INNER_BLOCKS,
+            MAX_L1_MSGS_POPPED,
+            &blocks[0],
+        ),
+        blocks[0].mpt_updates.clone(),
+    );
     let public_inputs = circuit.0.instance();
     let prover1 = MockProver::run(20, &circuit, public_inputs).unwrap();
 
-    let circuit = PiTestCircuit::<F, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS>(PiCircuit::new(
-        MAX_TXS,
-        MAX_CALLDATA,
-        MAX_INNER_BLOCKS,
-        &blocks[1],
-    ));
+    let circuit = PiTestCircuit::<F, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS>(
+        PiCircuit::new(
+            MAX_TXS,
+            MAX_CALLDATA,
+            MAX_INNER_BLOCKS,
+            MAX_L1_MSGS_POPPED,
+            &blocks[1],
+        ),
+        blocks[1].mpt_updates.clone(),
+    );
     let public_inputs = circuit.0.instance();
     let prover2 = MockProver::run(20, &circuit, public_inputs).unwrap();
 
@@ -150,3 +199,201 @@ fn variadic_size_check() {
     run_size_check::<Fr, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS>([block_0, block_2.clone()]);
     run_size_check::<Fr, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS>([block_1, block_2]);
 }
+
+fn run_l1_queue_range<
+    F: Field,
+    const MAX_TXS: usize,
+    const MAX_CALLDATA: usize,
+    const MAX_INNER_BLOCKS: usize,
+>(
+    block: Block<F>,
+    l1_queue_range: Option<L1QueueRange>,
+) -> Result<(), Vec<VerifyFailure>> {
+    run_with_public_data::<F, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS>(block, |public_data| {
+        if let Some(l1_queue_range) = l1_queue_range {
+            public_data.l1_queue_range = l1_queue_range;
+        }
+    })
+}
+
+fn run_with_public_data<
+    F: Field,
+    const MAX_TXS: usize,
+    const MAX_CALLDATA: usize,
+    const MAX_INNER_BLOCKS: usize,
+>(
+    block: Block<F>,
+    modify: impl FnOnce(&mut PublicData),
+) -> Result<(), Vec<VerifyFailure>> {
+    let mut circuit = PiTestCircuit::<F, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS>(
+        PiCircuit::new(
+            MAX_TXS,
+            MAX_CALLDATA,
+            MAX_INNER_BLOCKS,
+            MAX_L1_MSGS_POPPED,
+            &block,
+        ),
+        block.mpt_updates.clone(),
+    );
+    // the public input hash commits to the modified data, so only the constraints on the data
+    // itself can reject it
+    modify(&mut circuit.0.public_data);
+    let public_inputs = circuit.0.instance();
+
+    let prover = match MockProver::run(16, &circuit, public_inputs) {
+        Ok(prover) => prover,
+        Err(e) => panic!("{e:#?}"),
+    };
+    prover.verify()
+}
+
+#[cfg(feature = "scroll")]
+#[test]
+fn serial_test_pi_l1_queue_range() {
+    const MAX_TXS: usize = 4;
+    const MAX_CALLDATA: usize = 20;
+    const MAX_INNER_BLOCKS: usize = 4;
+
+    set_block_constants();
+
+    let block = block_2txs_with_l1_msg();
+    let l1_queue_range = PiCircuit::new(
+        MAX_TXS,
+        MAX_CALLDATA,
+        MAX_INNER_BLOCKS,
+        MAX_L1_MSGS_POPPED,
+        &block,
+    )
+    .public_data
+    .l1_queue_range;
+    assert_eq!(l1_queue_range.end_queue_index, 13);
+    assert_eq!(l1_queue_range.skipped_bitmap, vec![Word::from(0b011)]);
+
+    assert_eq!(
+        run_l1_queue_range::<Fr, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS>(block, None),
+        Ok(())
+    );
+}
+
+#[cfg(feature = "scroll")]
+#[test]
+fn serial_test_pi_l1_queue_range_tampered() {
+    const MAX_TXS: usize = 4;
+    const MAX_CALLDATA: usize = 20;
+    const MAX_INNER_BLOCKS: usize = 4;
+
+    set_block_constants();
+
+    let range = |end_queue_index: u64, skipped_bitmap: u64| L1QueueRange {
+        start_queue_index: 10,
+        end_queue_index,
+        skipped_bitmap: vec![Word::from(skipped_bitmap)],
+    };
+    for (desc, l1_queue_range) in [
+        ("l1 msg left out of the range", range(12, 0b11)),
+        ("l1 msg marked as skipped", range(13, 0b111)),
+        ("skipped msg marked as included", range(13, 0b01)),
+        (
+            "no msg popped",
+            L1QueueRange {
+                start_queue_index: 10,
+                end_queue_index: 10,
+                skipped_bitmap: vec![],
+            },
+        ),
+    ] {
+        assert!(
+            run_l1_queue_range::<Fr, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS>(
+                block_2txs_with_l1_msg(),
+                Some(l1_queue_range),
+            )
+            .is_err(),
+            "{desc}"
+        );
+    }
+}
+
+#[cfg(feature = "scroll")]
+#[test]
+fn serial_test_pi_too_many_l1_msgs_popped() {
+    const MAX_TXS: usize = 4;
+    const MAX_CALLDATA: usize = 20;
+    const MAX_INNER_BLOCKS: usize = 4;
+
+    set_block_constants();
+
+    // the block pops 3 l1 msgs, so its range is rejected and left empty
+    let block = block_2txs_with_l1_msg();
+    let circuit = PiTestCircuit::<Fr, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS>(
+        PiCircuit::new(MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS, 2, &block),
+        block.mpt_updates.clone(),
+    );
+    let public_inputs = circuit.0.instance();
+    let prover = MockProver::run(16, &circuit, public_inputs).unwrap();
+    assert!(prover.verify().is_err());
+
+    // more msgs popped than the l1 queue rows is a synthesis error instead of a panic
+    let mut circuit = PiTestCircuit::<Fr, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS>(
+        PiCircuit::new(
+            MAX_TXS,
+            MAX_CALLDATA,
+            MAX_INNER_BLOCKS,
+            MAX_L1_MSGS_POPPED,
+            &block,
+        ),
+        block.mpt_updates.clone(),
+    );
+    circuit.0.public_data.l1_queue_range.end_queue_index += MAX_L1_MSGS_POPPED as u64;
+    let public_inputs = circuit.0.instance();
+    assert!(MockProver::run(16, &circuit, public_inputs).is_err());
+}
+
+#[cfg(feature = "scroll")]
+#[test]
+fn serial_test_pi_withdraw_root() {
+    const MAX_TXS: usize = 4;
+    const MAX_CALLDATA: usize = 20;
+    const MAX_INNER_BLOCKS: usize = 4;
+
+    set_block_constants();
+
+    let block = block_1tx_with_withdraw_root();
+    assert_eq!(block.withdraw_root, WITHDRAW_ROOT);
+    assert_eq!(
+        run_with_public_data::<Fr, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS>(block, |_| {}),
+        Ok(())
+    );
+}
+
+#[cfg(feature = "scroll")]
+#[test]
+fn serial_test_pi_withdraw_root_tampered() {
+    const MAX_TXS: usize = 4;
+    const MAX_CALLDATA: usize = 20;
+    const MAX_INNER_BLOCKS: usize = 4;
+
+    set_block_constants();
+
+    let tampered_withdraw_root = |public_data: &mut PublicData| {
+        public_data.withdraw_trie_root = H256::from_low_u64_be(5);
+    };
+    let tampered_state_root = |public_data: &mut PublicData| {
+        public_data.next_state_root = H256::from_low_u64_be(5);
+    };
+    assert!(
+        run_with_public_data::<Fr, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS>(
+            block_1tx_with_withdraw_root(),
+            tampered_withdraw_root,
+        )
+        .is_err(),
+        "withdraw root other than the message queue storage"
+    );
+    assert!(
+        run_with_public_data::<Fr, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS>(
+            block_1tx_with_withdraw_root(),
+            tampered_state_root,
+        )
+        .is_err(),
+        "withdraw root looked up at another state root"
+    );
+}
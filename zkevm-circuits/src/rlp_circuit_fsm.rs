@@ -17,7 +17,10 @@ use crate::{
         Block, DataTable, Format, RlpFsmWitnessGen, RlpFsmWitnessRow, RlpTag, RomTableRow, State,
         State::{DecodeTagStart, End},
         Tag,
-        Tag::{AccessListAddress, AccessListStorageKey, BeginObject, EndObject, EndVector, TxType},
+        Tag::{
+            AccessListAddress, AccessListStorageKey, BeginObject, BlobVersionedHash, EndObject,
+            EndVector, TxType,
+        },
         Transaction,
     },
 };
@@ -307,7 +310,8 @@ pub struct RlpCircuitConfig<F> {
     is_same_rlp_instance: Column<Advice>,
 
     /// Boolean to reduce the circuit's degree
-    /// Indicates the start of another new access list item
+    /// Indicates the start of another new access list item, or of another
+    /// blob versioned hash whose index is also kept in access_list_idx
     is_new_access_list_address: Column<Advice>,
     /// Boolean to reduce the circuit's degree
     /// Indicates the start of another new storage key for an access list address
@@ -489,6 +493,7 @@ impl<F: Field> RlpCircuitConfig<F> {
         is_tag!(is_tag_end_vector, EndVector);
         is_tag!(is_access_list_address, AccessListAddress);
         is_tag!(is_access_list_storage_key, AccessListStorageKey);
+        is_tag!(is_blob_versioned_hash, BlobVersionedHash);
 
         //////////////////////////////////////////////////////////
         //////////// data table checks. //////////////////////////
@@ -1529,7 +1534,7 @@ impl<F: Field> RlpCircuitConfig<F> {
             cb.require_equal(
                 "is_new_access_list_address",
                 meta.query_advice(is_new_access_list_address, Rotation::cur()),
-                is_access_list_address(meta),
+                is_access_list_address(meta) + is_blob_versioned_hash(meta),
             );
             cb.require_equal(
                 "is_new_access_list_storage_key",
@@ -2491,7 +2496,8 @@ impl<F: Field> RlpCircuitConfig<F> {
         )?;
 
         let is_new_access_list_address = witness.state_machine.state == DecodeTagStart
-            && witness.state_machine.tag == AccessListAddress;
+            && (witness.state_machine.tag == AccessListAddress
+                || witness.state_machine.tag == BlobVersionedHash);
         region.assign_advice(
             || "is_new_access_list_address",
             self.is_new_access_list_address,
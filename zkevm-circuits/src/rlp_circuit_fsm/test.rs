@@ -192,3 +192,60 @@ fn test_eip2930_tx() {
 
     mock_prover.assert_satisfied_par();
 }
+
+#[test]
+fn test_eip4844_tx() {
+    use eth_types::{
+        geth_types::{
+            get_rlp_signed, get_rlp_unsigned, BLOB_VERSIONED_HASHES_KEY, MAX_FEE_PER_BLOB_GAS_KEY,
+        },
+        H256, U64,
+    };
+
+    let mut eth_tx = EthTransaction {
+        transaction_type: Some(U64::from(3)),
+        chain_id: Some(MOCK_CHAIN_ID.into()),
+        nonce: word!("0x7f"),
+        max_priority_fee_per_gas: Some(word!("0x3b9aca00")),
+        max_fee_per_gas: Some(word!("0x4a817c800")),
+        gas: word!("0x77320"),
+        to: Some(Address::random()),
+        value: eth(1),
+        input: vec![0x12, 0x34].into(),
+        access_list: Some(Default::default()),
+        v: U64::from(1),
+        r: word!("0x634f6d4b3b4fc658c2c26c1ba0966bd39d7e993b815390f1e778af9cf28d2c22"),
+        s: word!("0x5410b97e41240ea25eb6250e1af7554cda8991bc4159228c43cfb240503d9870"),
+        ..Default::default()
+    };
+    eth_tx.other.insert(
+        MAX_FEE_PER_BLOB_GAS_KEY.to_string(),
+        serde_json::to_value(word!("0x3b9aca00")).unwrap(),
+    );
+    eth_tx.other.insert(
+        BLOB_VERSIONED_HASHES_KEY.to_string(),
+        serde_json::to_value(vec![H256::random(), H256::random()]).unwrap(),
+    );
+
+    let tx = Transaction::new_from_rlp_bytes(
+        1,
+        TxType::Eip4844,
+        get_rlp_signed(&eth_tx),
+        get_rlp_unsigned(&eth_tx),
+    );
+    let rlp_circuit = RlpCircuit::<Fr, Transaction> {
+        txs: vec![tx],
+        max_txs: 10,
+        size: 1000,
+        _marker: Default::default(),
+    };
+
+    let mock_prover = MockProver::run(14, &rlp_circuit, vec![]);
+    assert!(mock_prover.is_ok());
+    let mock_prover = mock_prover.unwrap();
+    if let Err(errors) = mock_prover.verify_par() {
+        log::debug!("errors.len() = {}", errors.len());
+    }
+
+    mock_prover.assert_satisfied_par();
+}
@@ -20,7 +20,7 @@ use self::{
 };
 use crate::{
     evm_circuit::{param::N_BYTES_WORD, util::rlc},
-    table::{AccountFieldTag, LookupTable, MptTable, RwTable, RwTableTag},
+    table::{AccountFieldTag, LookupTable, MptTable, RwTable, RwTableTag, U16Table, U8Table},
     util::{Challenges, Expr, SubCircuit, SubCircuitConfig},
     witness::{self, MptUpdates, Rw, RwMap},
 };
@@ -84,6 +84,10 @@ pub struct StateCircuitConfigArgs<F: Field> {
     pub rw_table: RwTable,
     /// MptTable
     pub mpt_table: MptTable,
+    /// U8Table, shared with the other sub-circuits
+    pub u8_table: U8Table,
+    /// U16Table, shared with the other sub-circuits
+    pub u16_table: U16Table,
     /// Challenges
     pub challenges: Challenges<Expression<F>>,
 }
@@ -106,12 +110,14 @@ impl<F: Field> SubCircuitConfig<F> for StateCircuitConfig<F> {
         Self::ConfigArgs {
             rw_table,
             mpt_table,
+            u8_table,
+            u16_table,
             challenges,
         }: Self::ConfigArgs,
     ) -> Self {
         let selector = rw_table.q_enable;
         log::debug!("state circuit selector {:?}", selector);
-        let lookups = LookupsChip::configure(meta);
+        let lookups = LookupsChip::configure(meta, u8_table, u16_table);
         let power_of_randomness: [Expression<F>; 31] = challenges.evm_word_powers_of_randomness();
 
         let rw_counter = MpiChip::configure(meta, selector, rw_table.rw_counter, lookups);
@@ -195,13 +201,20 @@ impl<F: Field> SubCircuitConfig<F> for StateCircuitConfig<F> {
         for (name, lookup) in constraint_builder.lookups() {
             meta.lookup_any(name, |_| lookup);
         }
+        for (name, expression, n_bits) in constraint_builder.range_checks() {
+            match n_bits {
+                16 => lookups.range_check_u16(meta, name, |_| expression),
+                10 => lookups.range_check_u10(meta, name, |_| expression.clone()),
+                _ => unreachable!("no range table of {n_bits} bits"),
+            }
+        }
 
         config
     }
 }
 
 impl<F: Field> StateCircuitConfig<F> {
-    /// load fixed tables
+    /// load fixed tables, except the shared u8 and u16 tables
     pub(crate) fn load_aux_tables(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
         LookupsChip::construct(self.lookups).load(layouter)
     }
@@ -69,10 +69,12 @@ pub struct Queries<F: Field> {
 
 type Constraint<F> = (&'static str, Expression<F>);
 type Lookup<F> = (&'static str, Vec<(Expression<F>, Expression<F>)>);
+type RangeCheck<F> = (&'static str, Expression<F>, usize);
 
 pub struct ConstraintBuilder<F: Field> {
     pub constraints: Vec<Constraint<F>>,
     lookups: Vec<Lookup<F>>,
+    range_checks: Vec<RangeCheck<F>>,
     condition: Expression<F>,
 }
 
@@ -81,6 +83,7 @@ impl<F: Field> ConstraintBuilder<F> {
         Self {
             constraints: vec![],
             lookups: vec![],
+            range_checks: vec![],
             condition: 1.expr(),
         }
     }
@@ -97,6 +100,10 @@ impl<F: Field> ConstraintBuilder<F> {
         self.lookups.clone()
     }
 
+    pub fn range_checks(&self) -> Vec<RangeCheck<F>> {
+        self.range_checks.clone()
+    }
+
     pub fn build(&mut self, q: &Queries<F>) {
         self.build_general_constraints(q);
         self.condition(q.tag_matches(RwTableTag::Start), |cb| {
@@ -221,9 +228,10 @@ impl<F: Field> ConstraintBuilder<F> {
 
         // The address is aligned.
         let inv_32 = F::from(32).invert().unwrap();
-        self.add_lookup(
+        self.add_range_check(
             "limb fits into u16",
-            vec![(q.address.limbs[0].clone() * inv_32, q.lookups.u16.clone())],
+            q.address.limbs[0].clone() * inv_32,
+            16,
         );
 
         // 2.3. value is a word
@@ -255,9 +263,10 @@ impl<F: Field> ConstraintBuilder<F> {
             q.first_access() * (1.expr() - q.is_write()),
         );
         // 3.2. stack_ptr in range
-        self.add_lookup(
+        self.add_range_check(
             "stack address fits into 10 bits",
-            vec![(q.rw_table.address.clone(), q.lookups.u10.clone())],
+            q.rw_table.address.clone(),
+            10,
         );
         // 3.3. stack_ptr only increases by 0 or 1
         self.condition(q.is_tag_and_id_unchanged.clone(), |cb| {
@@ -553,6 +562,11 @@ impl<F: Field> ConstraintBuilder<F> {
         self.lookups.push((name, lookup));
     }
 
+    fn add_range_check(&mut self, name: &'static str, expression: Expression<F>, n_bits: usize) {
+        self.range_checks
+            .push((name, expression * self.condition.clone(), n_bits));
+    }
+
     fn condition(&mut self, condition: Expression<F>, build: impl FnOnce(&mut Self)) {
         let original_condition = self.condition.clone();
         self.condition = self.condition.clone() * condition;
@@ -2,7 +2,7 @@ pub use super::StateCircuit;
 
 use crate::{
     state_circuit::{StateCircuitConfig, StateCircuitConfigArgs},
-    table::{MptTable, RwTable},
+    table::{MptTable, RwTable, U16Table, U8Table},
     util::{Challenges, SubCircuit, SubCircuitConfig},
 };
 use eth_types::Field;
@@ -27,6 +27,8 @@ where
     fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
         let rw_table = RwTable::construct(meta);
         let mpt_table = MptTable::construct(meta);
+        let u8_table = U8Table::construct(meta);
+        let u16_table = U16Table::construct(meta);
         let challenges = Challenges::construct(meta);
 
         let config = {
@@ -36,6 +38,8 @@ where
                 StateCircuitConfigArgs {
                     rw_table,
                     mpt_table,
+                    u8_table,
+                    u16_table,
                     challenges,
                 },
             )
@@ -56,6 +60,8 @@ where
             self.n_rows,
             challenges.evm_word(),
         )?;
+        config.lookups.u8_table.load(&mut layouter)?;
+        config.lookups.u16_table.load(&mut layouter)?;
         self.synthesize_sub(&config, &challenges, &mut layouter)
     }
 }
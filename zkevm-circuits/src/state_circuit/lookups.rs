@@ -1,4 +1,4 @@
-use crate::table::CallContextFieldTag;
+use crate::table::{CallContextFieldTag, U16Table, U8Table};
 use eth_types::Field;
 use halo2_proofs::{
     circuit::{Layouter, Value},
@@ -10,11 +10,8 @@ use strum::IntoEnumIterator;
 
 #[derive(Clone, Copy, Debug)]
 pub struct Config {
-    // Can these be TableColumn's?
-    // https://github.com/zcash/halo2/blob/642efc1536d3ea2566b04814bd60a00c4745ae22/halo2_proofs/src/plonk/circuit.rs#L266
-    u8: Column<Fixed>,
-    u10: Column<Fixed>,
-    u16: Column<Fixed>,
+    pub u8_table: U8Table,
+    pub u16_table: U16Table,
     pub call_context_field_tag: Column<Fixed>,
 }
 
@@ -25,21 +22,15 @@ impl Config {
         msg: &'static str,
         exp_fn: impl FnOnce(&mut VirtualCells<'_, F>) -> Expression<F>,
     ) {
-        meta.lookup_any(msg, |meta| {
-            let exp = exp_fn(meta);
-            vec![(exp, meta.query_fixed(self.u8, Rotation::cur()))]
-        });
+        self.u8_table.range_check(meta, msg, exp_fn);
     }
     pub fn range_check_u10<F: Field>(
         &self,
         meta: &mut ConstraintSystem<F>,
         msg: &'static str,
-        exp_fn: impl FnOnce(&mut VirtualCells<'_, F>) -> Expression<F>,
+        exp_fn: impl Fn(&mut VirtualCells<'_, F>) -> Expression<F>,
     ) {
-        meta.lookup_any(msg, |meta| {
-            let exp = exp_fn(meta);
-            vec![(exp, meta.query_fixed(self.u10, Rotation::cur()))]
-        });
+        self.u16_table.range_check_bits(meta, msg, 10, exp_fn);
     }
     pub fn range_check_u16<F: Field>(
         &self,
@@ -47,27 +38,18 @@ impl Config {
         msg: &'static str,
         exp_fn: impl FnOnce(&mut VirtualCells<'_, F>) -> Expression<F>,
     ) {
-        meta.lookup_any(msg, |meta| {
-            let exp = exp_fn(meta);
-            vec![(exp, meta.query_fixed(self.u16, Rotation::cur()))]
-        });
+        self.u16_table.range_check(meta, msg, exp_fn);
     }
 }
 
 #[derive(Clone)]
 pub struct Queries<F> {
-    pub u8: Expression<F>,
-    pub u10: Expression<F>,
-    pub u16: Expression<F>,
     pub call_context_field_tag: Expression<F>,
 }
 
 impl<F: Field> Queries<F> {
     pub fn new(meta: &mut VirtualCells<'_, F>, c: Config) -> Self {
         Self {
-            u8: meta.query_fixed(c.u8, Rotation::cur()),
-            u10: meta.query_fixed(c.u10, Rotation::cur()),
-            u16: meta.query_fixed(c.u16, Rotation::cur()),
             call_context_field_tag: meta.query_fixed(c.call_context_field_tag, Rotation::cur()),
         }
     }
@@ -86,16 +68,18 @@ impl<F: Field> Chip<F> {
         }
     }
 
-    pub fn configure(meta: &mut ConstraintSystem<F>) -> Config {
+    /// The u8 and u16 tables are shared with the other sub-circuits, and are
+    /// not loaded by [`Chip::load`].
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        u8_table: U8Table,
+        u16_table: U16Table,
+    ) -> Config {
         let config = Config {
-            u8: meta.fixed_column(),
-            u10: meta.fixed_column(),
-            u16: meta.fixed_column(),
+            u8_table,
+            u16_table,
             call_context_field_tag: meta.fixed_column(),
         };
-        meta.annotate_lookup_any_column(config.u8, || "LOOKUP_u8");
-        meta.annotate_lookup_any_column(config.u10, || "LOOKUP_u10");
-        meta.annotate_lookup_any_column(config.u16, || "LOOKUP_u16");
         meta.annotate_lookup_any_column(config.call_context_field_tag, || {
             "LOOKUP_call_context_field_tag"
         });
@@ -103,26 +87,6 @@ impl<F: Field> Chip<F> {
     }
 
     pub fn load(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
-        for (column, exponent) in [
-            (self.config.u8, 8),
-            (self.config.u10, 10),
-            (self.config.u16, 16),
-        ] {
-            layouter.assign_region(
-                || format!("assign u{exponent} fixed column"),
-                |mut region| {
-                    for i in 0..(1 << exponent) {
-                        region.assign_fixed(
-                            || format!("assign {i} in u{exponent} fixed column"),
-                            column,
-                            i,
-                            || Value::known(F::from(i as u64)),
-                        )?;
-                    }
-                    Ok(())
-                },
-            )?;
-        }
         layouter.assign_region(
             || "assign call_context_field_tags fixed column",
             |mut region| {
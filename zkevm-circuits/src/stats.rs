@@ -1,276 +1,256 @@
-use std::cmp::Ordering;
+//! Layout and cost stats of the circuits.
+//!
+//! [`CircuitCostReport`] reports the columns, lookups and degrees used by each
+//! table and sub circuit of the super circuit, as JSON, so that the cost of
+//! the circuits can be compared between commits. [`CostProfile`] attributes
+//! the rows used to prove a block to the contracts, functions and opcodes
+//! executed by its transactions.
 
-use crate::evm_circuit::step::ExecutionState;
-use bus_mapping::{
-    circuit_input_builder::{self, CircuitsParams, ExecState},
-    mock::BlockData,
+mod profile;
+pub use profile::{CostProfile, CostSample, RowCost, RowKind};
+
+#[cfg(any(feature = "test", test))]
+mod states;
+#[cfg(any(feature = "test", test))]
+pub(crate) use states::{bytecode_prefix_op_big_rws, print_circuit_stats_by_states};
+
+#[cfg(feature = "zktrie")]
+use crate::mpt_circuit::MptCircuit;
+use crate::{
+    bytecode_circuit::circuit::BytecodeCircuit,
+    copy_circuit::CopyCircuit,
+    ecc_circuit::EccCircuit,
+    evm_circuit::{step::ExecutionState, EvmCircuit},
+    exp_circuit::ExpCircuit,
+    keccak_circuit::KeccakCircuit,
+    modexp_circuit::ModExpCircuit,
+    pi_circuit::PiCircuit,
+    poseidon_circuit::PoseidonCircuit,
+    rlp_circuit_fsm::RlpCircuit,
+    sha256_circuit::SHA256Circuit,
+    sig_circuit::SigCircuit,
+    state_circuit::StateCircuit,
+    super_circuit::{SuperCircuitConfig, SuperCircuitConfigArgs},
+    tx_circuit::TxCircuit,
+    util::{circuit_stats, Challenges, CircuitStats, SubCircuit},
+    witness::Transaction,
+};
+use halo2_proofs::{
+    halo2curves::bn256::Fr,
+    plonk::{ConstraintSystem, Expression},
 };
-use eth_types::{bytecode, evm_types::OpcodeId, geth_types::GethData, Address, Bytecode, ToWord};
-use mock::{eth, test_ctx::TestContext, MOCK_ACCOUNTS};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use strum::IntoEnumIterator;
 
-/// Helper type to print formatted tables in MarkDown
-pub(crate) struct DisplayTable<const N: usize> {
-    header: [String; N],
-    rows: Vec<[String; N]>,
+/// Layout of a table or sub circuit of the super circuit.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LayoutStats {
+    /// Name of the table or sub circuit
+    pub name: String,
+    /// Number of advice columns
+    pub advice_columns: usize,
+    /// Number of fixed columns, without the selectors
+    pub fixed_columns: usize,
+    /// Number of instance columns
+    pub instance_columns: usize,
+    /// Number of selectors
+    pub selectors: usize,
+    /// Number of gates
+    pub gates: usize,
+    /// Number of polynomial constraints of the gates
+    pub constraints: usize,
+    /// Number of lookups
+    pub lookups: usize,
+    /// Max degree of the gates
+    pub gate_degree: usize,
+    /// Max degree required by the gates and the lookups
+    pub max_constraint_degree: usize,
+    /// Rows at the end of the circuit that can't be assigned, only reported
+    /// for sub circuits
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unusable_rows: Option<usize>,
 }
 
-impl<const N: usize> DisplayTable<N> {
-    pub(crate) fn new(header: [String; N]) -> Self {
-        Self {
-            header,
-            rows: Vec::new(),
-        }
-    }
-    fn push_row(&mut self, row: [String; N]) {
-        self.rows.push(row)
-    }
-    fn print_row(row: &[String; N], rows_width: &[usize; N]) {
-        for (i, h) in row.iter().enumerate() {
-            if i == 0 {
-                print!("|");
-            }
-            print!(" {:width$} |", h, width = rows_width[i]);
-        }
-        println!();
-    }
-    pub(crate) fn print(&self) {
-        let mut rows_width = [0; N];
-        for row in std::iter::once(&self.header).chain(self.rows.iter()) {
-            for (i, s) in row.iter().enumerate() {
-                if s.len() > rows_width[i] {
-                    rows_width[i] = s.len();
-                }
-            }
-        }
-        Self::print_row(&self.header, &rows_width);
-        for (i, width) in rows_width.iter().enumerate() {
-            if i == 0 {
-                print!("|");
+/// Layout and cost of the super circuit.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CircuitCostReport {
+    /// Degree of the super circuit
+    pub degree: usize,
+    /// Total number of advice columns
+    pub advice_columns: usize,
+    /// Total number of fixed columns, without the selectors
+    pub fixed_columns: usize,
+    /// Total number of instance columns
+    pub instance_columns: usize,
+    /// Total number of lookups
+    pub lookups: usize,
+    /// Tables shared by the sub circuits, in configuration order
+    pub tables: Vec<LayoutStats>,
+    /// Sub circuits, in configuration order
+    pub sub_circuits: Vec<LayoutStats>,
+    /// Rows of the EVM circuit used by each execution state
+    pub evm_rows_per_execution_state: BTreeMap<String, usize>,
+}
+
+impl CircuitCostReport {
+    /// Configure the super circuit and report the cost of each of its parts.
+    pub fn new<
+        const MAX_TXS: usize,
+        const MAX_CALLDATA: usize,
+        const MAX_INNER_BLOCKS: usize,
+        const MOCK_RANDOMNESS: u64,
+    >() -> Self {
+        let mut meta = ConstraintSystem::<Fr>::default();
+        let challenges = Challenges::construct(&mut meta);
+        let start = circuit_stats(&meta);
+        let (_, layout) = SuperCircuitConfig::new_with_layout(
+            &mut meta,
+            SuperCircuitConfigArgs {
+                max_txs: MAX_TXS,
+                max_calldata: MAX_CALLDATA,
+                max_inner_blocks: MAX_INNER_BLOCKS,
+                mock_randomness: MOCK_RANDOMNESS,
+                challenges,
+            },
+        );
+
+        let mut tables = vec![];
+        let mut sub_circuits = vec![];
+        let mut prev = &start;
+        for (name, stats) in layout.iter() {
+            let mut part = layout_diff(&meta, name, prev, stats);
+            prev = stats;
+            if name.ends_with(" table") {
+                tables.push(part);
+            } else {
+                part.unusable_rows = sub_circuit_unusable_rows(name);
+                sub_circuits.push(part);
             }
-            print!(" {:-<width$} |", "", width = width);
         }
-        println!();
-        for row in &self.rows {
-            Self::print_row(row, &rows_width);
+
+        let evm_rows_per_execution_state = ExecutionState::iter()
+            .filter_map(|state| {
+                state
+                    .get_step_height_option()
+                    .map(|height| (format!("{state:?}"), height))
+            })
+            .collect();
+
+        Self {
+            degree: meta.degree(),
+            advice_columns: meta.num_advice_columns,
+            fixed_columns: meta.num_fixed_columns,
+            instance_columns: meta.num_instance_columns,
+            lookups: meta.lookups.len(),
+            tables,
+            sub_circuits,
+            evm_rows_per_execution_state,
         }
     }
+
+    /// Serialize the report as pretty printed JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("report is serializable")
+    }
 }
 
-/// Generate the prefix bytecode to trigger a big amount of rw operations
-pub(crate) fn bytecode_prefix_op_big_rws(opcode: OpcodeId) -> Bytecode {
-    match opcode {
-        OpcodeId::CODECOPY | OpcodeId::CALLDATACOPY => {
-            bytecode! {
-                PUSH4(0x1000) // size
-                PUSH2(0x00) // offset
-                PUSH2(0x00) // destOffset
-            }
-        }
-        OpcodeId::RETURNDATACOPY => {
-            bytecode! {
-                PUSH1(0x00) // retLength
-                PUSH1(0x00) // retOffset
-                PUSH1(0x00) // argsLength
-                PUSH1(0x00) // argsOffset
-                PUSH1(0x00) // value
-                PUSH32(MOCK_ACCOUNTS[3].to_word())
-                PUSH32(0x1_0000) // gas
-                CALL
-                PUSH4(0x1000) // size
-                PUSH2(0x00) // offset
-                PUSH2(0x00) // destOffset
-            }
-        }
-        OpcodeId::LOG0
-        | OpcodeId::LOG1
-        | OpcodeId::LOG2
-        | OpcodeId::LOG3
-        | OpcodeId::LOG4
-        | OpcodeId::SHA3
-        | OpcodeId::RETURN
-        | OpcodeId::REVERT => bytecode! {
-            PUSH4(0x1000) // size
-            PUSH2(0x00) // offset
-        },
-        OpcodeId::EXTCODECOPY => bytecode! {
-            PUSH4(0x1000) // size
-            PUSH2(0x00) // offset
-            PUSH2(0x00) // destOffset
-            PUSH2(0x00) // address
-        },
-        _ => bytecode! {
-            PUSH2(0x40)
-            PUSH2(0x50)
-        },
+/// Layout added to the constraint system between `prev` and `cur`.
+fn layout_diff(
+    meta: &ConstraintSystem<Fr>,
+    name: &str,
+    prev: &CircuitStats,
+    cur: &CircuitStats,
+) -> LayoutStats {
+    let gates = &meta.gates()[prev.num_gates..cur.num_gates];
+    let gate_degree = gates
+        .iter()
+        .flat_map(|gate| gate.polynomials())
+        .map(|poly| poly.degree())
+        .max()
+        .unwrap_or_default();
+    // same as `lookup::Argument::required_degree` of halo2
+    let lookup_degree = meta.lookups[prev.num_lookups..cur.num_lookups]
+        .iter()
+        .map(|lookup| {
+            let degree = |exprs: &[Expression<Fr>]| {
+                exprs
+                    .iter()
+                    .map(Expression::<Fr>::degree)
+                    .fold(1, usize::max)
+            };
+            (2 + degree(&lookup.input_expressions()[..]) + degree(&lookup.table_expressions()[..]))
+                .max(4)
+        })
+        .max()
+        .unwrap_or_default();
+
+    LayoutStats {
+        name: name.to_string(),
+        advice_columns: cur.num_advice_columns - prev.num_advice_columns,
+        fixed_columns: cur.num_fixed_columns - prev.num_fixed_columns,
+        instance_columns: cur.num_instance_columns - prev.num_instance_columns,
+        selectors: cur.num_selectors - prev.num_selectors,
+        gates: gates.len(),
+        constraints: cur.num_constraints - prev.num_constraints,
+        lookups: cur.num_lookups - prev.num_lookups,
+        gate_degree,
+        max_constraint_degree: gate_degree.max(lookup_degree),
+        unusable_rows: None,
     }
 }
 
-struct Row {
-    state: ExecutionState,
-    opcode: OpcodeId,
-    height: usize,
-    gas_cost: u64,
-    height_per_gas: f64,
+/// Unusable rows of the sub circuit configured under `name` by the super
+/// circuit.
+fn sub_circuit_unusable_rows(name: &str) -> Option<usize> {
+    Some(match name {
+        "keccak circuit" => KeccakCircuit::<Fr>::unusable_rows(),
+        "sha256 circuit" => SHA256Circuit::<Fr>::unusable_rows(),
+        "poseidon circuit" => PoseidonCircuit::<Fr>::unusable_rows(),
+        "rlp circuit" => RlpCircuit::<Fr, Transaction>::unusable_rows(),
+        "pi circuit" => PiCircuit::<Fr>::unusable_rows(),
+        "tx circuit" => TxCircuit::<Fr>::unusable_rows(),
+        "bytecode circuit" => BytecodeCircuit::<Fr>::unusable_rows(),
+        "copy circuit" => CopyCircuit::<Fr>::unusable_rows(),
+        #[cfg(feature = "zktrie")]
+        "zktrie circuit" => MptCircuit::<Fr>::unusable_rows(),
+        "modexp circuit" => ModExpCircuit::<Fr>::unusable_rows(),
+        "state circuit" => StateCircuit::<Fr>::unusable_rows(),
+        "exp circuit" => ExpCircuit::<Fr>::unusable_rows(),
+        "evm circuit" => EvmCircuit::<Fr>::unusable_rows(),
+        "sig circuit" => SigCircuit::<Fr>::unusable_rows(),
+        "ecc circuit" => EccCircuit::<Fr, 9>::unusable_rows(),
+        _ => return None,
+    })
 }
 
-/// This function prints to stdout a table with all the implemented states
-/// and their responsible opcodes with the following stats:
-/// - height: number of rows in a circuit used by the execution state
-/// - gas: gas value used for the opcode execution
-/// - height/gas: ratio between circuit cost and gas cost
-///
-/// The TestContext is as follows:
-/// - `MOCK_ACCOUNTS[0]` calls `MOCK_ACCOUNTS[1]` which has a proxy code that calls
-///   `MOCK_ACCOUNT[2]` which has the main code
-/// - `0x0` account has a copy of the main code
-/// - `MOCK_ACCOUNTS[3]` has a small code that returns a 0-memory chunk
-pub(crate) fn print_circuit_stats_by_states(
-    // Function to select which opcodes to analyze.  When this returns false,
-    // the opcode is skipped.
-    fn_filter: impl Fn(ExecutionState) -> bool,
-    // Function to generate bytecode that will be prefixed to the opcode,
-    // useful to set up arguments that cause worst height/gas case.
-    fn_bytecode_prefix_op: impl Fn(OpcodeId) -> Bytecode,
-    // Function that calculates the circuit height used by an opcode.  This function takes the
-    // circuit input builder Block, the current execution state, and the step index in circuit
-    // input builder tx.
-    fn_height: impl Fn(&circuit_input_builder::Block, ExecutionState, usize) -> usize,
-) {
-    let mut implemented_states = Vec::new();
-    for state in ExecutionState::iter() {
-        let height = state.get_step_height_option();
-        if height.is_some() {
-            implemented_states.push(state);
-        }
-    }
-    let smallcode = bytecode! {
-        PUSH4(0x1000) // size
-        PUSH2(0x00) // offset
-        RETURN
-    };
-    let proxy_code = bytecode! {
-        PUSH2(0x1000) // retLength
-        PUSH1(0x00) // retOffset
-        PUSH1(0x00) // argsLength
-        PUSH1(0x00) // argsOffset
-        PUSH1(0x00) // value
-        PUSH32(MOCK_ACCOUNTS[2].to_word())
-        PUSH32(800_000) // gas
-        CALL
-        STOP
-    };
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    let mut table = DisplayTable::new(["state", "opcode", "h", "g", "h/g"].map(|s| s.into()));
-    let mut rows = vec![];
-    for state in implemented_states {
-        if !fn_filter(state) {
-            continue;
-        }
-        for responsible_op in state.responsible_opcodes() {
-            let opcode = responsible_op.opcode();
-            let mut code = bytecode! {
-                PUSH2(0x00)
-                EXTCODESIZE // Warm up 0x0 address
-                PUSH1(0x00)
-                PUSH1(0x00)
-                PUSH1(0x00)
-                PUSH1(0x00)
-                PUSH1(0x00)
-                PUSH1(0x00)
-                PUSH1(0x00)
-                PUSH1(0x00)
-                PUSH1(0x00)
-                PUSH1(0x00)
-                PUSH2(0x00)
-                PUSH2(0x10)
-                PUSH2(0x20)
-                PUSH2(0x30)
-            };
-            let bytecode_prefix_op = fn_bytecode_prefix_op(opcode);
-            code.append(&bytecode_prefix_op);
-            code.write_op(opcode);
-            let opcode_pc = code.code.len() - 1;
-            // let opcode_step_index = (proxy_code.num_opcodes - 1 + code.num_opcodes) - 1;
-            code.op_stop();
-            let block: GethData = TestContext::<10, 1>::new(
-                None,
-                |accs| {
-                    accs[0].address(MOCK_ACCOUNTS[0]).balance(eth(10));
-                    accs[1]
-                        .address(MOCK_ACCOUNTS[1])
-                        .balance(eth(10))
-                        .code(proxy_code.clone());
-                    accs[2]
-                        .address(MOCK_ACCOUNTS[2])
-                        .balance(eth(10))
-                        .code(code.clone());
-                    accs[3].address(MOCK_ACCOUNTS[3]).code(smallcode.clone());
-                    accs[4].address(Address::zero()).balance(eth(10)).code(code);
-                },
-                |mut txs, accs| {
-                    txs[0]
-                        .from(accs[0].address)
-                        .to(accs[1].address)
-                        .input(vec![1, 2, 3, 4, 5, 6, 7].into());
-                },
-                |block, _tx| block.number(0xcafeu64),
-            )
-            .unwrap()
-            .into();
-            let mut builder = BlockData::new_from_geth_data_with_params(
-                block.clone(),
-                CircuitsParams {
-                    max_rws: 16_000,
-                    max_copy_rows: 8_000,
-                    ..CircuitsParams::default()
-                },
-            )
-            .new_circuit_input_builder();
-            builder
-                .handle_block(&block.eth_block, &block.geth_traces)
-                .unwrap();
-            // Find the step that executed our opcode by filtering on second call (because
-            // we run it via proxy) and the PC where we wrote the opcode.
-            let (step_index, step) = builder.block.txs[0]
-                .steps()
-                .iter()
-                .enumerate()
-                .find(|(_, s)| s.call_index == 1 && s.pc.0 == opcode_pc)
-                .unwrap();
-            assert_eq!(ExecState::Op(opcode), step.exec_state);
-            let height = fn_height(&builder.block, state, step_index);
+    #[test]
+    fn circuit_cost_report() {
+        let report = CircuitCostReport::new::<1, 32, 64, 0x100>();
 
-            // Substract 1 to step_index to remove the `BeginTx` step, which doesn't appear
-            // in the geth trace.
-            let geth_step = &block.geth_traces[0].struct_logs[step_index - 1];
-            assert_eq!(opcode, geth_step.op);
-            let gas_cost = geth_step.gas_cost.0;
-            rows.push(Row {
-                state,
-                opcode,
-                height,
-                gas_cost,
-                height_per_gas: height as f64 / gas_cost as f64,
-            });
-        }
-    }
-    rows.sort_by(|a, b| {
-        b.height_per_gas
-            .partial_cmp(&a.height_per_gas)
-            .unwrap_or(Ordering::Greater)
-    });
+        let total = |f: fn(&LayoutStats) -> usize| {
+            report
+                .tables
+                .iter()
+                .chain(report.sub_circuits.iter())
+                .map(f)
+                .sum::<usize>()
+        };
+        assert_eq!(total(|part| part.advice_columns), report.advice_columns);
+        assert_eq!(total(|part| part.lookups), report.lookups);
+        assert!(report.sub_circuits.iter().all(
+            |part| part.unusable_rows.is_some() && part.max_constraint_degree <= report.degree
+        ));
+        assert!(report.evm_rows_per_execution_state.contains_key("STOP"));
 
-    for row in rows.iter() {
-        let row = [
-            format!("{:?}", row.state),
-            format!("{:?}", row.opcode),
-            format!("{}", row.height),
-            format!("{}", row.gas_cost),
-            format!("{:1.3}", row.height_per_gas),
-        ];
-        table.push_row(row);
+        let json = report.to_json();
+        assert_eq!(
+            serde_json::from_str::<CircuitCostReport>(&json).unwrap(),
+            report
+        );
     }
-
-    table.print();
 }
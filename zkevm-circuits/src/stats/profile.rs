@@ -0,0 +1,521 @@
+//! Cost profile of a block, attributing the rows of the circuits to the
+//! contracts, functions and opcodes executed by its transactions.
+//!
+//! [`CostProfile::to_folded`] writes the profile as folded stacks, the input
+//! of flamegraph tools such as `inferno-flamegraph`, and
+//! [`CostProfile::to_chrome_trace`] as a Chrome trace laid out along the rows
+//! of the EVM circuit, which can be opened in Perfetto.
+
+use crate::{
+    copy_circuit::CopyCircuit,
+    evm_circuit::{step::ExecutionState, EvmCircuit},
+    keccak_circuit::{keccak_num_rows, KeccakCircuit},
+    poseidon_circuit::{self, bytecode_hash_num_rows},
+    state_circuit::StateCircuit,
+    table::RwTableTag,
+    util::SubCircuit,
+    witness::{Block, Call, ExecStep, Rw, RwMap, Transaction},
+};
+use eth_types::{Field, ToBigEndian, Word};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    ops::AddAssign,
+};
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+/// Sub circuits whose rows grow with the execution of the transactions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter)]
+pub enum RowKind {
+    /// EVM circuit
+    Evm,
+    /// State circuit
+    Rw,
+    /// Copy circuit
+    Copy,
+    /// Keccak circuit
+    Keccak,
+    /// Poseidon circuit
+    Poseidon,
+}
+
+impl RowKind {
+    /// Name of the rows in the exported profiles.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Evm => "evm_rows",
+            Self::Rw => "rw_rows",
+            Self::Copy => "copy_rows",
+            Self::Keccak => "keccak_rows",
+            Self::Poseidon => "poseidon_rows",
+        }
+    }
+}
+
+/// Rows used in each kind of sub circuit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RowCost {
+    /// Rows of the EVM circuit
+    pub evm_rows: usize,
+    /// Rows of the state circuit, one per read or write
+    pub rw_rows: usize,
+    /// Rows of the copy circuit
+    pub copy_rows: usize,
+    /// Rows of the keccak circuit
+    pub keccak_rows: usize,
+    /// Rows of the poseidon circuit
+    pub poseidon_rows: usize,
+}
+
+impl RowCost {
+    /// Returns the rows of `kind`.
+    pub fn get(&self, kind: RowKind) -> usize {
+        match kind {
+            RowKind::Evm => self.evm_rows,
+            RowKind::Rw => self.rw_rows,
+            RowKind::Copy => self.copy_rows,
+            RowKind::Keccak => self.keccak_rows,
+            RowKind::Poseidon => self.poseidon_rows,
+        }
+    }
+}
+
+impl AddAssign for RowCost {
+    fn add_assign(&mut self, rhs: Self) {
+        self.evm_rows += rhs.evm_rows;
+        self.rw_rows += rhs.rw_rows;
+        self.copy_rows += rhs.copy_rows;
+        self.keccak_rows += rhs.keccak_rows;
+        self.poseidon_rows += rhs.poseidon_rows;
+    }
+}
+
+/// Rows used by a step, or by the block itself.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CostSample {
+    /// Frames of the sample from the outermost: the transaction, the calls as
+    /// `contract:selector`, then the opcode or the execution state of the step
+    pub frames: Vec<String>,
+    /// First row of the sample in the EVM circuit
+    pub evm_row: usize,
+    /// Rows used by the sample
+    pub cost: RowCost,
+}
+
+/// Rows used to prove a block, attributed to the steps of its transactions.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CostProfile {
+    /// Samples of the steps in execution order, followed by the sample of the
+    /// rows not attributed to a step under the `block` frame
+    pub samples: Vec<CostSample>,
+}
+
+impl CostProfile {
+    /// Attribute the rows used by `block` to the steps of its transactions.
+    ///
+    /// A step is charged its EVM rows, its reads and writes, the copy events
+    /// starting at its reads and writes, the keccak rows of SHA3, CREATE and CREATE2
+    /// and the poseidon rows hashing the bytecode it is the first to execute.
+    /// The rows left, such as the hashes of the state trie, which are shared by
+    /// the steps touching the same accounts, are charged to the `block` frame.
+    pub fn new<F: Field>(block: &Block<F>) -> Self {
+        let memory = MemoryHistory::new(&block.rws);
+        let steps = block
+            .txs
+            .iter()
+            .flat_map(|tx| tx.steps.iter().map(move |step| (tx, step)))
+            .collect::<Vec<_>>();
+        let mut copy_events = block
+            .copy_events
+            .iter()
+            .map(|event| (event.rw_counter_start.0, event.full_length() as usize * 2))
+            .collect::<Vec<_>>();
+        copy_events.sort_unstable();
+        let mut copy_events = copy_events.into_iter().peekable();
+        let mut hashed_bytecodes = HashSet::new();
+
+        let mut samples = Vec::with_capacity(steps.len() + 1);
+        let mut frames_by_tx = HashMap::new();
+        let mut evm_row = 0;
+        let mut total = RowCost::default();
+        for (idx, (tx, step)) in steps.iter().enumerate() {
+            let call_frames = frames_by_tx
+                .entry(tx.id)
+                .or_insert_with(|| tx_call_frames(tx, &memory))
+                .get(step.call_index);
+
+            // Copy events start at the rw counter of the step emitting them.
+            let next_rw_counter = steps
+                .get(idx + 1)
+                .map_or(usize::MAX, |(_, next_step)| next_step.rw_counter);
+            let mut copy_rows = 0;
+            while let Some((_, rows)) =
+                copy_events.next_if(|(rw_counter, _)| *rw_counter < next_rw_counter)
+            {
+                copy_rows += rows;
+            }
+
+            let poseidon_rows = match tx.calls.get(step.call_index) {
+                Some(call) if hashed_bytecodes.insert(call.code_hash) => {
+                    block.bytecodes.get(&call.code_hash).map_or(0, |bytecode| {
+                        bytecode_hash_num_rows::<F>(bytecode.bytes.len())
+                    })
+                }
+                _ => 0,
+            };
+
+            let cost = RowCost {
+                evm_rows: step.execution_state.get_step_height(),
+                rw_rows: step.rw_indices.len() + step.copy_rw_counter_delta as usize,
+                copy_rows,
+                keccak_rows: step_keccak_num_rows(block, tx, step),
+                poseidon_rows,
+            };
+            total += cost;
+            samples.push(CostSample {
+                frames: [format!("tx {}", tx.id)]
+                    .into_iter()
+                    .chain(call_frames.into_iter().flatten().cloned())
+                    .chain([step_frame(step)])
+                    .collect(),
+                evm_row,
+                cost,
+            });
+            evm_row += cost.evm_rows;
+        }
+
+        let block_rows = |rows: usize, attributed: usize| rows.saturating_sub(attributed);
+        let cost = RowCost {
+            evm_rows: block_rows(
+                EvmCircuit::<F>::get_num_rows_required_no_padding(block),
+                total.evm_rows,
+            ),
+            rw_rows: block_rows(
+                StateCircuit::<F>::min_num_rows_block(block).0,
+                total.rw_rows,
+            ),
+            copy_rows: block_rows(
+                CopyCircuit::<F>::min_num_rows_block(block).0,
+                total.copy_rows,
+            ),
+            keccak_rows: block_rows(
+                KeccakCircuit::<F>::min_num_rows_block(block).0,
+                total.keccak_rows,
+            ),
+            poseidon_rows: block_rows(
+                poseidon_circuit::min_num_rows_block(block).0,
+                total.poseidon_rows,
+            ),
+        };
+        samples.push(CostSample {
+            frames: vec!["block".to_string()],
+            evm_row,
+            cost,
+        });
+
+        Self { samples }
+    }
+
+    /// Returns the rows used by all the samples.
+    pub fn total(&self) -> RowCost {
+        let mut total = RowCost::default();
+        for sample in &self.samples {
+            total += sample.cost;
+        }
+        total
+    }
+
+    /// Write the rows of `kind` as folded stacks, one line per distinct stack
+    /// of frames followed by its rows.
+    pub fn to_folded(&self, kind: RowKind) -> String {
+        let mut stacks = BTreeMap::<String, usize>::new();
+        for sample in &self.samples {
+            *stacks.entry(sample.frames.join(";")).or_default() += sample.cost.get(kind);
+        }
+        stacks
+            .into_iter()
+            .filter(|(_, rows)| *rows > 0)
+            .map(|(stack, rows)| format!("{stack} {rows}\n"))
+            .collect()
+    }
+
+    /// Write the profile as a Chrome trace, with a complete event per sample
+    /// and per frame enclosing consecutive samples. The timestamps and the
+    /// durations of the events are rows of the EVM circuit, their arguments
+    /// the rows of each kind.
+    pub fn to_chrome_trace(&self) -> String {
+        let event = |name: &str, evm_row: usize, cost: &RowCost| {
+            let mut args = serde_json::Map::new();
+            for kind in RowKind::iter() {
+                args.insert(kind.name().to_string(), cost.get(kind).into());
+            }
+            json!({
+                "name": name,
+                "ph": "X",
+                "ts": evm_row,
+                "dur": cost.evm_rows,
+                "pid": 0,
+                "tid": 0,
+                "args": args,
+            })
+        };
+
+        let mut events = vec![];
+        // Frames enclosing the current sample, with their first row and cost
+        let mut open: Vec<(&str, usize, RowCost)> = vec![];
+        for sample in &self.samples {
+            let (leaf, frames) = sample.frames.split_last().expect("sample has a frame");
+            let common = open
+                .iter()
+                .zip(frames)
+                .take_while(|((name, _, _), frame)| *name == frame.as_str())
+                .count();
+            for (name, evm_row, cost) in open.drain(common..).rev() {
+                events.push(event(name, evm_row, &cost));
+            }
+            for frame in &frames[common..] {
+                open.push((frame.as_str(), sample.evm_row, RowCost::default()));
+            }
+            for (_, _, cost) in open.iter_mut() {
+                *cost += sample.cost;
+            }
+            events.push(event(leaf, sample.evm_row, &sample.cost));
+        }
+        for (name, evm_row, cost) in open.into_iter().rev() {
+            events.push(event(name, evm_row, &cost));
+        }
+
+        serde_json::to_string(&json!({
+            "traceEvents": events,
+            "otherData": { "unit": "evm_rows" },
+        }))
+        .expect("trace is serializable")
+    }
+}
+
+/// Name of the frame of `step`: its opcode, or its execution state if it
+/// doesn't execute one successfully.
+fn step_frame(step: &ExecStep) -> String {
+    match step.opcode {
+        Some(opcode) if !step.execution_state.halts_in_exception() => format!("{opcode:?}"),
+        _ => format!("{:?}", step.execution_state),
+    }
+}
+
+/// Rows of the keccak circuit hashing the memory read by `step`. CREATE and
+/// CREATE2 only hash the init code and the preimage of the address when their
+/// precheck passes.
+fn step_keccak_num_rows<F: Field>(block: &Block<F>, tx: &Transaction, step: &ExecStep) -> usize {
+    let rw = |idx: usize| &block.rws[step.rw_indices[idx]];
+    match step.execution_state {
+        // offset, size
+        ExecutionState::SHA3 => keccak_num_rows(rw(1).stack_value().low_u64() as usize),
+        ExecutionState::CREATE | ExecutionState::CREATE2 => {
+            let is_create2 = step.execution_state == ExecutionState::CREATE2;
+            // TxId, Depth, RwCounterEndOfReversion, IsPersistent, IsStatic, value,
+            // offset, size, [salt,] address, caller address, caller balance,
+            // caller nonce
+            let value = rw(5).stack_value();
+            let size = rw(7).stack_value().low_u64() as usize;
+            let caller_balance = rw(10 + is_create2 as usize).account_balance_pair().1;
+            let caller_nonce = rw(11 + is_create2 as usize).account_nonce_pair().1;
+            let caller = &tx.calls[step.call_index];
+            let is_precheck_ok =
+                caller.depth < 1025 && caller_balance >= value && caller_nonce < u64::MAX.into();
+            if !is_precheck_ok {
+                return 0;
+            }
+            let address_preimage_len = if is_create2 {
+                // 0xff, caller address, salt, init code hash
+                1 + 20 + 32 + 32
+            } else {
+                let mut stream = ethers_core::utils::rlp::RlpStream::new();
+                stream.begin_list(2);
+                stream.append(&caller.callee_address);
+                stream.append(&caller_nonce);
+                stream.out().len()
+            };
+            keccak_num_rows(size) + keccak_num_rows(address_preimage_len)
+        }
+        _ => 0,
+    }
+}
+
+/// Frames of each call of `tx` from its root call, indexed like the calls.
+fn tx_call_frames(tx: &Transaction, memory: &MemoryHistory) -> Vec<Vec<String>> {
+    let call_index_by_id = tx
+        .calls
+        .iter()
+        .enumerate()
+        .map(|(idx, call)| (call.id, idx))
+        .collect::<HashMap<_, _>>();
+    let mut frames: Vec<Vec<String>> = Vec::with_capacity(tx.calls.len());
+    // Callers come before their callees.
+    for call in &tx.calls {
+        let mut call_frames = match call_index_by_id.get(&call.caller_id) {
+            Some(caller_idx) if !call.is_root => frames[*caller_idx].clone(),
+            _ => vec![],
+        };
+        call_frames.push(call_frame(tx, call, memory));
+        frames.push(call_frames);
+    }
+    frames
+}
+
+/// Name of the frame of `call`, as `contract:selector`.
+fn call_frame(tx: &Transaction, call: &Call, memory: &MemoryHistory) -> String {
+    if call.is_create {
+        return format!("{:?}:constructor", call.callee_address);
+    }
+    let contract = call.code_address.unwrap_or(call.callee_address);
+    let selector = if call.is_root {
+        tx.call_data
+            .get(..4)
+            .map(|selector| selector.try_into().unwrap())
+    } else {
+        (call.call_data_length >= 4).then(|| {
+            [0, 1, 2, 3]
+                .map(|idx| memory.byte_before(call.caller_id, call.call_data_offset + idx, call.id))
+        })
+    };
+    match selector {
+        Some(selector) => format!("{contract:?}:0x{:08x}", u32::from_be_bytes(selector)),
+        None => format!("{contract:?}:fallback"),
+    }
+}
+
+/// Memory words read or written by each call, to recover the call data of the
+/// internal calls from the memory of their caller.
+struct MemoryHistory(HashMap<usize, Vec<(usize, u64, Word)>>);
+
+impl MemoryHistory {
+    fn new(rws: &RwMap) -> Self {
+        let mut history = HashMap::<_, Vec<_>>::new();
+        for rw in rws.0.get(&RwTableTag::Memory).into_iter().flatten() {
+            if let Rw::Memory {
+                rw_counter,
+                call_id,
+                memory_address,
+                value,
+                ..
+            } = rw
+            {
+                history
+                    .entry(*call_id)
+                    .or_default()
+                    .push((*rw_counter, *memory_address, *value));
+            }
+        }
+        for words in history.values_mut() {
+            words.sort_unstable_by_key(|(rw_counter, _, _)| *rw_counter);
+        }
+        Self(history)
+    }
+
+    /// Byte at `address` in the memory of `call_id` before `rw_counter`, zero
+    /// if it wasn't accessed.
+    fn byte_before(&self, call_id: usize, address: u64, rw_counter: usize) -> u8 {
+        self.0
+            .get(&call_id)
+            .and_then(|words| {
+                words
+                    .iter()
+                    .rev()
+                    .skip_while(|(word_rw_counter, _, _)| *word_rw_counter >= rw_counter)
+                    .find(|(_, word_address, _)| {
+                        (*word_address..*word_address + 32).contains(&address)
+                    })
+            })
+            .map_or(0, |(_, word_address, value)| {
+                value.to_be_bytes()[(address - word_address) as usize]
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::witness::block_convert;
+    use bus_mapping::mock::BlockData;
+    use eth_types::{bytecode, geth_types::GethData, Bytecode};
+    use halo2_proofs::halo2curves::bn256::Fr;
+    use mock::{test_ctx::helpers::account_0_code_account_1_no_code, TestContext};
+
+    fn witness_block(code: Bytecode) -> Block<Fr> {
+        let block: GethData = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(code),
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[1].address)
+                    .to(accs[0].address)
+                    .input(vec![0xa9, 0x05, 0x9c, 0xbb].into());
+            },
+            |block, _tx| block.number(0xcafe_u64),
+        )
+        .unwrap()
+        .into();
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        block_convert::<Fr>(&builder.block, &builder.code_db).unwrap()
+    }
+
+    #[test]
+    fn cost_profile() {
+        let block = witness_block(bytecode! {
+            PUSH1(0x20)
+            PUSH1(0x00)
+            SHA3
+            STOP
+        });
+
+        let profile = CostProfile::new(&block);
+        let total = profile.total();
+        assert_eq!(
+            total.evm_rows,
+            EvmCircuit::<Fr>::get_num_rows_required_no_padding(&block)
+        );
+        assert_eq!(
+            total.rw_rows,
+            StateCircuit::<Fr>::min_num_rows_block(&block).0
+        );
+
+        let sha3 = profile
+            .samples
+            .iter()
+            .find(|sample| sample.frames.last().unwrap() == "SHA3")
+            .unwrap();
+        assert_eq!(sha3.cost.keccak_rows, keccak_num_rows(0x20));
+        assert!(sha3.frames[1].ends_with(":0xa9059cbb"));
+
+        let folded = profile.to_folded(RowKind::Evm);
+        assert!(folded.lines().any(|line| line.starts_with("tx 1;")));
+        let trace: serde_json::Value = serde_json::from_str(&profile.to_chrome_trace()).unwrap();
+        assert!(trace["traceEvents"].as_array().unwrap().len() > profile.samples.len());
+    }
+
+    #[test]
+    fn cost_profile_create2_precheck_failure() {
+        // The value exceeds the balance of the caller, so nothing is hashed.
+        let block = witness_block(bytecode! {
+            PUSH1(0x00) // salt
+            PUSH1(0x20) // size
+            PUSH1(0x00) // offset
+            PUSH32(Word::MAX) // value
+            CREATE2
+            STOP
+        });
+
+        let profile = CostProfile::new(&block);
+        let create2 = profile
+            .samples
+            .iter()
+            .find(|sample| sample.frames.last().unwrap() == "CREATE2")
+            .unwrap();
+        assert_eq!(create2.cost.keccak_rows, 0);
+    }
+}
@@ -49,6 +49,7 @@
 //!   - [x] Bytecode Circuit
 //!   - [x] Tx Circuit
 //!   - [ ] MPT Circuit
+pub mod params;
 pub(crate) mod precompile_block_trace;
 #[cfg(any(feature = "test", test))]
 pub(crate) mod test;
@@ -85,7 +86,7 @@ use crate::{
         SigTable, TxTable, U16Table, U8Table,
     },
     tx_circuit::{TxCircuit, TxCircuitConfig, TxCircuitConfigArgs},
-    util::{circuit_stats, log2_ceil, Challenges, SubCircuit, SubCircuitConfig},
+    util::{circuit_stats, log2_ceil, Challenges, CircuitStats, SubCircuit, SubCircuitConfig},
     witness::{block_convert, Block, Transaction},
 };
 
@@ -155,18 +156,29 @@ impl SubCircuitConfig<Fr> for SuperCircuitConfig<Fr> {
     type ConfigArgs = SuperCircuitConfigArgs;
 
     /// Configure SuperCircuitConfig
-    fn new(
+    fn new(meta: &mut ConstraintSystem<Fr>, args: Self::ConfigArgs) -> Self {
+        Self::new_with_layout(meta, args).0
+    }
+}
+
+impl SuperCircuitConfig<Fr> {
+    /// Configure SuperCircuitConfig, and return the stats of the constraint
+    /// system after each table and sub circuit is configured.
+    pub(crate) fn new_with_layout(
         meta: &mut ConstraintSystem<Fr>,
-        Self::ConfigArgs {
+        SuperCircuitConfigArgs {
             max_txs: _,
             max_calldata: _,
             max_inner_blocks: _,
             mock_randomness: _mock_randomness,
             challenges,
-        }: Self::ConfigArgs,
-    ) -> Self {
-        let log_circuit_info = |meta: &ConstraintSystem<Fr>, tag: &str| {
-            log::debug!("circuit info after {}: {:#?}", tag, circuit_stats(meta));
+        }: SuperCircuitConfigArgs,
+    ) -> (Self, Vec<(&'static str, CircuitStats)>) {
+        let mut layout = vec![];
+        let mut log_circuit_info = |meta: &ConstraintSystem<Fr>, tag: &'static str| {
+            let stats = circuit_stats(meta);
+            log::debug!("circuit info after {}: {:#?}", tag, stats);
+            layout.push((tag, stats));
         };
         let challenges_expr = challenges.exprs(meta);
 
@@ -249,6 +261,7 @@ impl SubCircuitConfig<Fr> for SuperCircuitConfig<Fr> {
                 block_table: block_table.clone(),
                 keccak_table: keccak_table.clone(),
                 tx_table: tx_table.clone(),
+                mpt_table,
                 challenges: challenges_expr.clone(),
             },
         );
@@ -325,6 +338,8 @@ impl SubCircuitConfig<Fr> for SuperCircuitConfig<Fr> {
             StateCircuitConfigArgs {
                 rw_table,
                 mpt_table,
+                u8_table,
+                u16_table,
                 challenges: challenges_expr.clone(),
             },
         );
@@ -386,7 +401,7 @@ impl SubCircuitConfig<Fr> for SuperCircuitConfig<Fr> {
             log::warn!("max_phase: {}", meta.max_phase());
         }
 
-        SuperCircuitConfig {
+        let config = SuperCircuitConfig {
             block_table,
             mpt_table,
             tx_table,
@@ -410,7 +425,8 @@ impl SubCircuitConfig<Fr> for SuperCircuitConfig<Fr> {
             ecc_circuit,
             #[cfg(feature = "zktrie")]
             mpt_circuit,
-        }
+        };
+        (config, layout)
     }
 }
 
@@ -426,6 +442,85 @@ pub struct SubcircuitRowUsage {
     pub row_num_total: usize,
 }
 
+/// Return the minimum number of rows required by each sub circuit to prove the
+/// block
+pub(crate) fn subcircuits_row_usage(block: &Block<Fr>) -> Vec<SubcircuitRowUsage> {
+    log::debug!("start min_num_rows_block_subcircuits");
+    let mut rows = Vec::new();
+    let mut push = |name, usage| {
+        log::debug!("{name} circuit row: {usage:?}");
+        rows.push((name, usage));
+    };
+    let evm = EvmCircuit::min_num_rows_block(block);
+    push("evm", evm);
+    let state = StateCircuit::min_num_rows_block(block);
+    push("state", state);
+    let bytecode = BytecodeCircuit::min_num_rows_block(block);
+    push("bytecode", bytecode);
+    let copy = CopyCircuit::min_num_rows_block(block);
+    push("copy", copy);
+    let keccak = KeccakCircuit::min_num_rows_block(block);
+    push("keccak", keccak);
+    let sha256 = SHA256Circuit::min_num_rows_block(block);
+    push("sha256", sha256);
+    let tx = TxCircuit::min_num_rows_block(block);
+    push("tx", tx);
+    let rlp = RlpCircuit::min_num_rows_block(block);
+    push("rlp", rlp);
+    let exp = ExpCircuit::min_num_rows_block(block);
+    push("exp", exp);
+    let mod_exp = ModExpCircuit::min_num_rows_block(block);
+    push("mod_exp", mod_exp);
+    let pi = PiCircuit::min_num_rows_block(block);
+    push("pi", pi);
+    let poseidon = PoseidonCircuit::min_num_rows_block(block);
+    push("poseidon", poseidon);
+    let sig = SigCircuit::min_num_rows_block(block);
+    push("sig", sig);
+    let ecc = EccCircuit::<Fr, 9>::min_num_rows_block(block);
+    push("ecc", ecc);
+    #[cfg(feature = "zktrie")]
+    {
+        let mpt = MptCircuit::<Fr>::min_num_rows_block(block);
+        push("mpt", mpt);
+    }
+
+    let row_usage_details = rows
+        .into_iter()
+        .map(|(name, (row_num_real, row_num_total))| SubcircuitRowUsage {
+            name: name.to_string(),
+            row_num_real,
+            row_num_total,
+        })
+        .collect_vec();
+    {
+        let mut row_usage_details_sorted = row_usage_details.clone();
+        row_usage_details_sorted.sort_by_key(|r| r.row_num_real);
+        row_usage_details_sorted.reverse();
+        for detail in &row_usage_details_sorted {
+            log::debug!("row detail {} {}", detail.name, detail.row_num_real);
+        }
+    }
+    row_usage_details
+}
+
+/// Return the number of rows that can't be used by any of the sub circuits
+pub(crate) fn subcircuits_unusable_rows() -> usize {
+    itertools::max([
+        EvmCircuit::<Fr>::unusable_rows(),
+        StateCircuit::<Fr>::unusable_rows(),
+        TxCircuit::<Fr>::unusable_rows(),
+        // TODO: The PiCircuit unusable_rows fn is not implemented
+        // and returns the arbitrary default number, causing overflow
+        // PiCircuit::<Fr>::unusable_rows(),
+        BytecodeCircuit::<Fr>::unusable_rows(),
+        CopyCircuit::<Fr>::unusable_rows(),
+        ExpCircuit::<Fr>::unusable_rows(),
+        KeccakCircuit::<Fr>::unusable_rows(),
+    ])
+    .unwrap()
+}
+
 /// The Super Circuit contains all the zkEVM circuits
 #[derive(Clone, Debug)]
 pub struct SuperCircuit<
@@ -490,63 +585,7 @@ impl<
     }
     /// Return the minimum number of rows required to prove the block
     pub fn min_num_rows_block_subcircuits(block: &Block<Fr>) -> Vec<SubcircuitRowUsage> {
-        log::debug!("start min_num_rows_block_subcircuits");
-        let mut rows = Vec::new();
-        let mut push = |name, usage| {
-            log::debug!("{name} circuit row: {usage:?}");
-            rows.push((name, usage));
-        };
-        let evm = EvmCircuit::min_num_rows_block(block);
-        push("evm", evm);
-        let state = StateCircuit::min_num_rows_block(block);
-        push("state", state);
-        let bytecode = BytecodeCircuit::min_num_rows_block(block);
-        push("bytecode", bytecode);
-        let copy = CopyCircuit::min_num_rows_block(block);
-        push("copy", copy);
-        let keccak = KeccakCircuit::min_num_rows_block(block);
-        push("keccak", keccak);
-        let sha256 = SHA256Circuit::min_num_rows_block(block);
-        push("sha256", sha256);
-        let tx = TxCircuit::min_num_rows_block(block);
-        push("tx", tx);
-        let rlp = RlpCircuit::min_num_rows_block(block);
-        push("rlp", rlp);
-        let exp = ExpCircuit::min_num_rows_block(block);
-        push("exp", exp);
-        let mod_exp = ModExpCircuit::min_num_rows_block(block);
-        push("mod_exp", mod_exp);
-        let pi = PiCircuit::min_num_rows_block(block);
-        push("pi", pi);
-        let poseidon = PoseidonCircuit::min_num_rows_block(block);
-        push("poseidon", poseidon);
-        let sig = SigCircuit::min_num_rows_block(block);
-        push("sig", sig);
-        let ecc = EccCircuit::<Fr, 9>::min_num_rows_block(block);
-        push("ecc", ecc);
-        #[cfg(feature = "zktrie")]
-        {
-            let mpt = MptCircuit::<Fr>::min_num_rows_block(block);
-            push("mpt", mpt);
-        }
-
-        let row_usage_details = rows
-            .into_iter()
-            .map(|(name, (row_num_real, row_num_total))| SubcircuitRowUsage {
-                name: name.to_string(),
-                row_num_real,
-                row_num_total,
-            })
-            .collect_vec();
-        {
-            let mut row_usage_details_sorted = row_usage_details.clone();
-            row_usage_details_sorted.sort_by_key(|r| r.row_num_real);
-            row_usage_details_sorted.reverse();
-            for detail in &row_usage_details_sorted {
-                log::debug!("row detail {} {}", detail.name, detail.row_num_real);
-            }
-        }
-        row_usage_details
+        subcircuits_row_usage(block)
     }
 }
 
@@ -564,19 +603,7 @@ impl<
     type Config = SuperCircuitConfig<Fr>;
 
     fn unusable_rows() -> usize {
-        itertools::max([
-            EvmCircuit::<Fr>::unusable_rows(),
-            StateCircuit::<Fr>::unusable_rows(),
-            TxCircuit::<Fr>::unusable_rows(),
-            // TODO: The PiCircuit unusable_rows fn is not implemented
-            // and returns the arbitrary default number, causing overflow
-            // PiCircuit::<Fr>::unusable_rows(),
-            BytecodeCircuit::<Fr>::unusable_rows(),
-            CopyCircuit::<Fr>::unusable_rows(),
-            ExpCircuit::<Fr>::unusable_rows(),
-            KeccakCircuit::<Fr>::unusable_rows(),
-        ])
-        .unwrap()
+        subcircuits_unusable_rows()
     }
 
     fn new_from_block(block: &Block<Fr>) -> Self {
@@ -704,6 +731,7 @@ impl<
             layouter,
             self.state_circuit.exports.borrow().as_ref(),
             self.evm_circuit.exports.borrow().as_ref(),
+            self.tx_circuit.exports.borrow().as_ref(),
         )?;
 
         log::debug!("assigning rlp_circuit");
@@ -0,0 +1,116 @@
+//! Circuit parameters sized to fit a witness block.
+//!
+//! Instead of hand-writing a [`CircuitsParams`] for a block, build it with a
+//! dynamic `max_rws` (0), and size the params from the witness block:
+//! ```rust, ignore
+//! let (params, k) = min_circuits_params_from_builder(&builder)?;
+//! ```
+
+use super::{subcircuits_row_usage, subcircuits_unusable_rows};
+use crate::{
+    bytecode_circuit::circuit::BytecodeCircuit,
+    copy_circuit::CopyCircuit,
+    evm_circuit::EvmCircuit,
+    exp_circuit::ExpCircuit,
+    keccak_circuit::KeccakCircuit,
+    modexp_circuit::ModExpCircuit,
+    pi_circuit::l1_queue_range,
+    poseidon_circuit::PoseidonCircuit,
+    rlp_circuit_fsm::RlpCircuit,
+    sha256_circuit::SHA256Circuit,
+    util::{log2_ceil, Challenges, SubCircuit},
+    witness::{block_convert, dynamic_max_rws, Block, RlpFsmWitnessGen, Transaction},
+};
+use bus_mapping::{
+    circuit_input_builder::{CircuitInputBuilder, CircuitsParams, PrecompileEcParams},
+    Error,
+};
+use halo2_proofs::{circuit::Value, halo2curves::bn256::Fr};
+
+#[cfg(feature = "zktrie")]
+use crate::mpt_circuit::MptCircuit;
+
+/// Returns the smallest [`CircuitsParams`] with which every sub circuit can
+/// prove `block`.
+///
+/// The rw table padding is fixed when the block is built, so the returned
+/// `max_rws` only matches the rws of `block` if it was built with a dynamic
+/// `max_rws`. Otherwise the block must be built again with the returned params.
+pub fn min_circuits_params(block: &Block<Fr>) -> CircuitsParams {
+    let max_txs = block.txs.len().max(1);
+    let max_calldata = block.txs.iter().map(|tx| tx.call_data.len()).sum::<usize>();
+
+    // the rlp circuit also encodes the padding txs
+    let challenges = Challenges::mock(Value::unknown(), Value::unknown(), Value::unknown());
+    let padding_rlp_rows = (max_txs - block.txs.len())
+        * RlpFsmWitnessGen::<Fr>::gen_sm_witness(&Transaction::dummy(block.chain_id), &challenges)
+            .len();
+    let rlp_rows = RlpCircuit::<Fr, Transaction>::min_num_rows_block(block).0 + padding_rlp_rows;
+    // the tx circuit requires `max_calldata < max_rlp_rows`, and the rlp state
+    // machine needs a row past the witness rows to end on at `q_last`
+    let max_rlp_rows = rlp_rows.max(max_calldata) + 1;
+
+    // sha256 and modexp circuits share the keccak circuit rows
+    let max_keccak_rows = KeccakCircuit::<Fr>::min_num_rows_block(block)
+        .0
+        .max(SHA256Circuit::min_num_rows_block(block).0)
+        .max(ModExpCircuit::<Fr>::min_num_rows_block(block).0);
+
+    let params = CircuitsParams {
+        max_rws: dynamic_max_rws(&block.end_block_last),
+        max_txs,
+        max_calldata,
+        max_rlp_rows,
+        max_copy_rows: CopyCircuit::<Fr>::min_num_rows_block(block).0,
+        max_inner_blocks: block.context.ctxs.len().max(1),
+        max_l1_msgs_popped: l1_queue_range(block, usize::MAX)
+            .map_or(0, |range| range.num_popped() as usize),
+        max_exp_steps: ExpCircuit::<Fr>::min_num_rows_block(block).0,
+        max_bytecode: BytecodeCircuit::<Fr>::min_num_rows_block(block).0
+            + BytecodeCircuit::<Fr>::unusable_rows(),
+        // EvmCircuit::get_num_rows_required adds the unused row
+        max_evm_rows: EvmCircuit::<Fr>::get_min_num_rows_required(block) - 1,
+        #[cfg(feature = "zktrie")]
+        max_mpt_rows: MptCircuit::<Fr>::min_num_rows_block(block).0 + 1,
+        #[cfg(not(feature = "zktrie"))]
+        max_mpt_rows: 0,
+        max_keccak_rows,
+        max_poseidon_rows: PoseidonCircuit::<Fr>::min_num_rows_block(block).0,
+        max_ec_ops: PrecompileEcParams {
+            ec_add: block.get_ec_add_ops().len().max(1),
+            ec_mul: block.get_ec_mul_ops().len().max(1),
+            ec_pairing: block.get_ec_pairing_ops().len().max(1),
+        },
+        max_vertical_circuit_rows: 0,
+        gas_table: block.circuits_params.gas_table,
+    };
+    log::debug!("min circuits params: {params:?}");
+    params
+}
+
+/// Returns the smallest degree of a super circuit proving `block` with
+/// `params`.
+pub fn min_degree(block: &Block<Fr>, params: CircuitsParams) -> u32 {
+    let block = Block {
+        circuits_params: params,
+        ..block.clone()
+    };
+    let rows_needed = subcircuits_row_usage(&block)
+        .iter()
+        .map(|usage| usage.row_num_total)
+        .max()
+        .unwrap();
+    let k = log2_ceil(subcircuits_unusable_rows() + rows_needed);
+    log::debug!("super circuit needs k = {k}, rows = {rows_needed}");
+    k
+}
+
+/// Returns the smallest [`CircuitsParams`] and degree for the circuit input
+/// of `builder`. See [`min_circuits_params`].
+pub fn min_circuits_params_from_builder(
+    builder: &CircuitInputBuilder,
+) -> Result<(CircuitsParams, u32), Error> {
+    let block = block_convert::<Fr>(&builder.block, &builder.code_db)?;
+    let params = min_circuits_params(&block);
+    Ok((params, min_degree(&block, params)))
+}
@@ -54,16 +54,88 @@ fn super_circuit_degree() {
     assert!(cs.degree() <= 9);
 }
 
+#[test]
+fn min_circuits_params_fit_block() {
+    let block = block_1tx();
+    let circuits_params = CircuitsParams {
+        max_rws: 0,
+        ..Default::default()
+    };
+    let mut builder = BlockData::new_from_geth_data_with_params(block.clone(), circuits_params)
+        .new_circuit_input_builder();
+    builder
+        .handle_block(&block.eth_block, &block.geth_traces)
+        .unwrap();
+
+    let (circuits_params, _) = params::min_circuits_params_from_builder(&builder).unwrap();
+    let mut block = block_convert::<Fr>(&builder.block, &builder.code_db).unwrap();
+    assert_eq!(circuits_params.max_rws, block.circuits_params.max_rws);
+    assert_eq!(circuits_params.max_txs, 1);
+
+    block.circuits_params = circuits_params;
+    for usage in subcircuits_row_usage(&block) {
+        assert!(usage.row_num_real <= usage.row_num_total, "{usage:?}");
+    }
+
+    // prove the block with the derived params at the derived degree
+    let k = params::min_degree(&block, circuits_params);
+    mock_prove_subcircuit::<EvmCircuit<Fr>>(k, &block);
+    mock_prove_subcircuit::<StateCircuit<Fr>>(k, &block);
+    mock_prove_subcircuit::<BytecodeCircuit<Fr>>(k, &block);
+    mock_prove_subcircuit::<CopyCircuit<Fr>>(k, &block);
+    mock_prove_subcircuit::<KeccakCircuit<Fr>>(k, &block);
+    mock_prove_subcircuit::<ExpCircuit<Fr>>(k, &block);
+    mock_prove_subcircuit::<RlpCircuit<Fr, Transaction>>(k, &block);
+}
+
+fn mock_prove_subcircuit<C: SubCircuit<Fr> + Circuit<Fr>>(k: u32, block: &Block<Fr>) {
+    let circuit = C::new_from_block(block);
+    let prover = MockProver::run(k, &circuit, circuit.instance()).unwrap();
+    prover.assert_satisfied_par();
+}
+
+// High memory usage test.  Run in serial with:
+// `cargo test [...] serial_ -- --ignored --test-threads 1`
+#[ignore]
 #[cfg(feature = "scroll")]
-fn test_super_circuit<
-    const MAX_TXS: usize,
-    const MAX_CALLDATA: usize,
-    const MAX_INNER_BLOCKS: usize,
-    const MOCK_RANDOMNESS: u64,
->(
-    l2_trace: BlockTrace,
-    circuits_params: CircuitsParams,
-) {
+#[test]
+fn serial_test_super_circuit_min_circuits_params() {
+    const MAX_TXS: usize = 1;
+    const MAX_CALLDATA: usize = 4;
+    const MAX_INNER_BLOCKS: usize = 1;
+    let l2_trace = block_1tx_ctx_with_input(vec![0xa9, 0x05, 0x9c, 0xbb])
+        .l2_trace()
+        .clone();
+
+    let block = super_circuit_witness_block(
+        l2_trace.clone(),
+        CircuitsParams {
+            max_rws: 0,
+            ..Default::default()
+        },
+    );
+    let circuits_params = params::min_circuits_params(&block);
+    let k = params::min_degree(&block, circuits_params);
+    assert_eq!(circuits_params.max_txs, MAX_TXS);
+    assert_eq!(circuits_params.max_calldata, MAX_CALLDATA);
+    assert_eq!(circuits_params.max_inner_blocks, MAX_INNER_BLOCKS);
+
+    // prove the block built with the derived params at the derived degree
+    let block = super_circuit_witness_block(l2_trace, circuits_params);
+    let circuit = SuperCircuit::<
+        Fr,
+        MAX_TXS,
+        MAX_CALLDATA,
+        MAX_INNER_BLOCKS,
+        TEST_MOCK_RANDOMNESS,
+    >::new_from_block(&block);
+    let instance = circuit.instance();
+    let prover = MockProver::run(k, &circuit, instance).unwrap();
+    prover.assert_satisfied_par();
+}
+
+#[cfg(feature = "scroll")]
+fn super_circuit_witness_block(l2_trace: BlockTrace, circuits_params: CircuitsParams) -> Block<Fr> {
     set_var("COINBASE", "0x0000000000000000000000000000000000000000");
     set_var("CHAIN_ID", MOCK_CHAIN_ID.to_string());
     let mut difficulty_be_bytes = [0u8; 32];
@@ -83,7 +155,20 @@ fn test_super_circuit<
         &mut block,
         &builder.mpt_init_state.expect("used non-light mode"),
     );
+    block
+}
 
+#[cfg(feature = "scroll")]
+fn test_super_circuit<
+    const MAX_TXS: usize,
+    const MAX_CALLDATA: usize,
+    const MAX_INNER_BLOCKS: usize,
+    const MOCK_RANDOMNESS: u64,
+>(
+    l2_trace: BlockTrace,
+    circuits_params: CircuitsParams,
+) {
+    let block = super_circuit_witness_block(l2_trace, circuits_params);
     let active_row_num =SuperCircuit::<
         Fr,
         MAX_TXS,
@@ -154,6 +239,10 @@ fn block_1tx_deploy() -> BlockTrace {
 }
 
 fn block_1tx_ctx() -> TestContext<2, 1> {
+    block_1tx_ctx_with_input(vec![])
+}
+
+fn block_1tx_ctx_with_input(input: Vec<u8>) -> TestContext<2, 1> {
     let mut rng = ChaCha20Rng::seed_from_u64(2);
 
     let chain_id = MOCK_CHAIN_ID;
@@ -181,6 +270,7 @@ fn block_1tx_ctx() -> TestContext<2, 1> {
             txs[0]
                 .from(wallet_a)
                 .to(accs[0].address)
+                .input(input.into())
                 .gas(Word::from(1_000_000u64));
         },
         |block, _tx| block.number(0xcafeu64),
@@ -19,12 +19,14 @@ use bus_mapping::{
         BigModExp, CopyDataType, CopyEvent, CopyStep, EcAddOp, EcMulOp, EcPairingOp, ExpEvent,
         PrecompileEcParams, N_BYTES_PER_PAIR, N_PAIRING_PER_OP,
     },
+    operation::Target,
     precompile::PrecompileCalls,
 };
 use core::iter::once;
 use eth_types::{sign_types::SignData, Field, ToLittleEndian, ToScalar, ToWord, Word, U256};
 use gadgets::{
     binary_number::{BinaryNumberChip, BinaryNumberConfig},
+    range_table::{RangeTableChip, RangeTableConfig},
     util::{and, not, split_u256, split_u256_limb64, Expr},
 };
 use halo2_proofs::{
@@ -33,6 +35,7 @@ use halo2_proofs::{
     plonk::{Advice, Any, Column, ConstraintSystem, Error, Expression, Fixed, VirtualCells},
     poly::Rotation,
 };
+use serde::{Deserialize, Serialize};
 use snark_verifier::util::arithmetic::PrimeCurveAffine;
 
 use std::iter::repeat;
@@ -44,7 +47,7 @@ use halo2_proofs::plonk::SecondPhase;
 
 use halo2_proofs::plonk::TableColumn;
 use itertools::Itertools;
-use keccak256::plain::Keccak;
+use keccak256::keccak256;
 use std::array;
 use strum_macros::{EnumCount, EnumIter};
 
@@ -190,6 +193,12 @@ pub enum TxFieldTag {
     MaxPriorityFeePerGas,
     /// Max Fee Per Gas (EIP1559)
     MaxFeePerGas,
+    /// Max Fee Per Blob Gas (EIP4844)
+    MaxFeePerBlobGas,
+    /// Blob versioned hash count (EIP4844)
+    BlobVersionedHashesLen,
+    /// Blob versioned hash (EIP4844)
+    BlobVersionedHash,
 }
 impl_expr!(TxFieldTag);
 
@@ -348,7 +357,7 @@ impl TxTable {
                     }
                 }
 
-                // Assign dynamic calldata and access list section
+                // Assign dynamic calldata, access list and blob hashes section
                 for tx in txs.iter().chain(padding_txs.iter()) {
                     for row in tx.table_assignments_dyn(*challenges).into_iter() {
                         assign_row(
@@ -377,6 +386,21 @@ impl TxTable {
                         )?;
                         offset += 1;
                     }
+                    for row in tx
+                        .table_assignments_blob_hashes_dyn(*challenges)
+                        .into_iter()
+                    {
+                        assign_row(
+                            &mut region,
+                            offset,
+                            self.q_enable,
+                            &advice_columns,
+                            &self.tag,
+                            &row,
+                            "",
+                        )?;
+                        offset += 1;
+                    }
                 }
 
                 Ok(tx_value_cells)
@@ -421,7 +445,7 @@ impl<F: Field> LookupTable<F> for TxTable {
 }
 
 /// Tag to identify the operation type in a RwTable row
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, EnumIter)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, EnumIter, Serialize, Deserialize)]
 pub enum RwTableTag {
     /// Start (used for padding)
     Start = 1,
@@ -468,8 +492,46 @@ impl From<RwTableTag> for usize {
     }
 }
 
+impl From<RwTableTag> for Target {
+    fn from(t: RwTableTag) -> Self {
+        match t {
+            RwTableTag::Start => Target::Start,
+            RwTableTag::Stack => Target::Stack,
+            RwTableTag::Memory => Target::Memory,
+            RwTableTag::TxAccessListAccount => Target::TxAccessListAccount,
+            RwTableTag::TxAccessListAccountStorage => Target::TxAccessListAccountStorage,
+            RwTableTag::TxRefund => Target::TxRefund,
+            RwTableTag::Account => Target::Account,
+            RwTableTag::AccountStorage => Target::Storage,
+            RwTableTag::CallContext => Target::CallContext,
+            RwTableTag::TxLog => Target::TxLog,
+            RwTableTag::TxReceipt => Target::TxReceipt,
+        }
+    }
+}
+
+impl From<Target> for RwTableTag {
+    fn from(t: Target) -> Self {
+        match t {
+            Target::Start => RwTableTag::Start,
+            Target::Stack => RwTableTag::Stack,
+            Target::Memory => RwTableTag::Memory,
+            Target::TxAccessListAccount => RwTableTag::TxAccessListAccount,
+            Target::TxAccessListAccountStorage => RwTableTag::TxAccessListAccountStorage,
+            Target::TxRefund => RwTableTag::TxRefund,
+            Target::Account => RwTableTag::Account,
+            Target::Storage => RwTableTag::AccountStorage,
+            Target::CallContext => RwTableTag::CallContext,
+            Target::TxLog => RwTableTag::TxLog,
+            Target::TxReceipt => RwTableTag::TxReceipt,
+        }
+    }
+}
+
 /// Tag for an AccountField in RwTable
-#[derive(Clone, Copy, Debug, EnumIter, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(
+    Clone, Copy, Debug, EnumIter, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize,
+)]
 pub enum AccountFieldTag {
     /// Nonce field
     Nonce,
@@ -487,7 +549,7 @@ pub enum AccountFieldTag {
 impl_expr!(AccountFieldTag);
 
 /// Tag for a TxLogField in RwTable
-#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter, Serialize, Deserialize)]
 pub enum TxLogFieldTag {
     /// Address field
     Address = 1,
@@ -499,7 +561,7 @@ pub enum TxLogFieldTag {
 impl_expr!(TxLogFieldTag);
 
 /// Tag for a TxReceiptField in RwTable
-#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter, EnumCount)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter, EnumCount, Serialize, Deserialize)]
 pub enum TxReceiptFieldTag {
     /// Tx result
     PostStateOrStatus = 1,
@@ -511,7 +573,7 @@ pub enum TxReceiptFieldTag {
 impl_expr!(TxReceiptFieldTag);
 
 /// Tag for a CallContextField in RwTable
-#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter, Serialize, Deserialize)]
 pub enum CallContextFieldTag {
     /// RwCounterEndOfReversion
     RwCounterEndOfReversion = 1,
@@ -1453,15 +1515,10 @@ impl KeccakTable {
             .keccak_input()
             .map(|challenge| rlc::value(input.iter().rev(), challenge));
         let input_len = F::from(input.len() as u64);
-        let mut keccak = Keccak::default();
-        keccak.update(input);
-        let output = keccak.digest();
-        let output_rlc = challenges.evm_word().map(|challenge| {
-            rlc::value(
-                &Word::from_big_endian(output.as_slice()).to_le_bytes(),
-                challenge,
-            )
-        });
+        let output = keccak256(input);
+        let output_rlc = challenges
+            .evm_word()
+            .map(|challenge| rlc::value(&Word::from_big_endian(&output).to_le_bytes(), challenge));
 
         vec![[
             Value::known(F::one()),
@@ -3109,45 +3166,57 @@ impl<F: Field> LookupTable<F> for PowOfRandTable {
     }
 }
 
-/// Lookup table for [0, MAX) range
+/// Lookup table for [0, 1 << N_BITS) range, which can be shared by the
+/// sub-circuits.
 #[derive(Clone, Copy, Debug)]
-pub struct RangeTable<const MAX: usize>(TableColumn);
+pub struct RangeTable<const N_BITS: usize>(RangeTableConfig<N_BITS>);
 
 /// Type Alias of u8 table, [0, 1 << 8)
-pub type U8Table = RangeTable<{ 1 << 8 }>;
+pub type U8Table = RangeTable<8>;
 /// Type Alias of u16 table, [0, 1 << 16)
-pub type U16Table = RangeTable<{ 1 << 16 }>;
+pub type U16Table = RangeTable<16>;
 
-impl<const MAX: usize> RangeTable<MAX> {
+impl<const N_BITS: usize> RangeTable<N_BITS> {
     /// Construct the range table.
     pub fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
-        let inner = meta.lookup_table_column();
-        meta.annotate_lookup_column(inner, || format!("range table [0, {MAX})"));
-        Self(inner)
+        Self(RangeTableChip::configure(meta))
     }
 
     /// Assign values to the table.
     pub fn load<F: Field>(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
-        layouter.assign_table(
-            || format!("range table [0, {MAX})"),
-            |mut table| {
-                for i in 0..MAX {
-                    table.assign_cell(
-                        || format!("range at offset = {i}"),
-                        self.0,
-                        i,
-                        || Value::known(F::from(i as u64)),
-                    )?;
-                }
+        RangeTableChip::construct(self.0).load(layouter)
+    }
 
-                Ok(())
-            },
-        )
+    /// Check that the expression is in [0, 1 << N_BITS).
+    pub fn range_check<F: Field>(
+        &self,
+        meta: &mut ConstraintSystem<F>,
+        name: &'static str,
+        expr_fn: impl FnOnce(&mut VirtualCells<'_, F>) -> Expression<F>,
+    ) {
+        self.0.range_check(meta, name, expr_fn)
+    }
+
+    /// Check that the expression is in [0, 1 << n_bits), for n_bits <= N_BITS.
+    pub fn range_check_bits<F: Field>(
+        &self,
+        meta: &mut ConstraintSystem<F>,
+        name: &'static str,
+        n_bits: usize,
+        expr_fn: impl Fn(&mut VirtualCells<'_, F>) -> Expression<F>,
+    ) {
+        self.0.range_check_bits(meta, name, n_bits, expr_fn)
+    }
+}
+
+impl<const N_BITS: usize> From<RangeTable<N_BITS>> for TableColumn {
+    fn from(table: RangeTable<N_BITS>) -> TableColumn {
+        table.0.table
     }
 }
 
-impl<const MAX: usize> From<RangeTable<MAX>> for TableColumn {
-    fn from(table: RangeTable<MAX>) -> TableColumn {
+impl<const N_BITS: usize> From<RangeTable<N_BITS>> for RangeTableConfig<N_BITS> {
+    fn from(table: RangeTable<N_BITS>) -> RangeTableConfig<N_BITS> {
         table.0
     }
 }
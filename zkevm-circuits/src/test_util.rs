@@ -4,6 +4,7 @@ use crate::{
     copy_circuit::CopyCircuit,
     evm_circuit::EvmCircuit,
     state_circuit::StateCircuit,
+    super_circuit::params::min_circuits_params,
     util::{log2_ceil, SubCircuit},
     witness::{Block, Rw},
 };
@@ -15,7 +16,7 @@ use halo2_proofs::{
     dev::{unwrap_value, MockProver},
     halo2curves::bn256::Fr,
 };
-use mock::TestContext;
+use mock::{ChunkTestContext, TestContext};
 
 #[cfg(feature = "scroll")]
 use bus_mapping::circuit_input_builder::CircuitInputBuilder;
@@ -80,7 +81,9 @@ fn init_env_logger() {
 /// ```
 pub struct CircuitTestBuilder<const NACC: usize, const NTX: usize> {
     test_ctx: Option<TestContext<NACC, NTX>>,
+    chunk_ctx: Option<ChunkTestContext<NACC>>,
     circuits_params: Option<CircuitsParams>,
+    auto_params: bool,
     block: Option<Block<Fr>>,
     evm_checks: Option<Box<dyn Fn(MockProver<Fr>, &Vec<usize>, &Vec<usize>)>>,
     state_checks: Option<Box<dyn Fn(MockProver<Fr>, &Vec<usize>, &Vec<usize>)>>,
@@ -93,7 +96,9 @@ impl<const NACC: usize, const NTX: usize> CircuitTestBuilder<NACC, NTX> {
     fn empty() -> Self {
         CircuitTestBuilder {
             test_ctx: None,
+            chunk_ctx: None,
             circuits_params: None,
+            auto_params: false,
             block: None,
             evm_checks: Some(Box::new(|prover, gate_rows, lookup_rows| {
                 assert_eq!(prover.verify_at_rows_par(
@@ -123,6 +128,13 @@ impl<const NACC: usize, const NTX: usize> CircuitTestBuilder<NACC, NTX> {
         Self::empty().test_ctx(ctx)
     }
 
+    /// Generates a CTBC from a [`ChunkTestContext`] passed with all the other
+    /// fields set to [`Default`]. `NTX` is the total number of txs of the
+    /// chunk.
+    pub fn new_from_chunk_ctx(ctx: ChunkTestContext<NACC>) -> Self {
+        Self::empty().chunk_ctx(ctx)
+    }
+
     /// Generates a CTBC from a [`Block`] passed with all the other fields
     /// set to [`Default`].
     pub fn new_from_block(block: Block<Fr>) -> Self {
@@ -136,6 +148,13 @@ impl<const NACC: usize, const NTX: usize> CircuitTestBuilder<NACC, NTX> {
         self
     }
 
+    /// Allows to produce a [`ChunkTestContext`] whose blocks will all be
+    /// handled into a single witness Block.
+    pub fn chunk_ctx(mut self, ctx: ChunkTestContext<NACC>) -> Self {
+        self.chunk_ctx = Some(ctx);
+        self
+    }
+
     /// Allows to pass a non-default [`CircuitsParams`] to the builder.
     /// This means that we can increase for example, the `max_rws` or `max_txs`.
     pub fn params(mut self, params: CircuitsParams) -> Self {
@@ -147,6 +166,13 @@ impl<const NACC: usize, const NTX: usize> CircuitTestBuilder<NACC, NTX> {
         self
     }
 
+    /// Sizes the [`CircuitsParams`] to the smallest ones that fit the block,
+    /// instead of using the default or provided ones.
+    pub fn auto_params(mut self) -> Self {
+        self.auto_params = true;
+        self
+    }
+
     /// Allows to pass a [`Block`] already built to the constructor.
     pub fn block(mut self, block: Block<Fr>) -> Self {
         self.block = Some(block);
@@ -209,10 +235,21 @@ impl<const NACC: usize, const NTX: usize> CircuitTestBuilder<NACC, NTX> {
             self.circuits_params.unwrap_or_default()
         };
         params.max_txs = NTX;
+        if self.auto_params {
+            // the rw table is sized when the block is built
+            params.max_rws = 0;
+        }
         log::debug!("params in CircuitTestBuilder: {:?}", params);
 
-        let block: Block<Fr> = if self.block.is_some() {
-            self.block.unwrap()
+        let block: Block<Fr> = if let Some(mut block) = self.block {
+            if self.auto_params {
+                // the rws of the block are already padded to its max_rws
+                block.circuits_params = CircuitsParams {
+                    max_rws: block.circuits_params.max_rws,
+                    ..min_circuits_params(&block)
+                };
+            }
+            block
         } else if self.test_ctx.is_some() {
             // use scroll l2 trace
             let full_witness_block = false;
@@ -250,6 +287,23 @@ impl<const NACC: usize, const NTX: usize> CircuitTestBuilder<NACC, NTX> {
                 // Build a witness block from trace result.
                 crate::witness::block_convert(&builder.block, &builder.code_db).unwrap()
             };
+            if self.auto_params {
+                block.circuits_params = min_circuits_params(&block);
+            }
+
+            for modifier_fn in self.block_modifiers {
+                modifier_fn.as_ref()(&mut block);
+            }
+            block
+        } else if let Some(chunk_ctx) = self.chunk_ctx {
+            let blocks: Vec<GethData> = chunk_ctx.into();
+            let builder = BlockData::handle_chunk(&blocks, params).unwrap();
+            // Build a witness block from the trace results of all the blocks.
+            let mut block =
+                crate::witness::block_convert(&builder.block, &builder.code_db).unwrap();
+            if self.auto_params {
+                block.circuits_params = min_circuits_params(&block);
+            }
 
             for modifier_fn in self.block_modifiers {
                 modifier_fn.as_ref()(&mut block);
@@ -19,9 +19,10 @@ use crate::{
         BlockContextFieldTag::{CumNumTxs, NumAllTxs, NumTxs},
         BlockTable, KeccakTable, LookupTable, RlpFsmRlpTable as RlpTable, SigTable, TxFieldTag,
         TxFieldTag::{
-            AccessListAddressesLen, AccessListRLC, AccessListStorageKeysLen, BlockNumber, CallData,
-            CallDataGasCost, CallDataLength, CallDataRLC, CalleeAddress, CallerAddress, ChainID,
-            Gas, GasPrice, IsCreate, MaxFeePerGas, MaxPriorityFeePerGas, Nonce, SigR, SigS, SigV,
+            AccessListAddressesLen, AccessListRLC, AccessListStorageKeysLen, BlobVersionedHash,
+            BlobVersionedHashesLen, BlockNumber, CallData, CallDataGasCost, CallDataLength,
+            CallDataRLC, CalleeAddress, CallerAddress, ChainID, Gas, GasPrice, IsCreate,
+            MaxFeePerBlobGas, MaxFeePerGas, MaxPriorityFeePerGas, Nonce, SigR, SigS, SigV,
             TxDataGasCost, TxHashLength, TxHashRLC, TxSignHash, TxSignLength, TxSignRLC,
         },
         TxTable, U16Table, U8Table,
@@ -34,8 +35,8 @@ use crate::{
     witness::{
         rlp_fsm::{Tag, ValueTagLength},
         Format::{
-            L1MsgHash, TxHashEip155, TxHashEip1559, TxHashEip2930, TxHashPreEip155, TxSignEip155,
-            TxSignEip1559, TxSignEip2930, TxSignPreEip155,
+            L1MsgHash, TxHashEip155, TxHashEip1559, TxHashEip2930, TxHashEip4844, TxHashPreEip155,
+            TxSignEip155, TxSignEip1559, TxSignEip2930, TxSignEip4844, TxSignPreEip155,
         },
         RlpTag,
         RlpTag::{GasCost, Len, Null, RLC},
@@ -47,7 +48,7 @@ use bus_mapping::circuit_input_builder::keccak_inputs_sign_verify;
 use eth_types::{
     geth_types::{
         access_list_size, TxType,
-        TxType::{Eip155, Eip1559, Eip2930, L1Msg, PreEip155},
+        TxType::{Eip155, Eip1559, Eip2930, Eip4844, L1Msg, PreEip155},
     },
     sign_types::SignData,
     AccessList, Address, Field, ToAddress, ToBigEndian, ToScalar,
@@ -61,8 +62,8 @@ use gadgets::{
     util::{and, not, select, sum, Expr},
 };
 use halo2_proofs::{
-    circuit::{AssignedCell, Layouter, Region, Value},
-    plonk::{Advice, Column, ConstraintSystem, Error, Expression, VirtualCells},
+    circuit::{AssignedCell, Cell, Layouter, Region, Value},
+    plonk::{Advice, Assigned, Column, ConstraintSystem, Error, Expression, VirtualCells},
     poly::Rotation,
 };
 use log::error;
@@ -83,11 +84,22 @@ use halo2_proofs::plonk::SecondPhase;
 use itertools::Itertools;
 
 /// Number of rows of one tx occupies in the fixed part of tx table
-pub const TX_LEN: usize = 28;
+pub const TX_LEN: usize = 30;
 /// Offset of TxHash tag in the tx table
 pub const TX_HASH_OFFSET: usize = 21;
 /// Offset of ChainID tag in the tx table
 pub const CHAIN_ID_OFFSET: usize = 12;
+/// Offset of Nonce tag in the tx table
+pub const NONCE_OFFSET: usize = 1;
+/// Offset of TxType tag in the tx table
+pub const TX_TYPE_OFFSET: usize = 22;
+
+/// Circuit exported cells after synthesis, used for subcircuit
+#[derive(Clone, Debug)]
+pub struct TxCircuitExports<V> {
+    /// number of l1 msgs popped before the first tx, i.e. the start l1 queue index
+    pub start_l1_queue_index: (Cell, Value<V>),
+}
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 enum LookupCondition {
@@ -101,6 +113,8 @@ enum LookupCondition {
     Keccak,
     // lookup into dynamic access list section of tx table
     TxAccessList,
+    // lookup into dynamic blob versioned hashes section of tx table
+    TxBlobHashes,
 }
 
 #[derive(Clone, Debug)]
@@ -122,7 +136,7 @@ pub struct TxCircuitConfig<F: Field> {
     // A selector which is enabled at 1st row
     q_first: Column<Fixed>,
     tx_table: TxTable,
-    tx_tag_bits: BinaryNumberConfig<TxFieldTag, 5>,
+    tx_tag_bits: BinaryNumberConfig<TxFieldTag, 6>,
 
     tx_type: Column<Advice>,
     tx_type_bits: BinaryNumberConfig<TxType, 3>,
@@ -147,11 +161,13 @@ pub struct TxCircuitConfig<F: Field> {
 
     /// Columns used to reduce degree
     is_tag_block_num: Column<Advice>,
+    is_tag_nonce: Column<Advice>,
     is_calldata: Column<Advice>,
     is_caller_address: Column<Advice>,
     is_l1_msg: Column<Advice>,
     is_eip2930: Column<Advice>,
     is_eip1559: Column<Advice>,
+    is_eip4844: Column<Advice>,
     is_chain_id: Column<Advice>,
     is_tx_id_zero: Column<Advice>,
     lookup_conditions: HashMap<LookupCondition, Column<Advice>>,
@@ -212,6 +228,10 @@ pub struct TxCircuitConfig<F: Field> {
     // no ommittance in access list dynamic section
     field_rlc: Column<Advice>,
 
+    // Blob versioned hashes columns
+    // blob versioned hash tag denoter, reduces degree
+    is_blob_versioned_hash: Column<Advice>,
+
     _marker: PhantomData<F>,
 }
 
@@ -304,11 +324,13 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
         let is_l1_msg = meta.advice_column();
         let is_eip2930 = meta.advice_column();
         let is_eip1559 = meta.advice_column();
+        let is_eip4844 = meta.advice_column();
         let is_calldata = meta.advice_column();
         let is_tx_id_zero = meta.advice_column();
         let is_caller_address = meta.advice_column();
         let is_chain_id = meta.advice_column();
         let is_tag_block_num = meta.advice_column();
+        let is_tag_nonce = meta.advice_column();
         let lookup_conditions = [
             LookupCondition::TxCalldata,
             LookupCondition::L1MsgHash,
@@ -316,6 +338,7 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             LookupCondition::RlpHashTag,
             LookupCondition::Keccak,
             LookupCondition::TxAccessList,
+            LookupCondition::TxBlobHashes,
         ]
         .into_iter()
         .map(|condition| (condition, meta.advice_column()))
@@ -330,9 +353,14 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
         let is_access_list_storage_key = meta.advice_column();
         let field_rlc = meta.advice_column();
 
+        // blob versioned hashes columns
+        let is_blob_versioned_hash = meta.advice_column();
+
         // TODO: add lookup to SignVerify table for sv_address
         let sv_address = meta.advice_column();
         meta.enable_equality(tx_table.value);
+        // copy the l1 msgs popped before the first tx to the pi circuit
+        meta.enable_equality(total_l1_popped_before);
 
         let log_deg = |s: &'static str, meta: &mut ConstraintSystem<F>| {
             debug_assert!(meta.degree() <= 9);
@@ -389,6 +417,9 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
         is_tx_tag!(is_tag_access_list_storage_key, AccessListStorageKey);
         is_tx_tag!(is_max_fee_per_gas, MaxFeePerGas);
         is_tx_tag!(is_max_priority_fee_per_gas, MaxPriorityFeePerGas);
+        is_tx_tag!(is_max_fee_per_blob_gas, MaxFeePerBlobGas);
+        is_tx_tag!(is_blob_versioned_hashes_len, BlobVersionedHashesLen);
+        is_tx_tag!(is_tag_blob_versioned_hash, BlobVersionedHash);
 
         let tx_id_unchanged = IsEqualChip::configure(
             meta,
@@ -397,22 +428,18 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             |meta| meta.query_advice(tx_table.tx_id, Rotation::next()),
         );
 
-        // testing if value is zero for tags
+        // testing if value is zero for tags. It's used by
+        // 1. CallerAddress: if caller_address is zero, then skip the sig verify.
+        // 2. CallDataLength: if call_data_length is zero, then skip lookup to tx table for call
+        //    data.
+        // 3. CallData: if call data byte is zero, then gas_cost = 4 (16 otherwise).
+        // 4. AccessListAddressesLen and BlobVersionedHashesLen: if zero, then skip lookup to the
+        //    dynamic section.
+        // It's enabled on every row (instead of only on the rows with above tags) to keep
+        // the degree low, as value is assigned on every row anyway.
         let value_is_zero = IsZeroChip::configure(
             meta,
-            |meta| {
-                and::expr(vec![
-                    meta.query_fixed(q_enable, Rotation::cur()),
-                    sum::expr(vec![
-                        // if caller_address is zero, then skip the sig verify.
-                        is_caller_addr(meta),
-                        // if call_data_length is zero, then skip lookup to tx table for call data
-                        is_data_length(meta),
-                        // if call data byte is zero, then gas_cost = 4 (16 otherwise)
-                        is_data(meta),
-                    ]),
-                ])
-            },
+            |meta| meta.query_fixed(q_enable, Rotation::cur()),
             tx_table.value,
             |meta| meta.advice_column_in(SecondPhase), // value is at 2nd phase
         );
@@ -435,7 +462,7 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             let mut cb = BaseConstraintBuilder::default();
 
             // if tag_next == Nonce, then tx_id' = tx_id + 1
-            cb.condition(tag_bits.value_equals(Nonce, Rotation::next())(meta), |cb| {
+            cb.condition(meta.query_advice(is_tag_nonce, Rotation::next()), |cb| {
                 cb.require_equal(
                     "tx_id increments",
                     meta.query_advice(tx_table.tx_id, Rotation::next()),
@@ -444,7 +471,7 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             });
             // if tag_next != Nonce, then tx_id' = tx_id, tx_type' = tx_type
             cb.condition(
-                not::expr(tag_bits.value_equals(Nonce, Rotation::next())(meta)),
+                not::expr(meta.query_advice(is_tag_nonce, Rotation::next())),
                 |cb| {
                     cb.require_equal(
                         "tx_id does not change",
@@ -518,6 +545,11 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
                     is_max_priority_fee_per_gas(meta),
                     Tag::MaxPriorityFeePerGas.into(),
                 ),
+                (is_max_fee_per_blob_gas(meta), Tag::MaxFeePerBlobGas.into()),
+                (
+                    is_tag_blob_versioned_hash(meta),
+                    Tag::BlobVersionedHash.into(),
+                ),
                 // tx tags which correspond to Null
                 (is_null(meta), Null),
                 (is_create(meta), Null),
@@ -532,6 +564,7 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
                 (is_access_list_addresses_len(meta), Null),
                 (is_access_list_storage_keys_len(meta), Null),
                 (is_access_list_rlc(meta), RLC),
+                (is_blob_versioned_hashes_len(meta), Null),
             ];
 
             cb.require_boolean(
@@ -548,6 +581,7 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
                     usize::from(L1Msg).expr(),
                     usize::from(Eip2930).expr(),
                     usize::from(Eip1559).expr(),
+                    usize::from(Eip4844).expr(),
                 ],
             );
 
@@ -609,9 +643,33 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             cb.condition(
                 and::expr([is_data_rlc(meta), not::expr(is_none_expr)]),
                 |cb| {
-                    cb.require_zero(
+                    // we rely on the assumption that CallDataLength is after CallDataRLC.
+                    // The TxCalldata lookup condition at CallDataLength row is equal to
+                    // (CallDataLength != 0), and it has lower degree than value_is_zero.
+                    cb.require_equal(
                         "CallDataLength != 0",
-                        value_is_zero.expr(Rotation::next())(meta),
+                        meta.query_advice(
+                            lookup_conditions[&LookupCondition::TxCalldata],
+                            Rotation::next(),
+                        ),
+                        1.expr(),
+                    );
+                },
+            );
+
+            // only eip4844 tx has blob related fields
+            cb.condition(
+                and::expr([
+                    not::expr(meta.query_advice(is_eip4844, Rotation::cur())),
+                    sum::expr([
+                        is_max_fee_per_blob_gas(meta),
+                        is_blob_versioned_hashes_len(meta),
+                    ]),
+                ]),
+                |cb| {
+                    cb.require_zero(
+                        "MaxFeePerBlobGas == 0 and BlobVersionedHashesLen == 0 for non eip4844 tx",
+                        meta.query_advice(tx_table.value, Rotation::cur()),
                     );
                 },
             );
@@ -718,8 +776,32 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
         });
 
+        meta.create_gate("is_tag_nonce", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            cb.require_equal(
+                "is_tag_nonce = (tag == Nonce)",
+                is_nonce(meta),
+                meta.query_advice(is_tag_nonce, Rotation::cur()),
+            );
+
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
+        meta.create_gate("is_blob_versioned_hash", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            cb.require_equal(
+                "is_blob_versioned_hash = (tag == BlobVersionedHash)",
+                is_tag_blob_versioned_hash(meta),
+                meta.query_advice(is_blob_versioned_hash, Rotation::cur()),
+            );
+
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
         meta.create_gate(
-            "distinguish tx type: is_l1_msg, is_eip2930, is_eip1559",
+            "distinguish tx type: is_l1_msg, is_eip2930, is_eip1559, is_eip4844",
             |meta| {
                 let mut cb = BaseConstraintBuilder::default();
 
@@ -741,6 +823,12 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
                     tx_type_bits.value_equals(Eip1559, Rotation::cur())(meta),
                 );
 
+                cb.require_equal(
+                    "is_eip4844 = (tx_type == Eip4844)",
+                    meta.query_advice(is_eip4844, Rotation::cur()),
+                    tx_type_bits.value_equals(Eip4844, Rotation::cur())(meta),
+                );
+
                 cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
             },
         );
@@ -781,13 +869,34 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
         });
 
+        meta.create_gate("lookup to blob hashes dynamic section condition", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            cb.require_equal(
+                "condition",
+                and::expr([
+                    is_blob_versioned_hashes_len(meta),
+                    not::expr(value_is_zero.expr(Rotation::cur())(meta)),
+                ]),
+                meta.query_advice(
+                    lookup_conditions[&LookupCondition::TxBlobHashes],
+                    Rotation::cur(),
+                ),
+            );
+
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
         meta.create_gate("sign tag lookup into RLP table condition", |meta| {
             let mut cb = BaseConstraintBuilder::default();
 
             let is_tag_in_tx_sign = sum::expr([
                 is_nonce(meta),
                 and::expr([
-                    not::expr(meta.query_advice(is_eip1559, Rotation::cur())),
+                    not::expr(sum::expr([
+                        meta.query_advice(is_eip1559, Rotation::cur()),
+                        meta.query_advice(is_eip4844, Rotation::cur()),
+                    ])),
                     is_gas_price(meta),
                 ]),
                 is_gas(meta),
@@ -800,16 +909,27 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
                         tx_type_bits.value_equals(Eip155, Rotation::cur())(meta),
                         meta.query_advice(is_eip2930, Rotation::cur()),
                         meta.query_advice(is_eip1559, Rotation::cur()),
+                        meta.query_advice(is_eip4844, Rotation::cur()),
                     ]),
                 ]),
                 and::expr([
-                    meta.query_advice(is_eip1559, Rotation::cur()),
+                    sum::expr([
+                        meta.query_advice(is_eip1559, Rotation::cur()),
+                        meta.query_advice(is_eip4844, Rotation::cur()),
+                    ]),
                     is_max_fee_per_gas(meta),
                 ]),
                 and::expr([
-                    meta.query_advice(is_eip1559, Rotation::cur()),
+                    sum::expr([
+                        meta.query_advice(is_eip1559, Rotation::cur()),
+                        meta.query_advice(is_eip4844, Rotation::cur()),
+                    ]),
                     is_max_priority_fee_per_gas(meta),
                 ]),
+                and::expr([
+                    meta.query_advice(is_eip4844, Rotation::cur()),
+                    is_max_fee_per_blob_gas(meta),
+                ]),
                 is_sign_length(meta),
                 is_sign_rlc(meta),
             ]);
@@ -835,7 +955,10 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             let is_tag_in_tx_hash = sum::expr([
                 is_nonce(meta),
                 and::expr([
-                    not::expr(meta.query_advice(is_eip1559, Rotation::cur())),
+                    not::expr(sum::expr([
+                        meta.query_advice(is_eip1559, Rotation::cur()),
+                        meta.query_advice(is_eip4844, Rotation::cur()),
+                    ])),
                     is_gas_price(meta),
                 ]),
                 is_gas(meta),
@@ -849,13 +972,23 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
                 is_hash_length(meta),
                 is_hash_rlc(meta),
                 and::expr([
-                    meta.query_advice(is_eip1559, Rotation::cur()),
+                    sum::expr([
+                        meta.query_advice(is_eip1559, Rotation::cur()),
+                        meta.query_advice(is_eip4844, Rotation::cur()),
+                    ]),
                     is_max_fee_per_gas(meta),
                 ]),
                 and::expr([
-                    meta.query_advice(is_eip1559, Rotation::cur()),
+                    sum::expr([
+                        meta.query_advice(is_eip1559, Rotation::cur()),
+                        meta.query_advice(is_eip4844, Rotation::cur()),
+                    ]),
                     is_max_priority_fee_per_gas(meta),
                 ]),
+                and::expr([
+                    meta.query_advice(is_eip4844, Rotation::cur()),
+                    is_max_fee_per_blob_gas(meta),
+                ]),
             ]);
 
             cb.require_equal(
@@ -937,6 +1070,7 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             is_l1_msg,
             is_eip2930,
             is_eip1559,
+            is_eip4844,
             sv_address,
             calldata_gas_cost_acc,
             section_rlc,
@@ -951,6 +1085,7 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             al_idx,
             sk_idx,
             sks_acc,
+            is_blob_versioned_hash,
         );
 
         meta.create_gate("tx_gas_cost == 0 for L1 msg", |meta| {
@@ -1016,22 +1151,26 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             |meta| meta.query_advice(block_num, Rotation::cur()),
         );
 
-        meta.lookup("block_num is non-decreasing till padding txs", |meta| {
-            // Block nums like this [1, 3, 5, 4, 0] is rejected by this. But [1, 2, 3, 5, 0] is
-            // acceptable.
-            let lookup_condition = and::expr([
-                // next row should not belong to a padding tx
-                not::expr(meta.query_advice(is_padding_tx, Rotation::next())),
-                // next row should not be in the calldata region
-                not::expr(meta.query_advice(is_calldata, Rotation::next())),
-                meta.query_advice(is_tag_block_num, Rotation::cur()),
-            ]);
+        u16_table.range_check(
+            meta,
+            "block_num is non-decreasing till padding txs",
+            |meta| {
+                // Block nums like this [1, 3, 5, 4, 0] is rejected by this. But [1, 2, 3, 5, 0] is
+                // acceptable.
+                let lookup_condition = and::expr([
+                    // next row should not belong to a padding tx
+                    not::expr(meta.query_advice(is_padding_tx, Rotation::next())),
+                    // next row should not be in the calldata region
+                    not::expr(meta.query_advice(is_calldata, Rotation::next())),
+                    meta.query_advice(is_tag_block_num, Rotation::cur()),
+                ]);
 
-            let block_num_diff = meta.query_advice(block_num, Rotation::next())
-                - meta.query_advice(block_num, Rotation::cur());
+                let block_num_diff = meta.query_advice(block_num, Rotation::next())
+                    - meta.query_advice(block_num, Rotation::cur());
 
-            vec![(lookup_condition * block_num_diff, u16_table.into())]
-        });
+                lookup_condition * block_num_diff
+            },
+        );
 
         meta.create_gate("num_all_txs in a block", |meta| {
             let mut cb = BaseConstraintBuilder::default();
@@ -1240,7 +1379,7 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             let mut cb = BaseConstraintBuilder::default();
 
             let (lt_expr, eq_expr) = tx_id_cmp_cum_num_txs.expr(meta, None);
-            cb.condition(is_block_num(meta), |cb| {
+            cb.condition(meta.query_advice(is_tag_block_num, Rotation::cur()), |cb| {
                 cb.require_equal("lt or eq", sum::expr([lt_expr, eq_expr]), true.expr());
             });
 
@@ -1293,7 +1432,7 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
         ////////////////////////////////////////////////////////////////////////
         ///////////  CallData length and gas_cost calculation  /////////////////
         ////////////////////////////////////////////////////////////////////////
-        meta.lookup("tx_id_diff must in u16", |meta| {
+        u16_table.range_check(meta, "tx_id_diff must in u16", |meta| {
             let q_enable = meta.query_fixed(q_enable, Rotation::next());
             let is_calldata = meta.query_advice(is_calldata, Rotation::cur());
             let tx_id = meta.query_advice(tx_table.tx_id, Rotation::cur());
@@ -1303,7 +1442,7 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             let lookup_condition =
                 and::expr([q_enable, is_calldata, not::expr(tx_id_next_is_zero)]);
 
-            vec![(lookup_condition * (tx_id_next - tx_id), u16_table.into())]
+            lookup_condition * (tx_id_next - tx_id)
         });
 
         meta.create_gate("last row of call data", |meta| {
@@ -1394,11 +1533,15 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             // on the final call data byte, must transition to another
             // calldata section or an access list section for the same tx
 
-            // on the final call data byte, if there's no access list, tx_id must change.
+            // on the final call data byte, if there's no access list or blob versioned hashes,
+            // tx_id must change.
             cb.condition(
                 and::expr([
                     is_final_cur.expr(),
-                    not::expr(meta.query_advice(is_access_list, Rotation::next())),
+                    not::expr(sum::expr([
+                        meta.query_advice(is_access_list, Rotation::next()),
+                        meta.query_advice(is_blob_versioned_hash, Rotation::next()),
+                    ])),
                 ]),
                 |cb| {
                     cb.require_zero(
@@ -1613,6 +1756,62 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             ]))
         });
 
+        ////////////////////////////////////////////////////////////////////////
+        ///////////  Blob Versioned Hashes Constraints (EIP-4844)  /////////////
+        ////////////////////////////////////////////////////////////////////////
+        meta.create_gate("tx blob versioned hashes", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let is_final_cur = meta.query_advice(is_final, Rotation::cur());
+            cb.require_boolean("is_final is boolean", is_final_cur.clone());
+
+            cb.require_equal(
+                "only eip4844 tx has blob versioned hashes",
+                meta.query_advice(is_eip4844, Rotation::cur()),
+                1.expr(),
+            );
+
+            // The blob versioned hashes section of a tx either follows its calldata section,
+            // its access list section or the sections of previous tx. In all cases, index
+            // starts with 1.
+            let is_prev_non_final_blob_hash = and::expr([
+                meta.query_advice(is_blob_versioned_hash, Rotation::prev()),
+                not::expr(meta.query_advice(is_final, Rotation::prev())),
+            ]);
+            cb.condition(not::expr(is_prev_non_final_blob_hash), |cb| {
+                cb.require_equal(
+                    "index starts with 1",
+                    meta.query_advice(tx_table.index, Rotation::cur()),
+                    1.expr(),
+                );
+            });
+
+            // within same tx, next row is also a blob versioned hash
+            cb.condition(not::expr(is_final_cur), |cb| {
+                cb.require_equal(
+                    "is_blob_versioned_hash' == 1",
+                    meta.query_advice(is_blob_versioned_hash, Rotation::next()),
+                    1.expr(),
+                );
+                cb.require_equal(
+                    "tx_id' == tx_id",
+                    tx_id_unchanged.is_equal_expression.clone(),
+                    1.expr(),
+                );
+                cb.require_equal(
+                    "index' == index + 1",
+                    meta.query_advice(tx_table.index, Rotation::next()),
+                    meta.query_advice(tx_table.index, Rotation::cur()) + 1.expr(),
+                );
+            });
+
+            cb.gate(and::expr(vec![
+                meta.query_fixed(q_enable, Rotation::cur()),
+                meta.query_advice(is_blob_versioned_hash, Rotation::cur()),
+                not::expr(tx_id_is_zero.expr(Rotation::cur())(meta)),
+            ]))
+        });
+
         ////////////////////////////////////////////////////////////////////////
         ///////////   SignVerify recover CallerAddress    //////////////////////
         ////////////////////////////////////////////////////////////////////////
@@ -1665,6 +1864,7 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             // TODO:
             //  4. eip1559 tx: v Є {0, 1}
             //  5. eip2930 tx: v Є {0, 1}
+            //  6. eip4844 tx: v Є {0, 1}
 
             cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
         });
@@ -1725,6 +1925,7 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             is_l1_msg,
             is_eip2930,
             is_eip1559,
+            is_eip4844,
             is_chain_id,
             is_final,
             calldata_gas_cost_acc,
@@ -1737,6 +1938,7 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             keccak_table,
             rlp_table,
             is_tag_block_num,
+            is_tag_nonce,
             al_idx,
             sk_idx,
             sks_acc,
@@ -1744,6 +1946,7 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             is_access_list_address,
             is_access_list_storage_key,
             field_rlc,
+            is_blob_versioned_hash,
             _marker: PhantomData,
             num_txs,
         }
@@ -1768,6 +1971,7 @@ impl<F: Field> TxCircuitConfig<F> {
         is_l1_msg_col: Column<Advice>,
         is_eip2930: Column<Advice>,
         is_eip1559: Column<Advice>,
+        is_eip4844: Column<Advice>,
         sv_address: Column<Advice>,
         calldata_gas_cost_acc: Column<Advice>,
         section_rlc: Column<Advice>,
@@ -1782,6 +1986,7 @@ impl<F: Field> TxCircuitConfig<F> {
         al_idx: Column<Advice>,
         sk_idx: Column<Advice>,
         sks_acc: Column<Advice>,
+        is_blob_versioned_hash: Column<Advice>,
     ) {
         macro_rules! is_tx_type {
             ($var:ident, $type_variant:ident) => {
@@ -1984,6 +2189,61 @@ impl<F: Field> TxCircuitConfig<F> {
                 .map(|(input, table)| (input * enable.expr(), table))
                 .collect()
         });
+        meta.lookup_any("lookup BlobVersionedHashesLen in the TxTable", |meta| {
+            let enable = and::expr([
+                meta.query_fixed(q_enable, Rotation::cur()),
+                meta.query_advice(is_blob_versioned_hash, Rotation::cur()),
+                meta.query_advice(is_final, Rotation::cur()),
+            ]);
+
+            let input_exprs = vec![
+                meta.query_advice(tx_table.tx_id, Rotation::cur()),
+                BlobVersionedHashesLen.expr(),
+                meta.query_advice(tx_table.index, Rotation::cur()),
+            ];
+            let table_exprs = vec![
+                meta.query_advice(tx_table.tx_id, Rotation::cur()),
+                meta.query_fixed(tx_table.tag, Rotation::cur()),
+                meta.query_advice(tx_table.value, Rotation::cur()),
+            ];
+
+            input_exprs
+                .into_iter()
+                .zip(table_exprs)
+                .map(|(input, table)| (input * enable.expr(), table))
+                .collect()
+        });
+        meta.lookup_any(
+            "is_final blob versioned hash row should be present",
+            |meta| {
+                let enable = and::expr(vec![
+                    meta.query_fixed(q_enable, Rotation::cur()),
+                    meta.query_advice(
+                        lookup_conditions[&LookupCondition::TxBlobHashes],
+                        Rotation::cur(),
+                    ),
+                ]);
+                let input_exprs = vec![
+                    meta.query_advice(tx_table.tx_id, Rotation::cur()),
+                    1.expr(),
+                    1.expr(),
+                    meta.query_advice(tx_table.value, Rotation::cur()), /* blob versioned hashes
+                                                                         * len */
+                ];
+                let table_exprs = vec![
+                    meta.query_advice(tx_table.tx_id, Rotation::cur()),
+                    meta.query_advice(is_blob_versioned_hash, Rotation::cur()),
+                    meta.query_advice(is_final, Rotation::cur()),
+                    meta.query_advice(tx_table.index, Rotation::cur()),
+                ];
+
+                input_exprs
+                    .into_iter()
+                    .zip(table_exprs)
+                    .map(|(input, table)| (input * enable.expr(), table))
+                    .collect()
+            },
+        );
 
         /////////////////////////////////////////////////////////////////
         /////////////////    RLP table lookups     //////////////////////
@@ -2036,7 +2296,8 @@ impl<F: Field> TxCircuitConfig<F> {
             let sign_format = is_pre_eip155(meta) * TxSignPreEip155.expr()
                 + is_eip155(meta) * TxSignEip155.expr()
                 + meta.query_advice(is_eip2930, Rotation::cur()) * TxSignEip2930.expr()
-                + meta.query_advice(is_eip1559, Rotation::cur()) * TxSignEip1559.expr();
+                + meta.query_advice(is_eip1559, Rotation::cur()) * TxSignEip1559.expr()
+                + meta.query_advice(is_eip4844, Rotation::cur()) * TxSignEip4844.expr();
 
             // q_enable, tx_id, format, rlp_tag, tag_value, is_output, is_none
             vec![
@@ -2079,7 +2340,8 @@ impl<F: Field> TxCircuitConfig<F> {
                 + is_eip155(meta) * TxHashEip155.expr()
                 + is_l1_msg(meta) * L1MsgHash.expr()
                 + meta.query_advice(is_eip2930, Rotation::cur()) * TxHashEip2930.expr()
-                + meta.query_advice(is_eip1559, Rotation::cur()) * TxHashEip1559.expr();
+                + meta.query_advice(is_eip1559, Rotation::cur()) * TxHashEip1559.expr()
+                + meta.query_advice(is_eip4844, Rotation::cur()) * TxHashEip4844.expr();
 
             vec![
                 1.expr(), // q_enable = true
@@ -2111,10 +2373,11 @@ impl<F: Field> TxCircuitConfig<F> {
                     meta.query_advice(is_access_list_address, Rotation::cur()),
                 ]);
 
-                // only eip2930 and eip1559 contains an access list
+                // only eip2930, eip1559 and eip4844 contains an access list
                 let sign_format = meta.query_advice(is_eip2930, Rotation::cur())
                     * TxSignEip2930.expr()
-                    + meta.query_advice(is_eip1559, Rotation::cur()) * TxSignEip1559.expr();
+                    + meta.query_advice(is_eip1559, Rotation::cur()) * TxSignEip1559.expr()
+                    + meta.query_advice(is_eip4844, Rotation::cur()) * TxSignEip4844.expr();
 
                 vec![
                     1.expr(), // q_enable = true
@@ -2144,10 +2407,11 @@ impl<F: Field> TxCircuitConfig<F> {
                     meta.query_advice(is_access_list_address, Rotation::cur()),
                 ]);
 
-                // only eip2930 and eip1559 contains an access list
+                // only eip2930, eip1559 and eip4844 contains an access list
                 let hash_format = meta.query_advice(is_eip2930, Rotation::cur())
                     * TxHashEip2930.expr()
-                    + meta.query_advice(is_eip1559, Rotation::cur()) * TxHashEip1559.expr();
+                    + meta.query_advice(is_eip1559, Rotation::cur()) * TxHashEip1559.expr()
+                    + meta.query_advice(is_eip4844, Rotation::cur()) * TxHashEip4844.expr();
 
                 vec![
                     1.expr(), // q_enable = true
@@ -2180,10 +2444,11 @@ impl<F: Field> TxCircuitConfig<F> {
                     meta.query_advice(is_access_list_storage_key, Rotation::cur()),
                 ]);
 
-                // only eip2930 and eip1559 contains an access list
+                // only eip2930, eip1559 and eip4844 contains an access list
                 let sign_format = meta.query_advice(is_eip2930, Rotation::cur())
                     * TxSignEip2930.expr()
-                    + meta.query_advice(is_eip1559, Rotation::cur()) * TxSignEip1559.expr();
+                    + meta.query_advice(is_eip1559, Rotation::cur()) * TxSignEip1559.expr()
+                    + meta.query_advice(is_eip4844, Rotation::cur()) * TxSignEip4844.expr();
 
                 vec![
                     1.expr(), // q_enable = true
@@ -2213,10 +2478,11 @@ impl<F: Field> TxCircuitConfig<F> {
                     meta.query_advice(is_access_list_storage_key, Rotation::cur()),
                 ]);
 
-                // only eip2930 and eip1559 contains an access list
+                // only eip2930, eip1559 and eip4844 contains an access list
                 let hash_format = meta.query_advice(is_eip2930, Rotation::cur())
                     * TxHashEip2930.expr()
-                    + meta.query_advice(is_eip1559, Rotation::cur()) * TxHashEip1559.expr();
+                    + meta.query_advice(is_eip1559, Rotation::cur()) * TxHashEip1559.expr()
+                    + meta.query_advice(is_eip4844, Rotation::cur()) * TxHashEip4844.expr();
 
                 vec![
                     1.expr(), // q_enable = true
@@ -2238,6 +2504,47 @@ impl<F: Field> TxCircuitConfig<F> {
             },
         );
 
+        // lookup blob versioned hash in RLP table
+        // 1. ensure field_rlc is correct
+        // 2. ensure value of blob versioned hash is correct
+        // 3. ensure the index of blob versioned hash is correct, which binds the order and the
+        //    number of the blob versioned hashes. The RLP table keeps it in access_list_idx.
+        for (name, format) in [
+            (
+                "Lookup blob versioned hash in RLP Table from tx circuit dynamic section (Signing)",
+                TxSignEip4844,
+            ),
+            (
+                "Lookup blob versioned hash in RLP Table from tx circuit dynamic section (Hashing)",
+                TxHashEip4844,
+            ),
+        ] {
+            meta.lookup_any(name, |meta| {
+                let enable = and::expr(vec![
+                    meta.query_fixed(q_enable, Rotation::cur()),
+                    meta.query_advice(is_blob_versioned_hash, Rotation::cur()),
+                ]);
+
+                vec![
+                    1.expr(), // q_enable = true
+                    meta.query_advice(tx_table.tx_id, Rotation::cur()),
+                    format.expr(),
+                    meta.query_advice(rlp_tag, Rotation::cur()),
+                    meta.query_advice(tx_table.value, Rotation::cur()),
+                    meta.query_advice(field_rlc, Rotation::cur()),
+                    32.expr(), // 32 bytes for blob versioned hash
+                    1.expr(),  // is_output = true
+                    0.expr(),  // is_none = false. must have value
+                    meta.query_advice(tx_table.index, Rotation::cur()), // blob hash index
+                    0.expr(),  // storage_key_idx
+                ]
+                .into_iter()
+                .zip_eq(rlp_table.table_exprs(meta))
+                .map(|(arg, table)| (enable.clone() * arg, table))
+                .collect()
+            });
+        }
+
         ////////////////////////////////////////////////////////////////////
         /////////////////    Sig table lookups     //////////////////////
         ///////////////// //////////////////////////////////////////////////
@@ -2349,7 +2656,7 @@ impl<F: Field> TxCircuitConfig<F> {
         num_txs: u64,
         cum_num_txs: u64,
         challenges: &Challenges<Value<F>>,
-    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+    ) -> Result<(Vec<AssignedCell<F, F>>, AssignedCell<F, F>), Error> {
         let keccak_input = challenges.keccak_input();
         let evm_word = challenges.evm_word();
         let zero_rlc = keccak_input.map(|_| F::zero());
@@ -2358,6 +2665,7 @@ impl<F: Field> TxCircuitConfig<F> {
         let sign_hash_rlc = rlc_be_bytes(&sign_hash, evm_word);
         let hash_rlc = rlc_be_bytes(&hash, evm_word);
         let mut tx_value_cells = vec![];
+        let mut total_l1_popped_before_cell = None;
         let rlp_sign_tag_length = if tx.tx_type.is_l1_msg() {
             // l1 msg does not have sign data
             0
@@ -2601,6 +2909,24 @@ impl<F: Field> TxCircuitConfig<F> {
                 }),
                 rlc_be_bytes(&tx.max_priority_fee_per_gas.to_be_bytes(), evm_word),
             ),
+            (
+                MaxFeePerBlobGas,
+                Some(RlpTableInputValue {
+                    tag: Tag::MaxFeePerBlobGas.into(),
+                    is_none: tx.max_fee_per_blob_gas.is_zero(),
+                    be_bytes_len: tx.max_fee_per_blob_gas.tag_length(),
+                    be_bytes_rlc: rlc_be_bytes(
+                        &tx.max_fee_per_blob_gas.to_be_bytes(),
+                        keccak_input,
+                    ),
+                }),
+                rlc_be_bytes(&tx.max_fee_per_blob_gas.to_be_bytes(), evm_word),
+            ),
+            (
+                BlobVersionedHashesLen,
+                None,
+                Value::known(F::from(tx.blob_versioned_hashes.len() as u64)),
+            ),
             (BlockNumber, None, Value::known(F::from(tx.block_number))),
         ];
         for (tx_tag, rlp_input, tx_value) in fixed_rows {
@@ -2680,6 +3006,11 @@ impl<F: Field> TxCircuitConfig<F> {
                     self.is_tag_block_num,
                     F::from((tx_tag == BlockNumber) as u64),
                 ),
+                (
+                    "is_tag_nonce",
+                    self.is_tag_nonce,
+                    F::from((tx_tag == Nonce) as u64),
+                ),
                 (
                     "is_tag_chain_id",
                     self.is_chain_id,
@@ -2691,7 +3022,12 @@ impl<F: Field> TxCircuitConfig<F> {
                     F::from((tx_tag == CallerAddress) as u64),
                 ),
             ] {
-                region.assign_advice(|| col_anno, col, *offset, || Value::known(col_val))?;
+                let cell =
+                    region.assign_advice(|| col_anno, col, *offset, || Value::known(col_val))?;
+                // total_l1_popped_before is only constrained at the BlockNumber row
+                if col == self.total_l1_popped_before && tx_tag == BlockNumber {
+                    total_l1_popped_before_cell = Some(cell);
+                }
             }
 
             // 2nd phase columns
@@ -2724,7 +3060,12 @@ impl<F: Field> TxCircuitConfig<F> {
                     F::zero()
                 }
             });
-            // 3. lookup to RLP table for signing (non L1 msg)
+            // 3. lookup to ensure the final row in the blob hashes dynamic section is present.
+            conditions.insert(LookupCondition::TxBlobHashes, {
+                let tag_enable = tx_tag == BlobVersionedHashesLen;
+                F::from((tag_enable && !tx.blob_versioned_hashes.is_empty()) as u64)
+            });
+            // 4. lookup to RLP table for signing (non L1 msg)
             conditions.insert(LookupCondition::RlpSignTag, {
                 let sign_set = [
                     Nonce,
@@ -2737,13 +3078,15 @@ impl<F: Field> TxCircuitConfig<F> {
                 ];
                 let is_tag_in_set = sign_set.into_iter().filter(|tag| tx_tag == *tag).count() == 1;
                 let case1 = is_tag_in_set && !is_l1_msg;
+                let is_dynamic_fee_tx = tx.tx_type.is_eip1559() || tx.tx_type.is_eip4844();
                 let case2 = !tx.tx_type.is_pre_eip155() && !is_l1_msg && (tx_tag == ChainID);
-                let case3 = !tx.tx_type.is_eip1559() && !is_l1_msg && (tx_tag == GasPrice);
-                let case4 = tx.tx_type.is_eip1559()
-                    && (tx_tag == MaxFeePerGas || tx_tag == MaxPriorityFeePerGas);
-                F::from((case1 || case2 || case3 || case4) as u64)
+                let case3 = !is_dynamic_fee_tx && !is_l1_msg && (tx_tag == GasPrice);
+                let case4 =
+                    is_dynamic_fee_tx && (tx_tag == MaxFeePerGas || tx_tag == MaxPriorityFeePerGas);
+                let case5 = tx.tx_type.is_eip4844() && (tx_tag == MaxFeePerBlobGas);
+                F::from((case1 || case2 || case3 || case4 || case5) as u64)
             });
-            // 4. lookup to RLP table for hashing (non L1 msg)
+            // 5. lookup to RLP table for hashing (non L1 msg)
             conditions.insert(LookupCondition::RlpHashTag, {
                 let hash_set = [
                     Nonce,
@@ -2759,13 +3102,15 @@ impl<F: Field> TxCircuitConfig<F> {
                     TxHashRLC,
                 ];
                 let is_tag_in_set = hash_set.into_iter().filter(|tag| tx_tag == *tag).count() == 1;
+                let is_dynamic_fee_tx = tx.tx_type.is_eip1559() || tx.tx_type.is_eip4844();
                 let case1 = is_tag_in_set && !is_l1_msg;
-                let case2 = !tx.tx_type.is_eip1559() && !is_l1_msg && (tx_tag == GasPrice);
-                let case3 = tx.tx_type.is_eip1559()
-                    && (tx_tag == MaxFeePerGas || tx_tag == MaxPriorityFeePerGas);
-                F::from((case1 || case2 || case3) as u64)
+                let case2 = !is_dynamic_fee_tx && !is_l1_msg && (tx_tag == GasPrice);
+                let case3 =
+                    is_dynamic_fee_tx && (tx_tag == MaxFeePerGas || tx_tag == MaxPriorityFeePerGas);
+                let case4 = tx.tx_type.is_eip4844() && (tx_tag == MaxFeePerBlobGas);
+                F::from((case1 || case2 || case3 || case4) as u64)
             });
-            // 5. lookup to RLP table for hashing (L1 msg)
+            // 6. lookup to RLP table for hashing (L1 msg)
             conditions.insert(LookupCondition::L1MsgHash, {
                 let hash_set = [
                     Nonce,
@@ -2781,7 +3126,7 @@ impl<F: Field> TxCircuitConfig<F> {
                 let is_tag_in_set = hash_set.into_iter().filter(|tag| tx_tag == *tag).count() == 1;
                 F::from((is_l1_msg && is_tag_in_set) as u64)
             });
-            // 6. lookup to Keccak table for tx_sign_hash and tx_hash
+            // 7. lookup to Keccak table for tx_sign_hash and tx_hash
             conditions.insert(LookupCondition::Keccak, {
                 let case1 = (tx_tag == TxSignLength) && !is_l1_msg;
                 let case2 = tx_tag == TxHashLength;
@@ -2824,7 +3169,10 @@ impl<F: Field> TxCircuitConfig<F> {
 
             *offset += 1;
         }
-        Ok(tx_value_cells)
+        Ok((
+            tx_value_cells,
+            total_l1_popped_before_cell.expect("BlockNumber is a fixed row of a tx"),
+        ))
     }
 
     /// Assign calldata byte rows of each tx
@@ -3043,6 +3391,63 @@ impl<F: Field> TxCircuitConfig<F> {
         Ok(())
     }
 
+    /// Assign blob versioned hash rows of each tx
+    fn assign_blob_versioned_hash_rows(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: &mut usize,
+        tx: &Transaction,
+        next_tx: Option<&Transaction>,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        let num_hashes = tx.blob_versioned_hashes.len();
+        for (idx, hash) in tx.blob_versioned_hashes.iter().enumerate() {
+            let is_final = idx == num_hashes - 1;
+            let tx_id_next = if is_final {
+                next_tx.map_or(0, |tx| tx.id)
+            } else {
+                tx.id
+            };
+
+            self.assign_common_part(
+                region,
+                *offset,
+                Some(tx),
+                tx_id_next,
+                BlobVersionedHash,
+                (idx + 1) as u64,
+                rlc_be_bytes(&hash.to_fixed_bytes(), challenges.evm_word()),
+                Value::known(F::zero()),
+            )?;
+
+            // 1st phase columns
+            for (col_anno, col, col_val) in [
+                ("block_num", self.block_num, F::from(tx.block_number)),
+                (
+                    "rlp_tag",
+                    self.rlp_tag,
+                    F::from(usize::from(Tag::BlobVersionedHash) as u64),
+                ),
+                ("is_final", self.is_final, F::from(is_final as u64)),
+                (
+                    "is_blob_versioned_hash",
+                    self.is_blob_versioned_hash,
+                    F::one(),
+                ),
+            ] {
+                region.assign_advice(|| col_anno, col, *offset, || Value::known(col_val))?;
+            }
+
+            // field_rlc to be looked up in the RLP table
+            let field_rlc = rlc_be_bytes(&hash.to_fixed_bytes(), challenges.keccak_input());
+            region.assign_advice(|| "field_rlc", self.field_rlc, *offset, || field_rlc)?;
+
+            *offset += 1;
+        }
+
+        Ok(())
+    }
+
     // Assigns to common columns in different parts of tx circuit
     // 1. 1st all zero row
     // 2. fixed rows of each tx
@@ -3115,6 +3520,11 @@ impl<F: Field> TxCircuitConfig<F> {
                 self.is_eip1559,
                 F::from(tx_type.is_eip1559() as u64),
             ),
+            (
+                "is_eip4844",
+                self.is_eip4844,
+                F::from(tx_type.is_eip4844() as u64),
+            ),
             (
                 "is_tx_id_zero",
                 self.is_tx_id_zero,
@@ -3240,6 +3650,8 @@ pub struct TxCircuit<F: Field> {
     pub size: usize,
     /// Tx value cells (exported for PI circuit)
     pub value_cells: RefCell<Option<Vec<AssignedCell<F, F>>>>,
+    /// Exported cells (for PI circuit)
+    pub exports: RefCell<Option<TxCircuitExports<Assigned<F>>>>,
     _marker: PhantomData<F>,
 }
 
@@ -3268,6 +3680,7 @@ impl<F: Field> TxCircuit<F> {
             chain_id,
             start_l1_queue_index,
             value_cells: RefCell::new(None),
+            exports: RefCell::new(None),
             _marker: PhantomData,
         }
     }
@@ -3415,7 +3828,7 @@ impl<F: Field> TxCircuit<F> {
         start_l1_queue_index: u64,
         sign_datas: Vec<SignData>,
         padding_txs: &[Transaction],
-    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+    ) -> Result<(Vec<AssignedCell<F, F>>, AssignedCell<F, F>), Error> {
         layouter.assign_region(
             || "tx table aux",
             |mut region| {
@@ -3445,6 +3858,7 @@ impl<F: Field> TxCircuit<F> {
                 };
 
                 let mut tx_value_cells = vec![];
+                let mut start_l1_queue_index_cell = None;
                 for (i, sign_data) in sigs.iter().enumerate() {
                     let tx = get_tx(i);
                     let block_num = tx.block_number;
@@ -3505,20 +3919,22 @@ impl<F: Field> TxCircuit<F> {
                         i,
                         num_all_txs_acc,
                     );
-                    tx_value_cells.extend_from_slice(
-                        config.assign_fixed_rows(
-                            &mut region,
-                            &mut offset,
-                            tx,
-                            sign_data,
-                            next_tx,
-                            total_l1_popped_before,
-                            num_all_txs_acc,
-                            num_txs,
-                            cum_num_txs,
-                            challenges,
-                        )?.as_slice()
-                    );
+                    let (cells, total_l1_popped_before_cell) = config.assign_fixed_rows(
+                        &mut region,
+                        &mut offset,
+                        tx,
+                        sign_data,
+                        next_tx,
+                        total_l1_popped_before,
+                        num_all_txs_acc,
+                        num_txs,
+                        cum_num_txs,
+                        challenges,
+                    )?;
+                    tx_value_cells.extend_from_slice(cells.as_slice());
+                    if i == 0 {
+                        start_l1_queue_index_cell = Some(total_l1_popped_before_cell);
+                    }
                     // set next tx's total_l1_popped_before
                     total_l1_popped_before = total_l1_popped_after;
                 }
@@ -3548,6 +3964,13 @@ impl<F: Field> TxCircuit<F> {
                         next_tx,
                         challenges,
                     )?;
+                    config.assign_blob_versioned_hash_rows(
+                        &mut region,
+                        &mut offset,
+                        tx,
+                        next_tx,
+                        challenges,
+                    )?;
                 }
                 assert!(offset <= calldata_last_row, "{offset}, {calldata_last_row}");
                 // 3.2 pad calldata with zeros
@@ -3564,7 +3987,10 @@ impl<F: Field> TxCircuit<F> {
                     region.assign_fixed(|| col_anno, col, row, || Value::known(F::one()))?;
                 }
 
-                Ok(tx_value_cells)
+                Ok((
+                    tx_value_cells,
+                    start_l1_queue_index_cell.expect("max_txs is at least 1"),
+                ))
             },
         )
     }
@@ -3669,7 +4095,7 @@ impl<F: Field> SubCircuit<F> for TxCircuit<F> {
             }
         }
 
-        let tx_value_cells = self.assign(
+        let (tx_value_cells, start_l1_queue_index_cell) = self.assign(
             config,
             challenges,
             layouter,
@@ -3679,6 +4105,12 @@ impl<F: Field> SubCircuit<F> for TxCircuit<F> {
         )?;
         // export tx value cells
         *self.value_cells.borrow_mut() = Some(tx_value_cells);
+        *self.exports.borrow_mut() = Some(TxCircuitExports {
+            start_l1_queue_index: (
+                start_l1_queue_index_cell.cell(),
+                start_l1_queue_index_cell.value_field(),
+            ),
+        });
 
         Ok(())
     }
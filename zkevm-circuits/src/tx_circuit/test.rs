@@ -160,6 +160,75 @@ fn build_eip1559_tx(id: usize) -> Transaction {
     tx
 }
 
+#[cfg(test)]
+fn build_eip4844_tx(id: usize, blob_versioned_hashes: Vec<H256>) -> Transaction {
+    use eth_types::geth_types::{
+        get_rlp_signed, get_rlp_unsigned, BLOB_VERSIONED_HASHES_KEY, MAX_FEE_PER_BLOB_GAS_KEY,
+    };
+    use ethers_signers::Signer;
+
+    let wallet = &mock::MOCK_WALLETS[0];
+    let max_fee_per_blob_gas = word!("0x3b9aca00");
+    let mut eth_tx = EthTransaction {
+        transaction_type: Some(U64::from(3)),
+        chain_id: Some(mock::MOCK_CHAIN_ID.into()),
+        nonce: word!("0x0"),
+        max_priority_fee_per_gas: Some(word!("0x3b9aca00")),
+        max_fee_per_gas: Some(word!("0x4a817c800")),
+        gas: word!("0x77320"),
+        from: wallet.address(),
+        to: Some(address!("0x00000000000000000000000000000000000000aa")),
+        value: word!("0x1"),
+        input: vec![0x12, 0x34].into(),
+        access_list: Some(Default::default()),
+        ..Default::default()
+    };
+    eth_tx.other.insert(
+        MAX_FEE_PER_BLOB_GAS_KEY.to_string(),
+        serde_json::to_value(max_fee_per_blob_gas).unwrap(),
+    );
+    eth_tx.other.insert(
+        BLOB_VERSIONED_HASHES_KEY.to_string(),
+        serde_json::to_value(&blob_versioned_hashes).unwrap(),
+    );
+
+    let rlp_unsigned = get_rlp_unsigned(&eth_tx);
+    let sig = wallet
+        .sign_hash(H256(keccak256(&rlp_unsigned)))
+        .expect("sign tx shall not fail");
+    eth_tx.v = U64::from(sig.v - 27);
+    eth_tx.r = sig.r;
+    eth_tx.s = sig.s;
+    let rlp_signed = get_rlp_signed(&eth_tx);
+
+    let mut tx = Transaction::new_from_rlp_bytes(id, TxType::Eip4844, rlp_signed, rlp_unsigned);
+
+    tx.hash = H256(keccak256(&tx.rlp_signed));
+    tx.block_number = 1;
+    tx.chain_id = mock::MOCK_CHAIN_ID;
+    tx.nonce = eth_tx.nonce.as_u64();
+    tx.value = eth_tx.value;
+    tx.gas = eth_tx.gas.as_u64();
+    tx.max_fee_per_gas = eth_tx.max_fee_per_gas.unwrap_or_default();
+    tx.max_priority_fee_per_gas = eth_tx.max_priority_fee_per_gas.unwrap_or_default();
+    tx.call_data = eth_tx.input.to_vec();
+    tx.callee_address = eth_tx.to;
+    tx.caller_address = eth_tx.from;
+    tx.is_create = eth_tx.to.is_none();
+    tx.call_data_length = tx.call_data.len();
+    tx.call_data_gas_cost = tx_data_gas_cost(&tx.call_data);
+    tx.access_list = eth_tx.access_list.clone();
+    tx.access_list_gas_cost = tx_access_list_gas_cost(&eth_tx.access_list);
+    tx.tx_data_gas_cost = tx_data_gas_cost(&tx.rlp_signed);
+    tx.max_fee_per_blob_gas = max_fee_per_blob_gas;
+    tx.blob_versioned_hashes = blob_versioned_hashes;
+    tx.v = eth_tx.v.as_u64();
+    tx.r = eth_tx.r;
+    tx.s = eth_tx.s;
+
+    tx
+}
+
 fn run<F: Field>(
     txs: Vec<Transaction>,
     chain_id: u64,
@@ -221,6 +290,37 @@ fn tx_circuit_2tx_2max_tx_eip1559() {
     );
 }
 
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_1tx_2max_eip4844() {
+    const MAX_TXS: usize = 2;
+    const MAX_CALLDATA: usize = 32;
+
+    let tx = build_eip4844_tx(1, vec![H256::repeat_byte(1), H256::repeat_byte(2)]);
+
+    assert_eq!(
+        run::<Fr>(vec![tx], mock::MOCK_CHAIN_ID, MAX_TXS, MAX_CALLDATA, 0),
+        Ok(())
+    );
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_eip4844_bad_blob_versioned_hashes() {
+    const MAX_TXS: usize = 2;
+    const MAX_CALLDATA: usize = 32;
+
+    let hashes = vec![H256::repeat_byte(1), H256::repeat_byte(2)];
+    // The blob versioned hashes in the tx table don't match the signed ones: in a different
+    // order, or with a hash repeated in place of another.
+    for bad_hashes in [vec![hashes[1], hashes[0]], vec![hashes[0], hashes[0]]] {
+        let mut tx = build_eip4844_tx(1, hashes.clone());
+        tx.blob_versioned_hashes = bad_hashes;
+
+        assert!(run::<Fr>(vec![tx], mock::MOCK_CHAIN_ID, MAX_TXS, MAX_CALLDATA, 0).is_err());
+    }
+}
+
 #[test]
 #[cfg(feature = "scroll")]
 fn tx_circuit_2tx_2max_tx() {
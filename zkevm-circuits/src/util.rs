@@ -6,7 +6,7 @@ use halo2_proofs::{
     circuit::{Layouter, Value},
     plonk::{Challenge, Circuit, ConstraintSystem, Error, Expression, FirstPhase, VirtualCells},
 };
-use keccak256::plain::Keccak;
+use keccak256::keccak256;
 
 #[cfg(feature = "onephase")]
 use halo2_proofs::plonk::FirstPhase as SecondPhase;
@@ -254,9 +254,7 @@ pub fn log2_ceil(n: usize) -> u32 {
 }
 
 pub(crate) fn keccak(msg: &[u8]) -> Word {
-    let mut keccak = Keccak::default();
-    keccak.update(msg);
-    Word::from_big_endian(keccak.digest().as_slice())
+    Word::from_big_endian(&keccak256(msg))
 }
 
 pub(crate) fn is_push_with_data(byte: u8) -> bool {
@@ -273,22 +271,23 @@ pub(crate) fn get_push_size(byte: u8) -> u64 {
 
 #[derive(Debug)]
 pub(crate) struct CircuitStats {
-    num_constraints: usize,
-    num_fixed_columns: usize,
-    num_lookups: usize,
-    num_advice_columns: usize,
-    num_instance_columns: usize,
-    num_selectors: usize,
-    num_simple_selectors: usize,
-    num_permutation_columns: usize,
-    degree: usize,
-    blinding_factors: usize,
-    num_challenges: usize,
-    max_phase: u8,
-    num_rotation: usize,
-    min_rotation: i32,
-    max_rotation: i32,
-    num_verification_ecmul: usize,
+    pub(crate) num_gates: usize,
+    pub(crate) num_constraints: usize,
+    pub(crate) num_fixed_columns: usize,
+    pub(crate) num_lookups: usize,
+    pub(crate) num_advice_columns: usize,
+    pub(crate) num_instance_columns: usize,
+    pub(crate) num_selectors: usize,
+    pub(crate) num_simple_selectors: usize,
+    pub(crate) num_permutation_columns: usize,
+    pub(crate) degree: usize,
+    pub(crate) blinding_factors: usize,
+    pub(crate) num_challenges: usize,
+    pub(crate) max_phase: u8,
+    pub(crate) num_rotation: usize,
+    pub(crate) min_rotation: i32,
+    pub(crate) max_rotation: i32,
+    pub(crate) num_verification_ecmul: usize,
 }
 
 pub(crate) fn circuit_stats<F: Field>(meta: &ConstraintSystem<F>) -> CircuitStats {
@@ -298,6 +297,7 @@ pub(crate) fn circuit_stats<F: Field>(meta: &ConstraintSystem<F>) -> CircuitStat
         .map(|(_, q)| q.0)
         .collect::<BTreeSet<i32>>();
     CircuitStats {
+        num_gates: meta.gates().len(),
         num_constraints: meta
             .gates()
             .iter()
@@ -3,8 +3,9 @@
 //! used to generate witnesses for circuits.
 
 mod block;
+pub(crate) use block::dynamic_max_rws;
 pub use block::{
-    block_apply_mpt_state, block_convert, block_convert_with_l1_queue_index,
+    block_apply_eth_trie, block_apply_mpt_state, block_convert, block_convert_with_l1_queue_index,
     block_mocking_apply_mpt, Block, BlockContext, BlockContexts,
 };
 
@@ -15,7 +16,10 @@ mod call;
 pub use call::Call;
 
 mod mpt;
-pub use mpt::{MptUpdate, MptUpdateRow, MptUpdates, WithdrawProof};
+pub use mpt::{
+    eth_trie::{EthAccount, EthTrie, EthTrieUpdate, TrieError, EMPTY_TRIE_ROOT},
+    MptUpdate, MptUpdateRow, MptUpdates, StateTrie, WithdrawProof,
+};
 
 mod receipt;
 pub use receipt::Receipt;
@@ -27,7 +31,7 @@ pub use rlp_fsm::{
 };
 
 mod rw;
-pub use rw::{Rw, RwMap, RwRow};
+pub use rw::{Rw, RwMap, RwRecord, RwRow};
 
 mod step;
 pub use step::ExecStep;
@@ -16,20 +16,28 @@ use bus_mapping::{
     },
     Error,
 };
-use eth_types::{sign_types::SignData, Address, Field, ToLittleEndian, ToScalar, Word, U256};
+use eth_types::{
+    chain_spec::ChainRules,
+    evm_types::{GasSchedule, GasTable, OpcodeId},
+    sign_types::SignData,
+    Address, Field, ToLittleEndian, ToScalar, Word, U256,
+};
 use halo2_proofs::circuit::Value;
 use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
 
 use super::{
-    mpt::ZktrieState as MptState, step::step_convert, tx::tx_convert, Bytecode, ExecStep,
-    MptUpdates, RwMap, Transaction,
+    mpt::ZktrieState as MptState, step::step_convert, tx::tx_convert, Bytecode, EthTrie,
+    EthTrieUpdate, ExecStep, MptUpdates, RwMap, Transaction,
 };
 use crate::util::Challenges;
 
 // TODO: Remove fields that are duplicated in`eth_block`
 /// Block is the struct used by all circuits, which contains all the needed
 /// data for witness generation.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(bound = "")]
 pub struct Block<F> {
     /// For historical reasons..
     pub _marker: std::marker::PhantomData<F>,
@@ -74,10 +82,12 @@ pub struct Block<F> {
     pub start_l1_queue_index: u64,
     /// IO to/from precompile calls.
     pub precompile_events: PrecompileEvents,
+    /// Rules of the hardfork the blocks are on
+    pub rules: ChainRules,
 }
 
 /// ...
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct BlockContexts {
     /// Hashmap that maps block number to its block context.
     pub ctxs: BTreeMap<u64, BlockContext>,
@@ -256,7 +266,10 @@ impl<F: Field> Block<F> {
         let num_rows_required_for_rw_table: usize = self.circuits_params.max_rws;
         let num_rows_required_for_fixed_table: usize = detect_fixed_table_tags(self)
             .iter()
-            .map(|tag| tag.build::<F>().count())
+            .map(|tag| {
+                tag.build::<F>(&self.circuits_params.gas_table, &self.rules)
+                    .count()
+            })
             .sum();
         let num_rows_required_for_bytecode_table: usize = self
             .bytecodes
@@ -308,7 +321,7 @@ impl<F: Field> Block<F> {
 }
 
 /// Block context for execution
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BlockContext {
     /// The address of the miner for the block
     pub coinbase: Address,
@@ -460,11 +473,96 @@ impl From<&circuit_input_builder::Block> for BlockContexts {
     }
 }
 
+/// Number of rows of the rw table when `max_rws` is dynamic, given the last
+/// EndBlock step.
+pub(crate) fn dynamic_max_rws(end_block_last: &ExecStep) -> usize {
+    end_block_last.rw_counter + end_block_last.rw_indices.len() + 1
+}
+
+/// Opcodes whose constant gas cost is the cost of an account access, a
+/// contract creation or a self destruct, which the circuits constrain apart
+/// from the fixed table.
+const UNREPRICEABLE_OPCODES: [OpcodeId; 11] = [
+    OpcodeId::BALANCE,
+    OpcodeId::EXTCODESIZE,
+    OpcodeId::EXTCODECOPY,
+    OpcodeId::EXTCODEHASH,
+    OpcodeId::CREATE,
+    OpcodeId::CREATE2,
+    OpcodeId::CALL,
+    OpcodeId::CALLCODE,
+    OpcodeId::DELEGATECALL,
+    OpcodeId::STATICCALL,
+    OpcodeId::SELFDESTRUCT,
+];
+
+/// Rules of the blocks of the chunk, which the fixed table of the EVM circuit
+/// holds, so a chunk can't cross a fork boundary. The chain is Scroll or not
+/// depending on the cargo features the circuits are built with.
+fn rules_convert(block: &circuit_input_builder::Block) -> Result<ChainRules, Error> {
+    let rules = block
+        .headers
+        .keys()
+        .map(|block_num| block.rules(*block_num))
+        .dedup()
+        .collect_vec();
+    let rules = match rules[..] {
+        [] => block.rules(0),
+        [rules] => rules,
+        _ => {
+            return Err(Error::InternalError(
+                "the blocks of a chunk must follow the rules of the same hardfork",
+            ))
+        }
+    };
+    if rules.scroll != cfg!(feature = "scroll") {
+        return Err(Error::InternalError(
+            "the circuits aren't built for the chain of the block",
+        ));
+    }
+    Ok(rules)
+}
+
+/// Collect the constant gas costs of `gas_schedule` differing from the
+/// default ones, failing on the repricings the circuits can't prove.
+fn gas_table_convert(gas_schedule: &dyn GasSchedule) -> Result<GasTable, Error> {
+    let mut gas_table = GasTable::default();
+    for opcode in OpcodeId::iter() {
+        let default_gas_cost = opcode.constant_gas_cost();
+        let gas_cost = gas_schedule.constant_gas_cost(opcode);
+        if gas_cost == default_gas_cost {
+            continue;
+        }
+        // Opcodes without constant gas cost have no row in the fixed table.
+        if default_gas_cost.0 == 0 || gas_cost.0 == 0 || UNREPRICEABLE_OPCODES.contains(&opcode) {
+            return Err(Error::InternalError(
+                "gas schedule reprices an opcode the circuits can't",
+            ));
+        }
+        gas_table = gas_table.with_constant_gas_cost(opcode, gas_cost);
+    }
+    Ok(gas_table)
+}
+
+/// Gas table of the circuits, which the fixed table of the EVM circuit holds,
+/// so the gas schedule of the block must be the same.
+fn circuits_gas_table_convert(block: &circuit_input_builder::Block) -> Result<GasTable, Error> {
+    let gas_table = gas_table_convert(&block.circuits_params.gas_table)?;
+    if gas_table_convert(block.gas_schedule())? != gas_table {
+        return Err(Error::InternalError(
+            "the gas schedule of the block isn't the one of the circuits",
+        ));
+    }
+    Ok(gas_table)
+}
+
 /// Convert a block struct in bus-mapping to a witness block used in circuits
 pub fn block_convert<F: Field>(
     block: &circuit_input_builder::Block,
     code_db: &bus_mapping::state_db::CodeDB,
 ) -> Result<Block<F>, Error> {
+    let rules = rules_convert(block)?;
+    let gas_table = circuits_gas_table_convert(block)?;
     let rws = RwMap::from(&block.container);
     rws.check_value()?;
     let num_txs = block.txs().len();
@@ -484,7 +582,7 @@ pub fn block_convert<F: Field>(
         end_block_last
     );
     let max_rws = if block.circuits_params.max_rws == 0 {
-        end_block_last.rw_counter + end_block_last.rw_indices.len() + 1
+        dynamic_max_rws(&end_block_last)
     } else {
         block.circuits_params.max_rws
     };
@@ -500,9 +598,10 @@ pub fn block_convert<F: Field>(
     } else {
         end_block_last.rw_counter + 1
     };
-    let total_tx_as_txid = num_txs;
+    // the withdraw root is read in a pseudo tx following the last tx
+    let withdraw_root_tx_id = num_txs + 1;
     let withdraw_root_entry = mpt_updates.get(&super::rw::Rw::AccountStorage {
-        tx_id: total_tx_as_txid,
+        tx_id: withdraw_root_tx_id,
         account_address: *bus_mapping::l2_predeployed::message_queue::ADDRESS,
         storage_key: bus_mapping::l2_predeployed::message_queue::WITHDRAW_TRIE_ROOT_SLOT,
         // following field is not used in Mpt::Key so we just fill them arbitrarily
@@ -564,6 +663,7 @@ pub fn block_convert<F: Field>(
         sha3_inputs: block.sha3_inputs.clone(),
         circuits_params: CircuitsParams {
             max_rws,
+            gas_table,
             ..block.circuits_params
         },
         prev_state_root: block.prev_state_root,
@@ -575,6 +675,7 @@ pub fn block_convert<F: Field>(
         chain_id,
         start_l1_queue_index: block.start_l1_queue_index,
         precompile_events: block.precompile_events.clone(),
+        rules,
     })
 }
 
@@ -599,9 +700,108 @@ pub fn block_apply_mpt_state<F: Field>(block: &mut Block<F>, mpt_state: &MptStat
     block.state_root = Some(block.mpt_updates.new_root());
 }
 
+/// Generate the mpt witness by applying the updates to the Ethereum state trie
+/// `trie`, instead of the zktrie. The hashed proof nodes are added to the
+/// keccak inputs. There is no circuit proving these updates yet, only the mpt
+/// table rows and the state roots are checked by the other circuits.
+pub fn block_apply_eth_trie<F: Field>(block: &mut Block<F>, trie: &mut EthTrie) {
+    block.mpt_updates.fill_state_roots_with_eth_trie(trie);
+    block.state_root = Some(block.mpt_updates.new_root());
+    let proof_nodes = block
+        .mpt_updates
+        .eth_trie_updates
+        .iter()
+        .flat_map(EthTrieUpdate::nodes)
+        .cloned()
+        .collect::<Vec<_>>();
+    block.keccak_inputs.extend(proof_nodes);
+}
+
 /// Mocking generate mpt witness from mpt states
 pub fn block_mocking_apply_mpt<F: Field>(block: &mut Block<F>) {
     block.mpt_updates.mock_fill_state_roots();
     block.state_root = Some(block.mpt_updates.new_root());
     block.prev_state_root = block.mpt_updates.old_root();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bus_mapping::circuit_input_builder::BlockHead;
+    use eth_types::{
+        chain_spec::{ChainSpec, ForkCondition, Hardfork},
+        evm_types::GasCost,
+    };
+    use std::sync::Arc;
+
+    #[test]
+    fn gas_table_convert_rejects_unprovable_repricings() {
+        let gas_table = GasTable::default()
+            .with_constant_gas_cost(OpcodeId::ADD, GasCost(5))
+            .with_constant_gas_cost(OpcodeId::MUL, OpcodeId::MUL.constant_gas_cost());
+        assert_eq!(
+            gas_table_convert(&gas_table).unwrap(),
+            GasTable::default().with_constant_gas_cost(OpcodeId::ADD, GasCost(5))
+        );
+
+        for (opcode, gas_cost) in [
+            (OpcodeId::ADD, GasCost(0)),
+            (OpcodeId::SLOAD, GasCost(100)),
+            (OpcodeId::BALANCE, GasCost(200)),
+        ] {
+            let gas_table = GasTable::default().with_constant_gas_cost(opcode, gas_cost);
+            assert!(gas_table_convert(&gas_table).is_err());
+        }
+    }
+
+    #[test]
+    fn rules_convert_rejects_chunks_crossing_a_fork() {
+        let spec = ChainSpec::london()
+            .with_scroll(cfg!(feature = "scroll"))
+            .with_fork(Hardfork::Shanghai, ForkCondition::Block(2));
+        let chunk = |numbers: &[u64]| {
+            let headers = numbers
+                .iter()
+                .map(|number| {
+                    let eth_block = eth_types::Block::<eth_types::Transaction> {
+                        author: Some(Address::zero()),
+                        number: Some((*number).into()),
+                        ..Default::default()
+                    };
+                    BlockHead::new(1, vec![], &eth_block).unwrap()
+                })
+                .collect_vec();
+            circuit_input_builder::Block::from_headers(&headers, Default::default())
+                .with_chain_spec(spec.clone())
+        };
+        assert!(!rules_convert(&chunk(&[0, 1])).unwrap().is_shanghai());
+        assert!(rules_convert(&chunk(&[2, 3])).unwrap().is_shanghai());
+        assert!(rules_convert(&chunk(&[1, 2])).is_err());
+
+        let other_chain =
+            chunk(&[2]).with_chain_spec(spec.clone().with_scroll(!cfg!(feature = "scroll")));
+        assert!(rules_convert(&other_chain).is_err());
+    }
+
+    #[test]
+    fn circuits_gas_table_convert_rejects_other_gas_schedules() {
+        let gas_table = GasTable::default().with_constant_gas_cost(OpcodeId::ADD, GasCost(5));
+        let block = circuit_input_builder::Block::from_headers(
+            &[],
+            CircuitsParams {
+                gas_table,
+                ..Default::default()
+            },
+        );
+        assert_eq!(circuits_gas_table_convert(&block).unwrap(), gas_table);
+
+        let same_schedule = block.clone().with_gas_schedule(Arc::new(gas_table));
+        assert_eq!(
+            circuits_gas_table_convert(&same_schedule).unwrap(),
+            gas_table
+        );
+
+        let other_schedule = block.with_gas_schedule(Arc::new(GasTable::default()));
+        assert!(circuits_gas_table_convert(&other_schedule).is_err());
+    }
+}
@@ -10,10 +10,53 @@ use mpt_zktrie::{
     state,
     state::{builder::init_hash_scheme, witness::WitnessGenerator},
 };
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 pub use state::ZktrieState;
 use std::collections::BTreeMap;
 
+pub mod eth_trie;
+use eth_trie::{EthTrie, EthTrieUpdate};
+
+/// A state trie the MPT updates can be applied to, which provides the state
+/// roots before and after each update along with its proof.
+pub trait StateTrie {
+    /// Proof of an update, as the witness of the circuit proving it
+    type Proof;
+
+    /// Apply the update of the account field selected by `proof_type`, or of
+    /// the storage slot `storage_key`, returns the old and new state roots,
+    /// as they are encoded in the MptTable, and the proof.
+    fn apply_update(
+        &mut self,
+        proof_type: MPTProofType,
+        address: Address,
+        new_value: Word,
+        old_value: Word,
+        storage_key: Option<Word>,
+    ) -> (Word, Word, Self::Proof);
+}
+
+impl StateTrie for WitnessGenerator {
+    type Proof = SMTTrace;
+
+    fn apply_update(
+        &mut self,
+        proof_type: MPTProofType,
+        address: Address,
+        new_value: Word,
+        old_value: Word,
+        storage_key: Option<Word>,
+    ) -> (Word, Word, Self::Proof) {
+        let smt_trace =
+            self.handle_new_state(proof_type, address, new_value, old_value, storage_key);
+        (
+            U256::from_little_endian(smt_trace.account_path[0].root.as_ref()),
+            U256::from_little_endian(smt_trace.account_path[1].root.as_ref()),
+            smt_trace,
+        )
+    }
+}
+
 /// Used to store withdraw proof
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
 pub struct WithdrawProof {
@@ -26,7 +69,7 @@ pub struct WithdrawProof {
 }
 
 /// An MPT update whose validity is proved by the MptCircuit
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MptUpdate {
     key: Key,
     old_value: Word,
@@ -35,6 +78,7 @@ pub struct MptUpdate {
     new_root: Word,
     // for debugging
     #[cfg(debug_assertions)]
+    #[serde(default)]
     original_rws: Vec<Rw>,
 }
 
@@ -89,19 +133,69 @@ impl Default for MptUpdate {
 }
 
 /// All the MPT updates in the MptCircuit, accessible by their key
-#[derive(Default, Clone, Debug)]
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+#[serde(remote = "Self")]
 pub struct MptUpdates {
     old_root: Word,
     new_root: Word,
+    #[serde(with = "serde_updates")]
     updates: BTreeMap<Key, MptUpdate>,
     /// TODO: is here the best place for this?
     /// Withdraw proof after this block
     pub withdraw_proof: WithdrawProof,
     /// The detailed mpt witness
     pub smt_traces: Vec<SMTTrace>,
+    /// The proofs of the updates, when they are applied to an [`EthTrie`]
+    pub eth_trie_updates: Vec<EthTrieUpdate>,
+    #[serde(skip)]
     pub(crate) proof_types: Vec<MPTProofType>,
 }
 
+impl Serialize for MptUpdates {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Self::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for MptUpdates {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut mpt_updates = Self::deserialize(deserializer)?;
+        // The proof types are not serialized, since they are given by the
+        // updates once those have been applied to a state trie.
+        if !mpt_updates.smt_traces.is_empty() || !mpt_updates.eth_trie_updates.is_empty() {
+            mpt_updates.proof_types = mpt_updates
+                .updates
+                .values()
+                .map(MptUpdate::proof_type)
+                .collect();
+        }
+        Ok(mpt_updates)
+    }
+}
+
+// The keys of the updates are not strings, so the map is serialized as a list
+// of entries.
+mod serde_updates {
+    use super::{Key, MptUpdate};
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::collections::BTreeMap;
+
+    pub(super) fn serialize<S: Serializer>(
+        updates: &BTreeMap<Key, MptUpdate>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(updates.iter())
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<BTreeMap<Key, MptUpdate>, D::Error> {
+        Ok(Vec::<(Key, MptUpdate)>::deserialize(deserializer)?
+            .into_iter()
+            .collect())
+    }
+}
+
 /// The field element encoding of an MPT update, which is used by the MptTable
 #[derive(Debug, Clone, Copy)]
 pub struct MptUpdateRow<F>(pub(crate) [F; 7]);
@@ -222,14 +316,38 @@ impl MptUpdates {
         &mut self,
         mut wit_gen: WitnessGenerator,
     ) -> WitnessGenerator {
-        self.smt_traces = Vec::new();
+        self.smt_traces = self.apply_updates(&mut wit_gen);
+        log::debug!(
+            "mpt update roots (after zktrie) {:#x} {:#x}",
+            self.old_root,
+            self.new_root
+        );
+
+        wit_gen
+    }
+
+    /// Fill the state roots by applying the updates to the Ethereum state
+    /// trie `trie`, instead of the zktrie.
+    pub(crate) fn fill_state_roots_with_eth_trie(&mut self, trie: &mut EthTrie) {
+        self.old_root = U256::from_big_endian(trie.root().as_bytes());
+        self.new_root = self.old_root;
+        self.eth_trie_updates = self.apply_updates(trie);
+        log::debug!(
+            "mpt update roots (after eth trie) {:#x} {:#x}",
+            self.old_root,
+            self.new_root
+        );
+    }
+
+    fn apply_updates<T: StateTrie>(&mut self, trie: &mut T) -> Vec<T::Proof> {
         self.proof_types = Vec::new();
+        let mut proofs = Vec::new();
 
         for (key, update) in &mut self.updates {
             log::trace!("apply update {:?} {:#?}", key, update);
             let key = key.set_non_exists(update.old_value, update.new_value);
             let proof_tip = update.proof_type();
-            let smt_trace = wit_gen.handle_new_state(
+            let (old_root, new_root, proof) = trie.apply_update(
                 proof_tip,
                 match key {
                     Key::Account { address, .. } | Key::AccountStorage { address, .. } => address,
@@ -241,24 +359,15 @@ impl MptUpdates {
                     Key::AccountStorage { storage_key, .. } => Some(storage_key),
                 },
             );
-            log::trace!(
-                "fill_state_roots {:?}->{:?}",
-                smt_trace.account_path[0].root,
-                smt_trace.account_path[1].root
-            );
-            update.old_root = U256::from_little_endian(smt_trace.account_path[0].root.as_ref());
-            update.new_root = U256::from_little_endian(smt_trace.account_path[1].root.as_ref());
+            log::trace!("fill_state_roots {:#x}->{:#x}", old_root, new_root);
+            update.old_root = old_root;
+            update.new_root = new_root;
             self.new_root = update.new_root;
-            self.smt_traces.push(smt_trace);
+            proofs.push(proof);
             self.proof_types.push(proof_tip);
         }
-        log::debug!(
-            "mpt update roots (after zktrie) {:#x} {:#x}",
-            self.old_root,
-            self.new_root
-        );
 
-        wit_gen
+        proofs
     }
 
     pub(crate) fn mock_from(rows: &[Rw]) -> Self {
@@ -425,7 +534,7 @@ impl MptUpdate {
     }
 }
 
-#[derive(Eq, PartialEq, Hash, Clone, Debug, Copy, PartialOrd, Ord)]
+#[derive(Eq, PartialEq, Hash, Clone, Debug, Copy, PartialOrd, Ord, Serialize, Deserialize)]
 enum Key {
     Account {
         address: Address,
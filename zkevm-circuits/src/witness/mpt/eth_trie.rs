@@ -0,0 +1,853 @@
+//! Ethereum (hexary, keccak and RLP) Merkle-Patricia state trie, used as a
+//! [`StateTrie`](super::StateTrie) backend instead of the zktrie.
+//!
+//! The trie nodes are kept in a db indexed by their hash, so a partial state
+//! can be built from the nodes of `eth_getProof`, as long as the updated
+//! accounts and storage slots have been proven.
+
+use super::StateTrie;
+use eth_types::{Address, Word, H256, U256};
+use ethers_core::utils::{
+    keccak256,
+    rlp::{self, DecoderError, Rlp, RlpStream},
+};
+use mpt_zktrie::mpt_circuits::MPTProofType;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fmt, sync::LazyLock};
+
+/// Root of an empty trie, `keccak256(rlp(""))`
+pub static EMPTY_TRIE_ROOT: LazyLock<H256> = LazyLock::new(|| H256(keccak256(EMPTY_NODE_REF)));
+
+/// RLP of an empty string, which is the reference to an empty node
+const EMPTY_NODE_REF: &[u8] = &[0x80];
+
+/// Error of a trie operation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TrieError {
+    /// The node is needed, but not in the db
+    MissingNode(H256),
+    /// The node can't be decoded
+    InvalidNode(DecoderError),
+}
+
+impl fmt::Display for TrieError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::MissingNode(hash) => write!(f, "missing trie node {hash:?}"),
+            Self::InvalidNode(err) => write!(f, "invalid trie node: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for TrieError {}
+
+impl From<DecoderError> for TrieError {
+    fn from(err: DecoderError) -> Self {
+        Self::InvalidNode(err)
+    }
+}
+
+/// An account in the Ethereum state trie
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EthAccount {
+    /// nonce
+    pub nonce: u64,
+    /// balance
+    pub balance: U256,
+    /// root of the storage trie
+    pub storage_root: H256,
+    /// keccak code hash, zero while the account does not exist
+    pub code_hash: H256,
+}
+
+impl Default for EthAccount {
+    fn default() -> Self {
+        Self {
+            nonce: 0,
+            balance: U256::zero(),
+            storage_root: *EMPTY_TRIE_ROOT,
+            code_hash: H256::zero(),
+        }
+    }
+}
+
+impl EthAccount {
+    /// An account with all its fields at default does not exist, so it is
+    /// removed from the trie
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut stream = RlpStream::new_list(4);
+        stream.append(&self.nonce);
+        stream.append(&self.balance);
+        stream.append(&self.storage_root);
+        stream.append(&self.code_hash);
+        stream.out().to_vec()
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Self, TrieError> {
+        let rlp = Rlp::new(bytes);
+        Ok(Self {
+            nonce: rlp.val_at(0)?,
+            balance: rlp.val_at(1)?,
+            storage_root: rlp.val_at(2)?,
+            code_hash: rlp.val_at(3)?,
+        })
+    }
+}
+
+/// Proof of an update of the [`EthTrie`], as the trie nodes on the paths to
+/// the updated account (and storage slot), before and after the update. The
+/// first node of an account proof is the state root node.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EthTrieUpdate {
+    /// account trie nodes before the update
+    pub old_account_proof: Vec<Vec<u8>>,
+    /// account trie nodes after the update
+    pub new_account_proof: Vec<Vec<u8>>,
+    /// storage trie nodes before the update, empty for account updates
+    pub old_storage_proof: Vec<Vec<u8>>,
+    /// storage trie nodes after the update, empty for account updates
+    pub new_storage_proof: Vec<Vec<u8>>,
+}
+
+impl EthTrieUpdate {
+    /// All the nodes of the proof
+    pub fn nodes(&self) -> impl Iterator<Item = &Vec<u8>> {
+        self.old_account_proof
+            .iter()
+            .chain(self.new_account_proof.iter())
+            .chain(self.old_storage_proof.iter())
+            .chain(self.new_storage_proof.iter())
+    }
+}
+
+/// Ethereum state trie, made of the account trie and the storage tries, which
+/// share the same node db
+#[derive(Debug, Clone)]
+pub struct EthTrie {
+    db: NodeDb,
+    root: H256,
+}
+
+impl Default for EthTrie {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EthTrie {
+    /// An empty state trie
+    pub fn new() -> Self {
+        Self {
+            db: NodeDb::default(),
+            root: *EMPTY_TRIE_ROOT,
+        }
+    }
+
+    /// A partial state trie at `root`, made of the nodes of account and
+    /// storage proofs
+    pub fn from_proofs<'a>(root: H256, nodes: impl IntoIterator<Item = &'a [u8]>) -> Self {
+        let mut trie = Self::new();
+        trie.root = root;
+        trie.add_nodes(nodes);
+        trie
+    }
+
+    /// Add the nodes of account or storage proofs to the db
+    pub fn add_nodes<'a>(&mut self, nodes: impl IntoIterator<Item = &'a [u8]>) {
+        for node in nodes {
+            self.db.0.insert(H256(keccak256(node)), node.to_vec());
+        }
+    }
+
+    /// State root
+    pub fn root(&self) -> H256 {
+        self.root
+    }
+
+    /// The account at `address`, `None` if it does not exist
+    pub fn account(&self, address: Address) -> Result<Option<EthAccount>, TrieError> {
+        self.db
+            .get(self.root, &account_path(address))?
+            .map(|bytes| EthAccount::decode(&bytes))
+            .transpose()
+    }
+
+    /// The value of the storage slot `key` of the account at `address`
+    pub fn storage(&self, address: Address, key: Word) -> Result<Word, TrieError> {
+        let Some(account) = self.account(address)? else {
+            return Ok(Word::zero());
+        };
+        self.db
+            .get(account.storage_root, &storage_path(key))?
+            .map(|bytes| rlp::decode::<Word>(&bytes).map_err(TrieError::from))
+            .unwrap_or(Ok(Word::zero()))
+    }
+
+    /// The account trie nodes from the root to the account at `address`
+    pub fn account_proof(&self, address: Address) -> Result<Vec<Vec<u8>>, TrieError> {
+        self.db.proof(self.root, &account_path(address))
+    }
+
+    /// The storage trie nodes from the storage root of the account at
+    /// `address` to the slot `key`, empty if the account does not exist
+    pub fn storage_proof(&self, address: Address, key: Word) -> Result<Vec<Vec<u8>>, TrieError> {
+        match self.account(address)? {
+            Some(account) => self.db.proof(account.storage_root, &storage_path(key)),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Set the account at `address`, or remove it if `account` is `None` or
+    /// empty
+    pub fn set_account(
+        &mut self,
+        address: Address,
+        account: Option<EthAccount>,
+    ) -> Result<(), TrieError> {
+        let value = account
+            .filter(|account| !account.is_empty())
+            .map(|account| account.encode());
+        self.root = self.db.set(self.root, &account_path(address), value)?;
+        Ok(())
+    }
+
+    /// Set the storage slot `key` of the account at `address`, a zero value
+    /// removes the slot
+    pub fn set_storage(
+        &mut self,
+        address: Address,
+        key: Word,
+        value: Word,
+    ) -> Result<(), TrieError> {
+        let mut account = self.account(address)?.unwrap_or_default();
+        let value = (!value.is_zero()).then(|| rlp::encode(&value).to_vec());
+        account.storage_root = self
+            .db
+            .set(account.storage_root, &storage_path(key), value)?;
+        self.set_account(address, Some(account))
+    }
+
+    /// Apply an update of the account field selected by `proof_type`, or of
+    /// the storage slot `storage_key`. The account fields which are not in an
+    /// Ethereum account (e.g. the code size) are left unchanged.
+    pub fn update(
+        &mut self,
+        proof_type: MPTProofType,
+        address: Address,
+        new_value: Word,
+        old_value: Word,
+        storage_key: Option<Word>,
+    ) -> Result<EthTrieUpdate, TrieError> {
+        let old_account_proof = self.account_proof(address)?;
+        let old_storage_proof = match storage_key {
+            Some(key) => self.storage_proof(address, key)?,
+            None => Vec::new(),
+        };
+
+        let current = if let Some(key) = storage_key {
+            let current = self.storage(address, key)?;
+            // writing zero to a slot of a non existing account proves its non existence
+            if !new_value.is_zero() || self.account(address)?.is_some() {
+                self.set_storage(address, key, new_value)?;
+            }
+            current
+        } else {
+            let mut account = self.account(address)?.unwrap_or_default();
+            let is_code_hash = if cfg!(feature = "poseidon-codehash") {
+                matches!(proof_type, MPTProofType::CodeHashExists)
+            } else {
+                matches!(proof_type, MPTProofType::PoseidonCodeHashExists)
+            };
+            let current = match proof_type {
+                MPTProofType::NonceChanged => {
+                    Word::from(std::mem::replace(&mut account.nonce, new_value.low_u64()))
+                }
+                MPTProofType::BalanceChanged => std::mem::replace(&mut account.balance, new_value),
+                _ if is_code_hash => {
+                    let mut code_hash = [0u8; 32];
+                    new_value.to_big_endian(&mut code_hash);
+                    let current = std::mem::replace(&mut account.code_hash, H256(code_hash));
+                    Word::from_big_endian(current.as_bytes())
+                }
+                _ => old_value,
+            };
+            self.set_account(address, Some(account))?;
+            current
+        };
+        if current != old_value {
+            log::error!(
+                "eth trie value of {address:?} {storage_key:?} ({proof_type:?}) is {current:#x}, expect {old_value:#x}"
+            );
+        }
+
+        Ok(EthTrieUpdate {
+            old_account_proof,
+            new_account_proof: self.account_proof(address)?,
+            new_storage_proof: match storage_key {
+                Some(key) => self.storage_proof(address, key)?,
+                None => Vec::new(),
+            },
+            old_storage_proof,
+        })
+    }
+}
+
+impl StateTrie for EthTrie {
+    type Proof = EthTrieUpdate;
+
+    fn apply_update(
+        &mut self,
+        proof_type: MPTProofType,
+        address: Address,
+        new_value: Word,
+        old_value: Word,
+        storage_key: Option<Word>,
+    ) -> (Word, Word, Self::Proof) {
+        let old_root = Word::from_big_endian(self.root.as_bytes());
+        let proof = self
+            .update(proof_type, address, new_value, old_value, storage_key)
+            .unwrap_or_else(|err| panic!("apply update of {address:?} to eth trie: {err}"));
+        let new_root = Word::from_big_endian(self.root.as_bytes());
+        (old_root, new_root, proof)
+    }
+}
+
+fn account_path(address: Address) -> Vec<u8> {
+    nibbles(&keccak256(address.as_bytes()))
+}
+
+fn storage_path(key: Word) -> Vec<u8> {
+    let mut key_bytes = [0u8; 32];
+    key.to_big_endian(&mut key_bytes);
+    nibbles(&keccak256(key_bytes))
+}
+
+fn nibbles(bytes: &[u8]) -> Vec<u8> {
+    bytes
+        .iter()
+        .flat_map(|byte| [byte >> 4, byte & 0xf])
+        .collect()
+}
+
+fn common_prefix(a: &[u8], b: &[u8]) -> usize {
+    a.iter().zip(b).take_while(|(a, b)| a == b).count()
+}
+
+/// Hex prefix encoding of a path
+fn encode_path(path: &[u8], is_leaf: bool) -> Vec<u8> {
+    let flag = if is_leaf { 2 } else { 0 };
+    let (first, rest) = if path.len() % 2 == 1 {
+        (((flag + 1) << 4) | path[0], &path[1..])
+    } else {
+        (flag << 4, path)
+    };
+    std::iter::once(first)
+        .chain(rest.chunks(2).map(|pair| (pair[0] << 4) | pair[1]))
+        .collect()
+}
+
+fn decode_path(bytes: &[u8]) -> Result<(Vec<u8>, bool), TrieError> {
+    let nibbles = nibbles(bytes);
+    let flag = *nibbles.first().ok_or(DecoderError::RlpIsTooShort)?;
+    let is_leaf = flag & 2 != 0;
+    let skip = if flag & 1 != 0 { 1 } else { 2 };
+    Ok((nibbles[skip..].to_vec(), is_leaf))
+}
+
+/// A trie node. The references to children are the RLP items stored in the
+/// node: the hash of the child, or the child itself if its RLP is shorter
+/// than 32 bytes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Node {
+    Empty,
+    Leaf {
+        path: Vec<u8>,
+        value: Vec<u8>,
+    },
+    Extension {
+        path: Vec<u8>,
+        child: Vec<u8>,
+    },
+    Branch {
+        children: Box<[Vec<u8>; 16]>,
+        value: Vec<u8>,
+    },
+}
+
+impl Node {
+    fn empty_children() -> Box<[Vec<u8>; 16]> {
+        Box::new(std::array::from_fn(|_| EMPTY_NODE_REF.to_vec()))
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        match self {
+            Self::Empty => EMPTY_NODE_REF.to_vec(),
+            Self::Leaf { path, value } => {
+                let mut stream = RlpStream::new_list(2);
+                stream.append(&encode_path(path, true));
+                stream.append(value);
+                stream.out().to_vec()
+            }
+            Self::Extension { path, child } => {
+                let mut stream = RlpStream::new_list(2);
+                stream.append(&encode_path(path, false));
+                stream.append_raw(child, 1);
+                stream.out().to_vec()
+            }
+            Self::Branch { children, value } => {
+                let mut stream = RlpStream::new_list(17);
+                for child in children.iter() {
+                    stream.append_raw(child, 1);
+                }
+                stream.append(value);
+                stream.out().to_vec()
+            }
+        }
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Self, TrieError> {
+        let rlp = Rlp::new(bytes);
+        if !rlp.is_list() {
+            return if rlp.is_empty() {
+                Ok(Self::Empty)
+            } else {
+                Err(DecoderError::RlpExpectedToBeList.into())
+            };
+        }
+        match rlp.item_count()? {
+            2 => {
+                let (path, is_leaf) = decode_path(rlp.at(0)?.data()?)?;
+                Ok(if is_leaf {
+                    Self::Leaf {
+                        path,
+                        value: rlp.at(1)?.data()?.to_vec(),
+                    }
+                } else {
+                    Self::Extension {
+                        path,
+                        child: rlp.at(1)?.as_raw().to_vec(),
+                    }
+                })
+            }
+            17 => {
+                let mut children = Self::empty_children();
+                for (i, child) in children.iter_mut().enumerate() {
+                    *child = rlp.at(i)?.as_raw().to_vec();
+                }
+                Ok(Self::Branch {
+                    children,
+                    value: rlp.at(16)?.data()?.to_vec(),
+                })
+            }
+            _ => Err(DecoderError::RlpIncorrectListLen.into()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct NodeDb(HashMap<H256, Vec<u8>>);
+
+impl Default for NodeDb {
+    fn default() -> Self {
+        Self(HashMap::from([(*EMPTY_TRIE_ROOT, EMPTY_NODE_REF.to_vec())]))
+    }
+}
+
+impl NodeDb {
+    fn get_raw(&self, hash: H256) -> Result<&Vec<u8>, TrieError> {
+        self.0.get(&hash).ok_or(TrieError::MissingNode(hash))
+    }
+
+    /// The RLP of the node referenced by `child`, and if it is stored by hash
+    fn resolve_raw(&self, child: &[u8]) -> Result<(Vec<u8>, bool), TrieError> {
+        let rlp = Rlp::new(child);
+        if rlp.is_list() {
+            return Ok((child.to_vec(), false));
+        }
+        match rlp.data()? {
+            [] => Ok((EMPTY_NODE_REF.to_vec(), false)),
+            hash if hash.len() == 32 => Ok((self.get_raw(H256::from_slice(hash))?.clone(), true)),
+            _ => Err(DecoderError::RlpInvalidLength.into()),
+        }
+    }
+
+    fn resolve(&self, child: &[u8]) -> Result<Node, TrieError> {
+        Node::decode(&self.resolve_raw(child)?.0)
+    }
+
+    /// Store the node, returns the reference to it
+    fn store(&mut self, node: &Node) -> Vec<u8> {
+        let bytes = node.encode();
+        if bytes.len() < 32 {
+            return bytes;
+        }
+        let hash = H256(keccak256(&bytes));
+        self.0.insert(hash, bytes);
+        rlp::encode(&hash).to_vec()
+    }
+
+    /// Store the root node, which is always referenced by hash
+    fn store_root(&mut self, node: &Node) -> H256 {
+        let bytes = node.encode();
+        let hash = H256(keccak256(&bytes));
+        self.0.insert(hash, bytes);
+        hash
+    }
+
+    fn get(&self, root: H256, path: &[u8]) -> Result<Option<Vec<u8>>, TrieError> {
+        let mut node = Node::decode(self.get_raw(root)?)?;
+        let mut path = path;
+        loop {
+            let child = match node {
+                Node::Empty => return Ok(None),
+                Node::Leaf {
+                    path: leaf_path,
+                    value,
+                } => return Ok((leaf_path == path).then_some(value)),
+                Node::Extension {
+                    path: ext_path,
+                    child,
+                } => match path.strip_prefix(ext_path.as_slice()) {
+                    Some(rest) => {
+                        path = rest;
+                        child
+                    }
+                    None => return Ok(None),
+                },
+                Node::Branch { children, value } => match path.split_first() {
+                    Some((&index, rest)) => {
+                        path = rest;
+                        children[index as usize].clone()
+                    }
+                    None => return Ok((!value.is_empty()).then_some(value)),
+                },
+            };
+            node = self.resolve(&child)?;
+        }
+    }
+
+    /// The nodes stored by hash on the path, from the root
+    fn proof(&self, root: H256, path: &[u8]) -> Result<Vec<Vec<u8>>, TrieError> {
+        let mut proof = vec![self.get_raw(root)?.clone()];
+        let mut node = Node::decode(&proof[0])?;
+        let mut path = path;
+        loop {
+            let child = match node {
+                Node::Empty | Node::Leaf { .. } => break,
+                Node::Extension {
+                    path: ext_path,
+                    child,
+                } => match path.strip_prefix(ext_path.as_slice()) {
+                    Some(rest) => {
+                        path = rest;
+                        child
+                    }
+                    None => break,
+                },
+                Node::Branch { children, .. } => match path.split_first() {
+                    Some((&index, rest)) => {
+                        path = rest;
+                        children[index as usize].clone()
+                    }
+                    None => break,
+                },
+            };
+            let (bytes, is_hashed) = self.resolve_raw(&child)?;
+            node = Node::decode(&bytes)?;
+            if is_hashed {
+                proof.push(bytes);
+            }
+        }
+        Ok(proof)
+    }
+
+    /// Set (or remove if `value` is `None`) the value at `path`, returns the
+    /// new root
+    fn set(&mut self, root: H256, path: &[u8], value: Option<Vec<u8>>) -> Result<H256, TrieError> {
+        let node = Node::decode(self.get_raw(root)?)?;
+        let node = match value {
+            Some(value) => self.insert(node, path, value)?,
+            None => self.remove(node, path)?,
+        };
+        Ok(self.store_root(&node))
+    }
+
+    fn insert(&mut self, node: Node, path: &[u8], value: Vec<u8>) -> Result<Node, TrieError> {
+        Ok(match node {
+            Node::Empty => Node::Leaf {
+                path: path.to_vec(),
+                value,
+            },
+            Node::Leaf {
+                path: leaf_path,
+                value: leaf_value,
+            } => {
+                if leaf_path == path {
+                    return Ok(Node::Leaf {
+                        path: leaf_path,
+                        value,
+                    });
+                }
+                let common = common_prefix(&leaf_path, path);
+                let mut children = Node::empty_children();
+                let mut branch_value = Vec::new();
+                self.branch_insert(
+                    &mut children,
+                    &mut branch_value,
+                    &leaf_path[common..],
+                    leaf_value,
+                );
+                self.branch_insert(&mut children, &mut branch_value, &path[common..], value);
+                self.join(
+                    &path[..common],
+                    Node::Branch {
+                        children,
+                        value: branch_value,
+                    },
+                )
+            }
+            Node::Extension {
+                path: ext_path,
+                child,
+            } => {
+                let common = common_prefix(&ext_path, path);
+                if common == ext_path.len() {
+                    let child_node = self.resolve(&child)?;
+                    let child_node = self.insert(child_node, &path[common..], value)?;
+                    return Ok(Node::Extension {
+                        path: ext_path,
+                        child: self.store(&child_node),
+                    });
+                }
+                let mut children = Node::empty_children();
+                let mut branch_value = Vec::new();
+                children[ext_path[common] as usize] = if ext_path.len() == common + 1 {
+                    child
+                } else {
+                    self.store(&Node::Extension {
+                        path: ext_path[common + 1..].to_vec(),
+                        child,
+                    })
+                };
+                self.branch_insert(&mut children, &mut branch_value, &path[common..], value);
+                self.join(
+                    &path[..common],
+                    Node::Branch {
+                        children,
+                        value: branch_value,
+                    },
+                )
+            }
+            Node::Branch {
+                mut children,
+                value: branch_value,
+            } => match path.split_first() {
+                Some((&index, rest)) => {
+                    let child_node = self.resolve(&children[index as usize])?;
+                    let child_node = self.insert(child_node, rest, value)?;
+                    children[index as usize] = self.store(&child_node);
+                    Node::Branch {
+                        children,
+                        value: branch_value,
+                    }
+                }
+                None => Node::Branch { children, value },
+            },
+        })
+    }
+
+    fn branch_insert(
+        &mut self,
+        children: &mut [Vec<u8>; 16],
+        branch_value: &mut Vec<u8>,
+        path: &[u8],
+        value: Vec<u8>,
+    ) {
+        match path.split_first() {
+            Some((&index, rest)) => {
+                children[index as usize] = self.store(&Node::Leaf {
+                    path: rest.to_vec(),
+                    value,
+                })
+            }
+            None => *branch_value = value,
+        }
+    }
+
+    fn remove(&mut self, node: Node, path: &[u8]) -> Result<Node, TrieError> {
+        Ok(match node {
+            Node::Empty => Node::Empty,
+            Node::Leaf {
+                path: leaf_path, ..
+            } if leaf_path == path => Node::Empty,
+            Node::Leaf { .. } => node,
+            Node::Extension {
+                path: ext_path,
+                child,
+            } => match path.strip_prefix(ext_path.as_slice()) {
+                Some(rest) => {
+                    let child_node = self.resolve(&child)?;
+                    let child_node = self.remove(child_node, rest)?;
+                    self.join(&ext_path, child_node)
+                }
+                None => Node::Extension {
+                    path: ext_path,
+                    child,
+                },
+            },
+            Node::Branch {
+                mut children,
+                mut value,
+            } => {
+                match path.split_first() {
+                    Some((&index, rest)) => {
+                        let child_node = self.resolve(&children[index as usize])?;
+                        let child_node = self.remove(child_node, rest)?;
+                        children[index as usize] = self.store(&child_node);
+                    }
+                    None => value.clear(),
+                }
+                let mut non_empty = children
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, child)| child.as_slice() != EMPTY_NODE_REF)
+                    .map(|(index, _)| index);
+                match (non_empty.next(), non_empty.next(), value.is_empty()) {
+                    (None, _, true) => Node::Empty,
+                    (None, _, false) => Node::Leaf {
+                        path: Vec::new(),
+                        value,
+                    },
+                    (Some(index), None, true) => {
+                        let child_node = self.resolve(&children[index])?;
+                        self.join(&[index as u8], child_node)
+                    }
+                    _ => Node::Branch { children, value },
+                }
+            }
+        })
+    }
+
+    /// The node at `prefix` above `node`, merging the paths of leaves and
+    /// extensions
+    fn join(&mut self, prefix: &[u8], node: Node) -> Node {
+        let joined = |path: Vec<u8>| [prefix, path.as_slice()].concat();
+        match node {
+            Node::Empty => Node::Empty,
+            Node::Leaf { path, value } => Node::Leaf {
+                path: joined(path),
+                value,
+            },
+            Node::Extension { path, child } => Node::Extension {
+                path: joined(path),
+                child,
+            },
+            Node::Branch { .. } if prefix.is_empty() => node,
+            Node::Branch { .. } => Node::Extension {
+                path: prefix.to_vec(),
+                child: self.store(&node),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn empty_trie_root() {
+        assert_eq!(
+            *EMPTY_TRIE_ROOT,
+            H256::from_str("0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421")
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn insert_and_remove() {
+        let mut trie = EthTrie::new();
+        let accounts = (1..=20u64)
+            .map(|i| {
+                (
+                    Address::from_low_u64_be(i),
+                    EthAccount {
+                        nonce: i,
+                        balance: U256::from(i * 1000),
+                        code_hash: H256::repeat_byte(i as u8),
+                        ..Default::default()
+                    },
+                )
+            })
+            .collect::<Vec<_>>();
+        let mut roots = vec![trie.root()];
+        for (address, account) in accounts.iter() {
+            trie.set_account(*address, Some(*account)).unwrap();
+            roots.push(trie.root());
+        }
+        for (address, account) in accounts.iter() {
+            assert_eq!(trie.account(*address).unwrap(), Some(*account));
+        }
+
+        // a trie rebuilt from the proofs only has the same accounts
+        let nodes = accounts
+            .iter()
+            .flat_map(|(address, _)| trie.account_proof(*address).unwrap())
+            .collect::<Vec<_>>();
+        let partial = EthTrie::from_proofs(trie.root(), nodes.iter().map(Vec::as_slice));
+        for (address, account) in accounts.iter() {
+            assert_eq!(partial.account(*address).unwrap(), Some(*account));
+        }
+
+        // removing in reverse order goes back through the same roots
+        for (address, _) in accounts.iter().rev() {
+            roots.pop();
+            trie.set_account(*address, None).unwrap();
+            assert_eq!(trie.root(), *roots.last().unwrap());
+        }
+        assert_eq!(trie.root(), *EMPTY_TRIE_ROOT);
+    }
+
+    #[test]
+    fn storage_update() {
+        let mut trie = EthTrie::new();
+        let address = Address::repeat_byte(0x11);
+        let code_hash_proof = if cfg!(feature = "poseidon-codehash") {
+            MPTProofType::CodeHashExists
+        } else {
+            MPTProofType::PoseidonCodeHashExists
+        };
+        trie.update(code_hash_proof, address, U256::one(), U256::zero(), None)
+            .unwrap();
+        let update = trie
+            .update(
+                MPTProofType::StorageChanged,
+                address,
+                U256::from(7),
+                U256::zero(),
+                Some(U256::from(3)),
+            )
+            .unwrap();
+        assert_eq!(trie.storage(address, U256::from(3)).unwrap(), U256::from(7));
+        assert_eq!(
+            keccak256(&update.new_account_proof[0]),
+            trie.root().to_fixed_bytes()
+        );
+        let account = trie.account(address).unwrap().unwrap();
+        assert_eq!(
+            keccak256(&update.new_storage_proof[0]),
+            account.storage_root.to_fixed_bytes()
+        );
+
+        // clearing the slot goes back to the empty storage
+        trie.set_storage(address, U256::from(3), U256::zero())
+            .unwrap();
+        let account = trie.account(address).unwrap().unwrap();
+        assert_eq!(account.storage_root, *EMPTY_TRIE_ROOT);
+    }
+}
@@ -1,8 +1,10 @@
 #![allow(missing_docs)]
-use std::collections::HashMap;
+use std::{collections::HashMap, io::Write};
 
 use bus_mapping::{
-    operation::{self, AccountField, CallContextField, TxLogField, TxReceiptField},
+    operation::{
+        self, AccountField, CallContextField, RwAttrs, RwFilter, Target, TxLogField, TxReceiptField,
+    },
     Error,
 };
 use eth_types::{Address, Field, ToLittleEndian, ToScalar, Word, U256};
@@ -10,6 +12,7 @@ use eth_types::{Address, Field, ToLittleEndian, ToScalar, Word, U256};
 use halo2_proofs::{circuit::Value, halo2curves::bn256::Fr};
 use itertools::Itertools;
 use rayon::prelude::{ParallelBridge, ParallelIterator};
+use serde::{Deserialize, Serialize};
 
 use crate::{
     evm_circuit::util::rlc,
@@ -23,7 +26,7 @@ const ERR_MSG_FIRST: &str = "first access reads don't change value";
 const ERR_MSG_NON_FIRST: &str = "non-first access reads don't change value";
 
 /// Rw constainer for a witness block
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct RwMap(pub HashMap<RwTableTag, Vec<Rw>>);
 
 impl std::ops::Index<(RwTableTag, usize)> for RwMap {
@@ -214,6 +217,86 @@ impl RwMap {
     pub fn rw_num(&self, tag: RwTableTag) -> usize {
         self.0.get(&tag).map(|v| v.len()).unwrap_or_default()
     }
+
+    /// Return the rows selected by `filter`, ordered by rw_counter.
+    pub fn query(&self, filter: &RwFilter) -> Vec<Rw> {
+        self.0
+            .iter()
+            .filter(|(tag, _)| {
+                filter
+                    .target_filter()
+                    .map_or(true, |target| target == Target::from(**tag))
+            })
+            .flat_map(|(_, rows)| rows)
+            .filter(|row| filter.matches(&row.attrs()))
+            .copied()
+            .sorted_by_key(Rw::rw_counter)
+            .collect()
+    }
+
+    /// Write rows as CSV, one [`RwRecord`] per line after a header line.
+    pub fn write_csv<W: Write>(rows: &[Rw], mut writer: W) -> std::io::Result<()> {
+        fn opt<T>(value: Option<T>, fmt: impl Fn(T) -> String) -> String {
+            value.map(fmt).unwrap_or_default()
+        }
+
+        writeln!(
+            writer,
+            "rw_counter,is_write,tag,id,address,field_tag,storage_key,value,value_prev"
+        )?;
+        for record in rows.iter().map(RwRecord::from) {
+            writeln!(
+                writer,
+                "{},{},{},{},{},{},{},{:#x},{}",
+                record.rw_counter,
+                record.is_write,
+                record.tag,
+                opt(record.id, |id| id.to_string()),
+                opt(record.address, |address| format!("{address:?}")),
+                opt(record.field_tag, |field_tag| field_tag.to_string()),
+                opt(record.storage_key, |key| format!("{key:#x}")),
+                record.value,
+                opt(record.value_prev, |value_prev| format!("{value_prev:#x}")),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Serialize rows as a JSON array of [`RwRecord`]s.
+    pub fn to_json(rows: &[Rw]) -> serde_json::Result<String> {
+        serde_json::to_string(&rows.iter().map(RwRecord::from).collect::<Vec<_>>())
+    }
+}
+
+/// Flat view of a [`Rw`] holding the rw table columns before they are
+/// encoded into field elements, used to export rows to external tools.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RwRecord {
+    pub rw_counter: usize,
+    pub is_write: bool,
+    pub tag: String,
+    pub id: Option<usize>,
+    pub address: Option<Address>,
+    pub field_tag: Option<u64>,
+    pub storage_key: Option<Word>,
+    pub value: Word,
+    pub value_prev: Option<Word>,
+}
+
+impl From<&Rw> for RwRecord {
+    fn from(rw: &Rw) -> Self {
+        Self {
+            rw_counter: rw.rw_counter(),
+            is_write: rw.is_write(),
+            tag: format!("{:?}", rw.tag()),
+            id: rw.id(),
+            address: rw.address(),
+            field_tag: rw.field_tag(),
+            storage_key: rw.storage_key(),
+            value: rw.value_word(),
+            value_prev: rw.value_prev_word(),
+        }
+    }
 }
 
 /// Rw key
@@ -221,7 +304,7 @@ pub type RwKey = (u64, usize, Address, u64, Word);
 
 /// Read-write records in execution. Rws are used for connecting evm circuit and
 /// state circuits.
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub enum Rw {
     /// Start
     Start { rw_counter: usize },
@@ -749,6 +832,24 @@ impl Rw {
         }
     }
 
+    pub(crate) fn value_prev_word(&self) -> Option<U256> {
+        match self {
+            Self::Account { value_prev, .. }
+            | Self::AccountStorage { value_prev, .. }
+            | Self::Memory { value_prev, .. } => Some(*value_prev),
+            Self::TxAccessListAccount { is_warm_prev, .. }
+            | Self::TxAccessListAccountStorage { is_warm_prev, .. } => {
+                Some(U256::from(*is_warm_prev as u64))
+            }
+            Self::TxRefund { value_prev, .. } => Some(U256::from(*value_prev)),
+            Self::Start { .. }
+            | Self::Stack { .. }
+            | Self::CallContext { .. }
+            | Self::TxLog { .. }
+            | Self::TxReceipt { .. } => None,
+        }
+    }
+
     pub(crate) fn value_prev_assignment<F: Field>(&self, randomness: F) -> Option<F> {
         match self {
             Self::Account {
@@ -798,6 +899,29 @@ impl Rw {
         }
     }
 
+    /// Return the attributes of this row used by [`RwFilter`].
+    pub fn attrs(&self) -> RwAttrs {
+        let (call_id, tx_id) = match self {
+            Self::CallContext { .. } | Self::Stack { .. } | Self::Memory { .. } => {
+                (self.id(), None)
+            }
+            _ => (None, self.id()),
+        };
+        // stack pointers, memory and log addresses are not account addresses
+        let address = match self {
+            Self::Stack { .. } | Self::Memory { .. } | Self::TxLog { .. } => None,
+            _ => self.address(),
+        };
+        RwAttrs {
+            target: self.tag().into(),
+            rw_counter: self.rw_counter(),
+            address,
+            storage_key: self.storage_key(),
+            call_id,
+            tx_id,
+        }
+    }
+
     #[inline(always)]
     pub(crate) fn as_key(&self) -> RwKey {
         (
@@ -1029,3 +1153,147 @@ impl From<&operation::OperationContainer> for RwMap {
         Self(rws)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eth_types::address;
+    use std::str::FromStr;
+
+    fn rw_map() -> RwMap {
+        let account = address!("0x00000000000000000000000000000000000000aa");
+        let other = address!("0x00000000000000000000000000000000000000bb");
+        let rws = vec![
+            Rw::CallContext {
+                rw_counter: 1,
+                is_write: false,
+                call_id: 1,
+                field_tag: CallContextFieldTag::TxId,
+                value: Word::one(),
+            },
+            Rw::Account {
+                rw_counter: 2,
+                is_write: true,
+                account_address: account,
+                field_tag: AccountFieldTag::Balance,
+                value: Word::from(90),
+                value_prev: Word::from(100),
+            },
+            Rw::Stack {
+                rw_counter: 3,
+                is_write: true,
+                call_id: 1,
+                stack_pointer: 1023,
+                value: Word::from(0xcafe),
+            },
+            Rw::AccountStorage {
+                rw_counter: 4,
+                is_write: true,
+                account_address: account,
+                storage_key: Word::from(7),
+                value: Word::from(2),
+                value_prev: Word::one(),
+                tx_id: 1,
+                committed_value: Word::one(),
+            },
+            Rw::Account {
+                rw_counter: 5,
+                is_write: true,
+                account_address: other,
+                field_tag: AccountFieldTag::Balance,
+                value: Word::from(10),
+                value_prev: Word::zero(),
+            },
+        ];
+        RwMap(rws.into_iter().into_group_map_by(|rw| rw.tag()))
+    }
+
+    fn rw_counters(rows: &[Rw]) -> Vec<usize> {
+        rows.iter().map(Rw::rw_counter).collect()
+    }
+
+    #[test]
+    fn query_filters() {
+        let rws = rw_map();
+        let account = address!("0x00000000000000000000000000000000000000aa");
+
+        assert_eq!(rw_counters(&rws.query(&RwFilter::new())), [1, 2, 3, 4, 5]);
+        assert_eq!(
+            rw_counters(&rws.query(&RwFilter::new().target(Target::Account))),
+            [2, 5]
+        );
+        // the stack pointer is not an account address
+        assert_eq!(
+            rw_counters(&rws.query(&RwFilter::new().address(account))),
+            [2, 4]
+        );
+        assert_eq!(rw_counters(&rws.query(&RwFilter::new().call_id(1))), [1, 3]);
+        assert_eq!(
+            rw_counters(
+                &rws.query(
+                    &RwFilter::new()
+                        .address(account)
+                        .storage_key(Word::from(7))
+                        .tx_id(1)
+                )
+            ),
+            [4]
+        );
+        assert_eq!(
+            rw_counters(&rws.query(&RwFilter::new().rw_counter_range(2..4))),
+            [2, 3]
+        );
+        assert!(rws
+            .query(&RwFilter::new().target(Target::Storage).call_id(1))
+            .is_empty());
+    }
+
+    fn parse_csv_record(line: &str) -> RwRecord {
+        fn opt<T>(field: &str, parse: impl Fn(&str) -> T) -> Option<T> {
+            (!field.is_empty()).then(|| parse(field))
+        }
+        fn word(field: &str) -> Word {
+            Word::from_str_radix(field.trim_start_matches("0x"), 16).unwrap()
+        }
+
+        let fields = line.split(',').collect::<Vec<_>>();
+        assert_eq!(fields.len(), 9, "{line}");
+        RwRecord {
+            rw_counter: fields[0].parse().unwrap(),
+            is_write: fields[1].parse().unwrap(),
+            tag: fields[2].to_string(),
+            id: opt(fields[3], |id| id.parse().unwrap()),
+            address: opt(fields[4], |address| Address::from_str(address).unwrap()),
+            field_tag: opt(fields[5], |field_tag| field_tag.parse().unwrap()),
+            storage_key: opt(fields[6], word),
+            value: word(fields[7]),
+            value_prev: opt(fields[8], word),
+        }
+    }
+
+    #[test]
+    fn csv_roundtrip() {
+        let rows = rw_map().query(&RwFilter::new());
+        let mut csv = Vec::new();
+        RwMap::write_csv(&rows, &mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next(),
+            Some("rw_counter,is_write,tag,id,address,field_tag,storage_key,value,value_prev")
+        );
+        let records = lines.map(parse_csv_record).collect::<Vec<_>>();
+        assert_eq!(records, rows.iter().map(RwRecord::from).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn json_roundtrip() {
+        let rows = rw_map().query(&RwFilter::new());
+        let json = RwMap::to_json(&rows).unwrap();
+        let records: Vec<RwRecord> = serde_json::from_str(&json).unwrap();
+        assert_eq!(records, rows.iter().map(RwRecord::from).collect::<Vec<_>>());
+        assert_eq!(records[3].storage_key, Some(Word::from(7)));
+        assert_eq!(records[3].value_prev, Some(Word::one()));
+    }
+}
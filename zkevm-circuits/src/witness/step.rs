@@ -16,9 +16,10 @@ use crate::{
     },
     table::RwTableTag,
 };
+use serde::{Deserialize, Serialize};
 
 /// Step executed in a transaction
-#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ExecStep {
     /// The index in the Transaction calls
     pub call_index: usize,
@@ -47,6 +48,7 @@ pub struct ExecStep {
     /// The counter for log index within tx
     pub log_id: usize,
     /// The opcode corresponds to the step
+    #[serde(with = "serde_opcode")]
     pub opcode: Option<OpcodeId>,
     /// The block number in which this step exists.
     pub block_num: u64,
@@ -54,6 +56,26 @@ pub struct ExecStep {
     pub aux_data: Option<PrecompileAuxData>,
 }
 
+// The opcode is serialized as its byte, since the deserialization of an
+// OpcodeId parses its geth name, which is not defined for all the opcodes.
+mod serde_opcode {
+    use bus_mapping::evm::OpcodeId;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub(super) fn serialize<S: Serializer>(
+        opcode: &Option<OpcodeId>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        opcode.map(|opcode| opcode.as_u8()).serialize(serializer)
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<OpcodeId>, D::Error> {
+        Ok(Option::<u8>::deserialize(deserializer)?.map(OpcodeId::from))
+    }
+}
+
 impl ExecStep {
     /// The memory size in word **before** this step
     pub fn memory_word_size(&self) -> u64 {
@@ -183,6 +205,7 @@ impl From<&circuit_input_builder::ExecStep> for ExecutionState {
                     OpcodeId::EXTCODEHASH => ExecutionState::EXTCODEHASH,
                     OpcodeId::EXTCODESIZE => ExecutionState::EXTCODESIZE,
                     OpcodeId::BLOCKHASH => ExecutionState::BLOCKHASH,
+                    OpcodeId::BLOBHASH => ExecutionState::BLOBHASH,
                     OpcodeId::TIMESTAMP | OpcodeId::NUMBER | OpcodeId::GASLIMIT => {
                         ExecutionState::BLOCKCTXU64
                     }
@@ -6,8 +6,8 @@ use crate::{
         rlp_fsm::{RlpStackOp, SmState},
         DataTable, Format,
         Format::{
-            L1MsgHash, TxHashEip155, TxHashEip1559, TxHashEip2930, TxHashPreEip155, TxSignEip155,
-            TxSignEip1559, TxSignEip2930, TxSignPreEip155,
+            L1MsgHash, TxHashEip155, TxHashEip1559, TxHashEip2930, TxHashEip4844, TxHashPreEip155,
+            TxSignEip155, TxSignEip1559, TxSignEip2930, TxSignEip4844, TxSignPreEip155,
         },
         RlpFsmWitnessGen, RlpFsmWitnessRow, RlpTable, RlpTag, State,
         State::DecodeTagStart,
@@ -36,12 +36,13 @@ use halo2_proofs::{
 use mock::MockTransaction;
 use num::Integer;
 use num_bigint::BigUint;
+use serde::{Deserialize, Serialize};
 use std::{cmp::Ordering, collections::BTreeMap};
 
 use super::{step::step_convert, Call, ExecStep};
 
 /// Transaction in a witness block
-#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Transaction {
     /// The block number in which this tx is included in
     pub block_number: u64,
@@ -97,6 +98,10 @@ pub struct Transaction {
     pub l1_fee_committed: TxL1Fee,
     /// Optional access list for EIP-2930
     pub access_list: Option<AccessList>,
+    /// Max fee per blob gas (EIP4844)
+    pub max_fee_per_blob_gas: Word,
+    /// Versioned hashes of the blobs carried by the tx (EIP4844)
+    pub blob_versioned_hashes: Vec<H256>,
     /// The calls made in the transaction
     pub calls: Vec<Call>,
     /// The steps executioned in the transaction
@@ -380,6 +385,22 @@ impl Transaction {
                 }),
                 Value::known(F::zero()),
             ],
+            [
+                Value::known(F::from(self.id as u64)),
+                Value::known(F::from(TxContextFieldTag::MaxFeePerBlobGas as u64)),
+                Value::known(F::zero()),
+                challenges.evm_word().map(|challenge| {
+                    rlc::value(&self.max_fee_per_blob_gas.to_le_bytes(), challenge)
+                }),
+                Value::known(F::zero()),
+            ],
+            [
+                Value::known(F::from(self.id as u64)),
+                Value::known(F::from(TxContextFieldTag::BlobVersionedHashesLen as u64)),
+                Value::known(F::zero()),
+                Value::known(F::from(self.blob_versioned_hashes.len() as u64)),
+                Value::known(F::zero()),
+            ],
             [
                 Value::known(F::from(self.id as u64)),
                 Value::known(F::from(TxContextFieldTag::BlockNumber as u64)),
@@ -444,6 +465,27 @@ impl Transaction {
         assignments
     }
 
+    /// Assignments for tx table blob versioned hashes
+    pub fn table_assignments_blob_hashes_dyn<F: Field>(
+        &self,
+        challenges: Challenges<Value<F>>,
+    ) -> Vec<[Value<F>; 5]> {
+        self.blob_versioned_hashes
+            .iter()
+            .enumerate()
+            .map(|(idx, hash)| {
+                [
+                    Value::known(F::from(self.id as u64)),
+                    Value::known(F::from(TxContextFieldTag::BlobVersionedHash as u64)),
+                    // blob hash index starts from 1 in tx-table.
+                    Value::known(F::from((idx + 1) as u64)),
+                    rlc_be_bytes(&hash.to_fixed_bytes(), challenges.evm_word()),
+                    Value::known(F::zero()),
+                ]
+            })
+            .collect()
+    }
+
     pub(crate) fn gen_rlp_witness<F: Field>(
         &self,
         is_hash: bool,
@@ -458,6 +500,7 @@ impl Transaction {
                     TxType::Eip1559 => TxHashEip1559,
                     TxType::L1Msg => L1MsgHash,
                     TxType::Eip2930 => TxHashEip2930,
+                    TxType::Eip4844 => TxHashEip4844,
                 },
             )
         } else {
@@ -468,6 +511,7 @@ impl Transaction {
                     TxType::PreEip155 => TxSignPreEip155,
                     TxType::Eip1559 => TxSignEip1559,
                     TxType::Eip2930 => TxSignEip2930,
+                    TxType::Eip4844 => TxSignEip4844,
                     _ => unreachable!("tx type {:?} not supported", self.tx_type),
                 },
             )
@@ -542,6 +586,9 @@ impl Transaction {
         // unique identifier of addresses and storage keys included in access list
         let mut access_list_idx: u64 = 0;
         let mut storage_key_idx: u64 = 0;
+        // The index of blob versioned hashes, which is exposed in the access_list_idx column
+        // of the rlp table but is not part of the stack op keys.
+        let mut blob_hash_idx: u64 = 0;
 
         loop {
             // default behavior
@@ -578,7 +625,9 @@ impl Transaction {
                             // note: depth alone currently is sufficient to ascertain
                             // the end of an access list as there's no other nested
                             // structure at depth 2 specified in EIP standards
+                            // or of blob versioned hashes
                             access_list_idx = 0;
+                            blob_hash_idx = 0;
                         } else if cur.depth == 0 {
                             // emit GasCost
                             is_output = true;
@@ -619,6 +668,10 @@ impl Transaction {
                             if cur.tag.is_access_list_storage_key() {
                                 storage_key_idx += 1;
                             }
+                            // detect start of blob versioned hash
+                            if cur.tag.is_blob_versioned_hash() {
+                                blob_hash_idx += 1;
+                            }
                         }
 
                         if let Some(rem) = remaining_bytes.last_mut() {
@@ -953,7 +1006,7 @@ impl Transaction {
                     tag_length,
                     is_output,
                     is_none,
-                    access_list_idx,
+                    access_list_idx: access_list_idx + blob_hash_idx,
                     storage_key_idx,
                 },
                 state_machine: StateMachine {
@@ -1098,6 +1151,7 @@ impl<F: Field> RlpFsmWitnessGen<F> for Transaction {
             TxType::PreEip155 => (TxHashPreEip155, Some(TxSignPreEip155)),
             TxType::Eip1559 => (TxHashEip1559, Some(TxSignEip1559)),
             TxType::Eip2930 => (TxHashEip2930, Some(TxSignEip2930)),
+            TxType::Eip4844 => (TxHashEip4844, Some(TxSignEip4844)),
             TxType::L1Msg => (L1MsgHash, None),
         };
 
@@ -1193,6 +1247,8 @@ impl From<MockTransaction> for Transaction {
             l1_fee: Default::default(),
             l1_fee_committed: Default::default(),
             access_list,
+            max_fee_per_blob_gas: Word::zero(),
+            blob_versioned_hashes: vec![],
             calls: vec![],
             steps: vec![],
         }
@@ -1228,12 +1284,12 @@ pub(super) fn tx_convert(
         nonce: tx.nonce,
         gas: tx.gas,
         gas_price: tx.gas_price,
-        max_fee_per_gas: if tx.tx_type.is_eip1559() {
+        max_fee_per_gas: if tx.tx_type.is_eip1559() || tx.tx_type.is_eip4844() {
             tx.gas_fee_cap
         } else {
             tx.gas_price
         },
-        max_priority_fee_per_gas: if tx.tx_type.is_eip1559() {
+        max_priority_fee_per_gas: if tx.tx_type.is_eip1559() || tx.tx_type.is_eip4844() {
             tx.gas_tip_cap
         } else {
             tx.gas_price
@@ -1256,6 +1312,8 @@ pub(super) fn tx_convert(
         l1_fee: tx.l1_fee,
         l1_fee_committed: tx.l1_fee_committed,
         access_list: tx.access_list.clone(),
+        max_fee_per_blob_gas: tx.max_fee_per_blob_gas,
+        blob_versioned_hashes: tx.blob_versioned_hashes.clone(),
         calls: tx
             .calls()
             .iter()
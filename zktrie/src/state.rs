@@ -6,22 +6,24 @@ use std::{collections::HashSet, io::Error};
 pub use zktrie::{Hash as ZkTrieHash, ZkMemoryDb, ZkTrie, ZkTrieNode};
 
 pub mod builder;
+pub mod store;
+pub mod verifier;
 pub mod witness;
 pub use builder::{AccountData, StorageData};
+pub use store::NodeStore;
+pub use verifier::{verify_account_proof, verify_storage_proof, ProofError};
 
-use std::{cell::RefCell, fmt, rc::Rc};
+use std::fmt;
 
 /// represent a storage state being applied in specified block
 #[derive(Clone)]
 pub struct ZktrieState {
-    zk_db: RefCell<Rc<ZkMemoryDb>>,
+    store: NodeStore,
     trie_root: ZkTrieHash,
     addr_cache: HashSet<Address>,
     storage_cache: HashSet<(Address, Word)>,
 }
 
-//unsafe impl Send for ZktrieState {}
-
 impl fmt::Debug for ZktrieState {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "ZktrieState: {:x?}", self.trie_root,)
@@ -39,6 +41,10 @@ impl ZktrieState {
     pub fn root(&self) -> &ZkTrieHash {
         &self.trie_root
     }
+    /// the node store backing this state
+    pub fn store(&self) -> &NodeStore {
+        &self.store
+    }
     /// construct from external data
     pub fn construct(
         //sdb: StateDB,
@@ -46,10 +52,16 @@ impl ZktrieState {
         //proofs: impl IntoIterator<Item = &'d [u8]>,
         //acc_storage_roots: impl IntoIterator<Item = (Address, Hash)>,
     ) -> Self {
+        Self::construct_with_store(state_root, NodeStore::in_memory())
+    }
+
+    /// construct on a node store, which may already contain the nodes of
+    /// `state_root`
+    pub fn construct_with_store(state_root: Hash, store: NodeStore) -> Self {
         builder::init_hash_scheme();
 
         Self {
-            zk_db: RefCell::new(ZkMemoryDb::new()),
+            store,
             trie_root: state_root.0,
             addr_cache: HashSet::new(),
             storage_cache: HashSet::new(),
@@ -72,7 +84,7 @@ impl ZktrieState {
     /// new snapshot since we consider it is not need to send more nodes data
     /// from storage trace for the updated leafs
     pub fn switch_to(&mut self, new_root: ZkTrieHash) -> bool {
-        let test_trie = self.zk_db.borrow_mut().new_trie(&new_root);
+        let test_trie = self.store.zk_db().new_trie(&new_root);
         if test_trie.is_none() {
             return false;
         }
@@ -126,13 +138,15 @@ impl ZktrieState {
         })
     }
 
-    /// incremental updating nodes in db from external data
+    /// incremental updating nodes in db from external data, nodes already in
+    /// the store are skipped
     pub fn update_from_trace<'d, BYTES1, BYTES2>(
         &mut self,
         account_proofs: impl Iterator<Item = (&'d Address, BYTES1)>,
         storage_proofs: impl Iterator<Item = (&'d Address, &'d Word, BYTES2)>,
         additional_proofs: impl Iterator<Item = &'d [u8]>,
-    ) where
+    ) -> Result<(), Error>
+    where
         BYTES1: IntoIterator<Item = &'d [u8]>,
         BYTES2: IntoIterator<Item = &'d [u8]>,
     {
@@ -145,10 +159,9 @@ impl ZktrieState {
                     .flat_map(|(_, _, bytes)| bytes),
             )
             .chain(additional_proofs);
-        let mut zk_db = self.zk_db.borrow_mut();
-        for bytes in proofs {
-            zk_db.add_node_bytes(bytes).unwrap();
-        }
+        let added = self.store.add_nodes(proofs)?;
+        log::trace!("add {added} nodes to zktrie store");
+        Ok(())
     }
 
     /// construct from external data, with additional proofs (trie node) can be
@@ -163,10 +176,32 @@ impl ZktrieState {
         BYTES1: IntoIterator<Item = &'d [u8]>,
         BYTES2: IntoIterator<Item = &'d [u8]>,
     {
-        let mut state = ZktrieState::construct(state_root);
+        Self::from_trace_with_store(
+            state_root,
+            NodeStore::in_memory(),
+            account_proofs,
+            storage_proofs,
+            additional_proofs,
+        )
+    }
+
+    /// construct from external data on a node store, only the nodes not in
+    /// the store yet are imported
+    pub fn from_trace_with_store<'d, BYTES1, BYTES2>(
+        state_root: Hash,
+        store: NodeStore,
+        account_proofs: impl Iterator<Item = (&'d Address, BYTES1)>,
+        storage_proofs: impl Iterator<Item = (&'d Address, &'d Word, BYTES2)>,
+        additional_proofs: impl Iterator<Item = &'d [u8]>,
+    ) -> Result<Self, Error>
+    where
+        BYTES1: IntoIterator<Item = &'d [u8]>,
+        BYTES2: IntoIterator<Item = &'d [u8]>,
+    {
+        let mut state = ZktrieState::construct_with_store(state_root, store);
 
         // a lot of poseidon computation
-        state.update_from_trace(account_proofs, storage_proofs, additional_proofs);
+        state.update_from_trace(account_proofs, storage_proofs, additional_proofs)?;
 
         Ok(state)
     }
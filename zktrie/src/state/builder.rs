@@ -276,18 +276,23 @@ impl<T: CanRead + Default> TryFrom<&[Bytes]> for TrieProof<T> {
     }
 }
 
-pub(crate) fn verify_proof_leaf<T: Default>(inp: TrieProof<T>, key_buf: &[u8; 32]) -> TrieProof<T> {
+/// hash a 32 bytes key (or value) into the trie, as the halves of 16 bytes
+pub(crate) fn hash_byte32(key_buf: &[u8; 32]) -> Fr {
     let first_16bytes: [u8; 16] = key_buf[..16].try_into().expect("expect first 16 bytes");
     let last_16bytes: [u8; 16] = key_buf[16..].try_into().expect("expect last 16 bytes");
 
     let bt_high = Fr::from_u128(u128::from_be_bytes(first_16bytes));
     let bt_low = Fr::from_u128(u128::from_be_bytes(last_16bytes));
 
+    Fr::hash_with_domain([bt_high, bt_low], Fr::from(SECURE_HASH_DOMAIN))
+}
+
+pub(crate) fn verify_proof_leaf<T: Default>(inp: TrieProof<T>, key_buf: &[u8; 32]) -> TrieProof<T> {
     if let Some(key) = inp.key {
         let rev_key_bytes: Vec<u8> = key.to_fixed_bytes().into_iter().rev().collect();
         let key_fr = Fr::from_bytes(&rev_key_bytes.try_into().unwrap()).unwrap();
 
-        let secure_hash = Fr::hash_with_domain([bt_high, bt_low], Fr::from(SECURE_HASH_DOMAIN));
+        let secure_hash = hash_byte32(key_buf);
 
         if key_fr == secure_hash {
             inp
@@ -0,0 +1,310 @@
+//! Persistent store of zktrie nodes
+//!
+//! A [`NodeStore`] keeps every trie node imported from the storage traces, so
+//! that a long running sequencer or prover does not import the same proof
+//! nodes again for each block. The store is a cheap, thread-safe handle: each
+//! thread using it keeps its own [`ZkMemoryDb`], which only imports the nodes
+//! added to the store since that thread last used it. The nodes are hashed into
+//! those dbs without holding the lock of the store.
+//!
+//! The store only grows until it is cleared with [`NodeStore::clear`], which
+//! drops the dbs of the threads as well.
+//!
+//! A store opened with [`NodeStore::open`] appends the nodes to a flat file,
+//! and loads them back when the file is opened again. Each node is written as
+//! its length (u32, little endian) followed by its bytes.
+//!
+//! Notice the nodes created when a trie is updated (e.g. by the witness
+//! generator) stay in the db of the thread that updated it.
+
+use super::builder::init_hash_scheme;
+use sha3::{Digest, Keccak256};
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    fmt,
+    fs::{File, OpenOptions},
+    io::{BufWriter, Error, ErrorKind, Read, Write},
+    path::Path,
+    rc::Rc,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex, MutexGuard, Weak,
+    },
+};
+use zktrie::ZkMemoryDb;
+
+static NEXT_STORE_ID: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Default)]
+struct Inner {
+    nodes: Vec<Arc<[u8]>>,
+    index: HashSet<[u8; 32]>,
+    file: Option<BufWriter<File>>,
+    /// bumped when the store is cleared, so the dbs of the threads are rebuilt
+    generation: usize,
+}
+
+impl Inner {
+    fn contains(&self, node: &[u8]) -> bool {
+        self.index.contains(&node_key(node))
+    }
+
+    fn push(&mut self, node: Arc<[u8]>) -> bool {
+        let added = self.index.insert(node_key(&node));
+        if added {
+            self.nodes.push(node);
+        }
+        added
+    }
+
+    fn append(&mut self, node: Arc<[u8]>) -> Result<bool, Error> {
+        if self.contains(&node) {
+            return Ok(false);
+        }
+        if let Some(file) = self.file.as_mut() {
+            let len = u32::try_from(node.len())
+                .map_err(|_| Error::new(ErrorKind::InvalidInput, "node too large"))?;
+            file.write_all(&len.to_le_bytes())?;
+            file.write_all(&node)?;
+        }
+        Ok(self.push(node))
+    }
+}
+
+/// The db of a [`NodeStore`] on the current thread
+struct LocalDb {
+    store: Weak<Mutex<Inner>>,
+    db: Rc<ZkMemoryDb>,
+    generation: usize,
+    synced: usize,
+}
+
+thread_local! {
+    static LOCAL_DBS: RefCell<HashMap<usize, LocalDb>> = RefCell::new(HashMap::new());
+}
+
+fn node_key(node: &[u8]) -> [u8; 32] {
+    Keccak256::digest(node).into()
+}
+
+fn import_node(db: &mut Rc<ZkMemoryDb>, node: &[u8]) -> Result<(), Error> {
+    db.add_node_bytes(node)
+        .map_err(|err| Error::new(ErrorKind::InvalidData, format!("{err:?}")))
+}
+
+/// Thread-safe handle to a store of zktrie nodes, shared by its clones
+#[derive(Clone)]
+pub struct NodeStore {
+    id: usize,
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl fmt::Debug for NodeStore {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "NodeStore {{ id: {}, nodes: {} }}", self.id, self.len())
+    }
+}
+
+impl Default for NodeStore {
+    fn default() -> Self {
+        Self::in_memory()
+    }
+}
+
+impl NodeStore {
+    fn new(inner: Inner) -> Self {
+        Self {
+            id: NEXT_STORE_ID.fetch_add(1, Ordering::Relaxed),
+            inner: Arc::new(Mutex::new(inner)),
+        }
+    }
+
+    /// create a store which only lives in memory
+    pub fn in_memory() -> Self {
+        Self::new(Inner::default())
+    }
+
+    /// open the store persisted in file `path`, the file is created if it
+    /// does not exist. An incomplete node at the end of the file, left by an
+    /// interrupted write, is dropped.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let mut file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(path)?;
+        let mut buf = Vec::new();
+        file.read_to_end(&mut buf)?;
+
+        let mut inner = Inner::default();
+        let mut offset = 0;
+        while let Some(len_bytes) = buf.get(offset..offset + 4) {
+            let len = u32::from_le_bytes(len_bytes.try_into().expect("4 bytes")) as usize;
+            let Some(node) = buf.get(offset + 4..offset + 4 + len) else {
+                break;
+            };
+            inner.push(node.into());
+            offset += 4 + len;
+        }
+        if offset < buf.len() {
+            log::warn!(
+                "drop {} bytes of incomplete node at the end of {}",
+                buf.len() - offset,
+                path.display()
+            );
+            file.set_len(offset as u64)?;
+        }
+        inner.file = Some(BufWriter::new(file));
+
+        log::debug!(
+            "open zktrie node store {} with {} nodes",
+            path.display(),
+            inner.nodes.len()
+        );
+        Ok(Self::new(inner))
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Inner> {
+        self.inner.lock().expect("node store lock poisoned")
+    }
+
+    /// number of nodes in the store
+    pub fn len(&self) -> usize {
+        self.lock().nodes.len()
+    }
+
+    /// if the store has no node
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// if the store has `node`
+    pub fn contains(&self, node: &[u8]) -> bool {
+        self.lock().contains(node)
+    }
+
+    /// remove all nodes from the store, and from its file if persisted. The
+    /// tries built on the store must not be used after it is cleared.
+    pub fn clear(&self) -> Result<(), Error> {
+        let mut inner = self.lock();
+        if let Some(file) = inner.file.as_mut() {
+            file.flush()?;
+            file.get_ref().set_len(0)?;
+        }
+        inner.nodes = Vec::new();
+        inner.index = HashSet::new();
+        inner.generation += 1;
+        Ok(())
+    }
+
+    /// add the nodes which are not in the store yet, return the number of
+    /// added nodes
+    pub fn add_nodes<'d>(&self, nodes: impl IntoIterator<Item = &'d [u8]>) -> Result<usize, Error> {
+        let (generation, synced) = self.sync_local_db();
+        let new_nodes = {
+            let inner = self.lock();
+            let mut keys = HashSet::new();
+            nodes
+                .into_iter()
+                .filter(|node| !inner.contains(node) && keys.insert(node_key(node)))
+                .map(Arc::from)
+                .collect::<Vec<Arc<[u8]>>>()
+        };
+        // only persist the nodes which can be imported, the lock is not held
+        // while they are hashed
+        self.with_local_db(generation, synced, |db| {
+            new_nodes.iter().try_for_each(|node| import_node(db, node))
+        })?;
+
+        let mut inner = self.lock();
+        if inner.generation != generation {
+            return Err(Error::new(
+                ErrorKind::Interrupted,
+                "node store cleared while adding nodes",
+            ));
+        }
+        // nodes added by other threads meanwhile, which are not in the db yet
+        let missed = inner.nodes[synced..].to_vec();
+        let mut added = 0;
+        for node in new_nodes {
+            added += inner.append(node)? as usize;
+        }
+        if let Some(file) = inner.file.as_mut() {
+            file.flush()?;
+        }
+        let synced = inner.nodes.len();
+        drop(inner);
+
+        self.with_local_db(generation, synced, |db| {
+            missed.iter().try_for_each(|node| import_node(db, node))
+        })?;
+        Ok(added)
+    }
+
+    /// the db of the current thread, with all nodes of the store imported
+    pub(crate) fn zk_db(&self) -> Rc<ZkMemoryDb> {
+        let (generation, synced) = self.sync_local_db();
+        self.with_local_db(generation, synced, |db| Ok(db.clone()))
+            .expect("nodes in store can be imported")
+    }
+
+    /// import the nodes of the store missing in the db of the current thread,
+    /// without holding the lock while they are hashed, and return the
+    /// generation and number of nodes of the store the db is synced to
+    fn sync_local_db(&self) -> (usize, usize) {
+        let (generation, synced) = LOCAL_DBS.with(|dbs| {
+            dbs.borrow()
+                .get(&self.id)
+                .map_or((usize::MAX, 0), |local| (local.generation, local.synced))
+        });
+        let (generation, synced, missing) = {
+            let inner = self.lock();
+            let synced = if inner.generation == generation {
+                synced
+            } else {
+                0
+            };
+            (
+                inner.generation,
+                inner.nodes.len(),
+                inner.nodes[synced..].to_vec(),
+            )
+        };
+        self.with_local_db(generation, synced, |db| {
+            missing.iter().try_for_each(|node| import_node(db, node))
+        })
+        .expect("nodes in store can be imported");
+        (generation, synced)
+    }
+
+    /// run `f` on the db of the current thread for `generation` of the store,
+    /// then mark it synced to the first `synced` nodes of the store
+    fn with_local_db<T>(
+        &self,
+        generation: usize,
+        synced: usize,
+        f: impl FnOnce(&mut Rc<ZkMemoryDb>) -> Result<T, Error>,
+    ) -> Result<T, Error> {
+        init_hash_scheme();
+        LOCAL_DBS.with(|dbs| {
+            let mut dbs = dbs.borrow_mut();
+            // release the dbs of dropped stores
+            dbs.retain(|_, local| local.store.strong_count() > 0);
+            let local = dbs.entry(self.id).or_insert_with(|| LocalDb {
+                store: Arc::downgrade(&self.inner),
+                db: ZkMemoryDb::new(),
+                generation,
+                synced: 0,
+            });
+            if local.generation != generation {
+                local.db = ZkMemoryDb::new();
+                local.generation = generation;
+            }
+            let ret = f(&mut local.db)?;
+            local.synced = synced;
+            Ok(ret)
+        })
+    }
+}
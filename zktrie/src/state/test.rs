@@ -275,3 +275,153 @@ fn witgen_update_one() {
 
     info!("ret {:?}", trace);
 }
+
+fn example_nodes(trace: &StorageTrace) -> Vec<&[u8]> {
+    trace
+        .proofs
+        .iter()
+        .flat_map(|kv_map| kv_map.values())
+        .chain(
+            trace
+                .storage_proofs
+                .values()
+                .flat_map(|kv_map| kv_map.values()),
+        )
+        .flatten()
+        .map(Bytes::as_ref)
+        .collect()
+}
+
+#[test]
+fn node_store_persist() {
+    use witness::WitnessGenerator;
+    let trace: StorageTrace = serde_json::from_str(EXAMPLE_TRACE).unwrap();
+    let nodes = example_nodes(&trace);
+    let path = std::env::temp_dir().join(format!("zktrie-nodes-{}.bin", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    let store = NodeStore::open(&path).unwrap();
+    let added = store.add_nodes(nodes.iter().copied()).unwrap();
+    assert!(added > 0 && added < nodes.len(), "shared nodes are deduped");
+    assert_eq!(store.add_nodes(nodes.iter().copied()).unwrap(), 0);
+    drop(store);
+
+    let store = NodeStore::open(&path).unwrap();
+    assert_eq!(store.len(), added);
+    let state = ZktrieState::construct_with_store(trace.root_before, store);
+    assert_eq!(WitnessGenerator::from(&state).root(), trace.root_before);
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn node_store_clear() {
+    use witness::WitnessGenerator;
+    let trace: StorageTrace = serde_json::from_str(EXAMPLE_TRACE).unwrap();
+    let nodes = example_nodes(&trace);
+    let store = NodeStore::in_memory();
+    let added = store.add_nodes(nodes.iter().copied()).unwrap();
+
+    store.clear().unwrap();
+    assert!(store.is_empty());
+    assert!(!store.contains(nodes[0]));
+
+    assert_eq!(store.add_nodes(nodes.iter().copied()).unwrap(), added);
+    let state = ZktrieState::construct_with_store(trace.root_before, store);
+    assert_eq!(WitnessGenerator::from(&state).root(), trace.root_before);
+}
+
+#[test]
+fn node_store_add_nodes_across_threads() {
+    let trace: StorageTrace = serde_json::from_str(EXAMPLE_TRACE).unwrap();
+    let nodes = example_nodes(&trace)
+        .into_iter()
+        .map(<[u8]>::to_vec)
+        .collect::<Vec<_>>();
+    let store = NodeStore::in_memory();
+
+    let (first, second) = nodes.split_at(nodes.len() / 2);
+    let other = store.clone();
+    let second = second.to_vec();
+    let handle = std::thread::spawn(move || other.add_nodes(second.iter().map(Vec::as_slice)));
+    let added = store.add_nodes(first.iter().map(Vec::as_slice)).unwrap();
+    let added = added + handle.join().unwrap().unwrap();
+    assert_eq!(store.len(), added);
+    assert_eq!(store.add_nodes(nodes.iter().map(Vec::as_slice)).unwrap(), 0);
+}
+
+#[test]
+fn zktrie_state_across_threads() {
+    use witness::WitnessGenerator;
+    let (state, _, _) = build_state_from_string(EXAMPLE_TRACE);
+    let root = WitnessGenerator::from(&state).root();
+
+    let other = state.clone();
+    let other_root = std::thread::spawn(move || WitnessGenerator::from(&other).root())
+        .join()
+        .unwrap();
+    assert_eq!(root, other_root);
+}
+
+#[test]
+fn verify_example_proofs() {
+    let trace: StorageTrace = serde_json::from_str(EXAMPLE_TRACE).unwrap();
+    let (_, accounts, storages) = build_state_from_string(EXAMPLE_TRACE);
+
+    for (addr, proof) in trace.proofs.iter().flatten() {
+        let account =
+            verify_account_proof(trace.root_before, *addr, proof.iter().map(Bytes::as_ref))
+                .unwrap();
+        assert_eq!(
+            account.as_ref(),
+            accounts
+                .get(addr)
+                .filter(|acc| **acc != AccountData::default())
+        );
+    }
+
+    for (addr, proofs) in trace.storage_proofs.iter() {
+        let storage_root = accounts[addr].storage_root;
+        for (key, proof) in proofs {
+            let value =
+                verify_storage_proof(storage_root, *key, proof.iter().map(Bytes::as_ref)).unwrap();
+            let expected = *storages[&(*addr, *key)].as_ref();
+            assert_eq!(value.unwrap_or_default(), expected);
+        }
+    }
+}
+
+#[test]
+fn verify_generated_proofs() {
+    use witness::WitnessGenerator;
+    let (state, accounts, _) = build_state_from_string(EXAMPLE_TRACE);
+    let w = WitnessGenerator::from(&state);
+    let root = w.root();
+
+    let (addr, account) = accounts
+        .iter()
+        .find(|(_, acc)| **acc != AccountData::default())
+        .unwrap();
+    let proof = w.account_proof(*addr);
+    assert_eq!(
+        verify_account_proof(root, *addr, proof.iter().map(Vec::as_slice)).unwrap(),
+        Some(*account)
+    );
+
+    // non-inclusion
+    let missing = Address::repeat_byte(0xab);
+    let proof = w.account_proof(missing);
+    assert_eq!(
+        verify_account_proof(root, missing, proof.iter().map(Vec::as_slice)).unwrap(),
+        None
+    );
+
+    // tampered balance in the leaf
+    let mut proof = w.account_proof(*addr);
+    let leaf = proof.len() - 2;
+    proof[leaf][37 + 63] ^= 1;
+    assert!(matches!(
+        verify_account_proof(root, *addr, proof.iter().map(Vec::as_slice)),
+        Err(ProofError::HashMismatch { .. }) | Err(ProofError::InvalidNode { .. })
+    ));
+}
@@ -0,0 +1,279 @@
+//! Verify zktrie proofs without the circuits
+//!
+//! A proof is the list of trie nodes on the path from the root to the key, in
+//! the format of `eth_getProof` and [`WitnessGenerator`](super::witness::WitnessGenerator).
+//! It ends with a leaf of the key (inclusion), or with an empty node or a leaf
+//! of another key (non-inclusion).
+//!
+//! ```rust, ignore
+//! let account = verify_account_proof(state_root, address, account_proof)?
+//!     .expect("account exists");
+//! let value = verify_storage_proof(account.storage_root, key, storage_proof)?;
+//! ```
+
+use super::builder::{
+    extend_address_to_h256, hash_byte32, AccountData, NODE_TYPE_EMPTY, NODE_TYPE_LEAF,
+    NODE_TYPE_MIDDLE_0, NODE_TYPE_MIDDLE_3,
+};
+use eth_types::{Address, Hash, Word};
+use halo2_proofs::halo2curves::{bn256::Fr, group::ff::PrimeField};
+use hash_circuit::hash::Hashable;
+use std::fmt;
+
+/// The node appended by zktrie at the end of a proof
+const MAGIC_NODE_BYTES: &[u8] = b"THIS IS SOME MAGIC BYTES FOR SMT m1rRXgP2xpDI";
+/// Domain of hashing n elements is n * HASH_DOMAIN_ELEMS_BASE
+const HASH_DOMAIN_ELEMS_BASE: u64 = 256;
+
+/// Error of verifying a proof
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProofError {
+    /// The node at `depth` can not be parsed
+    InvalidNode {
+        /// depth of the node, the root is at 0
+        depth: usize,
+    },
+    /// The hash of the node at `depth` is not the one committed by its parent
+    /// (or the root)
+    HashMismatch {
+        /// depth of the node, the root is at 0
+        depth: usize,
+    },
+    /// The leaf at `depth` is for another key which is not on the path of the
+    /// proven key
+    PathMismatch {
+        /// depth of the leaf, the root is at 0
+        depth: usize,
+    },
+    /// The proof ends before reaching a leaf or an empty node
+    Incomplete,
+    /// There are nodes after the leaf or empty node
+    TrailingNodes,
+    /// The leaf does not hold the expected kind of value
+    InvalidLeafValue,
+}
+
+impl fmt::Display for ProofError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidNode { depth } => write!(f, "invalid node at depth {depth}"),
+            Self::HashMismatch { depth } => write!(f, "hash mismatch at depth {depth}"),
+            Self::PathMismatch { depth } => write!(f, "leaf off the key path at depth {depth}"),
+            Self::Incomplete => write!(f, "proof ends before a leaf or empty node"),
+            Self::TrailingNodes => write!(f, "nodes after the end of proof"),
+            Self::InvalidLeafValue => write!(f, "unexpected leaf value"),
+        }
+    }
+}
+
+impl std::error::Error for ProofError {}
+
+/// Verify the proof of the account at `address` against the state `root`.
+/// Returns the account if it exists, or `None` if the proof shows it does not
+/// exist.
+pub fn verify_account_proof<'a>(
+    root: Hash,
+    address: Address,
+    proof: impl IntoIterator<Item = &'a [u8]>,
+) -> Result<Option<AccountData>, ProofError> {
+    let key = extend_address_to_h256(&address);
+    verify_proof(root, &key, proof)?
+        .map(|values| {
+            let fields: zktrie::AccountData = values
+                .try_into()
+                .map_err(|_| ProofError::InvalidLeafValue)?;
+            Ok(AccountData::from(fields))
+        })
+        .transpose()
+}
+
+/// Verify the proof of the storage slot `key` against the storage `root` of
+/// an account. Returns the value of the slot if it exists, or `None` if the
+/// proof shows it does not exist (i.e. the value is zero).
+pub fn verify_storage_proof<'a>(
+    root: Hash,
+    key: Word,
+    proof: impl IntoIterator<Item = &'a [u8]>,
+) -> Result<Option<Word>, ProofError> {
+    let mut key_buf = [0u8; 32];
+    key.to_big_endian(key_buf.as_mut_slice());
+    verify_proof(root, &key_buf, proof)?
+        .map(|values| match values.as_slice() {
+            [value] => Ok(Word::from_big_endian(value)),
+            _ => Err(ProofError::InvalidLeafValue),
+        })
+        .transpose()
+}
+
+/// Verify the proof of `key` against `root`, returns the values of the leaf
+/// if the key exists.
+fn verify_proof<'a>(
+    root: Hash,
+    key: &[u8; 32],
+    proof: impl IntoIterator<Item = &'a [u8]>,
+) -> Result<Option<Vec<[u8; 32]>>, ProofError> {
+    let node_key = hash_byte32(key);
+    let key_bits = node_key.to_repr();
+    let key_bit = |depth: usize| (key_bits.as_ref()[depth / 8] >> (depth % 8)) & 1 == 1;
+
+    let mut expected =
+        hash_from_bytes(root.as_fixed_bytes()).ok_or(ProofError::InvalidNode { depth: 0 })?;
+    let mut nodes = proof
+        .into_iter()
+        .filter(|bytes| *bytes != MAGIC_NODE_BYTES)
+        .enumerate();
+    while let Some((depth, bytes)) = nodes.next() {
+        let node = Node::parse(bytes).ok_or(ProofError::InvalidNode { depth })?;
+        if node.hash().ok_or(ProofError::InvalidNode { depth })? != expected {
+            return Err(ProofError::HashMismatch { depth });
+        }
+        let found = match node {
+            Node::Middle { left, right, .. } => {
+                expected = if key_bit(depth) { right } else { left };
+                continue;
+            }
+            Node::Empty => None,
+            Node::Leaf {
+                node_key: leaf_key,
+                values,
+                ..
+            } => {
+                if leaf_key == node_key {
+                    Some(values)
+                } else {
+                    // a leaf of another key proves the non-inclusion only if it
+                    // sits on the path of the key
+                    let leaf_bits = leaf_key.to_repr();
+                    let on_path = (0..depth)
+                        .all(|d| ((leaf_bits.as_ref()[d / 8] >> (d % 8)) & 1 == 1) == key_bit(d));
+                    if !on_path {
+                        return Err(ProofError::PathMismatch { depth });
+                    }
+                    None
+                }
+            }
+        };
+        if nodes.next().is_some() {
+            return Err(ProofError::TrailingNodes);
+        }
+        return Ok(found);
+    }
+    Err(ProofError::Incomplete)
+}
+
+/// hashes in the nodes are big endian field elements
+fn hash_from_bytes(bytes: &[u8; 32]) -> Option<Fr> {
+    let mut repr = *bytes;
+    repr.reverse();
+    Fr::from_repr(repr).into()
+}
+
+enum Node {
+    Middle {
+        node_type: u8,
+        left: Fr,
+        right: Fr,
+    },
+    Leaf {
+        node_key: Fr,
+        compressed_flags: u32,
+        values: Vec<[u8; 32]>,
+    },
+    Empty,
+}
+
+impl Node {
+    fn parse(bytes: &[u8]) -> Option<Self> {
+        let read_hash = |offset: usize| -> Option<Fr> {
+            hash_from_bytes(bytes.get(offset..offset + 32)?.try_into().ok()?)
+        };
+        match *bytes.first()? {
+            node_type @ NODE_TYPE_MIDDLE_0..=NODE_TYPE_MIDDLE_3 => Some(Self::Middle {
+                node_type,
+                left: read_hash(1)?,
+                right: read_hash(33)?,
+            }),
+            NODE_TYPE_LEAF => {
+                let node_key = read_hash(1)?;
+                // the number of values, then the compressed flags in the upper
+                // 24 bits
+                let flags = u32::from_le_bytes(bytes.get(33..37)?.try_into().ok()?);
+                let num_values = (flags & 0xff) as usize;
+                let values = bytes
+                    .get(37..37 + 32 * num_values)?
+                    .chunks_exact(32)
+                    .map(|value| value.try_into().expect("32 bytes"))
+                    .collect();
+                Some(Self::Leaf {
+                    node_key,
+                    compressed_flags: flags >> 8,
+                    values,
+                })
+            }
+            NODE_TYPE_EMPTY => Some(Self::Empty),
+            _ => None,
+        }
+    }
+
+    fn hash(&self) -> Option<Fr> {
+        Some(match self {
+            Self::Middle {
+                node_type,
+                left,
+                right,
+            } => Fr::hash_with_domain([*left, *right], Fr::from(*node_type as u64)),
+            Self::Leaf {
+                node_key,
+                compressed_flags,
+                values,
+            } => {
+                let value_hash = hash_values(*compressed_flags, values)?;
+                Fr::hash_with_domain([*node_key, value_hash], Fr::from(NODE_TYPE_LEAF as u64))
+            }
+            Self::Empty => Fr::zero(),
+        })
+    }
+}
+
+/// Hash the values of a leaf, the values flagged as compressed are hashed as
+/// 32 bytes, the others are field elements.
+fn hash_values(compressed_flags: u32, values: &[[u8; 32]]) -> Option<Fr> {
+    let elems = values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| {
+            if compressed_flags & (1 << i) != 0 {
+                Some(hash_byte32(value))
+            } else {
+                hash_from_bytes(value)
+            }
+        })
+        .collect::<Option<Vec<_>>>()?;
+    match elems.as_slice() {
+        [] => None,
+        [elem] => Some(*elem),
+        [fst, snd, rest @ ..] => {
+            let domain = Fr::from(elems.len() as u64 * HASH_DOMAIN_ELEMS_BASE);
+            Some(hash_elems_with_domain(domain, *fst, *snd, rest))
+        }
+    }
+}
+
+fn hash_elems_with_domain(domain: Fr, fst: Fr, snd: Fr, elems: &[Fr]) -> Fr {
+    let base = Fr::hash_with_domain([fst, snd], domain);
+    match elems {
+        [] => base,
+        [elem] => Fr::hash_with_domain([base, *elem], domain),
+        _ => {
+            let tmp = elems
+                .chunks(2)
+                .map(|pair| match pair {
+                    [a, b] => Fr::hash_with_domain([*a, *b], domain),
+                    [a] => *a,
+                    _ => unreachable!("chunks of 2"),
+                })
+                .collect::<Vec<_>>();
+            hash_elems_with_domain(domain, base, tmp[0], &tmp[1..])
+        }
+    }
+}
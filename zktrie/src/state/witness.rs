@@ -1,8 +1,6 @@
 //! witness generator
 use super::{
-    builder::{
-        extend_address_to_h256, AccountData, BytesArray, CanRead, TrieProof, SECURE_HASH_DOMAIN,
-    },
+    builder::{extend_address_to_h256, hash_byte32, AccountData, BytesArray, CanRead, TrieProof},
     MPTProofType, ZktrieState,
 };
 use eth_types::{Address, Hash, ToWord, Word, H256, U256};
@@ -47,7 +45,7 @@ pub struct WitnessGenerator {
 impl From<&ZktrieState> for WitnessGenerator {
     fn from(state: &ZktrieState) -> Self {
         Self {
-            trie: state.zk_db.borrow_mut().new_trie(&state.trie_root).unwrap(),
+            trie: state.store.zk_db().new_trie(&state.trie_root).unwrap(),
             storages_cache: HashMap::new(),
         }
     }
@@ -396,16 +394,7 @@ fn smt_hash_from_bytes(bt: &[u8]) -> SMTHash {
 }
 
 fn hash_zktrie_key(key_buf: &[u8; 32]) -> Word {
-    use halo2_proofs::halo2curves::bn256::Fr;
-    use hash_circuit::hash::Hashable;
-
-    let first_16bytes: [u8; 16] = key_buf[..16].try_into().expect("expect first 16 bytes");
-    let last_16bytes: [u8; 16] = key_buf[16..].try_into().expect("expect last 16 bytes");
-
-    let bt_high = Fr::from_u128(u128::from_be_bytes(first_16bytes));
-    let bt_low = Fr::from_u128(u128::from_be_bytes(last_16bytes));
-
-    let hash = Fr::hash_with_domain([bt_high, bt_low], Fr::from(SECURE_HASH_DOMAIN));
+    let hash = hash_byte32(key_buf);
 
     U256::from_little_endian(hash.to_repr().as_ref())
 }